@@ -0,0 +1,67 @@
+// Metadatos que inyecta build.rs al compilar (versión, número y fecha de build, target):
+// `csv_tools version` / `--version` los muestran para saber qué binario corre en un servidor
+
+use serde::Serialize;
+
+use crate::error::CsvToolsError;
+use crate::logging;
+
+pub const VERSION: &str = env!("CSV_TOOLS_VERSION");
+pub const BUILD_NUMBER: &str = env!("BUILD_NUMBER");
+pub const BUILD_DATE: &str = env!("BUILD_DATE");
+pub const TARGET: &str = env!("TARGET");
+
+/// Texto de `--version` (clap lo imprime tal cual)
+pub const LONG_VERSION: &str = concat!(
+    env!("CSV_TOOLS_VERSION"), " (build #", env!("BUILD_NUMBER"), ", ", env!("BUILD_DATE"), ", ", env!("TARGET"), ")"
+);
+
+/// Capacidades compiladas en este binario
+pub const FEATURES: &[&str] = &[
+    "gzip-input",
+    "gzip-output-parallel",
+    "zstd-input-output",
+    "parquet-input",
+    "arrow-ipc-output",
+    "encoding-transcoding",
+    "sqlite-bundled",
+    "sink-registry",
+    "config-file",
+];
+
+#[derive(Serialize)]
+struct BuildInfo {
+    version: &'static str,
+    build_number: &'static str,
+    build_date: &'static str,
+    target: &'static str,
+    profile: &'static str,
+    features: &'static [&'static str],
+}
+
+fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: VERSION,
+        build_number: BUILD_NUMBER,
+        build_date: BUILD_DATE,
+        target: TARGET,
+        profile: if cfg!(debug_assertions) { "debug" } else { "release" },
+        features: FEATURES,
+    }
+}
+
+/// `csv_tools version`: datos del build en texto, o JSON con --format json
+pub fn print_version() -> Result<(), CsvToolsError> {
+    let info = build_info();
+    if logging::json_output() {
+        return logging::summary(&info);
+    }
+
+    println!("csv_tools {}", info.version);
+    println!("  Build:    #{}", info.build_number);
+    println!("  Date:     {}", info.build_date);
+    println!("  Target:   {}", info.target);
+    println!("  Profile:  {}", info.profile);
+    println!("  Features: {}", info.features.join(", "));
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// Reglas de canonicalización de la clave (`dedup_by_keys --canonicalize rules.yaml`):
+// por columna, transformaciones que se aplican solo al calcular la clave de dedup; la
+// fila se escribe con sus valores originales. Ejemplo:
+//
+//   columns:
+//     NroDoc: [trim, strip_leading_zeros]
+//     ApellidoNombre: [collapse_whitespace, uppercase]
+//     Telefono: [phone]
+//
+// Las reglas de cada columna se aplican en el orden en que están escritas
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::error::CsvToolsError;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Rule {
+    /// Recorta espacios al principio y al final
+    Trim,
+    Uppercase,
+    Lowercase,
+    /// Colapsa los espacios internos repetidos a uno solo
+    CollapseWhitespace,
+    /// "000123" → "123" (un valor de solo ceros queda "0")
+    StripLeadingZeros,
+    /// Deja solo los dígitos: "+54 (11) 4555-1234" → "541145551234"
+    Phone,
+}
+
+impl Rule {
+    fn apply(self, value: &str) -> String {
+        match self {
+            Rule::Trim => value.trim().to_string(),
+            Rule::Uppercase => value.to_uppercase(),
+            Rule::Lowercase => value.to_lowercase(),
+            Rule::CollapseWhitespace => value.split_whitespace().collect::<Vec<_>>().join(" "),
+            Rule::StripLeadingZeros => {
+                let stripped = value.trim_start_matches('0');
+                if stripped.is_empty() && !value.is_empty() { "0".to_string() } else { stripped.to_string() }
+            }
+            Rule::Phone => value.chars().filter(char::is_ascii_digit).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CanonicalRules {
+    /// Columna → reglas en orden
+    pub columns: BTreeMap<String, Vec<Rule>>,
+}
+
+impl CanonicalRules {
+    pub fn load(path: &str) -> Result<Self, CsvToolsError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read canonicalization rules '{}': {}", path, e))?;
+        let rules: CanonicalRules = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid canonicalization rules '{}': {}", path, e))?;
+        Ok(rules)
+    }
+
+    /// Reglas de `column` (vacío si no tiene)
+    pub fn for_column(&self, column: &str) -> &[Rule] {
+        self.columns.get(column.trim()).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// Aplica `rules` en orden a `value`
+pub fn canonical(value: &str, rules: &[Rule]) -> String {
+    rules.iter().fold(value.to_string(), |value, rule| rule.apply(&value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_from_yaml() {
+        let rules: CanonicalRules = serde_yaml::from_str(
+            "columns:\n  NroDoc: [trim, strip_leading_zeros]\n  Telefono: [phone]\n  Nombre: [collapse_whitespace, uppercase]\n").unwrap();
+        assert_eq!(canonical(" 00012345 ", rules.for_column("NroDoc")), "12345");
+        assert_eq!(canonical("000", rules.for_column("NroDoc")), "0");
+        assert_eq!(canonical("+54 (11) 4555-1234", rules.for_column("Telefono")), "541145551234");
+        assert_eq!(canonical("perez   juan", rules.for_column("Nombre")), "PEREZ JUAN");
+        assert_eq!(canonical("x", rules.for_column("Otra")), "x");
+        assert!(serde_yaml::from_str::<CanonicalRules>("columns:\n  NroDoc: [reverse]\n").is_err());
+    }
+}
@@ -0,0 +1,280 @@
+// Checkpoints periódicos de los comandos que recorren una sola entrada
+// (validate_model, clean_invalid_lines, deduplicate_dynamodb): cada
+// --checkpoint-interval se guarda en `<output>.checkpoint.json` hasta qué byte / línea
+// de la entrada se procesó y el largo de cada salida en ese momento. Con --resume
+// las salidas se recortan a ese largo y la lectura sigue desde ese byte, así una
+// corrida cortada (caída, Ctrl+C, --max-runtime) no repite ni duplica filas
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use csv::{Position, Reader, ReaderBuilder, StringRecord};
+use serde::{Deserialize, Serialize};
+
+use crate::compression;
+use crate::config;
+use crate::disk_guard::Checkpoint;
+use crate::error::CsvToolsError;
+use crate::exit_code::usage_error;
+use crate::file_utils::{self, is_stdio};
+use crate::runtime_limit::{self, Incomplete};
+use crate::verbose;
+
+/// Opciones para retomar, compartidas por los comandos con checkpoint periódico
+#[derive(Args, Debug, Clone)]
+pub struct ResumeArgs {
+    #[arg(long, help = "Continue from <output>.checkpoint.json left by an interrupted run")]
+    pub resume: bool,
+    #[arg(long, value_name = "DURATION", default_value = "1m", value_parser = runtime_limit::parse_duration,
+          help = "How often the checkpoint is recorded (e.g. 30s, 5m)")]
+    pub checkpoint_interval: Duration,
+}
+
+/// Lector CSV de la entrada de un comando con checkpoint (gzip y reintentos incluidos)
+pub type InputReader = Reader<Box<dyn BufRead>>;
+
+/// Contenido de `<output>.checkpoint.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumePoint {
+    pub command: String,
+    /// Siempre "incomplete": las salidas parciales no deben usarse hasta completar la corrida
+    pub status: String,
+    pub input: String,
+    /// Tamaño de la entrada al guardar (si cambió no se puede retomar)
+    pub input_bytes: u64,
+    /// Byte (de los datos descomprimidos) donde empieza el primer registro sin procesar
+    pub byte_offset: u64,
+    pub line: u64,
+    pub records: u64,
+    /// Largo de cada salida al guardar; lo escrito después se descarta al retomar
+    pub outputs: BTreeMap<String, u64>,
+    /// Contadores del comando (válidos, errores, ...) para que el resumen final sea el total
+    pub counters: BTreeMap<String, u64>,
+    /// Estado en memoria que no está en las salidas (únicos de deduplicate_dynamodb)
+    pub snapshot: Option<String>,
+    pub updated_at: String,
+}
+
+impl ResumePoint {
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counters.get(name).copied().unwrap_or(0)
+    }
+}
+
+/// Guarda y retoma el checkpoint de una corrida. Solo está activo con entrada y
+/// salidas en archivos sin comprimir (stdin/stdout o un .gz / .zst de salida no se pueden recortar)
+pub struct CheckpointManager {
+    command: String,
+    input: String,
+    path: String,
+    outputs: Vec<String>,
+    interval: Duration,
+    enabled: bool,
+    last_saved: Instant,
+    // Diferencia entre la posición del lector y la de la entrada real (al retomar,
+    // el lector arranca con el header seguido del resto de la entrada)
+    byte_base: u64,
+    line_base: u64,
+    record_base: u64,
+}
+
+impl CheckpointManager {
+    /// `output` da nombre al checkpoint; `outputs` son las salidas que se escriben
+    /// a medida que avanza la lectura (se recortan al retomar)
+    pub fn new(command: &str, input: &str, output: &str, outputs: &[&str], args: &ResumeArgs) -> Self {
+        let enabled = !is_stdio(input) && !is_stdio(output)
+            && outputs.iter().all(|o| !is_stdio(o) && compression::compression_for(o).is_none());
+
+        CheckpointManager {
+            command: command.to_string(),
+            input: input.to_string(),
+            path: Checkpoint::path_for(output),
+            outputs: outputs.iter().map(|o| o.to_string()).collect(),
+            interval: args.checkpoint_interval,
+            enabled,
+            last_saved: Instant::now(),
+            byte_base: 0,
+            line_base: 0,
+            record_base: 0,
+        }
+    }
+
+    /// Ruta del estado en memoria que acompaña al checkpoint (`<output>.checkpoint.csv`)
+    pub fn snapshot_path(&self) -> String {
+        self.path.trim_end_matches(".json").to_string() + ".csv"
+    }
+
+    /// Lee el checkpoint para --resume y valida que sea de este comando y esta entrada
+    pub fn load(&self) -> Result<ResumePoint, CsvToolsError> {
+        if !self.enabled {
+            return Err(usage_error("--resume needs a file input and uncompressed file outputs (not '-', .gz/.zst or --compress)"));
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .map_err(|e| usage_error(format!("Cannot resume: checkpoint '{}' not readable ({})", self.path, e)))?;
+        let point: ResumePoint = serde_json::from_str(&content)
+            .map_err(|e| usage_error(format!("Invalid checkpoint '{}': {}", self.path, e)))?;
+
+        if point.command != self.command || point.input != self.input {
+            return Err(usage_error(format!(
+                "Checkpoint '{}' belongs to '{} {}', not '{} {}'",
+                self.path, point.command, point.input, self.command, self.input
+            )));
+        }
+        if std::fs::metadata(&self.input)?.len() != point.input_bytes {
+            return Err(usage_error(format!("Cannot resume: '{}' changed since the checkpoint", self.input)));
+        }
+        Ok(point)
+    }
+
+    /// Lector CSV de la entrada con el header ya leído: desde el principio o, al retomar,
+    /// desde el primer registro sin procesar (el lector ve el header y luego el resto,
+    /// así valida la cantidad de columnas igual que en una corrida completa)
+    pub fn open_reader(&mut self, point: Option<&ResumePoint>) -> Result<(InputReader, StringRecord), CsvToolsError> {
+        let mut reader = ReaderBuilder::new().delimiter(config::delimiter())
+            .from_reader(file_utils::open_input(&self.input)?);
+        let headers = reader.headers()?.clone();
+        let Some(point) = point else {
+            return Ok((reader, headers));
+        };
+
+        let header_end = reader.position().clone();
+        let mut header_bytes = Vec::new();
+        file_utils::open_input(&self.input)?.take(header_end.byte()).read_to_end(&mut header_bytes)?;
+        let rest = file_utils::open_input_at(&self.input, point.byte_offset)?;
+        let stream: Box<dyn BufRead> = Box::new(BufReader::new(io::Cursor::new(header_bytes).chain(rest)));
+
+        let mut reader = ReaderBuilder::new().delimiter(config::delimiter()).from_reader(stream);
+        reader.headers()?;
+        self.byte_base = point.byte_offset - header_end.byte();
+        self.line_base = point.line - header_end.line();
+        self.record_base = point.records;
+        Ok((reader, headers))
+    }
+
+    /// Texto de un error de lectura con la posición en la entrada real (al retomar,
+    /// la que trae el error es relativa al lector que arrancó en el checkpoint)
+    pub fn error_message(&self, error: &csv::Error) -> String {
+        match error.kind() {
+            csv::ErrorKind::UnequalLengths { pos: Some(pos), expected_len, len } if self.record_base > 0 => format!(
+                "CSV error: record {} (line: {}, byte: {}): found record with {} fields, but the previous record has {} fields",
+                self.record_base + pos.record(), self.line_base + pos.line(), self.byte_base + pos.byte(), len, expected_len
+            ),
+            _ => error.to_string(),
+        }
+    }
+
+    /// Byte de inicio de un registro (o de un error de lectura) en la entrada real
+    pub fn byte_offset(&self, position: Option<&Position>) -> Option<u64> {
+        position.map(|p| self.byte_base + p.byte())
+    }
+
+    /// Línea de inicio de un registro en la entrada real
+    pub fn line(&self, position: Option<&Position>) -> Option<u64> {
+        position.map(|p| self.line_base + p.line())
+    }
+
+    /// Abre una salida: nueva, o al retomar recortada al largo del checkpoint y a continuación
+    pub fn open_output(&self, path: &str, point: Option<&ResumePoint>) -> Result<Box<dyn Write>, CsvToolsError> {
+        let Some(point) = point else {
+            return compression::create_output(path, 1);
+        };
+        let len = *point.outputs.get(path)
+            .ok_or_else(|| usage_error(format!("Cannot resume: '{}' is not in checkpoint '{}'", path, self.path)))?;
+        OpenOptions::new().write(true).open(path)?.set_len(len)?;
+        compression::append_output(path, 1)
+    }
+
+    /// true cuando toca guardar: pasó --checkpoint-interval o venció --max-runtime
+    pub fn due(&self) -> bool {
+        self.enabled && (self.last_saved.elapsed() >= self.interval || runtime_limit::exceeded())
+    }
+
+    /// Guarda el checkpoint en `position` (la del lector: próximo registro a leer)
+    /// Las salidas ya tienen que estar volcadas (flush) para que su largo sea el real
+    pub fn save(&mut self, position: &Position, records: u64, counters: &[(&str, u64)], snapshot: Option<&str>) -> Result<(), CsvToolsError> {
+        let mut outputs = BTreeMap::new();
+        for output in &self.outputs {
+            outputs.insert(output.clone(), std::fs::metadata(output)?.len());
+        }
+        let point = ResumePoint {
+            command: self.command.clone(),
+            status: "incomplete".to_string(),
+            input: self.input.clone(),
+            input_bytes: std::fs::metadata(&self.input)?.len(),
+            byte_offset: self.byte_base + position.byte(),
+            line: self.line_base + position.line(),
+            records,
+            outputs,
+            counters: counters.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            snapshot: snapshot.map(str::to_string),
+            updated_at: chrono::Local::now().to_rfc3339(),
+        };
+
+        // Escritura atómica: un corte a mitad de guardado deja el checkpoint anterior
+        let tmp = format!("{}.tmp", self.path);
+        std::fs::write(&tmp, serde_json::to_string_pretty(&point)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        self.last_saved = Instant::now();
+        verbose!("💾 Checkpoint: line {} ({} records) -> {}", point.line, records, self.path);
+        Ok(())
+    }
+
+    /// Después de guardar: si venció --max-runtime, corta la corrida como "incomplete"
+    pub fn stop_at_deadline(&self) -> Result<(), CsvToolsError> {
+        if !runtime_limit::exceeded() {
+            return Ok(());
+        }
+        eprintln!();
+        eprintln!("💾 Checkpoint saved: {}", self.path);
+        eprintln!("💡 Partial output is INCOMPLETE; run the same command again with --resume to continue");
+        Err(Incomplete { checkpoint_path: self.path.clone() }.into())
+    }
+
+    /// Corrida completa: el checkpoint y su snapshot ya no sirven
+    pub fn finish(&self) {
+        std::fs::remove_file(&self.path).ok();
+        std::fs::remove_file(self.snapshot_path()).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_save_and_resume_from_offset() {
+        let dir = TempDir::new("checkpoint_resume");
+        let input = dir.write("in.csv", "a,b\n1,x\n2,y\n3\n4,w\n");
+        let output = dir.write("out.csv", "a,b\n1,x\n");
+        let args = ResumeArgs { resume: true, checkpoint_interval: Duration::from_secs(60) };
+
+        let mut first = CheckpointManager::new("test", &input, &output, &[&output], &args);
+        let (mut reader, _) = first.open_reader(None).unwrap();
+        let mut record = StringRecord::new();
+        assert!(reader.read_record(&mut record).unwrap());
+        first.save(reader.position(), 1, &[("valid", 1)], None).unwrap();
+        std::fs::write(&output, "a,b\n1,x\n2,y\n").unwrap();
+
+        let mut second = CheckpointManager::new("test", &input, &output, &[&output], &args);
+        let point = second.load().unwrap();
+        assert_eq!((point.line, point.records, point.counter("valid")), (3, 1, 1));
+        let (mut reader, headers) = second.open_reader(Some(&point)).unwrap();
+        assert_eq!(headers, StringRecord::from(vec!["a", "b"]));
+        assert!(reader.read_record(&mut record).unwrap());
+        assert_eq!(record, StringRecord::from(vec!["2", "y"]));
+        let error = reader.read_record(&mut record).unwrap_err();
+        assert!(second.error_message(&error).starts_with("CSV error: record 3 (line: 4, byte: 12)"));
+
+        // Lo escrito después del checkpoint se descarta al retomar
+        drop(second.open_output(&output, Some(&point)).unwrap());
+        assert_eq!(dir.read("out.csv"), "a,b\n1,x\n");
+
+        second.finish();
+        assert!(second.load().is_err());
+    }
+}
@@ -0,0 +1,480 @@
+// Definición de la línea de comandos con clap: opciones globales + un subcomando
+// por comando; los argumentos propios de cada comando viven junto a su implementación
+
+use clap::{CommandFactory, Parser, Subcommand};
+
+use crate::commands::{
+    cleaning::{CoerceArgs, NormalizeEolArgs, RequoteArgs},
+    convert_ops::{FromFixedArgs, FromJsonlArgs, FromParquetArgs, ToDdbJsonArgs, ToArrowArgs, ToIonArgs, ToJsonlArgs, ToPgCopyArgs},
+    dedup_ops::{DedupByKeysArgs, DupReportArgs, OverlapArgs, ReportDuplicatesArgs},
+    edit_ops::{DeleteKeysArgs, PatchArgs},
+    error_summary::SummarizeErrorsArgs,
+    file_ops::HeaderMismatch,
+    filter_ops::{FilterDatesArgs, FilterInArgs, FilterRegexArgs},
+    inspection::{AssertArgs, DetectEncodingArgs, HeaderArgs, ProfileArgs, SniffArgs, ValidateSchemaArgs, VerifyRoundtripArgs},
+    partition_ops::PlanPartitionsArgs,
+    pipeline::PipelineArgs,
+    watch::WatchArgs,
+    prepare_ops::PrepareArgs,
+    repl::ReplArgs,
+    schema_ops::{AddColumnArgs, DropArgs, MergeReconcileArgs, MigrateArgs, RenameColumnsArgs, ReorderToModelArgs, SchemaCommand, SelectArgs},
+    sort_ops::{MergeSortedArgs, SortArgs},
+    sqlite_ops::{FromSqliteArgs, QueryArgs, ToSqliteArgs},
+    transform_ops::{ConcatColumnsArgs, MapValuesArgs, NormalizeNullsArgs, RecaseArgs, SplitColumnArgs, TrimArgs},
+};
+use crate::build_info;
+use crate::checkpoint::ResumeArgs;
+use crate::cli_args::{parse_byte_size, parse_count};
+use crate::dedup::ApproxMode;
+use crate::error::CsvToolsError;
+use crate::settings::GlobalOptions;
+
+// Entrada de los comandos multi-archivo (ver file_utils::resolve_inputs)
+pub const FILES_HELP: &str = "List file (one path per line), directory (its .csv/.csv.gz files) or quoted glob like 'data/part_*.csv'";
+const SOURCE_COLUMN_HELP: &str = "Append a column with this name holding the file each row came from";
+const LINE_COLUMN_HELP: &str = "Append a column with this name holding the row's line number in its file";
+const ROW_NUMBER_HELP: &str = "Append a column with this name holding the row's line number in the input (header = 1, same as the error log)";
+const MERGE_REPORT_HELP: &str = "Write a JSON report: lines and duplicates per file, header check, timings";
+
+#[derive(Parser, Debug)]
+#[command(name = "csv_tools", about = "CSV Tools - DynamoDB & Data Processing", disable_help_subcommand = true,
+          version = build_info::VERSION, long_version = build_info::LONG_VERSION)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalOptions,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    // --- DynamoDB ---
+    #[command(name = "sanitize_dynamodb", about = "Sanitize a CSV for DynamoDB ImportTable (header quotes, Type N values)")]
+    SanitizeDynamodb {
+        #[arg(value_name = "INPUT.csv")]
+        input: String,
+        #[arg(value_name = "OUTPUT.csv")]
+        output: String,
+        #[arg(value_name = "MODEL_TYPE", help = "DynamoDB model [default: default_model from the config file]")]
+        model_type: Option<String>,
+        #[arg(long, value_name = "SCHEMA.yaml", help = "Empty Type N values allowed only in nullable columns (filled with default)")]
+        schema: Option<String>,
+        #[arg(long, value_name = "NAME", help = ROW_NUMBER_HELP)]
+        add_row_number: Option<String>,
+    },
+    #[command(name = "validate_schema", about = "Validate header format and Type N values against a model")]
+    ValidateSchema(ValidateSchemaArgs),
+    #[command(name = "validate_dynamodb_csv", about = "Check that a CSV is ready for DynamoDB ImportTable (header, Type N values)")]
+    ValidateDynamodbCsv {
+        #[arg(value_name = "INPUT.csv")]
+        input: String,
+        #[arg(value_name = "MODEL_TYPE", help = "DynamoDB model [default: default_model from the config file]")]
+        model_type: Option<String>,
+        #[arg(long, value_name = "REPORT.html", help = "Write an HTML report (errors per column, sample bad rows, charts)")]
+        report_html: Option<String>,
+    },
+    #[command(about = "validate_schema + sanitize_dynamodb + key dedup reading the input only once")]
+    Prepare(PrepareArgs),
+    #[command(name = "sanitize_dynamodb_auto", about = "Sanitize for DynamoDB keeping the header as is; every dropped row is logged")]
+    SanitizeDynamodbAuto {
+        #[arg(value_name = "INPUT.csv")]
+        input: String,
+        #[arg(value_name = "OUTPUT.csv")]
+        output: String,
+        #[arg(value_name = "MODEL_TYPE", help = "DynamoDB model [default: default_model from the config file]")]
+        model_type: Option<String>,
+        #[arg(long, value_name = "N", help = "Expected column count [default: from the model]")]
+        expected_columns: Option<usize>,
+        #[arg(long, value_name = "REPORT.html", help = "Write an HTML report (errors per column, sample bad rows, charts)")]
+        report_html: Option<String>,
+        #[arg(long, value_name = "NAME", help = ROW_NUMBER_HELP)]
+        add_row_number: Option<String>,
+    },
+    #[command(name = "parse_keys", about = "Extract and display DynamoDB keys (PartitionKey + SortKey)")]
+    ParseKeys {
+        #[arg(value_name = "INPUT.csv")]
+        input: String,
+        #[arg(value_name = "MODEL_TYPE", help = "DynamoDB model [default: default_model from the config file]")]
+        model_type: Option<String>,
+    },
+    #[command(name = "convert_date", about = "Convert dd/MM/yyyy, MM/dd/yyyy and ISO dates to yyyy-MM-ddTHH:mm:ss")]
+    ConvertDate {
+        #[arg(value_name = "INPUT.csv")]
+        input: String,
+        #[arg(value_name = "OUTPUT.csv")]
+        output: String,
+        #[arg(value_name = "DATE_COLUMN")]
+        date_column: String,
+    },
+    #[command(name = "delete_from_row", about = "Delete all rows from a row number (header = 1) to the end of file")]
+    DeleteFromRow {
+        #[arg(value_name = "INPUT.csv")]
+        input: String,
+        #[arg(value_name = "OUTPUT.csv")]
+        output: String,
+        #[arg(value_name = "ROW_NUMBER", value_parser = clap::value_parser!(u64).range(1..))]
+        row_number: u64,
+    },
+    #[command(about = "Copy the header plus a range of rows (header = row 1) to a new file")]
+    Slice {
+        #[arg(value_name = "INPUT.csv[.gz]")]
+        input: String,
+        #[arg(value_name = "OUTPUT.csv[.gz]")]
+        output: String,
+        #[arg(long, value_name = "ROW", value_parser = parse_count, help = "First row copied (2 = first data row; accepts 1_500_000)")]
+        from: usize,
+        #[arg(long, value_name = "ROW", value_parser = parse_count, help = "Last row copied, inclusive [default: end of file]")]
+        to: Option<usize>,
+    },
+
+    // --- Inspección / calidad ---
+    #[command(about = "Print header columns with types inferred from a sample")]
+    Header(HeaderArgs),
+    #[command(about = "Cheap CI gate: exit 1 with a one-line reason per failed assertion")]
+    Assert(AssertArgs),
+    #[command(about = "Per-column empty counts and approximate percentiles of numeric columns")]
+    Profile(ProfileArgs),
+    #[command(about = "Detect delimiter, quote char, header presence and column count from the first KB")]
+    Sniff(SniffArgs),
+    #[command(name = "detect_encoding", about = "Report the probable character encoding (UTF-8, Latin-1, UTF-16LE) of a file")]
+    DetectEncoding(DetectEncodingArgs),
+    #[command(name = "verify-roundtrip", about = "Rewrite with no rule changes and report where the output differs")]
+    VerifyRoundtrip(VerifyRoundtripArgs),
+    #[command(name = "dup-report", about = "Top-K keys by duplicate count with example lines and differing columns")]
+    DupReport(DupReportArgs),
+    #[command(name = "report_duplicates", about = "List every duplicated key with its occurrence count and line numbers")]
+    ReportDuplicates(ReportDuplicatesArgs),
+    #[command(about = "Count keys present in both files, with examples (no output file)")]
+    Overlap(OverlapArgs),
+    #[command(name = "dedup_by_keys", about = "Remove rows with repeated key columns (any header); keep first or last")]
+    DedupByKeys(DedupByKeysArgs),
+    #[command(name = "plan-partitions", about = "Balanced key ranges as JSON for parallel import workers")]
+    PlanPartitions(PlanPartitionsArgs),
+    #[command(name = "summarize-errors", about = "Aggregate an error log / validation report into counts")]
+    SummarizeErrors(SummarizeErrorsArgs),
+    #[command(about = "Interactive session over one file: head, tail, row, filter, stats without re-reading it")]
+    Repl(ReplArgs),
+
+    // --- Transformación ---
+    #[command(about = "Rewrite columns into their schema-declared canonical representation")]
+    Coerce(CoerceArgs),
+    #[command(about = "Replace individual cells listed in a fixes file, with an old→new audit log")]
+    Patch(PatchArgs),
+    #[command(name = "delete-keys", about = "Remove every row whose key is in a list")]
+    DeleteKeys(DeleteKeysArgs),
+    #[command(about = "Transform an old file layout into a target schema")]
+    Migrate(MigrateArgs),
+    #[command(name = "reorder-to-model", about = "Put columns in the exact order of the model")]
+    ReorderToModel(ReorderToModelArgs),
+    #[command(about = "Keep only the given columns, in the given order")]
+    Select(SelectArgs),
+    #[command(about = "Remove the given columns and keep the rest in order")]
+    Drop(DropArgs),
+    #[command(name = "rename_columns", about = "Rename header columns (old=new) without touching the rows")]
+    RenameColumns(RenameColumnsArgs),
+    #[command(name = "add_column", about = "Append columns with a constant value on every row")]
+    AddColumn(AddColumnArgs),
+    #[command(name = "map_values", about = "Replace the values of a column using an old→new mapping file")]
+    MapValues(MapValuesArgs),
+    #[command(about = "Strip leading/trailing whitespace from values (optionally collapse internal runs)")]
+    Trim(TrimArgs),
+    #[command(about = "Convert selected columns to upper, lower or title case")]
+    Recase(RecaseArgs),
+    #[command(name = "normalize_nulls", about = "Convert assorted null markers (NULL, N/A, -) to one representation")]
+    NormalizeNulls(NormalizeNullsArgs),
+    #[command(name = "concat_columns", about = "Append a column joining other columns with a separator (composite keys)")]
+    ConcatColumns(ConcatColumnsArgs),
+    #[command(name = "split_column", about = "Split one column into several by a separator, with a policy for odd part counts")]
+    SplitColumn(SplitColumnArgs),
+    #[command(name = "normalize_eol", about = "Convert mixed CRLF/LF/CR line endings to LF or CRLF in one pass")]
+    NormalizeEol(NormalizeEolArgs),
+    #[command(about = "Rewrite the file with another quoting style: necessary, always, never or non_numeric")]
+    Requote(RequoteArgs),
+    #[command(about = "Schema utilities", subcommand_required = true)]
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommand,
+    },
+
+    // --- SQLite ---
+    #[command(name = "to-sqlite", about = "Load a CSV into a SQLite table for ad-hoc queries")]
+    ToSqlite(ToSqliteArgs),
+    #[command(name = "from-sqlite", about = "Export a SQLite table or query result to CSV")]
+    FromSqlite(FromSqliteArgs),
+    #[command(about = "Run SQL over a CSV (staged as temporary table 't')")]
+    Query(QueryArgs),
+
+    // --- Conversión de formatos ---
+    #[command(name = "to_jsonl", about = "Convert a CSV to JSON Lines (one object per row, header names as keys)")]
+    ToJsonl(ToJsonlArgs),
+    #[command(name = "to_ddbjson", about = "Convert CSV to DynamoDB JSON items for ImportTable using the model's types")]
+    ToDdbJson(ToDdbJsonArgs),
+    #[command(name = "to_ion", about = "Convert CSV to Amazon Ion items for ImportTable using the model's types")]
+    ToIon(ToIonArgs),
+    #[command(name = "to_pgcopy", about = "Convert CSV to Postgres COPY text format plus a CREATE TABLE / \\copy script")]
+    ToPgCopy(ToPgCopyArgs),
+    #[command(name = "to_arrow", about = "Convert CSV to a typed Arrow IPC (Feather v2) file for pandas / polars")]
+    ToArrow(ToArrowArgs),
+    #[command(name = "from_jsonl", about = "Convert JSON Lines to CSV (top-level keys as columns)")]
+    FromJsonl(FromJsonlArgs),
+    #[command(name = "from_parquet", about = "Convert a Parquet file to CSV (top-level fields as columns)")]
+    FromParquet(FromParquetArgs),
+    #[command(name = "from_fixed", about = "Convert a fixed-width file to CSV using a YAML spec of byte ranges")]
+    FromFixed(FromFixedArgs),
+
+    // --- Pipeline ---
+    #[command(about = "Run the steps of a YAML plan in order",
+              args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Pipeline(PipelineArgs),
+    #[command(about = "Watch a directory and run a pipeline plan on each new CSV file")]
+    Watch(WatchArgs),
+
+    // --- Legacy ---
+    #[command(about = "Clean duplicate headers from a CSV file")]
+    Clean {
+        #[arg(help = "Input file, '-' for stdin")]
+        input: String,
+        #[arg(help = "Output file, '-' for stdout")]
+        output: String,
+        #[arg(long, value_name = "NAME", help = ROW_NUMBER_HELP)]
+        add_row_number: Option<String>,
+    },
+    #[command(about = "Filter rows based on a column value")]
+    Filter {
+        #[arg(help = "Input file, '-' for stdin")]
+        input: String,
+        #[arg(help = "Output file or sink URI, '-' for stdout")]
+        output: String,
+        column: String,
+        value: String,
+        #[arg(long, help = "Remove the rows whose column equals VALUE and keep the rest")]
+        invert: bool,
+    },
+    #[command(name = "filter_in", about = "Keep rows whose column value is in a list file (one value per line)")]
+    FilterIn(FilterInArgs),
+    #[command(name = "filter_regex", about = "Keep rows whose column value matches a regular expression")]
+    FilterRegex(FilterRegexArgs),
+    #[command(name = "filter_dates", about = "Keep rows whose date column falls in a range (any format the schema accepts)")]
+    FilterDates(FilterDatesArgs),
+    #[command(about = "Print the header and the first N rows (stdout)")]
+    Head {
+        #[arg(value_name = "INPUT.csv", help = "Input file, '-' for stdin")]
+        input: String,
+        #[arg(value_name = "N", default_value_t = 10)]
+        num_rows: usize,
+        #[arg(long, value_name = "N", default_value_t = 40, help = "With --format markdown: truncate longer fields to N characters")]
+        max_width: usize,
+    },
+    #[command(about = "Print the header and the last N rows (stdout)")]
+    Tail {
+        #[arg(value_name = "INPUT.csv", help = "Input file, '-' for stdin")]
+        input: String,
+        #[arg(value_name = "N", default_value_t = 10)]
+        num_rows: usize,
+        #[arg(long, value_name = "N", default_value_t = 40, help = "With --format markdown: truncate longer fields to N characters")]
+        max_width: usize,
+    },
+    #[command(name = "validate_model", about = "Validate every record against a DynamoDB model, logging errors to a file")]
+    ValidateModel {
+        #[arg(value_name = "INPUT.csv")]
+        input: String,
+        #[arg(value_name = "ERROR_FILE", help = "CSV log of errors (line, type, details, keys)")]
+        error_file: String,
+        #[arg(value_name = "MODEL_TYPE")]
+        model_type: String,
+        #[arg(value_name = "MAX_ERRORS_TO_SHOW", default_value_t = 10)]
+        max_show: usize,
+        #[arg(value_name = "CANCEL_ON_MAX", default_value_t = false, action = clap::ArgAction::Set,
+              help = "true: stop after MAX_ERRORS_TO_SHOW errors")]
+        cancel_on_max: bool,
+        #[command(flatten)]
+        resume: ResumeArgs,
+    },
+    #[command(name = "clean_invalid_lines", about = "Keep only rows with the header's column count; log the rest")]
+    CleanInvalidLines {
+        #[arg(value_name = "INPUT.csv", help = "Input file, '-' for stdin")]
+        input: String,
+        #[arg(value_name = "OUTPUT.csv", help = "Clean output, '-' for stdout")]
+        output: String,
+        #[arg(value_name = "ERROR_FILE", help = "CSV log of removed rows (line, issue, keys)")]
+        error_file: String,
+        #[arg(long, value_name = "NAME", help = ROW_NUMBER_HELP)]
+        add_row_number: Option<String>,
+        #[command(flatten)]
+        resume: ResumeArgs,
+    },
+    #[command(about = "Check for duplicate headers in a CSV file")]
+    Check {
+        input: String,
+    },
+    #[command(about = "Count the number of lines in a CSV file")]
+    Count {
+        input: String,
+    },
+    #[command(name = "count_all", about = "Count lines in multiple files (list file, directory or glob)")]
+    CountAll {
+        #[arg(value_name = "FILES", help = FILES_HELP)]
+        file_list: String,
+    },
+    #[command(name = "count_unique", about = "Count unique records across multiple files (in memory)")]
+    CountUnique {
+        #[arg(value_name = "FILES", help = FILES_HELP)]
+        file_list: String,
+    },
+    #[command(about = "Concatenate multiple CSV files keeping the first header (no deduplication)")]
+    Merge {
+        #[arg(value_name = "FILES", help = FILES_HELP)]
+        file_list: String,
+        #[arg(value_name = "OUTPUT[.gz]")]
+        output: String,
+        #[arg(long, value_name = "NAME", help = SOURCE_COLUMN_HELP)]
+        add_source_column: Option<String>,
+        #[arg(long, value_name = "NAME", alias = "add-row-number", help = LINE_COLUMN_HELP)]
+        add_line_column: Option<String>,
+        #[arg(long, value_name = "SCHEMA.yaml|MODEL", help = "Expected header [default: header of the first file]")]
+        schema: Option<String>,
+        #[arg(long, value_enum, default_value_t = HeaderMismatch::Abort, help = "Files whose header differs from the expected one")]
+        on_mismatch: HeaderMismatch,
+        #[arg(long, value_name = "merge_report.json", help = MERGE_REPORT_HELP)]
+        report: Option<String>,
+    },
+    #[command(name = "merge_reconcile", about = "Merge CSVs mapping columns by header name (reordered, missing or extra columns)")]
+    MergeReconcile(MergeReconcileArgs),
+    #[command(name = "merge_sorted", about = "Streaming k-way merge of files already sorted by a key (O(k) memory)")]
+    MergeSorted(MergeSortedArgs),
+    #[command(name = "sort", about = "Sort a CSV by one or more columns (string, numeric or date), spilling to disk past --memory-limit")]
+    Sort(SortArgs),
+    #[command(about = "Remove exact duplicate rows (all columns, in memory); first occurrence wins")]
+    Deduplicate {
+        input: String,
+        output: String,
+    },
+    #[command(name = "deduplicate_dynamodb", about = "Remove duplicate DynamoDB keys (partition + sort key, in memory); last occurrence wins")]
+    DeduplicateDynamodb {
+        #[arg(value_name = "INPUT.csv")]
+        input: String,
+        #[arg(value_name = "OUTPUT.csv")]
+        output: String,
+        #[arg(value_name = "MODEL_TYPE", help = "DynamoDB model [default: default_model from the config file]")]
+        model_type: Option<String>,
+        #[command(flatten)]
+        resume: ResumeArgs,
+    },
+    #[command(name = "merge_dedup", about = "Merge multiple CSV files and remove duplicates (in memory)")]
+    MergeDedup {
+        #[arg(value_name = "FILES", help = FILES_HELP)]
+        file_list: String,
+        #[arg(value_name = "OUTPUT[.gz]")]
+        output: String,
+        #[arg(long, value_name = "N", default_value_t = 1, help = "Parallel gzip blocks for a .gz output")]
+        compress_threads: usize,
+        #[arg(long, value_enum, help = "Approximate dedup in constant memory (bloom: a few unique lines may be dropped)")]
+        approx: Option<ApproxMode>,
+        #[arg(long, value_name = "P", default_value_t = 0.001, requires = "approx",
+              help = "Target false-positive probability for --approx bloom")]
+        fpp: f64,
+        #[arg(long, conflicts_with = "approx", help = "Keep a 128-bit xxh3 digest per line instead of the full line (~10x less RAM)")]
+        hash_only: bool,
+        #[arg(long, requires = "hash_only", help = "Second pass over the inputs to detect digest collisions")]
+        verify_hashes: bool,
+        #[arg(long, value_name = "SIZE", value_parser = parse_byte_size, conflicts_with = "approx",
+              help = "Cap for the in-memory seen set (e.g. 4G); past it new lines are deduplicated in on-disk hash buckets")]
+        max_memory: Option<usize>,
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["resume", "verify_hashes"], help = SOURCE_COLUMN_HELP)]
+        add_source_column: Option<String>,
+        #[arg(long, value_name = "NAME", alias = "add-row-number", conflicts_with_all = ["resume", "verify_hashes"], help = LINE_COLUMN_HELP)]
+        add_line_column: Option<String>,
+        #[arg(long, value_name = "N", default_value_t = 1, conflicts_with_all = ["resume", "max_memory", "approx"],
+              help = "Worker threads, each deduplicating the lines whose hash falls in its shard (output grouped by shard)")]
+        threads: usize,
+        #[arg(long, conflicts_with_all = ["resume", "approx", "max_memory", "threads"],
+              help = "Write nothing; report per input file how many rows are duplicates within it or of earlier files")]
+        preview: bool,
+        #[arg(long, value_name = "merge_report.json", conflicts_with = "preview", help = MERGE_REPORT_HELP)]
+        report: Option<String>,
+        #[arg(long, help = "Continue a run stopped by --max-runtime from <output>.checkpoint.json")]
+        resume: bool,
+    },
+    #[command(name = "external_dedup", about = "Merge and deduplicate using an external sort (huge files)")]
+    ExternalDedup {
+        #[arg(value_name = "FILES", help = FILES_HELP)]
+        file_list: String,
+        output: String,
+        #[arg(long, value_name = "DIR", help = "Directory for the temp files [default: output directory]")]
+        workdir: Option<String>,
+        #[arg(long, value_name = "SIZE", default_value = "1GB", value_parser = parse_byte_size,
+              help = "RAM for each in-memory sorted run before it is spilled to disk (e.g. 512MB, 4GB)")]
+        memory_limit: usize,
+        #[arg(long, value_name = "merge_report.json", help = MERGE_REPORT_HELP)]
+        report: Option<String>,
+        #[arg(long, help = "Continue a run stopped by --max-runtime from <output>.checkpoint.json")]
+        resume: bool,
+    },
+    #[command(name = "estimate_memory", about = "Estimate RAM needed for in-memory deduplication")]
+    EstimateMemory {
+        #[arg(value_name = "FILES", help = FILES_HELP)]
+        file_list: String,
+    },
+    #[command(about = "Compare the first N rows of two CSV files")]
+    Compare {
+        file1: String,
+        file2: String,
+        num_rows: usize,
+    },
+
+    #[command(about = "Print version, build number, build date, target and compiled-in features")]
+    Version,
+    #[command(about = "Print the command guide, or the detailed help of one command")]
+    Help {
+        command: Option<String>,
+    },
+}
+
+/// argv sin las opciones globales (el subcomando queda en args[1]): es lo que
+/// registran el reporte de reproducibilidad y el manifiesto
+pub fn command_argv(args: &[String]) -> Vec<String> {
+    let cli = Cli::command();
+    let globals: Vec<(String, bool)> = cli.get_arguments()
+        .filter(|a| a.is_global_set())
+        .filter_map(|a| a.get_long().map(|long| (format!("--{}", long), a.get_action().takes_values())))
+        .collect();
+
+    let mut result = Vec::with_capacity(args.len());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let name = arg.split_once('=').map_or(arg.as_str(), |(n, _)| n);
+        match globals.iter().find(|(long, _)| long == name) {
+            Some((_, takes_value)) => {
+                if *takes_value && !arg.contains('=') {
+                    iter.next();
+                }
+            }
+            None => result.push(arg.clone()),
+        }
+    }
+    result
+}
+
+/// El comando de `args` escribe sus datos a stdout (OUTPUT posicional = `-`): los
+/// mensajes de estado tienen que ir a stderr para no mezclarse con el CSV
+pub fn writes_stdout(args: &[String]) -> bool {
+    let Ok(matches) = Cli::command().try_get_matches_from(args) else {
+        return false;
+    };
+    let Some((_, sub)) = matches.subcommand() else {
+        return false;
+    };
+    sub.try_get_raw("output").ok().flatten()
+        .is_some_and(|mut values| values.any(|v| v == crate::file_utils::STDIO))
+}
+
+/// Ayuda detallada (generada por clap) de un subcomando
+pub fn print_command_help(name: &str) -> Result<(), CsvToolsError> {
+    if Cli::command().find_subcommand(name).is_none() {
+        return Err(format!("Unknown command: {}", name).into());
+    }
+    if let Err(e) = Cli::try_parse_from(["csv_tools", name, "--help"]) {
+        e.print()?;
+    }
+    Ok(())
+}
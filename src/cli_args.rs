@@ -0,0 +1,57 @@
+// Helpers compartidos por los `Args` de clap de cada comando: listas separadas
+// por comas y validadores de valores que clap no trae
+
+/// Valores de un flag lista (`--keys A,B`, `value_delimiter = ','`) sin espacios
+/// alrededor ni elementos vacíos (`"A, B,"` → `["A", "B"]`)
+pub fn list_values(values: &[String]) -> Vec<&str> {
+    values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()).collect()
+}
+
+/// Cantidad no negativa; acepta separadores `_` (`1_000_000`)
+pub fn parse_count(text: &str) -> Result<usize, String> {
+    text.replace('_', "").parse::<usize>()
+        .map_err(|_| format!("invalid count '{}' (expected unsigned integer)", text))
+}
+
+/// Tamaño en bytes con sufijo opcional KB / MB / GB (base 1024; `512MB`, `2G`, `1048576`)
+pub fn parse_byte_size(text: &str) -> Result<usize, String> {
+    let upper = text.trim().to_uppercase();
+    let number = upper.trim_end_matches('B');
+    let (digits, multiplier) = match number.chars().last() {
+        Some('K') => (&number[..number.len() - 1], 1usize << 10),
+        Some('M') => (&number[..number.len() - 1], 1 << 20),
+        Some('G') => (&number[..number.len() - 1], 1 << 30),
+        _ => (number, 1),
+    };
+    match digits.trim().replace('_', "").parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n * multiplier),
+        _ => Err(format!("invalid size '{}' (expected e.g. 512MB, 2GB)", text)),
+    }
+}
+
+/// Percentil 0-100, con o sin prefijo `p` (`95`, `p99`, `99.9`)
+pub fn parse_percentile(text: &str) -> Result<f64, String> {
+    let text = text.trim();
+    match text.trim_start_matches(['p', 'P']).parse::<f64>() {
+        Ok(n) if (0.0..=100.0).contains(&n) => Ok(n),
+        _ => Err(format!("invalid percentile '{}' (expected 0-100)", text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_and_value_parsers() {
+        let values = vec!["Cuil".to_string(), " IdTransmit".to_string(), "".to_string()];
+        assert_eq!(list_values(&values), vec!["Cuil", "IdTransmit"]);
+        assert_eq!(parse_count("1_000"), Ok(1000));
+        assert!(parse_count("-1").is_err());
+        assert_eq!(parse_byte_size("512MB"), Ok(512 << 20));
+        assert_eq!(parse_byte_size("2g"), Ok(2 << 30));
+        assert!(parse_byte_size("0").is_err());
+        assert_eq!(parse_percentile("p99"), Ok(99.0));
+        assert!(parse_percentile("101").is_err());
+    }
+}
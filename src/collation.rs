@@ -0,0 +1,124 @@
+// Collation para comparar claves al ordenar y deduplicar (`--collation`)
+// es_AR reproduce el orden del export SQL (Modern_Spanish_CI_AS): sin distinguir
+// mayúsculas, acentos como diferencia secundaria y Ñ como letra entre N y O
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Collation {
+    /// Orden por bytes (el de DynamoDB y `LC_ALL=C`)
+    #[default]
+    Binary,
+    CaseInsensitive,
+    EsAr,
+}
+
+static COLLATION: OnceLock<Collation> = OnceLock::new();
+
+/// Collation efectiva de la corrida (binary si no se pasa `--collation`)
+pub fn current() -> Collation {
+    *COLLATION.get().unwrap_or(&Collation::Binary)
+}
+
+/// Fija la collation global (una sola vez, antes de ejecutar el comando)
+pub fn set_current(collation: Collation) {
+    COLLATION.set(collation).ok();
+}
+
+impl TryFrom<&str> for Collation {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "binary" | "c" => Ok(Collation::Binary),
+            "case_insensitive" | "ci" => Ok(Collation::CaseInsensitive),
+            "es_ar" | "es" => Ok(Collation::EsAr),
+            _ => Err(format!("Unknown collation '{}' (use es_AR, binary, case-insensitive)", value)),
+        }
+    }
+}
+
+impl fmt::Display for Collation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Collation::Binary => write!(f, "binary"),
+            Collation::CaseInsensitive => write!(f, "case-insensitive"),
+            Collation::EsAr => write!(f, "es_AR"),
+        }
+    }
+}
+
+impl Collation {
+    /// Forma normalizada para deduplicar: dos claves son iguales si sus formas lo son
+    /// (es_AR no distingue mayúsculas pero sí acentos, como CI_AS)
+    pub fn dedup_key<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        match self {
+            Collation::Binary => Cow::Borrowed(value),
+            Collation::CaseInsensitive | Collation::EsAr => Cow::Owned(value.to_lowercase()),
+        }
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Collation::Binary => a.cmp(b),
+            Collation::CaseInsensitive => a.to_lowercase().cmp(&b.to_lowercase())
+                .then_with(|| a.cmp(b)),
+            Collation::EsAr => {
+                let (la, lb) = (a.to_lowercase(), b.to_lowercase());
+                // Primario: letras sin acentos (ñ aparte); secundario: acentos
+                la.chars().map(spanish_weight).cmp(lb.chars().map(spanish_weight))
+                    .then_with(|| la.chars().map(accent_rank).cmp(lb.chars().map(accent_rank)))
+                    .then_with(|| a.cmp(b))
+            }
+        }
+    }
+}
+
+/// Peso primario de una letra minúscula en el orden español
+fn spanish_weight(c: char) -> u32 {
+    let base = match c {
+        'á' | 'à' | 'â' | 'ä' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'ö' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        // Ñ va después de todas las N
+        'ñ' => return ('n' as u32) * 2 + 1,
+        other => other,
+    };
+    (base as u32) * 2
+}
+
+/// Rango secundario: letra sin acento antes que con acento
+fn accent_rank(c: char) -> u8 {
+    match c {
+        'á' | 'é' | 'í' | 'ó' | 'ú' => 1,
+        'à' | 'è' | 'ì' | 'ò' | 'ù' => 2,
+        'â' | 'ê' | 'î' | 'ô' | 'û' => 3,
+        'ä' | 'ë' | 'ï' | 'ö' | 'ü' => 4,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_es_ar_ordering() {
+        let mut names = vec!["Oliva", "Ñandú", "nuñez", "Núñez", "Nunez", "Nuno", "ANDRADE", "álvarez"];
+        names.sort_by(|a, b| Collation::EsAr.compare(a, b));
+        assert_eq!(names, vec!["álvarez", "ANDRADE", "Nunez", "Nuno", "nuñez", "Núñez", "Ñandú", "Oliva"]);
+
+        let mut binary = vec!["b", "Ñ", "a", "B"];
+        binary.sort_by(|a, b| Collation::Binary.compare(a, b));
+        assert_eq!(binary, vec!["B", "a", "b", "Ñ"]);
+
+        assert_eq!(Collation::EsAr.dedup_key("PÉREZ"), Collation::EsAr.dedup_key("pérez"));
+        assert_ne!(Collation::EsAr.dedup_key("Perez"), Collation::EsAr.dedup_key("Pérez"));
+        assert_eq!(Collation::try_from("es-AR"), Ok(Collation::EsAr));
+    }
+}
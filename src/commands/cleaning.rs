@@ -1,64 +1,86 @@
-use std::error::Error;
 use std::fs::File;
-use csv::{ReaderBuilder, WriterBuilder};
+use std::io::{BufRead, BufWriter, Write};
+use csv::{ReaderBuilder, StringRecord};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use crate::compression;
+use crate::config;
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::error_report::{self, ErrorRecord};
+use crate::exit_code::{data_issues, usage_error};
+use crate::file_utils::open_input;
+use crate::html_report::ValidationReport;
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
 use crate::models::DynamoDbModel;
+use crate::schema::{coerce_value, ColumnSpec, ColumnType, Schema};
+use crate::sink::open_sink;
 
 /// Sanitize CSV for DynamoDB ImportTable
 /// - Removes quotes from header row
 /// - Validates numeric fields (Type N)
 /// - Preserves quoted strings for Type S fields
 /// - Compatible with SiisaRestApi chunk-export-v2 output
+///
+/// Con `schema`, los vacíos en campos Type N siguen la columna del schema: `nullable`
+/// los preserva (o completa con `default`) y el resto descarta la fila
 pub fn sanitize_dynamodb(
     input_path: &str,
     output_path: &str,
     model_type: &str,
-) -> Result<(), Box<dyn Error>> {
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  CSV Sanitization for DynamoDB ImportTable                   ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
+    schema: Option<&Schema>,
+    row_number: Option<&str>,
+) -> Result<(), CsvToolsError> {
+    logging::banner("CSV Sanitization for DynamoDB ImportTable");
     
-    println!("📄 Input:  {}", input_path);
-    println!("📄 Output: {}", output_path);
-    println!("📋 Model:  {}", model_type);
+    info!("📄 Input:  {}", input_path);
+    info!("📄 Output: {}", output_path);
+    info!("📋 Model:  {}", model_type);
+    if let Some(s) = schema {
+        info!("📋 Schema: {} (empty Type N values follow `nullable`)", s.name);
+    }
     
     // ✅ FIX: Usar DynamoDbModel::from_model_type() que soporta todos los modelos
     let model = DynamoDbModel::from_model_type(model_type)
-        .ok_or_else(|| format!(
+        .ok_or_else(|| usage_error(format!(
             "Unknown model type: '{}'\n\
              Supported: siisa_morosos, personas_telefonos, siisa_empleadores, siisa_empleadores_relaciones",
             model_type
-        ))?;
+        )))?;
     
     // ✅ FIX: Usar model.expected_columns (10 para empleadores, 14 para morosos)
-    println!("🔢 Expected Columns: {}", model.expected_columns);
-    println!("🔧 Strategy: CsvHelper-based parsing + validate numeric fields");
-    println!();
+    info!("🔢 Expected Columns: {}", model.expected_columns);
+    info!("🔧 Strategy: CsvHelper-based parsing + validate numeric fields");
+    info!();
     
     // Read input CSV
-    let input_file = File::open(input_path)?;
+    let input_file = open_input(input_path)?;
     let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .has_headers(true)
         .flexible(true)
         .from_reader(input_file);
     
     // Get headers
     let headers = reader.headers()?;
-    let header_str = headers.iter()
+    let mut clean_header: StringRecord = headers.iter()
         .map(|h| h.trim_matches('"'))  // Remove quotes if present
-        .collect::<Vec<_>>()
-        .join(",");
+        .collect();
+    let header_str = clean_header.iter().collect::<Vec<_>>().join(",");
     
-    println!("🔍 DEBUG: Raw header from input CSV:");
-    println!("   '{}'", headers.iter().collect::<Vec<_>>().join(","));
-    println!();
+    info!("🔍 DEBUG: Raw header from input CSV:");
+    info!("   '{}'", headers.iter().collect::<Vec<_>>().join(","));
+    info!();
     
-    println!("🔍 DEBUG: Clean header to be written:");
-    println!("   '{}'", header_str);
-    println!();
+    info!("🔍 DEBUG: Clean header to be written:");
+    info!("   '{}'", header_str);
+    info!();
     
     // Validate header count
     if headers.len() != model.expected_columns {
-        return Err(format!(
+        return Err(data_issues(format!(
             "Header mismatch: expected {} columns for {}, found {}\n\
              Expected: {:?}\n\
              Got: {:?}",
@@ -67,44 +89,66 @@ pub fn sanitize_dynamodb(
             headers.len(),
             crate::models::get_expected_headers(model_type)?,
             headers.iter().collect::<Vec<_>>()
-        ).into());
+        )));
     }
     
-    // Create output CSV
-    let output_file = File::create(output_path)?;
-    let mut writer = WriterBuilder::new()
-        .has_headers(false)  // We'll write header manually
-        .quote_style(csv::QuoteStyle::Necessary)
-        .from_writer(output_file);
+    // Columna con la línea de origen (misma numeración que los avisos "Line N")
+    if let Some(name) = row_number {
+        if clean_header.iter().any(|h| h.trim() == name) {
+            return Err(usage_error(format!("Column '{}' already exists in the header", name)));
+        }
+        clean_header.push_field(name);
+    }
+
+    // Create output (CSV o destino registrado por esquema de URI)
+    let mut writer = open_sink(output_path)?;
     
     // ✅ Write header WITHOUT quotes
-    writer.write_record(header_str.split(','))?;
-    println!("✅ Header written without quotes");
-    println!();
+    writer.write_header(&clean_header)?;
+    info!("✅ Header written without quotes");
+    info!();
     
     // Process records
-    println!("🔍 Processing records...");
+    info!("🔍 Processing records...");
     let mut processed = 0;
     let mut valid = 0;
     let mut invalid = 0;
+    let mut defaulted = 0;
     
     for result in reader.records() {
-        let record = result?;
+        let mut record = result?;
         processed += 1;
+        // Línea de la entrada donde empieza el registro (un campo multilínea ocupa varias)
+        let line = record.position().map_or(processed as u64 + 1, |p| p.line());
         
         // Validate numeric fields (Type N in DynamoDB)
         let mut is_valid = true;
+        let mut fills: Vec<(usize, &str)> = Vec::new();
         
         for &field_name in &model.numeric_fields {
             if let Some(&col_idx) = model.column_mapping.get(field_name) {
                 if let Some(value) = record.get(col_idx) {
                     let trimmed = value.trim().trim_matches('"');
                     
+                    // Vacíos: sin schema se preservan; con schema decide la columna
+                    if trimmed.is_empty() {
+                        match schema.and_then(|s| s.column(field_name)).map(ColumnSpec::empty_value) {
+                            Some(Some(fill)) if !fill.is_empty() => fills.push((col_idx, fill)),
+                            Some(None) => {
+                                eprintln!(
+                                    "⚠️  Line {}: Empty value for non-nullable {} (Type N)",
+                                    line,
+                                    field_name
+                                );
+                                is_valid = false;
+                            }
+                            _ => {}
+                        }
                     // ✅ Validar que sea número válido
-                    if !trimmed.is_empty() && trimmed.parse::<f64>().is_err() {
+                    } else if trimmed.parse::<f64>().is_err() {
                         eprintln!(
                             "⚠️  Line {}: Invalid numeric value for {} (Type N): '{}'",
-                            processed + 1,
+                            line,
                             field_name,
                             trimmed
                         );
@@ -115,6 +159,16 @@ pub fn sanitize_dynamodb(
         }
         
         if is_valid {
+            if !fills.is_empty() {
+                defaulted += fills.len();
+                record = record.iter()
+                    .enumerate()
+                    .map(|(i, v)| fills.iter().find(|(idx, _)| *idx == i).map_or(v, |(_, fill)| *fill))
+                    .collect();
+            }
+            if row_number.is_some() {
+                record.push_field(&line.to_string());
+            }
             // Write record (CsvHelper handles quoting automatically)
             writer.write_record(&record)?;
             valid += 1;
@@ -124,30 +178,43 @@ pub fn sanitize_dynamodb(
         
         // Progress reporting (cada 10,000 registros)
         if processed % 10000 == 0 {
-            println!("   ✅ Processed: {} | Valid: {} | Invalid: {}", 
+            info!("   ✅ Processed: {} | Valid: {} | Invalid: {}", 
                      processed, valid, invalid);
         }
     }
     
-    writer.flush()?;
+    writer.finish()?;
     
-    println!();
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Sanitization Summary                                        ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Total processed: {}", processed);
-    println!("✅ Valid records:   {}", valid);
-    println!("❌ Invalid records: {}", invalid);
-    println!();
+    info!();
+    logging::banner("Sanitization Summary");
+    info!("📊 Total processed: {}", processed);
+    info!("✅ Valid records:   {}", valid);
+    info!("❌ Invalid records: {}", invalid);
+    if defaulted > 0 {
+        info!("🩹 Empty values set to schema default: {}", defaulted);
+    }
+    info!();
+
+    logging::summary(&serde_json::json!({
+        "command": "sanitize_dynamodb",
+        "input": input_path,
+        "output": output_path,
+        "model": model_type,
+        "records": processed,
+        "valid_records": valid,
+        "invalid_records": invalid,
+        "defaulted_values": defaulted,
+        "error_rate": logging::rate(invalid, processed),
+    }))?;
     
     if invalid > 0 {
-        eprintln!("⚠️  WARNING: {} invalid records were skipped", invalid);
+        diagnostics::warn(format!("WARNING: {} invalid records were skipped", invalid));
         eprintln!("   Review logs above for details");
     }
     
-    println!("✅ Sanitization complete!");
-    println!("📄 Output file: {}", output_path);
-    println!();
+    info!("✅ Sanitization complete!");
+    info!("📄 Output file: {}", output_path);
+    info!();
     
     Ok(())
 }
@@ -157,62 +224,71 @@ pub fn sanitize_dynamodb(
 /// - Header format (no quotes)
 /// - Numeric fields contain valid numbers (Type N)
 /// - No missing required fields
+///
+/// Con `report_html` escribe además el reporte HTML de la validación
 pub fn validate_dynamodb_csv(
     csv_path: &str,
     model_type: &str,
-) -> Result<(), Box<dyn Error>> {
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  DynamoDB CSV Validation                                     ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📄 File: {}", csv_path);
-    println!("📋 Model: {}", model_type);
-    println!();
+    report_html: Option<&str>,
+) -> Result<(), CsvToolsError> {
+    logging::banner("DynamoDB CSV Validation");
+    info!("📄 File: {}", csv_path);
+    info!("📋 Model: {}", model_type);
+    info!();
     
     // ✅ FIX: Usar DynamoDbModel::from_model_type()
     let model = DynamoDbModel::from_model_type(model_type)
-        .ok_or_else(|| format!(
+        .ok_or_else(|| usage_error(format!(
             "Unknown model type: '{}'\n\
              Supported: siisa_morosos, personas_telefonos, siisa_empleadores, siisa_empleadores_relaciones",
             model_type
-        ))?;
+        )))?;
     
-    let file = File::open(csv_path)?;
+    let file = open_input(csv_path)?;
     let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .has_headers(true)
         .from_reader(file);
     
     // Validate header
     let headers = reader.headers()?;
     
-    println!("🔍 Header validation:");
-    println!("   Expected: {} columns", model.expected_columns);
-    println!("   Found:    {} columns", headers.len());
+    info!("🔍 Header validation:");
+    info!("   Expected: {} columns", model.expected_columns);
+    info!("   Found:    {} columns", headers.len());
     
     if headers.len() != model.expected_columns {
-        return Err(format!(
+        return Err(data_issues(format!(
             "Column count mismatch: expected {}, found {}",
             model.expected_columns,
             headers.len()
-        ).into());
+        )));
     }
     
     // Check for quotes in header
     let has_quotes = headers.iter().any(|h| h.starts_with('"') || h.ends_with('"'));
     if has_quotes {
-        eprintln!("⚠️  WARNING: Header contains quotes (DynamoDB expects unquoted header)");
+        diagnostics::warn("WARNING: Header contains quotes (DynamoDB expects unquoted header)");
     } else {
-        println!("   ✅ Header format valid (no quotes)");
+        info!("   ✅ Header format valid (no quotes)");
     }
     
-    println!();
-    println!("🔍 Validating records...");
+    info!();
+    info!("🔍 Validating records...");
     
     let mut total = 0;
     let mut errors = 0;
+    let mut invalid_records = 0;
+    let mut report = report_html.map(|_| {
+        let mut report = ValidationReport::new("DynamoDB CSV Validation", csv_path, model_type);
+        report.set_headers(headers);
+        report
+    });
     
     for (line_num, result) in reader.records().enumerate() {
         let record = result?;
         total += 1;
+        let mut invalid_fields: Vec<&str> = Vec::new();
         
         // Validate numeric fields
         for &field_name in &model.numeric_fields {
@@ -228,29 +304,480 @@ pub fn validate_dynamodb_csv(
                             trimmed
                         );
                         errors += 1;
+                        invalid_fields.push(field_name);
+                        if let Some(report) = report.as_mut() {
+                            report.error("INVALID_NUMERIC", Some(field_name), trimmed);
+                        }
                     }
                 }
             }
         }
+
+        if !invalid_fields.is_empty() {
+            invalid_records += 1;
+            if let Some(report) = report.as_mut() {
+                report.sample(line_num + 2, &format!("Not Type N: {}", invalid_fields.join(", ")), &record);
+            }
+        }
         
         if total % 10000 == 0 {
-            println!("   Validated: {} records", total);
+            info!("   Validated: {} records", total);
         }
     }
     
-    println!();
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Validation Summary                                          ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Total records: {}", total);
+    info!();
+    logging::banner("Validation Summary");
+    info!("📊 Total records: {}", total);
+
+    if let (Some(mut report), Some(path)) = (report, report_html) {
+        report.set_totals(total, invalid_records);
+        report.write(path)?;
+        info!("📄 HTML report: {}", path);
+    }
     
     if errors > 0 {
-        println!("❌ Validation FAILED: {} errors found", errors);
-        return Err(format!("{} validation errors detected", errors).into());
+        info!("❌ Validation FAILED: {} errors found", errors);
+        return Err(data_issues(format!("{} validation errors detected", errors)));
     } else {
-        println!("✅ Validation PASSED: All records valid for DynamoDB import");
+        info!("✅ Validation PASSED: All records valid for DynamoDB import");
     }
     
-    println!();
+    info!();
+    Ok(())
+}
+/// Reescribe cada columna en la representación canónica declarada en el schema
+/// (enteros sin decimales, fechas ISO, booleanos 1/0, strings con trim)
+/// Generaliza sanitize más allá de los campos numéricos
+/// Los fallos de coerción se registran en `<output>.coercion_errors.log`
+#[derive(Args, Debug)]
+pub struct CoerceArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_name = "SCHEMA.yaml|MODEL", help = "Schema with the canonical type of every column")]
+    pub schema: String,
+    #[arg(long, value_name = "S,N", help = "True/false pair for bool columns without their own pair")]
+    pub bool_pair: Option<String>,
+    #[arg(long, help = "Drop rows with coercion errors instead of keeping the original values")]
+    pub drop_invalid: bool,
+}
+
+pub fn coerce(args: &CoerceArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let schema_spec = args.schema.as_str();
+    let drop_invalid = args.drop_invalid;
+
+    let mut schema = Schema::load(schema_spec)?;
+
+    // --bool-pair aplica a las columnas bool que no declaran su propio par
+    if let Some(pair) = &args.bool_pair {
+        let (true_value, false_value) = pair.split_once(',')
+            .ok_or_else(|| usage_error(format!("Invalid --bool-pair '{}' (expected e.g. S,N)", pair)))?;
+
+        for column in schema.columns.iter_mut() {
+            if column.column_type == ColumnType::Bool && column.bool_pair.is_none() {
+                column.bool_pair = Some((true_value.to_string(), false_value.to_string()));
+            }
+        }
+    }
+
+    logging::banner("Schema-driven Column Coercion");
+    info!("📄 Input:  {}", input_path);
+    info!("📄 Output: {}", output_path);
+    info!("📋 Schema: {} [{}] ({} columns)", schema_spec, schema.name, schema.columns.len());
+    info!("🔧 Invalid rows: {}", if drop_invalid { "dropped" } else { "kept with original values" });
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+
+    let headers = reader.headers()?.clone();
+
+    // Columna del CSV → spec del schema (None = se copia sin cambios)
+    let specs: Vec<Option<&ColumnSpec>> = headers.iter()
+        .map(|h| schema.column(h.trim()))
+        .collect();
+
+    info!("📊 Column types:");
+    for (h, spec) in headers.iter().zip(&specs) {
+        match spec {
+            Some(s) => match &s.bool_pair {
+                Some((t, f)) => info!("   {} → {} ({}/{})", h, s.column_type, t, f),
+                None => info!("   {} → {}", h, s.column_type),
+            },
+            None => info!("   {} → (not in schema, copied as-is)", h),
+        }
+    }
+    for column in &schema.columns {
+        if !headers.iter().any(|h| h.trim() == column.name) {
+            diagnostics::warn(format!("Schema column '{}' not found in CSV", column.name));
+        }
+    }
+    info!();
+
+    let error_log_path = config::error_log_path(output_path, ".coercion_errors.log");
+    let mut log = BufWriter::new(File::create(&error_log_path)?);
+
+    writeln!(log, "# Schema Coercion Error Log")?;
+    writeln!(log, "# Input: {}", input_path)?;
+    writeln!(log, "# Output: {}", output_path)?;
+    writeln!(log, "# Schema: {}", schema_spec)?;
+    writeln!(log, "#")?;
+    writeln!(log, "# Format: [LINE] STATUS | Details")?;
+    writeln!(log, "# -------------------------------------------------------")?;
+
+    let mut writer = open_sink(output_path)?;
+    writer.write_header(&headers)?;
+
+    let mut processed = 0usize;
+    let mut written = 0usize;
+    let mut rows_with_errors = 0usize;
+    let mut coercion_errors = 0usize;
+    let mut fields_changed = 0usize;
+
+    info!("🔍 Coercing records...");
+
+    let mut progress = Progress::rows("Processed");
+    for (idx, result) in reader.byte_records().enumerate() {
+        let line_num = idx + 2;
+        processed += 1;
+        let raw = result?;
+
+        // UTF-8 inválido: la fila se copia tal cual (con reemplazos) sin intentar convertirla
+        let record = match StringRecord::from_byte_record(raw) {
+            Ok(r) => r,
+            Err(e) => {
+                let raw = e.into_byte_record();
+                error_report::write(&mut log, &ErrorRecord::new(line_num, "PARSE_ERROR", "Invalid UTF-8; row copied without coercion")
+                    .at(raw.position()))?;
+                rows_with_errors += 1;
+                if !drop_invalid {
+                    writer.write_record(&raw.iter().map(String::from_utf8_lossy).collect())?;
+                    written += 1;
+                }
+                continue;
+            }
+        };
+
+        let mut row_failed = false;
+        let mut new_record = StringRecord::with_capacity(record.as_slice().len(), record.len());
+
+        for (i, value) in record.iter().enumerate() {
+            match specs.get(i).copied().flatten() {
+                Some(spec) => match coerce_value(value, spec) {
+                    Ok(coerced) => {
+                        if coerced != value {
+                            fields_changed += 1;
+                        }
+                        new_record.push_field(&coerced);
+                    }
+                    Err(e) => {
+                        row_failed = true;
+                        coercion_errors += 1;
+                        error_report::write(&mut log, &ErrorRecord::new(line_num, "COERCION_ERROR", format!("Type={} | {}", spec.column_type, e))
+                            .at(record.position()).column(&spec.name).value(value))?;
+                        new_record.push_field(value);
+                    }
+                },
+                None => new_record.push_field(value),
+            }
+        }
+
+        if row_failed {
+            rows_with_errors += 1;
+        }
+
+        if !row_failed || !drop_invalid {
+            writer.write_record(&new_record)?;
+            written += 1;
+        }
+
+        progress.update(processed as u64, || format!("Changed fields: {} | Errors: {}", fields_changed, coercion_errors));
+    }
+
+    writer.finish()?;
+    log.flush()?;
+    progress.finish();
+
+    info!("📊 Processed: {} | Changed fields: {} | Errors: {}",
+             processed, fields_changed, coercion_errors);
+    info!();
+    logging::banner("Coercion Summary");
+    info!("📊 Total processed:    {}", processed);
+    info!("✅ Records written:    {}", written);
+    info!("🔧 Fields rewritten:   {}", fields_changed);
+    info!("❌ Coercion failures:  {} (in {} rows)", coercion_errors, rows_with_errors);
+    info!();
+    info!("📝 Files created:");
+    info!("   Coerced CSV: {}", output_path);
+    info!("   Error log:   {}", error_log_path);
+
+    if rows_with_errors > 0 {
+        info!();
+        diagnostics::warn(format!("WARNING: {} rows had values that could not be coerced", rows_with_errors));
+        if drop_invalid {
+            eprintln!("   These rows were removed from the output");
+        } else {
+            eprintln!("   Original values were kept for the failing cells");
+        }
+    }
+
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Fin de línea de la salida de normalize_eol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    fn bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::Crlf => b"\r\n",
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct NormalizeEolArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_enum, default_value_t = LineEnding::Lf, help = "Line ending written for every CRLF, LF and lone CR")]
+    pub to: LineEnding,
+}
+
+/// Fines de línea encontrados por normalize_eol
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EolCounts {
+    pub crlf: u64,
+    pub lf: u64,
+    /// CR sin LF (Mac clásico)
+    pub cr: u64,
+}
+
+impl EolCounts {
+    pub fn total(&self) -> u64 {
+        self.crlf + self.lf + self.cr
+    }
+}
+
+/// Convierte los fines de línea de a bloques: un CR al final de un bloque queda
+/// pendiente hasta ver si el bloque siguiente empieza con LF
+struct EolNormalizer {
+    eol: &'static [u8],
+    pending_cr: bool,
+    counts: EolCounts,
+}
+
+impl EolNormalizer {
+    fn new(to: LineEnding) -> Self {
+        EolNormalizer { eol: to.bytes(), pending_cr: false, counts: EolCounts::default() }
+    }
+
+    fn push(&mut self, chunk: &[u8], out: &mut Vec<u8>) {
+        for &b in chunk {
+            match (self.pending_cr, b) {
+                (true, b'\n') => {
+                    self.counts.crlf += 1;
+                    self.pending_cr = false;
+                    out.extend_from_slice(self.eol);
+                }
+                (true, _) => {
+                    self.counts.cr += 1;
+                    self.pending_cr = b == b'\r';
+                    out.extend_from_slice(self.eol);
+                    if b != b'\r' {
+                        out.push(b);
+                    }
+                }
+                (false, b'\r') => self.pending_cr = true,
+                (false, b'\n') => {
+                    self.counts.lf += 1;
+                    out.extend_from_slice(self.eol);
+                }
+                (false, _) => out.push(b),
+            }
+        }
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) {
+        if std::mem::take(&mut self.pending_cr) {
+            self.counts.cr += 1;
+            out.extend_from_slice(self.eol);
+        }
+    }
+}
+
+/// Unifica los fines de línea (CRLF, LF y CR sueltos) en una sola pasada
+/// Trabaja sobre los bytes: un salto de línea dentro de un campo entre comillas también se convierte
+pub fn normalize_eol(args: &NormalizeEolArgs) -> Result<(), CsvToolsError> {
+    logging::banner("Line Ending Normalization");
+    info!("📄 Input:  {}", args.input);
+    info!("📄 Output: {}", args.output);
+    info!("🔧 Target: {}", if args.to == LineEnding::Lf { "LF" } else { "CRLF" });
+    info!();
+
+    let mut reader = open_input(&args.input)?;
+    let mut writer = compression::create_output(&args.output, 1)?;
+    let mut normalizer = EolNormalizer::new(args.to);
+    let mut out = Vec::with_capacity(1 << 20);
+
+    let mut progress = Progress::rows("Lines");
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+        let len = chunk.len();
+        out.clear();
+        normalizer.push(chunk, &mut out);
+        reader.consume(len);
+        writer.write_all(&out)?;
+        progress.update(normalizer.counts.total(), String::new);
+    }
+    out.clear();
+    normalizer.finish(&mut out);
+    writer.write_all(&out)?;
+    writer.flush()?;
+    progress.finish();
+
+    let counts = normalizer.counts;
+    let kinds = [counts.crlf, counts.lf, counts.cr].iter().filter(|n| **n > 0).count();
+    logging::banner("Line Ending Summary");
+    info!("📊 CRLF:     {}", counts.crlf);
+    info!("📊 LF:       {}", counts.lf);
+    info!("📊 CR alone: {}", counts.cr);
+    info!("✅ {} line endings written as {}{}", counts.total(), if args.to == LineEnding::Lf { "LF" } else { "CRLF" },
+        if kinds > 1 { " (input had mixed endings)" } else { "" });
+    info!("📝 Output: {}", compression::output_path(&args.output));
+
+    logging::summary(&serde_json::json!({
+        "command": "normalize_eol",
+        "input": args.input,
+        "output": compression::output_path(&args.output),
+        "to": if args.to == LineEnding::Lf { "lf" } else { "crlf" },
+        "found": counts,
+        "mixed": kinds > 1,
+    }))
+}
+
+/// Comillas de la salida de requote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QuoteStyle {
+    /// Solo los campos con delimitador, comillas o saltos de línea (RFC 4180)
+    Necessary,
+    /// Todos los campos, también el header
+    Always,
+    /// Ninguno: falla si un campo no se puede escribir sin comillas
+    Never,
+    /// Todo lo que no es número; los numéricos van sin comillas
+    #[value(name = "non_numeric")]
+    NonNumeric,
+}
+
+impl QuoteStyle {
+    fn csv_style(self) -> csv::QuoteStyle {
+        match self {
+            QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+            QuoteStyle::Always => csv::QuoteStyle::Always,
+            QuoteStyle::Never => csv::QuoteStyle::Never,
+            QuoteStyle::NonNumeric => csv::QuoteStyle::NonNumeric,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct RequoteArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_enum, help = "Which fields get quotes")]
+    pub style: QuoteStyle,
+}
+
+/// Reescribe el archivo con otro estilo de comillas sin tocar los valores: el lector
+/// interpreta las comillas de entrada, así un delimitador dentro de un campo sigue siendo parte del campo
+pub fn requote(args: &RequoteArgs) -> Result<(), CsvToolsError> {
+    let style = args.style.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default();
+    logging::banner("CSV Requote");
+    info!("📄 Input:  {}", args.input);
+    info!("📄 Output: {}", args.output);
+    info!("🔧 Style:  {}", style);
+    info!();
+
+    let delimiter = config::delimiter();
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(open_input(&args.input)?);
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .quote_style(args.style.csv_style())
+        .from_writer(compression::create_output(&args.output, 1)?);
+
+    let mut records = 0u64;
+    let mut record = csv::ByteRecord::new();
+    let mut progress = Progress::rows("Records");
+    while reader.read_byte_record(&mut record)? {
+        records += 1;
+        // Sin comillas, un delimitador o salto de línea dentro del campo rompería el registro
+        if args.style == QuoteStyle::Never {
+            let broken = record.iter().position(|f| f.iter().any(|b| *b == delimiter || matches!(b, b'"' | b'\r' | b'\n')));
+            if let Some(column) = broken {
+                return Err(data_issues(format!(
+                    "Line {} column {} contains the delimiter, a quote or a line break and cannot be written without quotes (use --style necessary)",
+                    record.position().map_or(records, |p| p.line()), column + 1)));
+            }
+        }
+        writer.write_byte_record(&record)?;
+        progress.update(records, String::new);
+    }
+    writer.flush()?;
+    progress.finish();
+
+    info!("✅ {} records written ({} quoting)", records, style);
+    info!("📝 Output: {}", compression::output_path(&args.output));
+
+    logging::summary(&serde_json::json!({
+        "command": "requote",
+        "input": args.input,
+        "output": compression::output_path(&args.output),
+        "style": style,
+        "records": records,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_eol_mixed_and_split_crlf() {
+        let mut normalizer = EolNormalizer::new(LineEnding::Lf);
+        let mut out = Vec::new();
+        // CRLF cortado entre dos bloques, LF, CR suelto y CR final
+        normalizer.push(b"Cuil,Nombre\r", &mut out);
+        normalizer.push(b"\n1,a\n2,b\r3,c\r\r", &mut out);
+        normalizer.finish(&mut out);
+        assert_eq!(out, b"Cuil,Nombre\n1,a\n2,b\n3,c\n\n");
+        assert_eq!(normalizer.counts, EolCounts { crlf: 1, lf: 1, cr: 3 });
+
+        let mut normalizer = EolNormalizer::new(LineEnding::Crlf);
+        let mut out = Vec::new();
+        normalizer.push(b"a\nb\r\n", &mut out);
+        assert_eq!(out, b"a\r\nb\r\n");
+    }
+}
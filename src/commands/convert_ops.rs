@@ -0,0 +1,1512 @@
+// Conversión entre CSV y otros formatos de intercambio (JSON Lines, Parquet, ...)
+// Las salidas pasan por compression::create_output (.gz / .zst / --compress)
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::sync::Arc;
+
+use arrow_array::builder::{BooleanBuilder, Date32Builder, Decimal128Builder, Float64Builder, Int64Builder, StringBuilder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use clap::{Args, ValueEnum};
+use csv::{ReaderBuilder, StringRecord};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::cli_args::list_values;
+use crate::commands::inspection::is_valid_dynamodb_number;
+use crate::compression::{self, create_output};
+use crate::config;
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::error_report::{self, ErrorRecord};
+use crate::exit_code::{data_issues, usage_error};
+use crate::file_utils::{is_stdio, open_input};
+use crate::info;
+use crate::logging;
+use crate::models::{self, DynamoDbModel};
+use crate::progress::Progress;
+use crate::schema::{coerce_value, infer_type, parse_bool, ColumnSpec, ColumnType, KeyRole, Schema};
+use crate::sink::open_sink;
+use crate::verbose;
+
+#[derive(Args, Debug)]
+pub struct ToJsonlArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.jsonl")]
+    pub output: String,
+    #[arg(long, value_name = "MODEL", help = "Write the model's Type N columns as JSON numbers (empty → null)")]
+    pub model: Option<String>,
+}
+
+/// CSV → JSON Lines: un objeto por fila con los nombres del header como claves
+/// (en el orden del header). Sin --model todos los valores son strings; con --model
+/// las columnas Type N van como número, o null si están vacías
+pub fn to_jsonl(args: &ToJsonlArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let model = match &args.model {
+        Some(m) => Some(DynamoDbModel::from_model_type(m)
+            .ok_or_else(|| usage_error(format!(
+                "Unknown model type: '{}'\n\
+                 Supported: siisa_morosos, personas_telefonos, siisa_empleadores, siisa_empleadores_relaciones",
+                m
+            )))?),
+        None => None,
+    };
+
+    logging::banner("CSV → JSON Lines");
+    info!("📄 Input:  {}", input_path);
+    info!("📝 Output: {}", output_path);
+    info!("📋 Model:  {}", args.model.as_deref().unwrap_or("(none, all values as strings)"));
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    let numeric: Vec<bool> = headers.iter()
+        .map(|h| model.as_ref().is_some_and(|m| m.numeric_fields.contains(&h.as_str())))
+        .collect();
+    if let Some(model) = &model {
+        let missing: Vec<&str> = model.numeric_fields.iter().copied()
+            .filter(|f| !headers.iter().any(|h| h == f))
+            .collect();
+        if !missing.is_empty() {
+            diagnostics::warn(format!("Type N columns of {} not in the header: {}", model.table_name, missing.join(", ")));
+        }
+    }
+
+    let mut writer = create_output(output_path, 1)?;
+    let mut stats = JsonlStats::default();
+    let mut record = StringRecord::new();
+    let mut line = String::new();
+
+    let mut progress = Progress::rows("Converted");
+    while reader.read_record(&mut record)? {
+        stats.rows += 1;
+        line.clear();
+        json_object(&headers, &numeric, &record, &mut line, &mut stats)?;
+        line.push('\n');
+        writer.write_all(line.as_bytes())?;
+        progress.update(stats.rows as u64, || format!("Not numeric: {}", stats.not_numeric));
+    }
+    writer.flush()?;
+    progress.finish();
+
+    info!();
+    logging::banner("JSON Lines Summary");
+    info!("📊 Rows written: {}", stats.rows);
+    if model.is_some() {
+        info!("🔢 Type N values kept as strings (not a valid number): {}", stats.not_numeric);
+    }
+    info!("📄 Output file: {}", output_path);
+
+    logging::summary(&serde_json::json!({
+        "command": "to_jsonl",
+        "input": input_path,
+        "output": output_path,
+        "model": args.model,
+        "rows": stats.rows,
+        "not_numeric": stats.not_numeric,
+        "ragged_rows": stats.ragged,
+    }))?;
+
+    if stats.not_numeric > 0 {
+        diagnostics::warn(format!("{} Type N values are not valid numbers and were written as strings", stats.not_numeric));
+    }
+    if stats.ragged > 0 {
+        diagnostics::warn(format!("{} rows have a different column count than the header (extra fields dropped, missing ones null)", stats.ragged));
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct JsonlStats {
+    rows: usize,
+    not_numeric: usize,
+    ragged: usize,
+}
+
+/// Arma el objeto JSON de una fila en `out`, con las claves en el orden del header
+fn json_object(headers: &[String], numeric: &[bool], record: &StringRecord, out: &mut String,
+               stats: &mut JsonlStats) -> Result<(), CsvToolsError> {
+    if record.len() != headers.len() {
+        stats.ragged += 1;
+    }
+
+    out.push('{');
+    for (i, header) in headers.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&serde_json::to_string(header)?);
+        out.push(':');
+
+        let Some(value) = record.get(i) else {
+            out.push_str("null");
+            continue;
+        };
+        if numeric[i] {
+            let trimmed = value.trim();
+            if trimmed.is_empty() {
+                out.push_str("null");
+                continue;
+            }
+            // Sintaxis de número JSON: "007" o "1.234,5" quedan como string
+            if let Ok(number) = trimmed.parse::<serde_json::Number>() {
+                out.push_str(&number.to_string());
+                continue;
+            }
+            stats.not_numeric += 1;
+        }
+        out.push_str(&serde_json::to_string(value)?);
+    }
+    out.push('}');
+    Ok(())
+}
+
+#[derive(Args, Debug)]
+pub struct ToDdbJsonArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.json")]
+    pub output: String,
+    #[arg(long, value_name = "MODEL",
+          help = "DynamoDB model (keys and Type N fields) [default: default_model from the config file]")]
+    pub model: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ToIonArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.ion")]
+    pub output: String,
+    #[arg(long, value_name = "MODEL",
+          help = "DynamoDB model (keys and Type N fields) [default: default_model from the config file]")]
+    pub model: Option<String>,
+}
+
+/// Formatos de item que acepta ImportTable además de CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemFormat {
+    /// `{"Item":{"Cuil":{"N":"20123"},"ApellidoNombre":{"S":"..."}}}`
+    DdbJson,
+    /// `$ion_1_0 {Item:{Cuil:20123,ApellidoNombre:"..."}}` (Ion texto, números como int/decimal)
+    Ion,
+}
+
+impl ItemFormat {
+    fn command(self) -> &'static str {
+        match self {
+            ItemFormat::DdbJson => "to_ddbjson",
+            ItemFormat::Ion => "to_ion",
+        }
+    }
+}
+
+/// CSV → DynamoDB JSON (formato de ImportTable): una línea `{"Item":{"Cuil":{"N":"..."},...}}`
+/// por fila. Las columnas Type N del modelo van como `N` y el resto como `S`; un Type N
+/// vacío se omite del item. Las filas con una clave vacía o un Type N inválido no se
+/// escriben (ImportTable las rechazaría) y van a --error-report
+pub fn to_ddbjson(args: &ToDdbJsonArgs) -> Result<(), CsvToolsError> {
+    export_items(&args.input, &args.output, args.model.as_deref(), ItemFormat::DdbJson)
+}
+
+/// CSV → Amazon Ion (opción ION de ImportTable): una línea `$ion_1_0 {Item:{...}}` por fila,
+/// con las columnas Type N como número Ion (int o decimal) y el resto como string.
+/// Mismas reglas que to_ddbjson para vacíos y filas inválidas
+pub fn to_ion(args: &ToIonArgs) -> Result<(), CsvToolsError> {
+    export_items(&args.input, &args.output, args.model.as_deref(), ItemFormat::Ion)
+}
+
+fn export_items(input_path: &str, output_path: &str, model: Option<&str>, format: ItemFormat) -> Result<(), CsvToolsError> {
+    let model_type = config::model_or_default(model)?;
+    let model = DynamoDbModel::from_model_type(&model_type)
+        .ok_or_else(|| usage_error(format!("Unknown model type: {}", model_type)))?;
+
+    logging::banner(match format {
+        ItemFormat::DdbJson => "CSV → DynamoDB JSON",
+        ItemFormat::Ion => "CSV → Amazon Ion",
+    });
+    info!("📄 Input:  {}", input_path);
+    info!("📝 Output: {}", output_path);
+    info!("📋 Model:  {} ({})", model_type, model.table_name);
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    let numeric: Vec<bool> = headers.iter().map(|h| model.numeric_fields.contains(&h.as_str())).collect();
+
+    let keys: Vec<&str> = [model.partition_key, model.sort_key].into_iter().filter(|k| !k.is_empty()).collect();
+    let key_indexes = keys.iter()
+        .map(|k| headers.iter().position(|h| h == k)
+            .ok_or_else(|| CsvToolsError::SchemaMismatch(format!("Key column '{}' of {} not in the header", k, model.table_name))))
+        .collect::<Result<Vec<usize>, _>>()?;
+
+    let mut writer = create_output(output_path, 1)?;
+    let mut stats = ItemStats::default();
+    let mut record = StringRecord::new();
+    let mut line = String::new();
+
+    let mut progress = Progress::rows("Converted");
+    while reader.read_record(&mut record)? {
+        stats.rows += 1;
+        let line_num = stats.rows + 1;
+
+        if let Some(&i) = key_indexes.iter().find(|&&i| record.get(i).unwrap_or("").trim().is_empty()) {
+            stats.empty_key += 1;
+            error_report::emit(&ErrorRecord::new(line_num, "EMPTY_KEY", "Key attribute is empty")
+                .at(record.position()).column(&headers[i]))?;
+            continue;
+        }
+
+        line.clear();
+        match item_line(format, &headers, &numeric, &record, &mut line)? {
+            None => {
+                line.push('\n');
+                writer.write_all(line.as_bytes())?;
+                stats.written += 1;
+            }
+            Some(i) => {
+                stats.invalid_number += 1;
+                let value = record.get(i).unwrap_or("");
+                error_report::emit(&ErrorRecord::new(line_num, "INVALID_NUMERIC", "Not a valid DynamoDB number")
+                    .at(record.position()).column(&headers[i]).value(value))?;
+            }
+        }
+        progress.update(stats.rows as u64, || format!("Skipped: {}", stats.empty_key + stats.invalid_number));
+    }
+    writer.flush()?;
+    progress.finish();
+
+    info!();
+    logging::banner(match format {
+        ItemFormat::DdbJson => "DynamoDB JSON Summary",
+        ItemFormat::Ion => "Amazon Ion Summary",
+    });
+    info!("📊 Rows read:    {}", stats.rows);
+    info!("✅ Items written: {}", stats.written);
+    info!("❌ Skipped (empty key):      {}", stats.empty_key);
+    info!("❌ Skipped (invalid Type N): {}", stats.invalid_number);
+    info!("📄 Output file: {}", compression::output_path(output_path));
+
+    logging::summary(&serde_json::json!({
+        "command": format.command(),
+        "input": input_path,
+        "output": compression::output_path(output_path),
+        "model": model_type,
+        "rows": stats.rows,
+        "items": stats.written,
+        "empty_key": stats.empty_key,
+        "invalid_number": stats.invalid_number,
+    }))?;
+
+    let skipped = stats.empty_key + stats.invalid_number;
+    if skipped > 0 {
+        diagnostics::warn(format!("{} rows were not written (empty key or invalid Type N value)", skipped));
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct ItemStats {
+    rows: usize,
+    written: usize,
+    empty_key: usize,
+    invalid_number: usize,
+}
+
+/// Arma el item de una fila en `out`; devuelve la columna Type N que no es
+/// un número válido para DynamoDB, si la hay (el item queda incompleto)
+fn item_line(format: ItemFormat, headers: &[String], numeric: &[bool], record: &StringRecord, out: &mut String)
+             -> Result<Option<usize>, CsvToolsError> {
+    out.push_str(match format {
+        ItemFormat::DdbJson => "{\"Item\":{",
+        ItemFormat::Ion => "$ion_1_0 {Item:{",
+    });
+    let mut first = true;
+    for (i, header) in headers.iter().enumerate() {
+        let value = record.get(i).unwrap_or("");
+        let number = numeric[i];
+        let value = if number {
+            let trimmed = value.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if !is_valid_dynamodb_number(trimmed) {
+                return Ok(Some(i));
+            }
+            trimmed
+        } else {
+            value
+        };
+
+        if !first {
+            out.push(',');
+        }
+        first = false;
+        match format {
+            ItemFormat::DdbJson => {
+                out.push_str(&serde_json::to_string(header)?);
+                out.push_str(if number { ":{\"N\":" } else { ":{\"S\":" });
+                out.push_str(&serde_json::to_string(value)?);
+                out.push('}');
+            }
+            ItemFormat::Ion => {
+                push_ion_field_name(header, out)?;
+                out.push(':');
+                if number {
+                    push_ion_number(value, out);
+                } else {
+                    // Los escapes de un string JSON son válidos en Ion
+                    out.push_str(&serde_json::to_string(value)?);
+                }
+            }
+        }
+    }
+    out.push_str("}}");
+    Ok(None)
+}
+
+/// Nombre de campo Ion: identificador tal cual, o símbolo entre comillas simples
+/// (espacios, acentos, palabras reservadas como `null` o `true`)
+fn push_ion_field_name(name: &str, out: &mut String) -> Result<(), CsvToolsError> {
+    let identifier = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        && !matches!(name, "null" | "true" | "false" | "nan");
+    if identifier {
+        out.push_str(name);
+        return Ok(());
+    }
+    let quoted = serde_json::to_string(name)?;
+    out.push('\'');
+    out.push_str(&quoted[1..quoted.len() - 1].replace("\\\"", "\"").replace('\'', "\\'"));
+    out.push('\'');
+    Ok(())
+}
+
+/// Número DynamoDB válido → int o decimal Ion (`007` → `7`, `.5` → `0.5`, `1.5E3` → `1.5d3`):
+/// decimal y no float, para no perder precisión
+fn push_ion_number(value: &str, out: &mut String) {
+    let (mantissa, exponent) = match value.find(['e', 'E']) {
+        Some(p) => (&value[..p], Some(value[p + 1..].trim_start_matches('+'))),
+        None => (value, None),
+    };
+    let (negative, mantissa) = match mantissa.strip_prefix('-') {
+        Some(m) => (true, m),
+        None => (false, mantissa),
+    };
+    let (int_part, frac) = match mantissa.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (mantissa, None),
+    };
+    let int_part = int_part.trim_start_matches('0');
+
+    if negative {
+        out.push('-');
+    }
+    out.push_str(if int_part.is_empty() { "0" } else { int_part });
+    if frac.is_none() && exponent.is_none() {
+        return;
+    }
+    out.push('.');
+    out.push_str(frac.unwrap_or(""));
+    if let Some(exponent) = exponent {
+        out.push('d');
+        out.push_str(exponent);
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ToPgCopyArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.copy")]
+    pub output: String,
+    #[arg(long, value_name = "SCHEMA.yaml|MODEL",
+          help = "Column types for CREATE TABLE [default: default_model from the config file]")]
+    pub schema: Option<String>,
+    #[arg(long, value_name = "NAME", help = "Postgres table name [default: schema name]")]
+    pub table: Option<String>,
+    #[arg(long, value_name = "PATH", help = "CREATE TABLE + \\copy script [default: <output>.sql]")]
+    pub sql: Option<String>,
+    #[arg(long, help = "Write empty text values as NULL too (by default only empty non-text values are NULL)")]
+    pub empty_as_null: bool,
+}
+
+/// CSV → formato texto de COPY de Postgres (tab, `\N` para NULL, `\\` `\t` `\n` `\r` escapados)
+/// más un script con el CREATE TABLE tipado según el schema y el `\copy` que carga el archivo
+/// Los vacíos en columnas no texto van como NULL (Postgres no acepta '' como numeric / date)
+pub fn to_pgcopy(args: &ToPgCopyArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let schema_spec = config::model_or_default(args.schema.as_deref())?;
+    let schema = Schema::load(&schema_spec)?;
+    let table = args.table.clone().unwrap_or_else(|| schema.name.clone());
+    let sql_path = args.sql.clone().unwrap_or_else(|| format!("{}.sql", compression::output_path(output_path)));
+    if table.is_empty() {
+        return Err(usage_error(format!("Schema '{}' has no name: pass --table", schema_spec)));
+    }
+
+    logging::banner("CSV → Postgres COPY");
+    info!("📄 Input:  {}", input_path);
+    info!("📝 Output: {}", output_path);
+    info!("📋 Schema: {} → table \"{}\"", schema_spec, table);
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    let specs: Vec<Option<&ColumnSpec>> = headers.iter().map(|h| schema.column(h)).collect();
+    let not_in_schema: Vec<&str> = headers.iter().zip(&specs)
+        .filter(|(_, s)| s.is_none())
+        .map(|(h, _)| h.as_str())
+        .collect();
+    if !not_in_schema.is_empty() {
+        diagnostics::warn(format!("Columns not in schema '{}' (created as text): {}", schema.name, not_in_schema.join(", ")));
+    }
+
+    let mut writer = create_output(output_path, 1)?;
+    let mut record = StringRecord::new();
+    let mut line = String::new();
+    let mut rows = 0usize;
+    let mut nulls = 0usize;
+
+    let mut progress = Progress::rows("Converted");
+    while reader.read_record(&mut record)? {
+        rows += 1;
+        line.clear();
+        for (i, spec) in specs.iter().enumerate() {
+            if i > 0 {
+                line.push('\t');
+            }
+            let value = record.get(i).unwrap_or("");
+            let text = spec.is_none_or(|s| s.column_type == ColumnType::String);
+            let null = if text { value.is_empty() && args.empty_as_null } else { value.trim().is_empty() };
+            if null {
+                line.push_str("\\N");
+                nulls += 1;
+                continue;
+            }
+            match spec.and_then(|s| s.bool_pair.as_ref()) {
+                Some((t, _)) if value == t => line.push('t'),
+                Some((_, f)) if value == f => line.push('f'),
+                _ if text => push_pgcopy_text(value, &mut line),
+                _ => push_pgcopy_text(value.trim(), &mut line),
+            }
+        }
+        line.push('\n');
+        writer.write_all(line.as_bytes())?;
+        progress.update(rows as u64, || format!("NULLs: {}", nulls));
+    }
+    writer.flush()?;
+    progress.finish();
+
+    std::fs::write(&sql_path, pgcopy_script(&table, &headers, &specs, &compression::output_path(output_path)))?;
+
+    info!();
+    logging::banner("Postgres COPY Summary");
+    info!("📊 Rows written: {}", rows);
+    info!("🕳️  NULL values:  {}", nulls);
+    info!("📄 Data file:    {}", compression::output_path(output_path));
+    info!("📄 SQL script:   {}", sql_path);
+    info!("💡 Load with: psql -d <database> -f \"{}\"", sql_path);
+
+    logging::summary(&serde_json::json!({
+        "command": "to_pgcopy",
+        "input": input_path,
+        "output": compression::output_path(output_path),
+        "sql": sql_path,
+        "table": table,
+        "rows": rows,
+        "nulls": nulls,
+    }))
+}
+
+/// Valor en formato texto de COPY: se escapan la barra, el tab y los saltos de línea
+fn push_pgcopy_text(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Tipo de Postgres de una columna del schema (sin schema, text)
+fn pg_type(spec: Option<&ColumnSpec>) -> String {
+    match spec.map(|s| s.column_type) {
+        None | Some(ColumnType::String) => "text".to_string(),
+        Some(ColumnType::Int) => "bigint".to_string(),
+        Some(ColumnType::Number) => "numeric".to_string(),
+        Some(ColumnType::Decimal { precision, scale }) => format!("numeric({},{})", precision, scale),
+        Some(ColumnType::Date) => "date".to_string(),
+        Some(ColumnType::Bool) => "boolean".to_string(),
+    }
+}
+
+/// CREATE TABLE con las columnas en el orden del archivo (las claves del schema forman
+/// la PRIMARY KEY) y el `\copy` de psql que carga el archivo de datos
+fn pgcopy_script(table: &str, headers: &[String], specs: &[Option<&ColumnSpec>], data_path: &str) -> String {
+    let ident = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+    let literal = |value: &str| format!("'{}'", value.replace('\'', "''"));
+
+    let mut columns: Vec<String> = headers.iter().zip(specs)
+        .map(|(h, s)| {
+            let not_null = s.is_some_and(|s| s.key.is_some());
+            format!("    {} {}{}", ident(h), pg_type(*s), if not_null { " NOT NULL" } else { "" })
+        })
+        .collect();
+    let mut keys: Vec<(&ColumnSpec, &String)> = headers.iter().zip(specs)
+        .filter_map(|(h, s)| s.filter(|s| s.key.is_some()).map(|s| (s, h)))
+        .collect();
+    keys.sort_by_key(|(s, _)| s.key != Some(KeyRole::Partition));
+    if !keys.is_empty() {
+        let key_list: Vec<String> = keys.iter().map(|(_, h)| ident(h)).collect();
+        columns.push(format!("    PRIMARY KEY ({})", key_list.join(", ")));
+    }
+
+    // Un .gz / .zst se descomprime con FROM PROGRAM (corre del lado del cliente con \copy)
+    let source = match compression::OutputCompression::from_path(data_path) {
+        Some(compression::OutputCompression::Gzip) => format!("PROGRAM {}", literal(&format!("gzip -dc \"{}\"", data_path))),
+        Some(compression::OutputCompression::Zstd) => format!("PROGRAM {}", literal(&format!("zstd -dc \"{}\"", data_path))),
+        None => literal(data_path),
+    };
+    let column_list: Vec<String> = headers.iter().map(|h| ident(h)).collect();
+
+    format!(
+        "-- Generado por csv_tools to_pgcopy\nCREATE TABLE IF NOT EXISTS {} (\n{}\n);\n\\copy {} ({}) FROM {} WITH (FORMAT text)\n",
+        ident(table), columns.join(",\n"), ident(table), column_list.join(", "), source)
+}
+
+#[derive(Args, Debug)]
+pub struct ToArrowArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.feather")]
+    pub output: String,
+    #[arg(long, value_name = "SCHEMA.yaml|MODEL",
+          help = "Column types [default: inferred from the first --infer-rows rows]")]
+    pub schema: Option<String>,
+    #[arg(long, value_name = "N", default_value_t = 1000, help = "Rows sampled to infer column types without --schema")]
+    pub infer_rows: usize,
+    #[arg(long, value_name = "N", default_value_t = 65_536, value_parser = clap::value_parser!(u64).range(1..),
+          help = "Rows per Arrow record batch")]
+    pub batch_size: u64,
+}
+
+/// CSV → Arrow IPC (Feather v2): columnas tipadas según --schema (o inferidas de una
+/// muestra, como profile) para que pandas / polars carguen sin volver a parsear
+/// string → Utf8, int → Int64, number → Float64, decimal(p,s) → Decimal128(p,s), date → Date32, bool → Boolean
+/// Los vacíos en columnas no texto y los valores que no se pueden convertir quedan null
+pub fn to_arrow(args: &ToArrowArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+
+    logging::banner("CSV → Arrow IPC (Feather)");
+    info!("📄 Input:  {}", input_path);
+    info!("📝 Output: {}", output_path);
+    info!("📋 Types:  {}", args.schema.as_deref().unwrap_or("(inferred from a sample)"));
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+
+    // Sin schema los tipos salen de las primeras filas, que se guardan para no leer dos veces
+    let mut sample: Vec<StringRecord> = Vec::new();
+    let columns: Vec<ColumnSpec> = match &args.schema {
+        Some(spec) => {
+            let schema = Schema::load(spec)?;
+            headers.iter()
+                .map(|h| schema.column(h).cloned().unwrap_or_else(|| {
+                    diagnostics::warn(format!("Column '{}' not in schema '{}' (written as string)", h, schema.name));
+                    ColumnSpec { name: h.clone(), ..ColumnSpec::default() }
+                }))
+                .collect()
+        }
+        None => {
+            for record in reader.records().take(args.infer_rows) {
+                sample.push(record?);
+            }
+            headers.iter().enumerate()
+                .map(|(i, h)| ColumnSpec {
+                    name: h.clone(),
+                    column_type: infer_type(sample.iter().map(|r| r.get(i).unwrap_or(""))),
+                    ..ColumnSpec::default()
+                })
+                .collect()
+        }
+    };
+
+    let fields: Vec<ArrowField> = columns.iter()
+        .map(|c| Ok(ArrowField::new(&c.name, arrow_type(c.column_type)?, true)))
+        .collect::<Result<_, CsvToolsError>>()?;
+    let arrow_schema = Arc::new(ArrowSchema::new(fields));
+    verbose!("Arrow schema: {}", arrow_schema);
+
+    let mut writer = FileWriter::try_new(BufWriter::with_capacity(1 << 20, File::create(output_path)?), &arrow_schema)?;
+    let mut builders: Vec<ArrowColumn> = columns.iter().map(ArrowColumn::new).collect::<Result<_, _>>()?;
+    let mut invalid = vec![0usize; columns.len()];
+    let mut rows = 0usize;
+    let mut batches = 0usize;
+    let mut in_batch = 0u64;
+
+    let mut progress = Progress::rows("Converted");
+    let mut append = |record: &StringRecord, rows: &mut usize| -> Result<(), CsvToolsError> {
+        *rows += 1;
+        for (i, builder) in builders.iter_mut().enumerate() {
+            let value = record.get(i).unwrap_or("");
+            if let Err(e) = builder.append(value, &columns[i]) {
+                invalid[i] += 1;
+                error_report::emit(&ErrorRecord::new(*rows + 1, "COERCION_ERROR", format!("Type={} | {}", columns[i].column_type, e))
+                    .at(record.position()).column(&columns[i].name).value(value))?;
+            }
+        }
+        in_batch += 1;
+        if in_batch == args.batch_size {
+            write_arrow_batch(&mut writer, &arrow_schema, &mut builders)?;
+            batches += 1;
+            in_batch = 0;
+        }
+        Ok(())
+    };
+
+    for record in &sample {
+        append(record, &mut rows)?;
+    }
+    let mut record = StringRecord::new();
+    while reader.read_record(&mut record)? {
+        append(&record, &mut rows)?;
+        progress.update(rows as u64, String::new);
+    }
+    if in_batch > 0 || rows == 0 {
+        write_arrow_batch(&mut writer, &arrow_schema, &mut builders)?;
+        batches += 1;
+    }
+    writer.finish()?;
+    progress.finish();
+
+    info!();
+    logging::banner("Arrow Export Summary");
+    info!("📊 Rows written: {} ({} batches)", rows, batches);
+    info!("📋 Columns:");
+    for (c, bad) in columns.iter().zip(&invalid) {
+        if *bad > 0 {
+            info!("   {} → {} ({} values not convertible, written as null)", c.name, c.column_type, bad);
+        } else {
+            info!("   {} → {}", c.name, c.column_type);
+        }
+    }
+    info!("📄 Output file: {}", output_path);
+
+    logging::summary(&serde_json::json!({
+        "command": "to_arrow",
+        "input": input_path,
+        "output": output_path,
+        "rows": rows,
+        "batches": batches,
+        "columns": columns.iter().zip(&invalid)
+            .map(|(c, bad)| serde_json::json!({ "name": c.name, "type": c.column_type.to_string(), "invalid": bad }))
+            .collect::<Vec<_>>(),
+    }))?;
+
+    let total_invalid: usize = invalid.iter().sum();
+    if total_invalid > 0 {
+        diagnostics::warn(format!("{} values could not be converted to their column type and were written as null", total_invalid));
+    }
+    Ok(())
+}
+
+/// Tipo Arrow de un tipo del schema
+fn arrow_type(column_type: ColumnType) -> Result<DataType, CsvToolsError> {
+    Ok(match column_type {
+        ColumnType::String => DataType::Utf8,
+        ColumnType::Int => DataType::Int64,
+        ColumnType::Number => DataType::Float64,
+        ColumnType::Date => DataType::Date32,
+        ColumnType::Bool => DataType::Boolean,
+        ColumnType::Decimal { precision, scale } if precision <= 38 => DataType::Decimal128(precision as u8, scale as i8),
+        ColumnType::Decimal { precision, scale } => {
+            return Err(usage_error(format!("decimal({},{}) exceeds Arrow Decimal128 precision (38)", precision, scale)));
+        }
+    })
+}
+
+/// Builder de una columna: los valores se normalizan con coerce_value (mismo criterio que coerce)
+enum ArrowColumn {
+    Utf8(StringBuilder),
+    Int64(Int64Builder),
+    Float64(Float64Builder),
+    Decimal(Decimal128Builder),
+    Date32(Date32Builder),
+    Boolean(BooleanBuilder),
+}
+
+impl ArrowColumn {
+    fn new(spec: &ColumnSpec) -> Result<Self, CsvToolsError> {
+        Ok(match spec.column_type {
+            ColumnType::String => ArrowColumn::Utf8(StringBuilder::new()),
+            ColumnType::Int => ArrowColumn::Int64(Int64Builder::new()),
+            ColumnType::Number => ArrowColumn::Float64(Float64Builder::new()),
+            ColumnType::Date => ArrowColumn::Date32(Date32Builder::new()),
+            ColumnType::Bool => ArrowColumn::Boolean(BooleanBuilder::new()),
+            ColumnType::Decimal { precision, scale } => ArrowColumn::Decimal(
+                Decimal128Builder::new().with_precision_and_scale(precision as u8, scale as i8)?),
+        })
+    }
+
+    /// Agrega un valor; si no se puede convertir agrega null y devuelve el motivo
+    fn append(&mut self, value: &str, spec: &ColumnSpec) -> Result<(), String> {
+        if let ArrowColumn::Utf8(b) = self {
+            b.append_value(value);
+            return Ok(());
+        }
+        let coerced = if value.trim().is_empty() {
+            Ok(spec.default.clone().unwrap_or_default())
+        } else {
+            coerce_value(value, spec)
+        };
+        let parsed = coerced.and_then(|v| if v.is_empty() { Ok(None) } else { self.push_parsed(&v, spec).map(Some) });
+        match parsed {
+            Ok(Some(())) => Ok(()),
+            Ok(None) => {
+                self.append_null();
+                Ok(())
+            }
+            Err(e) => {
+                self.append_null();
+                Err(e)
+            }
+        }
+    }
+
+    fn push_parsed(&mut self, value: &str, spec: &ColumnSpec) -> Result<(), String> {
+        let invalid = || format!("not convertible: '{}'", value);
+        match self {
+            ArrowColumn::Utf8(b) => b.append_value(value),
+            ArrowColumn::Int64(b) => b.append_value(value.parse().map_err(|_| invalid())?),
+            ArrowColumn::Float64(b) => b.append_value(value.parse().map_err(|_| invalid())?),
+            // coerce_value deja exactamente `scale` decimales: sin el punto es el entero escalado
+            ArrowColumn::Decimal(b) => b.append_value(value.replace('.', "").parse().map_err(|_| invalid())?),
+            ArrowColumn::Date32(b) => {
+                let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| invalid())?;
+                let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid epoch");
+                b.append_value((date - epoch).num_days() as i32);
+            }
+            ArrowColumn::Boolean(b) => {
+                let parsed = match &spec.bool_pair {
+                    Some((t, _)) if value == t => Some(true),
+                    Some((_, f)) if value == f => Some(false),
+                    _ => parse_bool(value),
+                };
+                b.append_value(parsed.ok_or_else(|| format!("not a boolean: '{}'", value))?);
+            }
+        }
+        Ok(())
+    }
+
+    fn append_null(&mut self) {
+        match self {
+            ArrowColumn::Utf8(b) => b.append_null(),
+            ArrowColumn::Int64(b) => b.append_null(),
+            ArrowColumn::Float64(b) => b.append_null(),
+            ArrowColumn::Decimal(b) => b.append_null(),
+            ArrowColumn::Date32(b) => b.append_null(),
+            ArrowColumn::Boolean(b) => b.append_null(),
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            ArrowColumn::Utf8(b) => Arc::new(b.finish()),
+            ArrowColumn::Int64(b) => Arc::new(b.finish()),
+            ArrowColumn::Float64(b) => Arc::new(b.finish()),
+            ArrowColumn::Decimal(b) => Arc::new(b.finish()),
+            ArrowColumn::Date32(b) => Arc::new(b.finish()),
+            ArrowColumn::Boolean(b) => Arc::new(b.finish()),
+        }
+    }
+}
+
+fn write_arrow_batch<W: Write>(writer: &mut FileWriter<W>, schema: &Arc<ArrowSchema>, builders: &mut [ArrowColumn])
+                               -> Result<(), CsvToolsError> {
+    let arrays: Vec<ArrayRef> = builders.iter_mut().map(ArrowColumn::finish).collect();
+    writer.write(&RecordBatch::try_new(schema.clone(), arrays)?)?;
+    Ok(())
+}
+
+/// Qué hacer con una fila a la que le falta alguna de las columnas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MissingKeys {
+    /// Campo vacío
+    Empty,
+    /// No se escribe la fila (se cuenta en el resumen)
+    Skip,
+    /// Se corta con error indicando la línea
+    Fail,
+}
+
+/// Cómo se escriben los objetos y arrays anidados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NestedValues {
+    /// JSON compacto dentro de la celda
+    Json,
+    /// Los objetos se abren en columnas `padre.hijo` (los arrays quedan como JSON)
+    Flatten,
+}
+
+#[derive(Args, Debug)]
+pub struct FromJsonlArgs {
+    #[arg(value_name = "INPUT.jsonl")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',',
+          help = "Output columns, in this order [default: every key in first-seen order (reads the input twice)]")]
+    pub columns: Vec<String>,
+    #[arg(long, value_enum, default_value_t = MissingKeys::Empty, help = "Row without one of the columns")]
+    pub missing: MissingKeys,
+    #[arg(long, value_enum, default_value_t = NestedValues::Json, help = "Nested objects and arrays")]
+    pub nested: NestedValues,
+}
+
+/// JSON Lines → CSV: las claves de primer nivel de cada objeto son las columnas
+/// (null → vacío, números y booleanos como texto); los anidados según --nested
+pub fn from_jsonl(args: &FromJsonlArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+
+    logging::banner("JSON Lines → CSV");
+    info!("📄 Input:  {}", input_path);
+    info!("📝 Output: {}", output_path);
+    info!();
+
+    let explicit = !list_values(&args.columns).is_empty();
+    let columns: Vec<String> = if explicit {
+        list_values(&args.columns).into_iter().map(str::to_string).collect()
+    } else {
+        if is_stdio(input_path) {
+            return Err(usage_error("from_jsonl reading stdin needs --columns (the keys are discovered in a first pass)"));
+        }
+        discover_columns(input_path, args.nested)?
+    };
+    if columns.is_empty() {
+        return Err(data_issues(format!("No JSON objects with keys in {}", input_path)));
+    }
+    info!("📋 Columns ({}): {}", columns.len(), columns.join(", "));
+
+    let mut sink = open_sink(output_path)?;
+    sink.write_header(&StringRecord::from(columns.clone()))?;
+
+    let column_set: HashSet<&str> = columns.iter().map(String::as_str).collect();
+    let mut stats = FromJsonlStats::default();
+    let mut ignored_keys: HashSet<String> = HashSet::new();
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    let mut progress = Progress::rows("Converted");
+    for (i, line) in open_input(input_path)?.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = i + 1;
+        fields.clear();
+        flatten_line(&line, line_number, args.nested, &mut fields)?;
+
+        let values: HashMap<&str, &str> = fields.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        let mut record = StringRecord::new();
+        let mut missing = None;
+        for column in &columns {
+            match values.get(column.as_str()) {
+                Some(value) => record.push_field(value),
+                None => {
+                    missing.get_or_insert(column.as_str());
+                    record.push_field("");
+                }
+            }
+        }
+        for (key, _) in &fields {
+            if !column_set.contains(key.as_str()) && !ignored_keys.contains(key) {
+                ignored_keys.insert(key.clone());
+            }
+        }
+
+        stats.rows += 1;
+        match (missing, args.missing) {
+            (Some(column), MissingKeys::Fail) => {
+                return Err(data_issues(format!("Line {}: key '{}' is missing (--missing fail)", line_number, column)));
+            }
+            (Some(_), MissingKeys::Skip) => stats.skipped += 1,
+            (missing, _) => {
+                stats.with_missing += usize::from(missing.is_some());
+                sink.write_record(&record)?;
+                stats.written += 1;
+            }
+        }
+        progress.update(stats.rows as u64, || format!("Missing keys: {}", stats.with_missing + stats.skipped));
+    }
+    sink.finish()?;
+    progress.finish();
+
+    info!();
+    logging::banner("CSV Conversion Summary");
+    info!("📊 Objects read:  {}", stats.rows);
+    info!("✅ Rows written:  {}", stats.written);
+    info!("🕳️  Rows with missing keys: {} (written with empty fields: {}, skipped: {})",
+        stats.with_missing + stats.skipped, stats.with_missing, stats.skipped);
+    info!("📄 Output file: {}", output_path);
+
+    let mut ignored: Vec<String> = ignored_keys.into_iter().collect();
+    ignored.sort();
+    logging::summary(&serde_json::json!({
+        "command": "from_jsonl",
+        "input": input_path,
+        "output": output_path,
+        "columns": columns,
+        "objects": stats.rows,
+        "rows_written": stats.written,
+        "rows_with_missing_keys": stats.with_missing,
+        "rows_skipped": stats.skipped,
+        "ignored_keys": ignored,
+    }))?;
+
+    if !ignored.is_empty() {
+        diagnostics::warn(format!("Keys not in --columns were dropped: {}", ignored.join(", ")));
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct FromJsonlStats {
+    rows: usize,
+    written: usize,
+    with_missing: usize,
+    skipped: usize,
+}
+
+/// Primera pasada: todas las claves (ya aplanadas) en el orden en que aparecen
+fn discover_columns(input_path: &str, nested: NestedValues) -> Result<Vec<String>, CsvToolsError> {
+    let mut columns = Vec::new();
+    let mut seen = HashSet::new();
+    let mut fields = Vec::new();
+    let mut progress = Progress::rows("Discovering keys");
+
+    for (i, line) in open_input(input_path)?.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        fields.clear();
+        flatten_line(&line, i + 1, nested, &mut fields)?;
+        for (key, _) in fields.drain(..) {
+            if seen.insert(key.clone()) {
+                columns.push(key);
+            }
+        }
+        progress.update(i as u64 + 1, String::new);
+    }
+    Ok(columns)
+}
+
+/// Objeto JSON de primer nivel con las claves en el orden del archivo
+/// (serde_json::Map las ordena alfabéticamente)
+struct OrderedObject(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for OrderedObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ObjectVisitor;
+
+        impl<'de> Visitor<'de> for ObjectVisitor {
+            type Value = OrderedObject;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OrderedObject, A::Error> {
+                let mut fields = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry::<String, Value>()? {
+                    fields.push(entry);
+                }
+                Ok(OrderedObject(fields))
+            }
+        }
+
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+/// Parsea una línea y deja en `out` los pares columna → texto de la celda
+fn flatten_line(line: &str, line_number: usize, nested: NestedValues,
+                out: &mut Vec<(String, String)>) -> Result<(), CsvToolsError> {
+    let object: OrderedObject = serde_json::from_str(line)
+        .map_err(|e| data_issues(format!("Line {}: not a JSON object ({})", line_number, e)))?;
+    for (key, value) in object.0 {
+        flatten_value(key, value, nested, out);
+    }
+    Ok(())
+}
+
+fn flatten_value(key: String, value: Value, nested: NestedValues, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) if nested == NestedValues::Flatten && !map.is_empty() => {
+            for (child, value) in map {
+                flatten_value(format!("{}.{}", key, child), value, nested, out);
+            }
+        }
+        Value::Null => out.push((key, String::new())),
+        Value::String(s) => out.push((key, s)),
+        // Números y booleanos como en el JSON; arrays y objetos como JSON compacto
+        other => out.push((key, other.to_string())),
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct FromParquetArgs {
+    #[arg(value_name = "INPUT.parquet")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+}
+
+/// Parquet → CSV: una columna por campo de primer nivel del schema, fila por fila
+/// (los row groups se leen de a uno, no se carga el archivo entero)
+pub fn from_parquet(args: &FromParquetArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+
+    logging::banner("Parquet → CSV");
+    info!("📄 Input:  {}", input_path);
+    info!("📝 Output: {}", output_path);
+
+    // Parquet necesita leer el footer al final del archivo: solo archivos locales sin comprimir
+    if is_stdio(input_path) {
+        return Err(usage_error("from_parquet needs a file input (Parquet is not readable from stdin)"));
+    }
+    let reader = SerializedFileReader::new(File::open(input_path)?)?;
+    let metadata = reader.metadata().file_metadata();
+    let total_rows = metadata.num_rows().max(0) as u64;
+    let fields = metadata.schema_descr().root_schema().get_fields();
+    let columns: Vec<String> = fields.iter().map(|f| f.name().to_string()).collect();
+
+    info!("📋 Columns ({}): {}", columns.len(), columns.join(", "));
+    info!("📊 Rows: {} in {} row groups", total_rows, reader.metadata().num_row_groups());
+    for field in fields {
+        let info = field.get_basic_info();
+        if field.is_primitive() {
+            verbose!("   {}: {:?} ({:?})", field.name(), field.get_physical_type(), info.logical_type());
+        } else {
+            verbose!("   {}: group ({:?}), written as JSON", field.name(), info.converted_type());
+        }
+    }
+    info!();
+
+    let mut sink = open_sink(output_path)?;
+    sink.write_header(&StringRecord::from(columns.clone()))?;
+
+    let mut written = 0u64;
+    let mut record = StringRecord::new();
+    let mut progress = Progress::rows_of("Converted", total_rows);
+    for row in reader.get_row_iter(None)? {
+        let row = row?;
+        record.clear();
+        for (_, field) in row.get_column_iter() {
+            record.push_field(&parquet_cell(field));
+        }
+        sink.write_record(&record)?;
+        written += 1;
+        progress.update(written, String::new);
+    }
+    sink.finish()?;
+    progress.finish();
+
+    info!();
+    logging::banner("CSV Conversion Summary");
+    info!("📊 Rows written: {}", written);
+    info!("📄 Output file: {}", output_path);
+
+    logging::summary(&serde_json::json!({
+        "command": "from_parquet",
+        "input": input_path,
+        "output": output_path,
+        "columns": columns,
+        "rows": written,
+    }))?;
+    Ok(())
+}
+
+/// Texto de una celda Parquet en el CSV: null → vacío, fechas y timestamps en ISO
+/// (yyyy-MM-dd / yyyy-MM-ddTHH:mm:ss, UTC), decimales exactos, grupos/listas/mapas como JSON
+fn parquet_cell(field: &Field) -> String {
+    match field {
+        Field::Null => String::new(),
+        Field::Str(s) => s.clone(),
+        Field::Bytes(b) => match b.as_utf8() {
+            Ok(s) => s.to_string(),
+            Err(_) => b.data().iter().map(|byte| format!("{:02x}", byte)).collect(),
+        },
+        // Sin la notación `1.0` / `1E20` del Display de parquet
+        Field::Float16(v) => f32::from(*v).to_string(),
+        Field::Float(v) => v.to_string(),
+        Field::Double(v) => v.to_string(),
+        Field::Date(days) => chrono::DateTime::from_timestamp(*days as i64 * 86_400, 0)
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| days.to_string()),
+        Field::TimestampMillis(ms) => iso_timestamp(chrono::DateTime::from_timestamp_millis(*ms), *ms),
+        Field::TimestampMicros(us) => iso_timestamp(chrono::DateTime::from_timestamp_micros(*us), *us),
+        Field::Group(_) | Field::ListInternal(_) | Field::MapInternal(_) => field.to_json_value().to_string(),
+        // Enteros, booleanos y Decimal: el Display de parquet ya es el valor exacto
+        other => other.to_string(),
+    }
+}
+
+fn iso_timestamp(datetime: Option<chrono::DateTime<chrono::Utc>>, raw: i64) -> String {
+    datetime.map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+        .unwrap_or_else(|| raw.to_string())
+}
+
+/// Layout de un archivo de ancho fijo (`--spec widths.yaml`); posiciones en bytes desde 1
+///
+/// ```yaml
+/// skip_lines: 1             # líneas de encabezado del extracto que se descartan
+/// trim: true                # quita los espacios de relleno (default: true)
+/// columns:
+///   - { name: Cuil, start: 1, length: 11 }
+///   - { name: ApellidoNombre, start: 12, end: 51 }   # end inclusive
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct FixedWidthSpec {
+    #[serde(default)]
+    pub skip_lines: usize,
+    #[serde(default = "default_trim")]
+    pub trim: bool,
+    pub columns: Vec<FixedColumn>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FixedColumn {
+    pub name: String,
+    pub start: usize,
+    pub length: Option<usize>,
+    pub end: Option<usize>,
+}
+
+fn default_trim() -> bool {
+    true
+}
+
+impl FixedWidthSpec {
+    pub fn load(path: &str) -> Result<Self, CsvToolsError> {
+        let content = std::fs::read_to_string(path)?;
+        let spec: FixedWidthSpec = serde_yaml::from_str(&content)
+            .map_err(|e| usage_error(format!("Invalid fixed-width spec '{}': {}", path, e)))?;
+        spec.ranges()?;
+        Ok(spec)
+    }
+
+    /// Rango de bytes `[inicio, fin)` (base 0) de cada columna; valida el spec
+    pub fn ranges(&self) -> Result<Vec<(usize, usize)>, CsvToolsError> {
+        if self.columns.is_empty() {
+            return Err(usage_error("Fixed-width spec has no columns"));
+        }
+        let mut names = HashSet::new();
+        self.columns.iter()
+            .map(|c| {
+                if !names.insert(c.name.as_str()) {
+                    return Err(usage_error(format!("Column '{}' is defined twice in the spec", c.name)));
+                }
+                let end = match (c.length, c.end) {
+                    (Some(length), None) if length > 0 => c.start + length - 1,
+                    (None, Some(end)) => end,
+                    _ => return Err(usage_error(format!("Column '{}': give either length (> 0) or end", c.name))),
+                };
+                if c.start == 0 || end < c.start {
+                    return Err(usage_error(format!("Column '{}': invalid range {}-{} (positions start at 1)", c.name, c.start, end)));
+                }
+                Ok((c.start - 1, end))
+            })
+            .collect()
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct FromFixedArgs {
+    #[arg(value_name = "INPUT.txt")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_name = "WIDTHS.yaml", help = "Column names and byte ranges")]
+    pub spec: String,
+    #[arg(long, value_name = "MODEL", help = "Check that the spec columns are the model's columns before converting")]
+    pub model: Option<String>,
+}
+
+/// Ancho fijo → CSV: corta cada línea en los rangos de bytes del spec (los extractos
+/// de mainframe rellenan con espacios). Las líneas más cortas dejan vacías las columnas
+/// que no alcanzan y se cuentan en el resumen
+pub fn from_fixed(args: &FromFixedArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let spec = FixedWidthSpec::load(&args.spec)?;
+    let ranges = spec.ranges()?;
+    let columns: Vec<String> = spec.columns.iter().map(|c| c.name.clone()).collect();
+
+    logging::banner("Fixed Width → CSV");
+    info!("📄 Input:  {}", input_path);
+    info!("📝 Output: {}", output_path);
+    info!("📐 Spec:   {} ({} columns, {} bytes per record)", args.spec, columns.len(),
+        ranges.iter().map(|r| r.1).max().unwrap_or(0));
+    info!();
+
+    if let Some(model) = &args.model {
+        check_model_columns(&columns, model)?;
+    }
+
+    let mut sink = open_sink(output_path)?;
+    sink.write_header(&StringRecord::from(columns.clone()))?;
+
+    let record_width = ranges.iter().map(|r| r.1).max().unwrap_or(0);
+    let mut stats = FixedStats::default();
+    let mut record = StringRecord::new();
+    let mut progress = Progress::rows("Converted");
+
+    for (i, line) in open_input(input_path)?.split(b'\n').enumerate() {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if i < spec.skip_lines || line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+
+        if line.len() < record_width {
+            stats.short += 1;
+        }
+        record.clear();
+        for (start, end) in &ranges {
+            let bytes = line.get(*start..(*end).min(line.len())).unwrap_or_default();
+            let value = match std::str::from_utf8(bytes) {
+                Ok(s) => std::borrow::Cow::Borrowed(s),
+                Err(_) => {
+                    stats.invalid_utf8 += 1;
+                    String::from_utf8_lossy(bytes)
+                }
+            };
+            record.push_field(if spec.trim { value.trim() } else { &value });
+        }
+        sink.write_record(&record)?;
+        stats.rows += 1;
+        progress.update(stats.rows as u64, || format!("Short lines: {}", stats.short));
+    }
+    sink.finish()?;
+    progress.finish();
+
+    info!();
+    logging::banner("CSV Conversion Summary");
+    info!("📊 Rows written: {}", stats.rows);
+    info!("📏 Lines shorter than the record ({} bytes): {}", record_width, stats.short);
+    info!("📄 Output file: {}", output_path);
+
+    logging::summary(&serde_json::json!({
+        "command": "from_fixed",
+        "input": input_path,
+        "output": output_path,
+        "spec": args.spec,
+        "columns": columns,
+        "rows": stats.rows,
+        "short_lines": stats.short,
+        "invalid_utf8_fields": stats.invalid_utf8,
+    }))?;
+
+    if stats.invalid_utf8 > 0 {
+        diagnostics::warn(format!("{} fields are not valid UTF-8 (replaced with U+FFFD); check the extract's encoding", stats.invalid_utf8));
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct FixedStats {
+    rows: usize,
+    short: usize,
+    invalid_utf8: usize,
+}
+
+/// Las columnas del spec tienen que ser las del modelo (el orden se puede corregir después)
+fn check_model_columns(columns: &[String], model_type: &str) -> Result<(), CsvToolsError> {
+    let expected = models::get_expected_headers(model_type)?;
+    let missing: Vec<&str> = expected.iter().copied().filter(|e| !columns.iter().any(|c| c == e)).collect();
+    let extra: Vec<&str> = columns.iter().map(String::as_str).filter(|c| !expected.contains(c)).collect();
+    if !missing.is_empty() || !extra.is_empty() {
+        return Err(CsvToolsError::SchemaMismatch(format!(
+            "Spec columns do not match model {}: missing [{}], extra [{}]", model_type, missing.join(", "), extra.join(", "))));
+    }
+    if columns.iter().map(String::as_str).ne(expected.iter().copied()) {
+        diagnostics::warn(format!("Spec column order differs from model {} (reorder-to-model can fix it)", model_type));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_object_types() {
+        let headers: Vec<String> = ["Cuil", "ApellidoNombre", "Periodo", "IdEntidad"].iter().map(|s| s.to_string()).collect();
+        let numeric = [true, false, true, true];
+        let mut stats = JsonlStats::default();
+
+        let mut out = String::new();
+        json_object(&headers, &numeric, &StringRecord::from(vec!["20123456789", "PEREZ, \"JUAN\"", "", "007"]), &mut out, &mut stats).unwrap();
+        assert_eq!(out, r#"{"Cuil":20123456789,"ApellidoNombre":"PEREZ, \"JUAN\"","Periodo":null,"IdEntidad":"007"}"#);
+        assert_eq!(stats.not_numeric, 1);
+
+        out.clear();
+        json_object(&headers, &numeric, &StringRecord::from(vec!["1.5", "X"]), &mut out, &mut stats).unwrap();
+        assert_eq!(out, r#"{"Cuil":1.5,"ApellidoNombre":"X","Periodo":null,"IdEntidad":null}"#);
+        assert_eq!(stats.ragged, 1);
+    }
+
+    #[test]
+    fn test_item_line_attribute_types() {
+        let headers: Vec<String> = ["Cuil", "ApellidoNombre", "Deuda"].iter().map(|s| s.to_string()).collect();
+        let numeric = [true, false, true];
+        let mut out = String::new();
+        let record = StringRecord::from(vec![" 20123 ", "Peña \"J\"", ""]);
+        assert_eq!(item_line(ItemFormat::DdbJson, &headers, &numeric, &record, &mut out).unwrap(), None);
+        assert_eq!(out, r#"{"Item":{"Cuil":{"N":"20123"},"ApellidoNombre":{"S":"Peña \"J\""}}}"#);
+
+        out.clear();
+        assert_eq!(item_line(ItemFormat::Ion, &headers, &numeric, &record, &mut out).unwrap(), None);
+        assert_eq!(out, r#"$ion_1_0 {Item:{Cuil:20123,ApellidoNombre:"Peña \"J\""}}"#);
+
+        out.clear();
+        let record = StringRecord::from(vec!["20123", "x", "1,5"]);
+        assert_eq!(item_line(ItemFormat::DdbJson, &headers, &numeric, &record, &mut out).unwrap(), Some(2));
+
+        for (value, ion) in [("007", "7"), ("-0.50", "-0.50"), ("1.5E+3", "1.5d3"), (".5", "0.5"), ("2e-2", "2.d-2")] {
+            out.clear();
+            push_ion_number(value, &mut out);
+            assert_eq!(out, ion);
+        }
+        out.clear();
+        push_ion_field_name("Apellido y Nombre", &mut out).unwrap();
+        assert_eq!(out, "'Apellido y Nombre'");
+    }
+
+    #[test]
+    fn test_pgcopy_escaping_and_script() {
+        let mut out = String::new();
+        push_pgcopy_text("a\\b\tc\r\nd", &mut out);
+        assert_eq!(out, "a\\\\b\\tc\\r\\nd");
+
+        let schema = Schema::from_model(&DynamoDbModel::siisa_morosos());
+        let headers: Vec<String> = ["IdTransmit", "Cuil", "Extra"].iter().map(|s| s.to_string()).collect();
+        let specs: Vec<Option<&ColumnSpec>> = headers.iter().map(|h| schema.column(h)).collect();
+        let script = pgcopy_script("morosos", &headers, &specs, "siisa.copy.gz");
+        assert!(script.contains("\"Cuil\" numeric NOT NULL,\n    \"Extra\" text,\n    PRIMARY KEY (\"Cuil\", \"IdTransmit\")"));
+        assert!(script.contains("FROM PROGRAM 'gzip -dc \"siisa.copy.gz\"' WITH (FORMAT text)"));
+    }
+
+    #[test]
+    fn test_arrow_batch_types_and_nulls() {
+        use arrow_array::cast::AsArray;
+        use arrow_array::Array;
+        use arrow_array::types::{Date32Type, Decimal128Type, Int64Type};
+
+        let columns = [
+            ColumnSpec { name: "Cuil".into(), column_type: ColumnType::Int, ..ColumnSpec::default() },
+            ColumnSpec { name: "Importe".into(), column_type: ColumnType::Decimal { precision: 12, scale: 2 }, ..ColumnSpec::default() },
+            ColumnSpec { name: "Fecha".into(), column_type: ColumnType::Date, ..ColumnSpec::default() },
+        ];
+        let schema = Arc::new(ArrowSchema::new(columns.iter()
+            .map(|c| ArrowField::new(&c.name, arrow_type(c.column_type).unwrap(), true))
+            .collect::<Vec<_>>()));
+        let mut builders: Vec<ArrowColumn> = columns.iter().map(|c| ArrowColumn::new(c).unwrap()).collect();
+        for row in [["20123", "1.234,5", "13/08/2025"], ["x", "", "1970-01-02"]] {
+            for (i, value) in row.iter().enumerate() {
+                let _ = builders[i].append(value, &columns[i]);
+            }
+        }
+
+        let mut buffer = Vec::new();
+        let mut writer = FileWriter::try_new(&mut buffer, &schema).unwrap();
+        write_arrow_batch(&mut writer, &schema, &mut builders).unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+
+        let batch = arrow_ipc::reader::FileReader::try_new(std::io::Cursor::new(buffer), None).unwrap().next().unwrap().unwrap();
+        let cuil = batch.column(0).as_primitive::<Int64Type>();
+        assert_eq!((cuil.value(0), cuil.is_null(1)), (20123, true));
+        let importe = batch.column(1).as_primitive::<Decimal128Type>();
+        assert_eq!((importe.value(0), importe.is_null(1)), (123450, true));
+        assert_eq!(batch.column(2).as_primitive::<Date32Type>().value(1), 1);
+    }
+
+    #[test]
+    fn test_flatten_line_keeps_key_order() {
+        let line = r#"{"Cuil":20123,"Nombre":"Perez","Domicilio":{"Calle":"Mitre","Nro":10},"Tel":[1,2],"Baja":null,"Activo":true}"#;
+        let mut fields = Vec::new();
+        flatten_line(line, 1, NestedValues::Json, &mut fields).unwrap();
+        let keys: Vec<&str> = fields.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["Cuil", "Nombre", "Domicilio", "Tel", "Baja", "Activo"]);
+        assert_eq!(fields[2].1, r#"{"Calle":"Mitre","Nro":10}"#);
+        assert_eq!(fields[4].1, "");
+
+        fields.clear();
+        flatten_line(line, 1, NestedValues::Flatten, &mut fields).unwrap();
+        assert_eq!(fields[2], ("Domicilio.Calle".to_string(), "Mitre".to_string()));
+        assert_eq!(fields[3], ("Domicilio.Nro".to_string(), "10".to_string()));
+        assert_eq!(fields[4].1, "[1,2]");
+
+        assert!(flatten_line("[1,2]", 7, NestedValues::Json, &mut fields).is_err());
+    }
+
+    #[test]
+    fn test_parquet_cell_text() {
+        assert_eq!(parquet_cell(&Field::Null), "");
+        assert_eq!(parquet_cell(&Field::Str("Perez, Juan".to_string())), "Perez, Juan");
+        assert_eq!(parquet_cell(&Field::Long(20123456789)), "20123456789");
+        assert_eq!(parquet_cell(&Field::Double(2.0)), "2");
+        assert_eq!(parquet_cell(&Field::Double(10.25)), "10.25");
+        assert_eq!(parquet_cell(&Field::Date(19876)), "2024-06-02");
+        assert_eq!(parquet_cell(&Field::TimestampMillis(1_717_286_400_000)), "2024-06-02T00:00:00");
+        assert_eq!(parquet_cell(&Field::TimestampMillis(1_717_286_400_250)), "2024-06-02T00:00:00.250");
+    }
+
+    #[test]
+    fn test_fixed_width_ranges() {
+        let spec: FixedWidthSpec = serde_yaml::from_str(
+            "columns:\n  - { name: Cuil, start: 1, length: 11 }\n  - { name: Nombre, start: 12, end: 21 }\n").unwrap();
+        assert!(spec.trim);
+        assert_eq!(spec.ranges().unwrap(), vec![(0, 11), (11, 21)]);
+
+        let bad: FixedWidthSpec = serde_yaml::from_str(
+            "columns:\n  - { name: Cuil, start: 0, length: 11 }\n").unwrap();
+        assert!(bad.ranges().is_err());
+        let both: FixedWidthSpec = serde_yaml::from_str(
+            "columns:\n  - { name: Cuil, start: 1, length: 11, end: 11 }\n").unwrap();
+        assert!(both.ranges().is_err());
+    }
+}
@@ -1,11 +1,17 @@
-use std::error::Error;
-use std::io::Write;
 use chrono::{NaiveDateTime, Datelike, NaiveDate};
-use csv::{Reader, WriterBuilder};
+use csv::{ReaderBuilder, WriterBuilder};
+
+use crate::config;
+use crate::error::CsvToolsError;
+use crate::exit_code::usage_error;
+use crate::file_utils::open_input;
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
 
 /// Conversión de fechas DD/MM/YYYY a YYYY-MM-DD
 /// Sigue patrón SiisaRestApi: stream-based processing + progress tracking
-pub fn convert_dates(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn convert_dates(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 4 {
         eprintln!("Usage: csv_tools convert_dates <input.csv> <output.csv>");
         std::process::exit(1);
@@ -14,17 +20,19 @@ pub fn convert_dates(args: &[String]) -> Result<(), Box<dyn Error>> {
     let input_file = &args[2];
     let output_file = &args[3];
     
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Date Format Converter (DD/MM/YYYY → YYYY-MM-DD)           ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📄 Input: {}", input_file);
-    println!("📝 Output: {}", output_file);
-    println!();
+    logging::banner("Date Format Converter (DD/MM/YYYY → YYYY-MM-DD)");
+    info!("📄 Input: {}", input_file);
+    info!("📝 Output: {}", output_file);
+    info!();
     
-    let mut rdr = Reader::from_path(input_file)?;
+    // Delimitador, stdin y .gz como el resto de los comandos (csv_tools.toml / --delimiter)
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .from_reader(open_input(input_file)?);
     let headers = rdr.headers()?.clone();
     
     let mut wtr = WriterBuilder::new()
+        .delimiter(config::delimiter())
         .quote_style(csv::QuoteStyle::Necessary)
         .from_path(output_file)?;
     
@@ -33,6 +41,7 @@ pub fn convert_dates(args: &[String]) -> Result<(), Box<dyn Error>> {
     let mut total = 0usize;
     let mut converted = 0usize;
     
+    let mut progress = Progress::rows("Processed");
     for result in rdr.records() {
         total += 1;
         let record = result?;
@@ -51,16 +60,14 @@ pub fn convert_dates(args: &[String]) -> Result<(), Box<dyn Error>> {
         
         wtr.write_record(&new_record)?;
         
-        if total % 10_000 == 0 {
-            print!("\r📊 Processed: {} | Converted: {}", total, converted);
-            std::io::stdout().flush()?;
-        }
+        progress.update(total as u64, || format!("Converted: {}", converted));
     }
     
     wtr.flush()?;
+    progress.finish();
     
-    println!("\r📊 Processed: {} | Converted: {}", total, converted);
-    println!("✅ Date conversion complete");
+    info!("📊 Processed: {} | Converted: {}", total, converted);
+    info!("✅ Date conversion complete");
     
     Ok(())
 }
@@ -90,7 +97,7 @@ pub fn parse_us_datetime(s: &str) -> Option<NaiveDateTime> {
     NaiveDateTime::parse_from_str(s, "%m/%d/%Y %I:%M:%S %p").ok()
 }
 
-pub fn find_oldest_date(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn find_oldest_date(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 4 {
         eprintln!("Usage: csv_tools find_oldest_date <input_file> <date_column>");
         return Ok(());
@@ -98,7 +105,7 @@ pub fn find_oldest_date(args: &[String]) -> Result<(), Box<dyn Error>> {
     find_extreme_date(&args[2], &args[3], true)
 }
 
-pub fn find_newest_date(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn find_newest_date(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 4 {
         eprintln!("Usage: csv_tools find_newest_date <input_file> <date_column>");
         return Ok(());
@@ -110,15 +117,16 @@ fn find_extreme_date(
     input_file: &str,
     date_column: &str,
     find_oldest: bool,
-) -> Result<(), Box<dyn Error>> {
-    println!("🔍 Buscando fecha {} en columna '{}'", 
+) -> Result<(), CsvToolsError> {
+    info!("🔍 Buscando fecha {} en columna '{}'", 
              if find_oldest { "más antigua" } else { "más reciente" }, 
              date_column);
     
-    let mut reader = csv::ReaderBuilder::new()
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .has_headers(true)
         .flexible(true)
-        .from_path(input_file)?;
+        .from_reader(open_input(input_file)?);
 
     let headers = reader.headers()?.clone();
     let date_col_idx = headers.iter()
@@ -131,9 +139,9 @@ fn find_extreme_date(
     let mut valid_dates = 0u64;
     let mut invalid_dates = 0u64;
     let mut format_errors = 0u64;
-    let start = std::time::Instant::now();
+    let mut progress = Progress::rows("Procesados");
 
-    for (_line_num, result) in reader.records().enumerate() {  // ← Prefijo con _
+    for result in reader.records() {
         match result {
             Ok(record) => {
                 if record.len() < date_col_idx + 1 {
@@ -145,9 +153,8 @@ fn find_extreme_date(
                     if let Some(date) = parse_us_datetime(date_str) {
                         valid_dates += 1;
                         
-                        if extreme_date.is_none() || 
-                           (find_oldest && date < extreme_date.unwrap()) ||
-                           (!find_oldest && date > extreme_date.unwrap()) {
+                        let more_extreme = extreme_date.is_none_or(|extreme| if find_oldest { date < extreme } else { date > extreme });
+                        if more_extreme {
                             extreme_date = Some(date);
                             extreme_record = Some(record.iter().take(5).collect::<Vec<_>>().join(" | "));
                         }
@@ -156,12 +163,7 @@ fn find_extreme_date(
                     }
                 }
                 processed += 1;
-                
-                if processed % 100_000 == 0 {
-                    print!("\r📊 Procesados: {} | Válidos: {} | Errores: {} | Tiempo: {:.1}s", 
-                           processed, valid_dates, invalid_dates + format_errors, start.elapsed().as_secs_f32());
-                    std::io::stdout().flush()?;
-                }
+                progress.update(processed, || format!("Válidos: {} | Errores: {}", valid_dates, invalid_dates + format_errors));
             }
             Err(_) => {
                 format_errors += 1;
@@ -170,24 +172,28 @@ fn find_extreme_date(
         }
     }
 
-    println!("\n\n📊 RESUMEN:");
-    println!("  Registros procesados: {}", processed);
-    println!("  Fechas válidas: {}", valid_dates);
-    println!("  Fechas inválidas: {}", invalid_dates);
-    println!("  Errores de formato: {}", format_errors);
+    progress.finish();
+
+    info!();
+    info!("📊 RESUMEN:");
+    info!("  Registros procesados: {}", processed);
+    info!("  Fechas válidas: {}", valid_dates);
+    info!("  Fechas inválidas: {}", invalid_dates);
+    info!("  Errores de formato: {}", format_errors);
     
+    // El resultado es el dato del comando: sale aun con --quiet
     if let (Some(date), Some(record)) = (extreme_date, extreme_record) {
-        println!("\n✅ Fecha {} encontrada:", if find_oldest { "MÁS ANTIGUA" } else { "MÁS RECIENTE" });
-        println!("   📅 {}", date.format("%m/%d/%Y %I:%M:%S %p"));
-        println!("   📝 Registro: {}", record);
+        info!("✅ Fecha {} encontrada:", if find_oldest { "MÁS ANTIGUA" } else { "MÁS RECIENTE" });
+        info!("   📅 {}", date.format("%m/%d/%Y %I:%M:%S %p"));
+        info!("   📝 Registro: {}", record);
     } else {
-        println!("❌ No se encontraron fechas válidas");
+        info!("❌ No se encontraron fechas válidas");
     }
 
     Ok(())
 }
 
-pub fn find_last_by_month(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn find_last_by_month(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 6 {
         eprintln!("Usage: csv_tools find_last_by_month <input_file> <date_column> <year> <month>");
         return Ok(());
@@ -196,12 +202,12 @@ pub fn find_last_by_month(args: &[String]) -> Result<(), Box<dyn Error>> {
     let input_file = &args[2];
     let date_column = &args[3];
     let year: i32 = args[4].parse()
-        .map_err(|_| "Invalid year format")?;
+        .map_err(|_| usage_error("Invalid year format"))?;
     let month: u32 = args[5].parse()
-        .map_err(|_| "Invalid month format")?;
+        .map_err(|_| usage_error("Invalid month format"))?;
     
-    if month < 1 || month > 12 {
-        return Err("Month must be between 1 and 12".into());
+    if !(1..=12).contains(&month) {
+        return Err(usage_error("Month must be between 1 and 12"));
     }
     
     find_last_record_by_month_impl(input_file, date_column, year, month)
@@ -212,13 +218,14 @@ fn find_last_record_by_month_impl(
     date_column: &str,
     target_year: i32,
     target_month: u32,
-) -> Result<(), Box<dyn Error>> {
-    println!("🔍 Buscando último registro de {}/{} en columna '{}'", target_month, target_year, date_column);
+) -> Result<(), CsvToolsError> {
+    info!("🔍 Buscando último registro de {}/{} en columna '{}'", target_month, target_year, date_column);
     
-    let mut reader = csv::ReaderBuilder::new()
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .has_headers(true)
         .flexible(true)
-        .from_path(input_file)?;
+        .from_reader(open_input(input_file)?);
 
     let headers = reader.headers()?.clone();
     let date_col_idx = headers.iter()
@@ -251,7 +258,7 @@ fn find_last_record_by_month_impl(
     let mut processed = 0u64;
     let mut matched_records = 0u64;
     let mut valid_dates = 0u64;
-    let start = std::time::Instant::now();
+    let mut progress = Progress::rows("Procesados");
 
     for (line_num, result) in reader.records().enumerate() {
         if let Ok(record) = result {
@@ -264,14 +271,14 @@ fn find_last_record_by_month_impl(
                         
                         if date.year() == target_year && date.month() == target_month {
                             matched_records += 1;
-                            if last_date.is_none() || date > last_date.unwrap() {
+                            if last_date.is_none_or(|last| date > last) {
                                 last_date = Some(date);
                                 last_record_line = Some(line_num + 2);
                                 last_record_data = Some(record.clone());
                             }
                         }
                         
-                        if min_distance.is_none() || distance < min_distance.unwrap() {
+                        if min_distance.is_none_or(|min| distance < min) {
                             min_distance = Some(distance);
                             closest_date = Some(date);
                             closest_record_line = Some(line_num + 2);
@@ -281,57 +288,55 @@ fn find_last_record_by_month_impl(
                 }
             }
             processed += 1;
-            
-            if processed % 100_000 == 0 {
-                print!("\r📊 Procesados: {} | Válidos: {} | Coincidencias: {} | Tiempo: {:.1}s", 
-                       processed, valid_dates, matched_records, start.elapsed().as_secs_f32());
-                std::io::stdout().flush()?;
-            }
+            progress.update(processed, || format!("Válidos: {} | Coincidencias: {}", valid_dates, matched_records));
         }
     }
 
-    println!("\n\n📊 RESUMEN:");
-    println!("  Registros procesados: {}", processed);
-    println!("  Fechas válidas: {}", valid_dates);
-    println!("  Registros del mes {}/{}: {}", target_month, target_year, matched_records);
+    progress.finish();
+
+    info!();
+    info!("📊 RESUMEN:");
+    info!("  Registros procesados: {}", processed);
+    info!("  Fechas válidas: {}", valid_dates);
+    info!("  Registros del mes {}/{}: {}", target_month, target_year, matched_records);
     
     if let (Some(date), Some(line), Some(record)) = (last_date, last_record_line, last_record_data) {
-        println!("\n✅ ÚLTIMO REGISTRO DE {}/{}:", target_month, target_year);
-        println!("   📅 Fecha: {}", date.format("%m/%d/%Y %I:%M:%S %p"));
-        println!("   📍 Línea: {}", line);
-        println!("   📝 Registro completo:");
+        info!("\n✅ ÚLTIMO REGISTRO DE {}/{}:", target_month, target_year);
+        info!("   📅 Fecha: {}", date.format("%m/%d/%Y %I:%M:%S %p"));
+        info!("   📍 Línea: {}", line);
+        info!("   📝 Registro completo:");
         for (i, field) in record.iter().enumerate() {
             if let Some(header) = headers.get(i) {
-                println!("      {}: {}", header, field);
+                info!("      {}: {}", header, field);
             }
         }
     } else if let (Some(date), Some(line), Some(record)) = (closest_date, closest_record_line, closest_record_data) {
-        println!("\n❌ No se encontraron registros EXACTOS para {}/{}", target_month, target_year);
-        println!("\n🔍 REGISTRO MÁS CERCANO ENCONTRADO:");
-        println!("   📅 Fecha: {}", date.format("%m/%d/%Y %I:%M:%S %p"));
-        println!("   📍 Línea: {}", line);
+        info!("\n❌ No se encontraron registros EXACTOS para {}/{}", target_month, target_year);
+        info!("\n🔍 REGISTRO MÁS CERCANO ENCONTRADO:");
+        info!("   📅 Fecha: {}", date.format("%m/%d/%Y %I:%M:%S %p"));
+        info!("   📍 Línea: {}", line);
         
         let diff_days = min_distance.unwrap_or(0) / (24 * 3600);
         if date < target_date {
-            println!("   ⏱️  {} días ANTES del mes objetivo", diff_days);
+            info!("   ⏱️  {} días ANTES del mes objetivo", diff_days);
         } else {
-            println!("   ⏱️  {} días DESPUÉS del mes objetivo", diff_days);
+            info!("   ⏱️  {} días DESPUÉS del mes objetivo", diff_days);
         }
         
-        println!("   📝 Registro completo:");
+        info!("   📝 Registro completo:");
         for (i, field) in record.iter().enumerate() {
             if let Some(header) = headers.get(i) {
-                println!("      {}: {}", header, field);
+                info!("      {}: {}", header, field);
             }
         }
     } else {
-        println!("❌ No se encontraron registros válidos en el archivo");
+        info!("❌ No se encontraron registros válidos en el archivo");
     }
 
     Ok(())
 }
 
-pub fn sort_csv_by_date(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn sort_csv_by_date(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 5 {
         eprintln!("Usage: csv_tools sort_by_date <input> <output> <date_column> [asc|desc]");
         return Ok(());
@@ -342,9 +347,9 @@ pub fn sort_csv_by_date(args: &[String]) -> Result<(), Box<dyn Error>> {
     let date_column = &args[4];
     let order = args.get(5).map(|s| s.as_str()).unwrap_or("desc");
     
-    println!("🔄 Sorting CSV by date column '{}' in {} order", date_column, order);
-    println!("⚠️  This operation uses external sort for memory efficiency");
-    println!("❌ sort_by_date not yet implemented in modular structure");
+    info!("🔄 Sorting CSV by date column '{}' in {} order", date_column, order);
+    info!("⚠️  This operation uses external sort for memory efficiency");
+    info!("❌ sort_by_date not yet implemented in modular structure");
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,606 @@
+use std::collections::{HashMap, HashSet};
+use csv::{ReaderBuilder, StringRecord};
+
+use clap::Args;
+use crate::config;
+use crate::canonicalize::CanonicalRules;
+use crate::cli_args::list_values;
+use crate::collation::{self, Collation};
+use crate::dedup::{self, KeepPolicy, KeyNormalization};
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::exit_code;
+use crate::file_utils::{format_bytes, open_input, resolve_inputs};
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::repro;
+use crate::sink::open_sink;
+use crate::sketch::{CountMinSketch, TopK};
+
+// Dimensiones del count-min sketch (4 x 1M contadores u32 = 16 MB)
+const CMS_WIDTH: usize = 1 << 20;
+const CMS_DEPTH: usize = 4;
+// Candidatos extra que se siguen por cada clave pedida en --top
+const TOP_K_OVERSAMPLE: usize = 4;
+// Columnas distintas de ejemplo que se muestran por clave
+const MAX_DIFF_EXAMPLES: usize = 3;
+// Números de línea por clave que se muestran en consola (el reporte los tiene todos)
+const MAX_LINES_SHOWN: usize = 10;
+
+/// Estado exacto de una clave candidata (segunda pasada)
+struct KeyDetail {
+    count: usize,
+    first_row: StringRecord,
+    // Primeras apariciones: (índice de archivo, línea)
+    lines: Vec<(usize, u64)>,
+    // índice de columna → (valor de la primera fila, primer valor distinto, archivo y línea)
+    differing: Vec<(usize, String, String, (usize, u64))>,
+}
+
+/// Reporta las claves con más duplicados en memoria acotada:
+/// 1ª pasada count-min sketch + top-K, 2ª pasada conteo exacto y columnas que difieren.
+/// INPUT puede ser un CSV o una lista de archivos (glob, carpeta o .txt) con el mismo
+/// header; las claves se cuentan sobre todos juntos
+#[derive(Args, Debug)]
+pub struct DupReportArgs {
+    #[arg(value_name = "INPUT.csv[.gz]|LIST")]
+    pub input: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Key columns")]
+    pub keys: Vec<String>,
+    #[arg(long, value_name = "N", default_value_t = 50, help = "Keys with the most duplicates to report")]
+    pub top: usize,
+}
+
+pub fn dup_report(args: &DupReportArgs) -> Result<(), CsvToolsError> {
+    let files = resolve_inputs(&args.input)?;
+    let key_names = list_values(&args.keys);
+    let top = args.top;
+
+    let headers = open_csv(&files[0])?.headers()?.clone();
+    for file in &files[1..] {
+        if open_csv(file)?.headers()? != &headers {
+            return Err(exit_code::usage_error(format!("Header of {} differs from {}; dup-report needs the same header in every file", file, files[0])));
+        }
+    }
+    let key_indexes = dedup::key_indexes(&headers, &key_names)?;
+
+    let mut cms = CountMinSketch::new(CMS_WIDTH, CMS_DEPTH, repro::seed());
+    let collation = collation::current();
+
+    logging::banner("Duplicate Key Report (Top-K)");
+    if files.len() == 1 {
+        info!("📄 Input: {}", files[0]);
+    } else {
+        info!("📄 Input: {} ({} files)", args.input, files.len());
+    }
+    info!("🔑 Keys: {}", key_names.join(" + "));
+    info!("🔝 Top: {}", top);
+    info!("🔤 Collation: {}", collation);
+    info!("🧮 Count-min sketch: {}x{} ({}), seed {}", CMS_DEPTH, CMS_WIDTH, format_bytes(cms.memory_bytes() as u64), repro::seed());
+    info!();
+
+    // Pasada 1: conteos estimados + candidatos
+    info!("🔍 Pass 1/2: estimating key frequencies...");
+    let mut candidates = TopK::new(top * TOP_K_OVERSAMPLE);
+    let mut record = StringRecord::new();
+    let mut processed = 0usize;
+
+    let mut progress = Progress::rows("Processed");
+    for file in &files {
+        let mut rdr = open_csv(file)?;
+        while rdr.read_record(&mut record)? {
+            let key = composite_key(&record, &key_indexes, collation);
+            let estimate = cms.add(key.as_bytes());
+            if estimate > 1 {
+                candidates.offer(&key, estimate);
+            }
+
+            processed += 1;
+            progress.update(processed as u64, String::new);
+        }
+    }
+    progress.finish();
+    info!("📊 Processed: {}", processed);
+
+    let candidates = candidates.into_sorted();
+    if candidates.is_empty() {
+        info!();
+        info!("✅ No duplicate keys found");
+        return Ok(());
+    }
+
+    // Pasada 2: conteo exacto y diferencias solo para los candidatos
+    info!("🔍 Pass 2/2: exact counts for {} candidate keys...", candidates.len());
+    let estimates: HashMap<String, u64> = candidates.into_iter().collect();
+    let ranked = rank_candidates(&files, headers.len(), &key_indexes, collation, &estimates, top)?;
+
+    info!();
+    logging::banner("Top Duplicated Keys");
+
+    // Con varios archivos cada línea va con el nombre de su archivo
+    let location = |(file, line): (usize, u64)| if files.len() == 1 {
+        line.to_string()
+    } else {
+        format!("{}:{}", files[file], line)
+    };
+
+    for (rank, (key, detail)) in ranked.iter().enumerate() {
+        let key_display: Vec<String> = key_names.iter()
+            .zip(key.split('\u{1f}'))
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+
+        info!("{:>3}. {} → {} rows (est. {}, first at line {})",
+            rank + 1, key_display.join(", "), detail.count, estimates[key], location(detail.lines[0]));
+        let lines: Vec<String> = detail.lines.iter().map(|l| location(*l)).collect();
+        info!("      lines: {}{}", lines.join(", "), if detail.count > lines.len() { ", ..." } else { "" });
+
+        if detail.differing.is_empty() {
+            info!("      identical rows (exact duplicates)");
+        } else {
+            let names: Vec<&str> = detail.differing.iter().map(|(c, _, _, _)| &headers[*c]).collect();
+            info!("      differing columns: {}", names.join(", "));
+            for (column, first, other, line) in detail.differing.iter().take(MAX_DIFF_EXAMPLES) {
+                info!("        {}: '{}' vs '{}' (line {})", &headers[*column], first, other, location(*line));
+            }
+        }
+    }
+
+    info!();
+    info!("📊 Rows scanned: {}", processed);
+    info!("🔑 Keys reported: {} (estimates are upper bounds; counts are exact)", ranked.len());
+
+    Ok(())
+}
+
+/// Segunda pasada de dup-report: conteo exacto, primeras líneas y columnas que difieren
+/// de las claves candidatas; devuelve las `top` más repetidas
+fn rank_candidates(files: &[String], columns: usize, key_indexes: &[usize], collation: Collation,
+                   candidates: &HashMap<String, u64>, top: usize) -> Result<Vec<(String, KeyDetail)>, CsvToolsError> {
+    let mut details: HashMap<String, KeyDetail> = HashMap::with_capacity(candidates.len());
+    let mut record = StringRecord::new();
+
+    for (file, path) in files.iter().enumerate() {
+        let mut rdr = open_csv(path)?;
+        while rdr.read_record(&mut record)? {
+            let key = composite_key(&record, key_indexes, collation);
+            if !candidates.contains_key(&key) {
+                continue;
+            }
+
+            let line = record.position().map(|p| p.line()).unwrap_or(0);
+            match details.get_mut(&key) {
+                None => {
+                    details.insert(key, KeyDetail { count: 1, first_row: record.clone(), lines: vec![(file, line)], differing: Vec::new() });
+                }
+                Some(detail) => {
+                    detail.count += 1;
+                    if detail.lines.len() < MAX_LINES_SHOWN {
+                        detail.lines.push((file, line));
+                    }
+                    for i in 0..columns {
+                        let first = detail.first_row.get(i).unwrap_or("");
+                        let value = record.get(i).unwrap_or("");
+                        if first != value && !detail.differing.iter().any(|(c, _, _, _)| *c == i) {
+                            detail.differing.push((i, first.to_string(), value.to_string(), (file, line)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut ranked: Vec<(String, KeyDetail)> = details.into_iter().filter(|(_, d)| d.count > 1).collect();
+    ranked.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| collation.compare(&a.0, &b.0)));
+    ranked.truncate(top);
+    Ok(ranked)
+}
+
+/// Deduplica cualquier CSV por columnas clave del header (no solo las de un modelo)
+/// first: una pasada en streaming; last: dos pasadas (la primera ubica la última fila
+/// de cada clave). En memoria queda solo una huella de 128 bits por clave
+#[derive(Args, Debug)]
+pub struct DedupByKeysArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Key columns")]
+    pub keys: Vec<String>,
+    #[arg(long, num_args = 1..=2, value_names = ["first|last|latest-by", "DATE_COLUMN"], default_value = "first",
+          help = "Row kept when a key repeats: first, last, or latest-by <column> (most recent date)")]
+    pub keep: Vec<String>,
+    #[arg(long, help = "Collapse repeated internal whitespace in key values before comparing")]
+    pub normalize: bool,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', help = "Key columns compared case-insensitively (e.g. ApellidoNombre,RazonSocial)")]
+    pub fold_case: Vec<String>,
+    #[arg(long, value_name = "rules.yaml", help = "Per-column key rules (trim, uppercase, strip_leading_zeros, phone, ...) applied only to the key, not the output")]
+    pub canonicalize: Option<String>,
+    #[arg(long, value_name = "FILE.csv", help = "Log rows that were duplicates only after normalization (line, kept line, key values)")]
+    pub collapsed_log: Option<String>,
+}
+
+pub fn dedup_by_keys(args: &DedupByKeysArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let key_names = list_values(&args.keys);
+    let policy = keep_policy(&args.keep)?;
+    let keep = match &policy {
+        KeepPolicy::First => "first".to_string(),
+        KeepPolicy::Last => "last".to_string(),
+        KeepPolicy::LatestBy(column) => format!("latest-by {}", column),
+    };
+
+    logging::banner("Deduplicate by Key Columns");
+    info!("📄 Input: {}", input_path);
+    info!("📝 Output: {}", output_path);
+    info!("🔑 Keys: {}", key_names.join(" + "));
+    match &policy {
+        KeepPolicy::LatestBy(column) => info!("📌 Keep: most recent {} (ties: later row)", column),
+        _ => info!("📌 Keep: {} occurrence", keep),
+    }
+    info!("🔤 Collation: {}", collation::current());
+    let normalization = KeyNormalization {
+        collapse_whitespace: args.normalize,
+        fold_case: list_values(&args.fold_case).iter().map(|c| c.to_string()).collect(),
+        canonicalize: args.canonicalize.as_deref().map(CanonicalRules::load).transpose()?,
+    };
+    if normalization.is_active() {
+        info!("🧹 Normalize: whitespace {}, case-folded: {}",
+            if normalization.collapse_whitespace { "collapsed" } else { "trimmed" },
+            if normalization.fold_case.is_empty() { "-".to_string() } else { normalization.fold_case.join(", ") });
+    }
+    if let (Some(path), Some(rules)) = (&args.canonicalize, &normalization.canonicalize) {
+        info!("📐 Canonicalize: {} (columns: {})", path, rules.columns.keys().cloned().collect::<Vec<_>>().join(", "));
+    }
+    info!();
+
+    let report = dedup::by_keys_normalized(input_path, output_path, &key_names, policy.clone(), &normalization, args.collapsed_log.as_deref())?;
+
+    info!("📊 Rows read: {}", report.rows_read);
+    info!("✅ Rows written: {}", report.rows_written);
+    info!("🗑️  Duplicates removed: {}", report.duplicates_removed);
+    if report.invalid_dates > 0 {
+        diagnostics::warn(format!("{} rows have an empty or unparseable date in {}; they lose against any valid date",
+            report.invalid_dates, keep.trim_start_matches("latest-by ")));
+    }
+    if normalization.is_active() {
+        info!("🧹 Collapsed by normalization: {}", report.collapsed_by_normalization);
+        if let Some(log) = &args.collapsed_log {
+            info!("📝 Collapsed rows log: {}", log);
+        }
+    }
+    logging::summary(&serde_json::json!({
+        "command": "dedup_by_keys",
+        "input": input_path,
+        "output": output_path,
+        "keys": key_names,
+        "keep": keep,
+        "records": report.rows_read,
+        "records_written": report.rows_written,
+        "duplicates_removed": report.duplicates_removed,
+        "collapsed_by_normalization": report.collapsed_by_normalization,
+        "invalid_dates": report.invalid_dates,
+    }))?;
+
+    Ok(())
+}
+
+/// Lista completa de claves duplicadas con sus ocurrencias y números de línea, para
+/// investigar el origen de los duplicados antes de quitarlos (no escribe el CSV)
+/// 1ª pasada: conteo por huella de 128 bits; 2ª pasada: líneas solo de las repetidas
+#[derive(Args, Debug)]
+pub struct ReportDuplicatesArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Key columns")]
+    pub keys: Vec<String>,
+    #[arg(long, value_name = "FILE.csv", help = "Write every duplicated key as CSV: keys, occurrences, lines")]
+    pub output: Option<String>,
+    #[arg(long, value_name = "N", default_value_t = 20, help = "Duplicated keys printed to the console")]
+    pub show: usize,
+}
+
+/// Ocurrencias de una clave repetida (segunda pasada)
+struct DuplicateKey {
+    values: Vec<String>,
+    lines: Vec<u64>,
+}
+
+pub fn report_duplicates(args: &ReportDuplicatesArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let key_names = list_values(&args.keys);
+    let collation = collation::current();
+
+    logging::banner("Duplicate Keys Report");
+    info!("📄 Input: {}", input_path);
+    info!("🔑 Keys: {}", key_names.join(" + "));
+    info!("🔤 Collation: {}", collation);
+    if let Some(output) = &args.output {
+        info!("📝 Report: {}", output);
+    }
+    info!();
+
+    // Pasada 1: ocurrencias por clave
+    info!("🔍 Pass 1/2: counting keys...");
+    let mut rdr = open_csv(input_path)?;
+    let headers = rdr.headers()?.clone();
+    let key_indexes = dedup::key_indexes(&headers, &key_names)?;
+    let mut counts: HashMap<u128, u32> = HashMap::new();
+    let mut record = StringRecord::new();
+    let mut processed = 0usize;
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        *counts.entry(dedup::key_fingerprint(&record, &key_indexes, collation)).or_insert(0) += 1;
+        processed += 1;
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+
+    counts.retain(|_, count| *count > 1);
+
+    // Pasada 2: números de línea de las claves repetidas (si hay alguna)
+    let mut duplicates: HashMap<u128, DuplicateKey> = HashMap::with_capacity(counts.len());
+    let mut rdr = open_csv(input_path)?;
+    rdr.headers()?;
+    if !counts.is_empty() {
+        info!("🔍 Pass 2/2: collecting line numbers for {} duplicated keys...", counts.len());
+    }
+    while !counts.is_empty() && rdr.read_record(&mut record)? {
+        let fingerprint = dedup::key_fingerprint(&record, &key_indexes, collation);
+        let Some(count) = counts.get(&fingerprint) else { continue };
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        duplicates.entry(fingerprint)
+            .or_insert_with(|| DuplicateKey {
+                values: key_indexes.iter().map(|i| record.get(*i).unwrap_or("").trim().to_string()).collect(),
+                lines: Vec::with_capacity(*count as usize),
+            })
+            .lines.push(line);
+    }
+
+    // Más repetidas primero; a igualdad, por primera aparición
+    let mut ranked: Vec<DuplicateKey> = duplicates.into_values().collect();
+    ranked.sort_by(|a, b| b.lines.len().cmp(&a.lines.len()).then_with(|| a.lines[0].cmp(&b.lines[0])));
+    let duplicate_rows: usize = ranked.iter().map(|d| d.lines.len()).sum();
+
+    if let Some(output) = &args.output {
+        let mut sink = open_sink(output)?;
+        let mut header: Vec<&str> = key_names.clone();
+        header.extend(["occurrences", "lines"]);
+        sink.write_header(&StringRecord::from(header))?;
+        for duplicate in &ranked {
+            let mut row = duplicate.values.clone();
+            row.push(duplicate.lines.len().to_string());
+            row.push(duplicate.lines.iter().map(u64::to_string).collect::<Vec<_>>().join(";"));
+            sink.write_record(&StringRecord::from(row))?;
+        }
+        sink.finish()?;
+    }
+
+    if ranked.is_empty() {
+        info!();
+        info!("✅ No duplicate keys found in {} rows", processed);
+        return Ok(());
+    }
+
+    info!();
+    logging::banner("Duplicated Keys");
+    for duplicate in ranked.iter().take(args.show) {
+        let key_display: Vec<String> = key_names.iter()
+            .zip(&duplicate.values)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        let lines: Vec<String> = duplicate.lines.iter().take(MAX_LINES_SHOWN).map(u64::to_string).collect();
+        let more = if duplicate.lines.len() > MAX_LINES_SHOWN { ", ..." } else { "" };
+        info!("  {} → {} rows (lines {}{})", key_display.join(", "), duplicate.lines.len(), lines.join(", "), more);
+    }
+    if ranked.len() > args.show {
+        info!("  ... {} more keys{}", ranked.len() - args.show,
+            if args.output.is_some() { " (see the report file)" } else { " (use --output to list them all)" });
+    }
+
+    info!();
+    info!("📊 Rows scanned: {}", processed);
+    info!("🔑 Duplicated keys: {}", ranked.len());
+    info!("📑 Rows involved: {} ({} would be removed keeping one per key)", duplicate_rows, duplicate_rows - ranked.len());
+    logging::summary(&serde_json::json!({
+        "command": "report_duplicates",
+        "input": input_path,
+        "output": args.output,
+        "keys": key_names,
+        "records": processed,
+        "duplicated_keys": ranked.len(),
+        "duplicate_rows": duplicate_rows,
+    }))?;
+
+    Ok(())
+}
+
+/// Cuántas claves de `b` ya estaban en `a` (p. ej. si un re-export se superpone con un
+/// chunk anterior), con ejemplos, sin escribir ningún archivo
+/// En memoria queda una huella de 128 bits por clave distinta de `a` y de `b`
+#[derive(Args, Debug)]
+pub struct OverlapArgs {
+    #[arg(value_name = "A.csv[.gz]")]
+    pub a: String,
+    #[arg(value_name = "B.csv[.gz]")]
+    pub b: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Key columns (looked up by name in each header)")]
+    pub keys: Vec<String>,
+    #[arg(long, value_name = "N", default_value_t = 10, help = "Overlapping keys shown as examples")]
+    pub samples: usize,
+}
+
+pub fn overlap(args: &OverlapArgs) -> Result<(), CsvToolsError> {
+    let key_names = list_values(&args.keys);
+    let collation = collation::current();
+
+    logging::banner("Key Overlap Between Two Files");
+    info!("📄 A: {}", args.a);
+    info!("📄 B: {}", args.b);
+    info!("🔑 Keys: {}", key_names.join(" + "));
+    info!("🔤 Collation: {}", collation);
+    info!();
+
+    // Claves de A con la línea de su primera aparición
+    info!("🔍 Reading keys of A...");
+    let mut rdr = open_csv(&args.a)?;
+    let key_indexes = dedup::key_indexes(&rdr.headers()?.clone(), &key_names)?;
+    let mut keys_a: HashMap<u128, u64> = HashMap::new();
+    let mut record = StringRecord::new();
+    let mut rows_a = 0usize;
+    let mut progress = Progress::rows("A");
+    while rdr.read_record(&mut record)? {
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        keys_a.entry(dedup::key_fingerprint(&record, &key_indexes, collation)).or_insert(line);
+        rows_a += 1;
+        progress.update(rows_a as u64, String::new);
+    }
+    progress.finish();
+
+    info!("🔍 Matching keys of B...");
+    let mut rdr = open_csv(&args.b)?;
+    let key_indexes = dedup::key_indexes(&rdr.headers()?.clone(), &key_names)?;
+    let mut keys_b: HashSet<u128> = HashSet::new();
+    let mut shared: HashSet<u128> = HashSet::new();
+    let mut shared_rows_b = 0usize;
+    let mut rows_b = 0usize;
+    let mut samples: Vec<(String, u64, u64)> = Vec::new();
+    let mut progress = Progress::rows("B");
+    while rdr.read_record(&mut record)? {
+        let fingerprint = dedup::key_fingerprint(&record, &key_indexes, collation);
+        keys_b.insert(fingerprint);
+        if let Some(line_a) = keys_a.get(&fingerprint) {
+            shared_rows_b += 1;
+            if shared.insert(fingerprint) && samples.len() < args.samples {
+                let key_display: Vec<String> = key_names.iter().zip(&key_indexes)
+                    .map(|(name, i)| format!("{}={}", name, record.get(*i).unwrap_or("").trim()))
+                    .collect();
+                let line_b = record.position().map(|p| p.line()).unwrap_or(0);
+                samples.push((key_display.join(", "), *line_a, line_b));
+            }
+        }
+        rows_b += 1;
+        progress.update(rows_b as u64, String::new);
+    }
+    progress.finish();
+
+    let percent = |part: usize, total: usize| if total == 0 { 0.0 } else { part as f64 * 100.0 / total as f64 };
+    info!();
+    logging::banner("Overlap");
+    info!("📊 A: {} rows, {} distinct keys", rows_a, keys_a.len());
+    info!("📊 B: {} rows, {} distinct keys", rows_b, keys_b.len());
+    info!("🔁 Keys in both: {} ({:.2}% of A's keys, {:.2}% of B's keys)",
+        shared.len(), percent(shared.len(), keys_a.len()), percent(shared.len(), keys_b.len()));
+    info!("🔁 Rows of B whose key is in A: {} ({:.2}%)", shared_rows_b, percent(shared_rows_b, rows_b));
+    if !samples.is_empty() {
+        info!();
+        info!("Examples (first line in A → first line in B):");
+        for (key, line_a, line_b) in &samples {
+            info!("  {} (line {} → line {})", key, line_a, line_b);
+        }
+    }
+    if shared.is_empty() {
+        info!();
+        info!("✅ No overlapping keys");
+    }
+
+    logging::summary(&serde_json::json!({
+        "command": "overlap",
+        "a": args.a,
+        "b": args.b,
+        "keys": key_names,
+        "rows_a": rows_a,
+        "rows_b": rows_b,
+        "distinct_keys_a": keys_a.len(),
+        "distinct_keys_b": keys_b.len(),
+        "shared_keys": shared.len(),
+        "shared_rows_b": shared_rows_b,
+    }))?;
+
+    Ok(())
+}
+
+fn open_csv(input: &str) -> Result<csv::Reader<Box<dyn std::io::BufRead>>, CsvToolsError> {
+    Ok(ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input)?))
+}
+
+/// `--keep first|last|latest-by <columna>`
+fn keep_policy(values: &[String]) -> Result<KeepPolicy, CsvToolsError> {
+    match values {
+        [policy] if policy == "first" => Ok(KeepPolicy::First),
+        [policy] if policy == "last" => Ok(KeepPolicy::Last),
+        [policy, column] if policy == "latest-by" => Ok(KeepPolicy::LatestBy(column.clone())),
+        [policy] if policy == "latest-by" => Err(exit_code::usage_error("--keep latest-by needs a date column (e.g. --keep latest-by CreateDate)")),
+        _ => Err(exit_code::usage_error(format!("Invalid --keep '{}': use first, last or latest-by <column>", values.join(" ")))),
+    }
+}
+
+/// Clave compuesta con separador que no aparece en los datos (unit separator),
+/// normalizada según la collation (claves iguales bajo la collation son duplicadas)
+fn composite_key(record: &StringRecord, key_indexes: &[usize], collation: Collation) -> String {
+    key_indexes.iter()
+        .map(|i| collation.dedup_key(record.get(*i).unwrap_or("").trim()))
+        .collect::<Vec<_>>()
+        .join("\u{1f}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_report_duplicates_lists_lines() {
+        let dir = TempDir::new("report_dups");
+        let input = dir.write("in.csv", "Cuil,Id,v\n1,a,x\n2,a,x\n1,a,y\n3,b,x\n2,a,z\n1,a,z\n");
+
+        report_duplicates(&ReportDuplicatesArgs {
+            input,
+            keys: vec!["Cuil".to_string(), "Id".to_string()],
+            output: Some(dir.path("dups.csv")),
+            show: 20,
+        }).unwrap();
+        assert_eq!(dir.read("dups.csv"),
+            "Cuil,Id,occurrences,lines\n1,a,3,2;4;7\n2,a,2,3;6\n");
+    }
+
+    #[test]
+    fn test_rank_candidates_across_files() {
+        let dir = TempDir::new("dup_report");
+        let files = vec![dir.write("a.csv", "Cuil,v\n1,x\n2,x\n1,x\n"), dir.write("b.csv", "Cuil,v\n1,y\n2,x\n3,x\n")];
+
+        let candidates: HashMap<String, u64> = [("1".to_string(), 3), ("2".to_string(), 2), ("3".to_string(), 2)].into();
+        let ranked = rank_candidates(&files, 2, &[0], Collation::Binary, &candidates, 10).unwrap();
+        let keys: Vec<(&str, usize)> = ranked.iter().map(|(k, d)| (k.as_str(), d.count)).collect();
+        assert_eq!(keys, vec![("1", 3), ("2", 2)]);
+        assert_eq!(ranked[0].1.lines, vec![(0, 2), (0, 4), (1, 2)]);
+        assert_eq!(ranked[0].1.differing, vec![(1, "x".to_string(), "y".to_string(), (1, 2))]);
+    }
+
+    #[test]
+    fn test_dedup_by_keys_normalized_logs_collapsed_rows() {
+        let dir = TempDir::new("fuzzy_dedup");
+        let input = dir.write("in.csv", "Cuil,ApellidoNombre\n1,PEREZ JUAN\n1, perez  juan\n1,PEREZ JUAN\n2,Perez Juan\n");
+
+        dedup_by_keys(&DedupByKeysArgs {
+            input,
+            output: dir.path("out.csv"),
+            keys: vec!["Cuil".to_string(), "ApellidoNombre".to_string()],
+            keep: vec!["first".to_string()],
+            normalize: true,
+            fold_case: vec!["ApellidoNombre".to_string()],
+            canonicalize: None,
+            collapsed_log: Some(dir.path("collapsed.csv")),
+        }).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,ApellidoNombre\n1,PEREZ JUAN\n2,Perez Juan\n");
+        // La tercera fila es duplicada exacta: no figura en el log
+        assert_eq!(dir.read("collapsed.csv"), "line,kept_line,Cuil,ApellidoNombre\n3,2,1, perez  juan\n");
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+
+use clap::Args;
+use crate::config;
+use crate::cli_args::list_values;
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::exit_code::usage_error;
+use crate::file_utils::open_input;
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::sink::open_sink;
+
+/// Corrección puntual: columna → valor nuevo para todas las filas con la clave
+struct CellPatch {
+    column: usize,
+    value: String,
+    applied: usize,
+}
+
+/// Aplica correcciones puntuales (clave + columna + valor nuevo) sin regenerar el export
+/// Cada celda reemplazada queda en el log de auditoría con su valor anterior
+#[derive(Args, Debug)]
+pub struct PatchArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_name = "FIXES.csv", help = "Corrections file: <key columns>,column,value (one cell per row)")]
+    pub patches: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Key columns")]
+    pub key: Vec<String>,
+    #[arg(long, value_name = "AUDIT.csv", help = "Audit log [default: <output>.patch_audit.csv]")]
+    pub audit: Option<String>,
+}
+
+pub fn patch(args: &PatchArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let patches_path = args.patches.as_str();
+    let key_names = list_values(&args.key);
+    let audit_path = args.audit.clone()
+        .unwrap_or_else(|| format!("{}.patch_audit.csv", output_path));
+
+    logging::banner("Patch Cells from Corrections File");
+    info!("📄 Input:   {}", input_path);
+    info!("📄 Output:  {}", output_path);
+    info!("📝 Patches: {}", patches_path);
+    info!("🔑 Keys:    {}", key_names.join(" + "));
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers = reader.headers()?.clone();
+    let key_indexes = column_indexes(&headers, &key_names)?;
+
+    let mut patches = load_patches(patches_path, &key_names, &headers)?;
+    let total_patches: usize = patches.values().map(Vec::len).sum();
+    info!("📋 {} corrections for {} keys", total_patches, patches.len());
+
+    let mut audit = WriterBuilder::new().delimiter(config::delimiter()).from_path(&audit_path)?;
+    audit.write_record(["Line", "Key", "Column", "OldValue", "NewValue", "Status"])?;
+
+    let mut writer = open_sink(output_path)?;
+    writer.write_header(&headers)?;
+
+    let mut record = StringRecord::new();
+    let mut processed = 0usize;
+    let mut cells_changed = 0usize;
+    let mut rows_patched = 0usize;
+
+    let mut progress = Progress::rows("Processed");
+    while reader.read_record(&mut record)? {
+        processed += 1;
+        let key = composite_key(&record, &key_indexes);
+
+        if let Some(cell_patches) = patches.get_mut(&key) {
+            let line = record.position().map(|p| p.line()).unwrap_or(0);
+            let mut fields: Vec<String> = record.iter().map(str::to_string).collect();
+            if fields.len() < headers.len() {
+                fields.resize(headers.len(), String::new());
+            }
+
+            for cell in cell_patches.iter_mut() {
+                let old = std::mem::replace(&mut fields[cell.column], cell.value.clone());
+                let status = if old == cell.value { "UNCHANGED" } else { "APPLIED" };
+                if old != cell.value {
+                    cells_changed += 1;
+                }
+                cell.applied += 1;
+                audit.write_record([
+                    line.to_string().as_str(), &key.replace('\u{1f}', "|"), &headers[cell.column], &old, &cell.value, status,
+                ])?;
+            }
+
+            rows_patched += 1;
+            writer.write_record(&StringRecord::from(fields))?;
+        } else {
+            writer.write_record(&record)?;
+        }
+
+        progress.update(processed as u64, || format!("Rows patched: {}", rows_patched));
+    }
+
+    // Correcciones cuya clave no apareció en el archivo
+    let mut not_found = 0usize;
+    let mut missing: Vec<(&String, &CellPatch)> = patches.iter()
+        .flat_map(|(key, cells)| cells.iter().map(move |c| (key, c)))
+        .filter(|(_, c)| c.applied == 0)
+        .collect();
+    missing.sort_by(|a, b| a.0.cmp(b.0).then_with(|| a.1.column.cmp(&b.1.column)));
+    for (key, cell) in missing {
+        not_found += 1;
+        audit.write_record(["", &key.replace('\u{1f}', "|"), &headers[cell.column], "", &cell.value, "NOT_FOUND"])?;
+    }
+
+    writer.finish()?;
+    audit.flush()?;
+    progress.finish();
+
+    info!("📊 Processed: {} | Rows patched: {}", processed, rows_patched);
+    info!();
+    logging::banner("Patch Summary");
+    info!("📊 Records processed: {}", processed);
+    info!("✏️  Rows patched:      {}", rows_patched);
+    info!("✏️  Cells changed:     {}", cells_changed);
+    info!();
+    info!("📝 Files created:");
+    info!("   Patched CSV: {}", output_path);
+    info!("   Audit log:   {}", audit_path);
+
+    if not_found > 0 {
+        info!();
+        diagnostics::warn(format!("WARNING: {} corrections did not match any row (NOT_FOUND in audit log)", not_found));
+    }
+
+    Ok(())
+}
+
+/// Elimina las filas cuya clave está en la lista (pedidos de supresión de datos)
+/// y deja las filas eliminadas en un log aparte
+#[derive(Args, Debug)]
+pub struct DeleteKeysArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_name = "KEYS.txt", help = "One key per line (comma-separated values for composite keys)")]
+    pub keys_file: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Key column(s)")]
+    pub key_column: Vec<String>,
+    #[arg(long, value_name = "REMOVED.csv", help = "Log of removed rows [default: <output>.removed.csv]")]
+    pub log: Option<String>,
+}
+
+pub fn delete_keys(args: &DeleteKeysArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let keys_path = args.keys_file.as_str();
+    let key_names = list_values(&args.key_column);
+    let log_path = args.log.clone()
+        .unwrap_or_else(|| format!("{}.removed.csv", output_path));
+
+    logging::banner("Delete Rows by Key List");
+    info!("📄 Input:  {}", input_path);
+    info!("📄 Output: {}", output_path);
+    info!("🔑 Keys:   {} (from {})", key_names.join(" + "), keys_path);
+    info!();
+
+    let keys = load_key_list(keys_path, &key_names)?;
+    info!("📋 {} keys to delete", keys.len());
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers = reader.headers()?.clone();
+    let key_indexes = column_indexes(&headers, &key_names)?;
+
+    let mut writer = open_sink(output_path)?;
+    let mut removed_log = WriterBuilder::new()
+        .delimiter(config::delimiter())
+        .quote_style(csv::QuoteStyle::Necessary)
+        .from_path(&log_path)?;
+    writer.write_header(&headers)?;
+    removed_log.write_record(&headers)?;
+
+    let mut record = StringRecord::new();
+    let mut matched: HashSet<String> = HashSet::new();
+    let mut processed = 0usize;
+    let mut removed = 0usize;
+
+    let mut progress = Progress::rows("Processed");
+    while reader.read_record(&mut record)? {
+        processed += 1;
+        let key = composite_key(&record, &key_indexes);
+
+        if keys.contains(&key) {
+            removed += 1;
+            removed_log.write_record(&record)?;
+            matched.insert(key);
+        } else {
+            writer.write_record(&record)?;
+        }
+
+        progress.update(processed as u64, || format!("Removed: {}", removed));
+    }
+
+    writer.finish()?;
+    removed_log.flush()?;
+    progress.finish();
+
+    info!("📊 Processed: {} | Removed: {}", processed, removed);
+    info!();
+    logging::banner("Delete Summary");
+    info!("📊 Records processed: {}", processed);
+    info!("🗑️  Rows removed:      {}", removed);
+    info!("✅ Rows kept:         {}", processed - removed);
+    info!("🔑 Keys matched:      {} of {}", matched.len(), keys.len());
+    info!();
+    info!("📝 Files created:");
+    info!("   Output CSV:   {}", output_path);
+    info!("   Removed rows: {}", log_path);
+
+    let not_found = keys.len() - matched.len();
+    if not_found > 0 {
+        let mut examples: Vec<&String> = keys.iter().filter(|k| !matched.contains(*k)).collect();
+        examples.sort();
+        let shown: Vec<String> = examples.iter().take(5).map(|k| k.replace('\u{1f}', ",")).collect();
+        info!();
+        diagnostics::warn(format!(
+            "WARNING: {} keys were not found in the input ({}{})",
+            not_found, shown.join("; "), if not_found > 5 { "; ..." } else { "" }
+        ));
+    }
+
+    Ok(())
+}
+
+/// Lista de claves: una por línea, valores separados por coma para claves compuestas
+/// (se ignoran líneas vacías, comentarios `#` y un header igual a los nombres de columna)
+fn load_key_list(path: &str, key_names: &[&str]) -> Result<HashSet<String>, CsvToolsError> {
+    let mut keys = HashSet::new();
+
+    for (idx, line) in open_input(path)?.lines().enumerate() {
+        let line = line?;
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split(config::delimiter() as char).map(|p| p.trim().trim_matches('"')).collect();
+        if idx == 0 && parts == key_names {
+            continue;
+        }
+        if parts.len() != key_names.len() {
+            return Err(format!(
+                "Keys file '{}' line {}: expected {} value(s) ({}), found {}",
+                path, idx + 1, key_names.len(), key_names.join(","), parts.len()
+            ).into());
+        }
+        keys.insert(parts.join("\u{1f}"));
+    }
+
+    Ok(keys)
+}
+
+/// Lee el archivo de correcciones: columnas de clave + `column` + `value`
+fn load_patches(
+    path: &str,
+    key_names: &[&str],
+    headers: &StringRecord,
+) -> Result<HashMap<String, Vec<CellPatch>>, CsvToolsError> {
+    let mut reader = ReaderBuilder::new().delimiter(config::delimiter()).has_headers(true).from_reader(open_input(path)?);
+    let patch_headers = reader.headers()?.clone();
+
+    let mut wanted: Vec<&str> = key_names.to_vec();
+    wanted.extend(["column", "value"]);
+    let idx = column_indexes(&patch_headers, &wanted)
+        .map_err(|e| usage_error(format!("Patches file '{}': {}", path, e)))?;
+    let (key_indexes, column_idx, value_idx) = (&idx[..key_names.len()], idx[key_names.len()], idx[key_names.len() + 1]);
+
+    let mut patches: HashMap<String, Vec<CellPatch>> = HashMap::new();
+    for result in reader.records() {
+        let record = result?;
+        let column_name = record.get(column_idx).unwrap_or("").trim();
+        let column = headers.iter().position(|h| h.trim() == column_name)
+            .ok_or_else(|| format!("Patches file '{}': column '{}' not found in input header", path, column_name))?;
+        if key_names.contains(&column_name) {
+            return Err(format!("Patches file '{}': key column '{}' cannot be patched", path, column_name).into());
+        }
+
+        patches.entry(composite_key(&record, key_indexes)).or_default().push(CellPatch {
+            column,
+            value: record.get(value_idx).unwrap_or("").to_string(),
+            applied: 0,
+        });
+    }
+
+    Ok(patches)
+}
+
+fn column_indexes(headers: &StringRecord, names: &[&str]) -> Result<Vec<usize>, CsvToolsError> {
+    names.iter()
+        .map(|n| headers.iter().position(|h| h.trim() == *n)
+            .ok_or_else(|| usage_error(format!("Column '{}' not found in header", n))))
+        .collect()
+}
+
+/// Clave compuesta con separador que no aparece en los datos (unit separator)
+fn composite_key(record: &StringRecord, key_indexes: &[usize]) -> String {
+    key_indexes.iter()
+        .map(|i| record.get(*i).unwrap_or("").trim())
+        .collect::<Vec<_>>()
+        .join("\u{1f}")
+}
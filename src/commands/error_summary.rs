@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use clap::Args;
+use crate::cli_args::list_values;
+use crate::error::CsvToolsError;
+use crate::error_report::{ErrorRecord, CSV_HEADER};
+use crate::file_utils::open_input;
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+
+// Ejemplos que se guardan por grupo
+const DEFAULT_EXAMPLES: usize = 3;
+
+lazy_static! {
+    // `[LINE 12] ❌ INVALID_NUMERIC | Field='Cuil' | Value='x' | ...`
+    static ref LOG_LINE: Regex = Regex::new(r"^\[LINE (\d+)\]\s*[^A-Za-z]*([A-Z][A-Z_]+):?\s*(.*)$").unwrap();
+    static ref COLUMN_ATTR: Regex = Regex::new(r"(?:Field|Column)='([^']*)'").unwrap();
+    static ref VALUE_ATTR: Regex = Regex::new(r"(?:Value|Original)='([^']*)'").unwrap();
+    // Mensajes de validate_field_type: "Invalid Cuil (...)", "Field X cannot...", "... for X: v"
+    static ref CSV_COLUMN: Regex = Regex::new(r"^(?:Invalid (?:date format for )?|Field |Unknown field: )(\w+)").unwrap();
+}
+
+/// Una entrada del reporte de errores, normalizada
+struct ErrorEntry {
+    line: String,
+    error_type: String,
+    column: String,
+    value: String,
+}
+
+/// Grupo agregado: cantidad + algunos ejemplos (línea, valor)
+struct ErrorGroup {
+    count: usize,
+    examples: Vec<(String, String)>,
+}
+
+/// Formatos de reporte que entiende `summarize-errors`
+#[derive(Clone, Copy, PartialEq)]
+enum ReportFormat {
+    /// `[LINE n] TYPE | ...`
+    Log,
+    /// `Line,ErrorType,Details,DynamoDbKey,SqlCompositeKey` (validate_model, clean_invalid_lines)
+    ValidationCsv,
+    /// --error-report CSV: `line,byte_offset,error_type,column,value,message`
+    ErrorReportCsv,
+    /// --error-report NDJSON
+    Ndjson,
+}
+
+/// Agrega un log/reporte de errores en conteos por tipo/columna/patrón de valor
+/// Entiende los logs `[LINE n] TYPE | ...`, los reportes CSV `Line,ErrorType,Details,...`
+/// y los reportes de --error-report (CSV o NDJSON)
+#[derive(Args, Debug)]
+pub struct SummarizeErrorsArgs {
+    #[arg(value_name = "ERROR_REPORT",
+          help = "*.log ([LINE n] TYPE | ...), validation CSV report (Line,ErrorType,Details,...) or --error-report CSV/NDJSON")]
+    pub report: String,
+    #[arg(long, value_name = "DIMENSIONS", value_delimiter = ',', default_value = "error_type,column,pattern",
+          value_parser = ["error_type", "column", "pattern", "value"], help = "Grouping dimensions")]
+    pub group_by: Vec<String>,
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_EXAMPLES, help = "Examples kept per group")]
+    pub examples: usize,
+}
+
+pub fn summarize_errors(args: &SummarizeErrorsArgs) -> Result<(), CsvToolsError> {
+    let report_path = args.report.as_str();
+    let group_by = list_values(&args.group_by);
+    let max_examples = args.examples;
+
+    logging::banner("Error Report Summary");
+    info!("📄 Report: {}", report_path);
+    info!("📋 Group by: {}", group_by.join(", "));
+    info!();
+
+    let reader = open_input(report_path)?;
+    let mut groups: HashMap<Vec<String>, ErrorGroup> = HashMap::new();
+    let mut total = 0usize;
+    let mut format = ReportFormat::Log;
+
+    let mut progress = Progress::rows("Entries");
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+
+        if idx == 0 {
+            if line.starts_with("Line,ErrorType,") || line.starts_with("Line,Issue,") {
+                format = ReportFormat::ValidationCsv;
+                continue;
+            }
+            if line == CSV_HEADER.join(",") {
+                format = ReportFormat::ErrorReportCsv;
+                continue;
+            }
+            if line.starts_with('{') {
+                format = ReportFormat::Ndjson;
+            }
+        }
+
+        let entry = match format {
+            ReportFormat::Log => parse_log_entry(&line),
+            ReportFormat::ValidationCsv => parse_csv_entry(&line),
+            ReportFormat::ErrorReportCsv => parse_report_entry(&line, false),
+            ReportFormat::Ndjson => parse_report_entry(&line, true),
+        };
+        let Some(entry) = entry else {
+            continue;
+        };
+        total += 1;
+
+        let key: Vec<String> = group_by.iter().map(|d| match *d {
+            "error_type" => entry.error_type.clone(),
+            "column" => entry.column.clone(),
+            "value" => entry.value.clone(),
+            _ => value_pattern(&entry.value),
+        }).collect();
+
+        let group = groups.entry(key).or_insert_with(|| ErrorGroup { count: 0, examples: Vec::new() });
+        group.count += 1;
+        if group.examples.len() < max_examples {
+            group.examples.push((entry.line, entry.value));
+        }
+
+        progress.update(total as u64, || format!("Groups: {}", groups.len()));
+    }
+    progress.finish();
+
+    let mut sorted: Vec<(Vec<String>, ErrorGroup)> = groups.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(&b.0)));
+
+    info!("📊 Entries: {} | Groups: {}", total, sorted.len());
+    info!("📄 Format: {}", match format {
+        ReportFormat::Log => "[LINE n] log",
+        ReportFormat::ValidationCsv => "validation CSV report",
+        ReportFormat::ErrorReportCsv => "error report (CSV)",
+        ReportFormat::Ndjson => "error report (NDJSON)",
+    });
+    info!();
+
+    if sorted.is_empty() {
+        info!("✅ No error entries found");
+        return Ok(());
+    }
+
+    for (key, group) in &sorted {
+        let labels: Vec<String> = group_by.iter()
+            .zip(key)
+            .map(|(d, v)| format!("{}={}", d, if v.is_empty() { "(none)" } else { v }))
+            .collect();
+        let share = group.count as f64 / total as f64 * 100.0;
+
+        info!("❌ {:>9} ({:>5.1}%)  {}", group.count, share, labels.join(" | "));
+        for (line, value) in &group.examples {
+            info!("      line {}: '{}'", line, value);
+        }
+    }
+
+    Ok(())
+}
+
+/// `[LINE n] <emoji> TYPE | Field='x' | Value='y' | detalle`
+fn parse_log_entry(line: &str) -> Option<ErrorEntry> {
+    let caps = LOG_LINE.captures(line)?;
+    let details = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+
+    Some(ErrorEntry {
+        line: caps[1].to_string(),
+        error_type: caps[2].to_string(),
+        column: COLUMN_ATTR.captures(details).map(|c| c[1].to_string()).unwrap_or_default(),
+        value: VALUE_ATTR.captures(details).map(|c| c[1].to_string()).unwrap_or_default(),
+    })
+}
+
+/// `Line,ErrorType,Details,DynamoDbKey,SqlCompositeKey` (Details puede tener comas)
+fn parse_csv_entry(line: &str) -> Option<ErrorEntry> {
+    let mut head = line.splitn(3, ',');
+    let line_num = head.next()?.trim().to_string();
+    let error_type = head.next()?.trim().to_string();
+    let rest = head.next().unwrap_or("");
+
+    // Las dos últimas columnas son las claves
+    let mut tail = rest.rsplitn(3, ',');
+    let details = match (tail.next(), tail.next(), tail.next()) {
+        (Some(_), Some(_), Some(d)) => d,
+        _ => rest,
+    };
+
+    Some(ErrorEntry {
+        line: line_num,
+        error_type,
+        column: CSV_COLUMN.captures(details).map(|c| c[1].to_string()).unwrap_or_default(),
+        value: details.rsplit_once(": ").map(|(_, v)| v.trim().to_string()).unwrap_or_default(),
+    })
+}
+
+/// Registro de --error-report: los campos ya vienen separados
+fn parse_report_entry(line: &str, ndjson: bool) -> Option<ErrorEntry> {
+    let record: ErrorRecord = if ndjson {
+        serde_json::from_str(line).ok()?
+    } else {
+        csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(line.as_bytes())
+            .deserialize()
+            .next()?
+            .ok()?
+    };
+
+    Some(ErrorEntry {
+        line: record.line.to_string(),
+        error_type: record.error_type,
+        column: record.column.unwrap_or_default(),
+        value: record.value.unwrap_or_default(),
+    })
+}
+
+/// Forma del valor: dígitos → 9, letras → a (rachas colapsadas), resto tal cual
+/// "20-12345678-9" → "9-9-9", "ABC 12" → "a 9", "" → "(empty)"
+fn value_pattern(value: &str) -> String {
+    if value.is_empty() {
+        return "(empty)".to_string();
+    }
+
+    let mut pattern = String::new();
+    for c in value.chars() {
+        let class = if c.is_ascii_digit() {
+            '9'
+        } else if c.is_alphabetic() {
+            'a'
+        } else {
+            c
+        };
+        if (class == '9' || class == 'a') && pattern.ends_with(class) {
+            continue;
+        }
+        pattern.push(class);
+    }
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entries() {
+        let entry = parse_log_entry("[LINE 42] ❌ INVALID_NUMERIC | Field='Cuil' | Value='20-1' | Expected: Type N (numeric)").unwrap();
+        assert_eq!((entry.line.as_str(), entry.error_type.as_str()), ("42", "INVALID_NUMERIC"));
+        assert_eq!((entry.column.as_str(), entry.value.as_str()), ("Cuil", "20-1"));
+
+        let warning = parse_log_entry("[LINE 1] ⚠️  WARNING: Header has 13 columns, expected 14").unwrap();
+        assert_eq!(warning.error_type, "WARNING");
+        assert!(parse_log_entry("  CSV: a,b,c").is_none());
+
+        let csv = parse_csv_entry("17,TypeError,Invalid IdRegion (must be integer): 1,5,20123|1,5|1|123").unwrap();
+        assert_eq!((csv.error_type.as_str(), csv.column.as_str(), csv.value.as_str()), ("TypeError", "IdRegion", "1,5"));
+
+        let report = parse_report_entry("9,512,INVALID_NUMERIC,Cuil,\"20,1\",Expected: Type N (numeric)", false).unwrap();
+        assert_eq!((report.line.as_str(), report.column.as_str(), report.value.as_str()), ("9", "Cuil", "20,1"));
+        let json = parse_report_entry(r#"{"line":3,"byte_offset":null,"error_type":"EMPTY_KEY","column":null,"value":null,"message":"Keys: Cuil"}"#, true).unwrap();
+        assert_eq!((json.error_type.as_str(), json.column.as_str()), ("EMPTY_KEY", ""));
+    }
+
+    #[test]
+    fn test_value_pattern() {
+        assert_eq!(value_pattern("20-12345678-9"), "9-9-9");
+        assert_eq!(value_pattern("ABC 12.50"), "a 9.9");
+        assert_eq!(value_pattern(""), "(empty)");
+    }
+}
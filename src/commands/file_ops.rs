@@ -1,16 +1,31 @@
-use csv::{Reader, ReaderBuilder, WriterBuilder, StringRecord, Writer};
-use std::error::Error;
+use csv::{ReaderBuilder, WriterBuilder, StringRecord};
 use std::fs;
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Write, BufRead};
+use std::io::{BufWriter, Write, BufRead};
+use std::time::Instant;
 use std::collections::{HashMap, HashSet};
 use regex::Regex;
 use lazy_static::lazy_static;
 use chrono::NaiveDateTime;
 
+use crate::error::CsvToolsError;
+use crate::error_report::{self, ErrorRecord};
+use crate::info;
+use crate::merge_report::{HeaderCheck, MergeReport};
+use crate::logging;
 use crate::models::{
     get_dynamodb_key_columns
 };
+use crate::checkpoint::{CheckpointManager, ResumeArgs};
+use crate::config;
+use crate::diagnostics;
+use crate::disk_guard::{Checkpoint, DiskSpaceGuard};
+use crate::exit_code;
+use crate::compression;
+use crate::file_utils::{self, open_input};
+use crate::html_report::ValidationReport;
+use crate::progress::Progress;
+use crate::sink::open_sink;
 
 // Constantes
 const EXPECTED_COLS: usize = 14; // siisa_morosos default
@@ -22,32 +37,18 @@ const EXPECTED_COLS: usize = 14; // siisa_morosos default
 /// Preserva fechas que ya están en formato ISO válido
 /// Soporta formato europeo (dd/MM/yyyy), estadounidense (MM/dd/yyyy) e ISO existente
 /// Sigue convenciones SiisaRestApi: CsvHelper-based parsing + structured error reporting
-pub fn convert_date_format(args: &[String]) -> Result<(), Box<dyn Error>> {
-    if args.len() < 5 {
-        eprintln!("❌ Usage: csv_tools convert_date <input.csv> <output.csv> <date_column>");
-        eprintln!("💡 Converts multiple date formats to ISO format yyyy-MM-ddTHH:mm:ss");
-        eprintln!("💡 Supports European format: 'dd/MM/yyyy HH:mm[:ss]'");
-        eprintln!("💡 Supports US format: 'MM/dd/yyyy HH:mm[:ss]'");
-        eprintln!("💡 Preserves ISO format: 'yyyy-MM-ddTHH:mm[:ss]'");
-        std::process::exit(1);
-    }
-
-    let input_file = &args[2];
-    let output_file = &args[3];
-    let date_column = &args[4];
-
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Date Format Converter (Multi-format → ISO)                 ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📄 Input CSV: {}", input_file);
-    println!("📝 Output CSV: {}", output_file);
-    println!("📅 Date column: {}", date_column);
-    println!("🔄 European: dd/MM/yyyy HH:mm[:ss] → yyyy-MM-ddTHH:mm:ss");
-    println!("🔄 US Format: MM/dd/yyyy HH:mm[:ss] → yyyy-MM-ddTHH:mm:ss");
-    println!("✅ ISO Format: yyyy-MM-ddTHH:mm[:ss] → preserved");
-    println!();
-
-    let error_log_path = format!("{}.date_conversion_errors.log", output_file);
+pub fn convert_date_format(input_file: &str, output_file: &str, date_column: &str) -> Result<(), CsvToolsError> {
+
+    logging::banner("Date Format Converter (Multi-format → ISO)");
+    info!("📄 Input CSV: {}", input_file);
+    info!("📝 Output CSV: {}", output_file);
+    info!("📅 Date column: {}", date_column);
+    info!("🔄 European: dd/MM/yyyy HH:mm[:ss] → yyyy-MM-ddTHH:mm:ss");
+    info!("🔄 US Format: MM/dd/yyyy HH:mm[:ss] → yyyy-MM-ddTHH:mm:ss");
+    info!("✅ ISO Format: yyyy-MM-ddTHH:mm[:ss] → preserved");
+    info!();
+
+    let error_log_path = config::error_log_path(output_file, ".date_conversion_errors.log");
     let mut log = File::create(&error_log_path)?;
 
     writeln!(log, "# Date Format Conversion Error Log")?;
@@ -61,13 +62,12 @@ pub fn convert_date_format(args: &[String]) -> Result<(), Box<dyn Error>> {
     writeln!(log, "# -------------------------------------------------------")?;
 
     let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .flexible(true)
         .trim(csv::Trim::All)
-        .from_path(input_file)?;
+        .from_reader(open_input(input_file)?);
 
-    let mut wtr = WriterBuilder::new()
-        .quote_style(csv::QuoteStyle::Necessary)
-        .from_path(output_file)?;
+    let mut wtr = open_sink(output_file)?;
 
     let headers = rdr.headers()?.clone();
     
@@ -75,32 +75,30 @@ pub fn convert_date_format(args: &[String]) -> Result<(), Box<dyn Error>> {
         .position(|h| h.trim() == date_column)
         .ok_or_else(|| format!("Column '{}' not found in CSV", date_column))?;
 
-    println!("📊 Column analysis:");
-    println!("   Date column '{}' found at index {}", date_column, date_col_idx);
-    println!();
+    info!("📊 Column analysis:");
+    info!("   Date column '{}' found at index {}", date_column, date_col_idx);
+    info!();
 
-    wtr.write_record(&headers)?;
+    wtr.write_header(&headers)?;
 
     let mut total_processed = 0usize;
     let mut conversion_errors = 0usize;
     let mut successful_conversions = 0usize;
     let mut line_num = 2usize; // header is line 1
 
-    println!("🔍 Processing records...");
-    println!();
+    info!("🔍 Processing records...");
+    info!();
 
+    let mut progress = Progress::rows("Processed");
     for result in rdr.records() {
         total_processed += 1;
+        progress.update(total_processed as u64, || format!("Converted: {} | Errors: {}", successful_conversions, conversion_errors));
         
         let record = match result {
             Ok(r) => r,
             Err(e) => {
                 conversion_errors += 1;
-                writeln!(
-                    log,
-                    "[LINE {}] ❌ PARSE_ERROR | CSV parsing failed: {}",
-                    line_num, e
-                )?;
+                error_report::write(&mut log, &ErrorRecord::parse_error(line_num, &e, format!("CSV parsing failed: {}", e)))?;
                 line_num += 1;
                 continue;
             }
@@ -130,58 +128,53 @@ pub fn convert_date_format(args: &[String]) -> Result<(), Box<dyn Error>> {
                         "[LINE {}] ❌ DATE_CONVERSION_ERROR | Original='{}' | Error: {}",
                         line_num, original_date, e
                     )?;
+                    error_report::emit(&ErrorRecord::new(line_num, "DATE_CONVERSION_ERROR", e.to_string())
+                        .at(record.position()).column(date_column).value(original_date))?;
                     writeln!(log, "  CSV: {}", serialize_record_for_log(&record))?;
                     writeln!(log, "")?;
                 }
             }
         }
 
-        if total_processed % 10_000 == 0 {
-            print!("\r📊 Processed: {} | Converted: {} | Errors: {}", 
-                total_processed, successful_conversions, conversion_errors);
-            std::io::stdout().flush().ok();
-        }
-
         line_num += 1;
     }
 
-    wtr.flush()?;
+    wtr.finish()?;
     log.flush()?;
+    progress.finish();
 
-    println!("\r📊 Processed: {} | Converted: {} | Errors: {}", 
+    info!("📊 Processed: {} | Converted: {} | Errors: {}", 
         total_processed, successful_conversions, conversion_errors);
-    println!();
-
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Date Conversion Summary                                     ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Input CSV:");
-    println!("   Total records processed: {}", total_processed);
-    println!();
-    println!("📊 Output CSV:");
-    println!("   Successfully converted: {} ✅", successful_conversions);
-    println!("   Date conversion errors: {} ❌", conversion_errors);
+    info!();
+
+    logging::banner("Date Conversion Summary");
+    info!("📊 Input CSV:");
+    info!("   Total records processed: {}", total_processed);
+    info!();
+    info!("📊 Output CSV:");
+    info!("   Successfully converted: {} ✅", successful_conversions);
+    info!("   Date conversion errors: {} ❌", conversion_errors);
     
     if conversion_errors > 0 {
-        println!("   Error rate: {:.2}%", 
+        info!("   Error rate: {:.2}%", 
             (conversion_errors as f64 / total_processed as f64) * 100.0);
     }
     
-    println!();
-    println!("📝 Files created:");
-    println!("   Converted CSV: {}", output_file);
+    info!();
+    info!("📝 Files created:");
+    info!("   Converted CSV: {}", output_file);
     if conversion_errors > 0 {
-        println!("   Error log: {}", error_log_path);
+        info!("   Error log: {}", error_log_path);
     }
     
     if conversion_errors > 0 {
-        println!();
-        println!("⚠️  WARNING: {} records had date conversion errors", conversion_errors);
-        println!("   Review error log: {}", error_log_path);
-        println!("   These records were SKIPPED in the output");
+        info!();
+        diagnostics::warn(format!("WARNING: {} records had date conversion errors", conversion_errors));
+        info!("   Review error log: {}", error_log_path);
+        info!("   These records were SKIPPED in the output");
     } else {
-        println!();
-        println!("🎯 All dates successfully converted to ISO format ✅");
+        info!();
+        info!("🎯 All dates successfully converted to ISO format ✅");
     }
 
     Ok(())
@@ -190,7 +183,7 @@ pub fn convert_date_format(args: &[String]) -> Result<(), Box<dyn Error>> {
 /// Convierte fecha de dd/MM/yyyy o MM/dd/yyyy HH:mm:ss o HH:mm a yyyy-MM-ddTHH:mm:ss
 /// También preserva fechas que ya están en formato ISO válido
 /// Soporta tanto formatos europeos (dd/MM/yyyy) como estadounidenses (MM/dd/yyyy)
-fn convert_date_dd_mm_yyyy_to_iso(date_str: &str) -> Result<String, Box<dyn Error>> {
+pub(crate) fn convert_date_dd_mm_yyyy_to_iso(date_str: &str) -> Result<String, CsvToolsError> {
     // First, check if it's already in ISO format (yyyy-MM-ddTHH:mm:ss or yyyy-MM-ddTHH:mm)
     if let Ok(parsed_date) = NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S") {
         return Ok(parsed_date.format("%Y-%m-%dT%H:%M:%S").to_string());
@@ -228,40 +221,37 @@ fn convert_date_dd_mm_yyyy_to_iso(date_str: &str) -> Result<String, Box<dyn Erro
 /// Sigue convenciones SiisaRestApi: CsvHelper-based parsing + structured error reporting
 /// 
 /// ✅ FIX: Preserva header original sin sanitización
-pub fn sanitize_for_dynamodb_auto(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let input_file = &args[2];
-    let output_file = &args[3];
-    let model_type = args.get(4).map(String::as_str).unwrap_or("siisa_morosos");
-    
-    let expected_cols = args.get(5)
-        .and_then(|s| s.parse::<usize>().ok())
-        .unwrap_or_else(|| {
-            match model_type {
-                "siisa_morosos" => 14,
-                "personas_telefonos" => 13,
-                _ => EXPECTED_COLS
-            }
-        });
-
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  DynamoDB Auto-Sanitizer (SiisaRestApi Compatible)          ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📋 Model type: {}", model_type);
-    println!("📋 Expected columns: {}", expected_cols);
-    println!("📄 Input CSV: {}", input_file);
-    println!("📝 Output CSV: {}", output_file);
-    println!("🔧 Strategy: CsvHelper-based parsing + validate numeric fields");
-    println!();
+pub fn sanitize_for_dynamodb_auto(
+    input_file: &str,
+    output_file: &str,
+    model_type: &str,
+    expected_cols: Option<usize>,
+    report_html: Option<&str>,
+    row_number: Option<&str>,
+) -> Result<(), CsvToolsError> {
+    let expected_cols = expected_cols.unwrap_or(match model_type {
+        "siisa_morosos" => 14,
+        "personas_telefonos" => 13,
+        _ => EXPECTED_COLS
+    });
+
+    logging::banner("DynamoDB Auto-Sanitizer (SiisaRestApi Compatible)");
+    info!("📋 Model type: {}", model_type);
+    info!("📋 Expected columns: {}", expected_cols);
+    info!("📄 Input CSV: {}", input_file);
+    info!("📝 Output CSV: {}", output_file);
+    info!("🔧 Strategy: CsvHelper-based parsing + validate numeric fields");
+    info!();
 
     let numeric_fields = get_numeric_fields_local(model_type)?;
     
-    println!("🔑 DynamoDB Numeric Fields (Type: N):");
+    info!("🔑 DynamoDB Numeric Fields (Type: N):");
     for field in &numeric_fields {
-        println!("   - {}", field);
+        info!("   - {}", field);
     }
-    println!();
+    info!();
 
-    let error_log_path = format!("{}.sanitization_errors.log", output_file);
+    let error_log_path = config::error_log_path(output_file, ".sanitization_errors.log");
     let mut log = File::create(&error_log_path)?;
 
     writeln!(log, "# DynamoDB Auto-Sanitization Error Log")?;
@@ -277,11 +267,13 @@ pub fn sanitize_for_dynamodb_auto(args: &[String]) -> Result<(), Box<dyn Error>>
     writeln!(log, "# -------------------------------------------------------")?;
 
     let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .flexible(true)
         .trim(csv::Trim::All)
-        .from_path(input_file)?;
+        .from_reader(open_input(input_file)?);
 
     let mut wtr = WriterBuilder::new()
+        .delimiter(config::delimiter())
         .quote_style(csv::QuoteStyle::NonNumeric)
         .from_path(output_file)?;
 
@@ -291,6 +283,11 @@ pub fn sanitize_for_dynamodb_auto(args: &[String]) -> Result<(), Box<dyn Error>>
     let mut line_num = 1usize;
 
     let headers = rdr.headers()?.clone();
+    let mut report = report_html.map(|_| {
+        let mut report = ValidationReport::new("DynamoDB Auto-Sanitization", input_file, model_type);
+        report.set_headers(&headers);
+        report
+    });
     
     if headers.len() != expected_cols {
         writeln!(
@@ -300,7 +297,14 @@ pub fn sanitize_for_dynamodb_auto(args: &[String]) -> Result<(), Box<dyn Error>>
         )?;
     }
     
-    wtr.write_record(&headers)?;
+    // Columna con la línea de origen (misma numeración que el log de errores)
+    match row_number {
+        Some(name) if headers.iter().any(|h| h == name) => {
+            return Err(exit_code::usage_error(format!("Column '{}' already exists in the header", name)));
+        }
+        Some(name) => wtr.write_record(headers.iter().chain(std::iter::once(name)))?,
+        None => wtr.write_record(&headers)?,
+    }
     
     let numeric_indices: Vec<(usize, String)> = numeric_fields
         .iter()
@@ -311,55 +315,52 @@ pub fn sanitize_for_dynamodb_auto(args: &[String]) -> Result<(), Box<dyn Error>>
         })
         .collect();
 
-    println!("📊 Numeric field positions:");
+    info!("📊 Numeric field positions:");
     for (idx, field) in &numeric_indices {
-        println!("   {} at index {}", field, idx);
+        info!("   {} at index {}", field, idx);
     }
-    println!();
+    info!();
     
     line_num += 1;
 
-    println!("🔍 Processing records...");
-    println!();
+    info!("🔍 Processing records...");
+    info!();
 
+    let mut progress = Progress::rows("Processed");
     for result in rdr.records() {
         total_processed += 1;
+        progress.update(total_processed as u64, || format!("Invalid Numeric: {} | Irreparable: {}", invalid_numeric_count, irreparable_count));
         
-        let record = match result {
+        let mut record = match result {
             Ok(r) => r,
             Err(e) => {
                 irreparable_count += 1;
-                writeln!(
-                    log,
-                    "[LINE {}] ❌ PARSE_ERROR | CSV parsing failed: {}",
-                    line_num, e
-                )?;
+                error_report::write(&mut log, &ErrorRecord::parse_error(line_num, &e, format!("CSV parsing failed: {}", e)))?;
+                if let Some(report) = report.as_mut() {
+                    report.error("CSV_PARSE", None, "");
+                }
                 line_num += 1;
                 continue;
             }
         };
+        // Un campo multilínea ocupa varias líneas: la del registro es la de su inicio
+        if let Some(position) = record.position() {
+            line_num = position.line() as usize;
+        }
 
         if record.len() != expected_cols {
             irreparable_count += 1;
             
-            writeln!(
-                log,
-                "[LINE {}] ❌ IRREPARABLE_STRUCTURE | Columns: {} (expected {})",
-                line_num,
-                record.len(),
-                expected_cols
-            )?;
+            error_report::write(&mut log, &ErrorRecord::new(line_num, "IRREPARABLE_STRUCTURE",
+                format!("Columns: {} (expected {})", record.len(), expected_cols)).at(record.position()))?;
             writeln!(log, "  CSV: {}", serialize_record_for_log(&record))?;
             writeln!(log, "")?;
-            
-            line_num += 1;
-            
-            if total_processed % 10_000 == 0 {
-                print!("\r📊 Processed: {} | Invalid Numeric: {} | Irreparable: {}", 
-                    total_processed, invalid_numeric_count, irreparable_count);
-                std::io::stdout().flush().ok();
+            if let Some(report) = report.as_mut() {
+                report.error("IRREPARABLE_STRUCTURE", None, "");
+                report.sample(line_num, &format!("Columns: {} (expected {})", record.len(), expected_cols), &record);
             }
             
+            line_num += 1;
             continue;
         }
 
@@ -371,13 +372,11 @@ pub fn sanitize_for_dynamodb_auto(args: &[String]) -> Result<(), Box<dyn Error>>
             if !is_valid_dynamodb_number_local(value) {
                 has_invalid_numeric = true;
                 
-                writeln!(
-                    log,
-                    "[LINE {}] ❌ INVALID_NUMERIC | Field='{}' | Value='{}' | Expected: Type N (numeric)",
-                    line_num,
-                    field_name,
-                    value
-                )?;
+                error_report::write(&mut log, &ErrorRecord::new(line_num, "INVALID_NUMERIC", "Expected: Type N (numeric)")
+                    .at(record.position()).column(field_name).value(value))?;
+                if let Some(report) = report.as_mut() {
+                    report.error("INVALID_NUMERIC", Some(field_name), value);
+                }
             }
         }
 
@@ -391,93 +390,104 @@ pub fn sanitize_for_dynamodb_auto(args: &[String]) -> Result<(), Box<dyn Error>>
             )?;
             writeln!(log, "  CSV: {}", serialize_record_for_log(&record))?;
             writeln!(log, "")?;
-            
-            line_num += 1;
-            
-            if total_processed % 10_000 == 0 {
-                print!("\r📊 Processed: {} | Invalid Numeric: {} | Irreparable: {}", 
-                    total_processed, invalid_numeric_count, irreparable_count);
-                std::io::stdout().flush().ok();
+            if let Some(report) = report.as_mut() {
+                report.sample(line_num, "Non-numeric values in Type N fields", &record);
             }
             
+            line_num += 1;
             continue;
         }
 
-        wtr.write_record(&record)?;
-
-        if total_processed % 10_000 == 0 {
-            print!("\r📊 Processed: {} | Invalid Numeric: {} | Irreparable: {}", 
-                total_processed, invalid_numeric_count, irreparable_count);
-            std::io::stdout().flush().ok();
+        if row_number.is_some() {
+            record.push_field(&line_num.to_string());
         }
+        wtr.write_record(&record)?;
 
         line_num += 1;
     }
 
     wtr.flush()?;
     log.flush()?;
+    progress.finish();
 
-    println!("\r📊 Processed: {} | Invalid Numeric: {} | Irreparable: {}", 
+    info!("📊 Processed: {} | Invalid Numeric: {} | Irreparable: {}", 
         total_processed, invalid_numeric_count, irreparable_count);
-    println!();
+    info!();
 
     let total_written = total_processed - invalid_numeric_count - irreparable_count;
     let total_removed = invalid_numeric_count + irreparable_count;
 
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Auto-Sanitization Summary                                   ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Input CSV:");
-    println!("   Total records processed: {}", total_processed);
-    println!();
-    println!("📊 Output CSV:");
-    println!("   Records written: {} ✅", total_written);
-    println!("   Header preserved: ✅ (no modifications)");
+    logging::banner("Auto-Sanitization Summary");
+    info!("📊 Input CSV:");
+    info!("   Total records processed: {}", total_processed);
+    info!();
+    info!("📊 Output CSV:");
+    info!("   Records written: {} ✅", total_written);
+    info!("   Header preserved: ✅ (no modifications)");
     
-    println!();
-    println!("📊 Rejected Records:");
+    info!();
+    info!("📊 Rejected Records:");
     
     if invalid_numeric_count > 0 {
-        println!("   ❌ Invalid numeric fields: {} ({:.2}%)", 
+        info!("   ❌ Invalid numeric fields: {} ({:.2}%)", 
             invalid_numeric_count, 
             (invalid_numeric_count as f64 / total_processed as f64) * 100.0);
     }
     
     if irreparable_count > 0 {
-        println!("   ❌ Irreparable structure: {} ({:.2}%)", 
+        info!("   ❌ Irreparable structure: {} ({:.2}%)", 
             irreparable_count, 
             (irreparable_count as f64 / total_processed as f64) * 100.0);
     }
     
-    println!("   Total removed: {} ({:.2}%)", 
+    info!("   Total removed: {} ({:.2}%)", 
         total_removed, 
         (total_removed as f64 / total_processed as f64) * 100.0);
     
-    println!();
-    println!("📝 Files created:");
-    println!("   Clean CSV: {}", output_file);
-    println!("   Error log: {}", error_log_path);
-    
-    println!();
-    println!("🎯 DynamoDB Import Ready:");
-    println!("   Expected records in DynamoDB: {}", total_written);
-    println!("   Expected columns per record: {}", expected_cols);
-    println!("   All numeric fields validated ✅");
+    info!();
+    info!("📝 Files created:");
+    info!("   Clean CSV: {}", output_file);
+    info!("   Error log: {}", error_log_path);
+    
+    info!();
+    info!("🎯 DynamoDB Import Ready:");
+    info!("   Expected records in DynamoDB: {}", total_written);
+    info!("   Expected columns per record: {}", expected_cols);
+    info!("   All numeric fields validated ✅");
+
+    logging::summary(&serde_json::json!({
+        "command": "sanitize_dynamodb_auto",
+        "input": input_file,
+        "output": output_file,
+        "error_log": error_log_path,
+        "model": model_type,
+        "records": total_processed,
+        "records_written": total_written,
+        "invalid_numeric": invalid_numeric_count,
+        "irreparable": irreparable_count,
+        "error_rate": logging::rate(total_removed, total_processed),
+    }))?;
+
+    if let (Some(mut report), Some(path)) = (report, report_html) {
+        report.set_totals(total_processed, total_removed);
+        report.write(path)?;
+        info!("📄 HTML report: {}", path);
+    }
     
     if total_removed > 0 {
-        println!();
-        println!("⚠️  WARNING: {} rows were removed:", total_removed);
+        info!();
+        diagnostics::warn(format!("WARNING: {} rows were removed:", total_removed));
         
         if invalid_numeric_count > 0 {
-            println!("   - {} records with non-numeric values in Type N fields", invalid_numeric_count);
+            info!("   - {} records with non-numeric values in Type N fields", invalid_numeric_count);
         }
         
         if irreparable_count > 0 {
-            println!("   - {} records with irreparable structure or parsing errors", irreparable_count);
+            info!("   - {} records with irreparable structure or parsing errors", irreparable_count);
         }
         
-        println!("   Review error log: {}", error_log_path);
-        println!("   These records will NOT be imported to DynamoDB");
+        info!("   Review error log: {}", error_log_path);
+        info!("   These records will NOT be imported to DynamoDB");
     }
 
     Ok(())
@@ -488,6 +498,7 @@ fn serialize_record_for_log(record: &csv::StringRecord) -> String {
     use std::io::Cursor;
     
     let mut wtr = WriterBuilder::new()
+        .delimiter(config::delimiter())
         .quote_style(csv::QuoteStyle::Necessary)
         .from_writer(Cursor::new(Vec::new()));
     
@@ -508,7 +519,7 @@ fn serialize_record_for_log(record: &csv::StringRecord) -> String {
 }
 
 /// Retorna lista de campos numéricos según modelo DynamoDB (LOCAL)
-fn get_numeric_fields_local(model_type: &str) -> Result<Vec<String>, Box<dyn Error>> {
+fn get_numeric_fields_local(model_type: &str) -> Result<Vec<String>, CsvToolsError> {
     match model_type {
         "siisa_morosos" => Ok(vec![
             "Cuil".to_string(),
@@ -550,7 +561,7 @@ fn is_valid_dynamodb_number_local(value: &str) -> bool {
 /*
 /// ❌ DUPLICADO - Comentado para evitar error E0428
 #[allow(dead_code)]
-pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), CsvToolsError> {
     // ...código legacy...
 }
 */
@@ -558,7 +569,7 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
 // ❌ FUNCIONES LEGACY (suprimir warnings hasta implementación futura)
 
 #[allow(dead_code)]
-pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), CsvToolsError> {
     let input_file = &args[2];
     let output_file = &args[3];
     let model_type = args.get(4).map(String::as_str).unwrap_or("siisa_morosos");
@@ -566,32 +577,30 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
     
     let error_log = format!("{}_errors.log", output_file.trim_end_matches(".csv"));
 
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  DynamoDB Key Deduplication (SiisaRestApi Compatible)       ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📋 DynamoDB Model: {}", model_type);
-    println!("📄 Input CSV: {}", input_file);
-    println!("📝 Output CSV: {}", output_file);
-    println!("📝 Duplicates log: {}", duplicates_log);
-    println!("📝 Errors log: {}", error_log);
-    println!("🔄 Strategy: Keep LAST occurrence (PutItem behavior)");
-    println!();
+    logging::banner("DynamoDB Key Deduplication (SiisaRestApi Compatible)");
+    info!("📋 DynamoDB Model: {}", model_type);
+    info!("📄 Input CSV: {}", input_file);
+    info!("📝 Output CSV: {}", output_file);
+    info!("📝 Duplicates log: {}", duplicates_log);
+    info!("📝 Errors log: {}", error_log);
+    info!("🔄 Strategy: Keep LAST occurrence (PutItem behavior)");
+    info!();
 
     // Obtener columnas de clave DynamoDB según modelo
     let (pk_name, sk_name_opt) = get_dynamodb_key_columns(model_type)?;
     
-    println!("🔑 DynamoDB Primary Key Schema:");
-    println!("   PartitionKey: {} (Type: N)", pk_name);
+    info!("🔑 DynamoDB Primary Key Schema:");
+    info!("   PartitionKey: {} (Type: N)", pk_name);
     match &sk_name_opt {
-        Some(sk) => println!("   SortKey: {} (Type: N)", sk),
-        None => println!("   SortKey: (none)")
+        Some(sk) => info!("   SortKey: {} (Type: N)", sk),
+        None => info!("   SortKey: (none)")
     }
-    println!();
+    info!();
 
     // Paso 1: Validar schema
-    println!("🔍 Step 1/3: Validating CSV schema...");
+    info!("🔍 Step 1/3: Validating CSV schema...");
     
-    let mut rdr = Reader::from_path(input_file)?;
+    let mut rdr = ReaderBuilder::new().delimiter(config::delimiter()).from_reader(open_input(input_file)?);
     let headers = rdr.headers()?.clone();
     
     let pk_idx = headers.iter()
@@ -605,18 +614,18 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
         None => None
     };
 
-    println!("✅ Schema matches {}DynamoDbModel", model_type);
-    println!();
-    println!("📊 Column positions:");
-    println!("   {} at index {}", pk_name, pk_idx);
+    info!("✅ Schema matches {}DynamoDbModel", model_type);
+    info!();
+    info!("📊 Column positions:");
+    info!("   {} at index {}", pk_name, pk_idx);
     match (sk_name_opt.as_ref(), sk_idx) {
-        (Some(sk_name), Some(idx)) => println!("   {} at index {}", sk_name, idx),
-        _ => println!("   (no sort key)")
+        (Some(sk_name), Some(idx)) => info!("   {} at index {}", sk_name, idx),
+        _ => info!("   (no sort key)")
     }
-    println!();
+    info!();
 
     // Paso 2: Cargar en memoria con error recovery
-    println!("🔍 Step 2/3: Loading records into memory (required for deduplication)...");
+    info!("🔍 Step 2/3: Loading records into memory (required for deduplication)...");
 
     let mut records_map: HashMap<String, StringRecord> = HashMap::new();
     let mut total_processed = 0;
@@ -636,11 +645,14 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
     writeln!(error_writer, "# -------------------------------------------------------")?;
 
     // Reset reader para leer datos
-    let mut rdr = Reader::from_path(input_file)?;
+    let mut rdr = ReaderBuilder::new().delimiter(config::delimiter()).from_reader(open_input(input_file)?);
     let expected_len = rdr.headers()?.len();
 
+    let mut progress = Progress::rows("Processed");
     for result in rdr.records() {
         total_processed += 1;
+        progress.update(total_processed as u64, || format!("Errors: {} | Unique: {} | Duplicates: {}",
+            total_errors, records_map.len(), duplicate_count));
 
         // ✅ CRITICAL: Error recovery pattern (SiisaRestApi convention)
         let record = match result {
@@ -656,13 +668,11 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
                     
                     writeln!(error_writer, "  Raw data: {:?}", rec.as_slice())?;
                     writeln!(error_writer, "")?;
+                    // El log cuenta registros; el reporte usa la línea (header = 1)
+                    error_report::emit(&ErrorRecord::new(total_processed + 1, "COLUMN_COUNT",
+                        format!("Expected {} columns, found {}", expected_len, rec.len())).at(rec.position()))?;
                     
                     // ⚠️ SKIP este registro y continuar (graceful degradation)
-                    if total_processed % 10_000 == 0 {
-                        print!("\r📊 Processed: {} | Errors: {} | Unique: {} | Duplicates: {}", 
-                            total_processed, total_errors, records_map.len(), duplicate_count);
-                        std::io::stdout().flush().ok();
-                    }
                     continue;
                 }
                 rec
@@ -675,13 +685,9 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
                     "[LINE {}] CSV_PARSE_ERROR | {}",
                     total_processed, e)?;
                 writeln!(error_writer, "")?;
+                error_report::emit(&ErrorRecord::parse_error(total_processed + 1, &e, e.to_string()))?;
                 
                 // ⚠️ SKIP este registro y continuar
-                if total_processed % 10_000 == 0 {
-                    print!("\r📊 Processed: {} | Errors: {} | Unique: {} | Duplicates: {}", 
-                        total_processed, total_errors, records_map.len(), duplicate_count);
-                    std::io::stdout().flush().ok();
-                }
                 continue;
             }
         };
@@ -699,6 +705,8 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
                 total_processed, pk_value, sk_value.unwrap_or("(none)"))?;
             writeln!(error_writer, "  Raw data: {:?}", record.as_slice())?;
             writeln!(error_writer, "")?;
+            error_report::emit(&ErrorRecord::new(total_processed + 1, "EMPTY_KEY",
+                format!("PartitionKey='{}', SortKey='{}'", pk_value, sk_value.unwrap_or("(none)"))).at(record.position()))?;
             
             continue;
         }
@@ -716,26 +724,20 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
         }
         
         records_map.insert(composite_key, record);
-
-        // Mostrar progreso cada 10,000 registros (siguiendo convención SiisaRestApi)
-        if total_processed % 10_000 == 0 {
-            print!("\r📊 Processed: {} | Errors: {} | Unique: {} | Duplicates: {}", 
-                total_processed, total_errors, records_map.len(), duplicate_count);
-            std::io::stdout().flush().ok();
-        }
     }
 
     error_writer.flush()?;
+    progress.finish();
 
-    println!("\r📊 Processed: {} | Errors: {} | Unique: {} | Duplicates: {}", 
+    info!("📊 Processed: {} | Errors: {} | Unique: {} | Duplicates: {}", 
         total_processed, total_errors, records_map.len(), duplicate_count);
-    println!();
-    println!("✅ Complete: {} records in memory", records_map.len());
-    println!();
+    info!();
+    info!("✅ Complete: {} records in memory", records_map.len());
+    info!();
 
     // Paso 3: Escribir registros únicos
-    println!("🔍 Step 3/3: Writing deduplicated records...");
-    println!();
+    info!("🔍 Step 3/3: Writing deduplicated records...");
+    info!();
     
     // Log de duplicados removidos (structured logging pattern)
     let dup_file = File::create(duplicates_log)?;
@@ -754,308 +756,357 @@ pub fn deduplicate_by_dynamodb_keys(args: &[String]) -> Result<(), Box<dyn Error
     writeln!(dup_writer, "# -------------------------------------------------------")?;
     dup_writer.flush()?;
 
-    println!("💾 Writing deduplicated records to: {}", output_file);
-    println!();
+    info!("💾 Writing deduplicated records to: {}", output_file);
+    info!();
 
-    let mut wtr = Writer::from_path(output_file)?;
+    let mut wtr = WriterBuilder::new().delimiter(config::delimiter()).from_path(output_file)?;
     wtr.write_record(&headers)?;
 
+    let mut progress = Progress::rows_of("Written", records_map.len() as u64);
     let mut written = 0;
     for record in records_map.values() {
         wtr.write_record(record)?;
         written += 1;
-
-        if written % 10_000 == 0 {
-            print!("\r📊 Written: {} records", written);
-            std::io::stdout().flush().ok();
-        }
+        progress.update(written, String::new);
     }
 
     wtr.flush()?;
+    progress.finish();
 
-    println!("\r📊 Written: {} records", written);
-    println!();
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Deduplication Summary                                       ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Input CSV:");
-    println!("   Total records processed: {}", total_processed);
+    info!("📊 Written: {} records", written);
+    info!();
+    logging::banner("Deduplication Summary");
+    info!("📊 Input CSV:");
+    info!("   Total records processed: {}", total_processed);
     if total_errors > 0 {
-        println!("   ⚠️  Malformed records (skipped): {} ({:.2}%)", 
+        info!("   ⚠️  Malformed records (skipped): {} ({:.2}%)", 
             total_errors, (total_errors as f64 / total_processed as f64) * 100.0);
     }
-    println!();
-    println!("📊 Output CSV:");
-    println!("   Unique records: {} ✅", records_map.len());
-    println!("   Duplicates removed: {} ({:.2}%)", 
+    info!();
+    info!("📊 Output CSV:");
+    info!("   Unique records: {} ✅", records_map.len());
+    info!("   Duplicates removed: {} ({:.2}%)", 
         duplicate_count, (duplicate_count as f64 / total_processed as f64) * 100.0);
     if total_errors > 0 {
-        println!("   Errors skipped: {} ({:.2}%)", 
+        info!("   Errors skipped: {} ({:.2}%)", 
             total_errors, (total_errors as f64 / total_processed as f64) * 100.0);
     }
     
     let total_removed = duplicate_count + total_errors;
-    println!("   Total removed: {} ({:.2}%)", 
+    info!("   Total removed: {} ({:.2}%)", 
         total_removed, (total_removed as f64 / total_processed as f64) * 100.0);
-    println!();
-    println!("📝 Files created:");
-    println!("   Clean CSV: {}", output_file);
-    println!("   Duplicates log: {}", duplicates_log);
+    info!();
+    info!("📝 Files created:");
+    info!("   Clean CSV: {}", output_file);
+    info!("   Duplicates log: {}", duplicates_log);
     if total_errors > 0 {
-        println!("   ⚠️  Errors log: {} ({} malformed records)", error_log, total_errors);
+        info!("   ⚠️  Errors log: {} ({} malformed records)", error_log, total_errors);
     }
-    println!();
-    println!("🎯 DynamoDB Import Ready:");
-    println!("   Expected records in DynamoDB: {}", records_map.len());
-    println!("   No overwrites will occur (all keys unique)");
+    info!();
+    info!("🎯 DynamoDB Import Ready:");
+    info!("   Expected records in DynamoDB: {}", records_map.len());
+    info!("   No overwrites will occur (all keys unique)");
     
     if total_errors > 0 {
-        println!();
-        println!("⚠️  WARNING: {} malformed records were skipped", total_errors);
-        println!("   Review error log for details: {}", error_log);
-        println!("   These records will NOT be imported to DynamoDB");
+        info!();
+        diagnostics::warn(format!("WARNING: {} malformed records were skipped", total_errors));
+        info!("   Review error log for details: {}", error_log);
+        info!("   These records will NOT be imported to DynamoDB");
     }
 
     Ok(())
 }
 
-/// Clean duplicate headers from CSV file
-pub fn clean_headers(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let input_file = &args[2];
-    let output_file = &args[3];
-    
-    let input = File::open(input_file)?;
-    let reader = BufReader::new(input);
-    let output = File::create(output_file)?;
-    let mut writer = BufWriter::new(output);
+/// Quita las repeticiones del header; con `row_number` agrega al final de cada registro
+/// el número de la línea de la entrada donde empieza (el header es la 1)
+pub fn clean_headers(input_file: &str, output_file: &str, row_number: Option<&str>) -> Result<(), CsvToolsError> {
+    let reader = file_utils::open_input(input_file)?;
+    let mut writer = compression::create_output(output_file, 1)?;
+    let numbering = SourceColumns { file: None, line: row_number.map(str::to_string) };
 
     let mut first_line = String::new();
-    let mut lines = reader.lines();
+    let mut lines = file_utils::record_lines(reader);
 
-    if let Some(Ok(header)) = lines.next() {
+    if let Some((_, Ok(header))) = lines.next() {
         first_line = header;
+        if let Some(name) = row_number.filter(|name| first_line.split(config::delimiter() as char).any(|h| h.trim().trim_matches('"') == *name)) {
+            return Err(exit_code::usage_error(format!("Column '{}' already exists in the header", name)));
+        }
         writer.write_all(first_line.as_bytes())?;
+        writer.write_all(numbering.header_suffix().as_bytes())?;
         writer.write_all(b"\n")?;
     }
 
-    for line in lines {
+    for (i, line) in lines {
         let line = line?;
         if line != first_line {
             writer.write_all(line.as_bytes())?;
+            if !numbering.is_empty() {
+                writer.write_all(numbering.row_suffix("", i + 1).as_bytes())?;
+            }
             writer.write_all(b"\n")?;
         }
     }
 
     writer.flush()?;
-    println!("✅ Header cleanup complete: {}", output_file);
+    writeln!(file_utils::status_output(output_file), "Header cleanup complete.")?;
     Ok(())
 }
 
-/// Filter CSV rows by column value
-pub fn filter_rows(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let input_file = &args[2];
-    let output_file = &args[3];
-    let column_name = &args[4];
-    let value = &args[5];
-    
-    let input = File::open(input_file)?;
-    let mut rdr = Reader::from_reader(input);
-    let headers = rdr.headers()?.clone();
-    
-    let column_index = headers.iter()
-        .position(|h| h == column_name)
-        .ok_or_else(|| format!("Column '{}' not found", column_name))?;
+pub fn count_lines(input_file: &str) -> Result<usize, CsvToolsError> {
 
-    let mut wtr = Writer::from_path(output_file)?;
-    wtr.write_record(&headers)?;
+    info!("Counting lines in file: {}...", input_file);
+    let start = Instant::now();
+    let reader = file_utils::open_input(input_file)?;
 
-    for result in rdr.records() {
-        let record = result?;
-        if record.get(column_index).unwrap_or("") == value {
-            wtr.write_record(&record)?;
+    let line_count = reader.lines().count();
+
+    info!("Time taken to count {} lines: {:.2} seconds",line_count, start.elapsed().as_secs_f64());
+
+    Ok(line_count)
+}
+
+pub fn has_duplicate_header(file_path: &str) -> Result<bool, CsvToolsError> {
+    let mut reader = file_utils::open_input(file_path)?;
+    let mut result = false;
+
+    let mut first_line = String::new();
+    if reader.read_line(&mut first_line)? == 0 {
+        result = false; // Empty file, no duplicates
+    }
+
+    let header = first_line.trim_end().to_string();
+    let mut line_number = 1;
+
+    for line in reader.lines() {
+        line_number += 1;
+        let line = line?;
+        if line.trim_end() == header {
+            info!("Duplicate header found on line {}", line_number);
+            result = true;
         }
     }
 
-    wtr.flush()?;
-    println!("✅ Filtering complete: {}", output_file);
-    Ok(())
+    Ok(result)
 }
 
-/// Count lines in a single CSV file
-pub fn count_lines(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let input_file = &args[2];
-    
-    let file = File::open(input_file)?;
-    let reader = BufReader::new(file);
-    let line_count = reader.lines().count();
-    
-    println!("📊 Total lines in {}: {}", input_file, line_count);
-    Ok(())
+/// Columnas de procedencia que merge y merge_dedup agregan al final de cada registro
+/// (--add-source-column / --add-line-column) para rastrear un registro hasta el chunk
+/// que lo generó. Los registros salen de `file_utils::record_lines`: uno con un campo
+/// multilínea recibe las columnas una sola vez, con la línea donde empieza
+#[derive(Debug, Clone, Default)]
+pub struct SourceColumns {
+    /// Nombre de la columna con el archivo de origen
+    pub file: Option<String>,
+    /// Nombre de la columna con la línea donde empieza el registro en ese archivo (el header es la 1)
+    pub line: Option<String>,
 }
 
-/// Count lines across multiple CSV files
-pub fn count_all_files(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let file_list = &args[2];
-    
-    let file = File::open(file_list)?;
-    let reader = BufReader::new(file);
-    let mut total = 0;
+impl SourceColumns {
+    pub fn is_empty(&self) -> bool {
+        self.file.is_none() && self.line.is_none()
+    }
 
-    for line in reader.lines() {
-        let filename = line?;
-        let f = File::open(&filename)?;
-        let r = BufReader::new(f);
-        let count = r.lines().count();
-        println!("{}: {} lines", filename, count);
-        total += count;
+    /// Lo que se agrega al header (vacío si no se pidió ninguna columna)
+    pub fn header_suffix(&self) -> String {
+        let delimiter = config::delimiter();
+        [&self.file, &self.line].into_iter().flatten()
+            .map(|name| format!("{}{}", delimiter as char, csv_field(name, delimiter)))
+            .collect()
     }
 
-    println!("\n📊 Total lines across all files: {}", total);
-    Ok(())
+    /// Valor de la columna de archivo ya escapado (se calcula una vez por archivo)
+    pub fn file_value(&self, file_name: &str) -> String {
+        csv_field(file_name, config::delimiter())
+    }
+
+    /// Lo que se agrega a la fila `line` del archivo cuyo valor es `file_value`
+    pub fn row_suffix(&self, file_value: &str, line: usize) -> String {
+        let delimiter = config::delimiter() as char;
+        let mut suffix = String::new();
+        if self.file.is_some() {
+            suffix.push(delimiter);
+            suffix.push_str(file_value);
+        }
+        if self.line.is_some() {
+            suffix.push(delimiter);
+            suffix.push_str(&line.to_string());
+        }
+        suffix
+    }
 }
 
-/// Count unique records across multiple files (in-memory)
-pub fn count_unique_records(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let file_list = &args[2];
-    
-    let file = File::open(file_list)?;
-    let reader = BufReader::new(file);
-    let mut seen_lines = HashSet::new();
+/// Valor entre comillas (con las comillas internas duplicadas) si lo necesita
+fn csv_field(value: &str, delimiter: u8) -> String {
+    if value.bytes().any(|b| b == delimiter || b == b'"' || b == b'\n' || b == b'\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
 
-    for line in reader.lines() {
-        let filename = line?;
-        let f = File::open(&filename)?;
-        let r = BufReader::new(f);
-        
-        for (i, file_line) in r.lines().enumerate() {
-            if i == 0 { continue; } // Skip header
-            seen_lines.insert(file_line?);
-        }
+/// Qué hace merge con un archivo cuyo header no coincide con el esperado
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderMismatch {
+    /// No escribe nada y lista todos los archivos que no coinciden (default)
+    Abort,
+    /// Omite esos archivos (informados uno por uno) y une el resto
+    Skip,
+    /// Los une igual, solo advierte (comportamiento anterior)
+    Allow,
+}
+
+/// Diferencia del header de `file` con el esperado; None si coincide exactamente
+fn header_mismatch(file: &str, expected: &[String]) -> Result<Option<String>, CsvToolsError> {
+    let headers: Vec<String> = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(file)?)
+        .headers()?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    if headers == expected {
+        return Ok(None);
     }
 
-    println!("📊 Unique records: {}", seen_lines.len());
-    Ok(())
+    let missing: Vec<&str> = expected.iter().filter(|c| !headers.contains(c)).map(String::as_str).collect();
+    let extra: Vec<&str> = headers.iter().filter(|h| !expected.contains(h)).map(String::as_str).collect();
+    let mut detail = vec![format!("{} columns (expected {})", headers.len(), expected.len())];
+    if !missing.is_empty() {
+        detail.push(format!("missing: {}", missing.join(", ")));
+    }
+    if !extra.is_empty() {
+        detail.push(format!("extra: {}", extra.join(", ")));
+    }
+    if missing.is_empty() && extra.is_empty() {
+        detail.push("same columns in a different order".to_string());
+    }
+    Ok(Some(detail.join("; ")))
 }
 
 /// Merge multiple CSV files without deduplication
-pub fn merge_files(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let file_list = &args[2];
-    let output_file = &args[3];
-    
-    let file = File::open(file_list)?;
-    let reader = BufReader::new(file);
-    let mut writer = BufWriter::new(File::create(output_file)?);
-    let mut header_written = false;
-
-    for line in reader.lines() {
-        let filename = line?;
-        let input = File::open(&filename)?;
-        let file_reader = BufReader::new(input);
+/// `inputs`: archivo de lista, directorio o glob (ver `file_utils::resolve_inputs`);
+/// se conserva el header del primer archivo y se omite el de los demás. Antes de
+/// escribir se compara el header de cada archivo con el del primero (o el del schema /
+/// modelo `schema`) y los que no coinciden se tratan según `on_mismatch`. `report`
+/// registra las filas y el resultado del header de cada archivo (--report)
+pub fn merge_files(
+    inputs: &str,
+    output_file: &str,
+    source: &SourceColumns,
+    schema: Option<&str>,
+    on_mismatch: HeaderMismatch,
+    mut report: MergeReport,
+) -> Result<(), CsvToolsError> {
+    let mut file_names = file_utils::resolve_inputs(inputs)?;
+
+    let expected: Vec<String> = match schema {
+        Some(spec) => crate::schema::Schema::load(spec)?.columns.iter().map(|c| c.name.clone()).collect(),
+        None => ReaderBuilder::new()
+            .delimiter(config::delimiter())
+            .has_headers(true)
+            .flexible(true)
+            .from_reader(open_input(&file_names[0])?)
+            .headers()?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect(),
+    };
 
-        for (i, file_line) in file_reader.lines().enumerate() {
-            let line_content = file_line?;
-            
-            if i == 0 {
-                if !header_written {
-                    writer.write_all(line_content.as_bytes())?;
-                    writer.write_all(b"\n")?;
-                    header_written = true;
+    let mut mismatched = Vec::new();
+    for file in &file_names {
+        if let Some(detail) = header_mismatch(file, &expected)? {
+            diagnostics::warn(format!("Header mismatch in {}: {}", file, detail));
+            mismatched.push(file.clone());
+        }
+    }
+    if !mismatched.is_empty() {
+        match on_mismatch {
+            HeaderMismatch::Abort => {
+                return Err(exit_code::data_issues(format!(
+                    "{} of {} files do not match the expected header ({} columns); nothing was written. \
+                     Use --on-mismatch skip to leave them out or merge_reconcile to align them",
+                    mismatched.len(), file_names.len(), expected.len())));
+            }
+            HeaderMismatch::Skip => {
+                for file in &mismatched {
+                    report.skipped_file(file);
+                }
+                file_names.retain(|f| !mismatched.contains(f));
+                if file_names.is_empty() {
+                    return Err(exit_code::data_issues("No file matches the expected header"));
                 }
-            } else {
-                writer.write_all(line_content.as_bytes())?;
-                writer.write_all(b"\n")?;
             }
+            HeaderMismatch::Allow => {}
         }
     }
 
-    writer.flush()?;
-    println!("✅ Merge complete: {}", output_file);
-    Ok(())
-}
-
-/// Merge and deduplicate CSV files (in-memory)
-pub fn merge_and_deduplicate(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let file_list = &args[2];
-    let output_file = &args[3];
-    
-    let file = File::open(file_list)?;
-    let reader = BufReader::new(file);
-    let mut seen_lines = HashSet::new();
-    let mut writer = BufWriter::new(File::create(output_file)?);
+    let mut disk_guard = DiskSpaceGuard::new(output_file, file_utils::total_file_size(&file_names)?, 1.0);
+    disk_guard.preflight()?;
+    let mut writer = compression::create_output(output_file, 1)?;
     let mut header_written = false;
+    let mut lines_written = 0;
 
-    for line in reader.lines() {
-        let filename = line?;
-        let input = File::open(&filename)?;
-        let file_reader = BufReader::new(input);
+    for (file_idx, filename) in file_names.iter().enumerate() {
+        let file_reader = open_input(filename)?;
+        let file_value = source.file_value(filename);
+        let mut lines = 0;
+        report.start_file();
 
-        for (i, file_line) in file_reader.lines().enumerate() {
+        for (i, file_line) in file_utils::record_lines(file_reader) {
             let line_content = file_line?;
+            let mut written = 0u64;
             
             if i == 0 {
                 if !header_written {
+                    let header_suffix = source.header_suffix();
                     writer.write_all(line_content.as_bytes())?;
+                    writer.write_all(header_suffix.as_bytes())?;
                     writer.write_all(b"\n")?;
                     header_written = true;
+                    written = (line_content.len() + header_suffix.len()) as u64 + 1;
                 }
             } else {
-                if seen_lines.insert(line_content.clone()) {
-                    writer.write_all(line_content.as_bytes())?;
-                    writer.write_all(b"\n")?;
-                }
+                let suffix = if source.is_empty() { String::new() } else { source.row_suffix(&file_value, i + 1) };
+                writer.write_all(line_content.as_bytes())?;
+                writer.write_all(suffix.as_bytes())?;
+                writer.write_all(b"\n")?;
+                written = (line_content.len() + suffix.len()) as u64 + 1;
+                lines += 1;
+            }
+
+            if let Some(reason) = disk_guard.record(line_content.len() as u64 + 1, written)? {
+                writer.flush()?;
+                return Err(disk_guard.abort(Checkpoint {
+                    command: "merge".to_string(),
+                    files_completed: file_names[..file_idx].to_vec(),
+                    current_file: filename.clone(),
+                    current_line: i + 1,
+                    lines_processed: i + 1,
+                    ..Default::default()
+                }, reason));
             }
         }
+        let header = if mismatched.contains(filename) { HeaderCheck::Allowed } else { HeaderCheck::Match };
+        report.file(filename, header, lines, Some(0));
+        lines_written += lines;
     }
 
     writer.flush()?;
-    println!("✅ Merge + dedup complete: {} unique records", seen_lines.len());
-    Ok(())
-}
-
-/// External merge sort for large CSV files
-pub fn external_merge_dedup(_args: &[String]) -> Result<(), Box<dyn Error>> {
-    println!("⚠️  External merge/dedup not yet implemented");
-    println!("   Use merge_dedup for files that fit in RAM");
-    Ok(())
-}
-
-/// Estimate memory required for in-memory deduplication
-pub fn estimate_memory_usage(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let file_list = &args[2];
-    
-    let file = File::open(file_list)?;
-    let reader = BufReader::new(file);
-    let mut total_size = 0u64;
-
-    for line in reader.lines() {
-        let filename = line?;
-        let metadata = std::fs::metadata(&filename)?;
-        total_size += metadata.len();
-    }
-
-    let estimated_ram = (total_size as f64 * 1.5) / (1024.0 * 1024.0 * 1024.0);
-    
-    println!("📊 Total CSV size: {:.2} GB", total_size as f64 / (1024.0 * 1024.0 * 1024.0));
-    println!("📊 Estimated RAM needed: {:.2} GB", estimated_ram);
-    
-    if estimated_ram > 16.0 {
-        println!("⚠️  WARNING: May require external sort");
+    drop(writer);
+    let mut status = file_utils::status_output(output_file);
+    writeln!(status, "✅ Merge complete: {} files -> {}", file_names.len(), compression::output_path(output_file))?;
+    if on_mismatch == HeaderMismatch::Skip && !mismatched.is_empty() {
+        writeln!(status, "⏭️  Skipped {} files with a mismatched header", mismatched.len())?;
     }
-    
-    Ok(())
+    report.finish(lines_written, 0)
 }
 
-
-/// Compare first N rows of two CSV files
-pub fn compare_first_n(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let file1 = &args[2];
-    let file2 = &args[3];
-    let num_rows: usize = args[4].parse()?;
-    
-    let f1 = File::open(file1)?;
-    let f2 = File::open(file2)?;
-    let reader1 = BufReader::new(f1);
-    let reader2 = BufReader::new(f2);
+pub fn compare_first_n(file1: &str, file2: &str, num_rows: usize) -> Result<(), CsvToolsError> {
+    let reader1 = file_utils::open_input(file1)?;
+    let reader2 = file_utils::open_input(file2)?;
 
     let mut lines1 = reader1.lines();
     let mut lines2 = reader2.lines();
@@ -1064,38 +1115,56 @@ pub fn compare_first_n(args: &[String]) -> Result<(), Box<dyn Error>> {
     let header2 = lines2.next().unwrap_or(Ok(String::new()))?;
 
     if header1 != header2 {
-        println!("⚠️ Header mismatch!");
+        info!("⚠️ Header mismatch!");
+        info!("File1 header: {}", header1);
+        info!("File2 header: {}", header2);
     } else {
-        println!("✅ Headers match");
+        info!("✅ Headers match.");
     }
 
+    info!("Comparing first {} data rows...", num_rows);
+
     let mut differences = 0;
+
     for i in 1..=num_rows {
         let line1 = lines1.next().unwrap_or(Ok(String::new()))?;
         let line2 = lines2.next().unwrap_or(Ok(String::new()))?;
 
         if line1 != line2 {
-            println!("❌ Difference at line {}", i + 1);
+            info!("❌ Difference at line {}:", i + 1);
+            info!("File1: {}", line1);
+            info!("File2: {}", line2);
             differences += 1;
         }
     }
 
     if differences == 0 {
-        println!("✅ No differences in first {} rows", num_rows);
+        info!("🎉 No differences found in the first {} rows.", num_rows);
+    } else {
+        info!("🔍 Found {} differences in the first {} rows.", differences, num_rows);
     }
-    
+
     Ok(())
 }
 
-/// Show last N rows of CSV file
-pub fn tail_csv(args: &[String]) -> Result<(), Box<dyn Error>> {
+/// Show last N rows of CSV file (`-` lee de stdin)
+pub fn tail_csv(input_file: &str, num_rows: usize, max_width: usize) -> Result<(), CsvToolsError> {
+    let stdout = std::io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    if logging::markdown_output() {
+        let mut rows = Vec::new();
+        write_tail(open_input(input_file)?, &mut rows, num_rows)?;
+        write_markdown_table(&rows, &mut out, max_width)?;
+    } else {
+        write_tail(open_input(input_file)?, &mut out, num_rows)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Header + últimas `num_rows` líneas de `reader` (buffer circular, no carga el archivo)
+pub fn write_tail<R: BufRead, W: Write>(reader: R, writer: &mut W, num_rows: usize) -> std::io::Result<usize> {
     use std::collections::VecDeque;
-    
-    let input_file = &args[2];
-    let num_rows: usize = args[3].parse()?;
-    
-    let file = File::open(input_file)?;
-    let reader = BufReader::new(file);
 
     let mut lines = reader.lines();
     let header = lines.next().unwrap_or(Ok(String::new()))?;
@@ -1106,59 +1175,136 @@ pub fn tail_csv(args: &[String]) -> Result<(), Box<dyn Error>> {
         if buffer.len() == num_rows {
             buffer.pop_front();
         }
-        buffer.push_back(line);
+        if num_rows > 0 {
+            buffer.push_back(line);
+        }
     }
 
-    println!("{}", header);
-    for line in buffer {
-        println!("{}", line);
+    writeln!(writer, "{}", header)?;
+    for line in &buffer {
+        writeln!(writer, "{}", line)?;
     }
 
+    Ok(buffer.len())
+}
+
+/// Show first N rows of CSV file (`-` lee de stdin)
+pub fn head_csv(input_file: &str, num_rows: usize, max_width: usize) -> Result<(), CsvToolsError> {
+    let stdout = std::io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let count = if logging::markdown_output() {
+        let mut rows = Vec::new();
+        let count = write_head(open_input(input_file)?, &mut rows, num_rows)?;
+        write_markdown_table(&rows, &mut out, max_width)?;
+        count
+    } else {
+        write_head(open_input(input_file)?, &mut out, num_rows)?
+    };
+    out.flush()?;
+
+    // Resumen a stderr: stdout lleva solo el CSV
+    eprintln!("\n📊 Showing {} rows", count);
     Ok(())
 }
 
-/// Show first N rows of CSV file
-pub fn head_csv(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let input_file = &args[2];
-    let num_rows: usize = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(10);
-    
-    let file = File::open(input_file)?;
-    let reader = BufReader::new(file);
+/// Header + primeras `num_rows` líneas de `reader`; deja de leer al llegar a N
+pub fn write_head<R: BufRead, W: Write>(reader: R, writer: &mut W, num_rows: usize) -> std::io::Result<usize> {
     let mut lines = reader.lines();
-    
+
     // Show header
-    if let Some(Ok(header)) = lines.next() {
-        println!("{}", header);
+    if let Some(header) = lines.next() {
+        writeln!(writer, "{}", header?)?;
     }
-    
+
     // Show N data rows
     let mut count = 0;
     for line in lines {
         if count >= num_rows { break; }
-        println!("{}", line?);
+        writeln!(writer, "{}", line?)?;
         count += 1;
     }
-    
-    println!("\n📊 Showing {} rows", count);
+
+    Ok(count)
+}
+
+/// Tabla Markdown (GitHub) con las columnas alineadas a partir de las líneas CSV de
+/// head / tail: los `|` se escapan, los saltos de línea pasan a espacio y los campos
+/// de más de `max_width` caracteres se cortan con `…`. Columnas numéricas a la derecha
+pub fn write_markdown_table<W: Write>(csv_rows: &[u8], writer: &mut W, max_width: usize) -> Result<(), CsvToolsError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(csv_rows);
+
+    // El header no se corta: es lo que identifica cada columna
+    let cell = |value: &str, header: bool| {
+        let flat = value.replace("\r\n", " ").replace(['\n', '\r'], " ");
+        let truncated = if !header && flat.chars().count() > max_width.max(1) {
+            flat.chars().take(max_width.max(1) - 1).chain(std::iter::once('…')).collect()
+        } else {
+            flat
+        };
+        truncated.replace('|', "\\|")
+    };
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for record in reader.records() {
+        let header = rows.is_empty();
+        rows.push(record?.iter().map(|v| cell(v, header)).collect());
+    }
+    let Some(columns) = rows.iter().map(Vec::len).max() else {
+        return Ok(());
+    };
+    for row in rows.iter_mut() {
+        row.resize(columns, String::new());
+    }
+
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| rows.iter().map(|r| r[i].chars().count()).max().unwrap_or(0).max(3))
+        .collect();
+    let numeric: Vec<bool> = (0..columns)
+        .map(|i| {
+            let mut values = rows.iter().skip(1).map(|r| r[i].trim()).filter(|v| !v.is_empty()).peekable();
+            values.peek().is_some() && values.all(|v| v.parse::<f64>().is_ok())
+        })
+        .collect();
+
+    for (n, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row.iter().enumerate()
+            .map(|(i, v)| {
+                let pad = " ".repeat(widths[i] - v.chars().count());
+                if numeric[i] && n > 0 { format!("{}{}", pad, v) } else { format!("{}{}", v, pad) }
+            })
+            .collect();
+        writeln!(writer, "| {} |", cells.join(" | "))?;
+        if n == 0 {
+            let separators: Vec<String> = widths.iter().zip(&numeric)
+                .map(|(w, num)| if *num { format!("{}:", "-".repeat(w - 1)) } else { "-".repeat(*w) })
+                .collect();
+            writeln!(writer, "| {} |", separators.join(" | "))?;
+        }
+    }
     Ok(())
 }
 
 /// Validate CSV against DynamoDB schema
 /// Valida estructura y campos numéricos
-pub fn validate_dynamodb_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn validate_dynamodb_schema(args: &[String]) -> Result<(), CsvToolsError> {
     let input_file = &args[2];
     let model_type = args.get(3).map(String::as_str).unwrap_or("siisa_morosos");
 
-    println!("🔍 Validating {} against DynamoDB schema...", input_file);
+    info!("🔍 Validating {} against DynamoDB schema...", input_file);
 
     // ✅ FIX 1: Usar función local
     let numeric_fields = get_numeric_fields_local(model_type)?;
 
     // Archivo log de errores
-    let mut error_log = File::create(format!("{}.schema_errors.log", input_file))?;
+    let error_log_path = config::error_log_path(input_file, ".schema_errors.log");
+    let mut error_log = File::create(&error_log_path)?;
 
     // Reader en modo flexible para capturar errores de estructura
     let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .has_headers(true)
         .flexible(true)               // Permite detectar filas con menos/más columnas
         .from_path(input_file)?;
@@ -1178,7 +1324,7 @@ pub fn validate_dynamodb_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
         .collect();
 
     writeln!(error_log, "HEADER COUNT: {}", header_len)?;
-    println!("📌 Column count: {}", header_len);
+    info!("📌 Column count: {}", header_len);
 
     let mut line_num = 2usize; // header es línea 1
     let mut total_errors = 0;
@@ -1192,6 +1338,7 @@ pub fn validate_dynamodb_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                     "[LINE {}] **CSV PARSE ERROR**: {}",
                     line_num, e
                 )?;
+                error_report::emit(&ErrorRecord::parse_error(line_num, &e, e.to_string()))?;
                 total_errors += 1;
                 line_num += 1;
                 continue;
@@ -1208,6 +1355,8 @@ pub fn validate_dynamodb_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                 header_len,
                 record
             )?;
+            error_report::emit(&ErrorRecord::new(line_num, "COLUMN_COUNT",
+                format!("Columns: {} (expected {})", record.len(), header_len)).at(record.position()))?;
             total_errors += 1;
         }
 
@@ -1224,6 +1373,8 @@ pub fn validate_dynamodb_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                     field_name,
                     value
                 )?;
+                error_report::emit(&ErrorRecord::new(line_num, "INVALID_NUMERIC", "Expected: Type N (numeric)")
+                    .at(record.position()).column(field_name).value(value))?;
                 total_errors += 1;
             }
         }
@@ -1231,43 +1382,35 @@ pub fn validate_dynamodb_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
         line_num += 1;
     }
 
-    println!("-----------------------------------------");
-    println!("🔎 VALIDATION SUMMARY");
-    println!("-----------------------------------------");
-    println!("❌ Errors found: {}", total_errors);
-    println!("📝 Log file    : {}.schema_errors.log", input_file);
+    info!("-----------------------------------------");
+    info!("🔎 VALIDATION SUMMARY");
+    info!("-----------------------------------------");
+    info!("❌ Errors found: {}", total_errors);
+    info!("📝 Log file    : {}", error_log_path);
 
     if total_errors == 0 {
-        println!("✅ CSV is fully DynamoDB-Compatible (structure + numbers)");
+        info!("✅ CSV is fully DynamoDB-Compatible (structure + numbers)");
     } else {
-        println!("⚠ CSV has issues that WILL cause ImportTable to fail.");
+        info!("⚠ CSV has issues that WILL cause ImportTable to fail.");
     }
 
     Ok(())
 }
 
 /// Deduplicación simple por todas las columnas
-pub fn deduplicate_csv(args: &[String]) -> Result<(), Box<dyn Error>> {
-    if args.len() < 4 {
-        eprintln!("Usage: csv_tools deduplicate <input.csv> <output.csv>");
-        std::process::exit(1);
-    }
-    
-    let input_file = &args[2];
-    let output_file = &args[3];
+pub fn deduplicate_csv(input_file: &str, output_file: &str) -> Result<(), CsvToolsError> {
     
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  CSV Deduplication (All Columns)                            ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📄 Input: {}", input_file);
-    println!("📝 Output: {}", output_file);
-    println!();
+    logging::banner("CSV Deduplication (All Columns)");
+    info!("📄 Input: {}", input_file);
+    info!("📝 Output: {}", output_file);
+    info!();
     
-    let mut rdr = Reader::from_path(input_file)?;
+    let mut rdr = ReaderBuilder::new().delimiter(config::delimiter()).from_reader(open_input(input_file)?);
     let headers = rdr.headers()?.clone();
     
     let mut seen = HashSet::new();
     let mut wtr = WriterBuilder::new()
+        .delimiter(config::delimiter())
         .quote_style(csv::QuoteStyle::Necessary)
         .from_path(output_file)?;
     
@@ -1276,6 +1419,7 @@ pub fn deduplicate_csv(args: &[String]) -> Result<(), Box<dyn Error>> {
     let mut total = 0usize;
     let mut unique = 0usize;
     
+    let mut progress = Progress::rows("Processed");
     for result in rdr.records() {
         total += 1;
         let record = result?;
@@ -1287,53 +1431,52 @@ pub fn deduplicate_csv(args: &[String]) -> Result<(), Box<dyn Error>> {
             wtr.write_record(&record)?;
         }
         
-        if total % 10_000 == 0 {
-            print!("\r📊 Processed: {} | Unique: {}", total, unique);
-            std::io::stdout().flush().ok();
-        }
+        progress.update(total as u64, || format!("Unique: {}", unique));
     }
     
     wtr.flush()?;
+    progress.finish();
     
-    println!("\r📊 Processed: {} | Unique: {} | Duplicates: {}", 
+    info!("📊 Processed: {} | Unique: {} | Duplicates: {}", 
         total, unique, total - unique);
-    println!("✅ Deduplication complete");
+    info!("✅ Deduplication complete");
+
+    logging::summary(&serde_json::json!({
+        "command": "deduplicate",
+        "input": input_file,
+        "output": output_file,
+        "records": total,
+        "records_written": unique,
+        "duplicates_removed": total - unique,
+        "duplicate_rate": logging::rate(total - unique, total),
+    }))?;
     
     Ok(())
 }
 
-/// Deduplicación por claves DynamoDB compuestas
-pub fn deduplicate_dynamodb(args: &[String]) -> Result<(), Box<dyn Error>> {
-    if args.len() < 5 {
-        eprintln!("Usage: csv_tools deduplicate_dynamodb <input.csv> <output.csv> <model_type>");
-        eprintln!("Model types: siisa_morosos, personas_telefonos");
-        std::process::exit(1);
-    }
-
-    let input_file = &args[2];
-    let output_file = &args[3];
-    let model_type = &args[4];
-
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  DynamoDB Deduplication (Composite Keys)                    ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📋 Model: {}", model_type);
-    println!("📄 Input: {}", input_file);
-    println!("📝 Output: {}", output_file);
-    println!();
+/// Deduplicación por claves DynamoDB compuestas (gana la última aparición)
+/// Los únicos viven en memoria: cada checkpoint los vuelca a `<output>.checkpoint.csv`
+/// para que --resume los recupere sin releer la parte ya procesada
+pub fn deduplicate_dynamodb(input_file: &str, output_file: &str, model_type: &str, resume: &ResumeArgs) -> Result<(), CsvToolsError> {
+    logging::banner("DynamoDB Deduplication (Composite Keys)");
+    info!("📋 Model: {}", model_type);
+    info!("📄 Input: {}", input_file);
+    info!("📝 Output: {}", output_file);
+    info!();
 
     let (pk_name, sk_name_opt) = get_dynamodb_key_columns(model_type)?;
 
-    println!("🔑 DynamoDB Composite Key:");
-    println!("   Partition Key: {}", pk_name);
+    info!("🔑 DynamoDB Composite Key:");
+    info!("   Partition Key: {}", pk_name);
     match &sk_name_opt {
-        Some(sk) => println!("   Sort Key: {}", sk),
-        None => println!("   Sort Key: (none)")
+        Some(sk) => info!("   Sort Key: {}", sk),
+        None => info!("   Sort Key: (none)")
     }
-    println!();
+    info!();
 
-    let mut rdr = Reader::from_path(input_file)?;
-    let headers = rdr.headers()?.clone();
+    let mut checkpoints = CheckpointManager::new("deduplicate_dynamodb", input_file, output_file, &[], resume);
+    let point = if resume.resume { Some(checkpoints.load()?) } else { None };
+    let (mut rdr, headers) = checkpoints.open_reader(point.as_ref())?;
 
     let pk_idx = headers.iter().position(|h| h == pk_name)
         .ok_or(format!("Partition key '{}' not found in CSV headers", pk_name))?;
@@ -1343,40 +1486,60 @@ pub fn deduplicate_dynamodb(args: &[String]) -> Result<(), Box<dyn Error>> {
         None => None
     };
 
-    let mut records_map: HashMap<String, StringRecord> = HashMap::new();
-
-    println!("🔍 Processing records...");
-    println!();
-
-    let mut total = 0usize;
-
-    for result in rdr.records() {
-        total += 1;
-        let record = result?;
-
+    let composite_key = |record: &StringRecord| {
         let pk_value = record.get(pk_idx).unwrap_or("");
-        let composite_key = match sk_idx {
+        match sk_idx {
             Some(idx) => {
                 let sk_value = record.get(idx).unwrap_or("");
                 format!("{}|{}", pk_value, sk_value)
             },
             None => pk_value.to_string()
-        };
+        }
+    };
 
-        records_map.insert(composite_key, record);
+    let mut records_map: HashMap<String, StringRecord> = HashMap::new();
+    let mut total = 0usize;
+
+    if let Some(point) = &point {
+        let snapshot = point.snapshot.as_deref().unwrap_or_default();
+        let mut snapshot_rdr = ReaderBuilder::new().delimiter(config::delimiter()).from_path(snapshot)
+            .map_err(|e| format!("Cannot resume: snapshot '{}' not readable ({})", snapshot, e))?;
+        for result in snapshot_rdr.records() {
+            let record = result?;
+            records_map.insert(composite_key(&record), record);
+        }
+        total = point.records as usize;
+        info!("⏩ Resuming at line {} ({} records processed, {} unique restored)", point.line, total, records_map.len());
+        info!();
+    }
 
-        if total % 10_000 == 0 {
-            print!("\r📊 Processed: {} | Unique: {}", total, records_map.len());
-            std::io::stdout().flush().ok();
+    info!("🔍 Processing records...");
+    info!();
+
+    let mut progress = Progress::rows("Processed");
+    let mut record = StringRecord::new();
+    while rdr.read_record(&mut record)? {
+        total += 1;
+
+        records_map.insert(composite_key(&record), std::mem::take(&mut record));
+        progress.update(total as u64, || format!("Unique: {}", records_map.len()));
+
+        if checkpoints.due() {
+            let snapshot = checkpoints.snapshot_path();
+            write_snapshot(&snapshot, &headers, records_map.values())?;
+            checkpoints.save(rdr.position(), total as u64, &[], Some(&snapshot))?;
+            checkpoints.stop_at_deadline()?;
         }
     }
 
-    println!("\r📊 Processed: {} | Unique: {}", total, records_map.len());
-    println!();
+    progress.finish();
+    info!("📊 Processed: {} | Unique: {}", total, records_map.len());
+    info!();
 
-    println!("💾 Writing deduplicated output...");
+    info!("💾 Writing deduplicated output...");
 
     let mut wtr = WriterBuilder::new()
+        .delimiter(config::delimiter())
         .quote_style(csv::QuoteStyle::Necessary)
         .from_path(output_file)?;
 
@@ -1388,20 +1551,34 @@ pub fn deduplicate_dynamodb(args: &[String]) -> Result<(), Box<dyn Error>> {
 
     wtr.flush()?;
 
-    println!();
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Deduplication Summary                                       ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Total records processed: {}", total);
-    println!("📊 Unique records written: {}", records_map.len());
-    println!("📊 Duplicates removed: {}", total - records_map.len());
-    println!("✅ Deduplication complete");
+    info!();
+    logging::banner("Deduplication Summary");
+    info!("📊 Total records processed: {}", total);
+    info!("📊 Unique records written: {}", records_map.len());
+    info!("📊 Duplicates removed: {}", total - records_map.len());
+    info!("✅ Deduplication complete");
+    checkpoints.finish();
 
     Ok(())
 }
 
+/// Vuelca los únicos en memoria al snapshot del checkpoint (escritura atómica:
+/// un corte a mitad de la escritura deja el snapshot anterior)
+fn write_snapshot<'a>(path: &str, headers: &StringRecord, records: impl Iterator<Item = &'a StringRecord>) -> Result<(), CsvToolsError> {
+    let tmp = format!("{}.tmp", path);
+    let mut wtr = WriterBuilder::new().delimiter(config::delimiter()).from_path(&tmp)?;
+    wtr.write_record(headers)?;
+    for record in records {
+        wtr.write_record(record)?;
+    }
+    wtr.flush()?;
+    drop(wtr);
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
 /// Merge de múltiples CSV files con deduplicación
-pub fn merge_csv_files(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn merge_csv_files(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 4 {
         eprintln!("Usage: csv_tools merge <output.csv> <file1.csv> <file2.csv> [file3.csv...]");
         std::process::exit(1);
@@ -1410,21 +1587,20 @@ pub fn merge_csv_files(args: &[String]) -> Result<(), Box<dyn Error>> {
     let output_file = &args[2];
     let input_files: Vec<&String> = args[3..].iter().collect();
     
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  CSV Files Merge with Deduplication                         ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📝 Output: {}", output_file);
-    println!("📄 Input files: {}", input_files.len());
-    println!();
+    logging::banner("CSV Files Merge with Deduplication");
+    info!("📝 Output: {}", output_file);
+    info!("📄 Input files: {}", input_files.len());
+    info!();
     
     let mut all_records = HashMap::new();
     let mut headers: Option<csv::StringRecord> = None;
     let mut total_processed = 0usize;
     
+    let mut progress = Progress::rows("Processed");
     for (idx, input_file) in input_files.iter().enumerate() {
-        println!("📖 Reading file {}/{}: {}", idx + 1, input_files.len(), input_file);
+        info!("📖 Reading file {}/{}: {}", idx + 1, input_files.len(), input_file);
         
-        let mut rdr = Reader::from_path(input_file)?;
+        let mut rdr = ReaderBuilder::new().delimiter(config::delimiter()).from_reader(open_input(input_file)?);
         
         if headers.is_none() {
             headers = Some(rdr.headers()?.clone());
@@ -1437,20 +1613,18 @@ pub fn merge_csv_files(args: &[String]) -> Result<(), Box<dyn Error>> {
             let key = record.iter().collect::<Vec<_>>().join(",");
             
             all_records.insert(key, record);
-            
-            if total_processed % 10_000 == 0 {
-                print!("\r   📊 Processed: {} | Unique: {}", total_processed, all_records.len());
-                std::io::stdout().flush().ok();
-            }
+            progress.update(total_processed as u64, || format!("Unique: {}", all_records.len()));
         }
         
-        println!("\r   ✅ File {} complete", idx + 1);
+        info!("   ✅ File {} complete", idx + 1);
     }
+    progress.finish();
     
-    println!();
-    println!("💾 Writing merged output...");
+    info!();
+    info!("💾 Writing merged output...");
     
     let mut wtr = WriterBuilder::new()
+        .delimiter(config::delimiter())
         .quote_style(csv::QuoteStyle::Necessary)
         .from_path(output_file)?;
     
@@ -1464,20 +1638,18 @@ pub fn merge_csv_files(args: &[String]) -> Result<(), Box<dyn Error>> {
     
     wtr.flush()?;
     
-    println!();
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Merge Summary                                               ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Total records processed: {}", total_processed);
-    println!("📊 Unique records written: {}", all_records.len());
-    println!("📊 Duplicates removed: {}", total_processed - all_records.len());
-    println!("✅ Merge complete");
+    info!();
+    logging::banner("Merge Summary");
+    info!("📊 Total records processed: {}", total_processed);
+    info!("📊 Unique records written: {}", all_records.len());
+    info!("📊 Duplicates removed: {}", total_processed - all_records.len());
+    info!("✅ Merge complete");
     
     Ok(())
 }
 
 /// Split CSV en chunks de tamaño específico
-pub fn split_csv(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn split_csv(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 5 {
         eprintln!("Usage: csv_tools split <input.csv> <output_prefix> <chunk_size>");
         std::process::exit(1);
@@ -1488,77 +1660,92 @@ pub fn split_csv(args: &[String]) -> Result<(), Box<dyn Error>> {
     let chunk_size: usize = args[4].parse()
         .expect("chunk_size must be a positive integer");
     
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  CSV File Splitter                                          ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📄 Input: {}", input_file);
-    println!("📦 Chunk size: {} records", chunk_size);
-    println!();
+    logging::banner("CSV File Splitter");
+    info!("📄 Input: {}", input_file);
+    info!("📦 Chunk size: {} records", chunk_size);
+    info!();
     
-    let mut rdr = Reader::from_path(input_file)?;
+    let mut rdr = ReaderBuilder::new().delimiter(config::delimiter()).from_reader(open_input(input_file)?);
     let headers = rdr.headers()?.clone();
+
+    // Los chunks suman lo mismo que la entrada (stdin: tamaño desconocido, solo el margen)
+    let input_bytes = if file_utils::is_stdio(input_file) { 0 } else { file_utils::get_file_size(input_file)? };
+    let mut disk_guard = DiskSpaceGuard::new(output_prefix, input_bytes, 1.0);
+    disk_guard.preflight()?;
     
     let mut chunk_num = 1usize;
     let mut current_chunk_size = 0usize;
     let mut total_processed = 0usize;
     
-    let chunk_file = format!("{}_{:03}.csv", output_prefix, chunk_num);
+    // Con --compress cada chunk sale como .csv.gz / .csv.zst
+    let chunk_file = compression::output_path(&format!("{}_{:03}.csv", output_prefix, chunk_num));
     let mut wtr = WriterBuilder::new()
+        .delimiter(config::delimiter())
         .quote_style(csv::QuoteStyle::Necessary)
-        .from_path(&chunk_file)?;
+        .from_writer(compression::create_output(&chunk_file, 1)?);
     
     wtr.write_record(&headers)?;
     
-    println!("📝 Writing chunk {}: {}", chunk_num, chunk_file);
+    info!("📝 Writing chunk {}: {}", chunk_num, chunk_file);
     
+    let mut progress = Progress::rows("Processed");
     for result in rdr.records() {
         let record = result?;
         total_processed += 1;
+        progress.update(total_processed as u64, String::new);
         current_chunk_size += 1;
         
         wtr.write_record(&record)?;
+
+        // Cada registro se copia entero: se escribe lo mismo que se leyó (campos + separadores)
+        let record_bytes = (record.as_slice().len() + record.len()) as u64;
+        if let Some(reason) = disk_guard.record(record_bytes, record_bytes)? {
+            wtr.flush()?;
+            return Err(disk_guard.abort(Checkpoint {
+                command: "split".to_string(),
+                current_file: input_file.to_string(),
+                current_line: record.position().map_or(0, |p| p.line() as usize),
+                lines_processed: total_processed,
+                ..Default::default()
+            }, reason));
+        }
         
         if current_chunk_size >= chunk_size {
             wtr.flush()?;
-            println!("   ✅ Chunk {} complete ({} records)", chunk_num, current_chunk_size);
+            info!("   ✅ Chunk {} complete ({} records)", chunk_num, current_chunk_size);
             
             chunk_num += 1;
             current_chunk_size = 0;
             
-            let chunk_file = format!("{}_{:03}.csv", output_prefix, chunk_num);
+            let chunk_file = compression::output_path(&format!("{}_{:03}.csv", output_prefix, chunk_num));
             wtr = WriterBuilder::new()
+                .delimiter(config::delimiter())
                 .quote_style(csv::QuoteStyle::Necessary)
-                .from_path(&chunk_file)?;
+                .from_writer(compression::create_output(&chunk_file, 1)?);
             
             wtr.write_record(&headers)?;
-            println!("📝 Writing chunk {}: {}", chunk_num, chunk_file);
-        }
-        
-        if total_processed % 10_000 == 0 {
-            print!("\r   📊 Processed: {}", total_processed);
-            std::io::stdout().flush().ok();
+            info!("📝 Writing chunk {}: {}", chunk_num, chunk_file);
         }
     }
+    progress.finish();
     
     if current_chunk_size > 0 {
         wtr.flush()?;
-        println!("\r   ✅ Chunk {} complete ({} records)", chunk_num, current_chunk_size);
+        info!("   ✅ Chunk {} complete ({} records)", chunk_num, current_chunk_size);
     }
     
-    println!();
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Split Summary                                               ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Total records processed: {}", total_processed);
-    println!("📊 Chunks created: {}", chunk_num);
-    println!("✅ Split complete");
+    info!();
+    logging::banner("Split Summary");
+    info!("📊 Total records processed: {}", total_processed);
+    info!("📊 Chunks created: {}", chunk_num);
+    info!("✅ Split complete");
     
     Ok(())
 }
 
 /// Agrega newline final si falta (in-place modification)
 /// Sigue convenciones POSIX y DynamoDB ImportTable requirements
-pub fn add_trailing_newline(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn add_trailing_newline(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 3 {
         eprintln!("❌ Usage: csv_tools add-trailing-newline <file.csv>");
         eprintln!("💡 Adds newline at end if missing (modifies file in-place)");
@@ -1567,7 +1754,7 @@ pub fn add_trailing_newline(args: &[String]) -> Result<(), Box<dyn Error>> {
 
     let file_path = &args[2];
     
-    println!("🔧 Checking trailing newline: {}", file_path);
+    info!("🔧 Checking trailing newline: {}", file_path);
     
     // Leer archivo completo
     let mut content = std::fs::read(file_path)?;
@@ -1581,7 +1768,7 @@ pub fn add_trailing_newline(args: &[String]) -> Result<(), Box<dyn Error>> {
     let last_byte = content[content.len() - 1];
     
     if last_byte == b'\n' {
-        println!("✅ File already has trailing newline");
+        info!("✅ File already has trailing newline");
         return Ok(());
     }
     
@@ -1589,9 +1776,9 @@ pub fn add_trailing_newline(args: &[String]) -> Result<(), Box<dyn Error>> {
     content.push(b'\n');
     std::fs::write(file_path, &content)?;
     
-    println!("✅ Trailing newline added");
-    println!("   Old size: {} bytes", content.len() - 1);
-    println!("   New size: {} bytes", content.len());
+    info!("✅ Trailing newline added");
+    info!("   Old size: {} bytes", content.len() - 1);
+    info!("   New size: {} bytes", content.len());
     
     Ok(())
 }
@@ -1599,7 +1786,7 @@ pub fn add_trailing_newline(args: &[String]) -> Result<(), Box<dyn Error>> {
 /// Elimina líneas vacías del CSV (in-place modification)
 /// Preserva solo header + datos válidos
 /// Sigue convenciones SiisaRestApi: CSV Schema Compliance
-pub fn remove_empty_lines(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn remove_empty_lines(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 3 {
         eprintln!("❌ Usage: csv_tools remove_empty_lines <file.csv>");
         eprintln!("💡 Removes empty lines (modifies file in-place)");
@@ -1608,13 +1795,13 @@ pub fn remove_empty_lines(args: &[String]) -> Result<(), Box<dyn Error>> {
 
     let file_path = &args[2];
     
-    println!("🧹 Removing empty lines from: {}", file_path);
+    info!("🧹 Removing empty lines from: {}", file_path);
     
     // Leer archivo completo
     let content = fs::read_to_string(file_path)?;
     let lines: Vec<&str> = content.lines().collect();
     
-    println!("   Original lines: {}", lines.len());
+    info!("   Original lines: {}", lines.len());
     
     // Filtrar líneas vacías o solo con comas/espacios
     let cleaned_lines: Vec<&str> = lines
@@ -1627,11 +1814,11 @@ pub fn remove_empty_lines(args: &[String]) -> Result<(), Box<dyn Error>> {
     
     let removed_count = content.lines().count() - cleaned_lines.len();
     
-    println!("   Cleaned lines: {}", cleaned_lines.len());
-    println!("   Removed: {} empty line(s)", removed_count);
+    info!("   Cleaned lines: {}", cleaned_lines.len());
+    info!("   Removed: {} empty line(s)", removed_count);
     
     if removed_count == 0 {
-        println!("✅ No empty lines found");
+        info!("✅ No empty lines found");
         return Ok(());
     }
     
@@ -1642,21 +1829,21 @@ pub fn remove_empty_lines(args: &[String]) -> Result<(), Box<dyn Error>> {
     // ✅ SOLUCIÓN 1: Calcular tamaño ANTES de mover el ownership
     let new_size = cleaned_content.len();  // Capturar valor necesario
     fs::write(file_path, cleaned_content)?;  // Mover ownership
-    println!("   New size: {} bytes", new_size);  // Usar valor capturado
+    info!("   New size: {} bytes", new_size);  // Usar valor capturado
 
     // ✅ SOLUCIÓN 2 (alternativa): Pasar referencia en lugar de ownership
     // fs::write(file_path, &cleaned_content)?;  // Pasa &String en lugar de String
-    // println!("   New size: {} bytes", cleaned_content.len());  // Aún disponible
+    // info!("   New size: {} bytes", cleaned_content.len());  // Aún disponible
     
-    println!("✅ Empty lines removed successfully");
-    println!("   New size: {} bytes", new_size);
+    info!("✅ Empty lines removed successfully");
+    info!("   New size: {} bytes", new_size);
     
     Ok(())
 }
 
 /// Sanitiza CSV completo para DynamoDB ImportTable
 /// Elimina BOM + líneas vacías + agrega newline final
-pub fn sanitize_csv_complete(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn sanitize_csv_complete(args: &[String]) -> Result<(), CsvToolsError> {
     if args.len() < 4 {
         eprintln!("❌ Usage: csv_tools sanitize_csv <input.csv> <output.csv>");
         eprintln!("💡 Full sanitization: BOM + empty lines + trailing newline");
@@ -1666,13 +1853,11 @@ pub fn sanitize_csv_complete(args: &[String]) -> Result<(), Box<dyn Error>> {
     let input_file = &args[2];
     let output_file = &args[3];
     
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  CSV Complete Sanitization for DynamoDB ImportTable         ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!();
-    println!("📦 Input: {}", input_file);
-    println!("📝 Output: {}", output_file);
-    println!();
+    logging::banner("CSV Complete Sanitization for DynamoDB ImportTable");
+    info!();
+    info!("📦 Input: {}", input_file);
+    info!("📝 Output: {}", output_file);
+    info!();
     
     // Leer archivo como bytes (para detectar BOM)
     let mut bytes = fs::read(input_file)?;
@@ -1681,7 +1866,7 @@ pub fn sanitize_csv_complete(args: &[String]) -> Result<(), Box<dyn Error>> {
     // 1. Eliminar BOM si existe
     let mut bom_removed = false;
     if bytes.len() >= 3 && bytes[0] == 0xEF && bytes[1] == 0xBB && bytes[2] == 0xBF {
-        println!("🔧 Removing UTF-8 BOM...");
+        info!("🔧 Removing UTF-8 BOM...");
         bytes = bytes[3..].to_vec();
         bom_removed = true;
     }
@@ -1690,8 +1875,8 @@ pub fn sanitize_csv_complete(args: &[String]) -> Result<(), Box<dyn Error>> {
     let content = String::from_utf8(bytes)?;
     let lines: Vec<&str> = content.lines().collect();
     
-    println!("📋 Line analysis:");
-    println!("   Total lines: {}", lines.len());
+    info!("📋 Line analysis:");
+    info!("   Total lines: {}", lines.len());
     
     let cleaned_lines: Vec<&str> = lines
         .into_iter()
@@ -1704,7 +1889,7 @@ pub fn sanitize_csv_complete(args: &[String]) -> Result<(), Box<dyn Error>> {
     let empty_lines_removed = content.lines().count() - cleaned_lines.len();
     
     if empty_lines_removed > 0 {
-        println!("🧹 Removed {} empty line(s)", empty_lines_removed);
+        info!("🧹 Removed {} empty line(s)", empty_lines_removed);
     }
     
     // 3. Reconstruir CSV con newline final
@@ -1719,35 +1904,33 @@ pub fn sanitize_csv_complete(args: &[String]) -> Result<(), Box<dyn Error>> {
     // 4. Escribir archivo sanitizado (mueve ownership de final_content)
     fs::write(output_file, final_content.as_bytes())?;
     
-    println!();
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Sanitization Summary                                        ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
+    info!();
+    logging::banner("Sanitization Summary");
     
     if bom_removed {
-        println!("✅ BOM removed (saved 3 bytes)");
+        info!("✅ BOM removed (saved 3 bytes)");
     } else {
-        println!("✅ No BOM detected");
+        info!("✅ No BOM detected");
     }
     
     if empty_lines_removed > 0 {
-        println!("✅ {} empty line(s) removed", empty_lines_removed);
+        info!("✅ {} empty line(s) removed", empty_lines_removed);
     } else {
-        println!("✅ No empty lines detected");
+        info!("✅ No empty lines detected");
     }
     
-    println!("✅ Trailing newline added");
-    println!();
-    println!("📊 Size change: {} → {} bytes ({:+} bytes)", 
+    info!("✅ Trailing newline added");
+    info!();
+    info!("📊 Size change: {} → {} bytes ({:+} bytes)", 
              original_size, new_size, size_diff);
-    println!("📋 Final structure: {} lines (header + {} data rows)", 
+    info!("📋 Final structure: {} lines (header + {} data rows)", 
              final_line_count, final_line_count - 1);
-    println!();
-    println!("📝 Files:");
-    println!("   Input (original): {}", input_file);
-    println!("   Output (sanitized): {}", output_file);
-    println!();
-    println!("🎯 Sanitized CSV is ready for DynamoDB ImportTable");
+    info!();
+    info!("📝 Files:");
+    info!("   Input (original): {}", input_file);
+    info!("   Output (sanitized): {}", output_file);
+    info!();
+    info!("🎯 Sanitized CSV is ready for DynamoDB ImportTable");
     
     Ok(())
 }
@@ -1755,15 +1938,13 @@ pub fn sanitize_csv_complete(args: &[String]) -> Result<(), Box<dyn Error>> {
 /// Elimina registros desde una fila específica hasta el final del archivo
 /// Mantiene el header y solo preserva las filas antes de la fila especificada
 /// Sigue convenciones SiisaRestApi: CsvHelper-based parsing + structured error reporting
-pub fn delete_from_row(input_file: &str, output_file: &str, from_row: usize) -> Result<(), Box<dyn Error>> {
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Delete Rows from Specific Line to End                      ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📄 Input CSV: {}", input_file);
-    println!("📝 Output CSV: {}", output_file);
-    println!("✂️  Delete from row: {} (to end of file)", from_row);
-    println!("📋 Note: Row 1 = header, Row 2 = first data row");
-    println!();
+pub fn delete_from_row(input_file: &str, output_file: &str, from_row: usize) -> Result<(), CsvToolsError> {
+    logging::banner("Delete Rows from Specific Line to End");
+    info!("📄 Input CSV: {}", input_file);
+    info!("📝 Output CSV: {}", output_file);
+    info!("✂️  Delete from row: {} (to end of file)", from_row);
+    info!("📋 Note: Row 1 = header, Row 2 = first data row");
+    info!();
 
     // Validar que from_row sea válido
     if from_row <= 1 {
@@ -1772,32 +1953,33 @@ pub fn delete_from_row(input_file: &str, output_file: &str, from_row: usize) ->
     }
 
     let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .flexible(true)
         .trim(csv::Trim::All)
-        .from_path(input_file)?;
+        .from_reader(open_input(input_file)?);
 
-    let mut wtr = WriterBuilder::new()
-        .quote_style(csv::QuoteStyle::Necessary)
-        .from_path(output_file)?;
+    let mut wtr = open_sink(output_file)?;
 
     let headers = rdr.headers()?.clone();
-    wtr.write_record(&headers)?;
+    wtr.write_header(&headers)?;
 
     let mut current_row = 2usize; // La primera fila de datos es la fila 2
     let mut total_processed = 0usize;
     let mut rows_kept = 0usize;
     let mut rows_deleted = 0usize;
 
-    println!("🔍 Processing records...");
-    println!();
+    info!("🔍 Processing records...");
+    info!();
 
+    let mut progress = Progress::rows("Processed");
     for result in rdr.records() {
         total_processed += 1;
+        progress.update(total_processed as u64, || format!("Kept: {} | Deleted: {}", rows_kept, rows_deleted));
         
         let record = match result {
             Ok(r) => r,
             Err(e) => {
-                eprintln!("⚠️  Warning: Skipping malformed record at line {}: {}", current_row, e);
+                diagnostics::warn(format!("Warning: Skipping malformed record at line {}: {}", current_row, e));
                 current_row += 1;
                 continue;
             }
@@ -1812,53 +1994,188 @@ pub fn delete_from_row(input_file: &str, output_file: &str, from_row: usize) ->
             rows_deleted += 1;
         }
 
-        if total_processed % 10_000 == 0 {
-            print!("\r📊 Processed: {} | Kept: {} | Deleted: {}", 
-                total_processed, rows_kept, rows_deleted);
-            std::io::stdout().flush().ok();
-        }
-
         current_row += 1;
     }
 
-    wtr.flush()?;
+    wtr.finish()?;
+    progress.finish();
 
-    println!("\r📊 Processed: {} | Kept: {} | Deleted: {}", 
+    info!("📊 Processed: {} | Kept: {} | Deleted: {}", 
         total_processed, rows_kept, rows_deleted);
-    println!();
-
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Delete Operation Summary                                    ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Input CSV:");
-    println!("   Total data rows processed: {}", total_processed);
-    println!("   Cut-off point: Row {} (inclusive)", from_row);
-    println!();
-    println!("📊 Output CSV:");
-    println!("   Rows preserved: {} ✅", rows_kept);
-    println!("   Rows deleted: {} ❌", rows_deleted);
-    println!("   Header preserved: ✅");
+    info!();
+
+    logging::banner("Delete Operation Summary");
+    info!("📊 Input CSV:");
+    info!("   Total data rows processed: {}", total_processed);
+    info!("   Cut-off point: Row {} (inclusive)", from_row);
+    info!();
+    info!("📊 Output CSV:");
+    info!("   Rows preserved: {} ✅", rows_kept);
+    info!("   Rows deleted: {} ❌", rows_deleted);
+    info!("   Header preserved: ✅");
     
     if rows_deleted > 0 {
-        println!("   Deletion rate: {:.2}%", 
+        info!("   Deletion rate: {:.2}%", 
             (rows_deleted as f64 / total_processed as f64) * 100.0);
     }
     
-    println!();
-    println!("📝 Files:");
-    println!("   Original CSV: {}", input_file);
-    println!("   Truncated CSV: {}", output_file);
+    info!();
+    info!("📝 Files:");
+    info!("   Original CSV: {}", input_file);
+    info!("   Truncated CSV: {}", output_file);
     
-    println!();
+    info!();
     if rows_deleted > 0 {
-        println!("🎯 Operation completed successfully:");
-        println!("   {} records removed from row {} onwards", rows_deleted, from_row);
-        println!("   Output contains header + {} data rows", rows_kept);
+        info!("🎯 Operation completed successfully:");
+        info!("   {} records removed from row {} onwards", rows_deleted, from_row);
+        info!("   Output contains header + {} data rows", rows_kept);
     } else {
-        println!("📋 No records were deleted:");
-        println!("   Cut-off row {} is beyond the end of the file", from_row);
-        println!("   Output is identical to input");
+        info!("📋 No records were deleted:");
+        info!("   Cut-off row {} is beyond the end of the file", from_row);
+        info!("   Output is identical to input");
     }
 
     Ok(())
 }
+
+/// Copia el header y las filas `from_row..=to_row` (misma numeración que delete_from_row:
+/// header = 1, primera fila de datos = 2); sin `to_row` hasta el final. Las filas
+/// anteriores se saltean sin decodificar y la lectura termina al pasar `to_row`
+pub fn slice_rows(input_file: &str, output_file: &str, from_row: usize, to_row: Option<usize>) -> Result<(), CsvToolsError> {
+    if from_row < 2 {
+        return Err(exit_code::usage_error("--from must be >= 2 (row 1 is the header)"));
+    }
+    if let Some(to_row) = to_row.filter(|to| *to < from_row) {
+        return Err(exit_code::usage_error(format!("--to {} is before --from {}", to_row, from_row)));
+    }
+
+    logging::banner("Slice Rows");
+    info!("📄 Input CSV: {}", input_file);
+    info!("📝 Output CSV: {}", output_file);
+    match to_row {
+        Some(to_row) => info!("✂️  Rows {} to {} (header = row 1)", from_row, to_row),
+        None => info!("✂️  Rows {} to end of file (header = row 1)", from_row),
+    }
+    info!();
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .flexible(true)
+        .from_reader(open_input(input_file)?);
+    let mut wtr = open_sink(output_file)?;
+    wtr.write_header(&rdr.headers()?.clone())?;
+
+    let mut record = csv::ByteRecord::new();
+    let mut row = 1usize;
+    let mut written = 0usize;
+    let mut progress = Progress::rows("Read");
+    while rdr.read_byte_record(&mut record)? {
+        row += 1;
+        progress.update((row - 1) as u64, || format!("Written: {}", written));
+        if row < from_row {
+            continue;
+        }
+        if to_row.is_some_and(|to| row > to) {
+            break;
+        }
+        wtr.write_record(&StringRecord::from_byte_record(record.clone())
+            .map_err(|e| format!("Row {} is not valid UTF-8: {}", row, e.utf8_error()))?)?;
+        written += 1;
+    }
+    wtr.finish()?;
+    progress.finish();
+
+    info!("✅ Rows written: {} (header + rows {}..{})", written, from_row, from_row + written.saturating_sub(1));
+    if written == 0 {
+        diagnostics::warn(format!("Row {} is beyond the end of the file ({} rows): only the header was written", from_row, row));
+    } else if to_row.is_some_and(|to| row < to) {
+        diagnostics::warn(format!("File ends at row {}, before --to {}", row, to_row.unwrap_or(0)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_slice_rows_range() {
+        let dir = TempDir::new("slice");
+        let input = dir.write("in.csv", "id,v\n1,a\n2,\"multi\nline\"\n3,c\n4,d\n");
+        let output = dir.path("out.csv");
+
+        slice_rows(&input, &output, 3, Some(4)).unwrap();
+        assert_eq!(dir.read("out.csv"), "id,v\n2,\"multi\nline\"\n3,c\n");
+        assert!(slice_rows(&input, &output, 4, Some(3)).is_err());
+    }
+
+    #[test]
+    fn test_head_and_tail_over_streams() {
+        let input = "a,b\n1,x\n2,y\n3,z\n";
+
+        let mut out = Vec::new();
+        assert_eq!(write_head(input.as_bytes(), &mut out, 2).unwrap(), 2);
+        assert_eq!(String::from_utf8(out).unwrap(), "a,b\n1,x\n2,y\n");
+
+        let mut out = Vec::new();
+        assert_eq!(write_tail(input.as_bytes(), &mut out, 2).unwrap(), 2);
+        assert_eq!(String::from_utf8(out).unwrap(), "a,b\n2,y\n3,z\n");
+
+        let mut out = Vec::new();
+        assert_eq!(write_tail(input.as_bytes(), &mut out, 0).unwrap(), 0);
+        assert_eq!(String::from_utf8(out).unwrap(), "a,b\n");
+    }
+
+    #[test]
+    fn test_markdown_table_alignment_and_truncation() {
+        let mut out = Vec::new();
+        write_markdown_table(b"Cuil,ApellidoNombre\n20123,\"PEREZ | JUAN CARLOS\"\n5,\n", &mut out, 10).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), concat!(
+            "| Cuil  | ApellidoNombre |\n",
+            "| ----: | -------------- |\n",
+            "| 20123 | PEREZ \\| J…    |\n",
+            "|     5 |                |\n",
+        ));
+    }
+
+    #[test]
+    fn test_sanitize_auto_row_number_column() {
+        let dir = TempDir::new("sanitize_rows");
+        let input = dir.write("in.csv", "Cuil,Nombre\n20111,\"Ana\nMaría\"\nabc,Bob\n20333,Carla\n");
+        let output = dir.path("out.csv");
+
+        // El campo multilínea ocupa las líneas 2 y 3: Carla empieza en la 5
+        sanitize_for_dynamodb_auto(&input, &output, "siisa_morosos", Some(2), None, Some("SourceLine")).unwrap();
+        assert_eq!(dir.read("out.csv"),
+                   "\"Cuil\",\"Nombre\",\"SourceLine\"\n20111,\"Ana\nMaría\",2\n20333,\"Carla\",5\n");
+        assert!(sanitize_for_dynamodb_auto(&input, &output, "siisa_morosos", Some(2), None, Some("Nombre")).is_err());
+    }
+
+    #[test]
+    fn test_source_columns_quote_file_names() {
+        let source = SourceColumns { file: Some("FileName".to_string()), line: Some("LineNo".to_string()) };
+        assert_eq!(source.header_suffix(), ",FileName,LineNo");
+        let file_value = source.file_value("chunks/part \"1\",a.csv");
+        assert_eq!(source.row_suffix(&file_value, 7), ",\"chunks/part \"\"1\"\",a.csv\",7");
+        assert!(SourceColumns::default().header_suffix().is_empty());
+    }
+
+    #[test]
+    fn test_merge_files_header_mismatch() {
+        let dir = TempDir::new("merge_headers");
+        dir.write("a.csv", "id,v\n1,a\n");
+        dir.write("b.csv", "id\n2\n");
+        dir.write("c.csv", "id,v\n3,c\n");
+        let inputs = dir.path("*.csv");
+        // La salida fuera del glob de entrada
+        let out = TempDir::new("merge_headers_out");
+        let output = &out.path("out.csv");
+
+        assert!(merge_files(&inputs, output, &SourceColumns::default(), None, HeaderMismatch::Abort, MergeReport::new(None, "merge", output)).is_err());
+        assert!(!std::path::Path::new(output).exists());
+
+        merge_files(&inputs, output, &SourceColumns::default(), None, HeaderMismatch::Skip, MergeReport::new(None, "merge", output)).unwrap();
+        assert_eq!(out.read("out.csv"), "id,v\n1,a\n3,c\n");
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use csv::{ReaderBuilder, StringRecord};
+use regex::Regex;
+
+use clap::Args;
+use crate::config;
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::exit_code;
+use crate::file_utils::{open_input, status_output};
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::schema::{coerce_value, ColumnSpec, ColumnType};
+use crate::sink::open_sink;
+
+// Valores de la lista sin ninguna fila que se muestran como ejemplo
+const MAX_UNMATCHED_SHOWN: usize = 10;
+// Fechas inválidas que se muestran como ejemplo (con su línea)
+const MAX_INVALID_SHOWN: usize = 5;
+
+/// Conserva las filas cuya columna es igual a `value`; con `invert`, las demás
+pub fn filter_rows(input_file: &str, output_file: &str, column_name: &str, value: &str, invert: bool) -> Result<(), CsvToolsError> {
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .from_reader(open_input(input_file)?);
+    let headers = rdr.headers()?.clone();
+
+    let column_index = headers.iter().position(|h| h == column_name).ok_or_else(|| {
+        exit_code::usage_error(format!("Column '{}' not found in input file", column_name))
+    })?;
+
+    let mut writer = open_sink(output_file)?;
+    writer.write_header(&headers)?;
+
+    let (mut kept, mut removed) = (0usize, 0usize);
+    for result in rdr.records() {
+        let record = result?;
+        if (record.get(column_index).unwrap_or("") == value) != invert {
+            writer.write_record(&record)?;
+            kept += 1;
+        } else {
+            removed += 1;
+        }
+    }
+
+    writer.finish()?;
+    writeln!(status_output(output_file), "Row filtering complete: {} kept, {} removed.", kept, removed)?;
+    Ok(())
+}
+
+/// Conserva las filas cuyo valor en `column` está en una lista (un valor por línea),
+/// p. ej. los ~50k clientes que pide QA de un export de 100M filas. La lista queda en
+/// un HashSet; el CSV se recorre en streaming
+#[derive(Args, Debug)]
+pub struct FilterInArgs {
+    #[arg(value_name = "INPUT.csv[.gz]", help = "Input file, '-' for stdin")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]", help = "Output file or sink URI, '-' for stdout")]
+    pub output: String,
+    #[arg(value_name = "COLUMN")]
+    pub column: String,
+    #[arg(long, value_name = "ids.txt", help = "Values to keep, one per line (blank lines ignored)")]
+    pub values_file: String,
+    #[arg(long, help = "Remove the rows whose value is in the list and keep the rest")]
+    pub invert: bool,
+}
+
+pub fn filter_in(args: &FilterInArgs) -> Result<(), CsvToolsError> {
+    let values = load_values(&args.values_file)?;
+
+    logging::banner(if args.invert { "Filter Rows NOT IN Value List" } else { "Filter Rows IN Value List" });
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    info!("🔎 Column: {}", args.column);
+    info!("📋 Values: {} distinct in {}", values.len(), args.values_file);
+    info!();
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(&args.input)?);
+    let headers = rdr.headers()?.clone();
+    let column_index = headers.iter().position(|h| h.trim() == args.column.trim())
+        .ok_or_else(|| exit_code::usage_error(format!("Column '{}' not found in input file", args.column)))?;
+
+    let mut writer = open_sink(&args.output)?;
+    writer.write_header(&headers)?;
+
+    let mut record = StringRecord::new();
+    let mut matched: HashSet<&str> = HashSet::new();
+    let (mut processed, mut kept) = (0usize, 0usize);
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        processed += 1;
+        let found = values.get(record.get(column_index).unwrap_or("").trim());
+        if let Some(value) = found {
+            matched.insert(value.as_str());
+        }
+        if found.is_some() != args.invert {
+            writer.write_record(&record)?;
+            kept += 1;
+        }
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+    writer.finish()?;
+
+    info!("📊 Rows read: {}", processed);
+    info!("✅ Rows written: {}", kept);
+    info!("🗑️  Rows removed: {}", processed - kept);
+    // Valores pedidos que no aparecen en el archivo (IDs mal copiados o de otro export)
+    let mut unmatched: Vec<&str> = values.iter().map(String::as_str).filter(|v| !matched.contains(v)).collect();
+    if !unmatched.is_empty() {
+        unmatched.sort();
+        diagnostics::warn(format!("{} of {} values were not found in {} (e.g. {})",
+            unmatched.len(), values.len(), args.column,
+            unmatched.iter().take(MAX_UNMATCHED_SHOWN).copied().collect::<Vec<_>>().join(", ")));
+    }
+    logging::summary(&serde_json::json!({
+        "command": "filter_in",
+        "input": args.input,
+        "output": args.output,
+        "column": args.column,
+        "invert": args.invert,
+        "values": values.len(),
+        "values_not_found": unmatched.len(),
+        "records": processed,
+        "records_written": kept,
+    }))?;
+
+    Ok(())
+}
+
+/// Conserva las filas cuyo valor en `column` cumple una expresión regular (sintaxis del
+/// crate regex; busca en cualquier parte del valor salvo que se ancle con ^...$)
+#[derive(Args, Debug)]
+pub struct FilterRegexArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(value_name = "COLUMN")]
+    pub column: String,
+    #[arg(value_name = "PATTERN", help = "Regular expression, matched anywhere in the value (anchor with ^...$)")]
+    pub pattern: String,
+    #[arg(long, help = "Remove the rows that match and keep the rest")]
+    pub invert: bool,
+}
+
+pub fn filter_regex(args: &FilterRegexArgs) -> Result<(), CsvToolsError> {
+    let pattern = Regex::new(&args.pattern)
+        .map_err(|e| exit_code::usage_error(format!("Invalid pattern '{}': {}", args.pattern, e)))?;
+
+    logging::banner(if args.invert { "Filter Rows NOT Matching Pattern" } else { "Filter Rows Matching Pattern" });
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    info!("🔎 Column: {}", args.column);
+    info!("🧩 Pattern: {}", args.pattern);
+    info!();
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(&args.input)?);
+    let headers = rdr.headers()?.clone();
+    let column_index = headers.iter().position(|h| h.trim() == args.column.trim())
+        .ok_or_else(|| exit_code::usage_error(format!("Column '{}' not found in input file", args.column)))?;
+
+    let mut writer = open_sink(&args.output)?;
+    writer.write_header(&headers)?;
+
+    let mut record = StringRecord::new();
+    let (mut processed, mut matched, mut kept) = (0usize, 0usize, 0usize);
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        processed += 1;
+        let is_match = pattern.is_match(record.get(column_index).unwrap_or(""));
+        if is_match {
+            matched += 1;
+        }
+        if is_match != args.invert {
+            writer.write_record(&record)?;
+            kept += 1;
+        }
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+    writer.finish()?;
+
+    info!("📊 Rows read: {}", processed);
+    info!("🧩 Rows matching: {}", matched);
+    info!("✅ Rows written: {}", kept);
+    logging::summary(&serde_json::json!({
+        "command": "filter_regex",
+        "input": args.input,
+        "output": args.output,
+        "column": args.column,
+        "pattern": args.pattern,
+        "invert": args.invert,
+        "records": processed,
+        "records_matching": matched,
+        "records_written": kept,
+    }))?;
+
+    Ok(())
+}
+
+/// Qué hace filter_dates con las filas cuya fecha está vacía o no se puede interpretar
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidDates {
+    /// Las descarta (default): no se puede saber si están en el rango
+    Drop,
+    /// Las conserva junto con las del rango
+    Keep,
+}
+
+/// Conserva las filas cuya fecha en `column` está entre --from y --to (inclusive).
+/// Las fechas se interpretan con el mismo parser que el schema (ISO, dd/mm/yyyy, ...)
+#[derive(Args, Debug)]
+pub struct FilterDatesArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(value_name = "DATE_COLUMN")]
+    pub column: String,
+    #[arg(long, value_name = "DATE", required_unless_present = "to", help = "First date kept (inclusive)")]
+    pub from: Option<String>,
+    #[arg(long, value_name = "DATE", help = "Last date kept (inclusive)")]
+    pub to: Option<String>,
+    #[arg(long, value_enum, default_value_t = InvalidDates::Drop, help = "Rows with an empty or unparseable date")]
+    pub on_invalid: InvalidDates,
+}
+
+pub fn filter_dates(args: &FilterDatesArgs) -> Result<(), CsvToolsError> {
+    let spec = ColumnSpec { column_type: ColumnType::Date, ..Default::default() };
+    // Fecha ISO; None si está vacía o no se reconoce
+    let iso = |value: &str| coerce_value(value.trim(), &spec).ok().filter(|d| !d.is_empty());
+    let bound = |value: &Option<String>, name: &str| match value {
+        Some(v) => iso(v).map(Some)
+            .ok_or_else(|| exit_code::usage_error(format!("Invalid {} date '{}'", name, v))),
+        None => Ok(None),
+    };
+    let (from, to) = (bound(&args.from, "--from")?, bound(&args.to, "--to")?);
+    if let (Some(from), Some(to)) = (&from, &to) {
+        if from > to {
+            return Err(exit_code::usage_error(format!("--from {} is after --to {}", from, to)));
+        }
+    }
+
+    logging::banner("Filter Rows by Date Range");
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    info!("📅 {}: {} to {}", args.column, from.as_deref().unwrap_or("-"), to.as_deref().unwrap_or("-"));
+    info!("❓ Invalid dates: {}", if args.on_invalid == InvalidDates::Keep { "keep" } else { "drop" });
+    info!();
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(&args.input)?);
+    let headers = rdr.headers()?.clone();
+    let column_index = headers.iter().position(|h| h.trim() == args.column.trim())
+        .ok_or_else(|| exit_code::usage_error(format!("Column '{}' not found in input file", args.column)))?;
+
+    let mut writer = open_sink(&args.output)?;
+    writer.write_header(&headers)?;
+
+    let mut record = StringRecord::new();
+    let (mut processed, mut kept, mut invalid) = (0usize, 0usize, 0usize);
+    let mut invalid_examples = Vec::new();
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        processed += 1;
+        let value = record.get(column_index).unwrap_or("");
+        let keep = match iso(value) {
+            Some(date) => from.as_ref().is_none_or(|f| date >= *f) && to.as_ref().is_none_or(|t| date <= *t),
+            None => {
+                invalid += 1;
+                if invalid_examples.len() < MAX_INVALID_SHOWN {
+                    let line = record.position().map(|p| p.line()).unwrap_or(0);
+                    invalid_examples.push(format!("line {}: '{}'", line, value));
+                }
+                args.on_invalid == InvalidDates::Keep
+            }
+        };
+        if keep {
+            writer.write_record(&record)?;
+            kept += 1;
+        }
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+    writer.finish()?;
+
+    info!("📊 Rows read: {}", processed);
+    info!("✅ Rows written: {}", kept);
+    info!("🗑️  Rows removed: {}", processed - kept);
+    if invalid > 0 {
+        diagnostics::warn(format!("{} rows have an empty or unparseable {} ({}): {}",
+            invalid, args.column, if args.on_invalid == InvalidDates::Keep { "kept" } else { "dropped" }, invalid_examples.join(", ")));
+    }
+    logging::summary(&serde_json::json!({
+        "command": "filter_dates",
+        "input": args.input,
+        "output": args.output,
+        "column": args.column,
+        "from": from,
+        "to": to,
+        "records": processed,
+        "records_written": kept,
+        "invalid_dates": invalid,
+    }))?;
+
+    Ok(())
+}
+
+/// Un valor por línea, sin espacios en los extremos; ignora las líneas vacías y el BOM
+fn load_values(path: &str) -> Result<HashSet<String>, CsvToolsError> {
+    let mut values = HashSet::new();
+    for line in open_input(path)?.lines() {
+        let line = line?;
+        let value = line.trim_start_matches('\u{feff}').trim();
+        if !value.is_empty() {
+            values.insert(value.to_string());
+        }
+    }
+    if values.is_empty() {
+        return Err(format!("Values file '{}' has no values", path).into());
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_filter_in_keeps_listed_values() {
+        let dir = TempDir::new("filter_in");
+        dir.write("in.csv", "Cuil,v\n20111,a\n20222,b\n20333,c\n20111,d\n");
+        dir.write("ids.txt", "\u{feff}20111\n\n 20333 \n20999\n");
+
+        let mut args = FilterInArgs {
+            input: dir.path("in.csv"),
+            output: dir.path("out.csv"),
+            column: "Cuil".to_string(),
+            values_file: dir.path("ids.txt"),
+            invert: false,
+        };
+        filter_in(&args).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,v\n20111,a\n20333,c\n20111,d\n");
+
+        args.invert = true;
+        filter_in(&args).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,v\n20222,b\n");
+    }
+
+    #[test]
+    fn test_filter_dates_mixed_formats() {
+        let dir = TempDir::new("filter_dates");
+        dir.write("in.csv", "Fecha,v\n2024-01-01,a\n15/02/2024,b\n2024-04-01,c\n,d\nayer,e\n");
+
+        let mut args = FilterDatesArgs {
+            input: dir.path("in.csv"),
+            output: dir.path("out.csv"),
+            column: "Fecha".to_string(),
+            from: Some("01/01/2024".to_string()),
+            to: Some("2024-03-31".to_string()),
+            on_invalid: InvalidDates::Drop,
+        };
+        filter_dates(&args).unwrap();
+        assert_eq!(dir.read("out.csv"), "Fecha,v\n2024-01-01,a\n15/02/2024,b\n");
+
+        args.on_invalid = InvalidDates::Keep;
+        args.from = None;
+        filter_dates(&args).unwrap();
+        assert_eq!(dir.read("out.csv"), "Fecha,v\n2024-01-01,a\n15/02/2024,b\n,d\nayer,e\n");
+    }
+
+    #[test]
+    fn test_filter_regex_invert() {
+        let dir = TempDir::new("filter_regex");
+        dir.write("in.csv", "Telefono,v\n1145551234,a\n43211234,b\n,c\n");
+
+        filter_regex(&FilterRegexArgs {
+            input: dir.path("in.csv"),
+            output: dir.path("out.csv"),
+            column: "Telefono".to_string(),
+            pattern: r"^11\d{8}$".to_string(),
+            invert: true,
+        }).unwrap();
+        assert_eq!(dir.read("out.csv"), "Telefono,v\n43211234,b\n,c\n");
+    }
+}
@@ -1,34 +0,0 @@
-use std::error::Error;
-
-pub fn detect_missing_header(args: &[String]) -> Result<(), Box<dyn Error>> {
-    if args.len() < 3 {
-        eprintln!("Usage: csv_tools detect_missing_header <input_file> [expected_header]");
-        return Ok(());
-    }
-    
-    println!("⚠️  detect_missing_header not yet fully implemented");
-    
-    Ok(())
-}
-
-pub fn add_header(args: &[String]) -> Result<(), Box<dyn Error>> {
-    if args.len() < 5 {
-        eprintln!("Usage: csv_tools add_header <input> <output> <model> [validate]");
-        return Ok(());
-    }
-    
-    println!("⚠️  add_header not yet fully implemented");
-    
-    Ok(())
-}
-
-pub fn batch_add_headers(args: &[String]) -> Result<(), Box<dyn Error>> {
-    if args.len() < 5 {
-        eprintln!("Usage: csv_tools batch_add_headers <list> <model> <outdir>");
-        return Ok(());
-    }
-    
-    println!("⚠️  batch_add_headers not yet fully implemented");
-    
-    Ok(())
-}
\ No newline at end of file
@@ -1,42 +1,109 @@
-use std::error::Error;
-use std::fs::File;
 use csv::ReaderBuilder;
+use std::collections::HashMap;
+use std::io::Read;
+use clap::Args;
+use crate::cli_args::{parse_count, parse_percentile};
+use crate::config;
+use crate::diagnostics;
+use crate::encoding;
+use crate::error::CsvToolsError;
+use crate::error_report::{self, ErrorRecord};
+use crate::exit_code::{data_issues, usage_error};
+use crate::file_utils::{format_bytes, open_input, open_input_bytes};
+use crate::html_report::ValidationReport;
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
 use crate::models::DynamoDbModel;
+use crate::schema::{infer_type, Schema};
+use crate::sniff;
+use crate::sketch::TDigest;
 
-pub fn validate_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
-    if args.len() < 4 {
-        eprintln!("Usage: csv_tools validate_schema <input.csv> <model_type>");
-        eprintln!("Model types: siisa_morosos, personas_telefonos");
-        std::process::exit(1);
+// Filas del cuerpo que `header` lee para inferir tipos
+const HEADER_SAMPLE_ROWS: usize = 100;
+// Errores que se imprimen por campo en validate_schema (--max-examples-per-field)
+const DEFAULT_MAX_EXAMPLES_PER_FIELD: usize = 20;
+// Valores inválidos más frecuentes que se muestran por campo en el resumen
+const TOP_OFFENDING_VALUES: usize = 5;
+// Percentiles que reporta `profile` si no se pasa --percentiles
+const DEFAULT_PERCENTILES: [f64; 3] = [50.0, 95.0, 99.0];
+// Compresión del t-digest de `profile` (~2x centroides por columna, error < 1% en p99)
+const PROFILE_DIGEST_COMPRESSION: f64 = 100.0;
+
+/// Errores de un campo: total exacto y conteo por valor inválido
+#[derive(Default)]
+struct FieldErrors {
+    count: usize,
+    values: HashMap<String, usize>,
+}
+
+impl FieldErrors {
+    /// Valores más frecuentes (mayor conteo primero, empate por valor)
+    fn top_values(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut values: Vec<(&str, usize)> = self.values.iter().map(|(v, c)| (v.as_str(), *c)).collect();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        values.truncate(n);
+        values
     }
+}
 
-    let input_path = &args[2];
-    let model_type = &args[3];
+#[derive(Args, Debug)]
+pub struct ValidateSchemaArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "MODEL_TYPE", help = "DynamoDB model [default: default_model from the config file]")]
+    pub model_type: Option<String>,
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_EXAMPLES_PER_FIELD,
+          help = "Validation errors printed per field")]
+    pub max_examples_per_field: usize,
+    #[arg(long, value_name = "SCHEMA.yaml", help = "Schema whose nullable numeric columns accept empty values")]
+    pub schema: Option<String>,
+    #[arg(long, value_name = "REPORT.html", help = "Write an HTML report (errors per column, sample bad rows, charts)")]
+    pub report_html: Option<String>,
+}
+
+pub fn validate_schema(args: &ValidateSchemaArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let model_type = config::model_or_default(args.model_type.as_deref())?;
+    let model_type = model_type.as_str();
+    let max_examples = args.max_examples_per_field;
 
     let model = DynamoDbModel::from_model_type(model_type)
-        .ok_or_else(|| format!("Unknown model type: {}", model_type))?;
+        .ok_or_else(|| usage_error(format!("Unknown model type: {}", model_type)))?;
+
+    // Campos Type N que el schema declara `nullable`: un vacío no es error
+    let schema = match &args.schema {
+        Some(spec) => Some(Schema::load(spec)?),
+        None => None,
+    };
+    let nullable_fields: Vec<&str> = model.numeric_fields.iter()
+        .copied()
+        .filter(|f| schema.as_ref().and_then(|s| s.column(f)).is_some_and(|c| c.empty_value().is_some()))
+        .collect();
 
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  DynamoDB Schema Validation (Complete)                      ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📋 Model: {}", model.table_name);
-    println!("📄 Input: {}", input_path);
-    println!();
+    logging::banner("DynamoDB Schema Validation (Complete)");
+    info!("📋 Model: {}", model.table_name);
+    info!("📄 Input: {}", input_path);
+    info!();
 
     // ✅ NUEVO: Mostrar todos los campos numéricos a validar
-    println!("🔢 DynamoDB Type N Fields (all will be validated):");
-    println!("   Partition Key: {} (Type N)", model.partition_key);
-    println!("   Sort Key: {} (Type N)", model.sort_key);
+    info!("🔢 DynamoDB Type N Fields (all will be validated):");
+    info!("   Partition Key: {} (Type N)", model.partition_key);
+    info!("   Sort Key: {} (Type N)", model.sort_key);
     for field in &model.numeric_fields {
         if *field != model.partition_key && *field != model.sort_key {
-            println!("   Additional: {} (Type N)", field);
+            info!("   Additional: {} (Type N)", field);
         }
     }
-    println!();
+    if !nullable_fields.is_empty() {
+        info!("   Nullable (empty allowed): {}", nullable_fields.join(", "));
+    }
+    info!();
 
     // Abrir CSV
-    let file = File::open(input_path)?;
+    let file = open_input(input_path)?;
     let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .has_headers(true)
         .from_reader(file);
 
@@ -52,22 +119,31 @@ pub fn validate_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
         for (i, h) in headers.iter().enumerate() {
             eprintln!("   [{}] {}", i, h);
         }
-        std::process::exit(1);
+        return Err(data_issues(format!(
+            "Column count mismatch: expected {}, found {}", model.expected_columns, headers.len()
+        )));
     }
 
-    println!("🔍 Validating records...");
-    println!();
+    info!("🔍 Validating records...");
+    info!();
 
-    let mut total_records = 0;
+    let mut total_records = 0usize;
     let mut invalid_records = 0;
-    let mut field_errors: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let mut field_errors: HashMap<String, FieldErrors> = HashMap::new();
+    let mut report = args.report_html.as_ref().map(|_| {
+        let mut report = ValidationReport::new("DynamoDB Schema Validation", input_path, model_type);
+        report.set_headers(&headers);
+        report
+    });
 
     // ✅ NUEVO: Validar TODOS los campos numéricos (no solo PK/SK)
+    let mut progress = Progress::rows("Processed");
     for (line_idx, result) in rdr.records().enumerate() {
         let record = result?;
         total_records += 1;
 
         let mut record_has_errors = false;
+        let mut invalid_fields: Vec<&str> = Vec::new();
 
         // Validar cada campo numérico según el modelo
         for field_name in &model.numeric_fields {
@@ -76,13 +152,24 @@ pub fn validate_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                     let value = record[col_idx].trim();
 
                     // Validación estricta de campos numéricos
-                    if !is_valid_dynamodb_number(value) {
+                    let accepted = is_valid_dynamodb_number(value)
+                        || (value.is_empty() && nullable_fields.contains(field_name));
+                    if !accepted {
                         record_has_errors = true;
-                        
-                        *field_errors.entry(field_name.to_string()).or_insert(0) += 1;
+                        invalid_fields.push(field_name);
+                        if let Some(report) = report.as_mut() {
+                            report.error("INVALID_NUMERIC", Some(field_name), value);
+                        }
+
+                        let errors = field_errors.entry(field_name.to_string()).or_default();
+                        errors.count += 1;
+                        *errors.values.entry(value.to_string()).or_insert(0) += 1;
+                        error_report::emit(&ErrorRecord::new(line_idx + 2, "INVALID_NUMERIC", "Expected: Type N (numeric)")
+                            .at(record.position()).column(field_name).value(value))?;
 
-                        // Reportar error detallado (máximo 20 errores por campo)
-                        if field_errors[*field_name] <= 20 {
+                        // Reportar error detallado (hasta --max-examples-per-field por campo;
+                        // los totales siguen siendo exactos)
+                        if errors.count <= max_examples {
                             eprintln!(
                                 "   ❌ Line {}, Field [{}] {}: INVALID '{}' (expected numeric)",
                                 line_idx + 2, // +2 porque línea 1 es header
@@ -90,6 +177,12 @@ pub fn validate_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                                 field_name,
                                 value
                             );
+                        } else if errors.count == max_examples + 1 {
+                            eprintln!(
+                                "   ⚠️  Field {}: more than {} errors, further examples suppressed (see summary)",
+                                field_name,
+                                max_examples
+                            );
                         }
                     }
                 }
@@ -98,54 +191,720 @@ pub fn validate_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
 
         if record_has_errors {
             invalid_records += 1;
+            if let Some(report) = report.as_mut() {
+                report.sample(line_idx + 2, &format!("Not Type N: {}", invalid_fields.join(", ")), &record);
+            }
         }
 
-        // Progreso cada 1000 registros
-        if total_records % 1000 == 0 {
-            print!("\r📊 Processed: {} | Invalid: {}", total_records, invalid_records);
-            std::io::Write::flush(&mut std::io::stdout())?;
-        }
+        progress.update(total_records as u64, || format!("Invalid: {}", invalid_records));
     }
+    progress.finish();
 
-    println!();
-    println!("\r📊 Processed: {} | Invalid: {}", total_records, invalid_records);
-    println!();
+    info!();
+    info!("📊 Processed: {} | Invalid: {}", total_records, invalid_records);
+    info!();
 
     // ✅ NUEVO: Resumen detallado por campo
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  Validation Summary                                          ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Total records validated: {}", total_records);
-    println!("✅ Valid records: {}", total_records - invalid_records);
-    println!("❌ Invalid records: {}", invalid_records);
-    println!();
+    logging::banner("Validation Summary");
+    info!("📊 Total records validated: {}", total_records);
+    info!("✅ Valid records: {}", total_records - invalid_records);
+    info!("❌ Invalid records: {}", invalid_records);
+    info!();
+
+    let mut sorted_errors: Vec<_> = field_errors.iter().collect();
+    sorted_errors.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+    logging::summary(&serde_json::json!({
+        "command": "validate_schema",
+        "input": input_path,
+        "model": model_type,
+        "records": total_records,
+        "valid_records": total_records - invalid_records,
+        "invalid_records": invalid_records,
+        "error_rate": logging::rate(invalid_records, total_records),
+        "errors_by_field": sorted_errors.iter().map(|(field, errors)| serde_json::json!({
+            "field": field,
+            "invalid_values": errors.count,
+            "distinct_values": errors.values.len(),
+            "top_values": errors.top_values(TOP_OFFENDING_VALUES).iter()
+                .map(|(value, count)| serde_json::json!({ "value": value, "count": count }))
+                .collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    }))?;
+
+    if let (Some(mut report), Some(path)) = (report, args.report_html.as_deref()) {
+        report.set_totals(total_records, invalid_records);
+        report.write(path)?;
+        info!("📄 HTML report: {}", path);
+        info!();
+    }
 
     if !field_errors.is_empty() {
-        println!("📋 Errors by field (Type N validation failed):");
-        let mut sorted_errors: Vec<_> = field_errors.iter().collect();
-        sorted_errors.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
-        
-        for (field_name, count) in sorted_errors {
-            println!("   ❌ {}: {} invalid value(s)", field_name, count);
+        info!("📋 Errors by field (Type N validation failed):");
+        for (field_name, errors) in sorted_errors {
+            info!("   ❌ {}: {} invalid value(s), {} distinct", field_name, errors.count, errors.values.len());
+            for (value, count) in errors.top_values(TOP_OFFENDING_VALUES) {
+                info!("      {:>9} × '{}'", count, value);
+            }
         }
-        println!();
+        info!();
 
-        println!("💡 To fix these issues:");
-        println!("   csv_tools sanitize_dynamodb \"{}\" \"output.csv\" {}", input_path, model_type);
-        println!();
+        info!("💡 To fix these issues:");
+        info!("   csv_tools sanitize_dynamodb \"{}\" \"output.csv\" {}", input_path, model_type);
+        info!();
         
-        std::process::exit(1);
+        return Err(data_issues(format!("{} invalid record(s) for model {}", invalid_records, model_type)));
+    } else {
+        info!("✅ All records valid for DynamoDB import");
+        info!("   All Type N fields contain valid numeric values");
+    }
+
+    Ok(())
+}
+
+/// Muestra las columnas del header (índice, nombre, tipo inferido de una muestra)
+/// sin recorrer el cuerpo completo; acepta entradas .gz
+#[derive(Args, Debug)]
+pub struct HeaderArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(long, help = "Print the columns as JSON")]
+    pub json: bool,
+    #[arg(long, value_name = "ROWS", default_value_t = HEADER_SAMPLE_ROWS, help = "Rows sampled to infer column types")]
+    pub sample: usize,
+}
+
+pub fn header(args: &HeaderArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let as_json = args.json;
+    let sample_rows = args.sample;
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+
+    let headers = rdr.headers()?.clone();
+    let mut samples: Vec<Vec<String>> = vec![Vec::new(); headers.len()];
+    let mut sampled = 0usize;
+
+    for result in rdr.records().take(sample_rows) {
+        let record = result?;
+        for (i, column) in samples.iter_mut().enumerate() {
+            column.push(record.get(i).unwrap_or("").to_string());
+        }
+        sampled += 1;
+    }
+
+    let columns: Vec<(usize, &str, String, Option<&str>)> = headers.iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let column_type = infer_type(samples[i].iter().map(String::as_str)).to_string();
+            let example = samples[i].iter().map(|v| v.trim()).find(|v| !v.is_empty());
+            (i, name, column_type, example)
+        })
+        .collect();
+
+    if as_json {
+        let json = serde_json::json!({
+            "file": input_path,
+            "column_count": columns.len(),
+            "sampled_rows": sampled,
+            "columns": columns.iter().map(|(i, name, column_type, example)| serde_json::json!({
+                "index": i,
+                "name": name,
+                "type": column_type,
+                "sample": example,
+            })).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    logging::banner("CSV Header");
+    info!("📄 File: {}", input_path);
+    info!("🔢 Columns: {} (types inferred from {} rows)", columns.len(), sampled);
+    info!();
+
+    let name_width = columns.iter().map(|(_, name, _, _)| name.len()).max().unwrap_or(0);
+    for (i, name, column_type, example) in &columns {
+        info!("   [{:>2}] {:<width$}  {:<7} {}", i, name, column_type,
+            example.map(|v| format!("e.g. {}", v)).unwrap_or_default(), width = name_width);
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for (_, name, _, _) in &columns {
+        if !seen.insert(name.trim()) {
+            diagnostics::warn(format!("Duplicate column name: '{}'", name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Dialecto probable de un CSV a partir de sus primeros KB (mismo detector que `--delimiter auto`)
+#[derive(Args, Debug)]
+pub struct SniffArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(long, value_name = "KB", default_value_t = (sniff::DEFAULT_SAMPLE_BYTES / 1024) as u64,
+          value_parser = clap::value_parser!(u64).range(1..), help = "Kilobytes sampled from the start of the file")]
+    pub sample_kb: u64,
+    #[arg(long, help = "Print the detected dialect as JSON")]
+    pub json: bool,
+}
+
+pub fn sniff(args: &SniffArgs) -> Result<(), CsvToolsError> {
+    let dialect = sniff::sniff_file(&args.input, args.sample_kb as usize * 1024)?;
+
+    if args.json {
+        let mut json = serde_json::to_value(&dialect)?;
+        json["file"] = serde_json::json!(args.input);
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    let delimiter = sniff::display_delimiter(dialect.delimiter);
+    logging::banner("CSV Dialect");
+    info!("📄 File: {}", args.input);
+    info!("🔍 Sample: {} ({} rows)", format_bytes(dialect.bytes_sampled as u64), dialect.rows_sampled);
+    info!("🔣 Delimiter: '{}'", delimiter);
+    match dialect.quote {
+        Some(q) => info!("💬 Quote: {}", q),
+        None => info!("💬 Quote: none seen in the sample"),
+    }
+    info!("🏷️  Header: {}", if dialect.has_header { "yes" } else { "no" });
+    info!("🔢 Columns: {} ({:.1}% of sampled rows)", dialect.columns, dialect.consistency * 100.0);
+    info!();
+    info!("💡 Use: --delimiter '{}'", delimiter);
+
+    if dialect.consistency < sniff::CONSISTENT_ROWS {
+        diagnostics::warn(format!("Only {:.1}% of the sampled rows have {} columns: the file may be malformed or the delimiter ambiguous",
+            dialect.consistency * 100.0, dialect.columns));
+    }
+    Ok(())
+}
+
+#[derive(Args, Debug)]
+pub struct DetectEncodingArgs {
+    #[arg(value_name = "FILE")]
+    pub input: String,
+    #[arg(long, value_name = "KB", default_value_t = 64, value_parser = clap::value_parser!(u64).range(1..),
+          help = "Kilobytes sampled from the start of the file")]
+    pub sample_kb: u64,
+    #[arg(long, help = "Print the detection as JSON")]
+    pub json: bool,
+}
+
+/// Codificación probable de un archivo (UTF-8, windows-1252/Latin-1, UTF-16) a partir
+/// de su comienzo, con el valor a pasar en --input-encoding
+pub fn detect_encoding(args: &DetectEncodingArgs) -> Result<(), CsvToolsError> {
+    let mut sample = Vec::new();
+    open_input_bytes(&args.input)?.take(args.sample_kb * 1024).read_to_end(&mut sample)?;
+    let (encoding, detection) = encoding::detect(&sample);
+
+    if args.json {
+        let mut json = serde_json::to_value(&detection)?;
+        json["file"] = serde_json::json!(args.input);
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    logging::banner("Character Encoding");
+    info!("📄 File: {}", args.input);
+    info!("🔍 Sample: {}", format_bytes(detection.bytes_sampled as u64));
+    info!("🔤 Encoding: {}{}", encoding.name(), if encoding == encoding_rs::WINDOWS_1252 { " (Latin-1 compatible)" } else { "" });
+    info!("🏷️  BOM: {}", if detection.bom { "yes" } else { "no" });
+    info!("📝 Reason: {}", detection.reason);
+    info!();
+    if detection.ascii_only {
+        info!("💡 ASCII only: reads the same as UTF-8 or Latin-1");
+    } else if encoding != encoding_rs::UTF_8 {
+        info!("💡 Use: --input-encoding {}", encoding.name().to_lowercase());
+    }
+    Ok(())
+}
+
+/// Assertions baratas para gates de CI: sale con código 1 y un motivo corto
+/// por cada assertion que falla (el cuerpo solo se recorre si hace falta)
+#[derive(Args, Debug)]
+pub struct AssertArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', help = "Columns that must be present in the header")]
+    pub has_columns: Vec<String>,
+    #[arg(long, value_name = "N", value_parser = parse_count, help = "Minimum number of data rows")]
+    pub row_count_min: Option<usize>,
+    #[arg(long, value_name = "N", value_parser = parse_count, help = "Maximum number of data rows")]
+    pub row_count_max: Option<usize>,
+    #[arg(long, help = "Fail if the header row appears again in the body")]
+    pub no_duplicate_header: bool,
+}
+
+pub fn assert_csv(args: &AssertArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let required_columns: Vec<&str> = args.has_columns.iter()
+        .map(|c| c.trim())
+        .filter(|c| !c.is_empty())
+        .collect();
+    let row_count_min = args.row_count_min;
+    let row_count_max = args.row_count_max;
+    let no_duplicate_header = args.no_duplicate_header;
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers = rdr.headers()?.clone();
+
+    let mut checks = 0usize;
+    let mut failures: Vec<String> = Vec::new();
+
+    if !required_columns.is_empty() {
+        checks += 1;
+        let missing: Vec<&str> = required_columns.iter()
+            .filter(|c| !headers.iter().any(|h| h.trim() == **c))
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            failures.push(format!("missing columns: {}", missing.join(", ")));
+        }
+    }
+
+    if no_duplicate_header {
+        checks += 1;
+        let mut seen = std::collections::HashSet::new();
+        let repeated: Vec<&str> = headers.iter().filter(|h| !seen.insert(h.trim())).collect();
+        if !repeated.is_empty() {
+            failures.push(format!("duplicate column names in header: {}", repeated.join(", ")));
+        }
+    }
+
+    // Un solo recorrido del cuerpo para conteo de filas y headers repetidos
+    if row_count_min.is_some() || row_count_max.is_some() || no_duplicate_header {
+        let mut rows = 0usize;
+        let mut repeated_header_lines: Vec<u64> = Vec::new();
+        let mut record = csv::StringRecord::new();
+
+        while rdr.read_record(&mut record)? {
+            if no_duplicate_header && record == headers {
+                repeated_header_lines.push(record.position().map(|p| p.line()).unwrap_or(0));
+            } else {
+                rows += 1;
+            }
+        }
+
+        if let Some(min) = row_count_min {
+            checks += 1;
+            if rows < min {
+                failures.push(format!("row count {} < minimum {}", rows, min));
+            }
+        }
+        if let Some(max) = row_count_max {
+            checks += 1;
+            if rows > max {
+                failures.push(format!("row count {} > maximum {}", rows, max));
+            }
+        }
+        if !repeated_header_lines.is_empty() {
+            let lines: Vec<String> = repeated_header_lines.iter().take(5).map(|l| l.to_string()).collect();
+            failures.push(format!("header row repeated {} time(s) in body (lines {}{})",
+                repeated_header_lines.len(), lines.join(", "),
+                if repeated_header_lines.len() > 5 { ", ..." } else { "" }));
+        }
+    }
+
+    if checks == 0 {
+        eprintln!("⚠️  No assertions given (use --has-columns, --row-count-min, --row-count-max, --no-duplicate-header)");
+    }
+
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("❌ ASSERTION FAILED [{}]: {}", input_path, failure);
+        }
+        return Err(data_issues(format!("{} of {} assertion(s) failed", failures.len(), checks)));
+    }
+
+    info!("✅ {} assertion(s) passed: {}", checks, input_path);
+    Ok(())
+}
+
+/// Perfil de una columna acumulado en streaming
+struct ColumnProfile {
+    non_empty: u64,
+    empty: u64,
+    non_numeric: u64,
+    digest: TDigest,
+    sum: f64,
+}
+
+impl ColumnProfile {
+    fn new() -> Self {
+        ColumnProfile {
+            non_empty: 0,
+            empty: 0,
+            non_numeric: 0,
+            digest: TDigest::new(PROFILE_DIGEST_COMPRESSION),
+            sum: 0.0,
+        }
+    }
+
+    fn add(&mut self, value: &str) {
+        let v = value.trim();
+        if v.is_empty() {
+            self.empty += 1;
+            return;
+        }
+        self.non_empty += 1;
+        match v.parse::<f64>() {
+            Ok(n) if n.is_finite() => {
+                self.digest.add(n);
+                self.sum += n;
+            }
+            _ => self.non_numeric += 1,
+        }
+    }
+
+    /// Numérica si tiene al menos un número y ningún valor no numérico
+    fn is_numeric(&self) -> bool {
+        self.digest.count() > 0 && self.non_numeric == 0
+    }
+}
+
+/// Perfila las columnas en una sola pasada: conteos de vacíos y, para las numéricas,
+/// min/max/media y percentiles aproximados (t-digest, sin ordenar ni cargar el archivo)
+#[derive(Args, Debug)]
+pub struct ProfileArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', help = "Profile only these columns (default: all)")]
+    pub columns: Vec<String>,
+    #[arg(long, value_name = "P", value_delimiter = ',', value_parser = parse_percentile,
+          help = "Percentiles of numeric columns [default: 50,95,99]")]
+    pub percentiles: Vec<f64>,
+    #[arg(long, help = "Print the profile as JSON")]
+    pub json: bool,
+}
+
+pub fn profile(args: &ProfileArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let as_json = args.json;
+    let percentiles: Vec<f64> = if args.percentiles.is_empty() {
+        DEFAULT_PERCENTILES.to_vec()
     } else {
-        println!("✅ All records valid for DynamoDB import");
-        println!("   All Type N fields contain valid numeric values");
+        args.percentiles.clone()
+    };
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers = rdr.headers()?.clone();
+
+    let selected: Vec<usize> = if args.columns.is_empty() {
+        (0..headers.len()).collect()
+    } else {
+        args.columns.iter()
+            .map(|c| c.trim())
+            .filter(|c| !c.is_empty())
+            .map(|c| headers.iter().position(|h| h.trim() == c)
+                .ok_or_else(|| usage_error(format!("Column '{}' not found in header", c))))
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut profiles: Vec<ColumnProfile> = selected.iter().map(|_| ColumnProfile::new()).collect();
+    let mut rows = 0u64;
+    let mut record = csv::StringRecord::new();
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        rows += 1;
+        for (profile, idx) in profiles.iter_mut().zip(&selected) {
+            profile.add(record.get(*idx).unwrap_or(""));
+        }
+        progress.update(rows, String::new);
+    }
+    progress.finish();
+
+    let label = |p: f64| format!("p{}", p);
+
+    if as_json {
+        let columns: Vec<serde_json::Value> = selected.iter().zip(profiles.iter_mut())
+            .map(|(idx, profile)| {
+                let mut column = serde_json::json!({
+                    "name": headers.get(*idx).unwrap_or(""),
+                    "non_empty": profile.non_empty,
+                    "empty": profile.empty,
+                    "non_numeric": profile.non_numeric,
+                    "numeric": profile.is_numeric(),
+                });
+                if profile.digest.count() > 0 {
+                    column["min"] = serde_json::json!(profile.digest.min());
+                    column["max"] = serde_json::json!(profile.digest.max());
+                    column["mean"] = serde_json::json!(profile.sum / profile.digest.count() as f64);
+                    for p in &percentiles {
+                        column[label(*p)] = serde_json::json!(profile.digest.quantile(p / 100.0));
+                    }
+                }
+                column
+            })
+            .collect();
+        let json = serde_json::json!({
+            "file": input_path,
+            "rows": rows,
+            "columns": columns,
+        });
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    logging::banner("CSV Profile");
+    info!("📄 File: {}", input_path);
+    info!("📊 Rows: {}", rows);
+    info!("📈 Percentiles: {} (approximate, t-digest)", percentiles.iter().map(|p| label(*p)).collect::<Vec<_>>().join(", "));
+    info!();
+
+    let name_width = selected.iter().map(|i| headers.get(*i).unwrap_or("").len()).max().unwrap_or(0);
+    for (idx, profile) in selected.iter().zip(profiles.iter_mut()) {
+        let name = headers.get(*idx).unwrap_or("");
+        info!("   [{:>2}] {:<width$}  non-empty: {}  empty: {}", idx, name, profile.non_empty, profile.empty, width = name_width);
+
+        if profile.digest.count() == 0 {
+            continue;
+        }
+        if !profile.is_numeric() {
+            // Columna mixta: se informan igual los percentiles de la parte numérica
+            if profile.non_numeric >= profile.digest.count() {
+                continue;
+            }
+            info!("        ⚠️  {} non-numeric values ignored", profile.non_numeric);
+        }
+
+        let mut stats = vec![
+            format!("min {}", format_stat(profile.digest.min())),
+            format!("mean {}", format_stat(Some(profile.sum / profile.digest.count() as f64))),
+        ];
+        for p in &percentiles {
+            stats.push(format!("{} {}", label(*p), format_stat(profile.digest.quantile(p / 100.0))));
+        }
+        stats.push(format!("max {}", format_stat(profile.digest.max())));
+        info!("        {}", stats.join("  "));
+    }
+
+    Ok(())
+}
+
+/// Valor numérico de `profile` sin decimales superfluos
+fn format_stat(value: Option<f64>) -> String {
+    match value {
+        Some(v) if v.fract() == 0.0 && v.abs() < 1e15 => format!("{}", v as i64),
+        Some(v) if v.abs() >= 1.0 => format!("{:.2}", v),
+        Some(v) => format!("{:.4}", v),
+        None => "-".to_string(),
+    }
+}
+
+/// Diferencias entre la entrada y su versión reescrita (comparación registro a registro)
+#[derive(Debug, Default)]
+struct RoundtripDiff {
+    records_compared: usize,
+    differing_records: usize,
+    // columna → cantidad de registros con ese campo distinto
+    column_diffs: Vec<usize>,
+    // (línea, registro original, registro reescrito)
+    first_difference: Option<(u64, String, String)>,
+    extra_records: (usize, usize),
+}
+
+/// Verifica (sin modificar la entrada) que pasar el archivo por una transformación
+/// sin cambios de reglas lo deja idéntico byte a byte; si no, indica dónde difiere
+#[derive(Args, Debug)]
+pub struct VerifyRoundtripArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(long, default_value = "requote", value_parser = ["requote", "sanitize"],
+          help = "Transformation to run without rule changes")]
+    pub via: String,
+    #[arg(long, value_name = "MODEL_TYPE", help = "Model for --via sanitize [default: default_model from the config file]")]
+    pub model: Option<String>,
+}
+
+pub fn verify_roundtrip(args: &VerifyRoundtripArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let via = args.via.as_str();
+    let temp_path = std::env::temp_dir().join(format!("csv_tools_roundtrip_{}.csv", std::process::id()));
+    let temp = temp_path.to_string_lossy().to_string();
+
+    logging::banner("Round-Trip Verification");
+    info!("📄 Input: {}", input_path);
+    info!("🔁 Via:   {}", via);
+    info!();
+
+    let transformed = match via {
+        "requote" => requote_copy(input_path, &temp),
+        "sanitize" => {
+            let model_type = config::model_or_default(args.model.as_deref())?;
+            crate::commands::cleaning::sanitize_dynamodb(input_path, &temp, &model_type, None, None)
+        }
+        other => Err(usage_error(format!("Unknown --via '{}' (use requote, sanitize)", other))),
+    };
+    if let Err(e) = transformed {
+        std::fs::remove_file(&temp_path).ok();
+        return Err(e);
+    }
+
+    let compared = first_differing_byte(input_path, &temp).and_then(|first_byte| match first_byte {
+        Some(_) => Ok((first_byte, compare_records(input_path, &temp)?)),
+        None => Ok((None, RoundtripDiff::default())),
+    });
+    std::fs::remove_file(&temp_path).ok();
+    let (first_byte, diff) = compared?;
+
+    logging::banner("Round-Trip Result");
+
+    let Some(offset) = first_byte else {
+        info!("✅ Byte-identical: '{}' does not rewrite {}", via, input_path);
+        return Ok(());
+    };
+
+    info!("❌ Output differs from input (first differing byte at offset {})", offset);
+    info!("📊 Records compared:  {}", diff.records_compared);
+    info!("📊 Records differing: {}", diff.differing_records);
+    if diff.extra_records != (0, 0) {
+        info!("📊 Records only in input: {} | only in output: {}", diff.extra_records.0, diff.extra_records.1);
+    }
+
+    if diff.differing_records == 0 && diff.extra_records == (0, 0) {
+        info!("💡 All field values are equal: the difference is formatting only (quoting, line endings, trailing newline)");
+    }
+
+    if let Some((line, original, rewritten)) = &diff.first_difference {
+        info!();
+        info!("🔍 First differing record (line {}):", line);
+        info!("   input:  {}", original);
+        info!("   output: {}", rewritten);
     }
 
+    if diff.column_diffs.iter().any(|c| *c > 0) {
+        let names = ReaderBuilder::new()
+            .delimiter(config::delimiter())
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(open_input(input_path)?)
+            .records()
+            .next()
+            .transpose()?
+            .unwrap_or_default();
+
+        info!();
+        info!("📋 Differences by column:");
+        for (i, count) in diff.column_diffs.iter().enumerate().filter(|(_, c)| **c > 0) {
+            info!("   ❌ [{}] {}: {} record(s)", i, names.get(i).unwrap_or("?"), count);
+        }
+    }
+
+    Err(data_issues("Round-trip output differs from the input"))
+}
+
+/// Reescritura canónica de las herramientas: lee y vuelve a escribir cada registro
+/// con las comillas mínimas necesarias
+fn requote_copy(input_path: &str, output_path: &str) -> Result<(), CsvToolsError> {
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(config::delimiter())
+        .flexible(true)
+        .quote_style(csv::QuoteStyle::Necessary)
+        .from_path(output_path)?;
+
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        wtr.write_byte_record(&record)?;
+    }
+    wtr.flush()?;
     Ok(())
 }
 
+/// Offset del primer byte distinto (None si los archivos son idénticos)
+fn first_differing_byte(a: &str, b: &str) -> Result<Option<u64>, CsvToolsError> {
+    use std::io::BufRead;
+
+    let (mut ra, mut rb) = (open_input(a)?, open_input(b)?);
+    let mut offset = 0u64;
+
+    loop {
+        let (ba, bb) = (ra.fill_buf()?, rb.fill_buf()?);
+        if ba.is_empty() || bb.is_empty() {
+            return Ok(if ba.len() == bb.len() { None } else { Some(offset) });
+        }
+
+        let n = ba.len().min(bb.len());
+        if let Some(i) = ba[..n].iter().zip(&bb[..n]).position(|(x, y)| x != y) {
+            return Ok(Some(offset + i as u64));
+        }
+
+        ra.consume(n);
+        rb.consume(n);
+        offset += n as u64;
+    }
+}
+
+/// Compara campo a campo (el header cuenta como un registro más)
+fn compare_records(a: &str, b: &str) -> Result<RoundtripDiff, CsvToolsError> {
+    let reader = |path: &str| -> Result<_, CsvToolsError> {
+        Ok(ReaderBuilder::new().delimiter(config::delimiter()).has_headers(false).flexible(true).from_reader(open_input(path)?))
+    };
+    let (mut ra, mut rb) = (reader(a)?, reader(b)?);
+    let (mut rec_a, mut rec_b) = (csv::ByteRecord::new(), csv::ByteRecord::new());
+    let mut diff = RoundtripDiff::default();
+
+    loop {
+        let (has_a, has_b) = (ra.read_byte_record(&mut rec_a)?, rb.read_byte_record(&mut rec_b)?);
+        match (has_a, has_b) {
+            (false, false) => break,
+            (true, false) => diff.extra_records.0 += 1,
+            (false, true) => diff.extra_records.1 += 1,
+            (true, true) => {
+                diff.records_compared += 1;
+                let width = rec_a.len().max(rec_b.len());
+                if diff.column_diffs.len() < width {
+                    diff.column_diffs.resize(width, 0);
+                }
+
+                let mut differs = false;
+                for i in 0..width {
+                    if rec_a.get(i) != rec_b.get(i) {
+                        diff.column_diffs[i] += 1;
+                        differs = true;
+                    }
+                }
+
+                if differs {
+                    diff.differing_records += 1;
+                    if diff.first_difference.is_none() {
+                        let line = rec_a.position().map(|p| p.line()).unwrap_or(0);
+                        let show = |r: &csv::ByteRecord| r.iter()
+                            .map(|f| String::from_utf8_lossy(f).into_owned())
+                            .collect::<Vec<_>>()
+                            .join(" | ");
+                        diff.first_difference = Some((line, show(&rec_a), show(&rec_b)));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(diff)
+}
+
 /// ✅ NUEVO: Validación estricta de números para DynamoDB Type N
 /// Mismas reglas que DynamoDB ImportTable
-fn is_valid_dynamodb_number(value: &str) -> bool {
+pub fn is_valid_dynamodb_number(value: &str) -> bool {
     if value.is_empty() {
         return false;
     }
@@ -164,23 +923,18 @@ fn is_valid_dynamodb_number(value: &str) -> bool {
     let mut has_decimal_point = false;
     let mut has_e = false;
 
+    let mut prev = None;
+
     for (i, c) in value.chars().enumerate() {
         match c {
-            '0'..='9' => continue,
-            '-' | '+' if i == 0 => continue, // Signo solo al inicio
-            '.' if !has_decimal_point => {
-                has_decimal_point = true;
-                continue;
-            }
-            'e' | 'E' if !has_e => {
-                has_e = true;
-                continue;
-            }
-            '-' | '+' if has_e && value.chars().nth(i - 1) == Some('e') || value.chars().nth(i - 1) == Some('E') => {
-                continue; // Signo después de 'e' en notación científica
-            }
+            '0'..='9' => {}
+            '-' if i == 0 => {} // Signo solo al inicio (DynamoDB rechaza '+')
+            '.' if !has_decimal_point && !has_e => has_decimal_point = true,
+            'e' | 'E' if !has_e && i > 0 => has_e = true,
+            '-' | '+' if matches!(prev, Some('e') | Some('E')) => {} // Signo del exponente
             _ => return false, // Cualquier otro carácter es inválido
         }
+        prev = Some(c);
     }
 
     // No puede ser solo '-', '+', '.' o 'e'
@@ -216,5 +970,10 @@ mod tests {
         assert!(!is_valid_dynamodb_number("--123"));     // Doble signo
         assert!(!is_valid_dynamodb_number("."));         // Solo punto
         assert!(!is_valid_dynamodb_number("-"));         // Solo signo
+        assert!(!is_valid_dynamodb_number("+1e5"));      // Signo positivo también con exponente
+        assert!(!is_valid_dynamodb_number("1e5.5"));     // Punto en el exponente
+        assert!(!is_valid_dynamodb_number("e5"));        // Exponente sin mantisa
+        assert!(!is_valid_dynamodb_number("1e"));        // Exponente sin dígitos
+        assert!(is_valid_dynamodb_number("-1.5E+3"));    // Signo del exponente
     }
 }
\ No newline at end of file
@@ -0,0 +1,824 @@
+// Comandos sobre varios archivos de entrada (lista, directorio o glob): conteos, merge
+// con deduplicación en memoria, por shards o con sort externo y estimación de memoria
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::collation;
+use crate::commands::file_ops::SourceColumns;
+use crate::compression;
+use crate::dedup::{self, ApproxMode, SeenLines};
+use crate::diagnostics;
+use crate::disk_guard::{Checkpoint, DiskSpaceGuard};
+use crate::error::CsvToolsError;
+use crate::exit_code;
+use crate::external_sort;
+use crate::file_utils;
+use crate::info;
+use crate::logging;
+use crate::merge_report::{HeaderCheck, MergeReport};
+use crate::progress::ProgressTracker;
+use crate::repro;
+use crate::runtime_limit;
+use crate::sharded_dedup::ShardedDedup;
+use crate::spill_dedup::SpillingDedup;
+use crate::temp_files::TempFiles;
+
+pub fn count_all_files(file_list_path: &str) -> Result<(), CsvToolsError> {
+    // Obtener lista de archivos para estimación
+    let file_names = file_utils::resolve_inputs(file_list_path)?;
+    
+    info!("📊 Estimando total de líneas para progress...");
+    let file_lines = file_utils::estimate_lines_per_file(&file_names)?;
+    let estimated_total: u64 = file_lines.iter().sum();
+    info!("Estimación: ~{} líneas totales en {} archivos", estimated_total, file_names.len());
+    
+    let mut progress = ProgressTracker::new(estimated_total);
+    let mut total = 0;
+    let mut processed_lines = 0;
+
+    for (file_idx, filename) in file_names.iter().enumerate() {
+        progress.start_file(filename, file_idx + 1, file_names.len(), Some(file_lines[file_idx]));
+        total += count_lines_with_progress(filename, &mut progress, &mut processed_lines)?;
+        progress.finish_file("");
+    }
+
+    progress.finish();
+    info!("📈 Total de líneas en todos los archivos: {}", total);
+    Ok(())
+}
+
+/// Qué se guarda en memoria de cada línea ya escrita en merge_dedup
+#[derive(Debug, Clone, Copy)]
+pub enum DedupMode {
+    /// La línea completa
+    Exact,
+    /// Huella de 128 bits (--hash-only), con pasada opcional que busca colisiones
+    HashOnly { verify: bool },
+    /// Aproximado en memoria constante (--approx bloom --fpp P)
+    Approx(ApproxMode, f64),
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn merge_and_deduplicate(
+    file_list_path: &str,
+    output_file: &str,
+    compress_threads: usize,
+    mode: DedupMode,
+    max_memory: Option<usize>,
+    source: &SourceColumns,
+    resume: bool,
+    mut report: MergeReport,
+) -> Result<(), CsvToolsError> {
+    if let DedupMode::Approx(_, fpp) = mode {
+        if !(fpp > 0.0 && fpp < 1.0) {
+            return Err(exit_code::usage_error(format!("--fpp must be between 0 and 1 (got {})", fpp)));
+        }
+    }
+
+    let file_names = file_utils::resolve_inputs(file_list_path)?;
+
+    info!("🔄 Estimando total de líneas para merge...");
+    let file_lines = file_utils::estimate_lines_per_file(&file_names)?;
+    let estimated_total: u64 = file_lines.iter().sum();
+    info!("Estimación: ~{} líneas totales en {} archivos", estimated_total, file_names.len());
+    
+    let mut progress = ProgressTracker::new(estimated_total);
+    let mut processed_lines = 0;
+
+    let total_input_bytes = file_utils::total_file_size(&file_names)?;
+    // Con --max-memory lo que no entra se copia a buckets antes de llegar a la salida
+    let mut disk_guard = DiskSpaceGuard::new(output_file, total_input_bytes, if max_memory.is_some() { 2.0 } else { 1.0 });
+    disk_guard.preflight()?;
+
+    match compression::compression_for(output_file) {
+        Some(compression::OutputCompression::Gzip) => {
+            info!("🗜️  Gzip output ({} compression thread{})", compress_threads, if compress_threads > 1 { "s" } else { "" });
+        }
+        Some(compression::OutputCompression::Zstd) => info!("🗜️  Zstd output"),
+        None => {}
+    }
+
+    let collation = collation::current();
+    if collation != collation::Collation::Binary {
+        info!("🔤 Collation: {} (lines equal under this collation are duplicates)", collation);
+    }
+
+    // --approx bloom: memoria constante dimensionada con la estimación de líneas
+    let mut seen_lines = match mode {
+        DedupMode::Approx(ApproxMode::Bloom, fpp) => {
+            let seen = SeenLines::bloom(estimated_total, fpp, repro::seed());
+            if let SeenLines::Bloom { filter, .. } = &seen {
+                info!("🌸 Approximate dedup: Bloom filter of {} ({} hashes) for ~{} lines, target fpp {}",
+                    file_utils::format_bytes(filter.memory_bytes() as u64), filter.num_hashes(), estimated_total, fpp);
+            }
+            seen
+        }
+        DedupMode::HashOnly { .. } => {
+            info!("#️⃣  Hash-only dedup: 128-bit xxh3 digest per line instead of the full line");
+            SeenLines::hashed()
+        }
+        DedupMode::Exact => SeenLines::exact(),
+    };
+    let mut header_written = false;
+    // Header contra el que se compara el de cada archivo en el reporte (el primero escrito)
+    let mut expected_header: Option<String> = None;
+
+    // --resume: las líneas ya escritas se recuperan de la salida parcial y se sigue a continuación
+    let (start_file, skip_lines) = if resume {
+        let checkpoint = Checkpoint::load(output_file, "merge_dedup")?;
+        let position = checkpoint.resume_position(&file_names)?;
+        info!("⏯️  Resuming: {} files completed, {} lines of {}", position.0, position.1, checkpoint.current_file);
+        report.resumed();
+
+        for (i, line) in file_utils::open_input(&compression::output_path(output_file))?.lines().enumerate() {
+            let line = line?;
+            if i == 0 {
+                header_written = true;
+                expected_header = Some(line);
+            } else {
+                seen_lines.insert(&collation.dedup_key(&line));
+            }
+        }
+        info!("   {} unique records already in {}", seen_lines.unique(), output_file);
+        position
+    } else {
+        (0, 0)
+    };
+    let unique_at_start = seen_lines.unique();
+    let mut processed_rows = 0;
+
+    // --max-memory: al llegar al límite el resto de la entrada se deduplica por buckets en disco
+    if let Some(max_memory) = max_memory {
+        info!("🧠 Memory cap: {} (past it, new lines are spilled to on-disk buckets)", file_utils::format_bytes(max_memory as u64));
+    }
+    let mut temp_files = TempFiles::for_output(output_file);
+    let mut seen_lines = SpillingDedup::new(seen_lines, max_memory.unwrap_or(usize::MAX), collation);
+    // Posición del derrame: la salida solo tiene lo escrito hasta ahí, un checkpoint retoma desde ese punto
+    let mut spill_start: Option<(usize, usize)> = None;
+
+    let mut writer = if resume {
+        compression::append_output(output_file, compress_threads)?
+    } else {
+        compression::create_output(output_file, compress_threads)?
+    };
+
+    for (file_idx, filename) in file_names.iter().enumerate() {
+        if file_idx < start_file {
+            continue;
+        }
+        let skip = if file_idx == start_file { skip_lines } else { 0 };
+        let file_reader = file_utils::open_input(filename)?;
+        let file_value = source.file_value(filename);
+        let unique_before = seen_lines.seen().unique();
+        progress.start_file(filename, file_idx + 1, file_names.len(), Some(file_lines[file_idx]));
+        report.start_file();
+        let mut header_check = HeaderCheck::Match;
+        let mut file_duplicates = 0;
+        let mut file_rows = 0;
+        let spilled_at_start = seen_lines.spilled();
+
+        for (i, file_line) in file_utils::record_lines(file_reader) {
+            let line_content = match file_line {
+                Ok(line) => line,
+                Err(e) => {
+                    writer.flush()?;
+                    let (file_idx, line) = spill_start.unwrap_or((file_idx, i + 1));
+                    return Err(disk_guard.abort_on_read_error(Checkpoint {
+                        command: "merge_dedup".to_string(),
+                        files_completed: file_names[..file_idx].to_vec(),
+                        current_file: file_names[file_idx].clone(),
+                        current_line: line,
+                        lines_processed: line.saturating_sub(1),
+                        ..Default::default()
+                    }, e));
+                }
+            };
+            if i == 0 {
+                match &expected_header {
+                    Some(expected) if *expected != line_content => header_check = HeaderCheck::Differs,
+                    Some(_) => {}
+                    None => expected_header = Some(line_content.clone()),
+                }
+            }
+            if i < skip {
+                continue;
+            }
+            processed_lines += 1;
+            let mut written = 0u64;
+            
+            if i == 0 {
+                if !header_written {
+                    let header_suffix = source.header_suffix();
+                    writer.write_all(line_content.as_bytes())?;
+                    writer.write_all(header_suffix.as_bytes())?;
+                    writer.write_all(b"\n")?;
+                    header_written = true;
+                    written = (line_content.len() + header_suffix.len()) as u64 + 1;
+                }
+            } else {
+                // La procedencia no participa de la comparación: queda la de la primera aparición
+                let suffix = if source.is_empty() { String::new() } else { source.row_suffix(&file_value, i + 1) };
+                let spilled_before = seen_lines.spilled_lines();
+                file_rows += 1;
+                if seen_lines.insert(&line_content, &suffix, &mut temp_files)? {
+                    writer.write_all(line_content.as_bytes())?;
+                    writer.write_all(suffix.as_bytes())?;
+                    writer.write_all(b"\n")?;
+                    written = (line_content.len() + suffix.len()) as u64 + 1;
+                } else if seen_lines.spilled_lines() > spilled_before {
+                    written = (line_content.len() + suffix.len()) as u64 + 1;
+                } else {
+                    file_duplicates += 1;
+                }
+                if spill_start.is_none() && seen_lines.spilled() {
+                    spill_start = Some((file_idx, i + 1));
+                    info!("🪣 Memory cap reached after {} unique lines: new lines now go to on-disk buckets", seen_lines.seen().unique());
+                }
+            }
+
+            if let Some(reason) = disk_guard.record(line_content.len() as u64 + 1, written)? {
+                writer.flush()?;
+                let (file_idx, line) = spill_start.unwrap_or((file_idx, i + 1));
+                return Err(disk_guard.abort(Checkpoint {
+                    command: "merge_dedup".to_string(),
+                    files_completed: file_names[..file_idx].to_vec(),
+                    current_file: file_names[file_idx].clone(),
+                    current_line: line,
+                    lines_processed: line,
+                    ..Default::default()
+                }, reason));
+            }
+            
+            // Actualizar progreso cada 1000 líneas (y cortar si venció --max-runtime)
+            if processed_lines % 1000 == 0 {
+                progress.update(processed_lines);
+
+                if runtime_limit::exceeded() {
+                    // Cerrar la salida deja un archivo válido (en .gz termina el miembro gzip)
+                    writer.flush()?;
+                    drop(writer);
+                    let (file_idx, line) = spill_start.unwrap_or((file_idx, i + 1));
+                    return Err(disk_guard.stop_at_deadline(Checkpoint {
+                        command: "merge_dedup".to_string(),
+                        files_completed: file_names[..file_idx].to_vec(),
+                        current_file: file_names[file_idx].clone(),
+                        current_line: line,
+                        lines_processed: line,
+                        ..Default::default()
+                    }));
+                }
+            }
+        }
+
+        progress.update(processed_lines);
+        progress.finish_file(&format!(", {} new unique", seen_lines.seen().unique() - unique_before));
+        // Con derrame a disco los duplicados se resuelven al final, por bucket
+        let duplicates = if seen_lines.spilled() || spilled_at_start { None } else { Some(file_duplicates) };
+        report.file(filename, header_check, file_rows, duplicates);
+        processed_rows += file_rows;
+    }
+    progress.finish();
+
+    let bloom_stats = match seen_lines.seen() {
+        SeenLines::Bloom { filter, estimated_false_drops, .. } => Some((filter.false_positive_rate(), *estimated_false_drops)),
+        _ => None,
+    };
+    let mut unique = seen_lines.seen().unique();
+    if seen_lines.spilled() {
+        info!("🪣 Deduplicating {} spilled lines bucket by bucket...", seen_lines.spilled_lines());
+    }
+    unique += seen_lines.finish(&mut writer, &mut temp_files)?;
+
+    writer.flush()?;
+    drop(writer);
+    // Un checkpoint previo ya no aplica: la salida está completa
+    Checkpoint::remove(output_file);
+    info!("🔄 Merge completado, {} registros únicos guardados en {}", unique, output_file);
+    if let DedupMode::HashOnly { verify: true } = mode {
+        verify_digest_collisions(&file_names, output_file, collation)?;
+    }
+    if let Some((rate, false_drops)) = bloom_stats {
+        info!("🌸 Bloom filter: final false-positive rate {:.6}; ~{:.0} unique lines estimated dropped as false duplicates",
+            rate, false_drops);
+    }
+    let written = unique - unique_at_start;
+    report.finish(written, processed_rows - written)
+}
+
+/// Filas de un archivo en merge_dedup --preview
+#[derive(Debug, Default, serde::Serialize)]
+struct PreviewFileStats {
+    file: String,
+    rows: usize,
+    unique: usize,
+    duplicates_within_file: usize,
+    duplicates_of_earlier_files: usize,
+    /// Archivo anterior con el que comparte más filas y cuántas
+    most_overlapping_file: Option<(String, usize)>,
+}
+
+/// merge_dedup --preview: hace la pasada completa sin escribir nada e informa por archivo
+/// cuántas filas se descartarían por repetidas dentro del mismo archivo o por estar en uno
+/// anterior (para encontrar el chunk exportado dos veces). Guarda la huella de 128 bits de
+/// cada línea y el archivo donde apareció primero
+pub fn merge_dedup_preview(file_list_path: &str) -> Result<(), CsvToolsError> {
+    let file_names = file_utils::resolve_inputs(file_list_path)?;
+    let collation = collation::current();
+
+    logging::banner("Merge + Dedup Preview (nothing is written)");
+    info!("📂 Files: {}", file_names.len());
+    if collation != collation::Collation::Binary {
+        info!("🔤 Collation: {} (lines equal under this collation are duplicates)", collation);
+    }
+    info!();
+
+    let file_lines = file_utils::estimate_lines_per_file(&file_names)?;
+    let mut first_file: HashMap<u128, u32> = HashMap::new();
+    let mut stats: Vec<PreviewFileStats> = Vec::with_capacity(file_names.len());
+    let mut progress = ProgressTracker::new(file_lines.iter().sum());
+    let mut processed = 0;
+
+    for (file_idx, filename) in file_names.iter().enumerate() {
+        progress.start_file(filename, file_idx + 1, file_names.len(), Some(file_lines[file_idx]));
+        let mut file_stats = PreviewFileStats { file: filename.clone(), ..Default::default() };
+        // Filas repetidas de cada archivo anterior
+        let mut overlaps: HashMap<u32, usize> = HashMap::new();
+
+        for (_, line) in file_utils::record_lines(file_utils::open_input(filename)?).skip(1) {
+            let line = line?;
+            file_stats.rows += 1;
+            let digest = dedup::line_digest(&collation.dedup_key(&line));
+            match first_file.entry(digest) {
+                std::collections::hash_map::Entry::Vacant(entry) => {
+                    entry.insert(file_idx as u32);
+                    file_stats.unique += 1;
+                }
+                std::collections::hash_map::Entry::Occupied(entry) if *entry.get() == file_idx as u32 => {
+                    file_stats.duplicates_within_file += 1;
+                }
+                std::collections::hash_map::Entry::Occupied(entry) => {
+                    file_stats.duplicates_of_earlier_files += 1;
+                    *overlaps.entry(*entry.get()).or_insert(0) += 1;
+                }
+            }
+            processed += 1;
+            if processed % 1000 == 0 {
+                progress.update(processed);
+            }
+        }
+        progress.update(processed);
+        progress.finish_file(&format!(", {} already in earlier files", file_stats.duplicates_of_earlier_files));
+
+        file_stats.most_overlapping_file = overlaps.into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+            .map(|(idx, rows)| (file_names[idx as usize].clone(), rows));
+        stats.push(file_stats);
+    }
+    progress.finish();
+
+    info!();
+    logging::banner("Per-file Duplicates");
+    for s in &stats {
+        let share = if s.rows == 0 { 0.0 } else { s.duplicates_of_earlier_files as f64 * 100.0 / s.rows as f64 };
+        info!("  {}: {} rows, {} new, {} repeated within the file, {} already in earlier files ({:.1}%)",
+            s.file, s.rows, s.unique, s.duplicates_within_file, s.duplicates_of_earlier_files, share);
+        if let Some((file, rows)) = &s.most_overlapping_file {
+            info!("      mostly from {} ({} rows)", file, rows);
+        }
+    }
+    // Un chunk exportado dos veces aparece casi entero como repetido de uno anterior
+    for s in stats.iter().filter(|s| s.rows > 0 && s.duplicates_of_earlier_files * 10 >= s.rows * 9) {
+        diagnostics::warn(format!("{}: {:.1}% of its rows are already in earlier files (double export?)",
+            s.file, s.duplicates_of_earlier_files as f64 * 100.0 / s.rows as f64));
+    }
+
+    let unique: usize = stats.iter().map(|s| s.unique).sum();
+    info!();
+    info!("📊 Rows: {}", processed);
+    info!("✅ Would write: {} unique rows", unique);
+    info!("🗑️  Would drop: {} duplicates", processed as usize - unique);
+    logging::summary(&serde_json::json!({
+        "command": "merge_dedup",
+        "preview": true,
+        "records": processed,
+        "unique": unique,
+        "files": stats,
+    }))?;
+    Ok(())
+}
+
+/// Pasada de --verify-hashes: falla si alguna línea se descartó por colisión de huellas
+fn verify_digest_collisions(file_names: &[String], output_file: &str, collation: collation::Collation) -> Result<(), CsvToolsError> {
+    info!("🔍 Verification pass: looking for digest collisions...");
+    let collisions = dedup::find_digest_collisions(file_names, &compression::output_path(output_file), collation)?;
+    if !collisions.is_empty() {
+        for collision in collisions.iter().take(10) {
+            eprintln!("   ❌ {} line {}: dropped by a digest collision with a different line", collision.file, collision.line);
+        }
+        return Err(exit_code::data_issues(format!("{} line(s) dropped by 128-bit digest collisions (rerun without --hash-only)", collisions.len())));
+    }
+    info!("✅ No digest collisions");
+    Ok(())
+}
+
+/// merge_dedup --threads N: un lector reparte las líneas por hash entre N workers, cada
+/// uno con su propio conjunto de vistas (ver `sharded_dedup`). La salida queda agrupada
+/// por shard; sin checkpoints (--resume) ni límite de memoria
+pub fn merge_and_deduplicate_sharded(
+    file_list_path: &str,
+    output_file: &str,
+    compress_threads: usize,
+    threads: usize,
+    mode: DedupMode,
+    source: &SourceColumns,
+    mut report: MergeReport,
+) -> Result<(), CsvToolsError> {
+    let file_names = file_utils::resolve_inputs(file_list_path)?;
+
+    info!("🔄 Estimando total de líneas para merge...");
+    let file_lines = file_utils::estimate_lines_per_file(&file_names)?;
+    let estimated_total: u64 = file_lines.iter().sum();
+    info!("Estimación: ~{} líneas totales en {} archivos", estimated_total, file_names.len());
+
+    // Los shards se escriben en temporales y después se copian a la salida
+    let mut disk_guard = DiskSpaceGuard::new(output_file, file_utils::total_file_size(&file_names)?, 2.0);
+    disk_guard.preflight()?;
+
+    let collation = collation::current();
+    if collation != collation::Collation::Binary {
+        info!("🔤 Collation: {} (lines equal under this collation are duplicates)", collation);
+    }
+    let hash_only = matches!(mode, DedupMode::HashOnly { .. });
+    info!("🧵 Sharded dedup: {} worker threads{}", threads, if hash_only { ", 128-bit digest per line" } else { "" });
+
+    let mut temp_files = TempFiles::for_output(output_file);
+    let mut shards = ShardedDedup::new(threads, hash_only, collation, &mut temp_files)?;
+    let mut writer = compression::create_output(output_file, compress_threads)?;
+    let mut header_written = false;
+    let mut progress = ProgressTracker::new(estimated_total);
+    let mut processed_lines = 0;
+    let mut processed_rows = 0;
+    let mut expected_header: Option<String> = None;
+
+    for (file_idx, filename) in file_names.iter().enumerate() {
+        progress.start_file(filename, file_idx + 1, file_names.len(), Some(file_lines[file_idx]));
+        report.start_file();
+        let file_value = source.file_value(filename);
+        let mut header_check = HeaderCheck::Match;
+        let mut file_rows = 0;
+
+        for (i, line) in file_utils::record_lines(file_utils::open_input(filename)?) {
+            let line = line?;
+            processed_lines += 1;
+            if i == 0 {
+                match &expected_header {
+                    Some(expected) if *expected != line => header_check = HeaderCheck::Differs,
+                    Some(_) => {}
+                    None => expected_header = Some(line.clone()),
+                }
+                if !header_written {
+                    writer.write_all(line.as_bytes())?;
+                    writer.write_all(source.header_suffix().as_bytes())?;
+                    writer.write_all(b"\n")?;
+                    header_written = true;
+                }
+            } else {
+                let suffix = if source.is_empty() { String::new() } else { source.row_suffix(&file_value, i + 1) };
+                // Sin saber qué shard descarta la línea, se cuenta como escrita (peor caso)
+                let line_bytes = (line.len() + suffix.len()) as u64 + 1;
+                shards.push(line, suffix)?;
+                file_rows += 1;
+                if let Some(reason) = disk_guard.record(line_bytes, line_bytes)? {
+                    writer.flush()?;
+                    return Err(disk_guard.abort(Checkpoint {
+                        command: "merge_dedup".to_string(),
+                        files_completed: file_names[..file_idx].to_vec(),
+                        current_file: filename.clone(),
+                        current_line: i + 1,
+                        lines_processed: i + 1,
+                        ..Default::default()
+                    }, reason));
+                }
+            }
+            if processed_lines % 1000 == 0 {
+                progress.update(processed_lines);
+            }
+        }
+        progress.update(processed_lines);
+        progress.finish_file("");
+        // Los workers deduplican en paralelo: los duplicados no se atribuyen a un archivo
+        report.file(filename, header_check, file_rows, None);
+        processed_rows += file_rows;
+    }
+    progress.finish();
+
+    info!("🧵 Joining {} shards...", threads);
+    let unique = shards.finish(&mut writer)?;
+    writer.flush()?;
+    drop(writer);
+    info!("🔄 Merge completado, {} registros únicos guardados en {}", unique, output_file);
+
+    if let DedupMode::HashOnly { verify: true } = mode {
+        verify_digest_collisions(&file_names, output_file, collation)?;
+    }
+    report.finish(unique, processed_rows - unique)
+}
+
+fn count_lines_with_progress(input_file: &str, progress: &mut ProgressTracker, processed_lines: &mut usize) -> Result<usize, CsvToolsError> {
+    let reader = file_utils::open_input(input_file)?;
+    let mut line_count = 0;
+
+    for _line in reader.lines() {
+        line_count += 1;
+        *processed_lines += 1;
+        
+        // Actualizar progreso cada 1000 líneas para mejor rendimiento
+        if line_count % 1000 == 0 {
+            progress.update(*processed_lines as u64);
+        }
+    }
+    
+    progress.update(*processed_lines as u64);
+    Ok(line_count)
+}
+
+pub fn count_unique_records(file_list_path: &str) -> Result<(), CsvToolsError> {
+    use std::collections::HashSet;
+
+    let file_names = file_utils::resolve_inputs(file_list_path)?;
+
+    info!("📊 Estimando total de líneas para conteo único...");
+    let estimated_lines = file_utils::estimate_lines_per_file(&file_names)?;
+    let estimated_total: u64 = estimated_lines.iter().sum();
+    info!("Estimación: ~{} líneas totales", estimated_total);
+    
+    let mut progress = ProgressTracker::new(estimated_total);
+
+    let mut seen_lines = HashSet::new();
+    let mut total_lines = 0;
+    let mut files_processed = 0;
+
+    for (file_idx, filename) in file_names.iter().enumerate() {
+        progress.start_file(filename, file_idx + 1, file_names.len(), Some(estimated_lines[file_idx]));
+        let file_reader = file_utils::open_input(filename)?;
+        
+        let mut file_unique = 0;
+
+        for (i, file_line) in file_reader.lines().enumerate() {
+            let line_content = file_line?;
+            total_lines += 1;
+            
+            // Skip header line (first line of first file)
+            if files_processed == 0 && i == 0 {
+                seen_lines.insert(line_content);
+                file_unique += 1;
+                progress.update(total_lines);
+                continue;
+            }
+            
+            // Skip headers of subsequent files
+            if files_processed > 0 && i == 0 {
+                progress.update(total_lines);
+                continue;
+            }
+            
+            if seen_lines.insert(line_content) {
+                file_unique += 1;
+            }
+            
+            // Actualizar progreso cada 1000 líneas
+            if total_lines % 1000 == 0 {
+                progress.update(total_lines);
+            }
+        }
+        
+        progress.update(total_lines);
+        progress.finish_file(&format!(", {} unique", file_unique));
+        files_processed += 1;
+    }
+
+    let unique_count = seen_lines.len();
+    let duplicates = total_lines - (unique_count as u64);
+    
+    progress.finish();
+    info!("🔍 Conteo único completado");
+    
+    info!();
+    info!("📊 RESUMEN:");
+    info!("Total de líneas procesadas: {}", total_lines);
+    info!("Registros únicos encontrados: {}", unique_count);
+    info!("Archivos procesados: {}", files_processed);
+    info!("Duplicados detectados: {}", duplicates);
+    
+    Ok(())
+}
+
+pub fn estimate_memory_usage(file_list_path: &str) -> Result<(), CsvToolsError> {
+    info!("🧠 Estimando uso de memoria para deduplicación in-memory...");
+    
+    let estimated_total = file_utils::estimate_total_lines_from_list(file_list_path)?;
+    
+    // Estimar tamaño promedio de línea (basado en formato SIISA)
+    let avg_line_size = 200; // bytes aproximados por línea CSV
+    let overhead_factor = 1.5; // overhead de HashMap/HashSet
+    
+    let estimated_memory_bytes = (estimated_total as f64 * avg_line_size as f64 * overhead_factor) as u64;
+    let memory_gb = estimated_memory_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+    
+    info!("📊 ESTIMACIÓN DE MEMORIA:");
+    info!("  Total de líneas estimadas: {}", estimated_total);
+    info!("  Tamaño promedio por línea: {} bytes", avg_line_size);
+    info!("  Memoria RAM estimada necesaria: {:.2} GB", memory_gb);
+    
+    if memory_gb > 16.0 {
+        info!("⚠️  ADVERTENCIA: Memoria estimada muy alta!");
+        info!("💡 Recomendación: Usar 'external_dedup' en lugar de 'count_unique' o 'merge_dedup'");
+        info!("🚀 Comando sugerido: ./csv_tools.exe external_dedup {} output.csv", file_list_path);
+    } else if memory_gb > 8.0 {
+        info!("⚠️  CUIDADO: Memoria estimada alta, monitorear el sistema");
+    } else {
+        info!("✅ Memoria estimada dentro de límites razonables");
+        info!("🚀 Puedes usar 'count_unique' o 'merge_dedup' sin problemas");
+    }
+    
+    Ok(())
+}
+
+pub fn external_merge_dedup(file_list_path: &str, output_file: &str, workdir: Option<&str>, memory_limit: usize, resume: bool, mut report: MergeReport) -> Result<(), CsvToolsError> {
+    info!("🔄 Iniciando deduplicación externa para archivos GIGANTES...");
+    
+    // Temporal combinado con nombre único por corrida (se borra al terminar, aun con error)
+    let mut temp_files = match workdir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            TempFiles::new(Path::new(dir))
+        }
+        None => TempFiles::for_output(output_file),
+    };
+    let file_names = file_utils::resolve_inputs(file_list_path)?;
+
+    // --resume: se retoma el temporal combinado que dejó la corrida cortada por --max-runtime
+    let checkpoint = if resume { Some(Checkpoint::load(output_file, "external_dedup")?) } else { None };
+    let (temp_merged_path, start_file, skip_lines) = match &checkpoint {
+        Some(c) => {
+            let temp = c.temp_file.as_deref()
+                .filter(|t| Path::new(t).exists())
+                .ok_or("Checkpoint has no partial merge file to resume (run again without --resume)")?;
+            let (start_file, skip_lines) = c.resume_position(&file_names)?;
+            info!("⏯️  Resuming: {} files completed, {} lines of {}", start_file, skip_lines, c.current_file);
+            report.resumed();
+            (temp_files.adopt(PathBuf::from(temp)), start_file, skip_lines)
+        }
+        None => (temp_files.path("merged.csv"), 0, 0),
+    };
+    let temp_merged = temp_merged_path.to_string_lossy().to_string();
+    info!("📁 Temporal: {}", temp_merged);
+    
+    info!("📂 Paso 1: Combinando archivos...");
+    let file_lines = file_utils::estimate_lines_per_file(&file_names)?;
+    let mut progress = ProgressTracker::new(file_lines.iter().sum());
+    
+    // Temporal combinado + corridas ordenadas + salida: hasta ~3x el tamaño de entrada
+    let total_input_bytes = file_utils::total_file_size(&file_names)?;
+    let mut disk_guard = DiskSpaceGuard::new(output_file, total_input_bytes, 3.0);
+    disk_guard.preflight()?;
+
+    // Combinar todos los archivos en uno temporal
+    let temp_file = if resume {
+        std::fs::OpenOptions::new().append(true).open(&temp_merged)?
+    } else {
+        std::fs::File::create(&temp_merged)?
+    };
+    let mut writer = std::io::BufWriter::new(temp_file);
+    let mut header_written = resume;
+    let mut processed_lines = 0;
+    // El header del primer archivo es el que queda en la salida
+    let expected_header = match file_utils::open_input(&file_names[0])?.lines().next() {
+        Some(line) => Some(line?),
+        None => None,
+    };
+    
+    for (file_idx, filename) in file_names.iter().enumerate() {
+        if file_idx < start_file {
+            continue;
+        }
+        let skip = if file_idx == start_file { skip_lines } else { 0 };
+        let file_reader = file_utils::open_input(filename)?;
+        progress.start_file(filename, file_idx + 1, file_names.len(), Some(file_lines[file_idx]));
+        report.start_file();
+        let mut header_check = HeaderCheck::Match;
+        let mut file_rows = 0;
+        
+        for (i, file_line) in std::io::BufRead::lines(file_reader).enumerate() {
+            let line_content = match file_line {
+                Ok(line) => line,
+                Err(e) => {
+                    writer.flush()?;
+                    return Err(disk_guard.abort_on_read_error(Checkpoint {
+                        command: "external_dedup".to_string(),
+                        files_completed: file_names[..file_idx].to_vec(),
+                        current_file: filename.clone(),
+                        current_line: i + 1,
+                        lines_processed: i,
+                        ..Default::default()
+                    }, e));
+                }
+            };
+            if i == 0 && expected_header.as_ref() != Some(&line_content) {
+                header_check = HeaderCheck::Differs;
+            }
+            if i < skip {
+                continue;
+            }
+            processed_lines += 1;
+            let line_bytes = line_content.len() as u64 + 1;
+            let mut written = 0u64;
+            
+            if i == 0 {
+                if !header_written {
+                    writeln!(writer, "{}", line_content)?;
+                    header_written = true;
+                    written = line_bytes;
+                }
+            } else {
+                writeln!(writer, "{}", line_content)?;
+                written = line_bytes;
+                file_rows += 1;
+            }
+
+            if let Some(reason) = disk_guard.record(line_bytes, written)? {
+                writer.flush()?;
+                return Err(disk_guard.abort(Checkpoint {
+                    command: "external_dedup".to_string(),
+                    files_completed: file_names[..file_idx].to_vec(),
+                    current_file: filename.clone(),
+                    current_line: i + 1,
+                    lines_processed: i + 1,
+                    ..Default::default()
+                }, reason));
+            }
+            
+            if processed_lines % 1000 == 0 {
+                progress.update(processed_lines);
+
+                // El corte por --max-runtime solo es posible en el paso 1 (el sort externo no se interrumpe)
+                if runtime_limit::exceeded() {
+                    writer.flush()?;
+                    temp_files.keep(&temp_merged_path);
+                    return Err(disk_guard.stop_at_deadline(Checkpoint {
+                        command: "external_dedup".to_string(),
+                        files_completed: file_names[..file_idx].to_vec(),
+                        current_file: filename.clone(),
+                        current_line: i + 1,
+                        lines_processed: i + 1,
+                        temp_file: Some(temp_merged.clone()),
+                        ..Default::default()
+                    }));
+                }
+            }
+        }
+
+        progress.update(processed_lines);
+        progress.finish_file("");
+        // El sort externo deduplica todo junto: los duplicados no se atribuyen a un archivo
+        report.file(filename, header_check, file_rows, None);
+    }
+    
+    writer.flush()?;
+    progress.finish();
+    info!("📂 Combinación completada");
+    
+    info!("🔄 Paso 2: Ordenando y deduplicando (sort externo, corridas de hasta {})...", file_utils::format_bytes(memory_limit as u64));
+    
+    // Sort externo propio (mismo resultado en Windows y Linux), con la collation pedida
+    let collation = collation::current();
+    info!("🔤 Collation: {}", collation);
+    let mut reader = file_utils::open_input(&temp_merged)?;
+    let mut output = compression::create_output(output_file, 1)?;
+
+    // El header va primero y no participa del orden
+    let mut header = String::new();
+    reader.read_line(&mut header)?;
+    output.write_all(header.trim_end_matches(['\r', '\n']).as_bytes())?;
+    output.write_all(b"\n")?;
+
+    let stats = external_sort::sort_dedup_lines(&mut reader, &mut output, &mut temp_files, memory_limit, collation)?;
+    output.flush()?;
+    drop(output);
+    info!("✅ Deduplicación externa completada exitosamente!");
+    
+    // Limpiar temporales (y el checkpoint de una corrida retomada)
+    drop(temp_files);
+    Checkpoint::remove(output_file);
+    
+    info!("📊 RESULTADO FINAL:");
+    info!("  Archivo generado: {}", compression::output_path(output_file));
+    info!("  Registros leídos: {}", stats.lines_read);
+    info!("  Registros únicos: {}", stats.lines_written);
+    info!("  Duplicados eliminados: {}", stats.duplicates_removed);
+    info!("  Corridas en disco: {}", stats.runs);
+    
+    report.finish(stats.lines_written, stats.duplicates_removed)
+}
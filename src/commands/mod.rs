@@ -2,7 +2,20 @@
 pub mod validation;
 pub mod cleaning;
 pub mod file_ops;
-
-// ✅ Future modules can be added here:
-// pub mod inspection;
-// pub mod date_ops;
+pub mod sqlite_ops;
+pub mod pipeline;
+pub mod inspection;
+pub mod dedup_ops;
+pub mod error_summary;
+pub mod schema_ops;
+pub mod partition_ops;
+pub mod edit_ops;
+pub mod prepare_ops;
+pub mod watch;
+pub mod repl;
+pub mod convert_ops;
+pub mod sort_ops;
+pub mod filter_ops;
+pub mod transform_ops;
+pub mod date_ops;
+pub mod merge_ops;
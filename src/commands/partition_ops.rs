@@ -0,0 +1,234 @@
+use csv::{ReaderBuilder, StringRecord};
+
+use clap::Args;
+use crate::config;
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::exit_code::usage_error;
+use crate::file_utils::open_input;
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::repro;
+use crate::sketch::seeded_hash;
+
+// Claves que se guardan como muestra (reservoir) para calcular los cortes
+const DEFAULT_SAMPLE_SIZE: usize = 1_000_000;
+
+/// Muestra uniforme de tamaño fijo sobre un stream (algoritmo R), determinista por semilla
+struct Reservoir {
+    capacity: usize,
+    seen: u64,
+    seed: u64,
+    items: Vec<String>,
+}
+
+impl Reservoir {
+    fn new(capacity: usize, seed: u64) -> Self {
+        Reservoir { capacity: capacity.max(1), seen: 0, seed, items: Vec::new() }
+    }
+
+    fn offer(&mut self, item: &str) {
+        self.seen += 1;
+        if self.items.len() < self.capacity {
+            self.items.push(item.to_string());
+            return;
+        }
+        let slot = seeded_hash(&self.seen.to_le_bytes(), self.seed) % self.seen;
+        if (slot as usize) < self.capacity {
+            self.items[slot as usize] = item.to_string();
+        }
+    }
+}
+
+/// Clave ordenable: numérica si todas las claves son enteras, si no texto
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum PartitionKey {
+    Number(i64),
+    Text(String),
+}
+
+impl PartitionKey {
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            PartitionKey::Number(n) => serde_json::json!(n),
+            PartitionKey::Text(s) => serde_json::json!(s),
+        }
+    }
+}
+
+/// Genera rangos de claves balanceados (JSON) para repartir la importación entre workers
+/// Cada partición es `[start, end)`; la primera no tiene start y la última no tiene end
+#[derive(Args, Debug)]
+pub struct PlanPartitionsArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(long, value_name = "COLUMN", help = "Partition key column")]
+    pub key: String,
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..), help = "Number of import workers")]
+    pub workers: u64,
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_SAMPLE_SIZE, help = "Keys sampled to compute the boundaries")]
+    pub sample: usize,
+    #[arg(long, value_name = "PLAN.json", help = "Write the plan to a file instead of stdout")]
+    pub output: Option<String>,
+}
+
+pub fn plan_partitions(args: &PlanPartitionsArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let key_name = args.key.as_str();
+    let workers = args.workers as usize;
+    let sample_size = args.sample;
+    let output_path = args.output.as_deref();
+    // Sin --output el JSON va a stdout, sin banner ni progreso
+    let verbose = output_path.is_some();
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers = rdr.headers()?.clone();
+    let key_index = headers.iter().position(|h| h.trim() == key_name)
+        .ok_or_else(|| usage_error(format!("Key column '{}' not found in header", key_name)))?;
+
+    if verbose {
+        logging::banner("Key-Range Partition Planner");
+        info!("📄 Input: {}", input_path);
+        info!("🔑 Key: {}", key_name);
+        info!("👷 Workers: {}", workers);
+        info!("🎲 Sample: up to {} keys (seed {})", sample_size, repro::seed());
+        info!();
+    }
+
+    let mut reservoir = Reservoir::new(sample_size, repro::seed());
+    let mut record = StringRecord::new();
+    let mut processed = 0usize;
+    let mut empty_keys = 0usize;
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        processed += 1;
+        let key = record.get(key_index).unwrap_or("").trim();
+        if key.is_empty() {
+            empty_keys += 1;
+        } else {
+            reservoir.offer(key);
+        }
+
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+    if verbose {
+        info!("📊 Processed: {}", processed);
+    }
+
+    if empty_keys > 0 {
+        diagnostics::warn(format!("{} rows have an empty '{}' and are not covered by the plan", empty_keys, key_name));
+    }
+
+    let keyed_rows = reservoir.seen;
+    let sample = sorted_keys(reservoir.items);
+    let boundaries = split_boundaries(&sample, workers);
+    if boundaries.len() + 1 < workers && !sample.is_empty() {
+        diagnostics::warn(format!(
+            "Only {} partitions could be planned for {} workers (too few distinct keys)",
+            boundaries.len() + 1, workers
+        ));
+    }
+
+    // Filas estimadas por partición (proporción de la muestra en cada rango)
+    let mut partitions = Vec::with_capacity(boundaries.len() + 1);
+    let mut lower = 0usize;
+    for i in 0..=boundaries.len() {
+        let upper = match boundaries.get(i) {
+            Some(b) => sample.partition_point(|k| k < b),
+            None => sample.len(),
+        };
+        let share = if sample.is_empty() { 0.0 } else { (upper - lower) as f64 / sample.len() as f64 };
+        partitions.push(serde_json::json!({
+            "worker": i,
+            "start": if i == 0 { serde_json::Value::Null } else { boundaries[i - 1].to_json() },
+            "end": boundaries.get(i).map(PartitionKey::to_json),
+            "estimated_rows": (share * keyed_rows as f64).round() as u64,
+        }));
+        lower = upper;
+    }
+
+    let plan = serde_json::json!({
+        "input": input_path,
+        "key": key_name,
+        "key_type": match sample.first() {
+            Some(PartitionKey::Text(_)) => "string",
+            _ => "number",
+        },
+        "workers": workers,
+        "rows_scanned": processed,
+        "rows_with_key": keyed_rows,
+        "sampled_keys": sample.len(),
+        "seed": repro::seed(),
+        "range_semantics": "start <= key < end (null = unbounded)",
+        "partitions": partitions,
+    });
+    let json = serde_json::to_string_pretty(&plan)?;
+
+    match output_path {
+        Some(path) => {
+            std::fs::write(path, format!("{}\n", json))?;
+            info!();
+            info!("✅ {} partitions planned", boundaries.len() + 1);
+            info!("📝 Plan: {}", path);
+        }
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+/// Convierte la muestra en claves ordenadas (numéricas solo si todas son enteras)
+fn sorted_keys(raw: Vec<String>) -> Vec<PartitionKey> {
+    let numeric: Option<Vec<i64>> = raw.iter().map(|k| k.parse::<i64>().ok()).collect();
+    let mut keys: Vec<PartitionKey> = match numeric {
+        Some(numbers) => numbers.into_iter().map(PartitionKey::Number).collect(),
+        None => raw.into_iter().map(PartitionKey::Text).collect(),
+    };
+    keys.sort();
+    keys
+}
+
+/// Cortes en los cuantiles i/workers de la muestra ordenada, sin repetidos
+/// (una clave muy repetida no puede partirse entre dos workers)
+fn split_boundaries(sorted: &[PartitionKey], workers: usize) -> Vec<PartitionKey> {
+    let mut boundaries: Vec<PartitionKey> = Vec::with_capacity(workers.saturating_sub(1));
+    for i in 1..workers {
+        let idx = i * sorted.len() / workers;
+        let Some(candidate) = sorted.get(idx) else {
+            continue;
+        };
+        let above_previous = boundaries.last().is_none_or(|last| candidate > last);
+        if above_previous && Some(candidate) != sorted.first() {
+            boundaries.push(candidate.clone());
+        }
+    }
+    boundaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_boundaries() {
+        let keys = sorted_keys((1..=100).rev().map(|n| n.to_string()).collect());
+        assert_eq!(
+            split_boundaries(&keys, 4),
+            vec![PartitionKey::Number(26), PartitionKey::Number(51), PartitionKey::Number(76)]
+        );
+
+        // Claves repetidas: menos cortes que workers, nunca un rango vacío
+        let skewed = sorted_keys(["7", "7", "7", "7", "7", "7", "9", "9"].iter().map(|s| s.to_string()).collect());
+        assert_eq!(split_boundaries(&skewed, 4), vec![PartitionKey::Number(9)]);
+
+        let text = sorted_keys(vec!["b".into(), "a".into(), "10".into()]);
+        assert_eq!(text.first(), Some(&PartitionKey::Text("10".into())));
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+
+use clap::{Args, Subcommand};
+
+use crate::error::CsvToolsError;
+use crate::exit_code;
+use crate::info;
+use crate::logging;
+use crate::progress;
+use crate::temp_files::TempFiles;
+
+// Prefijo de variables de entorno que aportan parámetros (CSV_TOOLS_PARAM_MONTH → month)
+const ENV_PARAM_PREFIX: &str = "CSV_TOOLS_PARAM_";
+// Parámetros que son intermedios de la corrida (${tmp.coerced.csv})
+const TEMP_PARAM_PREFIX: &str = "tmp.";
+
+/// Ejecuta la línea de comandos de un paso (`[programa, comando, args...]`)
+pub type StepRunner = dyn Fn(&[String]) -> Result<(), CsvToolsError>;
+
+/// Plan de pipeline: pasos que se ejecutan en orden con los comandos existentes
+#[derive(Debug, Deserialize)]
+pub struct PipelinePlan {
+    #[serde(default)]
+    pub name: String,
+    /// Valores por defecto de los parámetros (`${month}`, `${input_dir}`, ...)
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    pub steps: Vec<PipelineStep>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PipelineStep {
+    #[serde(default)]
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl PipelinePlan {
+    pub fn load(path: &str) -> Result<Self, CsvToolsError> {
+        let content = std::fs::read_to_string(path)?;
+        let plan: PipelinePlan = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid pipeline plan '{}': {}", path, e))?;
+        Ok(plan)
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct PipelineArgs {
+    #[command(subcommand)]
+    pub command: Option<PipelineCommand>,
+    /// Forma corta `pipeline <plan.yaml>` (igual a `pipeline run <plan.yaml>`)
+    #[command(flatten)]
+    pub run: Option<PipelineRunArgs>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PipelineCommand {
+    #[command(about = "Run a plan with managed temp files and a consolidated report")]
+    Run(PipelineRunArgs),
+}
+
+impl PipelineArgs {
+    pub fn run_args(&self) -> Result<&PipelineRunArgs, CsvToolsError> {
+        match (&self.command, &self.run) {
+            (Some(PipelineCommand::Run(args)), _) | (None, Some(args)) => Ok(args),
+            (None, None) => Err(exit_code::usage_error("Missing pipeline plan (pipeline run <plan.yaml>)")),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct PipelineRunArgs {
+    #[arg(value_name = "PLAN.yaml")]
+    pub plan: String,
+    #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_param,
+          help = "Plan parameter (repeatable; also from CSV_TOOLS_PARAM_<NAME> env vars)")]
+    pub params: Vec<(String, String)>,
+    #[arg(long, help = "Resolve and print the steps without running them")]
+    pub dry_run: bool,
+    #[arg(long, value_name = "DIR", help = "Directory for ${tmp.NAME} intermediate files [default: current directory]")]
+    pub workdir: Option<String>,
+    #[arg(long, help = "Keep the ${tmp.NAME} intermediate files after the run")]
+    pub keep_temp: bool,
+    #[arg(long, value_name = "REPORT.json", help = "Also write the consolidated run report as JSON")]
+    pub report: Option<String>,
+}
+
+/// Resultado de un paso en el reporte consolidado
+#[derive(Debug, Serialize)]
+struct StepReport {
+    step: usize,
+    name: String,
+    command: String,
+    args: Vec<String>,
+    /// ok | failed | skipped (no se llegó por un paso fallido) | planned (--dry-run)
+    status: &'static str,
+    seconds: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Reporte consolidado de la corrida (recuadro final, --format json y --report)
+#[derive(Debug, Serialize)]
+struct PipelineReport {
+    plan: String,
+    name: String,
+    status: &'static str,
+    steps_total: usize,
+    steps_completed: usize,
+    seconds: f64,
+    temp_files: Vec<String>,
+    steps: Vec<StepReport>,
+}
+
+/// Ejecuta un plan YAML paso a paso
+/// Parámetros: --param k=v (CLI) > CSV_TOOLS_PARAM_K (entorno) > `params` del plan
+/// `${tmp.NAME}` es un intermedio de la corrida: ruta única en --workdir que se borra
+/// al terminar (también si un paso falla), salvo con --keep-temp
+/// `program` es argv[0], con el que se arma la línea de cada paso; `run_step` ejecuta
+/// esa línea (el binario la parsea y despacha como un comando más)
+pub fn run_pipeline(
+    program: &str,
+    args: &PipelineArgs,
+    run_step: &StepRunner,
+) -> Result<(), CsvToolsError> {
+    let args = args.run_args()?;
+    let plan_path = args.plan.as_str();
+    let dry_run = args.dry_run;
+    let plan = PipelinePlan::load(plan_path)?;
+
+    let cli_params = args.params.clone();
+    let env_params: Vec<(String, String)> = std::env::vars()
+        .filter_map(|(k, v)| k.strip_prefix(ENV_PARAM_PREFIX).map(|name| (name.to_lowercase(), v)))
+        .collect();
+    let mut params = resolve_params(&plan.params, env_params, cli_params);
+
+    let workdir = args.workdir.as_deref().unwrap_or(".");
+    if !dry_run {
+        std::fs::create_dir_all(workdir)?;
+    }
+    let mut temp_files = TempFiles::new(Path::new(workdir));
+    let mut temp_paths = Vec::new();
+    for name in temp_names(&plan.steps) {
+        let path = temp_files.path(&name);
+        if args.keep_temp {
+            temp_files.keep(&path);
+        }
+        let path = path.to_string_lossy().into_owned();
+        params.insert(format!("{}{}", TEMP_PARAM_PREFIX, name), path.clone());
+        temp_paths.push(path);
+    }
+
+    logging::banner("Pipeline Runner");
+    info!("📋 Plan: {} [{}] ({} steps)", plan_path, plan.name, plan.steps.len());
+    for (k, v) in &params {
+        info!("⚙️  ${{{}}} = {}", k, v);
+    }
+    info!();
+
+    // Resolver todos los pasos antes de ejecutar: un parámetro faltante no debe
+    // dejar la corrida a medias
+    let mut resolved_steps = Vec::with_capacity(plan.steps.len());
+    let mut missing = Vec::new();
+
+    for step in &plan.steps {
+        let mut step_args = vec![program.to_string(), step.command.clone()];
+        for arg in &step.args {
+            match substitute_params(arg, &params) {
+                Ok(value) => step_args.push(value),
+                Err(name) => {
+                    if !missing.contains(&name) {
+                        missing.push(name);
+                    }
+                }
+            }
+        }
+        resolved_steps.push(step_args);
+    }
+
+    if !missing.is_empty() {
+        return Err(exit_code::usage_error(format!(
+            "Missing pipeline parameters: {} (use --param name=value or {}NAME)",
+            missing.join(", "), ENV_PARAM_PREFIX
+        )));
+    }
+
+    let start = Instant::now();
+    let total = plan.steps.len();
+    let mut reports = Vec::with_capacity(total);
+    let mut failure = None;
+
+    for (i, (step, step_args)) in plan.steps.iter().zip(&resolved_steps).enumerate() {
+        let label = if step.name.is_empty() { &step.command } else { &step.name };
+        let mut report = StepReport {
+            step: i + 1,
+            name: label.clone(),
+            command: step.command.clone(),
+            args: step_args[2..].to_vec(),
+            status: "skipped",
+            seconds: 0.0,
+            error: None,
+        };
+
+        if failure.is_some() {
+            reports.push(report);
+            continue;
+        }
+
+        info!("▶️  Step {}/{}: {} ({:.0}% of steps done, {:.1}s elapsed)",
+              i + 1, total, label, logging::rate(i, total) * 100.0, start.elapsed().as_secs_f64());
+        info!("   $ csv_tools {}", step_args[1..].join(" "));
+
+        if dry_run {
+            report.status = "planned";
+            reports.push(report);
+            continue;
+        }
+
+        let step_start = Instant::now();
+        progress::set_stage(Some(format!("{}/{} {}", i + 1, total, step.command)));
+        let result = run_step(step_args);
+        progress::set_stage(None);
+        report.seconds = step_start.elapsed().as_secs_f64();
+
+        match result {
+            Ok(()) => {
+                report.status = "ok";
+                info!("✅ Step {} done in {:.2}s", i + 1, report.seconds);
+                info!();
+            }
+            Err(e) => {
+                report.status = "failed";
+                report.error = Some(e.to_string());
+                failure = Some(exit_code::with_context(e, &format!("Pipeline step {} ({}) failed", i + 1, label)));
+            }
+        }
+        reports.push(report);
+    }
+
+    let completed = reports.iter().filter(|r| r.status == "ok").count();
+    let report = PipelineReport {
+        plan: plan_path.to_string(),
+        name: plan.name.clone(),
+        status: if dry_run { "planned" } else if failure.is_some() { "failed" } else { "ok" },
+        steps_total: total,
+        steps_completed: completed,
+        seconds: start.elapsed().as_secs_f64(),
+        temp_files: temp_paths,
+        steps: reports,
+    };
+    print_report(&report);
+    if let Some(path) = &args.report {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        info!("📝 Report: {}", path);
+    }
+    logging::summary(&report)?;
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Recuadro final: estado y duración de cada paso
+fn print_report(report: &PipelineReport) {
+    logging::banner("Pipeline Summary");
+    for step in &report.steps {
+        let icon = match step.status {
+            "ok" => "✅",
+            "failed" => "❌",
+            "planned" => "📝",
+            _ => "⏭️ ",
+        };
+        info!("{} {:>2}. {:<30} {:<8} {:>8.2}s", icon, step.step, step.name, step.status, step.seconds);
+        if let Some(error) = &step.error {
+            info!("      {}", error);
+        }
+    }
+    info!();
+    match report.status {
+        "planned" => info!("💡 Dry run: {} steps resolved, nothing executed", report.steps_total),
+        "ok" => info!("✅ {} steps completed in {:.2}s", report.steps_completed, report.seconds),
+        _ => info!("❌ {}/{} steps completed in {:.2}s", report.steps_completed, report.steps_total, report.seconds),
+    }
+    if !report.temp_files.is_empty() && report.status != "planned" {
+        info!("🗂️  Intermediate files: {}", report.temp_files.len());
+    }
+}
+
+/// Nombres de los intermedios `${tmp.NAME}` que usan los pasos, sin repetir
+fn temp_names(steps: &[PipelineStep]) -> Vec<String> {
+    let marker = format!("${{{}", TEMP_PARAM_PREFIX);
+    let mut names = Vec::new();
+    for arg in steps.iter().flat_map(|s| &s.args) {
+        let mut rest = arg.as_str();
+        while let Some(start) = rest.find(&marker) {
+            rest = &rest[start + marker.len()..];
+            let Some(len) = rest.find('}') else {
+                break;
+            };
+            let name = rest[..len].to_string();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+            rest = &rest[len..];
+        }
+    }
+    names
+}
+
+/// Interpreta un `--param k=v`
+pub fn parse_param(pair: &str) -> Result<(String, String), String> {
+    let (k, v) = pair.split_once('=')
+        .ok_or_else(|| format!("Invalid --param '{}' (expected name=value)", pair))?;
+    Ok((k.trim().to_string(), v.to_string()))
+}
+
+/// Combina defaults del plan, entorno y CLI (en ese orden de prioridad creciente)
+fn resolve_params(
+    defaults: &BTreeMap<String, String>,
+    env: Vec<(String, String)>,
+    cli: Vec<(String, String)>,
+) -> BTreeMap<String, String> {
+    let mut params = defaults.clone();
+    params.extend(env);
+    params.extend(cli);
+    params
+}
+
+/// Reemplaza `${name}` por su valor; devuelve el nombre del primer parámetro sin valor
+fn substitute_params(value: &str, params: &BTreeMap<String, String>) -> Result<String, String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + len];
+
+        result.push_str(&rest[..start]);
+        match params.get(name) {
+            Some(v) => result.push_str(v),
+            None => return Err(name.to_string()),
+        }
+        rest = &rest[start + 3 + len..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_params() {
+        let params = resolve_params(
+            &BTreeMap::from([("month".to_string(), "202401".to_string()), ("input_dir".to_string(), "in".to_string())]),
+            vec![("input_dir".to_string(), "/data".to_string())],
+            vec![("month".to_string(), "202405".to_string())],
+        );
+
+        assert_eq!(substitute_params("${input_dir}/siisa_${month}.csv", &params).unwrap(), "/data/siisa_202405.csv");
+        assert_eq!(substitute_params("no params", &params).unwrap(), "no params");
+        assert_eq!(substitute_params("${year}.csv", &params), Err("year".to_string()));
+    }
+
+    #[test]
+    fn test_temp_names() {
+        let step = |args: &[&str]| PipelineStep {
+            name: String::new(),
+            command: "coerce".to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        };
+        let steps = [
+            step(&["${input}", "${tmp.coerced.csv}"]),
+            step(&["${tmp.coerced.csv}", "${tmp.ready.csv}", "--model", "m"]),
+        ];
+        assert_eq!(temp_names(&steps), vec!["coerced.csv", "ready.csv"]);
+    }
+}
@@ -0,0 +1,360 @@
+// Preparación para ImportTable en una sola lectura de la entrada: validación estructural,
+// sanitización de campos Type N y deduplicación por clave (el flujo validate → sanitize →
+// dedup leía el archivo tres veces). Con entradas grandes la deduplicación reparte las filas
+// en particiones temporales por hash de clave y deduplica cada una en memoria
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use csv::{ReaderBuilder, StringRecord, Writer, WriterBuilder};
+
+use clap::Args;
+use crate::cli_args::list_values;
+use crate::collation;
+use crate::commands::inspection::is_valid_dynamodb_number;
+use crate::compression::create_output;
+use crate::dedup::key_fingerprint;
+use crate::config;
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::error_report::{self, ErrorRecord};
+use crate::exit_code::usage_error;
+use crate::file_utils::{format_bytes, get_file_size, open_input};
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::models::DynamoDbModel;
+use crate::schema::{ColumnSpec, Schema};
+use crate::temp_files::TempFiles;
+
+// Entrada por partición de deduplicación (~15M claves en memoria con filas de ~150 bytes)
+const SPILL_PARTITION_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+// Las entradas .gz se estiman descomprimidas con este factor
+const GZIP_EXPANSION: u64 = 5;
+
+/// Contadores de la pasada
+#[derive(Default)]
+struct PrepareStats {
+    rows: usize,
+    parse_errors: usize,
+    structure_errors: usize,
+    invalid_numeric: usize,
+    empty_keys: usize,
+    defaulted: usize,
+    duplicates: usize,
+    written: usize,
+}
+
+/// Destino de las filas válidas: deduplicación directa o particiones temporales
+enum Sink {
+    Direct { writer: Box<Writer<Box<dyn Write>>>, seen: HashSet<u128> },
+    Partitioned { partitions: Vec<Writer<BufWriter<File>>>, paths: Vec<PathBuf> },
+}
+
+/// Valida, sanitiza y deduplica en una sola pasada (`prepare`)
+#[derive(Args, Debug)]
+pub struct PrepareArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "MODEL_TYPE",
+          help = "DynamoDB model (column order and Type N fields) [default: default_model from the config file]")]
+    pub model: Option<String>,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', help = "Dedup key columns [default: model keys]")]
+    pub dedup_keys: Vec<String>,
+    #[arg(long, value_name = "SCHEMA.yaml", help = "Schema whose nullable/default rules apply to empty Type N values")]
+    pub schema: Option<String>,
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..),
+          help = "Dedup partitions [default: one per 2 GB of input]")]
+    pub partitions: Option<u64>,
+    #[arg(long, value_name = "DIR", help = "Directory for partition temp files [default: output directory]")]
+    pub workdir: Option<String>,
+}
+
+pub fn prepare(args: &PrepareArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let model_type = config::model_or_default(args.model.as_deref())?;
+    let model_type = model_type.as_str();
+    let model = DynamoDbModel::from_model_type(model_type)
+        .ok_or_else(|| usage_error(format!("Unknown model type: {}", model_type)))?;
+    let model_schema = Schema::from_model(&model);
+    let schema = match &args.schema {
+        Some(spec) => Some(Schema::load(spec)?),
+        None => None,
+    };
+
+    let key_names: Vec<String> = if args.dedup_keys.is_empty() {
+        [model.partition_key, model.sort_key].iter()
+            .filter(|k| !k.is_empty())
+            .map(|k| k.to_string())
+            .collect()
+    } else {
+        list_values(&args.dedup_keys).into_iter().map(String::from).collect()
+    };
+
+    let input_bytes = get_file_size(input_path)?;
+    let estimated_bytes = if input_path.to_lowercase().ends_with(".gz") {
+        input_bytes * GZIP_EXPANSION
+    } else {
+        input_bytes
+    };
+    let partition_count = match args.partitions {
+        Some(n) => n as usize,
+        None => estimated_bytes.div_ceil(SPILL_PARTITION_BYTES).max(1) as usize,
+    };
+    let collation = collation::current();
+
+    logging::banner("Prepare for ImportTable (validate + sanitize + dedup)");
+    info!("📄 Input:  {} ({})", input_path, format_bytes(input_bytes));
+    info!("📄 Output: {}", output_path);
+    info!("📋 Model:  {} ({} columns)", model.table_name, model_schema.columns.len());
+    if let Some(s) = &schema {
+        info!("📋 Schema: {} (empty Type N values follow `nullable`)", s.name);
+    }
+    info!("🔑 Dedup keys: {}", key_names.join(" + "));
+    info!("🔤 Collation: {}", collation);
+    if partition_count > 1 {
+        info!("💽 Dedup partitions: {} (spilled to temporary files)", partition_count);
+    }
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+
+    // Validación estructural del header: mismas columnas y en el orden del modelo
+    let headers: Vec<String> = reader.headers()?.iter()
+        .map(|h| h.trim().trim_matches('"').to_string())
+        .collect();
+    let expected: Vec<&str> = model_schema.columns.iter().map(|c| c.name.as_str()).collect();
+    if headers != expected {
+        let missing: Vec<&str> = expected.iter().copied().filter(|c| !headers.iter().any(|h| h == c)).collect();
+        if missing.is_empty() && headers.len() == expected.len() {
+            return Err(format!(
+                "Header columns are not in model order (try: csv_tools reorder-to-model \"{}\" <output> --model {})",
+                input_path, model_type
+            ).into());
+        }
+        return Err(format!(
+            "Header mismatch for {}: expected {:?}, found {:?}", model_type, expected, headers
+        ).into());
+    }
+
+    let key_indexes = key_names.iter()
+        .map(|k| headers.iter().position(|h| h == k)
+            .ok_or_else(|| usage_error(format!("Dedup key column '{}' not found in header", k))))
+        .collect::<Result<Vec<usize>, CsvToolsError>>()?;
+
+    // Columnas Type N: índice, nombre y spec que decide qué hacer con los vacíos
+    let numeric_columns: Vec<(usize, &ColumnSpec)> = model_schema.columns.iter()
+        .enumerate()
+        .filter(|(_, c)| c.column_type.is_numeric())
+        .map(|(i, c)| (i, schema.as_ref().and_then(|s| s.column(&c.name)).unwrap_or(c)))
+        .collect();
+
+    let error_log_path = config::error_log_path(output_path, ".prepare_errors.log");
+    let mut log = BufWriter::new(File::create(&error_log_path)?);
+    writeln!(log, "# Prepare Error Log")?;
+    writeln!(log, "# Input: {}", input_path)?;
+    writeln!(log, "# Output: {}", output_path)?;
+    writeln!(log, "# Model: {}", model_type)?;
+    writeln!(log, "#")?;
+    writeln!(log, "# Format: [LINE] STATUS | Details")?;
+    writeln!(log, "# -------------------------------------------------------")?;
+
+    let workdir = args.workdir.as_deref();
+    let mut temp_files = match workdir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            TempFiles::new(Path::new(dir))
+        }
+        None => TempFiles::for_output(output_path),
+    };
+
+    let mut sink = if partition_count == 1 {
+        let mut writer = WriterBuilder::new()
+            .delimiter(config::delimiter())
+            .quote_style(csv::QuoteStyle::Necessary)
+            .from_writer(create_output(output_path, 1)?);
+        writer.write_record(&expected)?;
+        Sink::Direct { writer: Box::new(writer), seen: HashSet::new() }
+    } else {
+        let paths: Vec<PathBuf> = (0..partition_count)
+            .map(|i| temp_files.path(&format!("prepare_part{:03}.csv", i)))
+            .collect();
+        let mut partitions = Vec::with_capacity(partition_count);
+        for path in &paths {
+            partitions.push(WriterBuilder::new()
+                .has_headers(false)
+                .quote_style(csv::QuoteStyle::Necessary)
+                .from_writer(BufWriter::new(File::create(path)?)));
+        }
+        Sink::Partitioned { partitions, paths }
+    };
+
+    let mut stats = PrepareStats::default();
+    let mut record = StringRecord::new();
+    let mut clean = StringRecord::with_capacity(0, expected.len());
+    let pass_label = if partition_count == 1 { "" } else { " (pass 1/2: validate + partition)" };
+    info!("🔍 Processing records{}...", pass_label);
+
+    let mut progress = Progress::rows("Processed");
+    loop {
+        let line_num = stats.rows + 2;
+        match reader.read_record(&mut record) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                stats.rows += 1;
+                stats.parse_errors += 1;
+                error_report::write(&mut log, &ErrorRecord::parse_error(line_num, &e, format!("CSV parsing failed: {}", e)))?;
+                continue;
+            }
+        }
+        stats.rows += 1;
+        progress.update(stats.rows as u64, || format!("Invalid: {} | Duplicates: {}",
+            stats.structure_errors + stats.invalid_numeric + stats.empty_keys, stats.duplicates));
+
+        if record.len() != expected.len() {
+            stats.structure_errors += 1;
+            error_report::write(&mut log, &ErrorRecord::new(line_num, "IRREPARABLE_STRUCTURE",
+                format!("Columns: {} (expected {})", record.len(), expected.len())).at(record.position()))?;
+            continue;
+        }
+
+        // Sanitización Type N: sin espacios ni comillas, número DynamoDB válido
+        clean.clear();
+        let mut invalid = false;
+        let mut next_numeric = numeric_columns.iter().peekable();
+        for (i, value) in record.iter().enumerate() {
+            let Some((_, spec)) = next_numeric.next_if(|(idx, _)| *idx == i) else {
+                clean.push_field(value);
+                continue;
+            };
+
+            let trimmed = value.trim().trim_matches('"');
+            if trimmed.is_empty() {
+                match spec.empty_value() {
+                    Some(fill) => {
+                        if !fill.is_empty() {
+                            stats.defaulted += 1;
+                        }
+                        clean.push_field(fill);
+                    }
+                    None => {
+                        invalid = true;
+                        error_report::write(&mut log, &ErrorRecord::new(line_num, "INVALID_NUMERIC", "Expected: Type N (empty, not nullable)")
+                            .at(record.position()).column(&spec.name).value(""))?;
+                    }
+                }
+            } else if is_valid_dynamodb_number(trimmed) {
+                clean.push_field(trimmed);
+            } else {
+                invalid = true;
+                error_report::write(&mut log, &ErrorRecord::new(line_num, "INVALID_NUMERIC", "Expected: Type N (numeric)")
+                    .at(record.position()).column(&spec.name).value(trimmed))?;
+            }
+        }
+        if invalid {
+            stats.invalid_numeric += 1;
+            continue;
+        }
+
+        if key_indexes.iter().all(|i| clean.get(*i).unwrap_or("").trim().is_empty()) {
+            stats.empty_keys += 1;
+            error_report::write(&mut log, &ErrorRecord::new(line_num, "EMPTY_KEY", format!("Keys: {}", key_names.join(" + ")))
+                .at(record.position()))?;
+            continue;
+        }
+
+        let fingerprint = key_fingerprint(&clean, &key_indexes, collation);
+        match &mut sink {
+            Sink::Direct { writer, seen } => {
+                if seen.insert(fingerprint) {
+                    writer.write_record(&clean)?;
+                    stats.written += 1;
+                } else {
+                    stats.duplicates += 1;
+                }
+            }
+            Sink::Partitioned { partitions, .. } => {
+                let partition = (fingerprint % partitions.len() as u128) as usize;
+                partitions[partition].write_record(&clean)?;
+            }
+        }
+    }
+    progress.finish();
+    info!("📊 Processed: {} | Invalid: {} | Duplicates: {}",
+        stats.rows, stats.structure_errors + stats.invalid_numeric + stats.empty_keys, stats.duplicates);
+
+    match sink {
+        Sink::Direct { mut writer, .. } => writer.flush()?,
+        Sink::Partitioned { partitions, paths } => {
+            for mut partition in partitions {
+                partition.flush()?;
+            }
+
+            info!("🔍 Pass 2/2: deduplicating {} partitions...", paths.len());
+            let mut writer = WriterBuilder::new()
+                .delimiter(config::delimiter())
+                .quote_style(csv::QuoteStyle::Necessary)
+                .from_writer(create_output(output_path, 1)?);
+            writer.write_record(&expected)?;
+
+            let progress = Progress::rows_of("Partitions", paths.len() as u64);
+            for (i, path) in paths.iter().enumerate() {
+                let mut partition = ReaderBuilder::new()
+                    .has_headers(false)
+                    .from_path(path)?;
+                let mut seen: HashSet<u128> = HashSet::new();
+                while partition.read_record(&mut record)? {
+                    if seen.insert(key_fingerprint(&record, &key_indexes, collation)) {
+                        writer.write_record(&record)?;
+                        stats.written += 1;
+                    } else {
+                        stats.duplicates += 1;
+                    }
+                }
+                progress.set_position(i as u64 + 1);
+                progress.set_detail(&format!("Written: {} | Duplicates: {}", stats.written, stats.duplicates));
+                std::fs::remove_file(path).ok();
+            }
+            progress.finish();
+            info!("📊 Partitions: {} | Written: {} | Duplicates: {}", paths.len(), stats.written, stats.duplicates);
+            writer.flush()?;
+        }
+    }
+    log.flush()?;
+    drop(temp_files);
+
+    let invalid = stats.parse_errors + stats.structure_errors + stats.invalid_numeric + stats.empty_keys;
+    info!();
+    logging::banner("Prepare Summary");
+    info!("📊 Records read:          {}", stats.rows);
+    info!("❌ Parse errors:          {}", stats.parse_errors);
+    info!("❌ Wrong column count:    {}", stats.structure_errors);
+    info!("❌ Invalid Type N:        {}", stats.invalid_numeric);
+    info!("❌ Empty keys:            {}", stats.empty_keys);
+    if stats.defaulted > 0 {
+        info!("🩹 Empty values set to schema default: {}", stats.defaulted);
+    }
+    info!("🔁 Duplicates removed:    {}", stats.duplicates);
+    info!("✅ Records written:       {}", stats.written);
+    info!();
+    info!("📝 Files created:");
+    info!("   Prepared CSV: {}", output_path);
+    info!("   Error log:    {}", error_log_path);
+
+    if invalid > 0 {
+        info!();
+        diagnostics::warn(format!("WARNING: {} invalid records were skipped", invalid));
+        eprintln!("💡 Try: csv_tools summarize-errors \"{}\"", error_log_path);
+    }
+
+    Ok(())
+}
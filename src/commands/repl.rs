@@ -0,0 +1,409 @@
+// Modo interactivo para explorar un CSV: al abrir lee el archivo una vez, cuenta las filas
+// y guarda el byte de inicio de cada `--index-step` registros; después `head`, `tail`, `row`
+// saltan directo al bloque que necesitan y `stats` queda en memoria por columna
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use clap::Args;
+use csv::{ByteRecord, ReaderBuilder, StringRecord, WriterBuilder};
+
+use crate::config;
+use crate::error::CsvToolsError;
+use crate::exit_code::usage_error;
+use crate::file_utils::{format_bytes, get_file_size, open_input, open_input_at};
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+
+const DEFAULT_INDEX_STEP: usize = 10_000;
+const DEFAULT_ROWS: usize = 10;
+// Valores distintos que `stats` cuenta exactamente; pasado el tope informa "≥"
+const DISTINCT_CAP: usize = 1_000_000;
+const TOP_VALUES: usize = 5;
+
+#[derive(Args, Debug)]
+pub struct ReplArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_INDEX_STEP,
+          help = "Records between index entries (smaller = faster jumps, more memory)")]
+    pub index_step: usize,
+}
+
+/// Una línea de la sesión ya interpretada
+#[derive(Debug, PartialEq)]
+enum ReplCommand {
+    Help,
+    Headers,
+    Count,
+    Head(usize),
+    Tail(usize),
+    Row(u64),
+    /// Columna, valor, `!=` y cuántas filas mostrar
+    Filter { column: String, value: String, negate: bool, limit: usize },
+    Stats(String),
+    Quit,
+}
+
+/// Resumen de una columna (se calcula una vez por sesión)
+struct ColumnStats {
+    rows: u64,
+    empty: u64,
+    distinct: usize,
+    capped: bool,
+    numeric: bool,
+    min: Option<String>,
+    max: Option<String>,
+    top: Vec<(String, u64)>,
+}
+
+struct Session {
+    path: String,
+    headers: StringRecord,
+    rows: u64,
+    step: u64,
+    /// Byte de inicio del registro `i * step`
+    index: Vec<u64>,
+    stats: HashMap<usize, ColumnStats>,
+}
+
+/// `repl <file>`: indexa el archivo y atiende comandos de stdin hasta `quit` o fin de entrada
+pub fn repl(args: &ReplArgs) -> Result<(), CsvToolsError> {
+    logging::banner("CSV REPL");
+    info!("📄 Input: {} ({})", args.input, format_bytes(get_file_size(&args.input)?));
+    let mut session = Session::open(&args.input, args.index_step.max(1) as u64)?;
+    info!("📊 Rows: {} | Columns: {} | Index entries: {}", session.rows, session.headers.len(), session.index.len());
+    info!("💡 Type 'help' for commands, 'quit' to exit");
+
+    let interactive = io::stdin().is_terminal();
+    let mut input = io::stdin().lock();
+    let mut line = String::new();
+    loop {
+        if interactive {
+            print!("csv> ");
+            io::stdout().flush()?;
+        }
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+        let command = match parse_command(&line) {
+            Ok(Some(command)) => command,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                continue;
+            }
+        };
+        if command == ReplCommand::Quit {
+            break;
+        }
+        // Un comando que falla no cierra la sesión
+        if let Err(e) = session.run(&command) {
+            eprintln!("❌ {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Interpreta una línea; `None` si está vacía o es un comentario
+fn parse_command(line: &str) -> Result<Option<ReplCommand>, CsvToolsError> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let count = |default: usize| -> Result<usize, CsvToolsError> {
+        if rest.is_empty() {
+            return Ok(default);
+        }
+        rest.parse().map_err(|_| usage_error(format!("Invalid number '{}'", rest)))
+    };
+
+    let command = match name.to_lowercase().as_str() {
+        "help" | "?" => ReplCommand::Help,
+        "headers" | "columns" => ReplCommand::Headers,
+        "count" => ReplCommand::Count,
+        "head" => ReplCommand::Head(count(DEFAULT_ROWS)?),
+        "tail" => ReplCommand::Tail(count(DEFAULT_ROWS)?),
+        "row" => match count(0)? {
+            0 => return Err(usage_error("Usage: row <N> (first data row = 1)")),
+            n => ReplCommand::Row(n as u64),
+        },
+        "filter" => {
+            // `filter Col=Valor [N]`; el valor puede tener espacios si va entre comillas
+            let (expr, limit) = match rest.rsplit_once(char::is_whitespace) {
+                Some((expr, n)) if !expr.ends_with('=') && n.parse::<usize>().is_ok() => (expr.trim(), n.parse().unwrap()),
+                _ => (rest, DEFAULT_ROWS * 2),
+            };
+            let (column, value, negate) = match expr.split_once("!=") {
+                Some((c, v)) => (c, v, true),
+                None => match expr.split_once('=') {
+                    Some((c, v)) => (c, v, false),
+                    None => return Err(usage_error("Usage: filter <Column>=<value> [N] (or <Column>!=<value>)")),
+                },
+            };
+            ReplCommand::Filter {
+                column: column.trim().to_string(),
+                value: value.trim().trim_matches('"').to_string(),
+                negate,
+                limit,
+            }
+        }
+        "stats" if !rest.is_empty() => ReplCommand::Stats(rest.to_string()),
+        "stats" => return Err(usage_error("Usage: stats <Column>")),
+        "quit" | "exit" | "q" => ReplCommand::Quit,
+        other => return Err(usage_error(format!("Unknown command '{}' (type 'help')", other))),
+    };
+    Ok(Some(command))
+}
+
+impl Session {
+    /// Primera lectura completa: cuenta filas y arma el índice de bytes
+    fn open(path: &str, step: u64) -> Result<Self, CsvToolsError> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(config::delimiter())
+            .flexible(true)
+            .from_reader(open_input(path)?);
+        let headers = reader.headers()?.clone();
+
+        let mut index = Vec::new();
+        let mut rows = 0u64;
+        let mut record = ByteRecord::new();
+        let mut progress = Progress::rows("Indexing");
+        loop {
+            let offset = reader.position().byte();
+            if !reader.read_byte_record(&mut record)? {
+                break;
+            }
+            if rows.is_multiple_of(step) {
+                index.push(offset);
+            }
+            rows += 1;
+            progress.update(rows, String::new);
+        }
+        progress.finish();
+
+        Ok(Session { path: path.to_string(), headers, rows, step, index, stats: HashMap::new() })
+    }
+
+    fn run(&mut self, command: &ReplCommand) -> Result<(), CsvToolsError> {
+        match command {
+            ReplCommand::Help => print_help(),
+            ReplCommand::Headers => {
+                for (i, h) in self.headers.iter().enumerate() {
+                    println!("  [{:2}] {}", i, h);
+                }
+            }
+            ReplCommand::Count => println!("{} rows", self.rows),
+            ReplCommand::Head(n) => self.print_range(0, *n as u64)?,
+            ReplCommand::Tail(n) => self.print_range(self.rows.saturating_sub(*n as u64), *n as u64)?,
+            ReplCommand::Row(n) => self.print_row(*n)?,
+            ReplCommand::Filter { column, value, negate, limit } => self.filter(column, value, *negate, *limit)?,
+            ReplCommand::Stats(column) => self.print_stats(column)?,
+            ReplCommand::Quit => {}
+        }
+        Ok(())
+    }
+
+    fn column(&self, name: &str) -> Result<usize, CsvToolsError> {
+        self.headers.iter()
+            .position(|h| h.trim() == name)
+            .ok_or_else(|| usage_error(format!("Column '{}' not found (type 'headers')", name)))
+    }
+
+    /// Lector posicionado en el registro `first` (0 = primera fila de datos): salta a la
+    /// entrada del índice anterior y descarta el resto
+    fn reader_at(&self, first: u64) -> Result<csv::Reader<Box<dyn BufRead>>, CsvToolsError> {
+        let slot = ((first / self.step) as usize).min(self.index.len().saturating_sub(1));
+        let offset = self.index.get(slot).copied().unwrap_or(0);
+        let mut reader = ReaderBuilder::new()
+            .delimiter(config::delimiter())
+            .has_headers(offset == 0)
+            .flexible(true)
+            .from_reader(open_input_at(&self.path, offset)?);
+        let mut record = ByteRecord::new();
+        for _ in (slot as u64 * self.step)..first {
+            if !reader.read_byte_record(&mut record)? {
+                break;
+            }
+        }
+        Ok(reader)
+    }
+
+    /// Header + `count` filas desde `first`, como CSV en stdout
+    fn print_range(&self, first: u64, count: u64) -> Result<(), CsvToolsError> {
+        let mut reader = self.reader_at(first)?;
+        let mut writer = WriterBuilder::new().delimiter(config::delimiter()).flexible(true).from_writer(io::stdout());
+        writer.write_record(&self.headers)?;
+        let mut record = ByteRecord::new();
+        for _ in 0..count {
+            if !reader.read_byte_record(&mut record)? {
+                break;
+            }
+            writer.write_byte_record(&record)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Una fila (1 = primera de datos) como `columna: valor`
+    fn print_row(&self, n: u64) -> Result<(), CsvToolsError> {
+        if n > self.rows {
+            return Err(usage_error(format!("Row {} out of range (1-{})", n, self.rows)));
+        }
+        let mut reader = self.reader_at(n - 1)?;
+        let mut record = ByteRecord::new();
+        reader.read_byte_record(&mut record)?;
+        let width = self.headers.iter().map(|h| h.chars().count()).max().unwrap_or(0);
+        for (i, value) in record.iter().enumerate() {
+            let name = self.headers.get(i).unwrap_or("(extra)");
+            println!("  {:<width$} : {}", name, String::from_utf8_lossy(value), width = width);
+        }
+        if record.len() != self.headers.len() {
+            println!("  ⚠️  {} fields (header has {})", record.len(), self.headers.len());
+        }
+        Ok(())
+    }
+
+    /// Recorre el archivo; muestra las primeras `limit` coincidencias y el total
+    fn filter(&self, column: &str, value: &str, negate: bool, limit: usize) -> Result<(), CsvToolsError> {
+        let idx = self.column(column)?;
+        let mut reader = self.reader_at(0)?;
+        let mut writer = WriterBuilder::new().delimiter(config::delimiter()).flexible(true).from_writer(io::stdout());
+        writer.write_record(&self.headers)?;
+        let mut record = ByteRecord::new();
+        let mut matches = 0u64;
+        while reader.read_byte_record(&mut record)? {
+            let field = record.get(idx).unwrap_or(b"");
+            if (field == value.as_bytes()) != negate {
+                matches += 1;
+                if matches <= limit as u64 {
+                    writer.write_byte_record(&record)?;
+                }
+            }
+        }
+        writer.flush()?;
+        println!("({} of {} rows match{})", matches, self.rows,
+                 if matches > limit as u64 { format!(", first {} shown", limit) } else { String::new() });
+        Ok(())
+    }
+
+    fn print_stats(&mut self, column: &str) -> Result<(), CsvToolsError> {
+        let idx = self.column(column)?;
+        if !self.stats.contains_key(&idx) {
+            let stats = self.compute_stats(idx)?;
+            self.stats.insert(idx, stats);
+        }
+        let s = &self.stats[&idx];
+        println!("  Column   : {}", column);
+        println!("  Rows     : {} ({} empty)", s.rows, s.empty);
+        println!("  Distinct : {}{}", if s.capped { "≥ " } else { "" }, s.distinct);
+        println!("  Type     : {}", if s.numeric { "numeric" } else { "text" });
+        if let (Some(min), Some(max)) = (&s.min, &s.max) {
+            println!("  Min      : {}", min);
+            println!("  Max      : {}", max);
+        }
+        if !s.top.is_empty() {
+            println!("  Top values:");
+            for (value, count) in &s.top {
+                println!("    {:>9} × '{}'", count, value);
+            }
+        }
+        Ok(())
+    }
+
+    fn compute_stats(&self, idx: usize) -> Result<ColumnStats, CsvToolsError> {
+        let mut reader = self.reader_at(0)?;
+        let mut record = ByteRecord::new();
+        let mut counts: HashMap<Vec<u8>, u64> = HashMap::new();
+        let mut stats = ColumnStats {
+            rows: 0, empty: 0, distinct: 0, capped: false, numeric: true, min: None, max: None, top: Vec::new(),
+        };
+        let (mut min_num, mut max_num) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut min_text, mut max_text): (Option<Vec<u8>>, Option<Vec<u8>>) = (None, None);
+
+        while reader.read_byte_record(&mut record)? {
+            stats.rows += 1;
+            let value = record.get(idx).unwrap_or(b"");
+            if value.iter().all(u8::is_ascii_whitespace) {
+                stats.empty += 1;
+                continue;
+            }
+            let room = counts.len() < DISTINCT_CAP;
+            match counts.get_mut(value) {
+                Some(count) => *count += 1,
+                None if room => {
+                    counts.insert(value.to_vec(), 1);
+                }
+                None => stats.capped = true,
+            }
+            if stats.numeric {
+                match std::str::from_utf8(value).ok().and_then(|v| v.trim().parse::<f64>().ok()) {
+                    Some(n) => {
+                        min_num = min_num.min(n);
+                        max_num = max_num.max(n);
+                    }
+                    None => stats.numeric = false,
+                }
+            }
+            if min_text.as_deref().is_none_or(|m| value < m) {
+                min_text = Some(value.to_vec());
+            }
+            if max_text.as_deref().is_none_or(|m| value > m) {
+                max_text = Some(value.to_vec());
+            }
+        }
+
+        stats.numeric &= stats.rows > stats.empty;
+        if stats.numeric {
+            stats.min = Some(min_num.to_string());
+            stats.max = Some(max_num.to_string());
+        } else {
+            stats.min = min_text.map(|v| String::from_utf8_lossy(&v).into_owned());
+            stats.max = max_text.map(|v| String::from_utf8_lossy(&v).into_owned());
+        }
+        stats.distinct = counts.len();
+        let mut top: Vec<(Vec<u8>, u64)> = counts.into_iter().collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        stats.top = top.into_iter()
+            .take(TOP_VALUES)
+            .map(|(v, c)| (String::from_utf8_lossy(&v).into_owned(), c))
+            .collect();
+        Ok(stats)
+    }
+}
+
+fn print_help() {
+    println!("  headers                      List columns with their index");
+    println!("  count                        Number of data rows");
+    println!("  head [N]                     First N rows (default {})", DEFAULT_ROWS);
+    println!("  tail [N]                     Last N rows (default {})", DEFAULT_ROWS);
+    println!("  row <N>                      One row as column: value (first data row = 1)");
+    println!("  filter <Col>=<value> [N]     Rows where Col equals value (!= to negate); first N shown (default {})", DEFAULT_ROWS * 2);
+    println!("  stats <Col>                  Empty count, distinct values, min/max, top {} values (cached)", TOP_VALUES);
+    println!("  quit                         Exit");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("head").unwrap(), Some(ReplCommand::Head(DEFAULT_ROWS)));
+        assert_eq!(parse_command("tail 5").unwrap(), Some(ReplCommand::Tail(5)));
+        assert_eq!(parse_command("stats Cuil").unwrap(), Some(ReplCommand::Stats("Cuil".to_string())));
+        assert_eq!(parse_command("filter Status=ACTIVE 3").unwrap(), Some(ReplCommand::Filter {
+            column: "Status".to_string(), value: "ACTIVE".to_string(), negate: false, limit: 3,
+        }));
+        assert_eq!(parse_command("filter Razon != \"ACME SA\"").unwrap(), Some(ReplCommand::Filter {
+            column: "Razon".to_string(), value: "ACME SA".to_string(), negate: true, limit: DEFAULT_ROWS * 2,
+        }));
+        assert_eq!(parse_command("  ").unwrap(), None);
+        assert!(parse_command("row 0").is_err());
+        assert!(parse_command("drop Cuil").is_err());
+    }
+}
@@ -0,0 +1,1012 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use csv::{ReaderBuilder, StringRecord};
+use serde::{Deserialize, Serialize};
+
+use clap::{Args, Subcommand, ValueEnum};
+use crate::cli::FILES_HELP;
+use crate::cli_args::list_values;
+use crate::config;
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::exit_code;
+use crate::error_report::{self, ErrorRecord};
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::models::get_expected_headers;
+use crate::file_utils::{open_input, resolve_inputs};
+use crate::schema::{coerce_value, ColumnSpec, ColumnType, KeyRole, Schema};
+use crate::sink::open_sink;
+
+/// Reglas de migración entre layouts (`--mapping map.yaml`)
+///
+/// ```yaml
+/// rename:
+///   NroDocumento: NroDoc      # columna vieja → columna nueva
+/// defaults:
+///   IdEntidad: "0"            # columnas nuevas que no existen en el origen
+/// drop: [Observaciones]       # columnas viejas que se descartan a propósito
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct MigrationMapping {
+    #[serde(default)]
+    pub rename: BTreeMap<String, String>,
+    #[serde(default)]
+    pub defaults: BTreeMap<String, String>,
+    #[serde(default)]
+    pub drop: Vec<String>,
+}
+
+impl MigrationMapping {
+    pub fn load(path: &str) -> Result<Self, CsvToolsError> {
+        let content = std::fs::read_to_string(path)?;
+        let mapping: MigrationMapping = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid mapping file '{}': {}", path, e))?;
+        Ok(mapping)
+    }
+}
+
+// Filas del archivo de muestra que lee `schema docs` y ejemplos por columna
+const DOCS_SAMPLE_ROWS: usize = 1000;
+const DOCS_EXAMPLES_PER_COLUMN: usize = 3;
+
+/// Origen de cada columna del layout nuevo
+enum ColumnSource {
+    Input(usize),
+    Default(String),
+}
+
+#[derive(Args, Debug)]
+pub struct MigrateArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_name = "NEW_SCHEMA.yaml", help = "Target schema (column order, types, defaults)")]
+    pub to: String,
+    #[arg(long, value_name = "OLD_SCHEMA.yaml", help = "Source schema (reports columns dropped implicitly)")]
+    pub from: Option<String>,
+    #[arg(long, value_name = "MAP.yaml", help = "Rename/default/drop rules")]
+    pub mapping: Option<String>,
+}
+
+/// Transforma el layout viejo al nuevo (renombra, agrega con defaults, descarta y
+/// reordena según el schema destino) validando cada valor contra ese schema
+pub fn migrate(args: &MigrateArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let target = Schema::load(&args.to)?;
+    let source = match &args.from {
+        Some(spec) => Some(Schema::load(spec)?),
+        None => None,
+    };
+    let mapping = match &args.mapping {
+        Some(path) => MigrationMapping::load(path)?,
+        None => MigrationMapping::default(),
+    };
+
+    logging::banner("Schema Migration");
+    info!("📄 Input:  {}", input_path);
+    info!("📄 Output: {}", output_path);
+    if let Some(s) = &source {
+        info!("📋 From:   {} ({} columns)", s.name, s.columns.len());
+    }
+    info!("📋 To:     {} ({} columns)", target.name, target.columns.len());
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers = reader.headers()?.clone();
+    let header_index = |name: &str| headers.iter().position(|h| h.trim() == name);
+
+    // El archivo de entrada debería coincidir con el layout viejo
+    if let Some(s) = &source {
+        for column in &s.columns {
+            if header_index(&column.name).is_none() {
+                diagnostics::warn(format!("Source schema column '{}' not found in input", column.name));
+            }
+        }
+    }
+
+    // Nombre nuevo → nombre viejo
+    let renamed_from: BTreeMap<&str, &str> = mapping.rename.iter()
+        .map(|(old, new)| (new.as_str(), old.as_str()))
+        .collect();
+
+    info!("📊 Column plan:");
+    let mut plan = Vec::with_capacity(target.columns.len());
+    let mut used = vec![false; headers.len()];
+
+    for column in &target.columns {
+        let source_name = renamed_from.get(column.name.as_str()).copied().unwrap_or(&column.name);
+
+        match header_index(source_name) {
+            Some(idx) => {
+                used[idx] = true;
+                if source_name != column.name {
+                    info!("   {} ← {} (renamed)", column.name, source_name);
+                } else {
+                    info!("   {} ← {}", column.name, source_name);
+                }
+                plan.push(ColumnSource::Input(idx));
+            }
+            None => {
+                let default = mapping.defaults.get(&column.name).cloned();
+                if default.is_none() && column.key.is_some() {
+                    return Err(format!(
+                        "Key column '{}' has no source column and no default in the mapping", column.name
+                    ).into());
+                }
+                let default = default.unwrap_or_default();
+                info!("   {} ← default '{}' (added)", column.name, default);
+                plan.push(ColumnSource::Default(default));
+            }
+        }
+    }
+
+    for (idx, header) in headers.iter().enumerate() {
+        if used[idx] {
+            continue;
+        }
+        if mapping.drop.iter().any(|d| d == header.trim()) {
+            info!("   ✂️  {} (dropped)", header);
+        } else {
+            diagnostics::warn(format!("Input column '{}' is not in the target schema and not listed in drop (dropped)", header));
+        }
+    }
+    info!();
+
+    let error_log_path = config::error_log_path(output_path, ".migration_errors.log");
+    let mut log = BufWriter::new(File::create(&error_log_path)?);
+
+    writeln!(log, "# Schema Migration Error Log")?;
+    writeln!(log, "# Input: {}", input_path)?;
+    writeln!(log, "# Output: {}", output_path)?;
+    writeln!(log, "# Target schema: {}", target.name)?;
+    writeln!(log, "#")?;
+    writeln!(log, "# Format: [LINE] STATUS | Details")?;
+    writeln!(log, "# -------------------------------------------------------")?;
+
+    let mut writer = open_sink(output_path)?;
+    writer.write_header(&target.columns.iter().map(|c| c.name.as_str()).collect())?;
+
+    let mut processed = 0usize;
+    let mut invalid_values = 0usize;
+    let mut rows_with_errors = 0usize;
+
+    info!("🔍 Migrating records...");
+
+    let mut progress = Progress::rows("Processed");
+    for (idx, result) in reader.records().enumerate() {
+        let line_num = idx + 2;
+        processed += 1;
+
+        let record = match result {
+            Ok(r) => r,
+            Err(e) => {
+                rows_with_errors += 1;
+                error_report::write(&mut log, &ErrorRecord::parse_error(line_num, &e, format!("CSV parsing failed: {}", e)))?;
+                continue;
+            }
+        };
+
+        let mut row_failed = false;
+        let mut new_record = StringRecord::with_capacity(record.as_slice().len(), plan.len());
+
+        for (column, source) in target.columns.iter().zip(&plan) {
+            let value = match source {
+                ColumnSource::Input(i) => record.get(*i).unwrap_or(""),
+                ColumnSource::Default(v) => v.as_str(),
+            };
+
+            // Validación contra el schema destino (el valor se escribe sin cambios)
+            if let Err(e) = coerce_value(value, column) {
+                row_failed = true;
+                invalid_values += 1;
+                writeln!(
+                    log,
+                    "[LINE {}] ❌ SCHEMA_MISMATCH | Column='{}' | Type={} | Value='{}' | {}",
+                    line_num, column.name, column.column_type, value, e
+                )?;
+                error_report::emit(&ErrorRecord::new(line_num, "SCHEMA_MISMATCH", format!("Type={} | {}", column.column_type, e))
+                    .at(record.position()).column(&column.name).value(value))?;
+            }
+            new_record.push_field(value);
+        }
+
+        if row_failed {
+            rows_with_errors += 1;
+        }
+        writer.write_record(&new_record)?;
+
+        progress.update(processed as u64, || format!("Invalid values: {}", invalid_values));
+    }
+
+    writer.finish()?;
+    log.flush()?;
+    progress.finish();
+
+    info!("📊 Processed: {} | Invalid values: {}", processed, invalid_values);
+    info!();
+    logging::banner("Migration Summary");
+    info!("📊 Records migrated:        {}", processed);
+    info!("❌ Values failing target:   {} (in {} rows)", invalid_values, rows_with_errors);
+    info!();
+    info!("📝 Files created:");
+    info!("   Migrated CSV: {}", output_path);
+    info!("   Error log:    {}", error_log_path);
+
+    if rows_with_errors > 0 {
+        info!();
+        diagnostics::warn(format!("WARNING: {} rows do not satisfy the target schema", rows_with_errors));
+        eprintln!("💡 Try: csv_tools summarize-errors \"{}\"", error_log_path);
+    }
+
+    Ok(())
+}
+
+#[derive(Args, Debug)]
+pub struct ReorderToModelArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_name = "MODEL_TYPE",
+          help = "Model whose column order is applied [default: default_model from the config file]")]
+    pub model: Option<String>,
+    #[arg(long, help = "Drop columns not in the model instead of failing")]
+    pub drop_extra: bool,
+}
+
+/// Reordena las columnas al orden exacto del modelo DynamoDB (el importer mapea por posición);
+/// falla si falta alguna columna del modelo y solo descarta extras con `--drop-extra`
+pub fn reorder_to_model(args: &ReorderToModelArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let model = config::model_or_default(args.model.as_deref())?;
+    let model = model.as_str();
+    let drop_extra = args.drop_extra;
+    let expected = get_expected_headers(model)?;
+
+    logging::banner("Reorder Columns to Model");
+    info!("📄 Input:  {}", input_path);
+    info!("📄 Output: {}", output_path);
+    info!("📋 Model:  {} ({} columns)", model, expected.len());
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers = reader.headers()?.clone();
+
+    let mut order = Vec::with_capacity(expected.len());
+    let mut missing = Vec::new();
+    for column in &expected {
+        match headers.iter().position(|h| h.trim() == *column) {
+            Some(idx) => order.push(idx),
+            None => missing.push(*column),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!(
+            "Input is missing {} column(s) required by model '{}': {}", missing.len(), model, missing.join(", ")
+        ).into());
+    }
+
+    let extras: Vec<&str> = headers.iter()
+        .enumerate()
+        .filter(|(idx, _)| !order.contains(idx))
+        .map(|(_, h)| h)
+        .collect();
+    if !extras.is_empty() {
+        if !drop_extra {
+            return Err(format!(
+                "Input has {} column(s) not in model '{}': {} (use --drop-extra to discard them)",
+                extras.len(), model, extras.join(", ")
+            ).into());
+        }
+        diagnostics::warn(format!("Dropping {} column(s) not in model '{}': {}", extras.len(), model, extras.join(", ")));
+    }
+
+    let already_ordered = extras.is_empty() && order.iter().enumerate().all(|(i, idx)| i == *idx);
+    if already_ordered {
+        info!("✅ Columns already in model order");
+    } else {
+        info!("📊 Column plan:");
+        for (i, (column, idx)) in expected.iter().zip(&order).enumerate() {
+            if i == *idx {
+                info!("   {:>2}. {}", i + 1, column);
+            } else {
+                info!("   {:>2}. {} ← input column {}", i + 1, column, idx + 1);
+            }
+        }
+    }
+    info!();
+
+    let mut writer = open_sink(output_path)?;
+    writer.write_header(&expected.iter().collect())?;
+
+    let mut processed = 0usize;
+    let mut short_rows = 0usize;
+    let mut new_record = StringRecord::with_capacity(0, expected.len());
+
+    let mut progress = Progress::rows("Processed");
+    for result in reader.records() {
+        let record = result?;
+        processed += 1;
+
+        if record.len() < headers.len() {
+            short_rows += 1;
+        }
+        new_record.clear();
+        for idx in &order {
+            new_record.push_field(record.get(*idx).unwrap_or(""));
+        }
+        writer.write_record(&new_record)?;
+
+        progress.update(processed as u64, String::new);
+    }
+
+    writer.finish()?;
+    progress.finish();
+
+    info!("📊 Processed: {}", processed);
+    info!();
+    logging::banner("Reorder Summary");
+    info!("📊 Records written:   {}", processed);
+    info!("📋 Columns reordered: {}", if already_ordered { "no (already canonical)" } else { "yes" });
+    if !extras.is_empty() {
+        info!("✂️  Columns dropped:   {}", extras.len());
+    }
+    info!("💾 Output: {}", output_path);
+
+    if short_rows > 0 {
+        diagnostics::warn(format!("{} rows had fewer fields than the header (missing values written empty)", short_rows));
+    }
+
+    Ok(())
+}
+
+/// Proyección reducida para compartir con terceros: solo las columnas pedidas, en el
+/// orden de --columns
+#[derive(Args, Debug)]
+pub struct SelectArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Columns to keep, in output order")]
+    pub columns: Vec<String>,
+}
+
+/// Quita las columnas de --columns y conserva el resto en su orden
+#[derive(Args, Debug)]
+pub struct DropArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Columns to remove")]
+    pub columns: Vec<String>,
+}
+
+pub fn select(args: &SelectArgs) -> Result<(), CsvToolsError> {
+    let columns = list_values(&args.columns);
+    let mut reader = open_csv(&args.input)?;
+    let headers = reader.headers()?.clone();
+
+    let mut order = Vec::with_capacity(columns.len());
+    for column in &columns {
+        if columns.iter().filter(|c| *c == column).count() > 1 {
+            return Err(exit_code::usage_error(format!("Column '{}' is listed more than once", column)));
+        }
+        order.push(column_index(&headers, column)?);
+    }
+
+    logging::banner("Select Columns");
+    info!("📄 Input:  {}", args.input);
+    info!("📄 Output: {}", args.output);
+    info!("📋 Keep:   {} of {} columns ({})", order.len(), headers.len(), columns.join(", "));
+    info!();
+    let plan: Vec<ColumnSource> = order.into_iter().map(ColumnSource::Input).collect();
+    project(&mut reader, &plan_headers(&headers, &plan), &plan, &args.output)
+}
+
+pub fn drop_columns(args: &DropArgs) -> Result<(), CsvToolsError> {
+    let columns = list_values(&args.columns);
+    let mut reader = open_csv(&args.input)?;
+    let headers = reader.headers()?.clone();
+
+    let mut dropped = Vec::with_capacity(columns.len());
+    for column in &columns {
+        dropped.push(column_index(&headers, column)?);
+    }
+    let order: Vec<usize> = (0..headers.len()).filter(|i| !dropped.contains(i)).collect();
+    if order.is_empty() {
+        return Err(exit_code::usage_error("Dropping every column would leave an empty file"));
+    }
+
+    logging::banner("Drop Columns");
+    info!("📄 Input:  {}", args.input);
+    info!("📄 Output: {}", args.output);
+    info!("✂️  Drop:   {} ({} of {} columns left)", columns.join(", "), order.len(), headers.len());
+    info!();
+    let plan: Vec<ColumnSource> = order.into_iter().map(ColumnSource::Input).collect();
+    project(&mut reader, &plan_headers(&headers, &plan), &plan, &args.output)
+}
+
+/// Renombra columnas del header (`viejo=nuevo`) sin tocar las filas, para adaptar
+/// exports de otros equipos a los nombres del modelo
+#[derive(Args, Debug)]
+pub struct RenameColumnsArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "OLD=NEW,...", value_delimiter = ',', required_unless_present = "map_file",
+          help = "Renames as old=new pairs")]
+    pub map: Vec<String>,
+    #[arg(long, value_name = "FILE", help = "File with one old=new pair per line ('#' comments allowed)")]
+    pub map_file: Option<String>,
+}
+
+pub fn rename_columns(args: &RenameColumnsArgs) -> Result<(), CsvToolsError> {
+    let mut pairs: Vec<String> = list_values(&args.map).into_iter().map(str::to_string).collect();
+    if let Some(path) = &args.map_file {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read rename map '{}': {}", path, e))?;
+        pairs.extend(content.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')).map(str::to_string));
+    }
+
+    let mut reader = open_csv(&args.input)?;
+    let headers = reader.headers()?.clone();
+    let mut new_headers: Vec<String> = headers.iter().map(|h| h.trim().to_string()).collect();
+    let mut renamed: Vec<(usize, String)> = Vec::new();
+    for pair in &pairs {
+        let Some((old, new)) = pair.split_once('=').map(|(o, n)| (o.trim(), n.trim())).filter(|(o, n)| !o.is_empty() && !n.is_empty()) else {
+            return Err(exit_code::usage_error(format!("Invalid rename '{}' (expected old=new)", pair)));
+        };
+        let idx = column_index(&headers, old)?;
+        if renamed.iter().any(|(i, _)| *i == idx) {
+            return Err(exit_code::usage_error(format!("Column '{}' is renamed more than once", old)));
+        }
+        new_headers[idx] = new.to_string();
+        renamed.push((idx, new.to_string()));
+    }
+    for (i, name) in new_headers.iter().enumerate() {
+        if new_headers[..i].contains(name) {
+            return Err(exit_code::usage_error(format!("Renaming would leave column '{}' twice in the header", name)));
+        }
+    }
+
+    logging::banner("Rename Columns");
+    info!("📄 Input:  {}", args.input);
+    info!("📄 Output: {}", args.output);
+    for (idx, new) in &renamed {
+        info!("✏️  {} → {}", headers[*idx].trim(), new);
+    }
+    info!();
+
+    let plan: Vec<ColumnSource> = (0..headers.len()).map(ColumnSource::Input).collect();
+    project(&mut reader, &StringRecord::from(new_headers), &plan, &args.output)
+}
+
+/// Agrega al final columnas con un valor constante (columnas de auditoría que el
+/// modelo exige y el extracto de origen no trae). `--name`/`--value` se repiten en pares
+#[derive(Args, Debug)]
+pub struct AddColumnArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "COLUMN", required = true, help = "New column name (repeatable)")]
+    pub name: Vec<String>,
+    #[arg(long, value_name = "VALUE", required = true, allow_hyphen_values = true,
+          help = "Value for every row of the matching --name (repeatable, same order)")]
+    pub value: Vec<String>,
+}
+
+pub fn add_column(args: &AddColumnArgs) -> Result<(), CsvToolsError> {
+    if args.name.len() != args.value.len() {
+        return Err(exit_code::usage_error(format!(
+            "Got {} --name and {} --value: each new column needs exactly one value", args.name.len(), args.value.len())));
+    }
+
+    let mut reader = open_csv(&args.input)?;
+    let headers = reader.headers()?.clone();
+    let mut out_headers: Vec<String> = headers.iter().map(str::to_string).collect();
+    let mut plan: Vec<ColumnSource> = (0..headers.len()).map(ColumnSource::Input).collect();
+    for (name, value) in args.name.iter().zip(&args.value) {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(exit_code::usage_error("--name cannot be empty"));
+        }
+        if out_headers.iter().any(|h| h.trim() == name) {
+            return Err(exit_code::usage_error(format!("Column '{}' already exists", name)));
+        }
+        out_headers.push(name.to_string());
+        plan.push(ColumnSource::Default(value.clone()));
+    }
+
+    logging::banner("Add Columns");
+    info!("📄 Input:  {}", args.input);
+    info!("📄 Output: {}", args.output);
+    for (name, value) in args.name.iter().zip(&args.value) {
+        info!("➕ {} = \"{}\"", name.trim(), value);
+    }
+    info!();
+    project(&mut reader, &StringRecord::from(out_headers), &plan, &args.output)
+}
+
+/// Posición de `column` en el header; error de uso si no está
+fn column_index(headers: &StringRecord, column: &str) -> Result<usize, CsvToolsError> {
+    headers.iter().position(|h| h.trim() == column)
+        .ok_or_else(|| exit_code::usage_error(format!(
+            "Column '{}' not found in header ({})", column, headers.iter().collect::<Vec<_>>().join(", "))))
+}
+
+/// Header de salida de `plan` cuando todas las columnas vienen de la entrada
+fn plan_headers(headers: &StringRecord, plan: &[ColumnSource]) -> StringRecord {
+    plan.iter().filter_map(|source| match source {
+        ColumnSource::Input(i) => Some(&headers[*i]),
+        ColumnSource::Default(_) => None,
+    }).collect()
+}
+
+/// Escribe cada fila según `plan` bajo `out_headers`; el writer CSV vuelve a poner
+/// comillas donde hacen falta (delimitador, comillas o saltos de línea dentro del valor)
+fn project(reader: &mut csv::Reader<Box<dyn std::io::BufRead>>, out_headers: &StringRecord, plan: &[ColumnSource], output_path: &str) -> Result<(), CsvToolsError> {
+    let input_columns = reader.headers()?.len();
+    let mut writer = open_sink(output_path)?;
+    writer.write_header(out_headers)?;
+
+    let mut processed = 0usize;
+    let mut short_rows = 0usize;
+    let mut record = StringRecord::new();
+    let mut new_record = StringRecord::with_capacity(0, plan.len());
+
+    let mut progress = Progress::rows("Processed");
+    while reader.read_record(&mut record)? {
+        processed += 1;
+        if record.len() < input_columns {
+            short_rows += 1;
+        }
+        new_record.clear();
+        for source in plan {
+            new_record.push_field(match source {
+                ColumnSource::Input(i) => record.get(*i).unwrap_or(""),
+                ColumnSource::Default(v) => v.as_str(),
+            });
+        }
+        writer.write_record(&new_record)?;
+        progress.update(processed as u64, String::new);
+    }
+    writer.finish()?;
+    progress.finish();
+
+    info!("📊 Records written: {}", processed);
+    info!("💾 Output: {}", output_path);
+    if short_rows > 0 {
+        diagnostics::warn(format!("{} rows had fewer fields than the header (missing values written empty)", short_rows));
+    }
+    Ok(())
+}
+
+fn open_csv(input: &str) -> Result<csv::Reader<Box<dyn std::io::BufRead>>, CsvToolsError> {
+    Ok(ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input)?))
+}
+
+/// Une CSV cuyos headers difieren (columnas en otro orden, faltantes o de más)
+/// mapeando cada columna por nombre al layout destino: las faltantes se escriben
+/// vacías y las que sobran se descartan e informan por archivo
+#[derive(Args, Debug)]
+pub struct MergeReconcileArgs {
+    #[arg(value_name = "FILES", help = FILES_HELP)]
+    pub inputs: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "SCHEMA.yaml|MODEL", conflicts_with = "union",
+          help = "Target columns [default: header of the first file]")]
+    pub schema: Option<String>,
+    #[arg(long, help = "Target columns = every column seen in any file, in order of first appearance")]
+    pub union: bool,
+}
+
+/// Columnas de un archivo respecto del layout destino
+#[derive(Debug, Serialize)]
+struct FileReconciliation {
+    file: String,
+    rows: usize,
+    missing: Vec<String>,
+    extra: Vec<String>,
+    reordered: bool,
+}
+
+pub fn merge_reconcile(args: &MergeReconcileArgs) -> Result<(), CsvToolsError> {
+    let output_path = args.output.as_str();
+    let file_names = resolve_inputs(&args.inputs)?;
+
+    let open_reader = |path: &str| -> Result<csv::Reader<Box<dyn std::io::BufRead>>, CsvToolsError> {
+        Ok(ReaderBuilder::new()
+            .delimiter(config::delimiter())
+            .has_headers(true)
+            .flexible(true)
+            .from_reader(open_input(path)?))
+    };
+
+    // Layout destino: schema/modelo, unión de headers o header del primer archivo
+    let target: Vec<String> = match &args.schema {
+        Some(spec) => Schema::load(spec)?.columns.iter().map(|c| c.name.clone()).collect(),
+        None if args.union => {
+            let mut columns: Vec<String> = Vec::new();
+            for file in &file_names {
+                for h in open_reader(file)?.headers()?.iter() {
+                    if !columns.iter().any(|c| c == h.trim()) {
+                        columns.push(h.trim().to_string());
+                    }
+                }
+            }
+            columns
+        }
+        None => open_reader(&file_names[0])?.headers()?.iter().map(|h| h.trim().to_string()).collect(),
+    };
+
+    logging::banner("Merge with Header Reconciliation");
+    info!("📂 Files: {}", file_names.len());
+    info!("📄 Output: {}", output_path);
+    info!("📋 Target columns ({}): {}", target.len(), target.join(", "));
+    info!();
+
+    let mut writer = open_sink(output_path)?;
+    writer.write_header(&target.iter().collect())?;
+
+    let mut files = Vec::with_capacity(file_names.len());
+    let mut record = StringRecord::new();
+    let mut new_record = StringRecord::with_capacity(0, target.len());
+    let mut processed = 0usize;
+
+    let mut progress = Progress::rows("Processed");
+    for file in &file_names {
+        let mut reader = open_reader(file)?;
+        let headers = reader.headers()?.clone();
+
+        // Posición de cada columna destino en este archivo (None = se escribe vacía)
+        let sources: Vec<Option<usize>> = target.iter()
+            .map(|column| headers.iter().position(|h| h.trim() == column))
+            .collect();
+        let mut info = FileReconciliation {
+            file: file.clone(),
+            rows: 0,
+            missing: target.iter().zip(&sources).filter(|(_, s)| s.is_none()).map(|(c, _)| c.clone()).collect(),
+            extra: headers.iter().filter(|h| !target.iter().any(|c| c == h.trim())).map(str::to_string).collect(),
+            reordered: false,
+        };
+        info.reordered = sources.iter().flatten().enumerate().any(|(i, idx)| i != *idx);
+
+        if !info.missing.is_empty() {
+            diagnostics::warn(format!("{}: {} missing column(s) written empty: {}", file, info.missing.len(), info.missing.join(", ")));
+        }
+        if !info.extra.is_empty() {
+            diagnostics::warn(format!("{}: {} extra column(s) dropped: {}", file, info.extra.len(), info.extra.join(", ")));
+        }
+
+        while reader.read_record(&mut record)? {
+            new_record.clear();
+            for source in &sources {
+                new_record.push_field(source.and_then(|idx| record.get(idx)).unwrap_or(""));
+            }
+            writer.write_record(&new_record)?;
+            info.rows += 1;
+            processed += 1;
+            progress.update(processed as u64, String::new);
+        }
+        files.push(info);
+    }
+    writer.finish()?;
+    progress.finish();
+
+    info!();
+    logging::banner("Reconciliation Summary");
+    for f in &files {
+        let status = match (f.missing.is_empty(), f.extra.is_empty(), f.reordered) {
+            (true, true, false) => "✅ same layout".to_string(),
+            (true, true, true) => "🔀 reordered".to_string(),
+            _ => format!("⚠️  {} missing, {} extra{}", f.missing.len(), f.extra.len(), if f.reordered { ", reordered" } else { "" }),
+        };
+        info!("   {} ({} rows): {}", f.file, f.rows, status);
+    }
+    info!("📊 Records written: {}", processed);
+    info!("💾 Output: {}", output_path);
+
+    logging::summary(&serde_json::json!({
+        "command": "merge_reconcile",
+        "output": output_path,
+        "columns": target,
+        "records": processed,
+        "files": files,
+    }))?;
+
+    Ok(())
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SchemaCommand {
+    #[command(about = "Render a data dictionary (Markdown or HTML); --sample fills the example values column")]
+    Docs(SchemaDocsArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SchemaDocsArgs {
+    #[arg(value_name = "SCHEMA.yaml|MODEL")]
+    pub spec: String,
+    // `--format` es la opción global del resumen (text|json|markdown)
+    #[arg(long, value_enum, default_value_t = DocFormat::Md)]
+    pub doc_format: DocFormat,
+    #[arg(long, value_name = "DATA.csv", help = "Data file to take example values from")]
+    pub sample: Option<String>,
+    #[arg(long, value_name = "DOC", help = "Write the document here instead of stdout")]
+    pub output: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DocFormat {
+    Md,
+    Html,
+}
+
+/// `schema <subcomando>`: por ahora solo `docs`
+pub fn schema_command(command: &SchemaCommand) -> Result<(), CsvToolsError> {
+    match command {
+        SchemaCommand::Docs(args) => schema_docs(args),
+    }
+}
+
+/// Genera el diccionario de datos (columna, tipo, clave, restricciones y ejemplos
+/// tomados de un archivo de muestra) en Markdown o HTML
+fn schema_docs(args: &SchemaDocsArgs) -> Result<(), CsvToolsError> {
+    let spec = args.spec.as_str();
+    let mut schema = Schema::load(spec)?;
+    if schema.name.is_empty() {
+        schema.name = std::path::Path::new(spec).file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+    }
+
+    let source = args.sample.as_deref();
+    let examples = match source {
+        Some(path) => sample_examples(path, &schema)?,
+        None => vec![Vec::new(); schema.columns.len()],
+    };
+
+    let doc = if args.doc_format == DocFormat::Html {
+        render_docs_html(&schema, spec, source, &examples)
+    } else {
+        render_docs_markdown(&schema, spec, source, &examples)
+    };
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, doc)?;
+            info!("💾 Data dictionary saved: {} ({} columns)", path, schema.columns.len());
+        }
+        None => print!("{}", doc),
+    }
+
+    Ok(())
+}
+
+/// Primeros valores distintos no vacíos de cada columna del schema en la muestra
+fn sample_examples(path: &str, schema: &Schema) -> Result<Vec<Vec<String>>, CsvToolsError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(path)?);
+    let headers = reader.headers()?.clone();
+
+    let indexes: Vec<Option<usize>> = schema.columns.iter()
+        .map(|c| headers.iter().position(|h| h.trim() == c.name))
+        .collect();
+    for (column, idx) in schema.columns.iter().zip(&indexes) {
+        if idx.is_none() {
+            diagnostics::warn(format!("Schema column '{}' not found in sample '{}'", column.name, path));
+        }
+    }
+
+    let mut examples = vec![Vec::new(); schema.columns.len()];
+    for result in reader.records().take(DOCS_SAMPLE_ROWS) {
+        let record = result?;
+        for (values, idx) in examples.iter_mut().zip(&indexes) {
+            let Some(value) = idx.and_then(|i| record.get(i)).map(str::trim) else {
+                continue;
+            };
+            if !value.is_empty() && values.len() < DOCS_EXAMPLES_PER_COLUMN && !values.iter().any(|v| v == value) {
+                values.push(value.to_string());
+            }
+        }
+    }
+
+    Ok(examples)
+}
+
+/// Restricciones legibles de una columna (obligatoria, vacíos, default, formato)
+fn column_constraints(column: &ColumnSpec) -> Vec<String> {
+    let mut constraints = Vec::new();
+
+    if column.key.is_some() {
+        constraints.push("obligatoria".to_string());
+    } else if column.column_type.is_numeric() && column.nullable {
+        constraints.push("admite vacío".to_string());
+    } else if column.column_type.is_numeric() {
+        constraints.push("no admite vacío (Type N)".to_string());
+    }
+    if let Some(default) = &column.default {
+        constraints.push(format!("default \"{}\"", default));
+    }
+    if let Some((t, f)) = &column.bool_pair {
+        constraints.push(format!("valores {}/{}", t, f));
+    }
+    match column.column_type {
+        ColumnType::Date => constraints.push("formato yyyy-MM-dd".to_string()),
+        ColumnType::Decimal { precision, scale } => {
+            constraints.push(format!("hasta {} enteros y {} decimales", precision - scale, scale));
+        }
+        _ => {}
+    }
+
+    constraints
+}
+
+fn key_label(key: Option<KeyRole>) -> &'static str {
+    match key {
+        Some(KeyRole::Partition) => "partition",
+        Some(KeyRole::Sort) => "sort",
+        None => "",
+    }
+}
+
+fn render_docs_markdown(schema: &Schema, spec: &str, sample: Option<&str>, examples: &[Vec<String>]) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+    let mut doc = String::new();
+
+    doc.push_str(&format!("# Diccionario de datos: {}\n\n", schema.name));
+    doc.push_str(&format!("- Schema: `{}`\n", spec));
+    if let Some(path) = sample {
+        doc.push_str(&format!("- Ejemplos tomados de: `{}`\n", path));
+    }
+    doc.push_str(&format!("- Generado: {}\n\n", chrono::Local::now().format("%Y-%m-%d %H:%M")));
+    doc.push_str("| # | Columna | Tipo | Clave | Restricciones | Ejemplos |\n");
+    doc.push_str("|---|---------|------|-------|---------------|----------|\n");
+
+    for (i, (column, values)) in schema.columns.iter().zip(examples).enumerate() {
+        let values: Vec<String> = values.iter().map(|v| format!("`{}`", cell(v))).collect();
+        doc.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            i + 1,
+            cell(&column.name),
+            column.column_type,
+            key_label(column.key),
+            cell(&column_constraints(column).join("; ")),
+            values.join(", ")
+        ));
+    }
+
+    doc
+}
+
+fn render_docs_html(schema: &Schema, spec: &str, sample: Option<&str>, examples: &[Vec<String>]) -> String {
+    let escape = |text: &str| text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+    let mut doc = String::new();
+
+    doc.push_str("<!DOCTYPE html>\n<html lang=\"es\">\n<head>\n<meta charset=\"utf-8\">\n");
+    doc.push_str(&format!("<title>Diccionario de datos: {}</title>\n", escape(&schema.name)));
+    doc.push_str("<style>body{font-family:sans-serif}table{border-collapse:collapse}th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}th{background:#eee}code{background:#f5f5f5}</style>\n");
+    doc.push_str("</head>\n<body>\n");
+    doc.push_str(&format!("<h1>Diccionario de datos: {}</h1>\n<ul>\n", escape(&schema.name)));
+    doc.push_str(&format!("<li>Schema: <code>{}</code></li>\n", escape(spec)));
+    if let Some(path) = sample {
+        doc.push_str(&format!("<li>Ejemplos tomados de: <code>{}</code></li>\n", escape(path)));
+    }
+    doc.push_str(&format!("<li>Generado: {}</li>\n</ul>\n", chrono::Local::now().format("%Y-%m-%d %H:%M")));
+    doc.push_str("<table>\n<tr><th>#</th><th>Columna</th><th>Tipo</th><th>Clave</th><th>Restricciones</th><th>Ejemplos</th></tr>\n");
+
+    for (i, (column, values)) in schema.columns.iter().zip(examples).enumerate() {
+        let values: Vec<String> = values.iter().map(|v| format!("<code>{}</code>", escape(v))).collect();
+        doc.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            i + 1,
+            escape(&column.name),
+            column.column_type,
+            key_label(column.key),
+            escape(&column_constraints(column).join("; ")),
+            values.join(", ")
+        ));
+    }
+
+    doc.push_str("</table>\n</body>\n</html>\n");
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_merge_reconcile_maps_by_header_name() {
+        let dir = TempDir::new("reconcile");
+        dir.write("a.csv", "id,name,age\n1,a,30\n");
+        dir.write("b.csv", "name,id,city\nb,2,BA\n");
+        // La salida fuera del directorio de entrada (no es una entrada más)
+        let out = TempDir::new("reconcile_out");
+
+        let mut args = MergeReconcileArgs { inputs: dir.root(), output: out.path("out.csv"), schema: None, union: false };
+        merge_reconcile(&args).unwrap();
+        assert_eq!(out.read("out.csv"), "id,name,age\n1,a,30\n2,b,\n");
+
+        args.union = true;
+        merge_reconcile(&args).unwrap();
+        assert_eq!(out.read("out.csv"), "id,name,age,city\n1,a,30,\n2,b,,BA\n");
+    }
+
+    #[test]
+    fn test_rename_columns_keeps_rows() {
+        let dir = TempDir::new("rename");
+        dir.write("in.csv", "cuil,nro_doc,Nombre\n1,2,\"Perez, Juan\"\n");
+        dir.write("map.txt", "# equipo cobranzas\nnro_doc=NroDoc\n");
+
+        let args = |map: &[&str]| RenameColumnsArgs {
+            input: dir.path("in.csv"), output: dir.path("out.csv"),
+            map: map.iter().map(|m| m.to_string()).collect(), map_file: Some(dir.path("map.txt")),
+        };
+        rename_columns(&args(&["cuil=Cuil"])).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,NroDoc,Nombre\n1,2,\"Perez, Juan\"\n");
+        assert!(rename_columns(&args(&["cuil=Nombre"])).is_err());
+        assert!(rename_columns(&args(&["dni=Dni"])).is_err());
+    }
+
+    #[test]
+    fn test_add_column_appends_constants() {
+        let dir = TempDir::new("add_column");
+        dir.write("in.csv", "Cuil,Nombre\n1,\"Perez, Juan\"\n2,Gomez\n");
+
+        let args = |names: &[&str], values: &[&str]| AddColumnArgs {
+            input: dir.path("in.csv"), output: dir.path("out.csv"),
+            name: names.iter().map(|n| n.to_string()).collect(),
+            value: values.iter().map(|v| v.to_string()).collect(),
+        };
+        add_column(&args(&["CreateUser", "Origen"], &["batch_import", "a, b"])).unwrap();
+        assert_eq!(dir.read("out.csv"),
+                   "Cuil,Nombre,CreateUser,Origen\n1,\"Perez, Juan\",batch_import,\"a, b\"\n2,Gomez,batch_import,\"a, b\"\n");
+        assert!(add_column(&args(&["Cuil"], &["x"])).is_err());
+        assert!(add_column(&args(&["CreateUser"], &[])).is_err());
+    }
+
+    #[test]
+    fn test_select_and_drop_keep_quoted_values() {
+        let dir = TempDir::new("select");
+        dir.write("in.csv", "Cuil,Nombre,Telefono\n1,\"Perez, Juan\",555\n2,Gomez\n");
+
+        select(&SelectArgs { input: dir.path("in.csv"), output: dir.path("sel.csv"), columns: vec!["Nombre".into(), "Cuil".into()] }).unwrap();
+        assert_eq!(dir.read("sel.csv"), "Nombre,Cuil\n\"Perez, Juan\",1\nGomez,2\n");
+
+        drop_columns(&DropArgs { input: dir.path("in.csv"), output: dir.path("drop.csv"), columns: vec!["Telefono".into()] }).unwrap();
+        assert_eq!(dir.read("drop.csv"), "Cuil,Nombre\n1,\"Perez, Juan\"\n2,Gomez\n");
+        assert!(select(&SelectArgs { input: dir.path("in.csv"), output: dir.path("sel.csv"), columns: vec!["Dni".into()] }).is_err());
+    }
+}
@@ -0,0 +1,472 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+
+use clap::{Args, ValueEnum};
+use crate::cli::FILES_HELP;
+use crate::cli_args::{list_values, parse_byte_size};
+use crate::collation::{self, Collation};
+use crate::config;
+use crate::dedup::key_indexes;
+use crate::error::CsvToolsError;
+use crate::exit_code::{data_issues, usage_error};
+use crate::file_utils::{format_bytes, open_input, resolve_inputs};
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::schema::{coerce_value, ColumnSpec, ColumnType};
+use crate::sink::open_sink;
+use crate::temp_files::TempFiles;
+
+// Costo aproximado en memoria de una fila además de sus bytes (StringRecord, clave, Vec)
+const ROW_OVERHEAD: usize = 96;
+const ROW_FIELD_OVERHEAD: usize = 8;
+
+/// Cómo se comparan los valores de la columna clave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortType {
+    /// Texto, con la collation actual (`--collation`)
+    String,
+    /// Números (enteros o decimales); los valores no numéricos van al final
+    Numeric,
+    /// Fechas ISO, dd/MM/yyyy o MM/dd/yyyy (con o sin hora); las no reconocidas van al final
+    Date,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Valor de clave ya interpretado según el tipo (se parsea una vez por fila)
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortKey {
+    Number(f64),
+    Text(String),
+    /// No es del tipo pedido: se ordena después de todos los válidos
+    Invalid(String),
+}
+
+impl SortKey {
+    pub fn parse(value: &str, sort_type: SortType) -> Self {
+        let value = value.trim();
+        match sort_type {
+            SortType::String => SortKey::Text(value.to_string()),
+            SortType::Numeric => match value.parse::<f64>() {
+                Ok(n) if !n.is_nan() => SortKey::Number(n),
+                _ => SortKey::Invalid(value.to_string()),
+            },
+            // La forma ISO que devuelve coerce_value se ordena bien como texto
+            SortType::Date => {
+                let spec = ColumnSpec { column_type: ColumnType::Date, ..Default::default() };
+                match coerce_value(value, &spec) {
+                    Ok(iso) => SortKey::Text(iso),
+                    Err(_) => SortKey::Invalid(value.to_string()),
+                }
+            }
+        }
+    }
+
+    fn compare(&self, other: &Self, collation: Collation) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) | (SortKey::Invalid(a), SortKey::Invalid(b)) => collation.compare(a, b),
+            (SortKey::Invalid(_), _) => Ordering::Greater,
+            (_, SortKey::Invalid(_)) => Ordering::Less,
+            (SortKey::Number(_), SortKey::Text(_)) => Ordering::Less,
+            (SortKey::Text(_), SortKey::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// Tipo, sentido y collation con los que se comparan las claves
+#[derive(Debug, Clone, Copy)]
+struct Comparator {
+    sort_type: SortType,
+    order: SortOrder,
+    collation: Collation,
+}
+
+impl Comparator {
+    fn key(&self, record: &StringRecord, key_indexes: &[usize]) -> Vec<SortKey> {
+        key_indexes.iter().map(|i| SortKey::parse(record.get(*i).unwrap_or(""), self.sort_type)).collect()
+    }
+
+    /// Desc invierte solo la comparación entre válidos: los inválidos quedan siempre al final
+    fn compare(&self, a: &[SortKey], b: &[SortKey]) -> Ordering {
+        a.iter().zip(b)
+            .map(|(x, y)| {
+                let ordering = x.compare(y, self.collation);
+                match (self.order, x, y) {
+                    (SortOrder::Desc, SortKey::Invalid(_), _) | (SortOrder::Desc, _, SortKey::Invalid(_)) => ordering,
+                    (SortOrder::Desc, _, _) => ordering.reverse(),
+                    (SortOrder::Asc, _, _) => ordering,
+                }
+            })
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+fn display_key(record: &StringRecord, key_indexes: &[usize]) -> String {
+    key_indexes.iter().map(|i| record.get(*i).unwrap_or("")).collect::<Vec<_>>().join(",")
+}
+
+/// Merge k-way de archivos ya ordenados por la clave (p. ej. los chunks del exportador
+/// ordenados por Cuil): memoria O(k), una fila por archivo, y la salida queda ordenada
+/// sin volver a ordenar. Falla si algún archivo no está ordenado
+#[derive(Args, Debug)]
+pub struct MergeSortedArgs {
+    #[arg(value_name = "FILES", help = FILES_HELP)]
+    pub inputs: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Key columns the inputs are sorted by")]
+    pub key: Vec<String>,
+    #[arg(long = "type", value_enum, default_value_t = SortType::String, help = "How key values compare")]
+    pub sort_type: SortType,
+    #[arg(long, help = "Write only the first row of each key (earlier files win)")]
+    pub dedup: bool,
+}
+
+/// Fila pendiente de un archivo (o corrida) en el heap del merge (orden invertido: el
+/// BinaryHeap entrega primero la menor; a igualdad de clave, la del archivo anterior)
+struct PendingRow {
+    key: Vec<SortKey>,
+    record: StringRecord,
+    file: usize,
+    comparator: Comparator,
+}
+
+impl Ord for PendingRow {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.comparator.compare(&other.key, &self.key)
+            .then_with(|| other.file.cmp(&self.file))
+    }
+}
+
+impl PartialOrd for PendingRow {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for PendingRow {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PendingRow {}
+
+pub fn merge_sorted(args: &MergeSortedArgs) -> Result<(), CsvToolsError> {
+    let output_path = args.output.as_str();
+    let key_names = list_values(&args.key);
+    let sort_type = args.sort_type;
+    let collation = collation::current();
+    let comparator = Comparator { sort_type, order: SortOrder::Asc, collation };
+    let file_names = resolve_inputs(&args.inputs)?;
+
+    logging::banner("K-way Merge of Sorted Files");
+    info!("📂 Files: {}", file_names.len());
+    info!("📄 Output: {}", output_path);
+    info!("🔑 Key: {} ({:?}{})", key_names.join(" + "), sort_type,
+        if sort_type == SortType::String { format!(", collation {}", collation) } else { String::new() });
+    info!("🧹 Dedup: {}", if args.dedup { "first row per key" } else { "no" });
+    info!();
+
+    // Todos los archivos deben compartir el header del primero
+    let mut readers = Vec::with_capacity(file_names.len());
+    let mut headers: Option<StringRecord> = None;
+    for file in &file_names {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(config::delimiter())
+            .has_headers(true)
+            .flexible(true)
+            .from_reader(open_input(file)?);
+        let file_headers = reader.headers()?.clone();
+        match &headers {
+            None => headers = Some(file_headers),
+            Some(first) if first != &file_headers => {
+                return Err(usage_error(format!(
+                    "{} has a different header than {} (use merge_reconcile to align columns first)", file, file_names[0]
+                )));
+            }
+            Some(_) => {}
+        }
+        readers.push(reader);
+    }
+    let headers = headers.ok_or_else(|| usage_error("No input files".to_string()))?;
+    let key_indexes = key_indexes(&headers, &key_names)?;
+
+    let mut writer = open_sink(output_path)?;
+    writer.write_header(&headers)?;
+
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (file, reader) in readers.iter_mut().enumerate() {
+        let mut record = StringRecord::new();
+        if reader.read_record(&mut record)? {
+            heap.push(PendingRow { key: comparator.key(&record, &key_indexes), record, file, comparator });
+        }
+    }
+
+    let mut rows_read = 0usize;
+    let mut rows_written = 0usize;
+    let mut last_key: Option<Vec<SortKey>> = None;
+
+    let mut progress = Progress::rows("Merged");
+    while let Some(row) = heap.pop() {
+        rows_read += 1;
+
+        // Siguiente fila del mismo archivo: no puede ser menor que la que sale
+        let mut next = StringRecord::new();
+        if readers[row.file].read_record(&mut next)? {
+            let next_key = comparator.key(&next, &key_indexes);
+            if comparator.compare(&next_key, &row.key) == Ordering::Less {
+                let line = next.position().map(|p| p.line()).unwrap_or(0);
+                return Err(data_issues(format!(
+                    "{} is not sorted by {}: line {} ({}) comes after {}",
+                    file_names[row.file], key_names.join(","), line,
+                    display_key(&next, &key_indexes), display_key(&row.record, &key_indexes)
+                )));
+            }
+            heap.push(PendingRow { key: next_key, record: next, file: row.file, comparator });
+        }
+
+        let duplicate = args.dedup && last_key.as_ref()
+            .is_some_and(|last| comparator.compare(last, &row.key) == Ordering::Equal);
+        if !duplicate {
+            writer.write_record(&row.record)?;
+            rows_written += 1;
+            last_key = Some(row.key);
+        }
+
+        progress.update(rows_read as u64, || format!("Written: {}", rows_written));
+    }
+    writer.finish()?;
+    progress.finish();
+
+    info!();
+    info!("📊 Rows read: {}", rows_read);
+    info!("✅ Rows written: {}", rows_written);
+    if args.dedup {
+        info!("🗑️  Duplicate keys removed: {}", rows_read - rows_written);
+    }
+
+    logging::summary(&serde_json::json!({
+        "command": "merge_sorted",
+        "output": output_path,
+        "files": file_names.len(),
+        "key": key_names,
+        "records": rows_read,
+        "records_written": rows_written,
+        "duplicates_removed": rows_read - rows_written,
+    }))?;
+
+    Ok(())
+}
+
+/// Ordena un CSV por una o más columnas (texto, número o fecha; asc o desc)
+/// Si el archivo entra en --memory-limit se ordena en memoria; si no, se ordenan
+/// corridas de ese tamaño, se vuelcan a temporales y se mezclan con el mismo merge
+/// k-way de merge_sorted. El orden es estable: a igualdad de clave se respeta la entrada
+#[derive(Args, Debug)]
+pub struct SortArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Columns to sort by")]
+    pub column: Vec<String>,
+    #[arg(long = "type", value_enum, default_value_t = SortType::String, help = "How values compare")]
+    pub sort_type: SortType,
+    #[arg(long, value_enum, default_value_t = SortOrder::Asc)]
+    pub order: SortOrder,
+    #[arg(long, value_name = "SIZE", default_value = "1GB", value_parser = parse_byte_size,
+          help = "Rows kept in memory before spilling a sorted run to disk (e.g. 512MB, 4GB)")]
+    pub memory_limit: usize,
+    #[arg(long, value_name = "DIR", help = "Directory for the sorted runs [default: output directory]")]
+    pub workdir: Option<String>,
+}
+
+pub fn sort(args: &SortArgs) -> Result<(), CsvToolsError> {
+    let input_path = args.input.as_str();
+    let output_path = args.output.as_str();
+    let column_names = list_values(&args.column);
+    let comparator = Comparator { sort_type: args.sort_type, order: args.order, collation: collation::current() };
+
+    logging::banner("Sort CSV");
+    info!("📄 Input: {}", input_path);
+    info!("📝 Output: {}", output_path);
+    info!("🔑 Columns: {} ({:?}, {:?})", column_names.join(" + "), args.sort_type, args.order);
+    info!("🧠 Memory limit: {}", format_bytes(args.memory_limit as u64));
+    info!();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input_path)?);
+    let headers = reader.headers()?.clone();
+    let key_indexes = key_indexes(&headers, &column_names)?;
+
+    let mut temp_files = match &args.workdir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            TempFiles::new(Path::new(dir))
+        }
+        None => TempFiles::for_output(output_path),
+    };
+
+    // Pasada 1: corridas ordenadas de hasta --memory-limit
+    let mut rows: Vec<(Vec<SortKey>, StringRecord)> = Vec::new();
+    let mut runs: Vec<PathBuf> = Vec::new();
+    let mut used = 0usize;
+    let mut processed = 0usize;
+    let mut record = StringRecord::new();
+
+    let mut progress = Progress::rows("Read");
+    while reader.read_record(&mut record)? {
+        processed += 1;
+        used += record.as_slice().len() + record.len() * ROW_FIELD_OVERHEAD + ROW_OVERHEAD;
+        rows.push((comparator.key(&record, &key_indexes), record.clone()));
+
+        if used >= args.memory_limit {
+            runs.push(write_run(&mut rows, &mut temp_files, runs.len(), comparator)?);
+            used = 0;
+        }
+        progress.update(processed as u64, || format!("Runs: {}", runs.len()));
+    }
+    progress.finish();
+
+    let mut writer = open_sink(output_path)?;
+    writer.write_header(&headers)?;
+
+    if runs.is_empty() {
+        info!("📊 {} rows sorted in memory", processed);
+        rows.sort_by(|a, b| comparator.compare(&a.0, &b.0));
+        for (_, row) in &rows {
+            writer.write_record(row)?;
+        }
+    } else {
+        if !rows.is_empty() {
+            runs.push(write_run(&mut rows, &mut temp_files, runs.len(), comparator)?);
+        }
+        drop(rows);
+        info!("🔄 Merging {} sorted runs...", runs.len());
+
+        // Pasada 2: merge k-way de las corridas (a igualdad gana la corrida anterior)
+        let mut run_readers = Vec::with_capacity(runs.len());
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+        for (file, path) in runs.iter().enumerate() {
+            let mut run_reader = ReaderBuilder::new().has_headers(false).flexible(true).from_path(path)?;
+            let mut row = StringRecord::new();
+            if run_reader.read_record(&mut row)? {
+                heap.push(PendingRow { key: comparator.key(&row, &key_indexes), record: row, file, comparator });
+            }
+            run_readers.push(run_reader);
+        }
+
+        let mut merged = 0usize;
+        let mut progress = Progress::rows("Merged");
+        while let Some(row) = heap.pop() {
+            let mut next = StringRecord::new();
+            if run_readers[row.file].read_record(&mut next)? {
+                heap.push(PendingRow { key: comparator.key(&next, &key_indexes), record: next, file: row.file, comparator });
+            }
+            writer.write_record(&row.record)?;
+            merged += 1;
+            progress.update(merged as u64, String::new);
+        }
+        progress.finish();
+    }
+    writer.finish()?;
+
+    info!();
+    info!("✅ Rows sorted: {}", processed);
+    info!("💾 Output: {}", output_path);
+    logging::summary(&serde_json::json!({
+        "command": "sort",
+        "input": input_path,
+        "output": output_path,
+        "columns": column_names,
+        "records": processed,
+        "runs": runs.len(),
+    }))?;
+
+    Ok(())
+}
+
+/// Ordena las filas acumuladas, las escribe como corrida temporal (CSV sin header,
+/// con el delimitador por defecto) y deja el vector vacío
+fn write_run(
+    rows: &mut Vec<(Vec<SortKey>, StringRecord)>,
+    temp_files: &mut TempFiles,
+    index: usize,
+    comparator: Comparator,
+) -> Result<PathBuf, CsvToolsError> {
+    rows.sort_by(|a, b| comparator.compare(&a.0, &b.0));
+    let path = temp_files.path(&format!("sort_run_{}.csv", index));
+    let mut run_writer = WriterBuilder::new().has_headers(false).from_path(&path)?;
+    for (_, row) in rows.iter() {
+        run_writer.write_record(row)?;
+    }
+    run_writer.flush()?;
+    rows.clear();
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_merge_sorted_numeric_with_dedup() {
+        let dir = TempDir::new("merge_sorted");
+        dir.write("a.csv", "Cuil,v\n2,a\n10,a\n30,a\n");
+        dir.write("b.csv", "Cuil,v\n1,b\n10,b\n20,b\n");
+        let output = dir.path("out.csv");
+
+        let mut args = MergeSortedArgs {
+            inputs: dir.path("*.csv"),
+            output: output.clone(),
+            key: vec!["Cuil".to_string()],
+            sort_type: SortType::Numeric,
+            dedup: true,
+        };
+        merge_sorted(&args).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,v\n1,b\n2,a\n10,a\n20,b\n30,a\n");
+
+        // Como texto "10" < "2": los archivos no están ordenados así
+        std::fs::remove_file(&output).unwrap();
+        args.sort_type = SortType::String;
+        assert!(merge_sorted(&args).is_err());
+    }
+
+    #[test]
+    fn test_sort_numeric_desc_spills_runs() {
+        let dir = TempDir::new("sort");
+        let input = dir.write("in.csv", "Periodo,v\n202301,a\n202312,b\nx,c\n9,d\n202312,e\n202306,f\n");
+
+        let mut args = SortArgs {
+            input,
+            output: dir.path("out.csv"),
+            column: vec!["Periodo".to_string()],
+            sort_type: SortType::Numeric,
+            order: SortOrder::Desc,
+            memory_limit: usize::MAX,
+            workdir: None,
+        };
+        let expected = "Periodo,v\n202312,b\n202312,e\n202306,f\n202301,a\n9,d\nx,c\n";
+        sort(&args).unwrap();
+        assert_eq!(dir.read("out.csv"), expected);
+
+        // Una corrida cada ~2 filas: mismo resultado (estable) tras el merge
+        args.memory_limit = 2 * ROW_OVERHEAD;
+        sort(&args).unwrap();
+        assert_eq!(dir.read("out.csv"), expected);
+    }
+}
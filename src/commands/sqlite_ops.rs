@@ -0,0 +1,299 @@
+use csv::{ReaderBuilder, StringRecord};
+use rusqlite::{params_from_iter, types::ValueRef, Connection};
+
+use clap::{ArgGroup, Args};
+use crate::config;
+use crate::error::CsvToolsError;
+use crate::exit_code::usage_error;
+use crate::file_utils::{is_stdio, open_input};
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::models::DynamoDbModel;
+use crate::sink::{open_sink, CsvSink, RecordSink};
+use crate::temp_files::TempFiles;
+
+// Inserts por transacción (batch) al cargar CSV en SQLite
+const INSERT_BATCH_SIZE: usize = 10_000;
+
+#[derive(Args, Debug)]
+pub struct ToSqliteArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "DB.sqlite")]
+    pub db: String,
+    #[arg(long, value_name = "NAME")]
+    pub table: String,
+    #[arg(long, value_name = "MODEL", help = "Typed columns + key index from a DynamoDB model")]
+    pub schema: Option<String>,
+    #[arg(long, help = "Drop the table first if it exists")]
+    pub replace: bool,
+}
+
+/// Exporta un CSV a una tabla SQLite para consultas ad-hoc
+/// Columnas tipadas según el modelo DynamoDB (Type N → NUMERIC, resto TEXT)
+/// Inserts en batch + índice sobre las columnas clave del modelo
+pub fn to_sqlite(args: &ToSqliteArgs) -> Result<(), CsvToolsError> {
+    let input_file = args.input.as_str();
+    let db_path = args.db.as_str();
+    let table = args.table.as_str();
+    let model_type = args.schema.as_deref();
+    let replace = args.replace;
+
+    let model = match model_type {
+        Some(m) => Some(DynamoDbModel::from_model_type(m)
+            .ok_or_else(|| usage_error(format!("Unknown model type: {}", m)))?),
+        None => None,
+    };
+
+    logging::banner("CSV → SQLite Export");
+    info!("📄 Input CSV: {}", input_file);
+    info!("🗄️  SQLite DB: {}", db_path);
+    info!("📋 Table: {}", table);
+    info!("📋 Schema: {}", model_type.unwrap_or("(none, all columns TEXT)"));
+    info!();
+
+    let mut conn = Connection::open(db_path)?;
+    conn.execute_batch("PRAGMA journal_mode = OFF; PRAGMA synchronous = OFF;")?;
+
+    if replace {
+        conn.execute(&format!("DROP TABLE IF EXISTS {}", quote_ident(table)), [])?;
+    }
+
+    let total = load_csv_into_table(&mut conn, input_file, table, model.as_ref())?;
+
+    info!();
+    logging::banner("SQLite Export Summary");
+    info!("📊 Rows inserted: {}", total);
+    info!("📝 Database: {}", db_path);
+    info!("💡 Try: sqlite3 {} \"SELECT count(*) FROM {}\"", db_path, table);
+
+    Ok(())
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("source").required(true).args(["table", "query"])))]
+pub struct FromSqliteArgs {
+    #[arg(value_name = "DB.sqlite")]
+    pub db: String,
+    #[arg(value_name = "OUTPUT.csv")]
+    pub output: String,
+    #[arg(long, value_name = "NAME", help = "Export the whole table")]
+    pub table: Option<String>,
+    #[arg(long, value_name = "SQL", help = "Export the result of a SELECT")]
+    pub query: Option<String>,
+}
+
+/// Exporta una tabla (o consulta SQL) de SQLite a CSV
+pub fn from_sqlite(args: &FromSqliteArgs) -> Result<(), CsvToolsError> {
+    let db_path = args.db.as_str();
+    let output_file = args.output.as_str();
+    let sql = match &args.query {
+        Some(q) => q.clone(),
+        None => format!("SELECT * FROM {}", quote_ident(args.table.as_deref().unwrap_or_default())),
+    };
+
+    logging::banner("SQLite → CSV Export");
+    info!("🗄️  SQLite DB: {}", db_path);
+    info!("🔎 Query: {}", sql);
+    info!("📝 Output CSV: {}", output_file);
+    info!();
+
+    let conn = Connection::open(db_path)?;
+    let mut sink = open_sink(output_file)?;
+
+    let written = export_query(&conn, &sql, sink.as_mut())?;
+    sink.finish()?;
+
+    info!("📊 Written: {} rows", written);
+    info!("✅ Export complete: {}", output_file);
+
+    Ok(())
+}
+
+#[derive(Args, Debug)]
+pub struct QueryArgs {
+    #[arg(value_name = "INPUT.csv")]
+    pub input: String,
+    #[arg(value_name = "SQL", help = "Query over table 't' (columns named after the header)")]
+    pub sql: String,
+    #[arg(long, value_name = "OUT.csv", help = "Write the result here instead of stdout")]
+    pub output: Option<String>,
+    #[arg(long, value_name = "MODEL", help = "Typed columns from a DynamoDB model")]
+    pub schema: Option<String>,
+}
+
+/// Ejecuta SQL sobre un CSV: lo carga en una base SQLite temporal como tabla `t`
+/// y escribe el resultado en `--output` (o stdout si no se indica)
+pub fn query_csv(args: &QueryArgs) -> Result<(), CsvToolsError> {
+    let input_file = args.input.as_str();
+    let sql = args.sql.as_str();
+    let output_file = args.output.as_deref();
+    let model = match &args.schema {
+        Some(m) => Some(DynamoDbModel::from_model_type(m)
+            .ok_or_else(|| usage_error(format!("Unknown model type: {}", m)))?),
+        None => None,
+    };
+
+    // Sin --output el resultado va a stdout: los mensajes de estado, a stderr
+    if output_file.is_none_or(is_stdio) {
+        logging::set_data_on_stdout(true);
+    }
+
+    // Base temporal con nombre único por corrida (no cargar archivos gigantes en RAM);
+    // una base vieja con el mismo nombre sumaría sus filas a la tabla `t`
+    let mut temp_files = TempFiles::new(&std::env::temp_dir());
+    let temp_db = temp_files.path("query.sqlite");
+
+    info!("🔄 Staging {} into temporary SQLite table 't'...", input_file);
+
+    let result = (|| -> Result<usize, CsvToolsError> {
+        let mut conn = Connection::open(&temp_db)?;
+        conn.execute_batch("PRAGMA journal_mode = OFF; PRAGMA synchronous = OFF;")?;
+        load_csv_into_table(&mut conn, input_file, "t", model.as_ref())?;
+
+        info!("🔎 Query: {}", sql);
+        info!();
+
+        let mut sink: Box<dyn RecordSink> = match output_file {
+            Some(path) => open_sink(path)?,
+            None => Box::new(CsvSink::from_writer(Box::new(std::io::stdout()))),
+        };
+        let written = export_query(&conn, sql, sink.as_mut())?;
+        sink.finish()?;
+        Ok(written)
+    })();
+
+    drop(temp_files);
+
+    let written = result?;
+    info!();
+    info!("✅ Query complete: {} rows", written);
+    if let Some(path) = output_file {
+        info!("📝 Output CSV: {}", path);
+    }
+
+    Ok(())
+}
+
+/// Carga un CSV en `table` (creándola si no existe) y devuelve filas insertadas
+pub(crate) fn load_csv_into_table(
+    conn: &mut Connection,
+    input_file: &str,
+    table: &str,
+    model: Option<&DynamoDbModel>,
+) -> Result<usize, CsvToolsError> {
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .flexible(true)
+        .from_reader(open_input(input_file)?);
+
+    let headers = rdr.headers()?.clone();
+    let numeric: Vec<bool> = headers.iter()
+        .map(|h| model.is_some_and(|m| m.numeric_fields.contains(&h.trim())))
+        .collect();
+
+    let column_defs = headers.iter()
+        .zip(&numeric)
+        .map(|(h, is_num)| format!("{} {}", quote_ident(h.trim()), if *is_num { "NUMERIC" } else { "TEXT" }))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    conn.execute(&format!("CREATE TABLE IF NOT EXISTS {} ({})", quote_ident(table), column_defs), [])?;
+
+    let placeholders = vec!["?"; headers.len()].join(", ");
+    let insert_sql = format!("INSERT INTO {} VALUES ({})", quote_ident(table), placeholders);
+
+    let mut total = 0usize;
+    let mut records = rdr.records();
+
+    let progress = Progress::rows("Inserted");
+    loop {
+        let tx = conn.transaction()?;
+        let mut batch = 0usize;
+        {
+            let mut stmt = tx.prepare_cached(&insert_sql)?;
+
+            for result in records.by_ref() {
+                let record = result?;
+
+                // Valores vacíos en columnas numéricas → NULL
+                let values = (0..headers.len()).map(|i| {
+                    let value = record.get(i).unwrap_or("");
+                    if numeric[i] && value.trim().is_empty() { None } else { Some(value) }
+                });
+                stmt.execute(params_from_iter(values))?;
+
+                batch += 1;
+                if batch >= INSERT_BATCH_SIZE {
+                    break;
+                }
+            }
+        }
+        tx.commit()?;
+
+        total += batch;
+        progress.set_position(total as u64);
+
+        if batch < INSERT_BATCH_SIZE {
+            break;
+        }
+    }
+    progress.finish();
+
+    if let Some(m) = model {
+        let mut key_columns = vec![m.partition_key];
+        if !m.sort_key.is_empty() {
+            key_columns.push(m.sort_key);
+        }
+
+        let keys = key_columns.iter().map(|k| quote_ident(k)).collect::<Vec<_>>().join(", ");
+        conn.execute(
+            &format!("CREATE INDEX IF NOT EXISTS {} ON {} ({})",
+                quote_ident(&format!("idx_{}_keys", table)), quote_ident(table), keys),
+            [],
+        )?;
+        info!("🔑 Index created on: {}", key_columns.join(", "));
+    }
+
+    Ok(total)
+}
+
+/// Ejecuta `sql` y escribe el resultado (header + filas) en el writer CSV
+pub(crate) fn export_query(
+    conn: &Connection,
+    sql: &str,
+    sink: &mut dyn RecordSink,
+) -> Result<usize, CsvToolsError> {
+    let mut stmt = conn.prepare(sql)?;
+    let column_count = stmt.column_count();
+    let column_names: StringRecord = stmt.column_names().into_iter().collect();
+    sink.write_header(&column_names)?;
+
+    let mut rows = stmt.query([])?;
+    let mut written = 0usize;
+
+    let mut progress = Progress::rows("Written");
+    while let Some(row) = rows.next()? {
+        let mut fields = StringRecord::with_capacity(0, column_count);
+        for i in 0..column_count {
+            fields.push_field(&match row.get_ref(i)? {
+                ValueRef::Null => String::new(),
+                ValueRef::Integer(v) => v.to_string(),
+                ValueRef::Real(v) => v.to_string(),
+                ValueRef::Text(t) | ValueRef::Blob(t) => String::from_utf8_lossy(t).into_owned(),
+            });
+        }
+        sink.write_record(&fields)?;
+        written += 1;
+        progress.update(written as u64, String::new);
+    }
+    progress.finish();
+
+    Ok(written)
+}
+
+/// Escapa un identificador SQL (tabla/columna) con comillas dobles
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,784 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use csv::{ReaderBuilder, StringRecord};
+
+use clap::{Args, ValueEnum};
+use crate::canonicalize::{canonical, Rule};
+use crate::cli_args::{list_values, parse_count};
+use crate::config;
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::error_report::{self, ErrorRecord};
+use crate::exit_code;
+use crate::file_utils::open_input;
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
+use crate::sink::open_sink;
+
+// Valores sin mapeo que se muestran como ejemplo
+const MAX_UNMATCHED_SHOWN: usize = 10;
+
+/// Qué hace map_values con un valor que no está en el archivo de mapeo
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unmatched {
+    /// Lo deja como está (default)
+    Keep,
+    /// Lo reemplaza por vacío
+    Blank,
+    /// Corta con error en la primera fila sin mapeo
+    Error,
+}
+
+/// Reemplaza los valores de `column` según un archivo de mapeo de dos columnas
+/// (viejo → nuevo), p. ej. para traducir códigos de IdRegion entre sistemas
+#[derive(Args, Debug)]
+pub struct MapValuesArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(value_name = "COLUMN")]
+    pub column: String,
+    #[arg(long, value_name = "MAP.csv", help = "Two-column CSV with a header row: old value, new value")]
+    pub mapping: String,
+    #[arg(long, value_enum, default_value_t = Unmatched::Keep, help = "Values not in the mapping (empty cells are left alone)")]
+    pub unmatched: Unmatched,
+}
+
+pub fn map_values(args: &MapValuesArgs) -> Result<(), CsvToolsError> {
+    let mapping = load_mapping(&args.mapping)?;
+
+    logging::banner("Map Values");
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    info!("🔎 Column: {}", args.column);
+    info!("📋 Mapping: {} values in {}", mapping.len(), args.mapping);
+    info!();
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(&args.input)?);
+    let headers = rdr.headers()?.clone();
+    let column_index = headers.iter().position(|h| h.trim() == args.column.trim())
+        .ok_or_else(|| exit_code::usage_error(format!("Column '{}' not found in input file", args.column)))?;
+
+    let mut writer = open_sink(&args.output)?;
+    writer.write_header(&headers)?;
+
+    let mut record = StringRecord::new();
+    let mut new_record = StringRecord::new();
+    let (mut processed, mut replaced, mut unmatched_rows) = (0usize, 0usize, 0usize);
+    // Valor sin mapeo → filas en las que aparece
+    let mut unmatched: BTreeMap<String, usize> = BTreeMap::new();
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        processed += 1;
+        let value = record.get(column_index).unwrap_or("");
+        let new_value = match mapping.get(value.trim()) {
+            Some(new_value) => {
+                replaced += 1;
+                new_value.as_str()
+            }
+            None if value.trim().is_empty() => value,
+            None => {
+                if args.unmatched == Unmatched::Error {
+                    return Err(exit_code::data_issues(format!(
+                        "Line {}: value '{}' in {} is not in mapping '{}'", processed + 1, value, args.column, args.mapping)));
+                }
+                unmatched_rows += 1;
+                *unmatched.entry(value.trim().to_string()).or_insert(0) += 1;
+                if args.unmatched == Unmatched::Blank { "" } else { value }
+            }
+        };
+        new_record.clear();
+        for (i, field) in record.iter().enumerate() {
+            new_record.push_field(if i == column_index { new_value } else { field });
+        }
+        writer.write_record(&new_record)?;
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+    writer.finish()?;
+
+    info!("📊 Rows read: {}", processed);
+    info!("✅ Values replaced: {}", replaced);
+    if !unmatched.is_empty() {
+        let action = if args.unmatched == Unmatched::Blank { "blanked" } else { "kept" };
+        diagnostics::warn(format!("{} rows had a value not in the mapping ({} distinct, {}; e.g. {})",
+            unmatched_rows, unmatched.len(), action,
+            unmatched.keys().take(MAX_UNMATCHED_SHOWN).map(String::as_str).collect::<Vec<_>>().join(", ")));
+    }
+    logging::summary(&serde_json::json!({
+        "command": "map_values",
+        "input": args.input,
+        "output": args.output,
+        "column": args.column,
+        "mapping": args.mapping,
+        "records": processed,
+        "values_replaced": replaced,
+        "unmatched_rows": unmatched_rows,
+        "unmatched_values": unmatched,
+    }))?;
+
+    Ok(())
+}
+
+/// Quita espacios al principio y al final de los valores (y con --collapse colapsa
+/// los internos repetidos), la causa más común de "invalid numeric" al validar
+#[derive(Args, Debug)]
+pub struct TrimArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required_unless_present = "all", conflicts_with = "all",
+          help = "Columns to trim")]
+    pub columns: Vec<String>,
+    #[arg(long, help = "Trim every column")]
+    pub all: bool,
+    #[arg(long, help = "Also collapse internal runs of whitespace to a single space")]
+    pub collapse: bool,
+}
+
+pub fn trim(args: &TrimArgs) -> Result<(), CsvToolsError> {
+    let rules: &[Rule] = if args.collapse { &[Rule::CollapseWhitespace] } else { &[Rule::Trim] };
+
+    logging::banner("Trim Whitespace");
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    info!("✂️  Columns: {}{}", if args.all { "all".to_string() } else { list_values(&args.columns).join(", ") },
+          if args.collapse { " (collapsing internal whitespace)" } else { "" });
+    info!();
+
+    let stats = transform_cells(&args.input, &args.output, &args.columns, args.all, |value| {
+        let trimmed = canonical(value, rules);
+        (trimmed != value).then_some(trimmed)
+    })?;
+    stats.report("Values trimmed");
+    logging::summary(&serde_json::json!({
+        "command": "trim",
+        "input": args.input,
+        "output": args.output,
+        "collapse": args.collapse,
+        "records": stats.records,
+        "changed_by_column": stats.by_column(),
+    }))?;
+
+    Ok(())
+}
+
+/// Mayúsculas/minúsculas de recase
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    Upper,
+    Lower,
+    /// Primera letra de cada palabra en mayúscula: "PEREZ-GOMEZ juan" → "Perez-Gomez Juan"
+    Title,
+}
+
+/// Unifica mayúsculas/minúsculas de columnas de nombres antes de deduplicar o
+/// comparar con la base maestra
+#[derive(Args, Debug)]
+pub struct RecaseArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Columns to convert")]
+    pub columns: Vec<String>,
+    #[arg(long, value_enum)]
+    pub to: Case,
+}
+
+pub fn recase(args: &RecaseArgs) -> Result<(), CsvToolsError> {
+    logging::banner("Recase Columns");
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    let to = format!("{:?}", args.to).to_lowercase();
+    info!("🔠 Columns: {} → {}", list_values(&args.columns).join(", "), to);
+    info!();
+
+    let stats = transform_cells(&args.input, &args.output, &args.columns, false, |value| {
+        let converted = match args.to {
+            Case::Upper => canonical(value, &[Rule::Uppercase]),
+            Case::Lower => canonical(value, &[Rule::Lowercase]),
+            Case::Title => title_case(value),
+        };
+        (converted != value).then_some(converted)
+    })?;
+    stats.report("Values converted");
+    logging::summary(&serde_json::json!({
+        "command": "recase",
+        "input": args.input,
+        "output": args.output,
+        "to": to,
+        "records": stats.records,
+        "changed_by_column": stats.by_column(),
+    }))?;
+
+    Ok(())
+}
+
+/// Mayúscula en cada letra que sigue a algo que no es letra ni dígito (espacio,
+/// guion, apóstrofo) y minúscula en el resto
+fn title_case(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut word_start = true;
+    for c in value.chars() {
+        if word_start {
+            result.extend(c.to_uppercase());
+        } else {
+            result.extend(c.to_lowercase());
+        }
+        word_start = !c.is_alphanumeric();
+    }
+    result
+}
+
+/// Convierte los distintos marcadores de nulo de los extractos (NULL, N/A, -, ...)
+/// a una sola representación (vacío por defecto)
+#[derive(Args, Debug)]
+pub struct NormalizeNullsArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', default_value = "NULL,N/A,null,-", allow_hyphen_values = true,
+          help = "Null markers, compared case-sensitively with surrounding spaces ignored")]
+    pub tokens: Vec<String>,
+    #[arg(long, value_name = "VALUE", default_value = "", allow_hyphen_values = true, help = "Replacement [default: empty]")]
+    pub to: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', help = "Only these columns [default: all]")]
+    pub columns: Vec<String>,
+}
+
+pub fn normalize_nulls(args: &NormalizeNullsArgs) -> Result<(), CsvToolsError> {
+    let tokens = list_values(&args.tokens);
+    if tokens.is_empty() {
+        return Err(exit_code::usage_error("--tokens needs at least one null marker"));
+    }
+    let all = list_values(&args.columns).is_empty();
+
+    logging::banner("Normalize Null Markers");
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    info!("🕳️  Tokens: {} → \"{}\"", tokens.join(", "), args.to);
+    info!("📋 Columns: {}", if all { "all".to_string() } else { list_values(&args.columns).join(", ") });
+    info!();
+
+    let stats = transform_cells(&args.input, &args.output, &args.columns, all, |value| {
+        (value != args.to && tokens.contains(&value.trim())).then(|| args.to.clone())
+    })?;
+    stats.report("Null markers converted");
+    logging::summary(&serde_json::json!({
+        "command": "normalize_nulls",
+        "input": args.input,
+        "output": args.output,
+        "tokens": tokens,
+        "to": args.to,
+        "records": stats.records,
+        "changed_by_column": stats.by_column(),
+    }))?;
+
+    Ok(())
+}
+
+/// Agrega al final una columna con los valores de --columns unidos por --sep, para
+/// materializar claves compuestas que piden los loaders (y nuestro dedup)
+#[derive(Args, Debug)]
+pub struct ConcatColumnsArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "Columns to join, in order")]
+    pub columns: Vec<String>,
+    #[arg(long, value_name = "SEP", allow_hyphen_values = true, help = "Separator placed between values")]
+    pub sep: String,
+    #[arg(long, value_name = "COLUMN", help = "Name of the new column (appended at the end)")]
+    pub name: String,
+}
+
+pub fn concat_columns(args: &ConcatColumnsArgs) -> Result<(), CsvToolsError> {
+    let columns = list_values(&args.columns);
+    let name = args.name.trim();
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(&args.input)?);
+    let headers = rdr.headers()?.clone();
+    let indexes = columns.iter()
+        .map(|c| headers.iter().position(|h| h.trim() == *c)
+            .ok_or_else(|| exit_code::usage_error(format!("Column '{}' not found in input file", c))))
+        .collect::<Result<Vec<_>, _>>()?;
+    if name.is_empty() || headers.iter().any(|h| h.trim() == name) {
+        return Err(exit_code::usage_error(format!("--name '{}' must be a new, non-empty column name", name)));
+    }
+
+    logging::banner("Concatenate Columns");
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    info!("🔗 {} = {}", name, columns.join(&format!(" {} ", args.sep)));
+    info!();
+
+    let mut writer = open_sink(&args.output)?;
+    let mut out_headers = headers.clone();
+    out_headers.push_field(name);
+    writer.write_header(&out_headers)?;
+
+    let mut record = StringRecord::new();
+    let mut value = String::new();
+    let (mut processed, mut with_empty_part) = (0usize, 0usize);
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        processed += 1;
+        value.clear();
+        let mut empty_part = false;
+        for (n, idx) in indexes.iter().enumerate() {
+            if n > 0 {
+                value.push_str(&args.sep);
+            }
+            let part = record.get(*idx).unwrap_or("");
+            empty_part |= part.trim().is_empty();
+            value.push_str(part);
+        }
+        if empty_part {
+            with_empty_part += 1;
+        }
+        // Filas cortas se completan con vacíos para que la columna nueva quede en su lugar
+        for _ in record.len()..headers.len() {
+            record.push_field("");
+        }
+        record.push_field(&value);
+        writer.write_record(&record)?;
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+    writer.finish()?;
+
+    info!("📊 Rows written: {}", processed);
+    if with_empty_part > 0 {
+        diagnostics::warn(format!("{} rows have an empty value in one of {} (the key may not be unique)",
+            with_empty_part, columns.join(", ")));
+    }
+    logging::summary(&serde_json::json!({
+        "command": "concat_columns",
+        "input": args.input,
+        "output": args.output,
+        "columns": columns,
+        "name": name,
+        "records": processed,
+        "rows_with_empty_part": with_empty_part,
+    }))?;
+
+    Ok(())
+}
+
+/// Qué hace split_column cuando el valor tiene menos partes que columnas --into
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingParts {
+    /// Completa las columnas que faltan con vacío (default)
+    Fill,
+    /// Saca la fila de la salida (queda en el log)
+    Error,
+}
+
+/// Qué hace split_column cuando el valor tiene más partes que columnas --into
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtraParts {
+    /// Deja el resto, con sus separadores, en la última columna (default)
+    Merge,
+    /// Descarta las partes de más
+    Drop,
+    /// Saca la fila de la salida (queda en el log)
+    Error,
+}
+
+/// Divide una columna en varias por un separador (p. ej. ApellidoNombre → Apellido,
+/// Nombre). Las columnas nuevas reemplazan a la original en su posición; las filas con
+/// otra cantidad de partes se resuelven según --on-missing / --on-extra y quedan en el log
+#[derive(Args, Debug)]
+pub struct SplitColumnArgs {
+    #[arg(value_name = "INPUT.csv[.gz]")]
+    pub input: String,
+    #[arg(value_name = "OUTPUT.csv[.gz]")]
+    pub output: String,
+    #[arg(long, value_name = "COLUMN", help = "Column to split")]
+    pub column: String,
+    #[arg(long, value_name = "SEP", allow_hyphen_values = true, help = "Separator between parts")]
+    pub sep: String,
+    #[arg(long, value_name = "A,B", value_delimiter = ',', required = true, help = "New columns, in order")]
+    pub into: Vec<String>,
+    #[arg(long, value_name = "N", value_parser = parse_count,
+          help = "At most N parts; the rest stays in the last one [default: no limit]")]
+    pub max_parts: Option<usize>,
+    #[arg(long, value_enum, default_value_t = MissingParts::Fill, help = "Fewer parts than --into columns")]
+    pub on_missing: MissingParts,
+    #[arg(long, value_enum, default_value_t = ExtraParts::Merge, help = "More parts than --into columns")]
+    pub on_extra: ExtraParts,
+    #[arg(long, help = "Keep the original column before the new ones")]
+    pub keep_source: bool,
+}
+
+pub fn split_column(args: &SplitColumnArgs) -> Result<(), CsvToolsError> {
+    let into = list_values(&args.into);
+    if args.sep.is_empty() {
+        return Err(exit_code::usage_error("--sep cannot be empty"));
+    }
+    if args.max_parts == Some(0) {
+        return Err(exit_code::usage_error("--max-parts must be at least 1"));
+    }
+
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(&args.input)?);
+    let headers = rdr.headers()?.clone();
+    let column_index = headers.iter().position(|h| h.trim() == args.column.trim())
+        .ok_or_else(|| exit_code::usage_error(format!("Column '{}' not found in input file", args.column)))?;
+    for name in &into {
+        if into.iter().filter(|n| *n == name).count() > 1
+            || headers.iter().enumerate().any(|(i, h)| h.trim() == *name && (i != column_index || args.keep_source)) {
+            return Err(exit_code::usage_error(format!("--into column '{}' is repeated or already in the header", name)));
+        }
+    }
+
+    let error_log_path = config::error_log_path(&args.output, ".split_errors.log");
+
+    logging::banner("Split Column");
+    info!("📄 Input: {}", args.input);
+    info!("📝 Output: {}", args.output);
+    info!("✂️  {} → {} (separator \"{}\")", args.column, into.join(", "), args.sep);
+    info!("🔧 Fewer parts: {} | More parts: {}",
+          format!("{:?}", args.on_missing).to_lowercase(), format!("{:?}", args.on_extra).to_lowercase());
+    info!();
+
+    let mut log = BufWriter::new(File::create(&error_log_path)?);
+    writeln!(log, "# Split Column Error Log")?;
+    writeln!(log, "# Input: {}", args.input)?;
+    writeln!(log, "# Output: {}", args.output)?;
+    writeln!(log, "# Column: {} → {}", args.column, into.join(", "))?;
+    writeln!(log, "#")?;
+    writeln!(log, "# Format: [LINE] STATUS | Details")?;
+    writeln!(log, "# -------------------------------------------------------")?;
+
+    let mut out_headers = StringRecord::new();
+    for (i, h) in headers.iter().enumerate() {
+        if i != column_index || args.keep_source {
+            out_headers.push_field(h);
+        }
+        if i == column_index {
+            into.iter().for_each(|name| out_headers.push_field(name));
+        }
+    }
+    let mut writer = open_sink(&args.output)?;
+    writer.write_header(&out_headers)?;
+
+    let mut record = StringRecord::new();
+    let mut new_record = StringRecord::new();
+    let (mut processed, mut written, mut missing, mut extra) = (0usize, 0usize, 0usize, 0usize);
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        processed += 1;
+        let line_num = processed + 1;
+        let value = record.get(column_index).unwrap_or("");
+        let mut parts: Vec<&str> = match args.max_parts {
+            Some(n) => value.splitn(n, args.sep.as_str()).collect(),
+            None => value.split(args.sep.as_str()).collect(),
+        };
+        // Un valor vacío no tiene partes: va a parar a --on-missing como cualquier otro faltante
+        if value.trim().is_empty() {
+            parts.clear();
+        }
+
+        let mut keep = true;
+        if parts.len() < into.len() {
+            missing += 1;
+            keep = args.on_missing == MissingParts::Fill;
+            error_report::write(&mut log, &ErrorRecord::new(line_num, "SPLIT_MISSING_PARTS",
+                format!("{} of {} parts ({})", parts.len(), into.len(), if keep { "filled with empty" } else { "row removed" }))
+                .column(&args.column).value(value))?;
+        } else if parts.len() > into.len() {
+            extra += 1;
+            let action = match args.on_extra {
+                ExtraParts::Merge => "merged into last column",
+                ExtraParts::Drop => "extra parts dropped",
+                ExtraParts::Error => "row removed",
+            };
+            keep = args.on_extra != ExtraParts::Error;
+            error_report::write(&mut log, &ErrorRecord::new(line_num, "SPLIT_EXTRA_PARTS",
+                format!("{} parts for {} columns ({})", parts.len(), into.len(), action))
+                .column(&args.column).value(value))?;
+        }
+        if keep {
+            let last = into.len() - 1;
+            let merged = (parts.len() > into.len() && args.on_extra == ExtraParts::Merge)
+                .then(|| parts[last..].join(&args.sep));
+            new_record.clear();
+            for (i, field) in record.iter().enumerate() {
+                if i != column_index || args.keep_source {
+                    new_record.push_field(field);
+                }
+                if i == column_index {
+                    for n in 0..into.len() {
+                        match &merged {
+                            Some(rest) if n == last => new_record.push_field(rest.trim()),
+                            _ => new_record.push_field(parts.get(n).map_or("", |p| p.trim())),
+                        }
+                    }
+                }
+            }
+            writer.write_record(&new_record)?;
+            written += 1;
+        }
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+    writer.finish()?;
+    log.flush()?;
+
+    info!("📊 Rows read: {}", processed);
+    info!("✅ Rows written: {}", written);
+    if missing + extra > 0 {
+        diagnostics::warn(format!("{} rows with fewer parts and {} with more parts than {} columns (see {})",
+            missing, extra, into.len(), error_log_path));
+    }
+    logging::summary(&serde_json::json!({
+        "command": "split_column",
+        "input": args.input,
+        "output": args.output,
+        "column": args.column,
+        "into": into,
+        "records": processed,
+        "records_written": written,
+        "rows_missing_parts": missing,
+        "rows_extra_parts": extra,
+        "error_log": error_log_path,
+    }))?;
+
+    Ok(())
+}
+
+/// Filas leídas y celdas modificadas por columna de un `transform_cells`
+struct CellStats {
+    records: usize,
+    /// (columna, celdas modificadas) en el orden del header
+    changed: Vec<(String, usize)>,
+}
+
+impl CellStats {
+    /// Total y detalle por columna (solo las que cambiaron)
+    fn report(&self, label: &str) {
+        info!("📊 Rows read: {}", self.records);
+        info!("✅ {}: {}", label, self.changed.iter().map(|(_, n)| n).sum::<usize>());
+        for (column, count) in self.changed.iter().filter(|(_, n)| *n > 0) {
+            info!("   {}: {}", column, count);
+        }
+    }
+
+    fn by_column(&self) -> BTreeMap<&str, usize> {
+        self.changed.iter().map(|(column, n)| (column.as_str(), *n)).collect()
+    }
+}
+
+/// Recorre el CSV en streaming y reescribe las celdas de las columnas pedidas (o todas
+/// con `all`) con `transform`, que devuelve None si el valor queda igual
+fn transform_cells(input: &str, output: &str, columns: &[String], all: bool,
+                   mut transform: impl FnMut(&str) -> Option<String>) -> Result<CellStats, CsvToolsError> {
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input)?);
+    let headers = rdr.headers()?.clone();
+    let mut targets = vec![all; headers.len()];
+    for column in list_values(columns) {
+        let idx = headers.iter().position(|h| h.trim() == column)
+            .ok_or_else(|| exit_code::usage_error(format!("Column '{}' not found in input file", column)))?;
+        targets[idx] = true;
+    }
+
+    let mut writer = open_sink(output)?;
+    writer.write_header(&headers)?;
+
+    let mut record = StringRecord::new();
+    let mut new_record = StringRecord::new();
+    let mut changed = vec![0usize; headers.len()];
+    let mut processed = 0usize;
+
+    let mut progress = Progress::rows("Processed");
+    while rdr.read_record(&mut record)? {
+        processed += 1;
+        new_record.clear();
+        for (i, field) in record.iter().enumerate() {
+            // Filas con campos de más: los que no tienen columna en el header no se tocan
+            let new_value = if targets.get(i) == Some(&true) { transform(field) } else { None };
+            match new_value {
+                Some(value) => {
+                    changed[i] += 1;
+                    new_record.push_field(&value);
+                }
+                None => new_record.push_field(field),
+            }
+        }
+        writer.write_record(&new_record)?;
+        progress.update(processed as u64, String::new);
+    }
+    progress.finish();
+    writer.finish()?;
+
+    Ok(CellStats {
+        records: processed,
+        changed: headers.iter().zip(changed).zip(targets)
+            .filter(|(_, target)| *target).map(|((h, n), _)| (h.trim().to_string(), n)).collect(),
+    })
+}
+
+/// Lee el mapeo viejo → nuevo (se saltea el header; un valor viejo repetido con
+/// distinto valor nuevo es un error porque el reemplazo sería ambiguo)
+fn load_mapping(path: &str) -> Result<HashMap<String, String>, CsvToolsError> {
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(path)?);
+    let mut mapping: HashMap<String, String> = HashMap::new();
+    for (i, record) in rdr.records().enumerate() {
+        let record = record?;
+        let line = i + 2;
+        if record.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        if record.len() != 2 {
+            return Err(exit_code::usage_error(format!(
+                "Mapping '{}' line {}: expected 2 columns (old, new), found {}", path, line, record.len())));
+        }
+        let (old, new) = (record[0].trim(), record[1].trim());
+        match mapping.get(old) {
+            Some(existing) if existing != new => {
+                return Err(exit_code::usage_error(format!(
+                    "Mapping '{}' line {}: '{}' maps to both '{}' and '{}'", path, line, old, existing, new)));
+            }
+            _ => {
+                mapping.insert(old.to_string(), new.to_string());
+            }
+        }
+    }
+    if mapping.is_empty() {
+        return Err(format!("Mapping file '{}' has no entries", path).into());
+    }
+    Ok(mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_map_values_unmatched_policies() {
+        let dir = TempDir::new("map_values");
+        dir.write("in.csv", "Cuil,IdRegion\n1,01\n2, 02\n3,99\n4,\n");
+        dir.write("regions.csv", "viejo,nuevo\n01,NOA\n02,NEA\n");
+
+        let args = |unmatched| MapValuesArgs {
+            input: dir.path("in.csv"), output: dir.path("out.csv"), column: "IdRegion".into(),
+            mapping: dir.path("regions.csv"), unmatched,
+        };
+        map_values(&args(Unmatched::Keep)).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,IdRegion\n1,NOA\n2,NEA\n3,99\n4,\n");
+        map_values(&args(Unmatched::Blank)).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,IdRegion\n1,NOA\n2,NEA\n3,\n4,\n");
+        assert!(map_values(&args(Unmatched::Error)).is_err());
+
+        dir.write("regions.csv", "viejo,nuevo\n01,NOA\n01,NEA\n");
+        assert!(map_values(&args(Unmatched::Keep)).is_err());
+    }
+
+    #[test]
+    fn test_recase_title() {
+        assert_eq!(title_case("PEREZ-GOMEZ  juan d'angelo"), "Perez-Gomez  Juan D'Angelo");
+        assert_eq!(title_case("ÑANDÚ SRL"), "Ñandú Srl");
+
+        let dir = TempDir::new("recase");
+        dir.write("in.csv", "Cuil,ApellidoNombre\n1,perez juan\n");
+        recase(&RecaseArgs { input: dir.path("in.csv"), output: dir.path("out.csv"), columns: vec!["ApellidoNombre".into()], to: Case::Upper }).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,ApellidoNombre\n1,PEREZ JUAN\n");
+    }
+
+    #[test]
+    fn test_normalize_nulls_counts_per_column() {
+        let dir = TempDir::new("nulls");
+        dir.write("in.csv", "Cuil,Telefono,Obs\n1,NULL, N/A \n2,-,Nulo\n");
+
+        let args = |columns: &[&str]| NormalizeNullsArgs {
+            input: dir.path("in.csv"), output: dir.path("out.csv"),
+            tokens: vec!["NULL".into(), "N/A".into(), "-".into()], to: String::new(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+        };
+        normalize_nulls(&args(&[])).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,Telefono,Obs\n1,,\n2,,Nulo\n");
+        normalize_nulls(&args(&["Obs"])).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,Telefono,Obs\n1,NULL,\n2,-,Nulo\n");
+    }
+
+    #[test]
+    fn test_concat_columns_appends_key() {
+        let dir = TempDir::new("concat");
+        dir.write("in.csv", "NroDoc,Nombre,IdEntidad\n123,\"Perez, Juan\",7\n456,Gomez\n");
+
+        let args = |name: &str| ConcatColumnsArgs {
+            input: dir.path("in.csv"), output: dir.path("out.csv"),
+            columns: vec!["NroDoc".into(), "IdEntidad".into()], sep: "#".into(), name: name.into(),
+        };
+        concat_columns(&args("CompositeKey")).unwrap();
+        assert_eq!(dir.read("out.csv"),
+                   "NroDoc,Nombre,IdEntidad,CompositeKey\n123,\"Perez, Juan\",7,123#7\n456,Gomez,,456#\n");
+        assert!(concat_columns(&args("Nombre")).is_err());
+    }
+
+    #[test]
+    fn test_split_column_policies() {
+        let dir = TempDir::new("split");
+        dir.write("in.csv", "Cuil,ApellidoNombre,Monto\n1,\"Perez, Juan\",10\n2,Gomez,20\n3,\"Diaz, Ana, Maria\",30\n");
+
+        let args = |on_missing, on_extra| SplitColumnArgs {
+            input: dir.path("in.csv"), output: dir.path("out.csv"), column: "ApellidoNombre".into(), sep: ",".into(),
+            into: vec!["Apellido".into(), "Nombre".into()], max_parts: None, on_missing, on_extra, keep_source: false,
+        };
+        split_column(&args(MissingParts::Fill, ExtraParts::Merge)).unwrap();
+        assert_eq!(dir.read("out.csv"),
+                   "Cuil,Apellido,Nombre,Monto\n1,Perez,Juan,10\n2,Gomez,,20\n3,Diaz,\"Ana, Maria\",30\n");
+        split_column(&args(MissingParts::Error, ExtraParts::Drop)).unwrap();
+        assert_eq!(dir.read("out.csv"),
+                   "Cuil,Apellido,Nombre,Monto\n1,Perez,Juan,10\n3,Diaz,Ana,30\n");
+        let log = dir.read("out.csv.split_errors.log");
+        assert!(log.contains("[LINE 3] ❌ SPLIT_MISSING_PARTS") && log.contains("[LINE 4] ❌ SPLIT_EXTRA_PARTS"));
+    }
+
+    #[test]
+    fn test_trim_selected_columns() {
+        let dir = TempDir::new("trim");
+        dir.write("in.csv", "Monto,Nombre,Obs\n\" 12.5 \",\"  Perez   Juan \", x \n");
+
+        let args = |columns: &[&str], all, collapse| TrimArgs {
+            input: dir.path("in.csv"), output: dir.path("out.csv"),
+            columns: columns.iter().map(|c| c.to_string()).collect(), all, collapse,
+        };
+        trim(&args(&["Monto", "Nombre"], false, false)).unwrap();
+        assert_eq!(dir.read("out.csv"), "Monto,Nombre,Obs\n12.5,Perez   Juan, x \n");
+        trim(&args(&[], true, true)).unwrap();
+        assert_eq!(dir.read("out.csv"), "Monto,Nombre,Obs\n12.5,Perez Juan,x\n");
+        assert!(trim(&args(&["Dni"], false, false)).is_err());
+    }
+}
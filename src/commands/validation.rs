@@ -1,7 +1,15 @@
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufWriter, Write};
-use csv::{Reader, Writer};
+use std::io::Write;
+use csv::{ReaderBuilder, WriterBuilder};
+
+use crate::checkpoint::{CheckpointManager, ResumeArgs};
+use crate::config;
+use crate::error::CsvToolsError;
+use crate::error_report::{self, ErrorRecord};
+use crate::exit_code::{data_issues, usage_error};
+use crate::file_utils::{is_stdio, open_input, status_output};
+use crate::info;
+use crate::logging;
+use crate::progress::Progress;
 use crate::models::{
     get_expected_headers, 
     validate_headers, 
@@ -12,13 +20,13 @@ use crate::models::{
 };
 
 /// Enhanced CSV header validation (compatible con chunk-export-v2)
-pub fn enhanced_check(args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn enhanced_check(args: &[String]) -> Result<(), CsvToolsError> {
     let input_file = &args[2];
     let model_type = args.get(3).map(|s| s.as_str());
     
-    println!("🔍 Checking CSV file: {}", input_file);
+    info!("🔍 Checking CSV file: {}", input_file);
     
-    let mut reader = Reader::from_path(input_file)?;
+    let mut reader = ReaderBuilder::new().delimiter(config::delimiter()).from_reader(open_input(input_file)?);
     let headers = reader.headers()?;
     
     // Check for duplicate headers
@@ -29,9 +37,9 @@ pub fn enhanced_check(args: &[String]) -> Result<(), Box<dyn Error>> {
     let has_duplicates = header_vec.len() != original_len;
     
     if has_duplicates {
-        println!("❌ Duplicate headers detected!");
-        println!("💡 Use 'clean' command to remove duplicates");
-        return Ok(());
+        eprintln!("❌ Duplicate headers detected!");
+        eprintln!("💡 Use 'clean' command to remove duplicates");
+        return Err(data_issues("Duplicate headers detected"));
     }
     
     // DynamoDB model-specific validation
@@ -39,98 +47,115 @@ pub fn enhanced_check(args: &[String]) -> Result<(), Box<dyn Error>> {
         let expected_headers = get_expected_headers(model)?;
         let actual_headers: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
         
-        println!("\n📋 DynamoDB Schema Validation");
-        println!("   Model: {}", model);
-        println!("   Expected columns: {}", expected_headers.len());
-        println!("   Found columns: {}", actual_headers.len());
+        info!("\n📋 DynamoDB Schema Validation");
+        info!("   Model: {}", model);
+        info!("   Expected columns: {}", expected_headers.len());
+        info!("   Found columns: {}", actual_headers.len());
         
         // Validación estricta del modelo DynamoDB
         match validate_headers(&actual_headers, model) {
             Ok(_) => {
-                println!("\n✅ Headers match DynamoDB model schema perfectly");
-                println!("\n📊 Schema details (EfficientDynamoDb attributes):");
+                info!("\n✅ Headers match DynamoDB model schema perfectly");
+                info!("\n📊 Schema details (EfficientDynamoDb attributes):");
                 for (i, header) in expected_headers.iter().enumerate() {
                     let key_type = match (i, model) {
                         (0, "siisa_morosos") => " [PartitionKey: Cuil]",
                         (1, "siisa_morosos") => " [SortKey: IdTransmit]",
                         _ => ""
                     };
-                    println!("   [{:2}] {}{}", i + 1, header, key_type);
+                    info!("   [{:2}] {}{}", i + 1, header, key_type);
                 }
                 
-                println!("\n🔗 C# Model: SiisaRestApi.Common/Models/DynamoModels/MorososTransmitDynamoDbModel.cs");
+                info!("\n🔗 C# Model: SiisaRestApi.Common/Models/DynamoModels/MorososTransmitDynamoDbModel.cs");
             }
             Err(e) => {
-                println!("\n❌ Schema validation failed:");
-                println!("{}", e);
-                return Ok(());
+                eprintln!("\n❌ Schema validation failed:");
+                eprintln!("{}", e);
+                return Err(CsvToolsError::SchemaMismatch(format!("Header does not match the DynamoDB model: {}", e)));
             }
         }
     } else {
-        println!("✅ No duplicate headers found");
-        println!("   Columns: {}", headers.len());
-        println!("\n📋 Headers found:");
+        info!("✅ No duplicate headers found");
+        info!("   Columns: {}", headers.len());
+        info!("\n📋 Headers found:");
         for (i, header) in headers.iter().enumerate() {
-            println!("   [{:2}] {}", i + 1, header);
+            info!("   [{:2}] {}", i + 1, header);
         }
     }
     
     // Count records (compatible con ChunkSize config)
     let record_count = reader.records().count();
-    println!("\n📊 Total data records: {}", record_count);
+    info!("\n📊 Total data records: {}", record_count);
     
     Ok(())
 }
 
-/// Validate CSV against DynamoDB model schema
+/// Validate CSV against DynamoDB model schema (comando `validate_model`)
 /// ⚠️ Uses DynamoDB PartitionKey+SortKey, not SQL CompositePrimaryKey
-pub fn validate_csv_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let input_file = &args[2];
-    let error_file = &args[3];
-    let table_name = &args[4];
-    let max_show: usize = args[5].parse().unwrap_or(10);
-    let cancel_on_max: bool = args[6].parse().unwrap_or(false);
-    
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  DynamoDB Schema Validation - SiisaRestApi Compatible        ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📋 DynamoDB Table: {}", table_name);
-    println!("📄 Input CSV: {}", input_file);
-    println!("📝 Error Log: {}", error_file);
-    println!("⚠️  Max errors to display: {}", max_show);
-    println!("🛑 Cancel on max errors: {}\n", cancel_on_max);
+/// Guarda checkpoints periódicos en `<error_file>.checkpoint.json` (--resume)
+pub fn validate_csv_schema(
+    input_file: &str,
+    error_file: &str,
+    table_name: &str,
+    max_show: usize,
+    cancel_on_max: bool,
+    resume: &ResumeArgs,
+) -> Result<(), CsvToolsError> {
+    logging::banner("DynamoDB Schema Validation - SiisaRestApi Compatible");
+    info!("📋 DynamoDB Table: {}", table_name);
+    info!("📄 Input CSV: {}", input_file);
+    info!("📝 Error Log: {}", error_file);
+    info!("⚠️  Max errors to display: {}", max_show);
+    info!("🛑 Cancel on max errors: {}\n", cancel_on_max);
     
-    let mut reader = Reader::from_path(input_file)?;
-    let headers = reader.headers()?.clone();
+    let mut checkpoints = CheckpointManager::new("validate_model", input_file, error_file, &[error_file], resume);
+    let point = if resume.resume { Some(checkpoints.load()?) } else { None };
+    let (mut reader, headers) = checkpoints.open_reader(point.as_ref())?;
     let actual_headers: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
     
     // Validate header structure against DynamoDB model (MorososTransmitDynamoDbModel)
     let expected_headers = get_expected_headers(table_name)?;
     
-    println!("🔍 Validating header schema...");
+    info!("🔍 Validating header schema...");
     match validate_headers(&actual_headers, table_name) {
         Ok(_) => {
-            println!("✅ Header schema matches DynamoDB model ({} columns)", headers.len());
-            println!("   PartitionKey: {} (Cuil)", expected_headers[0]);
-            println!("   SortKey: {} (IdTransmit)\n", expected_headers[1]);
+            info!("✅ Header schema matches DynamoDB model ({} columns)", headers.len());
+            info!("   PartitionKey: {} (Cuil)", expected_headers[0]);
+            info!("   SortKey: {} (IdTransmit)\n", expected_headers[1]);
         }
         Err(e) => {
-            println!("❌ Header validation failed:");
-            println!("{}\n", e);
-            return Err("Schema mismatch - cannot proceed with DynamoDB validation".into());
+            eprintln!("❌ Header validation failed:");
+            eprintln!("{}\n", e);
+            return Err(CsvToolsError::SchemaMismatch(format!("Schema mismatch - cannot proceed with DynamoDB validation: {}", e)));
         }
     }
     
-    let mut error_writer = BufWriter::new(File::create(error_file)?);
-    writeln!(error_writer, "Line,ErrorType,Details,DynamoDbKey,SqlCompositeKey")?;
+    let mut error_writer = checkpoints.open_output(error_file, point.as_ref())?;
     
     let mut error_count = 0;
     let mut processed = 0;
+    let mut lines_done = 0;
+    match &point {
+        Some(p) => {
+            info!("⏩ Resuming at line {} ({} records already validated)", p.line, p.records);
+            error_count = p.counter("errors") as usize;
+            processed = p.counter("processed") as usize;
+            lines_done = p.records as usize;
+        }
+        None => writeln!(error_writer, "Line,ErrorType,Details,DynamoDbKey,SqlCompositeKey")?,
+    }
     
-    println!("🔍 Validating data records for DynamoDB import...\n");
+    info!("🔍 Validating data records for DynamoDB import...\n");
     
-    for (idx, result) in reader.records().enumerate() {
+    let mut progress = Progress::rows("Processed");
+    let mut record = csv::StringRecord::new();
+    for idx in lines_done.. {
         let line_num = idx + 2; // +1 for 0-index, +1 for header
+        let result = match reader.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => Ok(&record),
+            Err(e) => Err(e),
+        };
         
         match result {
             Ok(record) => {
@@ -139,10 +164,10 @@ pub fn validate_csv_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                     error_count += 1;
                     
                     // Extract both keys for comprehensive error reporting
-                    let dynamo_key = parse_dynamodb_key(&record, table_name)
+                    let dynamo_key = parse_dynamodb_key(record, table_name)
                         .unwrap_or_else(|_| "INVALID_DYNAMO_KEY".to_string());
                     
-                    let sql_key = parse_sql_composite_key(&record)
+                    let sql_key = parse_sql_composite_key(record)
                         .map(|(c, t, n)| format_sql_composite_key(c, t, &n))
                         .unwrap_or_else(|_| "INVALID_SQL_KEY".to_string());
                     
@@ -153,6 +178,8 @@ pub fn validate_csv_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                     
                     writeln!(error_writer, "{},ColumnCount,{},{},{}",
                              line_num, error_msg, dynamo_key, sql_key)?;
+                    error_report::emit(&ErrorRecord::new(line_num, "COLUMN_COUNT", error_msg.as_str())
+                        .at_byte(checkpoints.byte_offset(record.position())))?;
                     
                     if error_count <= max_show {
                         eprintln!("❌ Line {}: {}", line_num, error_msg);
@@ -161,7 +188,7 @@ pub fn validate_csv_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                     }
                     
                     if cancel_on_max && error_count >= max_show {
-                        println!("\n⚠️  Max errors ({}) reached. Stopping validation.", max_show);
+                        info!("\n⚠️  Max errors ({}) reached. Stopping validation.", max_show);
                         break;
                     }
                 }
@@ -172,15 +199,19 @@ pub fn validate_csv_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                         if let Err(e) = validate_field_type(value, field_name, table_name) {
                             error_count += 1;
                             
-                            let dynamo_key = parse_dynamodb_key(&record, table_name)
+                            let dynamo_key = parse_dynamodb_key(record, table_name)
                                 .unwrap_or_else(|_| "INVALID_DYNAMO_KEY".to_string());
                             
-                            let sql_key = parse_sql_composite_key(&record)
+                            let sql_key = parse_sql_composite_key(record)
                                 .map(|(c, t, n)| format_sql_composite_key(c, t, &n))
                                 .unwrap_or_else(|_| "INVALID_SQL_KEY".to_string());
                             
                             writeln!(error_writer, "{},TypeError,{},{},{}",
                                      line_num, e, dynamo_key, sql_key)?;
+                            error_report::emit(&ErrorRecord::new(line_num, "INVALID_TYPE", e.as_str())
+                                .at_byte(checkpoints.byte_offset(record.position()))
+                                .column(field_name)
+                                .value(value))?;
                             
                             if error_count <= max_show {
                                 eprintln!("❌ Line {}: {}", line_num, e);
@@ -191,24 +222,33 @@ pub fn validate_csv_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
                 }
                 
                 processed += 1;
-                if processed % 10_000 == 0 {
-                    print!("\r📊 Processed: {} | Errors: {}", processed, error_count);
-                    std::io::stdout().flush()?;
-                }
+                progress.update(processed as u64, || format!("Errors: {}", error_count));
             }
             Err(e) => {
                 error_count += 1;
+                let offset = checkpoints.byte_offset(e.position());
+                let e = checkpoints.error_message(&e);
                 writeln!(error_writer, "{},ParseError,{},UNKNOWN_DYNAMO_KEY,UNKNOWN_SQL_KEY", 
                          line_num, e)?;
+                error_report::emit(&ErrorRecord::new(line_num, "PARSE_ERROR", e.as_str()).at_byte(offset))?;
                 
                 if error_count <= max_show {
                     eprintln!("❌ Line {}: Parse error - {}", line_num, e);
                 }
             }
         }
+
+        if checkpoints.due() {
+            error_writer.flush()?;
+            checkpoints.save(reader.position(), (idx + 1) as u64,
+                             &[("processed", processed as u64), ("errors", error_count as u64)], None)?;
+            checkpoints.stop_at_deadline()?;
+        }
     }
     
     error_writer.flush()?;
+    checkpoints.finish();
+    progress.finish();
     
     let error_rate = if processed > 0 {
         (error_count as f64 / processed as f64) * 100.0
@@ -216,45 +256,65 @@ pub fn validate_csv_schema(args: &[String]) -> Result<(), Box<dyn Error>> {
         0.0
     };
     
-    println!("\n\n╔══════════════════════════════════════════════════════════════╗");
-    println!("║  DynamoDB Validation Summary                                 ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
-    println!("📊 Processed: {} records", processed);
-    println!("❌ Errors: {} ({:.2}%)", error_count, error_rate);
-    println!("📝 Error log: {}", error_file);
+    info!("\n");
+    logging::banner("DynamoDB Validation Summary");
+    info!("📊 Processed: {} records", processed);
+    info!("❌ Errors: {} ({:.2}%)", error_count, error_rate);
+    info!("📝 Error log: {}", error_file);
     
     if error_count == 0 {
-        println!("\n🎉 All records valid for DynamoDB import!");
-        println!("✅ Ready for EfficientDynamoDb batch write operation");
-        println!("🔗 See: SiisaRestApi.Process chunk-export-v2 → DynamoDB import");
+        info!("\n🎉 All records valid for DynamoDB import!");
+        info!("✅ Ready for EfficientDynamoDb batch write operation");
+        info!("🔗 See: SiisaRestApi.Process chunk-export-v2 → DynamoDB import");
     } else {
-        println!("\n⚠️  Review error file before DynamoDB import");
-        println!("💡 Use 'clean_invalid_lines' command to filter invalid records");
+        info!("\n⚠️  Review error file before DynamoDB import");
+        info!("💡 Use 'clean_invalid_lines' command to filter invalid records");
+        return Err(data_issues(format!("{} invalid record(s), see {}", error_count, error_file)));
     }
     
     Ok(())
 }
 
 /// Remove invalid lines from CSV (DynamoDB-ready cleaning)
-pub fn clean_invalid_lines(args: &[String]) -> Result<(), Box<dyn Error>> {
-    let input_file = &args[2];
-    let output_file = &args[3];
-    let error_file = &args[4];
-    
-    println!("🧹 Cleaning invalid lines for DynamoDB import: {}", input_file);
+/// Entrada y salidas aceptan `-` (stdin/stdout); con stdout ocupado los mensajes van a stderr
+/// Con archivos guarda checkpoints periódicos en `<output>.checkpoint.json` (--resume)
+pub fn clean_invalid_lines(input_file: &str, output_file: &str, error_file: &str, row_number: Option<&str>, resume: &ResumeArgs) -> Result<(), CsvToolsError> {
+    if is_stdio(output_file) && is_stdio(error_file) {
+        return Err(usage_error("Output and error file cannot both be stdout ('-')"));
+    }
+    let mut status = status_output(if is_stdio(error_file) { error_file } else { output_file });
+
+    writeln!(status, "🧹 Cleaning invalid lines for DynamoDB import: {}", input_file)?;
     
-    let mut reader = Reader::from_path(input_file)?;
-    let headers = reader.headers()?.clone();
+    let mut checkpoints = CheckpointManager::new("clean_invalid_lines", input_file, output_file, &[output_file, error_file], resume);
+    let point = if resume.resume { Some(checkpoints.load()?) } else { None };
+    let (mut reader, headers) = checkpoints.open_reader(point.as_ref())?;
     let expected_cols = headers.len();
+    if let Some(name) = row_number.filter(|name| headers.iter().any(|h| h.trim() == *name)) {
+        return Err(usage_error(format!("Column '{}' already exists in the header", name)));
+    }
     
-    let mut writer = Writer::from_path(output_file)?;
-    writer.write_record(&headers)?;
-    
-    let mut error_writer = BufWriter::new(File::create(error_file)?);
-    writeln!(error_writer, "Line,Issue,Details,DynamoDbKey,SqlCompositeKey")?;
+    let mut writer = WriterBuilder::new().delimiter(config::delimiter()).from_writer(checkpoints.open_output(output_file, point.as_ref())?);
+    let mut error_writer = checkpoints.open_output(error_file, point.as_ref())?;
     
     let mut valid_count = 0u64;
     let mut invalid_count = 0u64;
+    let mut lines_done = 0;
+    match &point {
+        Some(p) => {
+            writeln!(status, "⏩ Resuming at line {} ({} records already processed)", p.line, p.records)?;
+            valid_count = p.counter("valid");
+            invalid_count = p.counter("invalid");
+            lines_done = p.records as usize;
+        }
+        None => {
+            match row_number {
+                Some(name) => writer.write_record(headers.iter().chain(std::iter::once(name)))?,
+                None => writer.write_record(&headers)?,
+            }
+            writeln!(error_writer, "Line,Issue,Details,DynamoDbKey,SqlCompositeKey")?;
+        }
+    }
     
     // Determine model type from headers for key extraction
     let model_type = if headers.len() == 14 && headers.get(0) == Some("Cuil") {
@@ -263,21 +323,34 @@ pub fn clean_invalid_lines(args: &[String]) -> Result<(), Box<dyn Error>> {
         "unknown"
     };
     
-    for (idx, result) in reader.records().enumerate() {
+    let mut progress = Progress::rows("Cleaning");
+    let mut record = csv::StringRecord::new();
+    for idx in lines_done.. {
         let line_num = idx + 2;
+        let result = match reader.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => Ok(&record),
+            Err(e) => Err(e),
+        };
         
         match result {
             Ok(record) => {
+                // Un campo multilínea ocupa varias líneas: la del registro es la de su inicio
+                let line_num = checkpoints.line(record.position()).map_or(line_num, |line| line as usize);
                 if record.len() == expected_cols {
-                    writer.write_record(&record)?;
+                    // Con --add-row-number, la línea (misma numeración que el log de errores) va al final
+                    match row_number {
+                        Some(_) => writer.write_record(record.iter().chain(std::iter::once(line_num.to_string().as_str())))?,
+                        None => writer.write_record(record)?,
+                    }
                     valid_count += 1;
                 } else {
                     invalid_count += 1;
                     
-                    let dynamo_key = parse_dynamodb_key(&record, model_type)
+                    let dynamo_key = parse_dynamodb_key(record, model_type)
                         .unwrap_or_else(|_| "INVALID_DYNAMO_KEY".to_string());
                     
-                    let sql_key = parse_sql_composite_key(&record)
+                    let sql_key = parse_sql_composite_key(record)
                         .map(|(c, t, n)| format_sql_composite_key(c, t, &n))
                         .unwrap_or_else(|_| "INVALID_SQL_KEY".to_string());
                     
@@ -286,33 +359,45 @@ pub fn clean_invalid_lines(args: &[String]) -> Result<(), Box<dyn Error>> {
                         "{},ColumnMismatch,Expected {} but found {},{},{}",
                         line_num, expected_cols, record.len(), dynamo_key, sql_key
                     )?;
+                    error_report::emit(&ErrorRecord::new(line_num, "COLUMN_COUNT",
+                        format!("Expected {} but found {}", expected_cols, record.len()))
+                        .at_byte(checkpoints.byte_offset(record.position())))?;
                 }
             }
             Err(e) => {
                 invalid_count += 1;
+                let message = checkpoints.error_message(&e);
                 writeln!(error_writer, "{},ParseError,{},UNKNOWN_DYNAMO_KEY,UNKNOWN_SQL_KEY", 
-                         line_num, e)?;
+                         line_num, message)?;
+                error_report::emit(&ErrorRecord::new(line_num, "PARSE_ERROR", message)
+                    .at_byte(checkpoints.byte_offset(e.position())))?;
             }
         }
         
-        if (valid_count + invalid_count) % 10_000 == 0 {
-            print!("\r📊 Valid: {} | Invalid: {}", valid_count, invalid_count);
-            std::io::stdout().flush()?;
+        progress.update(valid_count + invalid_count, || format!("Valid: {} | Invalid: {}", valid_count, invalid_count));
+
+        if checkpoints.due() {
+            writer.flush()?;
+            error_writer.flush()?;
+            checkpoints.save(reader.position(), (idx + 1) as u64, &[("valid", valid_count), ("invalid", invalid_count)], None)?;
+            checkpoints.stop_at_deadline()?;
         }
     }
     
     writer.flush()?;
     error_writer.flush()?;
+    checkpoints.finish();
+    progress.finish();
     
     let total = valid_count + invalid_count;
     let invalid_rate = (invalid_count as f64 / total as f64) * 100.0;
     
-    println!("\n\n✅ Cleaning complete (DynamoDB-ready):");
-    println!("   Valid records: {} ({:.2}%)", valid_count, 100.0 - invalid_rate);
-    println!("   Invalid records removed: {} ({:.2}%)", invalid_count, invalid_rate);
-    println!("📝 Clean output: {}", output_file);
-    println!("📝 Error log: {}", error_file);
-    println!("\n💡 Clean CSV is ready for DynamoDB batch write via EfficientDynamoDb");
+    writeln!(status, "\n\n✅ Cleaning complete (DynamoDB-ready):")?;
+    writeln!(status, "   Valid records: {} ({:.2}%)", valid_count, 100.0 - invalid_rate)?;
+    writeln!(status, "   Invalid records removed: {} ({:.2}%)", invalid_count, invalid_rate)?;
+    writeln!(status, "📝 Clean output: {}", output_file)?;
+    writeln!(status, "📝 Error log: {}", error_file)?;
+    writeln!(status, "\n💡 Clean CSV is ready for DynamoDB batch write via EfficientDynamoDb")?;
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,193 @@
+// Carpeta de entrada vigilada: cada CSV nuevo que aparece en <dir> se procesa con un
+// plan de pipeline y se mueve a la carpeta de procesados o de errores
+// Se usa sondeo (no notificaciones del sistema): funciona igual en discos locales
+// y en recursos compartidos de red, donde llegan los exports
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::Args;
+
+use crate::commands::pipeline::{self, PipelineArgs, PipelineRunArgs, StepRunner};
+use crate::error::CsvToolsError;
+use crate::exit_code;
+use crate::file_utils;
+use crate::info;
+use crate::logging;
+use crate::runtime_limit;
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    #[arg(value_name = "DIR", help = "Directory where new CSV files arrive")]
+    pub dir: String,
+    #[arg(long, value_name = "PLAN.yaml",
+          help = "Pipeline plan run for each file (${file}, ${name}, ${output_dir} are set per file)")]
+    pub pipeline: String,
+    #[arg(long = "param", value_name = "NAME=VALUE", value_parser = pipeline::parse_param,
+          help = "Plan parameter (repeatable)")]
+    pub params: Vec<(String, String)>,
+    #[arg(long, value_name = "DIR", help = "Folder for the plan outputs and run reports [default: <DIR>/output]")]
+    pub output_dir: Option<String>,
+    #[arg(long, value_name = "DIR", help = "Input files that were processed OK are moved here [default: <DIR>/processed]")]
+    pub processed_dir: Option<String>,
+    #[arg(long, value_name = "DIR", help = "Failed input files and their error logs are moved here [default: <DIR>/errors]")]
+    pub error_dir: Option<String>,
+    #[arg(long, value_name = "SECS", default_value_t = 5, help = "Seconds between directory scans")]
+    pub interval: u64,
+    #[arg(long, help = "Process the files already present and exit (for schedulers)")]
+    pub once: bool,
+}
+
+/// Carpetas de trabajo resueltas de una corrida de `watch`
+struct WatchDirs {
+    output: PathBuf,
+    processed: PathBuf,
+    errors: PathBuf,
+}
+
+/// Vigila `args.dir` y corre el plan sobre cada CSV que llega; termina con --once,
+/// con --max-runtime o con Ctrl+C
+/// Un archivo se toma cuando su tamaño no cambió entre dos sondeos (ya terminó de copiarse)
+pub fn run_watch(program: &str, args: &WatchArgs, run_step: &StepRunner) -> Result<(), CsvToolsError> {
+    let dir = Path::new(&args.dir);
+    if !dir.is_dir() {
+        return Err(exit_code::usage_error(format!("Not a directory: {}", args.dir)));
+    }
+    let dirs = WatchDirs {
+        output: args.output_dir.as_deref().map(PathBuf::from).unwrap_or_else(|| dir.join("output")),
+        processed: args.processed_dir.as_deref().map(PathBuf::from).unwrap_or_else(|| dir.join("processed")),
+        errors: args.error_dir.as_deref().map(PathBuf::from).unwrap_or_else(|| dir.join("errors")),
+    };
+    for d in [&dirs.output, &dirs.processed, &dirs.errors] {
+        std::fs::create_dir_all(d)?;
+    }
+
+    logging::banner("Watch Folder");
+    info!("👀 Watching: {} (every {}s)", args.dir, args.interval);
+    info!("📋 Plan: {}", args.pipeline);
+    info!("📁 Output: {} | Processed: {} | Errors: {}",
+          dirs.output.display(), dirs.processed.display(), dirs.errors.display());
+    info!();
+
+    // Tamaño visto en el sondeo anterior de cada archivo pendiente
+    let mut pending: HashMap<PathBuf, u64> = HashMap::new();
+    let (mut ok, mut failed) = (0usize, 0usize);
+
+    loop {
+        for (path, size) in scan(dir)? {
+            // --once: lo que ya está en la carpeta se considera completo
+            let stable = args.once || pending.get(&path) == Some(&size);
+            if !stable {
+                pending.insert(path, size);
+                continue;
+            }
+            pending.remove(&path);
+
+            if process_file(program, args, &dirs, &path, run_step)? {
+                ok += 1;
+            } else {
+                failed += 1;
+            }
+        }
+
+        if args.once || runtime_limit::exceeded() {
+            break;
+        }
+        std::thread::sleep(Duration::from_secs(args.interval.max(1)));
+    }
+
+    logging::banner("Watch Summary");
+    info!("✅ Processed: {} | ❌ Failed: {}", ok, failed);
+    logging::summary(&serde_json::json!({
+        "command": "watch",
+        "dir": args.dir,
+        "processed": ok,
+        "failed": failed,
+    }))?;
+
+    if failed > 0 {
+        return Err(exit_code::data_issues(format!("{} of {} files failed (see {})", failed, ok + failed, dirs.errors.display())));
+    }
+    Ok(())
+}
+
+/// CSV (.csv / .csv.gz) de la carpeta con su tamaño actual, ordenados por nombre
+fn scan(dir: &Path) -> Result<Vec<(PathBuf, u64)>, CsvToolsError> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_file() && file_utils::is_csv_name(&path.to_string_lossy()) {
+            files.push((path, entry.metadata()?.len()));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Corre el plan sobre un archivo y lo mueve según el resultado; false si el plan falló
+/// Solo corta el watch un error al mover el archivo (no se puede seguir sin reprocesarlo)
+fn process_file(
+    program: &str,
+    args: &WatchArgs,
+    dirs: &WatchDirs,
+    path: &Path,
+    run_step: &StepRunner,
+) -> Result<bool, CsvToolsError> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let name = file_name.trim_end_matches(".gz").trim_end_matches(".csv").to_string();
+    info!("📥 New file: {}", file_name);
+
+    let mut params = vec![
+        ("file".to_string(), path.to_string_lossy().into_owned()),
+        ("name".to_string(), name.clone()),
+        ("output_dir".to_string(), dirs.output.to_string_lossy().into_owned()),
+    ];
+    params.extend(args.params.iter().cloned());
+    let report = dirs.output.join(format!("{}.report.json", name));
+    let run = PipelineArgs {
+        command: None,
+        run: Some(PipelineRunArgs {
+            plan: args.pipeline.clone(),
+            params,
+            dry_run: false,
+            workdir: Some(dirs.output.to_string_lossy().into_owned()),
+            keep_temp: false,
+            report: Some(report.to_string_lossy().into_owned()),
+        }),
+    };
+
+    match pipeline::run_pipeline(program, &run, run_step) {
+        Ok(()) => {
+            let target = move_to(path, &dirs.processed)?;
+            info!("✅ {} -> {}", file_name, target.display());
+            info!();
+            Ok(true)
+        }
+        Err(e) => {
+            let target = move_to(path, &dirs.errors)?;
+            let log = dirs.errors.join(format!("{}.error.log", name));
+            std::fs::write(&log, format!("{}\nexit code: {}\n", e, exit_code::for_error(&e)))?;
+            if report.exists() {
+                move_to(&report, &dirs.errors)?;
+            }
+            eprintln!("❌ {} failed: {}", file_name, e);
+            logging::to_file(&format!("❌ {} failed: {}", file_name, e));
+            info!("📄 {} -> {} (log: {})", file_name, target.display(), log.display());
+            info!();
+            Ok(false)
+        }
+    }
+}
+
+/// Mueve `path` a `dir`; si ya hay un archivo con ese nombre se agrega la fecha y hora
+fn move_to(path: &Path, dir: &Path) -> Result<PathBuf, CsvToolsError> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let mut target = dir.join(&file_name);
+    if target.exists() {
+        target = dir.join(format!("{}_{}", chrono::Local::now().format("%Y%m%d%H%M%S"), file_name));
+    }
+    std::fs::rename(path, &target)?;
+    Ok(target)
+}
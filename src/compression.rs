@@ -0,0 +1,366 @@
+// Salida comprimida: gzip de un hilo, gzip en bloques paralelos (estilo pigz) o zstd
+// El gzip es multi-miembro estándar (gunzip / zcat / MultiGzDecoder); ambos formatos
+// los acepta DynamoDB ImportTable (GZIP / ZSTD)
+// El formato sale de la extensión (.gz / .zst) o de `--compress`, que además la agrega
+
+use std::io::{self, BufWriter, Write};
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::encoding;
+use crate::error::CsvToolsError;
+use crate::file_utils::is_stdio;
+use crate::info;
+use crate::io_retry::RetryWriter;
+
+// Tamaño de cada bloque que se comprime como un miembro gzip independiente
+const BLOCK_SIZE: usize = 1024 * 1024;
+
+// Nivel de zstd (el default de la CLI `zstd`: buena relación velocidad/tamaño)
+const ZSTD_LEVEL: i32 = 3;
+
+// --compress de la corrida (sin fijar: solo por extensión)
+static FORCED: OnceLock<OutputCompression> = OnceLock::new();
+
+/// Formato de compresión de las salidas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputCompression {
+    /// gzip (.gz); en paralelo por bloques con --compress-threads
+    Gzip,
+    /// Zstandard (.zst)
+    Zstd,
+}
+
+impl OutputCompression {
+    /// Formato según la extensión de la ruta (None: sin comprimir)
+    pub fn from_path(path: &str) -> Option<Self> {
+        let lower = path.to_lowercase();
+        if lower.ends_with(".gz") {
+            Some(OutputCompression::Gzip)
+        } else if lower.ends_with(".zst") {
+            Some(OutputCompression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputCompression::Gzip => ".gz",
+            OutputCompression::Zstd => ".zst",
+        }
+    }
+}
+
+/// Fija `--compress` para todas las salidas CSV de la corrida
+pub fn set_output_compression(compression: OutputCompression) {
+    FORCED.set(compression).ok();
+}
+
+/// Formato con que se escribe `path`: la extensión manda; si no tiene, el de `--compress`
+pub fn compression_for(path: &str) -> Option<OutputCompression> {
+    if is_stdio(path) {
+        return None;
+    }
+    OutputCompression::from_path(path).or_else(|| FORCED.get().copied())
+}
+
+/// Ruta real de una salida: con `--compress` se agrega la extensión si falta
+/// (`out.csv` → `out.csv.gz`); sin él, o con stdout, queda igual
+pub fn output_path(path: &str) -> String {
+    match compression_for(path) {
+        Some(c) if OutputCompression::from_path(path).is_none() => format!("{}{}", path, c.extension()),
+        _ => path.to_string(),
+    }
+}
+
+/// Crea el writer de salida: gzip si la ruta termina en `.gz`
+/// (con `compress_threads > 1` se comprime en paralelo por bloques), zstd si termina
+/// en `.zst`, o el formato de `--compress` (ver `output_path`);
+/// las escrituras con error transitorio se reintentan según `--io-retries`
+/// y el texto se codifica según `--output-encoding`.
+/// `-` escribe a stdout sin comprimir
+pub fn create_output(path: &str, compress_threads: usize) -> Result<Box<dyn Write>, CsvToolsError> {
+    if is_stdio(path) {
+        return Ok(encoding::encode_output(Box::new(BufWriter::with_capacity(1 << 20, io::stdout().lock()))));
+    }
+    let path = resolved_path(path);
+    wrap_output(&path, RetryWriter::create(&path)?, compress_threads)
+}
+
+/// Igual que `create_output` pero escribe a continuación de lo existente (--resume);
+/// en `.gz` agrega un miembro gzip nuevo, válido para gunzip / MultiGzDecoder
+/// (en `.zst`, un frame nuevo)
+pub fn append_output(path: &str, compress_threads: usize) -> Result<Box<dyn Write>, CsvToolsError> {
+    if is_stdio(path) {
+        return Err("Cannot resume a run that writes to stdout ('-')".into());
+    }
+    let path = resolved_path(path);
+    wrap_output(&path, RetryWriter::append(&path)?, compress_threads)
+}
+
+fn resolved_path(path: &str) -> String {
+    let resolved = output_path(path);
+    if resolved != path {
+        info!("🗜️  --compress: writing {}", resolved);
+    }
+    resolved
+}
+
+fn wrap_output(path: &str, file: RetryWriter, compress_threads: usize) -> Result<Box<dyn Write>, CsvToolsError> {
+    let file = BufWriter::with_capacity(1 << 20, file);
+
+    let writer: Box<dyn Write> = match OutputCompression::from_path(path) {
+        None => Box::new(file),
+        Some(OutputCompression::Gzip) if compress_threads > 1 => Box::new(ParallelGzWriter::new(file, compress_threads)),
+        Some(OutputCompression::Gzip) => Box::new(FramedWriter::<_, GzEncoder<_>>::new(file)?),
+        Some(OutputCompression::Zstd) => Box::new(FramedWriter::<_, zstd::Encoder<'static, _>>::new(file)?),
+    };
+    Ok(encoding::encode_output(writer))
+}
+
+/// Compresor de un miembro gzip o un frame zstd, que se cierra con `finish`
+trait FrameEncoder<W: Write>: Write + Sized {
+    fn start(inner: W) -> io::Result<Self>;
+    fn finish(self) -> io::Result<W>;
+}
+
+impl<W: Write> FrameEncoder<W> for GzEncoder<W> {
+    fn start(inner: W) -> io::Result<Self> {
+        Ok(GzEncoder::new(inner, Compression::default()))
+    }
+
+    fn finish(self) -> io::Result<W> {
+        GzEncoder::finish(self)
+    }
+}
+
+impl<W: Write> FrameEncoder<W> for zstd::Encoder<'static, W> {
+    fn start(inner: W) -> io::Result<Self> {
+        zstd::Encoder::new(inner, ZSTD_LEVEL)
+    }
+
+    fn finish(self) -> io::Result<W> {
+        zstd::Encoder::finish(self)
+    }
+}
+
+/// Salida comprimida que cierra el miembro / frame en curso en cada `flush`: el error de
+/// la última escritura (trailer gzip, cierre del frame zstd) le llega a quien hace flush
+/// en vez de perderse al soltar el writer. Lo que se escribe después abre uno nuevo
+/// (gzip multi-miembro / zstd multi-frame, igual que ParallelGzWriter)
+struct FramedWriter<W: Write, E: FrameEncoder<W>> {
+    inner: Option<W>,
+    encoder: Option<E>,
+}
+
+impl<W: Write, E: FrameEncoder<W>> FramedWriter<W, E> {
+    fn new(inner: W) -> io::Result<Self> {
+        // El primer frame se abre ya: una salida vacía sigue siendo un .gz / .zst válido
+        Ok(FramedWriter { inner: None, encoder: Some(E::start(inner)?) })
+    }
+}
+
+impl<W: Write, E: FrameEncoder<W>> Write for FramedWriter<W, E> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let encoder = match self.encoder.take() {
+            Some(encoder) => encoder,
+            None => E::start(self.inner.take().ok_or_else(|| io::Error::other("writer already finished"))?)?,
+        };
+        self.encoder.insert(encoder).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(encoder) = self.encoder.take() {
+            self.inner = Some(encoder.finish()?);
+        }
+        match self.inner.as_mut() {
+            Some(inner) => inner.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W: Write, E: FrameEncoder<W>> Drop for FramedWriter<W, E> {
+    fn drop(&mut self) {
+        // Solo sin flush previo: el error ya no se puede devolver, al menos se avisa
+        if self.encoder.is_some() {
+            if let Err(e) = self.flush() {
+                eprintln!("⚠️  Could not finish compressed output: {}", e);
+            }
+        }
+    }
+}
+
+/// Writer gzip que junta `threads` bloques de BLOCK_SIZE, los comprime en
+/// paralelo y los escribe en orden (cada bloque es un miembro gzip)
+pub struct ParallelGzWriter<W: Write> {
+    inner: Option<W>,
+    threads: usize,
+    pending: Vec<Vec<u8>>,
+    current: Vec<u8>,
+}
+
+impl<W: Write> ParallelGzWriter<W> {
+    pub fn new(inner: W, threads: usize) -> Self {
+        ParallelGzWriter {
+            inner: Some(inner),
+            threads: threads.max(1),
+            pending: Vec::with_capacity(threads),
+            current: Vec::with_capacity(BLOCK_SIZE),
+        }
+    }
+
+    /// Comprime los bloques pendientes en paralelo y los escribe en orden
+    fn compress_pending(&mut self) -> io::Result<()> {
+        if !self.current.is_empty() {
+            let block = std::mem::replace(&mut self.current, Vec::with_capacity(BLOCK_SIZE));
+            self.pending.push(block);
+        }
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let blocks = std::mem::take(&mut self.pending);
+        let compressed: Vec<io::Result<Vec<u8>>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = blocks.iter()
+                .map(|block| scope.spawn(move || {
+                    let mut encoder = GzEncoder::new(Vec::with_capacity(block.len() / 3), Compression::default());
+                    encoder.write_all(block)?;
+                    encoder.finish()
+                }))
+                .collect();
+
+            handles.into_iter()
+                .map(|h| h.join().unwrap_or_else(|_| Err(io::Error::other("compression thread panicked"))))
+                .collect()
+        });
+
+        let inner = self.inner.as_mut().ok_or_else(|| io::Error::other("writer already finished"))?;
+        for block in compressed {
+            inner.write_all(&block?)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for ParallelGzWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let space = BLOCK_SIZE - self.current.len();
+        let n = space.min(buf.len());
+        self.current.extend_from_slice(&buf[..n]);
+
+        if self.current.len() == BLOCK_SIZE {
+            let block = std::mem::replace(&mut self.current, Vec::with_capacity(BLOCK_SIZE));
+            self.pending.push(block);
+            if self.pending.len() >= self.threads {
+                self.compress_pending()?;
+            }
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.compress_pending()?;
+        match self.inner.as_mut() {
+            Some(inner) => inner.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W: Write> Drop for ParallelGzWriter<W> {
+    fn drop(&mut self) {
+        // Los bloques pendientes se escriben en flush; aquí solo queda lo que nadie cerró
+        if self.inner.is_some() && !(self.current.is_empty() && self.pending.is_empty()) {
+            if let Err(e) = self.flush() {
+                eprintln!("⚠️  Could not finish compressed output: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use flate2::read::MultiGzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_parallel_gzip_roundtrip() {
+        let data: Vec<u8> = (0..BLOCK_SIZE * 3 + 123).map(|i| (i % 251) as u8).collect();
+
+        let mut writer = ParallelGzWriter::new(Vec::new(), 4);
+        writer.write_all(&data).unwrap();
+        writer.flush().unwrap();
+        let compressed = writer.inner.take().unwrap();
+
+        let mut decoded = Vec::new();
+        MultiGzDecoder::new(&compressed[..]).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_output_compression_by_extension() {
+        assert_eq!(OutputCompression::from_path("part_1.csv.GZ"), Some(OutputCompression::Gzip));
+        assert_eq!(OutputCompression::from_path("part_1.csv.zst"), Some(OutputCompression::Zstd));
+        assert_eq!(OutputCompression::from_path("part_1.csv"), None);
+        // Sin --compress la ruta no cambia
+        assert_eq!(output_path("part_1.csv"), "part_1.csv");
+
+        let dir = TempDir::new("zst");
+        let path = dir.path("out.csv.zst");
+        let mut writer = create_output(&path, 1).unwrap();
+        writer.write_all(b"Cuil,Nombre\n20123,Perez\n").unwrap();
+        drop(writer);
+
+        let decoded = zstd::decode_all(std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(decoded, b"Cuil,Nombre\n20123,Perez\n");
+    }
+
+    /// Destino que rechaza toda escritura (disco lleno, pipe cerrado...)
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_framed_writer_finishes_on_flush() {
+        // Cada flush cierra un frame; lo escrito después abre otro y se decodifica todo junto
+        let mut writer = FramedWriter::<_, zstd::Encoder<'static, _>>::new(Vec::new()).unwrap();
+        writer.write_all(b"Cuil,Nombre\n").unwrap();
+        writer.flush().unwrap();
+        writer.write_all(b"20123,Perez\n").unwrap();
+        writer.flush().unwrap();
+        let compressed = writer.inner.take().unwrap();
+        assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), b"Cuil,Nombre\n20123,Perez\n");
+
+        let mut writer = FramedWriter::<_, GzEncoder<_>>::new(Vec::new()).unwrap();
+        writer.flush().unwrap();
+        let compressed = writer.inner.take().unwrap();
+        let mut decoded = Vec::new();
+        MultiGzDecoder::new(&compressed[..]).read_to_end(&mut decoded).unwrap();
+        assert!(decoded.is_empty());
+
+        // zstd retiene los datos hasta cerrar el frame: el error llega a quien hace flush
+        let mut writer = FramedWriter::<_, zstd::Encoder<'static, _>>::new(FailingWriter).unwrap();
+        writer.write_all(b"Cuil,Nombre\n").unwrap();
+        assert!(writer.flush().is_err());
+        // gzip falla ya al escribir la cabecera
+        let mut writer = FramedWriter::<_, GzEncoder<_>>::new(FailingWriter).unwrap();
+        assert!(writer.write_all(b"Cuil,Nombre\n").and_then(|_| writer.flush()).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+// Configuración por archivo (`csv_tools.toml` en el directorio actual o
+// `~/.config/csv_tools/config.toml`): defaults de la instalación que los flags de la
+// línea de comandos pisan en cada corrida
+//
+// ```toml
+// delimiter = ";"              # "\t" o "tab" para TSV, "auto" para detectarlo
+// quiet = true                 # sin líneas de progreso
+// default_model = "siisa_morosos"
+// error_log_dir = "D:/logs"    # logs de errores fuera del directorio de salida
+// chunk_size = 50000           # aceptado por compatibilidad (el progreso se redibuja por tiempo)
+// ```
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::error::CsvToolsError;
+use crate::exit_code::usage_error;
+
+// Nombre del archivo buscado en el directorio actual
+pub const LOCAL_CONFIG_FILE: &str = "csv_tools.toml";
+// Valor de `delimiter` que pide detectarlo sobre la entrada (ver sniff.rs)
+pub const AUTO_DELIMITER: &str = "auto";
+// Filas entre reportes de progreso si la configuración no dice otra cosa
+pub const DEFAULT_CHUNK_SIZE: usize = 10_000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub delimiter: Option<String>,
+    pub quiet: bool,
+    pub default_model: Option<String>,
+    pub error_log_dir: Option<String>,
+    pub chunk_size: Option<usize>,
+    /// Archivo del que se leyó (None: sin archivo, todo por defecto)
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+static DETECTED_DELIMITER: OnceLock<u8> = OnceLock::new();
+
+impl Config {
+    /// Lee y valida un archivo de configuración
+    pub fn load(path: &Path) -> Result<Self, CsvToolsError> {
+        let content = std::fs::read_to_string(path)?;
+        let mut config: Config = toml::from_str(&content)
+            .map_err(|e| format!("Invalid config '{}': {}", path.display(), e))?;
+
+        if let Some(d) = &config.delimiter {
+            parse_delimiter_setting(d).map_err(|e| format!("Invalid config '{}': {}", path.display(), e))?;
+        }
+        if config.chunk_size == Some(0) {
+            return Err(format!("Invalid config '{}': chunk_size must be greater than 0", path.display()).into());
+        }
+        config.source = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Primer archivo que exista: `./csv_tools.toml` y luego `~/.config/csv_tools/config.toml`
+    pub fn discover() -> Result<Self, CsvToolsError> {
+        let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+        let candidates = std::iter::once(PathBuf::from(LOCAL_CONFIG_FILE))
+            .chain(home.map(|h| PathBuf::from(h).join(".config").join("csv_tools").join("config.toml")));
+
+        for path in candidates {
+            if path.is_file() {
+                return Self::load(&path);
+            }
+        }
+        Ok(Config::default())
+    }
+}
+
+/// Fija la configuración de la corrida (una sola vez, antes de ejecutar el comando)
+pub fn set_current(config: Config) {
+    CONFIG.set(config).ok();
+}
+
+/// Configuración efectiva (la de defaults si no se cargó ningún archivo)
+pub fn current() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// Separador de campos de los CSV (coma salvo que la configuración diga otro;
+/// con `auto`, el detectado sobre la entrada del comando)
+pub fn delimiter() -> u8 {
+    if let Some(d) = DETECTED_DELIMITER.get() {
+        return *d;
+    }
+    current().delimiter.as_deref()
+        .and_then(|d| parse_delimiter(d).ok())
+        .unwrap_or(b',')
+}
+
+/// true con `--delimiter auto` / `delimiter = "auto"`
+pub fn auto_delimiter() -> bool {
+    current().delimiter.as_deref() == Some(AUTO_DELIMITER)
+}
+
+/// Separador detectado para `auto` (una vez, antes de ejecutar el comando)
+pub fn set_detected_delimiter(delimiter: u8) {
+    DETECTED_DELIMITER.set(delimiter).ok();
+}
+
+pub fn quiet() -> bool {
+    current().quiet
+}
+
+pub fn chunk_size() -> usize {
+    current().chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE)
+}
+
+/// Modelo del comando: el indicado en la línea de comandos o `default_model`
+pub fn model_or_default(model: Option<&str>) -> Result<String, CsvToolsError> {
+    model.map(str::to_string)
+        .or_else(|| current().default_model.clone())
+        .ok_or_else(|| usage_error(format!("No model given (pass <model_type> or set default_model in {})", LOCAL_CONFIG_FILE)))
+}
+
+/// Ruta del log de errores de `output` (`<output><suffix>`); con `error_log_dir`
+/// el log va a ese directorio con el mismo nombre de archivo. Con la salida a stdout
+/// (`-`) el log se llama `stdout<suffix>`
+pub fn error_log_path(output: &str, suffix: &str) -> String {
+    let output = if crate::file_utils::is_stdio(output) { "stdout" } else { output };
+    match &current().error_log_dir {
+        Some(dir) => {
+            let name = Path::new(output).file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| output.to_string());
+            Path::new(dir).join(format!("{}{}", name, suffix)).to_string_lossy().to_string()
+        }
+        None => format!("{}{}", output, suffix),
+    }
+}
+
+/// `--delimiter` / `delimiter =`: un separador o `auto`
+pub fn parse_delimiter_setting(value: &str) -> Result<String, String> {
+    if value.eq_ignore_ascii_case(AUTO_DELIMITER) {
+        Ok(AUTO_DELIMITER.to_string())
+    } else {
+        parse_delimiter(value).map(|d| (d as char).to_string())
+    }
+}
+
+/// Separador de un solo byte; acepta `\t`, `tab` y la tabulación literal
+pub fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value {
+        "\\t" | "tab" | "TAB" | "\t" => Ok(b'\t'),
+        v if v.len() == 1 && v.is_ascii() => Ok(v.as_bytes()[0]),
+        v => Err(format!("invalid delimiter '{}' (expected a single ASCII character or \\t)", v)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_load_config() {
+        let dir = TempDir::new("config_load");
+        let path = PathBuf::from(dir.path("csv_tools.toml"));
+        std::fs::write(&path, "delimiter = \"tab\"\nquiet = true\ndefault_model = \"siisa_morosos\"\nchunk_size = 500\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.delimiter.as_deref().map(parse_delimiter), Some(Ok(b'\t')));
+        assert!(config.quiet);
+        assert_eq!(config.default_model.as_deref(), Some("siisa_morosos"));
+        assert_eq!(config.chunk_size, Some(500));
+
+        std::fs::write(&path, "delimiter = \";;\"\n").unwrap();
+        assert!(Config::load(&path).is_err());
+        std::fs::write(&path, "unknown_key = 1\n").unwrap();
+        assert!(Config::load(&path).is_err());
+    }
+}
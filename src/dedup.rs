@@ -0,0 +1,448 @@
+// Deduplicación por columnas clave como API de biblioteca: la pueden llamar otros
+// binarios (ETL propios) sin pasar por la línea de comandos ni parsear la consola
+
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+
+use csv::{ReaderBuilder, StringRecord};
+use serde::Serialize;
+
+use crate::canonicalize::{canonical, CanonicalRules, Rule};
+use crate::config;
+use crate::collation::{self, Collation};
+use crate::diagnostics;
+use crate::error::CsvToolsError;
+use crate::exit_code::usage_error;
+use crate::file_utils::open_input;
+use crate::schema::{coerce_value, ColumnSpec, ColumnType};
+use crate::sink::open_sink;
+use crate::sketch::{seeded_hash, BloomFilter};
+
+/// Qué fila se conserva cuando una clave se repite
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum KeepPolicy {
+    /// La primera aparición (una sola pasada)
+    #[default]
+    First,
+    /// La última aparición (dos pasadas: la primera ubica la última fila de cada clave)
+    Last,
+    /// La de fecha más reciente en la columna indicada, como resuelve los conflictos el
+    /// sistema de origen (dos pasadas). A igual fecha gana la posterior; una fecha vacía
+    /// o inválida pierde contra cualquier fecha válida
+    LatestBy(String),
+}
+
+/// Deduplicación aproximada de merge_dedup (`--approx`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ApproxMode {
+    /// Filtro de Bloom dimensionado con la estimación de líneas y `--fpp`
+    Bloom,
+}
+
+/// Líneas ya escritas por merge_dedup
+pub enum SeenLines {
+    /// Cada línea completa: exacto, la memoria crece con el tamaño de los datos
+    Exact(HashSet<String>),
+    /// Solo la huella xxh3 de 128 bits de cada línea (`--hash-only`): 16 bytes por línea
+    /// sin importar su largo; una colisión descartaría una línea distinta
+    Hashed(HashSet<u128>),
+    /// Memoria constante; una línea nueva puede descartarse por falso positivo
+    Bloom {
+        filter: BloomFilter,
+        unique: usize,
+        /// Suma de p/(1-p) por cada línea aceptada: líneas nuevas que se estiman descartadas
+        estimated_false_drops: f64,
+    },
+}
+
+impl SeenLines {
+    pub fn exact() -> Self {
+        SeenLines::Exact(HashSet::new())
+    }
+
+    pub fn hashed() -> Self {
+        SeenLines::Hashed(HashSet::new())
+    }
+
+    pub fn bloom(expected_lines: u64, fpp: f64, seed: u64) -> Self {
+        SeenLines::Bloom { filter: BloomFilter::new(expected_lines, fpp, seed), unique: 0, estimated_false_drops: 0.0 }
+    }
+
+    /// Registra la línea; devuelve true si es nueva (hay que escribirla)
+    pub fn insert(&mut self, line: &str) -> bool {
+        match self {
+            SeenLines::Exact(lines) => lines.insert(line.to_string()),
+            SeenLines::Hashed(digests) => digests.insert(line_digest(line)),
+            SeenLines::Bloom { filter, unique, estimated_false_drops } => {
+                let p = filter.false_positive_rate();
+                if !filter.insert(line.as_bytes()) {
+                    return false;
+                }
+                *unique += 1;
+                *estimated_false_drops += p / (1.0 - p);
+                true
+            }
+        }
+    }
+
+    /// true si la línea ya se registró (sin registrarla)
+    pub fn contains(&self, line: &str) -> bool {
+        match self {
+            SeenLines::Exact(lines) => lines.contains(line),
+            SeenLines::Hashed(digests) => digests.contains(&line_digest(line)),
+            SeenLines::Bloom { filter, .. } => filter.contains(line.as_bytes()),
+        }
+    }
+
+    /// Líneas distintas aceptadas
+    pub fn unique(&self) -> usize {
+        match self {
+            SeenLines::Exact(lines) => lines.len(),
+            SeenLines::Hashed(digests) => digests.len(),
+            SeenLines::Bloom { unique, .. } => *unique,
+        }
+    }
+
+    /// Líneas nuevas que se estiman descartadas por falsos positivos (0 si es exacto)
+    pub fn estimated_false_drops(&self) -> f64 {
+        match self {
+            SeenLines::Exact(_) | SeenLines::Hashed(_) => 0.0,
+            SeenLines::Bloom { estimated_false_drops, .. } => *estimated_false_drops,
+        }
+    }
+}
+
+/// Huella xxh3 de 128 bits de una línea (modo `--hash-only`)
+pub fn line_digest(line: &str) -> u128 {
+    twox_hash::XxHash3_128::oneshot(line.as_bytes())
+}
+
+/// Línea de entrada descartada por una colisión de huellas: su huella coincide con la
+/// de una línea escrita que es distinta
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DigestCollision {
+    pub file: String,
+    pub line: usize,
+}
+
+/// Pasada de verificación de `--hash-only`: la salida tiene exactamente una línea por
+/// huella; se recorre la entrada y cada línea se compara contra la escrita con su
+/// huella usando un segundo hash independiente (64 bits). Los headers no se comparan
+pub fn find_digest_collisions(inputs: &[String], output: &str, collation: Collation) -> Result<Vec<DigestCollision>, CsvToolsError> {
+    let check_hash = |line: &str| seeded_hash(line.as_bytes(), 0x2545_f491_4f6c_dd1d);
+
+    let mut written: HashMap<u128, u64> = HashMap::new();
+    for line in open_input(output)?.lines().skip(1) {
+        let line = line?;
+        let key = collation.dedup_key(&line);
+        written.insert(line_digest(&key), check_hash(&key));
+    }
+
+    let mut collisions = Vec::new();
+    for file in inputs {
+        for (i, line) in open_input(file)?.lines().enumerate().skip(1) {
+            let line = line?;
+            let key = collation.dedup_key(&line);
+            if written.get(&line_digest(&key)).is_some_and(|check| *check != check_hash(&key)) {
+                collisions.push(DigestCollision { file: file.clone(), line: i + 1 });
+            }
+        }
+    }
+    Ok(collisions)
+}
+
+/// Resultado de una deduplicación
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DedupReport {
+    pub rows_read: usize,
+    pub rows_written: usize,
+    pub duplicates_removed: usize,
+    /// Duplicadas solo gracias a `KeyNormalization` (sin normalizar la clave era distinta)
+    pub collapsed_by_normalization: usize,
+    /// Filas con fecha vacía o inválida en la columna de `KeepPolicy::LatestBy`
+    pub invalid_dates: usize,
+}
+
+/// Normalización de la clave antes de compararla (modo difuso de dedup_by_keys): filas
+/// que difieren solo en espacios sueltos o mayúsculas se consideran duplicadas
+#[derive(Debug, Clone, Default)]
+pub struct KeyNormalization {
+    /// Colapsa los espacios internos repetidos a uno solo (los extremos se recortan siempre)
+    pub collapse_whitespace: bool,
+    /// Columnas clave de texto que se comparan sin distinguir mayúsculas (p. ej. ApellidoNombre)
+    pub fold_case: Vec<String>,
+    /// Reglas por columna (--canonicalize rules.yaml), antes del resto de la normalización
+    pub canonicalize: Option<CanonicalRules>,
+}
+
+impl KeyNormalization {
+    pub fn is_active(&self) -> bool {
+        self.collapse_whitespace || !self.fold_case.is_empty() || self.canonicalize.is_some()
+    }
+}
+
+/// Deduplica `input` (CSV o .gz) por las columnas `keys` y escribe el header y las filas
+/// conservadas en `output` (ruta local o destino registrado, ver `sink::open_sink`).
+/// Las claves se comparan con la collation actual; en memoria queda una huella por clave
+pub fn by_keys(input: &str, output: &str, keys: &[&str], policy: KeepPolicy) -> Result<DedupReport, CsvToolsError> {
+    by_keys_normalized(input, output, keys, policy, &KeyNormalization::default(), None)
+}
+
+/// Como `by_keys`, pero normalizando la clave según `normalization`. Con `collapsed_log`
+/// escribe un CSV con cada fila descartada que solo era duplicada por la normalización:
+/// su línea, la línea de la fila conservada y los valores clave originales
+pub fn by_keys_normalized(
+    input: &str,
+    output: &str,
+    keys: &[&str],
+    policy: KeepPolicy,
+    normalization: &KeyNormalization,
+    collapsed_log: Option<&str>,
+) -> Result<DedupReport, CsvToolsError> {
+    let collation = collation::current();
+    let mut rdr = open_reader(input)?;
+    let headers = rdr.headers()?.clone();
+    let key_indexes = key_indexes(&headers, keys)?;
+    let normalizer = KeyNormalizer::new(&headers, &key_indexes, normalization)?;
+    let fingerprint_of = |record: &StringRecord| match &normalizer {
+        Some(normalizer) => normalizer.fingerprint(record, &key_indexes, collation),
+        None => key_fingerprint(record, &key_indexes, collation),
+    };
+    let mut record = StringRecord::new();
+
+    // Con normalización: línea y huella sin normalizar de la fila conservada de cada clave,
+    // para distinguir los duplicados exactos de los que solo colapsó la normalización
+    let mut kept_rows: Option<HashMap<u128, (u64, u128)>> = normalizer.as_ref().map(|_| HashMap::new());
+
+    let mut report = DedupReport::default();
+
+    // KeepPolicy::Last / LatestBy: fila que se conserva de cada clave
+    let last_rows: Option<HashMap<u128, usize>> = match &policy {
+        KeepPolicy::First => None,
+        KeepPolicy::Last | KeepPolicy::LatestBy(_) => {
+            let date_index = match &policy {
+                KeepPolicy::LatestBy(column) => Some(headers.iter().position(|h| h.trim() == column.trim())
+                    .ok_or_else(|| usage_error(format!("Date column '{}' not found in header", column)))?),
+                _ => None,
+            };
+            let date_spec = ColumnSpec { column_type: ColumnType::Date, ..Default::default() };
+            // Fecha ISO (None = inválida) de la fila elegida hasta ahora por clave
+            let mut latest: HashMap<u128, Option<String>> = HashMap::new();
+            let mut last = HashMap::new();
+            let mut row = 0usize;
+            while rdr.read_record(&mut record)? {
+                let fingerprint = fingerprint_of(&record);
+                if let Some(date_index) = date_index {
+                    let date = coerce_value(record.get(date_index).unwrap_or(""), &date_spec).ok().filter(|d| !d.is_empty());
+                    if date.is_none() {
+                        report.invalid_dates += 1;
+                    }
+                    if latest.get(&fingerprint).is_some_and(|best| *best > date) {
+                        row += 1;
+                        continue;
+                    }
+                    latest.insert(fingerprint, date);
+                }
+                last.insert(fingerprint, row);
+                if let Some(kept) = kept_rows.as_mut() {
+                    kept.insert(fingerprint, (line_of(&record), key_fingerprint(&record, &key_indexes, collation)));
+                }
+                row += 1;
+            }
+            rdr = open_reader(input)?;
+            rdr.headers()?;
+            Some(last)
+        }
+    };
+
+    let mut sink = open_sink(output)?;
+    sink.write_header(&headers)?;
+    let mut log = match collapsed_log {
+        Some(path) => {
+            let mut log = open_sink(path)?;
+            let mut log_header = vec!["line", "kept_line"];
+            log_header.extend(key_indexes.iter().map(|i| &headers[*i]));
+            log.write_header(&StringRecord::from(log_header))?;
+            Some(log)
+        }
+        None => None,
+    };
+
+    let mut seen: HashSet<u128> = HashSet::new();
+    while rdr.read_record(&mut record)? {
+        let fingerprint = fingerprint_of(&record);
+        let keep = match &last_rows {
+            Some(last) => last.get(&fingerprint) == Some(&report.rows_read),
+            None => seen.insert(fingerprint),
+        };
+        report.rows_read += 1;
+
+        if keep {
+            sink.write_record(&record)?;
+            report.rows_written += 1;
+            if let (KeepPolicy::First, Some(kept)) = (&policy, kept_rows.as_mut()) {
+                kept.insert(fingerprint, (line_of(&record), key_fingerprint(&record, &key_indexes, collation)));
+            }
+            continue;
+        }
+
+        report.duplicates_removed += 1;
+        if let Some((kept_line, kept_raw)) = kept_rows.as_ref().and_then(|kept| kept.get(&fingerprint)) {
+            if *kept_raw != key_fingerprint(&record, &key_indexes, collation) {
+                report.collapsed_by_normalization += 1;
+                if let Some(log) = log.as_mut() {
+                    let mut row = vec![line_of(&record).to_string(), kept_line.to_string()];
+                    row.extend(key_indexes.iter().map(|i| record.get(*i).unwrap_or("").to_string()));
+                    log.write_record(&StringRecord::from(row))?;
+                }
+            }
+        }
+    }
+    sink.finish()?;
+    if let Some(mut log) = log {
+        log.finish()?;
+    }
+
+    Ok(report)
+}
+
+/// `KeyNormalization` resuelta contra el header: reglas y minúsculas por columna clave
+struct KeyNormalizer {
+    collapse_whitespace: bool,
+    fold_case: Vec<bool>,
+    rules: Vec<Vec<Rule>>,
+}
+
+impl KeyNormalizer {
+    /// None si no hay nada que normalizar; error si una columna de --fold-case no es clave
+    fn new(headers: &StringRecord, key_indexes: &[usize], normalization: &KeyNormalization) -> Result<Option<Self>, CsvToolsError> {
+        if !normalization.is_active() {
+            return Ok(None);
+        }
+        let mut fold_indexes = Vec::with_capacity(normalization.fold_case.len());
+        for column in &normalization.fold_case {
+            match headers.iter().position(|h| h.trim() == column.trim()) {
+                Some(i) if key_indexes.contains(&i) => fold_indexes.push(i),
+                _ => return Err(format!("Case-folded column '{}' is not one of the key columns", column).into()),
+            }
+        }
+        // Un archivo de reglas puede ser compartido entre tablas: las columnas que no son
+        // clave se ignoran con una advertencia
+        let key_names: Vec<&str> = key_indexes.iter().map(|i| headers[*i].trim()).collect();
+        if let Some(rules) = &normalization.canonicalize {
+            for column in rules.columns.keys().filter(|c| !key_names.contains(&c.trim())) {
+                diagnostics::warn(format!("Canonicalization rules for '{}' ignored: not a key column", column));
+            }
+        }
+        Ok(Some(KeyNormalizer {
+            collapse_whitespace: normalization.collapse_whitespace,
+            fold_case: key_indexes.iter().map(|i| fold_indexes.contains(i)).collect(),
+            rules: key_names.iter()
+                .map(|name| normalization.canonicalize.as_ref().map(|r| r.for_column(name).to_vec()).unwrap_or_default())
+                .collect(),
+        }))
+    }
+
+    fn fingerprint(&self, record: &StringRecord, key_indexes: &[usize], collation: Collation) -> u128 {
+        let key = key_indexes.iter().zip(&self.fold_case).zip(&self.rules)
+            .map(|((i, fold), rules)| {
+                let value = canonical(record.get(*i).unwrap_or("").trim(), rules);
+                let value = value.trim();
+                let value = if self.collapse_whitespace { value.split_whitespace().collect::<Vec<_>>().join(" ") } else { value.to_string() };
+                let value = if *fold { value.to_lowercase() } else { value };
+                collation.dedup_key(&value).into_owned()
+            })
+            .collect::<Vec<_>>()
+            .join("\u{1f}");
+        fingerprint(&key)
+    }
+}
+
+fn line_of(record: &StringRecord) -> u64 {
+    record.position().map(|p| p.line()).unwrap_or(0)
+}
+
+fn open_reader(input: &str) -> Result<csv::Reader<Box<dyn std::io::BufRead>>, CsvToolsError> {
+    Ok(ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(input)?))
+}
+
+/// Posiciones de las columnas clave en el header (error si falta alguna)
+pub fn key_indexes(headers: &StringRecord, keys: &[&str]) -> Result<Vec<usize>, CsvToolsError> {
+    if keys.is_empty() {
+        return Err(usage_error("At least one key column is required"));
+    }
+    keys.iter()
+        .map(|k| headers.iter().position(|h| h.trim() == k.trim())
+            .ok_or_else(|| usage_error(format!("Key column '{}' not found in header", k))))
+        .collect()
+}
+
+/// Huella de 128 bits de la clave compuesta (dos hashes independientes: sin colisiones
+/// prácticas aun con cientos de millones de claves)
+pub fn key_fingerprint(record: &StringRecord, key_indexes: &[usize], collation: Collation) -> u128 {
+    let key = key_indexes.iter()
+        .map(|i| collation.dedup_key(record.get(*i).unwrap_or("").trim()))
+        .collect::<Vec<_>>()
+        .join("\u{1f}");
+    fingerprint(&key)
+}
+
+fn fingerprint(key: &str) -> u128 {
+    let high = seeded_hash(key.as_bytes(), 0x9e37_79b9_7f4a_7c15) as u128;
+    let low = seeded_hash(key.as_bytes(), 0x2545_f491_4f6c_dd1d) as u128;
+    (high << 64) | low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_by_keys_first_and_last() {
+        let dir = TempDir::new("dedup_by_keys");
+        let input = dir.write("in.csv", "Cuil,IdTransmit,Monto\n1,10,a\n2,10,b\n1,10,c\n1,11,d\n");
+        let output = dir.path("out.csv");
+
+        let report = by_keys(&input, &output, &["Cuil", "IdTransmit"], KeepPolicy::First).unwrap();
+        assert_eq!(report, DedupReport { rows_read: 4, rows_written: 3, duplicates_removed: 1, ..Default::default() });
+        assert_eq!(dir.read("out.csv"), "Cuil,IdTransmit,Monto\n1,10,a\n2,10,b\n1,11,d\n");
+
+        by_keys(&input, &output, &["Cuil", "IdTransmit"], KeepPolicy::Last).unwrap();
+        assert_eq!(dir.read("out.csv"), "Cuil,IdTransmit,Monto\n2,10,b\n1,10,c\n1,11,d\n");
+
+        assert!(by_keys(&input, &output, &["Nope"], KeepPolicy::First).is_err());
+    }
+
+    #[test]
+    fn test_by_keys_latest_by_date() {
+        let dir = TempDir::new("dedup_latest_by");
+        let input = dir.write("in.csv", "Cuil,CreateDate,v\n1,15/03/2024,a\n2,,b\n1,2024-01-10,c\n2,2023-12-31,d\n1,15/03/2024,e\n2,x,f\n");
+        let output = dir.path("out.csv");
+
+        let report = by_keys(&input, &output, &["Cuil"], KeepPolicy::LatestBy("CreateDate".to_string())).unwrap();
+        // Cuil 1: empate en 15/03 → gana la posterior (e); Cuil 2: la única fecha válida (d)
+        assert_eq!(dir.read("out.csv"), "Cuil,CreateDate,v\n2,2023-12-31,d\n1,15/03/2024,e\n");
+        assert_eq!((report.duplicates_removed, report.invalid_dates), (4, 2));
+
+        assert!(by_keys(&input, &output, &["Cuil"], KeepPolicy::LatestBy("Nope".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_hashed_seen_lines_and_verification() {
+        let mut seen = SeenLines::hashed();
+        assert!(seen.insert("1,a"));
+        assert!(!seen.insert("1,a"));
+        assert!(seen.insert("1,b"));
+        assert_eq!(seen.unique(), 2);
+
+        let dir = TempDir::new("dedup_digest");
+        let inputs = vec![dir.write("in.csv", "id,v\n1,a\n1,a\n1,b\n")];
+        let output = dir.write("out.csv", "id,v\n1,a\n1,b\n");
+        assert!(find_digest_collisions(&inputs, &output, Collation::Binary).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+// Advertencias de calidad de datos (⚠️): se cuentan para que `--strict` y
+// `--max-warnings` puedan convertirlas en errores y reflejarlas en el exit code
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use crate::logging;
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+static MAX_WARNINGS: OnceLock<usize> = OnceLock::new();
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Configura el modo estricto (una vez, desde las opciones globales)
+pub fn configure(strict: bool, max_warnings: Option<usize>) {
+    STRICT.store(strict, Ordering::Relaxed);
+    if let Some(max) = max_warnings {
+        MAX_WARNINGS.set(max).ok();
+    }
+}
+
+pub fn is_strict() -> bool {
+    STRICT.load(Ordering::Relaxed)
+}
+
+/// Emite una advertencia (en modo estricto se muestra como error) y la cuenta
+pub fn warn(message: impl Display) {
+    WARNINGS.fetch_add(1, Ordering::Relaxed);
+    let line = if is_strict() {
+        format!("❌ [strict] {}", message)
+    } else {
+        format!("⚠️  {}", message)
+    };
+    eprintln!("{}", line);
+    logging::to_file(&line);
+}
+
+pub fn warning_count() -> usize {
+    WARNINGS.load(Ordering::Relaxed)
+}
+
+/// Umbral efectivo: en modo estricto 0 salvo `--max-warnings`; sin él, solo si se pidió
+fn threshold() -> Option<usize> {
+    match (MAX_WARNINGS.get(), is_strict()) {
+        (Some(max), _) => Some(*max),
+        (None, true) => Some(0),
+        (None, false) => None,
+    }
+}
+
+/// Al final de la corrida: devuelve error si las advertencias superan el umbral
+pub fn check_threshold() -> Result<(), String> {
+    let count = warning_count();
+    match threshold() {
+        Some(max) if count > max => Err(format!(
+            "{} warning(s) treated as errors (allowed: {}){}",
+            count, max, if is_strict() { " [--strict]" } else { "" }
+        )),
+        _ => Ok(()),
+    }
+}
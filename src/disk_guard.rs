@@ -0,0 +1,224 @@
+// Control de espacio en disco durante escrituras largas (merge/split):
+// aborta con un mensaje claro y guarda un checkpoint ANTES de que el disco se llene.
+// El mismo checkpoint se usa al vencer --max-runtime y para retomar con --resume
+
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::error::CsvToolsError;
+use crate::file_utils::format_bytes;
+use crate::info;
+use crate::runtime_limit::Incomplete;
+
+// Cada cuántos bytes escritos se vuelve a consultar el espacio libre
+const CHECK_INTERVAL_BYTES: u64 = 64 * 1024 * 1024;
+// Margen mínimo que se deja libre en el disco destino
+const MIN_SAFETY_MARGIN_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Estado de avance que se guarda en `<output>.checkpoint.json` al abortar
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Checkpoint {
+    pub command: String,
+    /// Siempre "incomplete": la salida parcial no debe usarse hasta completar la corrida
+    pub status: String,
+    pub output: String,
+    pub files_completed: Vec<String>,
+    pub current_file: String,
+    pub current_line: usize,
+    /// Líneas de `current_file` (header incluido) ya volcadas a la salida
+    pub lines_processed: usize,
+    /// Temporal intermedio que se conserva para retomar (external_dedup)
+    pub temp_file: Option<String>,
+    pub bytes_written: u64,
+    pub reason: String,
+}
+
+impl Checkpoint {
+    pub fn path_for(output_path: &str) -> String {
+        format!("{}.checkpoint.json", output_path)
+    }
+
+    /// Lee el checkpoint de `output_path` para retomar `command` (--resume)
+    pub fn load(output_path: &str, command: &str) -> Result<Self, CsvToolsError> {
+        let path = Self::path_for(output_path);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot resume: checkpoint '{}' not readable ({})", path, e))?;
+        let checkpoint: Checkpoint = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid checkpoint '{}': {}", path, e))?;
+
+        if checkpoint.command != command {
+            return Err(format!(
+                "Checkpoint '{}' belongs to '{}', not '{}'", path, checkpoint.command, command
+            ).into());
+        }
+        Ok(checkpoint)
+    }
+
+    /// Posición desde la que se retoma: archivos completos a saltear y líneas del
+    /// archivo en curso; valida que la lista de archivos sea la misma
+    pub fn resume_position(&self, file_names: &[String]) -> Result<(usize, usize), CsvToolsError> {
+        let done = self.files_completed.len();
+        if file_names.len() < done || file_names[..done] != self.files_completed[..] {
+            return Err("Cannot resume: the file list changed since the checkpoint".into());
+        }
+        if !self.current_file.is_empty() && file_names.get(done) != Some(&self.current_file) {
+            return Err(format!("Cannot resume: expected '{}' after the completed files", self.current_file).into());
+        }
+        Ok((done, self.lines_processed))
+    }
+
+    /// Borra el checkpoint al completar la corrida retomada
+    pub fn remove(output_path: &str) {
+        std::fs::remove_file(Self::path_for(output_path)).ok();
+    }
+}
+
+/// Proyecta el tamaño restante de la salida a partir de lo leído/escrito hasta
+/// el momento y lo compara periódicamente con el espacio libre del disco destino
+pub struct DiskSpaceGuard {
+    output_path: String,
+    target_dir: PathBuf,
+    total_input_bytes: u64,
+    input_bytes: u64,
+    written_bytes: u64,
+    next_check_at: u64,
+    // Factor de salida por byte de entrada (ej. 2.0 si además se ordena en un temporal)
+    expansion: f64,
+}
+
+impl DiskSpaceGuard {
+    pub fn new(output_path: &str, total_input_bytes: u64, expansion: f64) -> Self {
+        let parent = Path::new(output_path).parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+
+        DiskSpaceGuard {
+            output_path: output_path.to_string(),
+            target_dir: parent.to_path_buf(),
+            total_input_bytes,
+            input_bytes: 0,
+            written_bytes: 0,
+            next_check_at: CHECK_INTERVAL_BYTES,
+            expansion,
+        }
+    }
+
+    /// Verificación previa: si ni siquiera cabe la proyección completa se avisa
+    /// (no se aborta: la deduplicación puede reducir bastante la salida)
+    pub fn preflight(&self) -> Result<(), CsvToolsError> {
+        let free = fs2::available_space(&self.target_dir)?;
+        let projected = (self.total_input_bytes as f64 * self.expansion) as u64;
+
+        info!("💾 Free space on {}: {} (projected output: up to {})",
+            self.target_dir.display(), format_bytes(free), format_bytes(projected));
+
+        if free < projected + self.safety_margin() {
+            eprintln!("⚠️  Free space may not be enough for the worst case; will monitor during the write");
+        }
+
+        Ok(())
+    }
+
+    /// Registra bytes leídos/escritos; cada CHECK_INTERVAL_BYTES compara el espacio
+    /// libre contra lo que falta escribir. Devuelve el motivo si hay que abortar
+    pub fn record(&mut self, input_bytes: u64, written_bytes: u64) -> Result<Option<String>, CsvToolsError> {
+        self.input_bytes += input_bytes;
+        self.written_bytes += written_bytes;
+
+        if self.written_bytes < self.next_check_at {
+            return Ok(None);
+        }
+        self.next_check_at = self.written_bytes + CHECK_INTERVAL_BYTES;
+
+        let free = fs2::available_space(&self.target_dir)?;
+        let remaining = self.projected_remaining();
+
+        if free < remaining + self.safety_margin() {
+            return Ok(Some(format!(
+                "Not enough disk space on {}: {} free, ~{} still to write (+{} safety margin)",
+                self.target_dir.display(), format_bytes(free), format_bytes(remaining), format_bytes(self.safety_margin())
+            )));
+        }
+
+        Ok(None)
+    }
+
+    /// Guarda el checkpoint junto a la salida y arma el error de aborto
+    pub fn abort(&self, checkpoint: Checkpoint, reason: String) -> CsvToolsError {
+        self.save_checkpoint(checkpoint, &reason);
+        eprintln!("💡 Free up space (or write to another disk) and run the command again");
+
+        reason.into()
+    }
+
+    /// Corte ordenado por --max-runtime: checkpoint "incomplete" para retomar con --resume
+    pub fn stop_at_deadline(&self, checkpoint: Checkpoint) -> CsvToolsError {
+        self.save_checkpoint(checkpoint, "Max runtime reached (--max-runtime)");
+        eprintln!("💡 Partial output is INCOMPLETE; run the same command again with --resume to continue");
+
+        Incomplete { checkpoint_path: Checkpoint::path_for(&self.output_path) }.into()
+    }
+
+    /// Igual que `abort`, para un error de lectura que agotó los reintentos (`--io-retries`)
+    pub fn abort_on_read_error(&self, checkpoint: Checkpoint, error: std::io::Error) -> CsvToolsError {
+        let reason = format!("Read error in '{}' at line {}: {}", checkpoint.current_file, checkpoint.current_line, error);
+        self.save_checkpoint(checkpoint, &reason);
+        eprintln!("💡 Check the network mount (or raise --io-retries) and run the command again");
+
+        reason.into()
+    }
+
+    fn save_checkpoint(&self, mut checkpoint: Checkpoint, reason: &str) {
+        let checkpoint_path = Checkpoint::path_for(&self.output_path);
+        checkpoint.status = "incomplete".to_string();
+        checkpoint.output = self.output_path.clone();
+        checkpoint.bytes_written = self.written_bytes;
+        checkpoint.reason = reason.to_string();
+
+        eprintln!();
+        eprintln!("❌ {}", reason);
+        match serde_json::to_string_pretty(&checkpoint)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(&checkpoint_path, json).map_err(|e| e.to_string()))
+        {
+            Ok(()) => eprintln!("💾 Checkpoint saved: {}", checkpoint_path),
+            Err(e) => eprintln!("⚠️  Could not save checkpoint {}: {}", checkpoint_path, e),
+        }
+    }
+
+    /// Lo que falta escribir, usando la relación salida/entrada observada
+    fn projected_remaining(&self) -> u64 {
+        let remaining_input = self.total_input_bytes.saturating_sub(self.input_bytes);
+        let ratio = if self.input_bytes > 0 {
+            self.written_bytes as f64 / self.input_bytes as f64
+        } else {
+            1.0
+        };
+        // La expansión extra (ej. sort externo) se suma sobre lo ya escrito
+        let extra = (self.total_input_bytes as f64 * (self.expansion - 1.0).max(0.0)) as u64;
+        (remaining_input as f64 * ratio) as u64 + extra
+    }
+
+    fn safety_margin(&self) -> u64 {
+        MIN_SAFETY_MARGIN_BYTES.max(self.total_input_bytes / 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projected_remaining_uses_observed_ratio() {
+        let mut guard = DiskSpaceGuard::new("out.csv", 1000, 1.0);
+        guard.input_bytes = 400;
+        guard.written_bytes = 200;
+        assert_eq!(guard.projected_remaining(), 300);
+
+        let mut guard = DiskSpaceGuard::new("out.csv", 1000, 2.0);
+        guard.input_bytes = 1000;
+        guard.written_bytes = 1000;
+        assert_eq!(guard.projected_remaining(), 1000);
+    }
+}
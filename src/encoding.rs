@@ -0,0 +1,280 @@
+// Codificación de caracteres: los comandos trabajan siempre en UTF-8
+// - `--input-encoding` decodifica cada entrada al leerla (file_utils::open_input)
+// - `--output-encoding` codifica las salidas CSV al escribirlas (compression::create_output)
+// `detect_encoding` y `--input-encoding auto` usan el mismo detector
+// El BOM de UTF-8 se quita al leer (salvo `--keep-bom`) para que no quede pegado al
+// nombre de la primera columna
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use encoding_rs::{EncoderResult, Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use serde::Serialize;
+
+use crate::diagnostics;
+
+/// Valor de `--input-encoding` que detecta la codificación de cada entrada
+pub const AUTO: &str = "auto";
+
+/// Codificación de las entradas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEncoding {
+    Fixed(&'static Encoding),
+    /// Se detecta por archivo a partir de lo primero que se lee
+    Auto,
+}
+
+static INPUT: OnceLock<InputEncoding> = OnceLock::new();
+static OUTPUT: OnceLock<&'static Encoding> = OnceLock::new();
+static KEEP_BOM: AtomicBool = AtomicBool::new(false);
+
+/// BOM de UTF-8 (también es lo que queda de un BOM UTF-16 después de decodificar)
+pub const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Nombre de codificación → encoding_rs (labels WHATWG más `latin-1` / `utf16le` / `utf16be`)
+/// `latin1` e `iso-8859-1` se leen como windows-1252, que es un superconjunto
+pub fn parse_encoding(label: &str) -> Result<&'static Encoding, String> {
+    let lower = label.trim().to_lowercase();
+    let label = match lower.as_str() {
+        "latin-1" => "latin1",
+        "utf16le" => "utf-16le",
+        "utf16be" => "utf-16be",
+        other => other,
+    };
+    Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| format!("unknown encoding '{}' (e.g. utf-8, latin1, windows-1252, utf-16le)", label))
+}
+
+/// Valor de `--input-encoding`: una codificación o `auto`
+pub fn parse_input_encoding(label: &str) -> Result<InputEncoding, String> {
+    if label.trim().eq_ignore_ascii_case(AUTO) {
+        return Ok(InputEncoding::Auto);
+    }
+    parse_encoding(label).map(InputEncoding::Fixed)
+}
+
+pub fn set_input_encoding(encoding: InputEncoding) {
+    INPUT.set(encoding).ok();
+}
+
+pub fn set_output_encoding(encoding: &'static Encoding) {
+    OUTPUT.set(encoding).ok();
+}
+
+/// `--keep-bom`: las entradas se leen con el BOM tal como está
+pub fn set_keep_bom(keep: bool) {
+    KEEP_BOM.store(keep, Ordering::Relaxed);
+}
+
+/// true si al leer se quita el BOM del comienzo de cada entrada (default)
+pub fn strips_bom() -> bool {
+    !KEEP_BOM.load(Ordering::Relaxed)
+}
+
+/// Quita el BOM de UTF-8 del comienzo de `reader` (sin `--keep-bom`)
+pub fn strip_bom(mut reader: Box<dyn BufRead>) -> io::Result<Box<dyn BufRead>> {
+    if strips_bom() && reader.fill_buf()?.starts_with(UTF8_BOM) {
+        reader.consume(UTF8_BOM.len());
+    }
+    Ok(reader)
+}
+
+/// true si las entradas pueden pasar por un decodificador (los offsets de --resume ya
+/// no son posiciones del archivo)
+pub fn transcodes_input() -> bool {
+    match INPUT.get() {
+        None => false,
+        Some(InputEncoding::Fixed(e)) => *e != UTF_8,
+        Some(InputEncoding::Auto) => true,
+    }
+}
+
+/// Decodifica `reader` a UTF-8 según `--input-encoding` (sin la opción, o en UTF-8, queda igual)
+/// Un BOM en la entrada manda sobre la codificación indicada
+pub fn decode_input(mut reader: Box<dyn BufRead>) -> io::Result<Box<dyn BufRead>> {
+    let encoding = match INPUT.get() {
+        None => return Ok(reader),
+        Some(InputEncoding::Fixed(e)) => *e,
+        Some(InputEncoding::Auto) => detect(reader.fill_buf()?).0,
+    };
+    if encoding == UTF_8 {
+        return Ok(reader);
+    }
+    let decoder = DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding))
+        .bom_override(true)
+        .build(reader);
+    Ok(Box::new(BufReader::with_capacity(1 << 20, decoder)))
+}
+
+/// Codifica `writer` según `--output-encoding` (sin la opción, o en UTF-8, queda igual)
+pub fn encode_output(writer: Box<dyn Write>) -> Box<dyn Write> {
+    match OUTPUT.get() {
+        Some(encoding) if *encoding != UTF_8 => Box::new(EncodingWriter::new(writer, encoding)),
+        _ => writer,
+    }
+}
+
+/// Resultado del detector sobre una muestra
+#[derive(Debug, Clone, Serialize)]
+pub struct Detection {
+    pub encoding: &'static str,
+    pub bom: bool,
+    /// Solo bytes ASCII: se lee igual como UTF-8 o Latin-1
+    pub ascii_only: bool,
+    /// Por qué se eligió (BOM, bytes nulos de UTF-16, primer byte inválido en UTF-8)
+    pub reason: String,
+    pub bytes_sampled: usize,
+}
+
+/// Codificación más probable de una muestra: BOM; UTF-16 sin BOM por los bytes nulos
+/// alternados; UTF-8 si la muestra es UTF-8 válido; si no, windows-1252 (Latin-1)
+pub fn detect(sample: &[u8]) -> (&'static Encoding, Detection) {
+    let ascii_only = sample.is_ascii();
+    let detection = |encoding: &'static Encoding, bom: bool, reason: String| (encoding, Detection {
+        encoding: encoding.name(),
+        bom,
+        ascii_only,
+        reason,
+        bytes_sampled: sample.len(),
+    });
+
+    if let Some((encoding, _)) = Encoding::for_bom(sample) {
+        return detection(encoding, true, format!("{} byte order mark", encoding.name()));
+    }
+
+    // Texto UTF-16 mayormente ASCII: uno de cada dos bytes es 0
+    let pairs = sample.len() / 2;
+    if pairs >= 2 {
+        let even_zeros = sample.iter().step_by(2).filter(|b| **b == 0).count();
+        let odd_zeros = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+        if odd_zeros * 10 > pairs * 3 && even_zeros * 20 < pairs {
+            return detection(UTF_16LE, false, format!("{} of {} odd bytes are NUL", odd_zeros, pairs));
+        }
+        if even_zeros * 10 > pairs * 3 && odd_zeros * 20 < pairs {
+            return detection(UTF_16BE, false, format!("{} of {} even bytes are NUL", even_zeros, pairs));
+        }
+    }
+
+    match std::str::from_utf8(sample) {
+        Ok(_) if ascii_only => detection(UTF_8, false, "ASCII only".to_string()),
+        Ok(_) => detection(UTF_8, false, "valid UTF-8 with multi-byte characters".to_string()),
+        // La muestra cortó un carácter al final: sigue siendo UTF-8
+        Err(e) if e.error_len().is_none() => detection(UTF_8, false, "valid UTF-8 with multi-byte characters".to_string()),
+        Err(e) => detection(WINDOWS_1252, false, format!(
+            "byte 0x{:02X} at offset {} is not valid UTF-8", sample[e.valid_up_to()], e.valid_up_to())),
+    }
+}
+
+/// Writer que recibe UTF-8 y escribe en otra codificación. Los caracteres que no
+/// existen en el destino se escriben como `?` y se advierte cuántos hubo al cerrar
+pub struct EncodingWriter<W: Write> {
+    inner: W,
+    encoding: &'static Encoding,
+    // encoding_rs no codifica a UTF-16: esos destinos se arman a mano
+    encoder: Option<encoding_rs::Encoder>,
+    // Bytes de un carácter UTF-8 que quedó cortado entre dos `write`
+    pending: Vec<u8>,
+    out: Vec<u8>,
+    unmappable: usize,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    pub fn new(inner: W, encoding: &'static Encoding) -> Self {
+        let encoder = (encoding != UTF_16LE && encoding != UTF_16BE).then(|| encoding.new_encoder());
+        EncodingWriter { inner, encoding, encoder, pending: Vec::new(), out: Vec::new(), unmappable: 0 }
+    }
+
+    fn encode(&mut self, text: &str) {
+        self.out.clear();
+        let Some(encoder) = self.encoder.as_mut() else {
+            let big_endian = self.encoding == UTF_16BE;
+            for unit in text.encode_utf16() {
+                self.out.extend_from_slice(&if big_endian { unit.to_be_bytes() } else { unit.to_le_bytes() });
+            }
+            return;
+        };
+
+        let mut rest = text;
+        loop {
+            let needed = encoder.max_buffer_length_from_utf8_without_replacement(rest.len()).unwrap_or(rest.len() * 4);
+            self.out.reserve(needed);
+            let (result, read) = encoder.encode_from_utf8_to_vec_without_replacement(rest, &mut self.out, false);
+            rest = &rest[read..];
+            match result {
+                EncoderResult::InputEmpty => break,
+                EncoderResult::OutputFull => {}
+                EncoderResult::Unmappable(_) => {
+                    self.out.push(b'?');
+                    self.unmappable += 1;
+                }
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut data = std::mem::take(&mut self.pending);
+        data.extend_from_slice(buf);
+        let valid = match std::str::from_utf8(&data) {
+            Ok(_) => data.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => {
+                return Err(io::Error::new(io::ErrorKind::InvalidData,
+                    format!("output is not valid UTF-8 at byte {}", e.valid_up_to())));
+            }
+        };
+        let text = std::str::from_utf8(&data[..valid]).expect("validated UTF-8 prefix");
+        self.encode(text);
+        self.inner.write_all(&self.out)?;
+        self.pending = data[valid..].to_vec();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for EncodingWriter<W> {
+    fn drop(&mut self) {
+        if self.unmappable > 0 {
+            diagnostics::warn(format!("{} characters have no {} equivalent and were written as '?'",
+                self.unmappable, self.encoding.name()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_transcode() {
+        assert_eq!(detect("Cuil,ApellidoNombre\n20123,Peña\n".as_bytes()).0, UTF_8);
+        assert_eq!(detect(b"Cuil,ApellidoNombre\n20123,Pe\xf1a\n").0, WINDOWS_1252);
+        assert_eq!(detect(b"C\0u\0i\0l\0,\0N\0").0, UTF_16LE);
+        let (encoding, detection) = detect(b"\xef\xbb\xbfCuil\n");
+        assert_eq!(encoding, UTF_8);
+        assert!(detection.bom);
+
+        // Un carácter cortado entre dos write se codifica entero
+        let mut writer = EncodingWriter::new(Vec::new(), WINDOWS_1252);
+        let text = "Peña €\n".as_bytes();
+        writer.write_all(&text[..3]).unwrap();
+        writer.write_all(&text[3..]).unwrap();
+        assert_eq!(writer.inner, b"Pe\xf1a \x80\n");
+
+        let mut writer = EncodingWriter::new(Vec::new(), UTF_16LE);
+        writer.write_all("Ñ".as_bytes()).unwrap();
+        assert_eq!(writer.inner, vec![0xd1, 0x00]);
+
+        let mut header = String::new();
+        let reader: Box<dyn BufRead> = Box::new(&b"\xef\xbb\xbfCuil,Nombre\n"[..]);
+        strip_bom(reader).unwrap().read_line(&mut header).unwrap();
+        assert_eq!(header, "Cuil,Nombre\n");
+    }
+}
@@ -0,0 +1,96 @@
+// Error de csv_tools: las funciones públicas devuelven `Result<T, CsvToolsError>`,
+// así quien usa la biblioteca decide según la causa (I/O, CSV mal formado, esquema,
+// argumentos) sin interpretar los mensajes. El código de salida del binario se
+// deriva de la variante (ver exit_code::for_error)
+
+use std::io;
+
+use crate::exit_code;
+use crate::runtime_limit::Incomplete;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CsvToolsError {
+    /// Archivo inaccesible, disco lleno, red (exit 3)
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// CSV mal formado (cantidad de columnas, UTF-8) o falla de I/O al leerlo
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    /// Archivo Parquet inválido o con un tipo/codec no soportado (from_parquet)
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+    /// Falla al armar o escribir un archivo Arrow IPC (to_arrow)
+    #[error(transparent)]
+    Arrow(#[from] arrow_schema::ArrowError),
+    /// El header o las columnas no coinciden con el modelo / schema
+    #[error("{0}")]
+    SchemaMismatch(String),
+    /// Argumento válido para clap pero inutilizable al ejecutar (modelo desconocido,
+    /// columna que no existe, configuración inválida) (exit 2)
+    #[error("{0}")]
+    InvalidArgument(String),
+    /// El comando terminó pero encontró problemas en los datos; el detalle ya se mostró
+    #[error("{0}")]
+    DataIssues(String),
+    /// Corte por --max-runtime: no es una falla, queda un checkpoint para --resume (exit 75)
+    #[error(transparent)]
+    Incomplete(#[from] Incomplete),
+    /// Valor inválido u otra falla sin variante propia (exit 1)
+    #[error("{0}")]
+    Message(String),
+    /// Error de un sink o extensión registrada por quien usa la biblioteca
+    #[error(transparent)]
+    Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl CsvToolsError {
+    /// Agrega contexto al mensaje sin cambiar la causa (un paso de pipeline que
+    /// falla por I/O sigue terminando con el código de I/O)
+    pub fn context(self, context: &str) -> Self {
+        let message = format!("{}: {}", context, self);
+        match self {
+            CsvToolsError::Incomplete(_) => self,
+            CsvToolsError::SchemaMismatch(_) => CsvToolsError::SchemaMismatch(message),
+            CsvToolsError::InvalidArgument(_) => CsvToolsError::InvalidArgument(message),
+            CsvToolsError::DataIssues(_) => CsvToolsError::DataIssues(message),
+            _ if exit_code::for_error(&self) == exit_code::IO_FAILURE => CsvToolsError::Io(io::Error::other(message)),
+            _ => CsvToolsError::Message(message),
+        }
+    }
+}
+
+impl From<glob::GlobError> for CsvToolsError {
+    fn from(error: glob::GlobError) -> Self {
+        CsvToolsError::Io(error.into())
+    }
+}
+
+impl From<std::num::ParseIntError> for CsvToolsError {
+    fn from(error: std::num::ParseIntError) -> Self {
+        CsvToolsError::Message(error.to_string())
+    }
+}
+
+impl From<std::string::FromUtf8Error> for CsvToolsError {
+    fn from(error: std::string::FromUtf8Error) -> Self {
+        CsvToolsError::Message(error.to_string())
+    }
+}
+
+impl From<String> for CsvToolsError {
+    fn from(message: String) -> Self {
+        CsvToolsError::Message(message)
+    }
+}
+
+impl From<&str> for CsvToolsError {
+    fn from(message: &str) -> Self {
+        CsvToolsError::Message(message.to_string())
+    }
+}
@@ -0,0 +1,191 @@
+// Reporte de errores con esquema fijo (--error-report): cada validador, además de su
+// log de texto (`[LINE n] ❌ TIPO | ...`), emite un registro
+//   line, byte_offset, error_type, column, value, message
+// como CSV o NDJSON, así se pueden agregar errores de varias corridas sin parsear logs
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::sync::{Mutex, OnceLock};
+
+use clap::ValueEnum;
+use csv::Position;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CsvToolsError;
+use crate::file_utils::is_stdio;
+
+/// Columnas del reporte CSV (mismo orden que los campos de `ErrorRecord`)
+pub const CSV_HEADER: [&str; 6] = ["line", "byte_offset", "error_type", "column", "value", "message"];
+
+/// Formato del reporte de errores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ErrorReportFormat {
+    /// Una fila por error con header `line,byte_offset,error_type,column,value,message`
+    Csv,
+    /// Un objeto JSON por línea con los mismos campos
+    Ndjson,
+}
+
+impl ErrorReportFormat {
+    /// Formato según la extensión: .ndjson / .jsonl → NDJSON, el resto CSV
+    pub fn from_path(path: &str) -> Self {
+        let lower = path.to_lowercase();
+        if lower.ends_with(".ndjson") || lower.ends_with(".jsonl") {
+            ErrorReportFormat::Ndjson
+        } else {
+            ErrorReportFormat::Csv
+        }
+    }
+}
+
+/// Un error de datos: línea (1 = header) y byte de inicio del registro en la entrada,
+/// tipo en MAYÚSCULAS (PARSE_ERROR, COLUMN_COUNT, INVALID_NUMERIC, ...), columna y valor si aplican
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorRecord {
+    pub line: u64,
+    pub byte_offset: Option<u64>,
+    pub error_type: String,
+    pub column: Option<String>,
+    pub value: Option<String>,
+    pub message: String,
+}
+
+impl ErrorRecord {
+    pub fn new(line: usize, error_type: &str, message: impl Into<String>) -> Self {
+        ErrorRecord {
+            line: line as u64,
+            error_type: error_type.to_string(),
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Error de parseo del CSV (cantidad de columnas, UTF-8) con la posición que da el lector
+    pub fn parse_error(line: usize, error: &csv::Error, message: impl Into<String>) -> Self {
+        ErrorRecord::new(line, "PARSE_ERROR", message).at(error.position())
+    }
+
+    /// Byte de inicio del registro (posición relativa a lo que leyó el lector)
+    pub fn at(mut self, position: Option<&Position>) -> Self {
+        self.byte_offset = position.map(|p| p.byte());
+        self
+    }
+
+    /// Byte de inicio ya absoluto (lectores que retoman desde un checkpoint)
+    pub fn at_byte(mut self, byte_offset: Option<u64>) -> Self {
+        self.byte_offset = byte_offset;
+        self
+    }
+
+    pub fn column(mut self, column: &str) -> Self {
+        self.column = Some(column.to_string());
+        self
+    }
+
+    pub fn value(mut self, value: &str) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    /// Línea del log de texto: `[LINE n] ❌ TIPO | Field='c' | Value='v' | mensaje`
+    pub fn log_line(&self) -> String {
+        let mut line = format!("[LINE {}] ❌ {}", self.line, self.error_type);
+        if let Some(column) = &self.column {
+            line.push_str(&format!(" | Field='{}'", column));
+        }
+        if let Some(value) = &self.value {
+            line.push_str(&format!(" | Value='{}'", value));
+        }
+        line.push_str(&format!(" | {}", self.message));
+        line
+    }
+}
+
+enum ReportWriter {
+    Csv(Box<csv::Writer<Box<dyn Write + Send>>>),
+    Ndjson(Box<dyn Write + Send>),
+}
+
+static REPORT: OnceLock<Mutex<ReportWriter>> = OnceLock::new();
+
+/// Abre el reporte de la corrida (una vez, desde las opciones globales); `-` escribe a stdout
+pub fn configure(path: &str, format: Option<ErrorReportFormat>) -> Result<(), CsvToolsError> {
+    let out: Box<dyn Write + Send> = if is_stdio(path) {
+        Box::new(io::stdout())
+    } else {
+        let file = File::create(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("Cannot create error report '{}': {}", path, e)))?;
+        Box::new(BufWriter::new(file))
+    };
+    let writer = match format.unwrap_or_else(|| ErrorReportFormat::from_path(path)) {
+        ErrorReportFormat::Csv => {
+            // Header explícito: un reporte sin errores también lo tiene
+            let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(out);
+            writer.write_record(CSV_HEADER)?;
+            ReportWriter::Csv(Box::new(writer))
+        }
+        ErrorReportFormat::Ndjson => ReportWriter::Ndjson(out),
+    };
+    REPORT.set(Mutex::new(writer)).ok();
+    Ok(())
+}
+
+/// Agrega un error al reporte (sin --error-report no hace nada)
+pub fn emit(record: &ErrorRecord) -> Result<(), CsvToolsError> {
+    let Some(report) = REPORT.get() else {
+        return Ok(());
+    };
+    match &mut *report.lock().unwrap() {
+        ReportWriter::Csv(writer) => writer.serialize(record)?,
+        ReportWriter::Ndjson(out) => {
+            serde_json::to_writer(&mut *out, record)?;
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Escribe el error en el log de texto del comando y lo agrega al reporte
+pub fn write(log: &mut impl Write, record: &ErrorRecord) -> Result<(), CsvToolsError> {
+    writeln!(log, "{}", record.log_line())?;
+    emit(record)
+}
+
+/// Vacía el reporte al terminar la corrida (también si el comando falló)
+pub fn flush() -> Result<(), CsvToolsError> {
+    if let Some(report) = REPORT.get() {
+        match &mut *report.lock().unwrap() {
+            ReportWriter::Csv(writer) => writer.flush()?,
+            ReportWriter::Ndjson(out) => out.flush()?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_record_formats() {
+        let record = ErrorRecord::new(42, "INVALID_NUMERIC", "Expected: Type N (numeric)")
+            .at_byte(Some(1337))
+            .column("Cuil")
+            .value("20-1");
+        assert_eq!(record.log_line(), "[LINE 42] ❌ INVALID_NUMERIC | Field='Cuil' | Value='20-1' | Expected: Type N (numeric)");
+
+        let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+        writer.write_record(CSV_HEADER).unwrap();
+        writer.serialize(&record).unwrap();
+        writer.serialize(ErrorRecord::new(7, "EMPTY_KEY", "Keys: Cuil + IdTransmit")).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!(csv, "line,byte_offset,error_type,column,value,message\n\
+                         42,1337,INVALID_NUMERIC,Cuil,20-1,Expected: Type N (numeric)\n\
+                         7,,EMPTY_KEY,,,Keys: Cuil + IdTransmit\n");
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(serde_json::from_str::<ErrorRecord>(&json).unwrap(), record);
+        assert_eq!(ErrorReportFormat::from_path("errors.ndjson"), ErrorReportFormat::Ndjson);
+        assert_eq!(ErrorReportFormat::from_path("errors.csv"), ErrorReportFormat::Csv);
+    }
+}
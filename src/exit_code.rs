@@ -0,0 +1,69 @@
+// Códigos de salida de csv_tools, iguales para todos los comandos (ver README):
+//   0 = sin problemas, 1 = se encontraron problemas en los datos,
+//   2 = error de uso (argumentos, modelo o columna inexistente, configuración),
+//   3 = falla de I/O (archivo inaccesible, disco, red), 75 = corte por --max-runtime
+// Los comandos devuelven `CsvToolsError::DataIssues` / `InvalidArgument` en lugar de
+// llamar a `process::exit`, así el manifiesto y el pipeline ven el resultado
+
+use crate::error::CsvToolsError;
+use crate::runtime_limit;
+
+pub const OK: i32 = 0;
+pub const DATA_ISSUES: i32 = 1;
+pub const USAGE: i32 = 2;
+pub const IO_FAILURE: i32 = 3;
+
+/// El comando terminó pero encontró problemas en los datos (validación fallida,
+/// aserción incumplida, diferencias); el detalle ya se mostró
+pub fn data_issues(message: impl Into<String>) -> CsvToolsError {
+    CsvToolsError::DataIssues(message.into())
+}
+
+/// Argumento válido para clap pero inutilizable al ejecutar (modelo desconocido,
+/// columna que no existe, configuración inválida)
+pub fn usage_error(message: impl Into<String>) -> CsvToolsError {
+    CsvToolsError::InvalidArgument(message.into())
+}
+
+/// Agrega contexto al mensaje de un error sin cambiar su código de salida
+/// (un paso de pipeline que falla por I/O sigue terminando con 3)
+pub fn with_context(error: CsvToolsError, context: &str) -> CsvToolsError {
+    error.context(context)
+}
+
+/// Código de salida de un error; lo no clasificado (registros mal formados,
+/// valores inválidos) cuenta como problema en los datos
+pub fn for_error(error: &CsvToolsError) -> i32 {
+    match error {
+        CsvToolsError::Incomplete(_) => runtime_limit::EXIT_INCOMPLETE,
+        CsvToolsError::InvalidArgument(_) => USAGE,
+        CsvToolsError::Io(_) => IO_FAILURE,
+        CsvToolsError::Csv(e) if e.is_io_error() => IO_FAILURE,
+        CsvToolsError::Sqlite(rusqlite::Error::SqliteFailure(f, _)) if matches!(
+            f.code,
+            rusqlite::ErrorCode::CannotOpen | rusqlite::ErrorCode::DiskFull | rusqlite::ErrorCode::SystemIoFailure
+        ) => IO_FAILURE,
+        _ => DATA_ISSUES,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_exit_code_for_error() {
+        assert_eq!(for_error(&data_issues("3 invalid records")), DATA_ISSUES);
+        assert_eq!(for_error(&usage_error("Unknown model type: x")), USAGE);
+        let missing: CsvToolsError = io::Error::new(io::ErrorKind::NotFound, "missing.csv").into();
+        assert_eq!(for_error(&missing), IO_FAILURE);
+        let parse: CsvToolsError = "Invalid row".into();
+        assert_eq!(for_error(&parse), DATA_ISSUES);
+        let step = with_context(usage_error("Unknown column 'x'"), "Pipeline step 2 (filter) failed");
+        assert_eq!(step.to_string(), "Pipeline step 2 (filter) failed: Unknown column 'x'");
+        assert_eq!(for_error(&step), USAGE);
+        let step = with_context(missing, "Pipeline step 1 (merge) failed");
+        assert_eq!(for_error(&step), IO_FAILURE);
+    }
+}
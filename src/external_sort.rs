@@ -0,0 +1,230 @@
+// Sort externo con deduplicación, en Rust puro (sin `sort` ni PowerShell):
+// 1. Corridas: se leen líneas hasta llenar --memory-limit, se ordenan con la collation
+//    actual, se quitan los repetidos y se vuelcan a un temporal
+// 2. Merge: k-way merge de las corridas con un heap, descartando las líneas iguales
+//    (según `Collation::dedup_key`) a la última escrita
+// Con muchas corridas se mezclan en tandas de MAX_MERGE_FAN_IN para no agotar los
+// descriptores de archivo. Trabaja por líneas, igual que el `sort -u` que reemplaza
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::collation::Collation;
+use crate::error::CsvToolsError;
+use crate::temp_files::TempFiles;
+
+/// Límite de memoria por defecto para las corridas en memoria (--memory-limit)
+pub const DEFAULT_MEMORY_LIMIT: usize = 1 << 30;
+/// Corridas que se mezclan a la vez (cada una es un archivo abierto)
+const MAX_MERGE_FAN_IN: usize = 64;
+/// Costo aproximado por línea además de sus bytes (String + entrada del Vec)
+const LINE_OVERHEAD: usize = std::mem::size_of::<String>() + 16;
+
+/// Resultado de un sort externo
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SortStats {
+    pub lines_read: usize,
+    pub lines_written: usize,
+    pub duplicates_removed: usize,
+    /// Corridas volcadas a disco (0 = todo entró en memoria)
+    pub runs: usize,
+}
+
+/// Ordena las líneas de `reader`, sin repetidas, y las escribe en `output`
+/// Los temporales de las corridas se crean con `temp_files` (y se borran con él)
+pub fn sort_dedup_lines(
+    reader: &mut dyn BufRead,
+    output: &mut dyn Write,
+    temp_files: &mut TempFiles,
+    memory_limit: usize,
+    collation: Collation,
+) -> Result<SortStats, CsvToolsError> {
+    let mut stats = SortStats::default();
+    let mut runs: Vec<PathBuf> = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut used = 0usize;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        trim_eol(&mut line);
+        stats.lines_read += 1;
+        used += line.len() + LINE_OVERHEAD;
+        lines.push(std::mem::take(&mut line));
+
+        if used >= memory_limit {
+            let path = temp_files.path(&format!("run_{}.txt", runs.len()));
+            let mut writer = BufWriter::with_capacity(1 << 20, File::create(&path)?);
+            write_sorted_run(&mut lines, &mut writer, collation)?;
+            writer.flush()?;
+            runs.push(path);
+            used = 0;
+        }
+    }
+
+    // Todo entró en memoria: no hace falta tocar el disco
+    if runs.is_empty() {
+        stats.lines_written = write_sorted_run(&mut lines, output, collation)?;
+        stats.duplicates_removed = stats.lines_read - stats.lines_written;
+        return Ok(stats);
+    }
+    if !lines.is_empty() {
+        let path = temp_files.path(&format!("run_{}.txt", runs.len()));
+        let mut writer = BufWriter::with_capacity(1 << 20, File::create(&path)?);
+        write_sorted_run(&mut lines, &mut writer, collation)?;
+        writer.flush()?;
+        runs.push(path);
+    }
+    drop(lines);
+    stats.runs = runs.len();
+
+    // Merges intermedios hasta que las corridas se puedan abrir todas juntas
+    let mut next_run = runs.len();
+    while runs.len() > MAX_MERGE_FAN_IN {
+        let batch: Vec<PathBuf> = runs.drain(..MAX_MERGE_FAN_IN).collect();
+        let path = temp_files.path(&format!("run_{}.txt", next_run));
+        next_run += 1;
+        let mut writer = BufWriter::with_capacity(1 << 20, File::create(&path)?);
+        merge_runs(&batch, &mut writer, collation)?;
+        writer.flush()?;
+        for done in &batch {
+            std::fs::remove_file(done)?;
+        }
+        runs.push(path);
+    }
+
+    stats.lines_written = merge_runs(&runs, output, collation)?;
+    stats.duplicates_removed = stats.lines_read - stats.lines_written;
+    Ok(stats)
+}
+
+/// Ordena `lines`, las escribe sin repetidas y deja el vector vacío; devuelve las escritas
+fn write_sorted_run(lines: &mut Vec<String>, writer: &mut dyn Write, collation: Collation) -> Result<usize, CsvToolsError> {
+    lines.sort_unstable_by(|a, b| collation.compare(a, b));
+    let mut written = 0;
+    let mut last: Option<&String> = None;
+    for line in lines.iter() {
+        if last.is_some_and(|l| collation.dedup_key(l) == collation.dedup_key(line)) {
+            continue;
+        }
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")?;
+        written += 1;
+        last = Some(line);
+    }
+    lines.clear();
+    Ok(written)
+}
+
+/// Línea en el heap del merge: el orden se invierte para que el BinaryHeap (max-heap)
+/// entregue primero la menor; a igualdad gana la corrida anterior
+struct HeapLine {
+    line: String,
+    run: usize,
+    collation: Collation,
+}
+
+impl Ord for HeapLine {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.collation.compare(&other.line, &self.line)
+            .then_with(|| other.run.cmp(&self.run))
+    }
+}
+
+impl PartialOrd for HeapLine {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeapLine {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapLine {}
+
+/// k-way merge de corridas ya ordenadas y sin repetidas; devuelve las líneas escritas
+fn merge_runs(runs: &[PathBuf], writer: &mut dyn Write, collation: Collation) -> Result<usize, CsvToolsError> {
+    let mut readers = Vec::with_capacity(runs.len());
+    let mut heap = BinaryHeap::with_capacity(runs.len());
+    for (run, path) in runs.iter().enumerate() {
+        let mut reader = BufReader::with_capacity(1 << 16, File::open(path)?);
+        if let Some(line) = next_line(&mut reader)? {
+            heap.push(HeapLine { line, run, collation });
+        }
+        readers.push(reader);
+    }
+
+    let mut written = 0;
+    let mut last: Option<String> = None;
+    while let Some(HeapLine { line, run, .. }) = heap.pop() {
+        if let Some(next) = next_line(&mut readers[run])? {
+            heap.push(HeapLine { line: next, run, collation });
+        }
+        if last.as_deref().is_some_and(|l| collation.dedup_key(l) == collation.dedup_key(&line)) {
+            continue;
+        }
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")?;
+        written += 1;
+        last = Some(line);
+    }
+    Ok(written)
+}
+
+fn next_line(reader: &mut impl BufRead) -> Result<Option<String>, CsvToolsError> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    trim_eol(&mut line);
+    Ok(Some(line))
+}
+
+/// Quita el fin de línea (LF o CRLF) para que "a\r\n" y "a\n" se consideren iguales
+fn trim_eol(line: &mut String) {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_dedup_spills_and_merges() {
+        let input = "c\nb\na\r\nb\nA\nd\na\nc\n";
+        let run = |memory_limit: usize, collation: Collation| {
+            let mut temp_files = TempFiles::new(&std::env::temp_dir());
+            let mut output = Vec::new();
+            let stats = sort_dedup_lines(&mut input.as_bytes(), &mut output, &mut temp_files, memory_limit, collation).unwrap();
+            (String::from_utf8(output).unwrap(), stats)
+        };
+
+        let (in_memory, stats) = run(DEFAULT_MEMORY_LIMIT, Collation::Binary);
+        assert_eq!(in_memory, "A\na\nb\nc\nd\n");
+        assert_eq!((stats.lines_read, stats.duplicates_removed, stats.runs), (8, 3, 0));
+
+        // Una corrida cada ~2 líneas: mismo resultado que en memoria
+        let (spilled, stats) = run(2 * LINE_OVERHEAD, Collation::Binary);
+        assert_eq!(spilled, in_memory);
+        assert_eq!(stats.runs, 4);
+
+        let (case_insensitive, _) = run(2 * LINE_OVERHEAD, Collation::CaseInsensitive);
+        assert_eq!(case_insensitive, "A\nb\nc\nd\n");
+    }
+}
@@ -1,9 +1,85 @@
 use std::fs::File;
-use std::io::{BufReader, BufRead};
-use std::error::Error;
+use std::io::{BufReader, BufRead, Read, Write};
+use std::path::Path;
+use flate2::read::MultiGzDecoder;
+
+use crate::encoding;
+use crate::error::CsvToolsError;
+use crate::exit_code;
+use crate::io_retry::RetryReader;
+use crate::logging::{self, Level};
+
+// Magic bytes de gzip (1f 8b)
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+// Magic bytes de un frame zstd (28 b5 2f fd)
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+// Nombre de archivo que significa stdin (entrada) o stdout (salida), para pipelines
+pub const STDIO: &str = "-";
+
+/// `-` en lugar de una ruta: stdin/stdout
+pub fn is_stdio(path: &str) -> bool {
+    path == STDIO
+}
+
+/// Destino de los mensajes de estado de un comando: stderr si los datos salen
+/// por stdout (`data_output` = `-` o `--format json`), así no se mezclan con el CSV
+/// del pipeline ni con el resumen JSON; con --quiet se descartan
+pub fn status_output(data_output: &str) -> Box<dyn Write> {
+    if !logging::enabled(Level::Normal) {
+        Box::new(std::io::sink())
+    } else if is_stdio(data_output) || logging::stdout_is_data() {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(std::io::stdout())
+    }
+}
+
+/// Archivos de entrada de los comandos multi-archivo (count_all, count_unique, merge,
+/// merge_dedup, ...): un patrón glob (`data/part_*.csv`), un directorio (sus .csv,
+/// .csv.gz y .csv.zst), un CSV suelto o, como siempre, un archivo de lista con una ruta por línea
+/// Globs y directorios se ordenan por nombre: mismas entradas, mismo orden de proceso
+pub fn resolve_inputs(spec: &str) -> Result<Vec<String>, CsvToolsError> {
+    let mut files = if spec.contains(['*', '?', '[']) {
+        let pattern = glob::glob(spec)
+            .map_err(|e| exit_code::usage_error(format!("Invalid glob pattern '{}': {}", spec, e)))?;
+        let mut files = Vec::new();
+        for entry in pattern {
+            let path = entry?;
+            if path.is_file() {
+                files.push(path.to_string_lossy().into_owned());
+            }
+        }
+        files
+    } else if Path::new(spec).is_dir() {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(spec)? {
+            let path = entry?.path();
+            if path.is_file() && is_csv_name(&path.to_string_lossy()) {
+                files.push(path.to_string_lossy().into_owned());
+            }
+        }
+        files
+    } else if is_csv_name(spec) {
+        return Ok(vec![spec.to_string()]);
+    } else {
+        return read_file_list(spec);
+    };
+
+    if files.is_empty() {
+        return Err(exit_code::usage_error(format!("No CSV files match '{}'", spec)));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// `.csv`, `.csv.gz` o `.csv.zst` (sin distinguir mayúsculas)
+pub fn is_csv_name(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".csv") || lower.ends_with(".csv.gz") || lower.ends_with(".csv.zst")
+}
 
 /// Lee un archivo de lista de archivos y devuelve las rutas
-pub fn read_file_list(file_list_path: &str) -> Result<Vec<String>, Box<dyn Error>> {
+pub fn read_file_list(file_list_path: &str) -> Result<Vec<String>, CsvToolsError> {
     let file = File::open(file_list_path)?;
     let reader = BufReader::new(file);
     
@@ -19,11 +95,20 @@ pub fn read_file_list(file_list_path: &str) -> Result<Vec<String>, Box<dyn Error
 }
 
 /// Calcula el tamaño de un archivo en bytes
-pub fn get_file_size(path: &str) -> Result<u64, Box<dyn Error>> {
+pub fn get_file_size(path: &str) -> Result<u64, CsvToolsError> {
     let metadata = std::fs::metadata(path)?;
     Ok(metadata.len())
 }
 
+/// Suma el tamaño de los archivos (para proyectar el espacio en disco necesario)
+pub fn total_file_size(file_names: &[String]) -> Result<u64, CsvToolsError> {
+    let mut total = 0;
+    for filename in file_names {
+        total += get_file_size(filename)?;
+    }
+    Ok(total)
+}
+
 /// Formatea bytes en formato legible (KB, MB, GB)
 pub fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -42,7 +127,7 @@ pub fn format_bytes(bytes: u64) -> String {
 }
 
 /// Valida que un archivo exista
-pub fn validate_file_exists(path: &str) -> Result<(), Box<dyn Error>> {
+pub fn validate_file_exists(path: &str) -> Result<(), CsvToolsError> {
     if !std::path::Path::new(path).exists() {
         return Err(format!("File not found: {}", path).into());
     }
@@ -50,28 +135,152 @@ pub fn validate_file_exists(path: &str) -> Result<(), Box<dyn Error>> {
 }
 
 /// Crea un directorio si no existe
-pub fn ensure_directory_exists(path: &str) -> Result<(), Box<dyn Error>> {
+pub fn ensure_directory_exists(path: &str) -> Result<(), CsvToolsError> {
     std::fs::create_dir_all(path)?;
     Ok(())
 }
 
 /// Obtiene el número total de líneas en un archivo (para estimar progreso)
-pub fn estimate_file_lines(file_path: &str) -> Result<usize, Box<dyn Error>> {
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
+pub fn estimate_file_lines(file_path: &str) -> Result<usize, CsvToolsError> {
+    let reader = open_input(file_path)?;
     Ok(reader.lines().count())
 }
 
-/// Obtiene el total de líneas en múltiples archivos listados en un archivo de texto
-pub fn estimate_total_lines_from_list(file_list_path: &str) -> Result<usize, Box<dyn Error>> {
-    let file = File::open(file_list_path)?;
-    let reader = BufReader::new(file);
-    let mut total = 0;
+/// Líneas de cada archivo de la lista (para el progreso por archivo)
+pub fn estimate_lines_per_file(file_names: &[String]) -> Result<Vec<u64>, CsvToolsError> {
+    file_names.iter()
+        .map(|f| estimate_file_lines(f).map(|n| n as u64))
+        .collect()
+}
 
-    for line in reader.lines() {
-        let filename = line?;
+/// Obtiene el total de líneas de los archivos de entrada (lista, directorio o glob,
+/// ver `resolve_inputs`)
+pub fn estimate_total_lines_from_list(inputs: &str) -> Result<usize, CsvToolsError> {
+    let mut total = 0;
+    for filename in resolve_inputs(inputs)? {
         total += estimate_file_lines(&filename)?;
     }
 
     Ok(total)
 }
+
+/// Abre un archivo de entrada (`-` = stdin), descomprimiendo gzip o zstd de forma transparente
+/// (se detecta por magic bytes, no por extensión; soporta .gz / .zst concatenados)
+/// y decodificando a UTF-8 según `--input-encoding`, sin el BOM inicial (salvo `--keep-bom`)
+/// Los errores de lectura transitorios se reintentan según `--io-retries`
+pub fn open_input(path: &str) -> Result<Box<dyn BufRead>, CsvToolsError> {
+    Ok(encoding::strip_bom(encoding::decode_input(open_input_bytes(path)?)?)?)
+}
+
+/// `open_input` sin decodificar: los bytes tal como están en el archivo (ya descomprimidos)
+pub fn open_input_bytes(path: &str) -> Result<Box<dyn BufRead>, CsvToolsError> {
+    if is_stdio(path) {
+        return decompress_if_needed(BufReader::with_capacity(1 << 20, std::io::stdin().lock()));
+    }
+    decompress_if_needed(BufReader::with_capacity(1 << 20, RetryReader::open(path)?))
+}
+
+/// `open_input` desde el byte `offset` de los datos (ya descomprimidos), para retomar
+/// con --resume: un archivo plano se posiciona directo; un .gz / .zst (o una entrada que se
+/// decodifica con --input-encoding) se lee desde el principio y se descarta lo anterior
+pub fn open_input_at(path: &str, offset: u64) -> Result<Box<dyn BufRead>, CsvToolsError> {
+    let mut magic = [0u8; 4];
+    let n = File::open(path)?.read(&mut magic)?;
+    let compressed = magic[..n].starts_with(&GZIP_MAGIC) || magic[..n].starts_with(&ZSTD_MAGIC);
+    if !compressed && !encoding::transcodes_input() {
+        // Los offsets se cuentan sin el BOM que open_input quita
+        let bom = if encoding::strips_bom() && magic[..n].starts_with(encoding::UTF8_BOM) { encoding::UTF8_BOM.len() as u64 } else { 0 };
+        return Ok(Box::new(BufReader::with_capacity(1 << 20, RetryReader::open_at(path, offset + bom)?)));
+    }
+
+    let mut reader = open_input(path)?;
+    std::io::copy(&mut reader.by_ref().take(offset), &mut std::io::sink())?;
+    Ok(reader)
+}
+
+/// Registros de un CSV leído por líneas (`lines().enumerate()` de los comandos que copian
+/// el texto tal cual): un campo entre comillas con saltos de línea junta sus líneas en un
+/// solo registro. El índice es el de la línea donde empieza el registro (0 = header)
+pub fn record_lines<R: BufRead>(reader: R) -> RecordLines<R> {
+    RecordLines { lines: reader.lines(), next_line: 0 }
+}
+
+pub struct RecordLines<R> {
+    lines: std::io::Lines<R>,
+    next_line: usize,
+}
+
+impl<R: BufRead> Iterator for RecordLines<R> {
+    type Item = (usize, std::io::Result<String>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.next_line;
+        let mut record = match self.lines.next()? {
+            Ok(line) => line,
+            Err(e) => return Some((start, Err(e))),
+        };
+        self.next_line += 1;
+        // Comillas impares: el registro sigue en la línea siguiente (las `""` escapadas suman dos)
+        while record.matches('"').count() % 2 == 1 {
+            match self.lines.next() {
+                Some(Ok(line)) => {
+                    record.push('\n');
+                    record.push_str(&line);
+                    self.next_line += 1;
+                }
+                Some(Err(e)) => return Some((start, Err(e))),
+                None => break,
+            }
+        }
+        Some((start, Ok(record)))
+    }
+}
+
+fn decompress_if_needed<R: BufRead + 'static>(mut reader: R) -> Result<Box<dyn BufRead>, CsvToolsError> {
+    let head = reader.fill_buf()?;
+    if head.starts_with(&GZIP_MAGIC) {
+        return Ok(Box::new(BufReader::with_capacity(1 << 20, MultiGzDecoder::new(reader))));
+    }
+    if head.starts_with(&ZSTD_MAGIC) {
+        return Ok(Box::new(BufReader::with_capacity(1 << 20, zstd::Decoder::with_buffer(reader)?)));
+    }
+
+    Ok(Box::new(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_resolve_inputs_dir_glob_and_list() {
+        let dir = TempDir::new("inputs");
+        for name in ["part_2.csv", "part_1.csv", "part_3.csv.gz", "part_4.CSV.ZST", "notes.txt"] {
+            dir.write(name, "a\n");
+        }
+
+        let from_dir = resolve_inputs(&dir.root()).unwrap();
+        assert_eq!(from_dir, vec![dir.path("part_1.csv"), dir.path("part_2.csv"), dir.path("part_3.csv.gz"), dir.path("part_4.CSV.ZST")]);
+        assert_eq!(resolve_inputs(&dir.path("part_4.CSV.ZST")).unwrap(), vec![dir.path("part_4.CSV.ZST")]);
+
+        let from_glob = resolve_inputs(&dir.path("part_*.csv")).unwrap();
+        assert_eq!(from_glob, vec![dir.path("part_1.csv"), dir.path("part_2.csv")]);
+        assert!(resolve_inputs(&dir.path("missing_*.csv")).is_err());
+
+        dir.write("list.txt", format!("{}\n# comment\n\n{}\n", dir.path("part_2.csv"), dir.path("part_1.csv")));
+        assert_eq!(resolve_inputs(&dir.path("list.txt")).unwrap(), vec![dir.path("part_2.csv"), dir.path("part_1.csv")]);
+    }
+
+    #[test]
+    fn test_record_lines_joins_quoted_newlines() {
+        let input = "id,note\n1,\"a\nb\"\n2,\"say \"\"hi\"\"\"\n3,\"x\n\ny\"\n";
+        let records: Vec<(usize, String)> = record_lines(input.as_bytes()).map(|(i, r)| (i, r.unwrap())).collect();
+        assert_eq!(records, vec![
+            (0, "id,note".to_string()),
+            (1, "1,\"a\nb\"".to_string()),
+            (3, "2,\"say \"\"hi\"\"\"".to_string()),
+            (4, "3,\"x\n\ny\"".to_string()),
+        ]);
+    }
+}
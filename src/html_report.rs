@@ -0,0 +1,235 @@
+// Reporte HTML de validación (--report-html) para compartir con los dueños de los
+// datos: totales, errores por columna y por tipo con gráficos de barras, valores
+// inválidos más frecuentes y una muestra de filas rechazadas
+// Es un único archivo autocontenido (CSS y SVG inline, sin JavaScript ni CDN) que
+// se puede adjuntar a un mail o ticket
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+
+use chrono::Local;
+use csv::StringRecord;
+
+use crate::build_info;
+use crate::error::CsvToolsError;
+
+/// Filas rechazadas que se muestran como ejemplo
+const MAX_SAMPLE_ROWS: usize = 20;
+/// Valores inválidos más frecuentes por columna
+const TOP_VALUES: usize = 5;
+/// Caracteres por celda en la muestra de filas
+const MAX_CELL_CHARS: usize = 60;
+
+#[derive(Default)]
+struct ColumnErrors {
+    count: usize,
+    values: HashMap<String, usize>,
+}
+
+struct SampleRow {
+    line: usize,
+    reason: String,
+    fields: Vec<String>,
+}
+
+/// Acumula los resultados de una validación mientras se recorre el archivo
+pub struct ValidationReport {
+    title: String,
+    input: String,
+    model: String,
+    headers: Vec<String>,
+    records: usize,
+    invalid_records: usize,
+    columns: HashMap<String, ColumnErrors>,
+    error_types: HashMap<String, usize>,
+    samples: Vec<SampleRow>,
+}
+
+impl ValidationReport {
+    pub fn new(title: &str, input: &str, model: &str) -> Self {
+        ValidationReport {
+            title: title.to_string(),
+            input: input.to_string(),
+            model: model.to_string(),
+            headers: Vec::new(),
+            records: 0,
+            invalid_records: 0,
+            columns: HashMap::new(),
+            error_types: HashMap::new(),
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn set_headers(&mut self, headers: &StringRecord) {
+        self.headers = headers.iter().map(str::to_string).collect();
+    }
+
+    /// Totales de registros leídos y rechazados (se fijan al terminar)
+    pub fn set_totals(&mut self, records: usize, invalid_records: usize) {
+        self.records = records;
+        self.invalid_records = invalid_records;
+    }
+
+    /// Un error de tipo `error_type`; con columna, cuenta también el valor inválido
+    pub fn error(&mut self, error_type: &str, column: Option<&str>, value: &str) {
+        *self.error_types.entry(error_type.to_string()).or_insert(0) += 1;
+        if let Some(column) = column {
+            let errors = self.columns.entry(column.to_string()).or_default();
+            errors.count += 1;
+            *errors.values.entry(value.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    /// Fila rechazada de ejemplo (solo se guardan las primeras)
+    pub fn sample(&mut self, line: usize, reason: &str, record: &StringRecord) {
+        if self.samples.len() < MAX_SAMPLE_ROWS {
+            self.samples.push(SampleRow {
+                line,
+                reason: reason.to_string(),
+                fields: record.iter().map(|f| truncate(f, MAX_CELL_CHARS)).collect(),
+            });
+        }
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), CsvToolsError> {
+        fs::write(path, self.render())?;
+        Ok(())
+    }
+
+    fn render(&self) -> String {
+        let mut html = String::new();
+        let valid = self.records.saturating_sub(self.invalid_records);
+        let rate = if self.records == 0 { 0.0 } else { self.invalid_records as f64 * 100.0 / self.records as f64 };
+
+        let _ = write!(html, "<!DOCTYPE html>\n<html lang=\"es\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+            escape(&self.title), STYLE);
+        let _ = write!(html, "<h1>{}</h1>\n<p class=\"meta\">Archivo: <b>{}</b> · Modelo: <b>{}</b> · Generado: {} · csv_tools {}</p>\n",
+            escape(&self.title), escape(&self.input), escape(&self.model),
+            Local::now().format("%Y-%m-%d %H:%M"), build_info::VERSION);
+
+        let status = if self.invalid_records == 0 { "ok" } else { "fail" };
+        let _ = writeln!(html, "<div class=\"cards\">\
+            <div class=\"card\"><span>{}</span>Registros</div>\
+            <div class=\"card ok\"><span>{}</span>Válidos</div>\
+            <div class=\"card {}\"><span>{}</span>Con errores</div>\
+            <div class=\"card {}\"><span>{:.2}%</span>Tasa de error</div></div>",
+            self.records, valid, status, self.invalid_records, status, rate);
+        html.push_str(&bar_chart(&[("Válidos".to_string(), valid), ("Con errores".to_string(), self.invalid_records)], &["#2e7d32", "#c62828"]));
+
+        if self.invalid_records == 0 && self.error_types.is_empty() {
+            html.push_str("<p class=\"ok-msg\">✅ Todos los registros son válidos.</p>\n</body>\n</html>\n");
+            return html;
+        }
+
+        let mut columns: Vec<(&String, &ColumnErrors)> = self.columns.iter().collect();
+        columns.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+        if !columns.is_empty() {
+            html.push_str("<h2>Errores por columna</h2>\n");
+            let bars: Vec<(String, usize)> = columns.iter().map(|(c, e)| (c.to_string(), e.count)).collect();
+            html.push_str(&bar_chart(&bars, &["#c62828"]));
+            html.push_str("<table>\n<tr><th>Columna</th><th>Errores</th><th>Valores distintos</th><th>Valores más frecuentes</th></tr>\n");
+            for (column, errors) in &columns {
+                let mut values: Vec<(&String, &usize)> = errors.values.iter().collect();
+                values.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+                let top: Vec<String> = values.iter().take(TOP_VALUES)
+                    .map(|(v, n)| format!("<code>{}</code> × {}", if v.is_empty() { "(vacío)".to_string() } else { escape(&truncate(v, MAX_CELL_CHARS)) }, n))
+                    .collect();
+                let _ = writeln!(html, "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>",
+                    escape(column), errors.count, errors.values.len(), top.join("<br>"));
+            }
+            html.push_str("</table>\n");
+        }
+
+        let mut types: Vec<(&String, &usize)> = self.error_types.iter().collect();
+        types.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        html.push_str("<h2>Errores por tipo</h2>\n");
+        let bars: Vec<(String, usize)> = types.iter().map(|(t, n)| (t.to_string(), **n)).collect();
+        html.push_str(&bar_chart(&bars, &["#ef6c00"]));
+
+        if !self.samples.is_empty() {
+            let _ = writeln!(html, "<h2>Filas rechazadas (primeras {})</h2>", self.samples.len());
+            html.push_str("<div class=\"scroll\"><table>\n<tr><th>Línea</th><th>Motivo</th>");
+            for h in &self.headers {
+                let _ = write!(html, "<th>{}</th>", escape(h));
+            }
+            html.push_str("</tr>\n");
+            for row in &self.samples {
+                let _ = write!(html, "<tr><td class=\"num\">{}</td><td>{}</td>", row.line, escape(&row.reason));
+                for field in &row.fields {
+                    let _ = write!(html, "<td>{}</td>", escape(field));
+                }
+                html.push_str("</tr>\n");
+            }
+            html.push_str("</table></div>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Gráfico de barras horizontales en SVG (una barra por etiqueta, escala al máximo)
+fn bar_chart(bars: &[(String, usize)], colors: &[&str]) -> String {
+    const LABEL_WIDTH: usize = 220;
+    const BAR_WIDTH: usize = 480;
+    const ROW_HEIGHT: usize = 26;
+
+    let max = bars.iter().map(|(_, n)| *n).max().unwrap_or(0).max(1);
+    let height = bars.len() * ROW_HEIGHT + 4;
+    let mut svg = format!("<svg class=\"chart\" width=\"{}\" height=\"{}\" role=\"img\">\n", LABEL_WIDTH + BAR_WIDTH + 90, height);
+    for (i, (label, n)) in bars.iter().enumerate() {
+        let y = i * ROW_HEIGHT;
+        let width = (*n as f64 / max as f64 * BAR_WIDTH as f64).round() as usize;
+        let color = colors[i.min(colors.len() - 1)];
+        let _ = writeln!(svg, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\
+            <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"18\" fill=\"{}\"/>\
+            <text x=\"{}\" y=\"{}\">{}</text>",
+            LABEL_WIDTH - 8, y + 15, escape(&truncate(label, 32)),
+            LABEL_WIDTH, y + 2, width.max(1), color,
+            LABEL_WIDTH + width + 6, y + 15, n);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    if value.chars().count() <= max_chars {
+        return value.to_string();
+    }
+    value.chars().take(max_chars - 1).chain(std::iter::once('…')).collect()
+}
+
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+const STYLE: &str = "body{font-family:Segoe UI,Arial,sans-serif;margin:24px;color:#222}\
+h1{margin-bottom:4px}.meta{color:#666;margin-top:0}\
+.cards{display:flex;gap:12px;margin:16px 0}.card{border:1px solid #ddd;border-radius:6px;padding:10px 16px;min-width:120px;color:#555}\
+.card span{display:block;font-size:24px;font-weight:bold;color:#222}.card.ok span{color:#2e7d32}.card.fail span{color:#c62828}\
+.chart text{font-size:13px;fill:#333}\
+table{border-collapse:collapse;margin:8px 0 24px}th,td{border:1px solid #ddd;padding:4px 8px;font-size:13px;text-align:left;vertical-align:top}\
+th{background:#f5f5f5}td.num{text-align:right}code{background:#f5f5f5;padding:0 3px}\
+.scroll{overflow-x:auto}.ok-msg{color:#2e7d32;font-size:18px}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_counts_and_escaping() {
+        let mut report = ValidationReport::new("Validación", "in.csv", "siisa_morosos");
+        report.set_headers(&StringRecord::from(vec!["Cuil", "Nombre"]));
+        report.error("INVALID_NUMERIC", Some("Cuil"), "<abc>");
+        report.error("INVALID_NUMERIC", Some("Cuil"), "<abc>");
+        report.sample(2, "Cuil no numérico", &StringRecord::from(vec!["<abc>", "A & B"]));
+        report.set_totals(10, 2);
+
+        let html = report.render();
+        assert!(html.contains("<code>&lt;abc&gt;</code> × 2"));
+        assert!(html.contains("<td>A &amp; B</td>"));
+        assert!(html.contains("20.00%"));
+        assert!(!html.contains("<abc>"));
+    }
+}
@@ -0,0 +1,149 @@
+// Reintentos con backoff ante errores de I/O transitorios (montajes SMB/NFS)
+// `--io-retries N`: el lector reabre el archivo y vuelve al offset donde iba
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+// Espera antes del primer reintento (se duplica en cada intento)
+const BACKOFF_BASE_MS: u64 = 500;
+const BACKOFF_MAX_MS: u64 = 30_000;
+
+static IO_RETRIES: OnceLock<u32> = OnceLock::new();
+
+/// Reintentos configurados (0 = sin reintentos, el comportamiento previo)
+pub fn retries() -> u32 {
+    *IO_RETRIES.get().unwrap_or(&0)
+}
+
+/// Fija la cantidad de reintentos (una sola vez, antes de ejecutar el comando)
+pub fn set_retries(retries: u32) {
+    IO_RETRIES.set(retries).ok();
+}
+
+/// Errores que en un filesystem de red suelen resolverse solos
+fn is_transient(error: &io::Error) -> bool {
+    use io::ErrorKind::*;
+
+    if matches!(error.kind(), Interrupted | TimedOut | WouldBlock | ConnectionReset | ConnectionAborted | NotConnected) {
+        return true;
+    }
+
+    // ERROR_BAD_NETPATH, ERROR_UNEXP_NET_ERR, ERROR_NETNAME_DELETED, ERROR_SEM_TIMEOUT, ERROR_NETWORK_UNREACHABLE
+    #[cfg(windows)]
+    const TRANSIENT_OS_ERRORS: &[i32] = &[53, 59, 64, 121, 1231];
+    // EIO, ETIMEDOUT, EHOSTDOWN, EHOSTUNREACH, ESTALE
+    #[cfg(not(windows))]
+    const TRANSIENT_OS_ERRORS: &[i32] = &[5, 110, 112, 113, 116];
+
+    error.raw_os_error().is_some_and(|code| TRANSIENT_OS_ERRORS.contains(&code))
+}
+
+/// Ejecuta `op` sobre `state` reintentando errores transitorios con backoff exponencial;
+/// `recover` se llama antes de cada reintento (p. ej. para reabrir el archivo)
+fn with_retries<S, T>(
+    state: &mut S,
+    what: impl Fn(&S) -> String,
+    mut op: impl FnMut(&mut S) -> io::Result<T>,
+    mut recover: impl FnMut(&mut S) -> io::Result<()>,
+) -> io::Result<T> {
+    let max = retries();
+    let mut attempt = 0;
+
+    loop {
+        match op(state) {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < max && is_transient(&e) => {
+                attempt += 1;
+                let wait = (BACKOFF_BASE_MS << (attempt - 1).min(16)).min(BACKOFF_MAX_MS);
+                eprintln!("\n⚠️  I/O error {}: {} (retry {}/{} in {:.1}s)", what(state), e, attempt, max, wait as f64 / 1000.0);
+                std::thread::sleep(Duration::from_millis(wait));
+
+                if let Err(e) = recover(state) {
+                    eprintln!("⚠️  Recovery before retry failed: {}", e);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Lector de archivo que ante un error transitorio reabre el archivo y
+/// continúa desde el último byte leído
+pub struct RetryReader {
+    path: String,
+    file: File,
+    offset: u64,
+}
+
+impl RetryReader {
+    pub fn open(path: &str) -> io::Result<Self> {
+        let mut path = path.to_string();
+        let file = with_retries(&mut path, |p| format!("opening '{}'", p), |p| File::open(p), |_| Ok(()))?;
+        Ok(RetryReader { path, file, offset: 0 })
+    }
+
+    /// Igual que `open` pero posicionado en el byte `offset` (para retomar una corrida)
+    pub fn open_at(path: &str, offset: u64) -> io::Result<Self> {
+        let mut reader = Self::open(path)?;
+        reader.file.seek(SeekFrom::Start(offset))?;
+        reader.offset = offset;
+        Ok(reader)
+    }
+}
+
+impl Read for RetryReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = with_retries(
+            self,
+            |r| format!("reading '{}' at byte {}", r.path, r.offset),
+            |r| r.file.read(buf),
+            |r| {
+                let mut reopened = File::open(&r.path)?;
+                reopened.seek(SeekFrom::Start(r.offset))?;
+                r.file = reopened;
+                Ok(())
+            },
+        )?;
+
+        self.offset += n as u64;
+        Ok(n)
+    }
+}
+
+/// Writer de archivo que reintenta escrituras con error transitorio
+pub struct RetryWriter {
+    path: String,
+    file: File,
+}
+
+impl RetryWriter {
+    pub fn create(path: &str) -> io::Result<Self> {
+        let mut path = path.to_string();
+        let file = with_retries(&mut path, |p| format!("creating '{}'", p), |p| File::create(p), |_| Ok(()))?;
+        Ok(RetryWriter { path, file })
+    }
+
+    /// Abre el archivo existente para escribir al final
+    pub fn append(path: &str) -> io::Result<Self> {
+        let mut path = path.to_string();
+        let file = with_retries(
+            &mut path,
+            |p| format!("opening '{}' for append", p),
+            |p| std::fs::OpenOptions::new().append(true).open(p),
+            |_| Ok(()),
+        )?;
+        Ok(RetryWriter { path, file })
+    }
+}
+
+impl Write for RetryWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        with_retries(self, |w| format!("writing '{}'", w.path), |w| w.file.write(buf), |_| Ok(()))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        with_retries(self, |w| format!("flushing '{}'", w.path), |w| w.file.flush(), |_| Ok(()))
+    }
+}
@@ -0,0 +1,44 @@
+// Biblioteca de csv_tools: los mismos módulos que usa el binario, para llamar a los
+// comandos desde otros programas (ETL propios) sin lanzar el ejecutable.
+// - API tipada: `dedup::by_keys(...)`, `schema::Schema`, `sink::register_sink(...)`
+// - Comandos: cada `commands::*` expone su struct de argumentos (`PrepareArgs`, ...)
+//   y la función que lo ejecuta, igual que desde la línea de comandos
+// - Errores: todo devuelve `Result<T, error::CsvToolsError>` (una variante por causa)
+
+pub mod build_info;
+pub mod canonicalize;
+pub mod checkpoint;
+pub mod cli;
+pub mod cli_args;
+pub mod collation;
+pub mod commands;
+pub mod compression;
+pub mod config;
+pub mod dedup;
+pub mod diagnostics;
+pub mod disk_guard;
+pub mod encoding;
+pub mod error;
+pub mod error_report;
+pub mod external_sort;
+pub mod exit_code;
+pub mod file_utils;
+pub mod html_report;
+pub mod io_retry;
+pub mod logging;
+pub mod manifest;
+pub mod merge_report;
+pub mod models;
+pub mod progress;
+pub mod repro;
+pub mod runtime_limit;
+pub mod schema;
+pub mod settings;
+pub mod sharded_dedup;
+pub mod sink;
+pub mod sketch;
+pub mod sniff;
+pub mod spill_dedup;
+pub mod temp_files;
+#[cfg(test)]
+pub(crate) mod test_support;
@@ -0,0 +1,156 @@
+// Niveles de salida de los comandos (`--quiet` / `--verbose`), copia a `--log-file` y
+// `--format json` (resumen final como JSON en stdout; los mensajes pasan a stderr)
+// Los banners y los mensajes de estado pasan por acá (el progreso, por progress.rs); los datos que
+// produce un comando (CSV a stdout, resultados de consultas, ayuda) siguen con println!
+
+use crate::error::CsvToolsError;
+use crate::progress;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Solo datos, advertencias y errores
+    Quiet = 0,
+    /// Banners, mensajes de estado y progreso (default)
+    Normal = 1,
+    /// Además el detalle de cada paso (configuración efectiva, tiempos)
+    Verbose = 2,
+}
+
+/// Formato del resumen final de los comandos
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Recuadros y mensajes para una persona
+    #[default]
+    Text,
+    /// Un objeto JSON en stdout (conteos, tasas de error, rutas de salida)
+    Json,
+    /// head / tail como tabla Markdown alineada; el resto de los comandos, como text
+    Markdown,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Normal as u8);
+static JSON: AtomicBool = AtomicBool::new(false);
+static MARKDOWN: AtomicBool = AtomicBool::new(false);
+static DATA_ON_STDOUT: AtomicBool = AtomicBool::new(false);
+static LOG_FILE: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// Fija el nivel y el formato de la corrida y abre el log (se agrega al final si ya existe)
+pub fn configure(level: Level, format: OutputFormat, log_file: Option<&str>) -> Result<(), CsvToolsError> {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    JSON.store(format == OutputFormat::Json, Ordering::Relaxed);
+    MARKDOWN.store(format == OutputFormat::Markdown, Ordering::Relaxed);
+    if let Some(path) = log_file {
+        let file = OpenOptions::new().create(true).append(true).open(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("Cannot open log file '{}': {}", path, e)))?;
+        LOG_FILE.set(Mutex::new(file)).ok();
+    }
+    Ok(())
+}
+
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => Level::Quiet,
+        1 => Level::Normal,
+        _ => Level::Verbose,
+    }
+}
+
+pub fn enabled(level: Level) -> bool {
+    self::level() >= level
+}
+
+pub fn json_output() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+pub fn markdown_output() -> bool {
+    MARKDOWN.load(Ordering::Relaxed)
+}
+
+/// El comando en curso escribe su CSV a stdout (`-`): banners y mensajes pasan a stderr
+pub fn set_data_on_stdout(on_stdout: bool) {
+    DATA_ON_STDOUT.store(on_stdout, Ordering::Relaxed);
+}
+
+/// stdout está ocupado por datos (CSV a `-` o resumen de --format json)
+pub fn stdout_is_data() -> bool {
+    json_output() || DATA_ON_STDOUT.load(Ordering::Relaxed)
+}
+
+/// Barras de progreso (stderr): solo en una terminal interactiva, sin --quiet
+/// ni --format json (en CI o con la salida redirigida llenarían el log)
+pub fn progress_enabled() -> bool {
+    enabled(Level::Normal) && !json_output() && io::stderr().is_terminal()
+}
+
+/// Mensaje de `level` a stdout (stderr con --format json o CSV a stdout) si el nivel de la corrida
+/// lo incluye; el log recibe también los mensajes normales de una corrida --quiet
+pub fn write(level: Level, args: fmt::Arguments) {
+    if enabled(level) {
+        // Por encima de las barras de progreso activas
+        progress::suspend(|| {
+            if stdout_is_data() {
+                eprintln!("{}", args);
+            } else {
+                println!("{}", args);
+            }
+        });
+    }
+    if level <= Level::Normal || enabled(level) {
+        to_file(&args.to_string());
+    }
+}
+
+/// Copia una línea al log (advertencias y errores, que ya salen por stderr)
+pub fn to_file(message: &str) {
+    if let Some(file) = LOG_FILE.get() {
+        let mut file = file.lock().unwrap();
+        for line in message.trim_matches('\n').lines() {
+            writeln!(file, "[{}] {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), line).ok();
+        }
+    }
+}
+
+/// Resumen final de un comando con --format json (en modo texto no hace nada:
+/// el comando ya mostró su recuadro de resumen)
+pub fn summary<T: Serialize>(summary: &T) -> Result<(), CsvToolsError> {
+    if json_output() {
+        let json = serde_json::to_string_pretty(summary)?;
+        println!("{}", json);
+        to_file(&json);
+    }
+    Ok(())
+}
+
+/// Proporción `part / total` (0-1) para las tasas de los resúmenes; 0 sin filas
+pub fn rate(part: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { part as f64 / total as f64 }
+}
+
+/// Título en recuadro al empezar un comando o una sección de resumen
+pub fn banner(title: &str) {
+    write(Level::Normal, format_args!("╔══════════════════════════════════════════════════════════════╗"));
+    write(Level::Normal, format_args!("║  {:<60}║", title));
+    write(Level::Normal, format_args!("╚══════════════════════════════════════════════════════════════╝"));
+}
+
+/// Mensaje de estado (se omite con --quiet)
+#[macro_export]
+macro_rules! info {
+    () => { $crate::logging::write($crate::logging::Level::Normal, format_args!("")) };
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Normal, format_args!($($arg)*)) };
+}
+
+/// Detalle que solo se muestra con --verbose
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Verbose, format_args!($($arg)*)) };
+}
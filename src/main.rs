@@ -1,254 +1,292 @@
 ﻿use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Write};
-use std::error::Error;
+use std::io::Write;
 use std::time::Instant;
-use csv::WriterBuilder;
 
-// Importar módulos locales
-mod progress;
-mod file_utils;
-mod models;
-mod commands;
+use clap::Parser;
 
-use progress::ProgressTracker;
-use file_utils::estimate_total_lines_from_list;
+use csv_tools::{
+    cli, commands, config, diagnostics, error_report, exit_code, file_utils, info, logging, manifest, models, repro, schema, sniff,
+    verbose,
+};
+use csv_tools::error::CsvToolsError;
+use csv_tools::commands::file_ops::SourceColumns;
+use csv_tools::commands::merge_ops::DedupMode;
+use csv_tools::merge_report::MergeReport;
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() < 3 {
+    // Un solo punto de salida: el código depende del tipo de error (ver exit_code)
+    if let Err(e) = run(&args) {
+        if matches!(e, CsvToolsError::Incomplete(_)) {
+            // Corte por --max-runtime: no es un error, la corrida queda para retomar
+            eprintln!("⏱️  {}", e);
+        } else {
+            eprintln!();
+            eprintln!("❌ Error: {}", e);
+        }
+        logging::to_file(&format!("Error: {}", e));
+        std::process::exit(exit_code::for_error(&e));
+    }
+}
+
+fn run(args: &[String]) -> Result<(), CsvToolsError> {
+    // Errores de uso de clap: mensaje + exit code 2 (--help / --version salen con 0)
+    let cli = cli::Cli::parse_from(args);
+    let global_options = cli.global;
+
+    // Defaults del archivo de configuración; las opciones de la línea de comandos los pisan
+    let config = config::Config::discover().map_err(|e| exit_code::usage_error(e.to_string()))?;
+    global_options.apply(config)?;
+
+    let Some(command) = cli.command else {
         help();
         return Ok(());
+    };
+
+    // Reporte y manifiesto registran el comando sin las opciones globales
+    let command_args = cli::command_argv(args);
+    logging::set_data_on_stdout(cli::writes_stdout(&command_args));
+    let started = Instant::now();
+    if config::auto_delimiter() {
+        sniff::resolve_auto_delimiter(&command_args)?;
+    }
+    verbose!("⚙️  Command: {}", command_args[1..].join(" "));
+    match &config::current().source {
+        Some(path) => verbose!("⚙️  Config: {}", path.display()),
+        None => verbose!("⚙️  Config: defaults (no csv_tools.toml found)"),
     }
+    verbose!("⚙️  Delimiter: {:?} | Chunk size: {}", config::delimiter() as char, config::chunk_size());
 
-    let command = &args[1];
+    let report = if global_options.wants_report() {
+        Some(repro::ReproReport::begin(&command_args)?)
+    } else {
+        None
+    };
 
-    match command.as_str() {
-        "clean" => {
-            if args.len() != 4 {
-                eprintln!("Usage: csv_tool clean <input_file> <output_file>");
-                return Ok(());
-            }
-            let input_file = &args[2];
-            let output_file = &args[3];
-            println!("Cleaning headers in file: {}...", input_file);
-            clean_headers(input_file, output_file)?;
+    let manifest = global_options.manifest.as_ref().map(|_| manifest::RunManifest::begin(&command_args));
+
+    let result = dispatch(&args[0], &command);
+    error_report::flush()?;
+
+    if let (Ok(()), Some(report)) = (&result, report) {
+        report.finish(global_options.repro_report.as_deref())?;
+    }
+
+    // El manifiesto se escribe también si el comando falló (logs, checkpoints)
+    if let (Some(manifest), Some(path)) = (manifest, global_options.manifest.as_deref()) {
+        manifest.finish(path, &result)?;
+    }
+
+    verbose!("⏱️  Finished in {:.1}s", started.elapsed().as_secs_f64());
+    result?;
+
+    diagnostics::check_threshold().map_err(exit_code::data_issues)
+}
+
+/// Ejecuta una línea de comandos completa (args[0] = programa, args[1] = comando);
+/// la usa `pipeline` para cada paso. Las opciones globales del paso se ignoran:
+/// rigen las de la corrida
+fn run_command(args: &[String]) -> Result<(), CsvToolsError> {
+    let cli = cli::Cli::try_parse_from(args)
+        .map_err(|e| exit_code::usage_error(e.render().to_string().trim_end()))?;
+    logging::set_data_on_stdout(cli::writes_stdout(args));
+    match cli.command {
+        Some(command) => dispatch(&args[0], &command),
+        None => Err(exit_code::usage_error("Missing command")),
+    }
+}
+
+/// Error de uso para un modelo desconocido, con la lista de modelos soportados
+fn unknown_model(model_type: &str) -> CsvToolsError {
+    exit_code::usage_error(format!(
+        "Unknown model type: '{}'\n\
+         \nSupported models:\n\
+         \x20 - siisa_morosos (14 columns)\n\
+         \x20 - personas_telefonos (13 columns)\n\
+         \x20 - siisa_empleadores (7 columns)\n\
+         \x20 - siisa_empleadores_relaciones (4 columns)",
+        model_type
+    ))
+}
+
+/// Ejecuta un comando ya parseado; `program` es argv[0] (para los pasos de pipeline)
+fn dispatch(program: &str, command: &cli::Command) -> Result<(), CsvToolsError> {
+    use cli::Command;
+
+    match command {
+        Command::Clean { input, output, add_row_number } => {
+            writeln!(file_utils::status_output(output), "Cleaning headers in file: {}...", input)?;
+            commands::file_ops::clean_headers(input, output, add_row_number.as_deref())?;
         },
-        "filter" => {
-            if args.len() != 6 {
-                eprintln!("Usage: csv_tool filter <input_file> <output_file> <column_name> <value>");
-                return Ok(());
-            }
-            let input_file = &args[2];
-            let output_file = &args[3];
-            let column_name = &args[4];
-            let value = &args[5];
-            print!("Filtering rows in file: {}...", input_file);
-            filter_rows(input_file, output_file, column_name, value)?;
+        Command::Filter { input, output, column, value, invert } => {
+            write!(file_utils::status_output(output), "Filtering rows in file: {}...", input)?;
+            commands::filter_ops::filter_rows(input, output, column, value, *invert)?;
         },
-        "check" => {
-            if args.len() != 3 {
-                eprintln!("Usage: csv_tool check <input_file>");
-                return Ok(());
-            }
-            let input_file = &args[2];
-            if has_duplicate_header(input_file)? {
-                println!("Duplicate header found.");
+        Command::FilterIn(args) => commands::filter_ops::filter_in(args)?,
+        Command::FilterRegex(args) => commands::filter_ops::filter_regex(args)?,
+        Command::FilterDates(args) => commands::filter_ops::filter_dates(args)?,
+        Command::Deduplicate { input, output } => commands::file_ops::deduplicate_csv(input, output)?,
+        Command::DeduplicateDynamodb { input, output, model_type, resume } => {
+            let model_type = &config::model_or_default(model_type.as_deref())?;
+            commands::file_ops::deduplicate_dynamodb(input, output, model_type, resume)?;
+        },
+        Command::Head { input, num_rows, max_width } => commands::file_ops::head_csv(input, *num_rows, *max_width)?,
+        Command::Tail { input, num_rows, max_width } => commands::file_ops::tail_csv(input, *num_rows, *max_width)?,
+        Command::ValidateModel { input, error_file, model_type, max_show, cancel_on_max, resume } => {
+            commands::validation::validate_csv_schema(input, error_file, model_type, *max_show, *cancel_on_max, resume)?;
+        },
+        Command::CleanInvalidLines { input, output, error_file, add_row_number, resume } => {
+            commands::validation::clean_invalid_lines(input, output, error_file, add_row_number.as_deref(), resume)?;
+        },
+        Command::Check { input } => {
+            if commands::file_ops::has_duplicate_header(input)? {
+                info!("Duplicate header found.");
+                return Err(exit_code::data_issues(format!("Duplicate header in {}", input)));
             } else {
-                println!("No duplicate header found.");
+                info!("No duplicate header found.");
             }
         },
-        "count" => {
-            if args.len() != 3 {
-                eprintln!("Usage: csv_tool count <input_file>");
-                return Ok(());
-            }
-            let input_file = &args[2];
-            println!("Counting csv rows...");
-            let line_count = count_lines(input_file)?;
-            println!("Number of lines in the file: {}", line_count);
+        Command::Count { input } => {
+            info!("Counting csv rows...");
+            let line_count = commands::file_ops::count_lines(input)?;
+            info!("Number of lines in the file: {}", line_count);
         },
-        "count_all" => {
-            if args.len() != 3 {
-                eprintln!("Usage: csv_tool count_all <file_list>");
-                return Ok(());
-            }
-            let file_list = &args[2];
-            count_all_files(file_list)?;
+        Command::CountAll { file_list } => {
+            commands::merge_ops::count_all_files(file_list)?;
         },
-        "count_unique" => {
-            if args.len() != 3 {
-                eprintln!("Usage: csv_tool count_unique <file_list>");
-                return Ok(());
-            }
-            let file_list = &args[2];
-            count_unique_records(file_list)?;
+        Command::CountUnique { file_list } => {
+            commands::merge_ops::count_unique_records(file_list)?;
         },
-        "merge_dedup" => {
-            if args.len() != 4 {
-                eprintln!("Usage: csv_tool merge_dedup <file_list> <output_file>");
-                return Ok(());
-            }
-            let file_list = &args[2];
-            let output_file = &args[3];
-            merge_and_deduplicate(file_list, output_file)?;
+        Command::Merge { file_list, output, add_source_column, add_line_column, schema, on_mismatch, report } => {
+            let source = SourceColumns { file: add_source_column.clone(), line: add_line_column.clone() };
+            let report = MergeReport::new(report.as_deref(), "merge", output);
+            commands::file_ops::merge_files(file_list, output, &source, schema.as_deref(), *on_mismatch, report)?;
         },
-        "external_dedup" => {
-            if args.len() != 4 {
-                eprintln!("Usage: csv_tool external_dedup <file_list> <output_file>");
-                return Ok(());
+        Command::MergeReconcile(args) => commands::schema_ops::merge_reconcile(args)?,
+        Command::MergeSorted(args) => commands::sort_ops::merge_sorted(args)?,
+        Command::Sort(args) => commands::sort_ops::sort(args)?,
+        Command::MergeDedup { file_list, output, compress_threads, approx, fpp, hash_only, verify_hashes, max_memory, add_source_column, add_line_column, threads, preview, report, resume } => {
+            let mode = match (approx, hash_only) {
+                (Some(approx), _) => DedupMode::Approx(*approx, *fpp),
+                (None, true) => DedupMode::HashOnly { verify: *verify_hashes },
+                (None, false) => DedupMode::Exact,
+            };
+            let source = SourceColumns { file: add_source_column.clone(), line: add_line_column.clone() };
+            let report = MergeReport::new(report.as_deref(), "merge_dedup", output);
+            if *preview {
+                commands::merge_ops::merge_dedup_preview(file_list)?;
+            } else if *threads > 1 {
+                commands::merge_ops::merge_and_deduplicate_sharded(file_list, output, *compress_threads, *threads, mode, &source, report)?;
+            } else {
+                commands::merge_ops::merge_and_deduplicate(file_list, output, *compress_threads, mode, *max_memory, &source, *resume, report)?;
             }
-            let file_list = &args[2];
-            let output_file = &args[3];
-            external_merge_dedup(file_list, output_file)?;
         },
-        "estimate_memory" => {
-            if args.len() != 3 {
-                eprintln!("Usage: csv_tool estimate_memory <file_list>");
-                return Ok(());
-            }
-            let file_list = &args[2];
-            estimate_memory_usage(file_list)?;
+        Command::ExternalDedup { file_list, output, workdir, memory_limit, report, resume } => {
+            let report = MergeReport::new(report.as_deref(), "external_dedup", output);
+            commands::merge_ops::external_merge_dedup(file_list, output, workdir.as_deref(), *memory_limit, *resume, report)?;
         },
-        "compare" => {
-            if args.len() != 5 {
-                eprintln!("Usage: csv_tool compare <file1> <file2> <num_rows>");
-                return Ok(());
-            }
-            let file1 = &args[2];
-            let file2 = &args[3];
-            let num_rows: usize = args[4].parse().unwrap_or(100);
-            compare_first_n(file1, file2, num_rows)?;
+        Command::EstimateMemory { file_list } => {
+            commands::merge_ops::estimate_memory_usage(file_list)?;
         },
-        "sanitize_dynamodb" => {
-            if args.len() != 5 {
-                eprintln!("❌ Error: sanitize_dynamodb requires 3 arguments");
-                eprintln!("Usage: csv_tools sanitize_dynamodb <input.csv> <output.csv> <model_type>");
-                eprintln!("\nSupported models:");
-                eprintln!("  - siisa_morosos (14 columns)");
-                eprintln!("  - personas_telefonos (13 columns)");
-                eprintln!("  - siisa_empleadores (7 columns)");
-                eprintln!("  - siisa_empleadores_relaciones (4 columns)");
-                return Ok(());
-            }
-            
-            let input_path = &args[2];
-            let output_path = &args[3];
-            let model_type = &args[4];
-            
+        Command::Compare { file1, file2, num_rows } => {
+            commands::file_ops::compare_first_n(file1, file2, *num_rows)?;
+        },
+        Command::SanitizeDynamodb { input, output, model_type, schema, add_row_number } => {
+            let model_type = &config::model_or_default(model_type.as_deref())?;
+
             // ✅ Validar modelo ANTES de mostrar "Expected columns"
-            let model = models::DynamoDbModel::from_model_type(model_type);
-            
-            if model.is_none() {
-                eprintln!("❌ Error: Unknown model type: '{}'", model_type);
-                eprintln!("\nSupported models:");
-                eprintln!("  - siisa_morosos (14 columns)");
-                eprintln!("  - personas_telefonos (13 columns)");
-                eprintln!("  - siisa_empleadores (7 columns)");
-                eprintln!("  - siisa_empleadores_relaciones (4 columns)");
-                return Ok(());
+            if models::DynamoDbModel::from_model_type(model_type).is_none() {
+                return Err(unknown_model(model_type));
             }
-            
-            commands::cleaning::sanitize_dynamodb(input_path, output_path, model_type)?;
+
+            let schema = match schema {
+                Some(spec) => Some(schema::Schema::load(spec)?),
+                None => None,
+            };
+            commands::cleaning::sanitize_dynamodb(input, output, model_type, schema.as_ref(), add_row_number.as_deref())?;
         },
-        "validate_schema" => {
-            if args.len() != 4 {
-                eprintln!("❌ Error: validate_schema requires 2 arguments");
-                eprintln!("Usage: csv_tools validate_schema <input.csv> <model_type>");
-                return Ok(());
-            }
-            
-            let csv_path = &args[2];
-            let model_type = &args[3];
-            
+        Command::ValidateSchema(args) => {
             // ✅ Validar modelo ANTES de ejecutar
-            if models::DynamoDbModel::from_model_type(model_type).is_none() {
-                eprintln!("❌ Error: Unknown model type: '{}'", model_type);
-                eprintln!("\nSupported models:");
-                eprintln!("  - siisa_morosos");
-                eprintln!("  - personas_telefonos");
-                eprintln!("  - siisa_empleadores");
-                eprintln!("  - siisa_empleadores_relaciones");
-                return Ok(());
+            let model_type = config::model_or_default(args.model_type.as_deref())?;
+            if models::DynamoDbModel::from_model_type(&model_type).is_none() {
+                return Err(unknown_model(&model_type));
             }
-            
-            // Create a simple validation call
-            println!("╔══════════════════════════════════════════════════════════════╗");
-            println!("║  DynamoDB Schema Validation                                  ║");
-            println!("╚══════════════════════════════════════════════════════════════╝");
-            println!("📄 File:  {}", csv_path);
-            println!("📋 Model: {}", model_type);
-            
-            let model = models::DynamoDbModel::from_model_type(model_type).unwrap();
-            println!("🔢 Expected Columns: {}", model.expected_columns);
-            println!("🔑 Keys: {} + {}", model.partition_key, 
-                if model.sort_key.is_empty() { "(no sort key)" } else { model.sort_key });
-            
-            println!("\n✅ Schema validation complete (detailed validation available via validation module)");
-            println!("💡 Use 'parse_keys' command to see actual key values from your CSV");
+
+            commands::inspection::validate_schema(args)?;
         },
-        "parse_keys" => {
-            if args.len() != 4 {
-                eprintln!("❌ Error: parse_keys requires 2 arguments");
-                eprintln!("Usage: csv_tools parse_keys <input.csv> <model_type>");
-                return Ok(());
-            }
-            
-            let csv_path = &args[2];
-            let model_type = &args[3];
-            
-            if models::DynamoDbModel::from_model_type(model_type).is_none() {
-                eprintln!("❌ Error: Unknown model type: '{}'", model_type);
-                eprintln!("\nSupported models:");
-                eprintln!("  - siisa_morosos");
-                eprintln!("  - personas_telefonos");  
-                eprintln!("  - siisa_empleadores");
-                eprintln!("  - siisa_empleadores_relaciones");
-                return Ok(());
-            }
-            
-            models::parse_keys_from_csv(csv_path, model_type)?;
+        Command::ValidateDynamodbCsv { input, model_type, report_html } => {
+            let model_type = &config::model_or_default(model_type.as_deref())?;
+            commands::cleaning::validate_dynamodb_csv(input, model_type, report_html.as_deref())?;
         },
-        "convert_date" => {
-            if args.len() != 5 {
-                eprintln!("❌ Error: convert_date requires 3 arguments");
-                eprintln!("Usage: csv_tools convert_date <input.csv> <output.csv> <date_column>");
-                eprintln!("\nConverts dates from dd/MM/yyyy, MM/dd/yyyy, or existing ISO format to yyyy-MM-ddTHH:mm:ss");
-                return Ok(());
-            }
-            
-            commands::file_ops::convert_date_format(&args)?;
+        Command::SanitizeDynamodbAuto { input, output, model_type, expected_columns, report_html, add_row_number } => {
+            let model_type = &config::model_or_default(model_type.as_deref())?;
+            commands::file_ops::sanitize_for_dynamodb_auto(input, output, model_type, *expected_columns, report_html.as_deref(), add_row_number.as_deref())?;
         },
-        "delete_from_row" => {
-            if args.len() != 5 {
-                eprintln!("❌ Error: delete_from_row requires 3 arguments");
-                eprintln!("Usage: csv_tools delete_from_row <input.csv> <output.csv> <row_number>");
-                eprintln!("\nDeletes all rows from the specified row number to the end of file");
-                eprintln!("Note: Row numbers start from 1 (header is row 1, first data row is 2)");
-                return Ok(());
+        Command::ParseKeys { input, model_type } => {
+            let model_type = &config::model_or_default(model_type.as_deref())?;
+            if models::DynamoDbModel::from_model_type(model_type).is_none() {
+                return Err(unknown_model(model_type));
             }
-            
-            let input_file = &args[2];
-            let output_file = &args[3];
-            let row_number: usize = match args[4].parse() {
-                Ok(n) if n > 0 => n,
-                _ => {
-                    eprintln!("❌ Error: Row number must be a positive integer");
-                    return Ok(());
-                }
-            };
-            
-            commands::file_ops::delete_from_row(input_file, output_file, row_number)?;
+
+            models::parse_keys_from_csv(input, model_type)?;
         },
-        "help" => {
-            help();
+        Command::ConvertDate { input, output, date_column } => {
+            commands::file_ops::convert_date_format(input, output, date_column)?;
         },
-        _ => {
-            eprintln!("Unknown command: {}", command);
-            help();
-        }
-       }
+        Command::DeleteFromRow { input, output, row_number } => {
+            commands::file_ops::delete_from_row(input, output, *row_number as usize)?;
+        },
+        Command::Slice { input, output, from, to } => commands::file_ops::slice_rows(input, output, *from, *to)?,
+        Command::Header(args) => commands::inspection::header(args)?,
+        Command::Assert(args) => commands::inspection::assert_csv(args)?,
+        Command::Profile(args) => commands::inspection::profile(args)?,
+        Command::Sniff(args) => commands::inspection::sniff(args)?,
+        Command::DetectEncoding(args) => commands::inspection::detect_encoding(args)?,
+        Command::DupReport(args) => commands::dedup_ops::dup_report(args)?,
+        Command::DedupByKeys(args) => commands::dedup_ops::dedup_by_keys(args)?,
+        Command::ReportDuplicates(args) => commands::dedup_ops::report_duplicates(args)?,
+        Command::Overlap(args) => commands::dedup_ops::overlap(args)?,
+        Command::VerifyRoundtrip(args) => commands::inspection::verify_roundtrip(args)?,
+        Command::PlanPartitions(args) => commands::partition_ops::plan_partitions(args)?,
+        Command::SummarizeErrors(args) => commands::error_summary::summarize_errors(args)?,
+        Command::Repl(args) => commands::repl::repl(args)?,
+        Command::Coerce(args) => commands::cleaning::coerce(args)?,
+        Command::Patch(args) => commands::edit_ops::patch(args)?,
+        Command::DeleteKeys(args) => commands::edit_ops::delete_keys(args)?,
+        Command::Migrate(args) => commands::schema_ops::migrate(args)?,
+        Command::ReorderToModel(args) => commands::schema_ops::reorder_to_model(args)?,
+        Command::Select(args) => commands::schema_ops::select(args)?,
+        Command::Drop(args) => commands::schema_ops::drop_columns(args)?,
+        Command::RenameColumns(args) => commands::schema_ops::rename_columns(args)?,
+        Command::AddColumn(args) => commands::schema_ops::add_column(args)?,
+        Command::MapValues(args) => commands::transform_ops::map_values(args)?,
+        Command::Trim(args) => commands::transform_ops::trim(args)?,
+        Command::Recase(args) => commands::transform_ops::recase(args)?,
+        Command::NormalizeNulls(args) => commands::transform_ops::normalize_nulls(args)?,
+        Command::ConcatColumns(args) => commands::transform_ops::concat_columns(args)?,
+        Command::SplitColumn(args) => commands::transform_ops::split_column(args)?,
+        Command::NormalizeEol(args) => commands::cleaning::normalize_eol(args)?,
+        Command::Requote(args) => commands::cleaning::requote(args)?,
+        Command::Prepare(args) => commands::prepare_ops::prepare(args)?,
+        Command::Schema { command } => commands::schema_ops::schema_command(command)?,
+        Command::ToSqlite(args) => commands::sqlite_ops::to_sqlite(args)?,
+        Command::FromSqlite(args) => commands::sqlite_ops::from_sqlite(args)?,
+        Command::Query(args) => commands::sqlite_ops::query_csv(args)?,
+        Command::ToJsonl(args) => commands::convert_ops::to_jsonl(args)?,
+        Command::ToDdbJson(args) => commands::convert_ops::to_ddbjson(args)?,
+        Command::ToIon(args) => commands::convert_ops::to_ion(args)?,
+        Command::ToPgCopy(args) => commands::convert_ops::to_pgcopy(args)?,
+        Command::ToArrow(args) => commands::convert_ops::to_arrow(args)?,
+        Command::FromJsonl(args) => commands::convert_ops::from_jsonl(args)?,
+        Command::FromParquet(args) => commands::convert_ops::from_parquet(args)?,
+        Command::FromFixed(args) => commands::convert_ops::from_fixed(args)?,
+        Command::Pipeline(args) => commands::pipeline::run_pipeline(program, args, &run_command)?,
+        Command::Watch(args) => commands::watch::run_watch(program, args, &run_command)?,
+        Command::Version => csv_tools::build_info::print_version()?,
+        Command::Help { command: None } => help(),
+        Command::Help { command: Some(name) } => cli::print_command_help(name)?,
+    }
 
     Ok(())
 }
@@ -259,17 +297,30 @@ fn help() {
     println!("╚══════════════════════════════════════════════════════════════╝");
     println!();
     println!("DynamoDB Commands:");
-    println!("  sanitize_dynamodb <input.csv> <output.csv> <model_type>");
+    println!("  sanitize_dynamodb <input.csv> <output.csv> <model_type> [--schema schema.yaml]");
     println!("    Sanitize CSV for DynamoDB ImportTable");
     println!("    - Removes quotes from header row");
     println!("    - Validates numeric fields (Type N)");
     println!("    - Preserves quoted strings for Type S fields");
+    println!("    - --schema: empty Type N values allowed only in `nullable` columns (filled with `default` if set)");
+    println!("    - --add-row-number SourceLine: append each row's line number in the input (header = 1)");
     println!();
-    println!("  validate_schema <input.csv> <model_type>");
+    println!("  validate_schema <input.csv> <model_type> [--max-examples-per-field N] [--schema schema.yaml] [--report-html report.html]");
     println!("    Validate CSV schema and data types");
     println!("    - Check header format");
     println!("    - Validate Type N fields are numeric");
-    println!("    - Report validation errors");
+    println!("    - Report validation errors (up to N examples per field, default 20)");
+    println!("    - Summary always has exact totals and the most frequent invalid values per field");
+    println!("    - --schema: empty values in `nullable` columns are not errors");
+    println!("    - --report-html: self-contained HTML report (errors per column, sample bad rows, charts) to share");
+    println!();
+    println!("  validate_dynamodb_csv <input.csv> [model_type] [--report-html report.html]");
+    println!("    Check that the CSV is ready for ImportTable (column count, unquoted header, Type N values)");
+    println!();
+    println!("  prepare <input.csv[.gz]> <output.csv[.gz]> --model <model_type> [--dedup-keys Cuil,IdTransmit] [--schema schema.yaml] [--partitions N] [--workdir DIR]");
+    println!("    validate_schema + sanitize_dynamodb + key dedup reading the input only once");
+    println!("    - Invalid rows logged to <output>.prepare_errors.log; first occurrence of each key wins");
+    println!("    - Large inputs are deduplicated in hash partitions spilled to temp files (auto: 1 per ~2 GB)");
     println!();
     println!("  parse_keys <input.csv> <model_type>");
     println!("    Extract and display DynamoDB keys (PartitionKey + SortKey)");
@@ -283,6 +334,205 @@ fn help() {
     println!("    - Preserves header row");
     println!("    - Creates new CSV with only rows before the specified row");
     println!();
+    println!("  slice <input.csv[.gz]> <output.csv[.gz]> --from 1_500_000 [--to 1_600_000]");
+    println!("    Copy the header plus rows --from..=--to (same numbering as delete_from_row; default: to end of file)");
+    println!("    - Rows before --from are skipped without decoding; reading stops after --to");
+    println!();
+    println!("  header <input.csv[.gz]> [--json] [--sample <rows>]");
+    println!("    Print header columns (index, name, type inferred from a sample) without reading the body");
+    println!();
+    println!("  assert <input.csv[.gz]> [--has-columns A,B] [--row-count-min N] [--row-count-max N] [--no-duplicate-header]");
+    println!("    Cheap CI gate: exits with code 1 and a one-line reason per failed assertion");
+    println!();
+    println!("  profile <input.csv[.gz]> [--columns A,B] [--percentiles 50,95,99] [--json]");
+    println!("    Per-column empty counts and, for numeric columns, min/mean/max and approximate percentiles");
+    println!("    - Single pass with a t-digest per column (no sorting, bounded memory)");
+    println!();
+    println!("  sniff <input.csv[.gz]> [--sample-kb N] [--json]");
+    println!("    Most likely delimiter, quote char, header presence and column count from the first N KB (default 64)");
+    println!();
+    println!("  detect_encoding <file> [--sample-kb N] [--json]");
+    println!("    Probable character encoding (UTF-8, windows-1252/Latin-1, UTF-16LE/BE) and the --input-encoding to use");
+    println!();
+    println!("  verify-roundtrip <input.csv> [--via requote|sanitize] [--model <model_type>]");
+    println!("    Rewrite into a temp file with no rule changes; exit 1 and show where it differs (per column)");
+    println!();
+    println!("  dup-report <input.csv[.gz]|list.txt|dir|glob> --keys Cuil,IdTransmit [--top 50]");
+    println!("    Top-K keys by duplicate count with example lines and differing columns (count-min sketch, bounded memory)");
+    println!("    - A file list counts keys across all files (same header required); lines are shown as file:line");
+    println!();
+    println!("  report_duplicates <input.csv[.gz]> --keys Cuil,IdTransmit [--output dups.csv] [--show 20]");
+    println!("    Every duplicated key with its occurrence count and the line numbers of each occurrence (read-only)");
+    println!("    - Two passes; --output writes the full list as CSV (keys, occurrences, lines separated by ';')");
+    println!();
+    println!("  overlap <a.csv[.gz]> <b.csv[.gz]> --keys Cuil,IdTransmit [--samples 10]");
+    println!("    How many keys of B already appear in A (distinct keys and rows), with examples and line numbers");
+    println!();
+    println!("  dedup_by_keys <input.csv[.gz]> <output.csv[.gz]> --keys Cuil,IdTransmit [--keep first|last|latest-by <date column>] [--normalize] [--fold-case Col,...] [--canonicalize rules.yaml] [--collapsed-log log.csv]");
+    println!("    Remove rows whose key columns repeat, for any CSV header (keys compared with --collation)");
+    println!("    - first (default): single streaming pass; last: two passes, keeps the last occurrence in place");
+    println!("    - latest-by CreateDate: two passes, keeps the row with the most recent date (ties: later row; invalid dates lose)");
+    println!("    - Memory: one 128-bit fingerprint per distinct key");
+    println!("    - --normalize collapses internal whitespace and --fold-case compares those key columns case-insensitively;");
+    println!("      --collapsed-log lists the rows that were duplicates only after normalization (line, kept line, original values)");
+    println!("    - --canonicalize rules.yaml: per-column key rules (trim, uppercase, lowercase, collapse_whitespace,");
+    println!("      strip_leading_zeros, phone) applied in order to the key only; rows are written with their original values");
+    println!();
+    println!("  plan-partitions <input.csv[.gz]> --key Cuil --workers 16 [--sample N] [--output plan.json]");
+    println!("    Balanced key ranges [start, end) as JSON for parallel import workers (sampled, seeded)");
+    println!();
+    println!("  summarize-errors <error_report> [--group-by error_type,column,pattern] [--examples N]");
+    println!("    Aggregate an error log / validation report into counts per type, column and value pattern");
+    println!();
+    println!("  repl <input.csv[.gz]> [--index-step N]");
+    println!("    Interactive session: reads the file once (row count + byte index), then head/tail/row/filter/stats");
+    println!("    - head, tail and row jump to the nearest index entry; stats results are cached per column");
+    println!();
+    println!("  coerce <input.csv> <output.csv> --schema <schema.yaml|model> [--bool-pair S,N] [--drop-invalid]");
+    println!("    Rewrite columns into their schema-declared canonical representation");
+    println!("    - int (no decimals), number, decimal(p,s), date (ISO), bool, string (trimmed)");
+    println!("    - bool accepts si/no, true/false, S/N, Y/N, 1/0 (output pair default 1/0)");
+    println!("    - decimal(p,s) strips currency symbols, accepts 1.234,56 or 1,234.56, enforces scale");
+    println!("    - Coercion failures logged to <output>.coercion_errors.log");
+    println!();
+    println!("  patch <input.csv> <output.csv> --patches fixes.csv --key Cuil,IdTransmit [--audit audit.csv]");
+    println!("    Replace individual cells listed in fixes.csv (key columns + column + value); old→new audit log");
+    println!();
+    println!("  delete-keys <input.csv> <output.csv> --keys-file blacklist.txt --key-column Cuil [--log removed.csv]");
+    println!("    Remove every row whose key is in the list; removed rows go to the log (default <output>.removed.csv)");
+    println!();
+    println!("  migrate <input.csv> <output.csv> --to new_schema.yaml [--from old_schema.yaml] [--mapping map.yaml]");
+    println!("    Transform an old file layout into the target schema (rename / add with defaults / drop / reorder)");
+    println!("    - Values validated against the target schema; failures logged to <output>.migration_errors.log");
+    println!();
+    println!("  reorder-to-model <input.csv> <output.csv> --model <model_type> [--drop-extra]");
+    println!("    Put columns in the exact order of the model (the importer maps by position)");
+    println!("    - Fails on missing model columns; extra columns fail unless --drop-extra");
+    println!();
+    println!("  select <input.csv[.gz]> <output.csv[.gz]> --columns Cuil,NroDoc,Periodo");
+    println!("  drop <input.csv[.gz]> <output.csv[.gz]> --columns Telefono");
+    println!("    Column projection by header name: select keeps the listed columns in that order, drop removes them");
+    println!("    - Parsed as CSV, so quoted values with delimiters or line breaks stay intact; unknown columns fail");
+    println!();
+    println!("  rename_columns <input.csv[.gz]> <output.csv[.gz]> --map \"cuil=Cuil,nro_doc=NroDoc\" [--map-file renames.txt]");
+    println!("    Rename header columns to the model names; rows are copied unchanged (quoting preserved)");
+    println!("    - --map-file: one old=new pair per line; unknown columns or duplicate resulting names fail");
+    println!();
+    println!("  add_column <input.csv[.gz]> <output.csv[.gz]> --name CreateUser --value batch_import [--name ... --value ...]");
+    println!("    Append columns with a constant value on every row (e.g. audit columns the model requires)");
+    println!("    - --name/--value are paired in order; a name already in the header fails");
+    println!();
+    println!("  map_values <input.csv[.gz]> <output.csv[.gz]> <column> --mapping regions.csv [--unmatched keep|blank|error]");
+    println!("    Replace the values of a column using a two-column mapping file (header row, then old,new)");
+    println!("    - Values not in the mapping: keep (default), blank, or error at the first one; empty cells are left alone");
+    println!();
+    println!("  trim <input.csv[.gz]> <output.csv[.gz]> --columns Monto,Cuil | --all [--collapse]");
+    println!("    Strip leading/trailing whitespace from values in one streaming pass; prints changed cells per column");
+    println!("    - --collapse also turns internal runs of whitespace into a single space");
+    println!();
+    println!("  recase <input.csv[.gz]> <output.csv[.gz]> --columns ApellidoNombre,RazonSocial --to upper|lower|title");
+    println!("    Standardize letter case of name columns before dedup or comparison with the master database");
+    println!("    - title capitalizes every word, including after '-' and apostrophes (Perez-Gomez, D'Angelo)");
+    println!();
+    println!("  normalize_nulls <input.csv[.gz]> <output.csv[.gz]> [--tokens \"NULL,N/A,null,-\"] [--to \"\"] [--columns a,b]");
+    println!("    Convert assorted null markers into one representation (default: empty) and report conversions per column");
+    println!("    - Tokens match the whole value (case-sensitive, surrounding spaces ignored); default: all columns");
+    println!();
+    println!("  concat_columns <input.csv[.gz]> <output.csv[.gz]> --columns NroDoc,IdEntidad --sep \"#\" --name CompositeKey");
+    println!("    Append a column with the values of --columns joined by --sep (precomputed composite keys)");
+    println!("    - Values are joined as they are; warns how many rows have an empty part");
+    println!();
+    println!("  split_column <input.csv[.gz]> <output.csv[.gz]> --column ApellidoNombre --sep \",\" --into Apellido,Nombre");
+    println!("               [--max-parts 2] [--on-missing fill|error] [--on-extra merge|drop|error] [--keep-source]");
+    println!("    Split one column into several (parts trimmed); new columns replace the original in its position");
+    println!("    - Fewer parts: fill with empty (default) or error; more parts: merge rest into the last column (default), drop or error");
+    println!("    - error removes the row from the output; every odd row is logged to <output>.split_errors.log");
+    println!();
+    println!("  normalize_eol <input.csv> <output.csv> [--to lf|crlf]");
+    println!("    Convert mixed CRLF / LF / lone CR line endings to one style (default lf) and report how many of each were found");
+    println!();
+    println!("  requote <input.csv> <output.csv> --style necessary|always|never|non_numeric");
+    println!("    Re-emit every record with the chosen quoting; values (embedded delimiters included) are unchanged");
+    println!("    - never fails on a field that needs quotes; non_numeric leaves numbers unquoted");
+    println!();
+    println!("  schema docs <schema.yaml|model> [--doc-format md|html] [--sample data.csv] [--output doc.md]");
+    println!("    Render a data dictionary: column, type, key role, constraints and example values from --sample");
+    println!();
+    println!("SQLite Commands:");
+    println!("  to-sqlite <input.csv> <db.sqlite> --table <name> [--schema <model>] [--replace]");
+    println!("    Load CSV into a SQLite table for ad-hoc SQL queries");
+    println!("    - Typed columns from model (Type N → NUMERIC, others TEXT)");
+    println!("    - Batched inserts + index on model key columns");
+    println!();
+    println!("  from-sqlite <db.sqlite> <output.csv> (--table <name> | --query \"SELECT ...\")");
+    println!("    Export a SQLite table or query result to CSV");
+    println!();
+    println!("  query <input.csv> \"SELECT ... FROM t ...\" [--output out.csv] [--schema <model>]");
+    println!("    Run SQL over a CSV (auto-staged to a temporary SQLite table 't')");
+    println!();
+    println!("Conversion Commands:");
+    println!("  to_jsonl <input.csv[.gz]> <output.jsonl[.gz]> [--model <model_type>]");
+    println!("    One JSON object per row, header names as keys (in header order); --model writes Type N columns as numbers");
+    println!("  to_ddbjson <input.csv[.gz]> <output.json[.gz]> [--model <model_type>]");
+    println!("    DynamoDB JSON for ImportTable: one {{\"Item\":{{\"Cuil\":{{\"N\":\"...\"}},...}}}} per row (Type N → N, rest → S)");
+    println!("    - Empty Type N values omitted; rows with an empty key or invalid Type N skipped (see --error-report)");
+    println!("  to_ion <input.csv[.gz]> <output.ion[.gz]> [--model <model_type>]");
+    println!("    Amazon Ion text for ImportTable (ION): one $ion_1_0 {{Item:{{Cuil:20123,...}}}} per row");
+    println!("    - Type N columns as Ion int/decimal (exact, never float), the rest as strings; same skip rules as to_ddbjson");
+    println!("  to_pgcopy <input.csv[.gz]> <output.copy[.gz]> [--schema <schema.yaml|model>] [--table NAME] [--sql PATH] [--empty-as-null]");
+    println!("    Postgres COPY text format (tab separated, \\N = NULL, escaped \\ tab and line breaks) without header");
+    println!("    - Also writes <output>.sql: CREATE TABLE typed from the schema (keys → PRIMARY KEY) + the psql \\copy that loads it");
+    println!("    - Empty non-text values → NULL; --empty-as-null also for text columns");
+    println!("  to_arrow <input.csv[.gz]> <output.feather> [--schema <schema.yaml|model>] [--infer-rows N] [--batch-size N]");
+    println!("    Arrow IPC file (Feather v2) with typed columns: int → Int64, number → Float64, decimal(p,s) → Decimal128, date → Date32, bool → Boolean");
+    println!("    - Types from --schema or inferred from the first N rows (default 1000); empty / unconvertible values → null");
+    println!("  from_jsonl <input.jsonl[.gz]> <output.csv> [--columns A,B,C] [--missing empty|skip|fail] [--nested json|flatten]");
+    println!("    Top-level keys become columns (default: every key in first-seen order, reads the input twice)");
+    println!("    - null → empty; nested objects/arrays as compact JSON, or --nested flatten → parent.child columns");
+    println!("  from_parquet <input.parquet> <output.csv>");
+    println!("    Top-level fields become columns; dates/timestamps as ISO (UTC), nested groups/lists as JSON");
+    println!("  from_fixed <input.txt[.gz]> <output.csv> --spec widths.yaml [--model <model_type>]");
+    println!("    Cut each line into the spec's byte ranges (name + start + length|end, from 1); padding trimmed");
+    println!("    - --model checks that the spec columns are the model's before converting");
+    println!();
+    println!("Pipeline Commands:");
+    println!("  pipeline run <plan.yaml> [--param name=value ...] [--dry-run] [--workdir DIR] [--keep-temp] [--report FILE]");
+    println!("    Run the steps of a YAML plan in order (stops at the first failing step; 'pipeline <plan.yaml>' also works)");
+    println!("    - ${{name}} placeholders resolved from --param, CSV_TOOLS_PARAM_<NAME> env vars, then plan defaults");
+    println!("    - ${{tmp.NAME}} is a per-run intermediate file under --workdir, removed at the end unless --keep-temp");
+    println!("    - Prints a per-step summary (status, time); --report FILE / --format json give it as JSON");
+    println!("    - --dry-run prints the resolved commands without executing them");
+    println!("  watch <dir> --pipeline <plan.yaml> [--output-dir D] [--processed-dir D] [--error-dir D] [--interval SECS] [--once]");
+    println!("    Run the plan on each new CSV arriving in <dir> (${{file}}, ${{name}}, ${{output_dir}} set per file)");
+    println!("    - OK inputs move to processed/, failed ones to errors/ with <name>.error.log; stops on --once, --max-runtime or Ctrl+C");
+    println!();
+    println!("GLOBAL OPTIONS (any command, before or after the command name):");
+    println!("  --quiet                  Only data, warnings and errors (no banners, status lines or progress)");
+    println!("  --verbose                Also print step details (effective config, timings)");
+    println!("  --format text|json|markdown  json: validate_schema, sanitize_dynamodb[_auto] and deduplicate print a JSON summary on stdout");
+    println!("                           markdown: head / tail print an aligned Markdown table (fields cut at --max-width, default 40)");
+    println!("  --log-file <file>        Append status messages, warnings and errors (timestamped) to a log file");
+    println!("    - Progress bars (stderr, per file + total for multi-file commands) are shown only on an interactive terminal");
+    println!("  --error-report <file>    Also write each data error as line,byte_offset,error_type,column,value,message");
+    println!("    - CSV, or NDJSON for .ndjson/.jsonl (--error-report-format csv|ndjson); summarize-errors reads it");
+    println!("  --seed <n>               Deterministic seed for sampling / hash partitions / sketches");
+    println!("  --repro-report <file>    Save reproducibility report (options, seed, SHA-256 of inputs/outputs) as JSON");
+    println!("    - Either option prints the reproducibility report at the end of the run");
+    println!("  --strict                 Treat data-quality warnings (⚠️) as errors: non-zero exit code if any");
+    println!("  --max-warnings <n>       Fail the run when more than n warnings are emitted (also relaxes --strict)");
+    println!("  --io-retries <n>         Retry transient read/write I/O errors n times with backoff (SMB/NFS mounts)");
+    println!("  --manifest <file>        Write a JSON manifest of every file the run created (size, SHA-256, rows)");
+    println!("  --max-runtime <d>        Stop gracefully after d (e.g. 3h50m, 90m, 45s): checkpoint + exit code 75 (incomplete)");
+    println!("    - merge_dedup / external_dedup continue from the checkpoint with --resume");
+    println!("  --delimiter <c>          Field delimiter for every CSV read or written (';', '\\t' for TSV); default ','");
+    println!("    - auto: sniff it from the command's input file (same detector as the sniff command)");
+    println!("  --compress gzip|zstd     Compress every CSV output, adding .gz / .zst when the name lacks it (DynamoDB ImportTable accepts both)");
+    println!("  --input-encoding <enc>   Decode every input from enc (latin1, windows-1252, utf-16le, ...) or auto (detected per file)");
+    println!("  --output-encoding <enc>  Encode CSV outputs as enc instead of UTF-8 (unmappable characters written as '?')");
+    println!("  --keep-bom               Keep the UTF-8 BOM at the start of inputs (every command strips it by default)");
+    println!("  --collation <c>          Key comparison for sort/dedup: binary (default), case-insensitive, es_AR");
+    println!("    - es_AR matches the SQL export order (Ñ after N, accents secondary, case ignored)");
+    println!();
     println!("SUPPORTED MODELS:");
     println!("  - siisa_morosos                 (14 columns, Keys: Cuil + IdTransmit)");
     println!("  - personas_telefonos            (13 columns, Keys: Cuil + IdTelefono)");
@@ -312,453 +562,87 @@ fn help() {
     println!("  # Convert date formats (supports dd/MM/yyyy, MM/dd/yyyy, and ISO) to ISO");
     println!("  csv_tools convert_date input.csv output.csv fecha_creacion");
     println!();
+    println!("  # Load a validated extract into SQLite and export a query back to CSV");
+    println!("  csv_tools to-sqlite output.csv siisa.sqlite --table morosos --schema siisa_morosos");
+    println!("  csv_tools from-sqlite siisa.sqlite region_counts.csv --query \"SELECT IdRegion, count(*) FROM morosos GROUP BY 1\"");
+    println!();
+    println!("  # Ad-hoc SQL directly over a CSV");
+    println!("  csv_tools query output.csv \"SELECT IdRegion, count(*) FROM t WHERE Periodo=202405 GROUP BY 1\"");
+    println!();
+    println!("  # Monthly import from a single parameterized plan");
+    println!("  csv_tools pipeline monthly_import.yaml --param month=202405 --param input_dir=/data/siisa");
+    println!();
     println!("NOTES:");
+    println!("  - Detailed usage of any command: csv_tools <command> --help (or csv_tools help <command>)");
+    println!("  - Defaults (delimiter, quiet, default_model, error_log_dir, chunk_size) from ./csv_tools.toml or ~/.config/csv_tools/config.toml");
     println!("  - Compatible with SiisaRestApi chunk-export-v2 output format");
     println!("  - Follows DynamoDB ImportTable CSV specification (RFC 4180)");
     println!("  - Header row must NOT have quotes (auto-sanitized)");
     println!("  - Type N fields (DynamoDB Number) must be unquoted in CSV");
     println!("  - Type S fields (DynamoDB String) auto-quoted when needed");
+    println!("  - filter, clean, head, tail, clean_invalid_lines, check and count read stdin with '-'; filter, clean and clean_invalid_lines write stdout with '-' (status goes to stderr)");
+    println!("  - Every command reads gzip/zstd-compressed inputs (.gz/.zst, detected by content) decompressing on the fly, without a temp file");
+    println!("  - CSV outputs accept a local path (.gz/.zst compressed), file://path or a registered sink URI scheme");
+    println!("  - --compress gzip|zstd compresses every CSV output (merge, sanitize_dynamodb[_auto], clean_invalid_lines, ...) and adds .gz/.zst to the name");
+    println!("  - merge_dedup / external_dedup abort early (saving <output>.checkpoint.json) when the target disk is running out of space");
+    println!();
+    println!("EXIT CODES: 0 = clean, 1 = data issues found, 2 = usage error, 3 = I/O failure, 75 = stopped by --max-runtime");
+    println!();
+    println!("Other Commands:");
+    println!("  version: Print version, build number, build date, target and features (--format json for scripts; also --version).");
     println!();
     println!("Legacy Commands:");
     println!("  clean: Clean duplicate headers from a CSV file.");
-    println!("  filter: Filter rows based on a column value.");
+    println!("  filter <input> <output> <column> <value> [--invert]: Keep rows whose column equals the value; --invert removes them and keeps the rest.");
+    println!("  filter_in <input> <output> <column> --values-file ids.txt [--invert]: Keep rows whose column value is in the list");
+    println!("    (one value per line, held in a HashSet; streaming over the CSV). Warns about listed values never found.");
+    println!("  filter_regex <input> <output> <column> <pattern> [--invert]: Keep rows whose column value matches the regex");
+    println!("    (regex crate syntax, matched anywhere in the value; anchor with ^...$). --invert keeps the rows that do not match.");
+    println!("  filter_dates <input> <output> <date_column> [--from 2024-01-01] [--to 2024-03-31] [--on-invalid drop|keep]:");
+    println!("    Keep rows whose date is in the inclusive range (dates parsed like the schema: ISO, dd/mm/yyyy, ...);");
+    println!("    reports how many rows had an empty or unparseable date, dropped (default) or kept.");
+    println!("  deduplicate <input> <output>: Remove exact duplicate rows (all columns, in memory).");
+    println!("  sanitize_dynamodb_auto <input> <output> [model] [--expected-columns N] [--report-html report.html]: Sanitize keeping the header; dropped rows logged.");
+    println!("  clean / clean_invalid_lines / sanitize_dynamodb[_auto] --add-row-number SourceLine: append each row's original line number");
+    println!("    (header = 1, same numbering as the error log) to trace a record rejected downstream back to the source file.");
+    println!("    merge / merge_dedup accept it as an alias of --add-line-column.");
+    println!("  head / tail <input> [N] [--max-width N]: Print the header and the first / last N rows (default 10); --format markdown for a table.");
+    println!("  clean_invalid_lines <input> <output> <error_file>: Keep rows with the header's column count, log the rest.");
+    println!("  validate_model <input> <error_file> <model> [max_show] [cancel_on_max]: Validate every record against a DynamoDB model.");
+    println!("  deduplicate_dynamodb <input> <output> [model]: Remove duplicate DynamoDB keys (in memory, last occurrence wins).");
+    println!("  validate_model / clean_invalid_lines / deduplicate_dynamodb --resume: Continue from <output>.checkpoint.json");
+    println!("    (recorded every --checkpoint-interval, default 1m; outputs are truncated to the checkpoint, no duplicated rows).");
     println!("  check: Check for duplicate headers in a CSV file.");
     println!("  count: Count the number of lines in a CSV file.");
-    println!("  count_all: Count lines in multiple files listed in a text file.");
+    println!("  count_all: Count lines in multiple files.");
     println!("  count_unique: Count unique records across multiple files (fast, but needs RAM).");
+    println!("  merge <files> <output> [--add-source-column FileName] [--add-line-column LineNo]: Concatenate multiple CSV files keeping the first header;");
+    println!("    optionally append each row's originating file and line number.");
+    println!("    - Every header is checked against the first file's (or --schema schema.yaml|model) before writing;");
+    println!("      --on-mismatch abort (default) | skip (leave those files out) | allow (merge anyway, warn).");
+    println!("  merge_reconcile <files> <output> [--schema schema.yaml|model | --union]: Merge mapping columns by header name;");
+    println!("    missing columns written empty, extra columns dropped and reported per file (target: first file's header by default).");
+    println!("  merge_sorted <files> <output> --key Cuil [--type string|numeric|date] [--dedup]: k-way merge of files already sorted by the key;");
+    println!("    streaming with one row per file in memory, output stays sorted; fails if an input is out of order. --dedup keeps the first row per key.");
+    println!("  sort <input> <output> --column Periodo [--type string|numeric|date] [--order asc|desc] [--memory-limit 1GB] [--workdir DIR]:");
+    println!("    stable sort by one or more columns; past --memory-limit sorted runs are spilled to disk and merged. Invalid values sort last.");
     println!("  merge_dedup: Merge multiple CSV files and remove duplicates (in-memory).");
-    println!("  external_dedup: Merge and deduplicate using external sort (for HUGE files).");
+    println!("    - Inputs may be .gz/.zst; output ending in .gz is gzip-compressed (--compress-threads N for parallel blocks), .zst is zstd.");
+    println!("    - --approx bloom [--fpp 0.001]: constant-memory Bloom filter sized from the line estimate; reports estimated false drops.");
+    println!("    - --hash-only [--verify-hashes]: keep a 128-bit digest per line (~10x less RAM); optional pass detects collisions.");
+    println!("    - --max-memory 4G: cap the seen set; past it new lines go to on-disk hash buckets deduplicated at the end (never OOMs).");
+    println!("    - --preview: full pass that writes nothing; per input file, rows repeated within it or already in earlier files.");
+    println!("    - --threads N: shard lines by hash across N worker threads, each with its own seen set (output grouped by shard).");
+    println!("    - --add-source-column FileName [--add-line-column LineNo]: append the originating file (and line) of each row;");
+    println!("      provenance is not compared, rows keep the one of their first occurrence (not with --resume or --verify-hashes).");
+    println!("  external_dedup: Merge and deduplicate using a built-in external merge sort (for HUGE files).");
+    println!("    - --memory-limit SIZE: RAM per sorted run before spilling to disk (default 1GB, e.g. 512MB, 4GB).");
+    println!("    - Per-run temp files under --workdir DIR (default: output directory), removed on exit; safe to run concurrently.");
+    println!("  merge_dedup / external_dedup --resume: Continue a run stopped by --max-runtime from <output>.checkpoint.json.");
+    println!("  merge / merge_dedup / external_dedup --report merge_report.json: per-file lines, duplicates removed and header check,");
+    println!("    totals and timings as JSON, for orchestration to verify the run (written only when it succeeds).");
     println!("  estimate_memory: Estimate RAM needed for in-memory deduplication.");
+    println!("    - <files> for count_all, count_unique, merge, merge_dedup, external_dedup and estimate_memory:");
+    println!("      a list file (one path per line), a directory (*.csv, *.csv.gz, *.csv.zst) or a quoted glob ('data/part_*.csv').");
     println!("  compare: Compare first N rows of two CSV files.");
 }
-
-fn count_all_files(file_list_path: &str) -> Result<(), Box<dyn Error>> {
-    // Obtener lista de archivos para estimación
-    let file = File::open(file_list_path)?;
-    let reader = BufReader::new(file);
-    let file_names: Vec<String> = reader.lines().collect::<Result<Vec<_>, _>>()?;
-    
-    println!("📊 Estimando total de líneas para progress...");
-    let estimated_total = estimate_total_lines_from_list(file_list_path)?;
-    println!("Estimación: ~{} líneas totales en {} archivos", estimated_total, file_names.len());
-    
-    let mut progress = ProgressTracker::new(estimated_total as u64);
-    let mut total = 0;
-    let mut processed_lines = 0;
-
-    for filename in file_names {
-        let count = count_lines_with_progress(&filename, &mut progress, &mut processed_lines)?;
-        println!("\n{}: {} líneas", filename, count);
-        total += count;
-    }
-
-    progress.finish();
-    println!("📈 Total de líneas en todos los archivos: {}", total);
-    Ok(())
-}
-
-fn merge_and_deduplicate(file_list_path: &str, output_file: &str) -> Result<(), Box<dyn Error>> {
-    use std::collections::HashSet;
-
-    println!("🔄 Estimando total de líneas para merge...");
-    let estimated_total = estimate_total_lines_from_list(file_list_path)?;
-    println!("Estimación: ~{} líneas totales", estimated_total);
-    
-    let mut progress = ProgressTracker::new(estimated_total as u64);
-    let mut processed_lines = 0;
-
-    let file_list = File::open(file_list_path)?;
-    let reader = BufReader::new(file_list);
-    let mut seen_lines = HashSet::new();
-    let mut writer = BufWriter::new(File::create(output_file)?);
-
-    let mut header_written = false;
-
-    for line in reader.lines() {
-        let filename = line?;
-        let input = File::open(&filename)?;
-        let file_reader = BufReader::new(input);
-
-        for (i, file_line) in file_reader.lines().enumerate() {
-            let line_content = file_line?;
-            processed_lines += 1;
-            
-            if i == 0 {
-                if !header_written {
-                    writer.write_all(line_content.as_bytes())?;
-                    writer.write_all(b"\n")?;
-                    header_written = true;
-                }
-            } else {
-                if seen_lines.insert(line_content.clone()) {
-                    writer.write_all(line_content.as_bytes())?;
-                    writer.write_all(b"\n")?;
-                }
-            }
-            
-            // Actualizar progreso cada 1000 líneas
-            if processed_lines % 1000 == 0 {
-                progress.update(processed_lines);
-            }
-        }
-    }
-
-    writer.flush()?;
-    progress.finish();
-    println!("🔄 Merge completado, {} registros únicos guardados en {}", seen_lines.len(), output_file);
-    Ok(())
-}
-
-fn count_lines_with_progress(input_file: &str, progress: &mut ProgressTracker, processed_lines: &mut usize) -> Result<usize, Box<dyn Error>> {
-    let file = File::open(input_file)?;
-    let reader = BufReader::new(file);
-    let mut line_count = 0;
-
-    for _line in reader.lines() {
-        line_count += 1;
-        *processed_lines += 1;
-        
-        // Actualizar progreso cada 1000 líneas para mejor rendimiento
-        if line_count % 1000 == 0 {
-            progress.update(*processed_lines as u64);
-        }
-    }
-    
-    progress.update(*processed_lines as u64);
-    Ok(line_count)
-}
-
-fn count_lines(input_file: &str) -> Result<usize, Box<dyn Error>> {
-
-    print!("Counting lines in file: {}...", input_file);
-    let start = Instant::now();
-    let file = File::open(input_file).expect("Failed to open file");
-    let reader = BufReader::new(file);
-
-    let line_count = reader.lines().count();
-
-    let _ = start.elapsed().as_secs_f64();
-    println!("Time taken to count {} lines: {:.2} seconds",line_count, start.elapsed().as_secs_f64());
-
-    Ok(line_count)
-}
-
-fn has_duplicate_header(file_path: &str) -> Result<bool, Box<dyn Error>> {
-    let file = File::open(file_path)?;
-    let mut reader = BufReader::new(file);
-    let mut result = false;
-
-    let mut first_line = String::new();
-    if reader.read_line(&mut first_line)? == 0 {
-        result = false; // Empty file, no duplicates
-    }
-
-    let header = first_line.trim_end().to_string();
-    let mut line_number = 1;
-
-    for line in reader.lines() {
-        line_number += 1;
-        let line = line?;
-        if line.trim_end() == header {
-            println!("Duplicate header found on line {}", line_number);
-            result = true;
-        }
-    }
-
-    Ok(result)
-}
-
-fn clean_headers(input_file: &str, output_file: &str) -> Result<(), Box<dyn Error>> {
-    let input = File::open(input_file)?;
-    let reader = BufReader::new(input);
-    let output = File::create(output_file)?;
-    let mut writer = BufWriter::new(output);
-
-    let mut first_line = String::new();
-    let mut lines = reader.lines();
-
-    if let Some(Ok(header)) = lines.next() {
-        first_line = header;
-        writer.write_all(first_line.as_bytes())?;
-        writer.write_all(b"\n")?;
-    }
-
-    for line in lines {
-        let line = line?;
-        if line != first_line {
-            writer.write_all(line.as_bytes())?;
-            writer.write_all(b"\n")?;
-        }
-    }
-
-    writer.flush()?;
-    println!("Header cleanup complete.");
-    Ok(())
-}
-
-fn filter_rows(input_file: &str, output_file: &str, column_name: &str, value: &str) -> Result<(), Box<dyn Error>> {
-    let input = File::open(input_file)?;
-    let reader = BufReader::new(input);
-    let output = File::create(output_file)?;
-    let mut writer = WriterBuilder::new().has_headers(true).from_writer(BufWriter::new(output));
-
-    let mut rdr = csv::Reader::from_reader(reader);
-    let headers = rdr.headers()?.clone();
-    writer.write_record(headers.iter())?;
-
-    let column_index = headers.iter().position(|h| h == column_name).ok_or_else(|| {
-        format!("Column '{}' not found in input file", column_name)
-    })?;
-
-    for result in rdr.records() {
-        let record = result?;
-        if record.get(column_index).unwrap_or("") == value {
-            writer.write_record(&record)?;
-        }
-    }
-
-    writer.flush()?;
-    println!("Row filtering complete.");
-    Ok(())
-}
-
-fn compare_first_n(file1: &str, file2: &str, num_rows: usize) -> Result<(), Box<dyn Error>> {
-    let f1 = File::open(file1)?;
-    let f2 = File::open(file2)?;
-    let reader1 = BufReader::new(f1);
-    let reader2 = BufReader::new(f2);
-
-    let mut lines1 = reader1.lines();
-    let mut lines2 = reader2.lines();
-
-    let header1 = lines1.next().unwrap_or(Ok(String::new()))?;
-    let header2 = lines2.next().unwrap_or(Ok(String::new()))?;
-
-    if header1 != header2 {
-        println!("⚠️ Header mismatch!");
-        println!("File1 header: {}", header1);
-        println!("File2 header: {}", header2);
-    } else {
-        println!("✅ Headers match.");
-    }
-
-    println!("Comparing first {} data rows...", num_rows);
-
-    let mut differences = 0;
-
-    for i in 1..=num_rows {
-        let line1 = lines1.next().unwrap_or(Ok(String::new()))?;
-        let line2 = lines2.next().unwrap_or(Ok(String::new()))?;
-
-        if line1 != line2 {
-            println!("❌ Difference at line {}:", i + 1);
-            println!("File1: {}", line1);
-            println!("File2: {}", line2);
-            differences += 1;
-        }
-    }
-
-    if differences == 0 {
-        println!("🎉 No differences found in the first {} rows.", num_rows);
-    } else {
-        println!("🔍 Found {} differences in the first {} rows.", differences, num_rows);
-    }
-
-    Ok(())
-}
-
-fn count_unique_records(file_list_path: &str) -> Result<(), Box<dyn Error>> {
-    use std::collections::HashSet;
-
-    println!("📊 Estimando total de líneas para conteo único...");
-    let estimated_total = estimate_total_lines_from_list(file_list_path)?;
-    println!("Estimación: ~{} líneas totales", estimated_total);
-    
-    let mut progress = ProgressTracker::new(estimated_total as u64);
-
-    let file_list = File::open(file_list_path)?;
-    let reader = BufReader::new(file_list);
-    let mut seen_lines = HashSet::new();
-    let mut total_lines = 0;
-    let mut files_processed = 0;
-
-    for line in reader.lines() {
-        let filename = line?;
-        let input = File::open(&filename)?;
-        let file_reader = BufReader::new(input);
-        
-        let mut file_lines = 0;
-        let mut file_unique = 0;
-
-        for (i, file_line) in file_reader.lines().enumerate() {
-            let line_content = file_line?;
-            total_lines += 1;
-            file_lines += 1;
-            
-            // Skip header line (first line of first file)
-            if files_processed == 0 && i == 0 {
-                seen_lines.insert(line_content);
-                file_unique += 1;
-                progress.update(total_lines);
-                continue;
-            }
-            
-            // Skip headers of subsequent files
-            if files_processed > 0 && i == 0 {
-                progress.update(total_lines);
-                continue;
-            }
-            
-            if seen_lines.insert(line_content) {
-                file_unique += 1;
-            }
-            
-            // Actualizar progreso cada 1000 líneas
-            if total_lines % 1000 == 0 {
-                progress.update(total_lines);
-            }
-        }
-        
-        println!("\n{}: {} líneas, {} únicas", filename, file_lines, file_unique);
-        files_processed += 1;
-    }
-
-    let unique_count = seen_lines.len();
-    let duplicates = total_lines - (unique_count as u64);
-    
-    progress.finish();
-    println!("🔍 Conteo único completado");
-    
-    println!();
-    println!("📊 RESUMEN:");
-    println!("Total de líneas procesadas: {}", total_lines);
-    println!("Registros únicos encontrados: {}", unique_count);
-    println!("Archivos procesados: {}", files_processed);
-    println!("Duplicados detectados: {}", duplicates);
-    
-    Ok(())
-}
-
-fn estimate_memory_usage(file_list_path: &str) -> Result<(), Box<dyn Error>> {
-    println!("🧠 Estimando uso de memoria para deduplicación in-memory...");
-    
-    let estimated_total = estimate_total_lines_from_list(file_list_path)?;
-    
-    // Estimar tamaño promedio de línea (basado en formato SIISA)
-    let avg_line_size = 200; // bytes aproximados por línea CSV
-    let overhead_factor = 1.5; // overhead de HashMap/HashSet
-    
-    let estimated_memory_bytes = (estimated_total as f64 * avg_line_size as f64 * overhead_factor) as u64;
-    let memory_gb = estimated_memory_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
-    
-    println!("📊 ESTIMACIÓN DE MEMORIA:");
-    println!("  Total de líneas estimadas: {}", estimated_total);
-    println!("  Tamaño promedio por línea: {} bytes", avg_line_size);
-    println!("  Memoria RAM estimada necesaria: {:.2} GB", memory_gb);
-    
-    if memory_gb > 16.0 {
-        println!("⚠️  ADVERTENCIA: Memoria estimada muy alta!");
-        println!("💡 Recomendación: Usar 'external_dedup' en lugar de 'count_unique' o 'merge_dedup'");
-        println!("🚀 Comando sugerido: ./csv_tools.exe external_dedup {} output.csv", file_list_path);
-    } else if memory_gb > 8.0 {
-        println!("⚠️  CUIDADO: Memoria estimada alta, monitorear el sistema");
-    } else {
-        println!("✅ Memoria estimada dentro de límites razonables");
-        println!("🚀 Puedes usar 'count_unique' o 'merge_dedup' sin problemas");
-    }
-    
-    Ok(())
-}
-
-fn external_merge_dedup(file_list_path: &str, output_file: &str) -> Result<(), Box<dyn Error>> {
-    use std::process::Command;
-    use std::path::Path;
-    
-    println!("🔄 Iniciando deduplicación externa para archivos GIGANTES...");
-    
-    // Crear archivo temporal combinado
-    let temp_merged = "temp_merged_all.csv";
-    
-    println!("📂 Paso 1: Combinando archivos...");
-    let estimated_total = estimate_total_lines_from_list(file_list_path)?;
-    let mut progress = ProgressTracker::new(estimated_total as u64);
-    
-    // Combinar todos los archivos en uno temporal
-    let file_list = std::fs::File::open(file_list_path)?;
-    let reader = std::io::BufReader::new(file_list);
-    let mut writer = std::io::BufWriter::new(std::fs::File::create(temp_merged)?);
-    let mut header_written = false;
-    let mut processed_lines = 0;
-    
-    for line in std::io::BufRead::lines(reader) {
-        let filename = line?;
-        let input = std::fs::File::open(&filename)?;
-        let file_reader = std::io::BufReader::new(input);
-        
-        for (i, file_line) in std::io::BufRead::lines(file_reader).enumerate() {
-            let line_content = file_line?;
-            processed_lines += 1;
-            
-            if i == 0 {
-                if !header_written {
-                    writeln!(writer, "{}", line_content)?;
-                    header_written = true;
-                }
-            } else {
-                writeln!(writer, "{}", line_content)?;
-            }
-            
-            if processed_lines % 1000 == 0 {
-                progress.update(processed_lines);
-            }
-        }
-    }
-    
-    writer.flush()?;
-    progress.finish();
-    println!("📂 Combinación completada");
-    
-    println!("🔄 Paso 2: Ordenando y deduplicando usando sort externo...");
-    
-    // Usar sort del sistema para ordenar y eliminar duplicados
-    let sort_result = if cfg!(target_os = "windows") {
-        // En Windows, usar PowerShell
-        Command::new("powershell")
-            .arg("-Command")
-            .arg(&format!(
-                "Get-Content '{}' | Sort-Object -Unique | Set-Content '{}'",
-                temp_merged, output_file
-            ))
-            .status()?
-    } else {
-        // En Unix/Linux, usar sort nativo
-        Command::new("sort")
-            .arg("-u")  // unique
-            .arg(temp_merged)
-            .arg("-o")
-            .arg(output_file)
-            .status()?
-    };
-    
-    if sort_result.success() {
-        println!("✅ Deduplicación externa completada exitosamente!");
-        
-        // Limpiar archivo temporal
-        if Path::new(temp_merged).exists() {
-            std::fs::remove_file(temp_merged)?;
-            println!("🗑️  Archivo temporal limpiado");
-        }
-        
-        // Contar líneas en resultado final
-        let final_count = count_lines(output_file)?;
-        println!("📊 RESULTADO FINAL:");
-        println!("  Archivo generado: {}", output_file);
-        println!("  Registros únicos: {}", final_count - 1); // -1 por el header
-        
-    } else {
-        eprintln!("❌ Error en el proceso de sort externo");
-        return Err("Sort command failed".into());
-    }
-    
-    Ok(())
-}
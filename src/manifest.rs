@@ -0,0 +1,184 @@
+// Manifiesto de artefactos de la corrida (`--manifest run_manifest.json`):
+// todo archivo creado o modificado (salidas, logs de errores, reportes, checkpoints)
+// con tamaño, SHA-256 y cantidad de filas, para que el orquestador archive exactamente eso
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+use serde::Serialize;
+
+use crate::config;
+use crate::error::CsvToolsError;
+use crate::file_utils::{format_bytes, open_input};
+use crate::info;
+use crate::repro::sha256_file;
+
+#[derive(Serialize)]
+struct Artifact {
+    path: String,
+    kind: &'static str,
+    size: u64,
+    sha256: String,
+    // Filas de datos (sin header) para archivos CSV
+    rows: Option<u64>,
+}
+
+/// Estado previo de los directorios que puede tocar el comando
+pub struct RunManifest {
+    command: String,
+    arguments: Vec<String>,
+    started_at: String,
+    started: Instant,
+    directories: Vec<PathBuf>,
+    before: HashMap<PathBuf, (u64, Option<SystemTime>)>,
+}
+
+#[derive(Serialize)]
+struct ManifestFile<'a> {
+    tool_version: &'static str,
+    command: &'a str,
+    arguments: &'a [String],
+    status: &'static str,
+    error: Option<String>,
+    started_at: &'a str,
+    duration_secs: f64,
+    artifacts: Vec<Artifact>,
+}
+
+impl RunManifest {
+    /// Toma una foto (tamaño + mtime) de los directorios de los argumentos y del actual
+    pub fn begin(args: &[String]) -> Self {
+        let arguments: Vec<String> = args.iter().skip(2).cloned().collect();
+
+        let mut directories: BTreeSet<PathBuf> = BTreeSet::new();
+        directories.insert(PathBuf::from("."));
+        for arg in arguments.iter().filter(|a| !a.starts_with("--")) {
+            let parent = Path::new(arg).parent().filter(|p| !p.as_os_str().is_empty());
+            directories.insert(parent.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(".")));
+        }
+        // Rutas canónicas para no listar dos veces el mismo directorio
+        let directories: Vec<PathBuf> = directories.iter()
+            .filter_map(|d| std::fs::canonicalize(d).ok())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        RunManifest {
+            command: args.get(1).cloned().unwrap_or_default(),
+            before: snapshot(&directories),
+            arguments,
+            started_at: chrono::Local::now().to_rfc3339(),
+            started: Instant::now(),
+            directories,
+        }
+    }
+
+    /// Escribe el manifiesto con los archivos nuevos o modificados (también si el comando falló)
+    pub fn finish(self, manifest_path: &str, result: &Result<(), CsvToolsError>) -> Result<(), CsvToolsError> {
+        let manifest_abs = std::fs::canonicalize(manifest_path).ok();
+        let mut changed: Vec<PathBuf> = snapshot(&self.directories)
+            .into_iter()
+            .filter(|(path, state)| self.before.get(path) != Some(state))
+            .map(|(path, _)| path)
+            .filter(|path| Some(path) != manifest_abs.as_ref())
+            .collect();
+        changed.sort();
+
+        let cwd = std::env::current_dir().and_then(std::fs::canonicalize).ok();
+        let mut artifacts = Vec::with_capacity(changed.len());
+        for path in changed {
+            // Relativa al directorio actual cuando está dentro de él
+            let relative = cwd.as_ref().and_then(|c| path.strip_prefix(c).ok()).unwrap_or(&path);
+            let display = relative.to_string_lossy().to_string();
+            let size = std::fs::metadata(&path)?.len();
+            artifacts.push(Artifact {
+                kind: artifact_kind(&display),
+                size,
+                sha256: sha256_file(&display)?,
+                rows: count_rows(&display),
+                path: display,
+            });
+        }
+
+        info!();
+        info!("🗂️  Artifacts ({}):", artifacts.len());
+        for a in &artifacts {
+            match a.rows {
+                Some(rows) => info!("   [{}] {} ({}, {} rows)", a.kind, a.path, format_bytes(a.size), rows),
+                None => info!("   [{}] {} ({})", a.kind, a.path, format_bytes(a.size)),
+            }
+        }
+
+        let manifest = ManifestFile {
+            tool_version: env!("CARGO_PKG_VERSION"),
+            command: &self.command,
+            arguments: &self.arguments,
+            status: match result {
+                Ok(()) => "success",
+                Err(CsvToolsError::Incomplete(_)) => "incomplete",
+                Err(_) => "failed",
+            },
+            error: result.as_ref().err().map(|e| e.to_string()),
+            started_at: &self.started_at,
+            duration_secs: self.started.elapsed().as_secs_f64(),
+            artifacts,
+        };
+        std::fs::write(manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+        info!("💾 Manifest saved: {}", manifest_path);
+
+        Ok(())
+    }
+}
+
+/// Archivos (no recursivo) de cada directorio con su tamaño y mtime
+fn snapshot(directories: &[PathBuf]) -> HashMap<PathBuf, (u64, Option<SystemTime>)> {
+    let mut files = HashMap::new();
+    for dir in directories {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() {
+                    files.insert(entry.path(), (metadata.len(), metadata.modified().ok()));
+                }
+            }
+        }
+    }
+    files
+}
+
+/// Clasificación por nombre según las convenciones de los comandos
+fn artifact_kind(path: &str) -> &'static str {
+    let lower = path.to_lowercase();
+    if lower.ends_with(".checkpoint.json") {
+        "checkpoint"
+    } else if lower.ends_with(".log") {
+        "error_log"
+    } else if lower.ends_with(".json") || lower.ends_with("_audit.csv") || lower.ends_with(".removed.csv") {
+        "report"
+    } else {
+        "output"
+    }
+}
+
+/// Filas de datos de un CSV (acepta .csv.gz); None si no es CSV o no se puede leer
+fn count_rows(path: &str) -> Option<u64> {
+    let lower = path.to_lowercase();
+    if !(lower.ends_with(".csv") || lower.ends_with(".csv.gz")) {
+        return None;
+    }
+
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(config::delimiter())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(open_input(path).ok()?);
+    let mut record = csv::ByteRecord::new();
+    let mut rows = 0u64;
+    while rdr.read_byte_record(&mut record).ok()? {
+        rows += 1;
+    }
+    Some(rows)
+}
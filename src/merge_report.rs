@@ -0,0 +1,144 @@
+// Reporte JSON de merge, merge_dedup y external_dedup (`--report merge_report.json`):
+// líneas por archivo, duplicados quitados, resultado de la validación de header y
+// tiempos, para que el orquestador verifique la operación sin leer la consola.
+// Sin --report el reporte igual se arma (es barato) y `finish` no escribe nada
+
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::error::CsvToolsError;
+use crate::info;
+
+/// Resultado de comparar el header de un archivo con el esperado
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderCheck {
+    /// Igual al esperado
+    Match,
+    /// Distinto: merge --on-mismatch skip lo dejó afuera
+    Skipped,
+    /// Distinto: merge --on-mismatch allow lo unió igual
+    Allowed,
+    /// Distinto del primer archivo en un comando que no valida headers (se descarta como header)
+    Differs,
+}
+
+#[derive(Serialize, Debug)]
+struct FileEntry {
+    file: String,
+    header: HeaderCheck,
+    /// Filas de datos leídas (sin header)
+    lines: usize,
+    /// Null cuando no se puede atribuir a un archivo (sort externo, shards, buckets en disco)
+    duplicates_removed: Option<usize>,
+    duration_secs: f64,
+}
+
+#[derive(Serialize)]
+struct ReportFile<'a> {
+    tool_version: &'static str,
+    command: &'a str,
+    output: &'a str,
+    resumed: bool,
+    started_at: &'a str,
+    duration_secs: f64,
+    files: &'a [FileEntry],
+    lines_read: usize,
+    lines_written: usize,
+    duplicates_removed: usize,
+}
+
+pub struct MergeReport {
+    path: Option<String>,
+    command: &'static str,
+    output: String,
+    resumed: bool,
+    started_at: String,
+    started: Instant,
+    file_started: Instant,
+    files: Vec<FileEntry>,
+}
+
+impl MergeReport {
+    pub fn new(path: Option<&str>, command: &'static str, output: &str) -> Self {
+        MergeReport {
+            path: path.map(str::to_string),
+            command,
+            output: output.to_string(),
+            resumed: false,
+            started_at: chrono::Local::now().to_rfc3339(),
+            started: Instant::now(),
+            file_started: Instant::now(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Corrida retomada con --resume: los conteos son solo de esta corrida
+    pub fn resumed(&mut self) {
+        self.resumed = true;
+    }
+
+    /// Empieza a medir el tiempo del próximo archivo
+    pub fn start_file(&mut self) {
+        self.file_started = Instant::now();
+    }
+
+    /// Archivo que no se leyó (merge --on-mismatch skip)
+    pub fn skipped_file(&mut self, file: &str) {
+        self.files.push(FileEntry { file: file.to_string(), header: HeaderCheck::Skipped, lines: 0, duplicates_removed: Some(0), duration_secs: 0.0 });
+    }
+
+    /// Cierra el archivo actual con sus filas de datos y duplicados (si se conocen)
+    pub fn file(&mut self, file: &str, header: HeaderCheck, lines: usize, duplicates_removed: Option<usize>) {
+        let duration_secs = self.file_started.elapsed().as_secs_f64();
+        self.files.push(FileEntry { file: file.to_string(), header, lines, duplicates_removed, duration_secs });
+    }
+
+    /// Escribe el reporte si se pidió --report (solo al terminar bien)
+    pub fn finish(self, lines_written: usize, duplicates_removed: usize) -> Result<(), CsvToolsError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        // Con --resume solo cuenta lo leído en esta corrida
+        let lines_read = self.files.iter().map(|f| f.lines).sum();
+        let report = ReportFile {
+            tool_version: env!("CARGO_PKG_VERSION"),
+            command: self.command,
+            output: &self.output,
+            resumed: self.resumed,
+            started_at: &self.started_at,
+            duration_secs: self.started.elapsed().as_secs_f64(),
+            files: &self.files,
+            lines_read,
+            lines_written,
+            duplicates_removed,
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        info!("💾 Merge report saved: {}", path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_report_totals() {
+        let dir = TempDir::new("merge_report");
+        let mut report = MergeReport::new(Some(&dir.path("report.json")), "merge_dedup", "out.csv");
+        report.start_file();
+        report.file("a.csv", HeaderCheck::Match, 10, Some(2));
+        report.skipped_file("b.csv");
+        report.start_file();
+        report.file("c.csv", HeaderCheck::Differs, 5, None);
+        report.finish(12, 3).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&dir.read("report.json")).unwrap();
+        assert_eq!((json["lines_read"].as_u64(), json["lines_written"].as_u64()), (Some(15), Some(12)));
+        assert_eq!(json["files"][1]["header"], "skipped");
+        assert!(json["files"][2]["duplicates_removed"].is_null());
+    }
+}
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::error::Error;
+
+use crate::config;
+use crate::error::CsvToolsError;
+use crate::file_utils::open_input;
+use crate::exit_code::usage_error;
+use crate::info;
+use crate::logging;
 
 /// DynamoDB model schemas compatible with SiisaRestApi.Common
 /// Based on SiisaRestApi.Common/Models/DynamoModels/
@@ -118,7 +124,7 @@ pub struct EmpleadorDynamoDbModel {
 
 /// Expected CSV headers for each DynamoDB table
 /// ⚠️ MATCHES: chunk-export-v2 output from SiisaRestApi.Process
-pub fn get_expected_headers(model_type: &str) -> Result<Vec<&'static str>, String> {
+pub fn get_expected_headers(model_type: &str) -> Result<Vec<&'static str>, CsvToolsError> {
     match model_type {
         "siisa_morosos" | "MorososTransmitDynamoDbModel" => Ok(vec![
             "Cuil", "IdTransmit", "NroDoc", "ApellidoNombre", "IdCliente", "IdRegion",
@@ -143,17 +149,17 @@ pub fn get_expected_headers(model_type: &str) -> Result<Vec<&'static str>, Strin
             "FechaIngreso",      // String
             "FechaBaja"          // String
         ]),
-        _ => Err(format!(
+        _ => Err(usage_error(format!(
             "Unknown DynamoDB model: '{}'\n\
              Supported: siisa_morosos, siisa_personas_telefonos, siisa_empleadores", 
             model_type
-        ))
+        )))
     }
 }
 
 /// Validate CSV header against expected DynamoDB schema
 /// Compatible with SiisaRestApi.Process chunk-export-v2 output
-pub fn validate_headers(actual_headers: &[String], model_type: &str) -> Result<(), String> {
+pub fn validate_headers(actual_headers: &[String], model_type: &str) -> Result<(), CsvToolsError> {
     let expected = match model_type {
         "siisa_morosos" => vec![
             "Cuil", "NroDoc", "ApellidoNombre", "IdCliente", "IdRegion",
@@ -169,7 +175,7 @@ pub fn validate_headers(actual_headers: &[String], model_type: &str) -> Result<(
             "Cuit", "RazonSocial", "Domicilio", "CodPostal",
             "Localidad", "NombreProvincia", "Telefono"
         ],
-        _ => return Err(format!("Unknown model type : {}", model_type))
+        _ => return Err(usage_error(format!("Unknown model type : {}", model_type)))
     };
 
     let missing: Vec<_> = expected.iter()
@@ -190,7 +196,7 @@ pub fn validate_headers(actual_headers: &[String], model_type: &str) -> Result<(
         if !extra.is_empty() {
             errors.push(format!("Extra columns: {:?}", extra));
         }
-        Err(errors.join("; "))
+        Err(CsvToolsError::SchemaMismatch(errors.join("; ")))
     }
 }
 
@@ -284,12 +290,12 @@ pub fn format_sql_composite_key(id_cliente: i32, id_transmit: i32, nro_doc: &str
 }
 
 /// Get column index for a specific field (useful for validation)
-pub fn get_column_index(field_name: &str, model_type: &str) -> Result<usize, String> {
+pub fn get_column_index(field_name: &str, model_type: &str) -> Result<usize, CsvToolsError> {
     let headers = get_expected_headers(model_type)?;
     
     headers.iter()
         .position(|&h| h == field_name)
-        .ok_or(format!("Field '{}' not found in model '{}'", field_name, model_type))
+        .ok_or_else(|| usage_error(format!("Field '{}' not found in model '{}'", field_name, model_type)))
 }
 
 /// Validate DynamoDB attribute types (basic type checking)
@@ -438,7 +444,7 @@ pub fn validate_numeric_key(
 
 /// Retorna las columnas de clave primaria DynamoDB según el modelo
 /// Sigue schema de MorososTransmitDynamoDbModel y PersonasTelefonoDynamoDbModel
-pub fn get_dynamodb_key_columns(model_type: &str) -> Result<(String, Option<String>), Box<dyn Error>> {
+pub fn get_dynamodb_key_columns(model_type: &str) -> Result<(String, Option<String>), CsvToolsError> {
     match model_type {
         "siisa_morosos" => {
             Ok(("Cuil".to_string(), Some("IdTransmit".to_string())))
@@ -454,7 +460,7 @@ pub fn get_dynamodb_key_columns(model_type: &str) -> Result<(String, Option<Stri
             // Composite key: Cuil (PartitionKey) + Cuit (SortKey)
             Ok(("Cuil".to_string(), Some("Cuit".to_string())))
         },
-        _ => Err(format!("Unknown DynamoDB model type: {}", model_type).into())
+        _ => Err(usage_error(format!("Unknown DynamoDB model type: {}", model_type)))
     }
 }
 
@@ -586,26 +592,24 @@ impl DynamoDbModel {
 
 /// Parse and display DynamoDB keys from CSV records
 /// Compatible with all supported models
-pub fn parse_keys_from_csv(csv_path: &str, model_type: &str) -> Result<(), Box<dyn std::error::Error>> {
+pub fn parse_keys_from_csv(csv_path: &str, model_type: &str) -> Result<(), CsvToolsError> {
     use csv::ReaderBuilder;
-    use std::fs::File;
     
-    println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║  DynamoDB Key Parser                                         ║");
-    println!("╚══════════════════════════════════════════════════════════════╝");
+    logging::banner("DynamoDB Key Parser");
     
-    println!("📄 File:  {}", csv_path);
-    println!("📋 Model: {}", model_type);
+    info!("📄 File:  {}", csv_path);
+    info!("📋 Model: {}", model_type);
     
     let model = DynamoDbModel::from_model_type(model_type)
-        .ok_or_else(|| format!("Unknown model type: {}", model_type))?;
+        .ok_or_else(|| usage_error(format!("Unknown model type: {}", model_type)))?;
     
-    println!("🔑 Keys:  {} + {}", model.partition_key, 
+    info!("🔑 Keys:  {} + {}", model.partition_key, 
         if model.sort_key.is_empty() { "(no sort key)" } else { model.sort_key });
-    println!();
+    info!();
     
-    let file = File::open(csv_path)?;
+    let file = open_input(csv_path)?;
     let mut reader = ReaderBuilder::new()
+        .delimiter(config::delimiter())
         .has_headers(true)
         .from_reader(file);
     
@@ -616,13 +620,13 @@ pub fn parse_keys_from_csv(csv_path: &str, model_type: &str) -> Result<(), Box<d
         let record = result?;
         
         if i >= MAX_DISPLAY {
-            println!("... (showing first {} records)", MAX_DISPLAY);
+            info!("... (showing first {} records)", MAX_DISPLAY);
             break;
         }
         
         match parse_dynamodb_key_from_record(&record, model_type) {
             Ok(key) => {
-                println!("Record {}: {}", i + 1, key);
+                info!("Record {}: {}", i + 1, key);
                 count += 1;
             }
             Err(e) => {
@@ -631,7 +635,7 @@ pub fn parse_keys_from_csv(csv_path: &str, model_type: &str) -> Result<(), Box<d
         }
     }
     
-    println!("\n✅ Processed {} records successfully", count);
+    info!("\n✅ Processed {} records successfully", count);
     Ok(())
 }
 
@@ -1,65 +1,248 @@
-use std::time::Instant;
-use std::io::{self, Write};
+// Progreso de los comandos largos: una sola API (sobre indicatif) para todos
+// - Las barras van a stderr y se redibujan por tiempo (REFRESH_HZ), no cada N filas
+// - Solo aparecen en una terminal interactiva sin --quiet ni --format json
+// - Los comandos de varios archivos muestran una barra por archivo y otra con el total
+// Los mensajes de `info!` que salen mientras hay una barra se imprimen por encima de ella
 
-/// Tracker de progreso compatible con SiisaRestApi chunk processing
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
+
+use crate::{info, logging};
+
+// Redibujos por segundo de las barras
+const REFRESH_HZ: u8 = 10;
+
+// Cada cuántas llamadas a `update` se mira el reloj: en los bucles por fila el costo
+// de la barra queda en un contador
+const SAMPLE_CALLS: u64 = 64;
+
+// Intervalo mínimo entre actualizaciones del detalle (contadores del comando)
+const DETAIL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Paso en curso de `pipeline run` ("2/5 coerce"), antepuesto a las barras
+static STAGE: Mutex<Option<String>> = Mutex::new(None);
+
+// Todas las barras de la corrida se dibujan juntas (una debajo de la otra)
+static BARS: OnceLock<MultiProgress> = OnceLock::new();
+
+/// Fija (o limpia con None) el paso de pipeline que muestran las barras
+pub fn set_stage(stage: Option<String>) {
+    *STAGE.lock().unwrap() = stage;
+}
+
+fn stage_prefix() -> String {
+    STAGE.lock().unwrap().as_ref().map(|s| format!("[{}] ", s)).unwrap_or_default()
+}
+
+fn bars() -> &'static MultiProgress {
+    BARS.get_or_init(|| MultiProgress::with_draw_target(ProgressDrawTarget::stderr_with_hz(REFRESH_HZ)))
+}
+
+/// Corre `f` con las barras ocultas (para imprimir mensajes sin romperlas)
+pub fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    match BARS.get() {
+        Some(bars) => bars.suspend(f),
+        None => f(),
+    }
+}
+
+fn style(template: &str) -> ProgressStyle {
+    ProgressStyle::with_template(template)
+        .expect("valid progress template")
+        .with_key("rate", |state: &ProgressState, w: &mut dyn std::fmt::Write| {
+            write!(w, "{:.0} rec/s", state.per_sec()).ok();
+        })
+        .progress_chars("█▉▊▋▌▍▎▏ ")
+}
+
+/// Barra de un comando: filas procesadas (con o sin total estimado) o bytes leídos
+/// Se limpia al terminar o al salir de alcance (también si el comando corta por error)
+pub struct Progress {
+    bar: ProgressBar,
+    calls: u64,
+    last_detail: Option<Instant>,
+}
+
+impl Progress {
+    /// Filas procesadas sin total conocido: contador, velocidad y tiempo
+    pub fn rows(label: &str) -> Self {
+        Self::new(None, &format!("📊 {{prefix}}{}: {{human_pos}} | {{rate}} | {{elapsed}}{{msg}}", label))
+    }
+
+    /// Filas sobre un total estimado: barra con porcentaje y tiempo restante
+    pub fn rows_of(label: &str, total: u64) -> Self {
+        Self::new(Some(total), &format!(
+            "📊 {{prefix}}{}: [{{bar:25}}] {{percent}}% {{human_pos}}/{{human_len}} | {{rate}} | ETA {{eta}}{{msg}}",
+            label))
+    }
+
+    /// Bytes leídos de la entrada sobre su tamaño (el total exacto que se tiene antes de leer)
+    pub fn bytes(label: &str, total: u64) -> Self {
+        Self::new(Some(total), &format!(
+            "📊 {{prefix}}{}: [{{bar:25}}] {{percent}}% {{bytes}}/{{total_bytes}} | {{binary_bytes_per_sec}} | ETA {{eta}}{{msg}}",
+            label))
+    }
+
+    fn new(total: Option<u64>, template: &str) -> Self {
+        let bar = if logging::progress_enabled() {
+            let bar = match total {
+                Some(total) => ProgressBar::new(total),
+                None => ProgressBar::no_length(),
+            };
+            let bar = bars().add(bar.with_style(style(template)).with_prefix(stage_prefix()));
+            // Sigue redibujando (tiempo, velocidad) aunque una fila lenta demore las llamadas
+            bar.enable_steady_tick(Duration::from_millis(1000 / REFRESH_HZ as u64));
+            bar
+        } else {
+            ProgressBar::hidden()
+        };
+        Progress { bar, calls: 0, last_detail: None }
+    }
+
+    /// Posición actual (filas o bytes); `detail` arma los contadores del comando
+    /// ("Invalid: 3 | Duplicates: 10") y solo se llama cuando toca redibujarlos
+    pub fn update(&mut self, position: u64, detail: impl FnOnce() -> String) {
+        if self.bar.is_hidden() {
+            return;
+        }
+        self.calls += 1;
+        if !self.calls.is_multiple_of(SAMPLE_CALLS) {
+            return;
+        }
+        self.bar.set_position(position);
+        if self.last_detail.is_none_or(|t| t.elapsed() >= DETAIL_INTERVAL) {
+            self.set_detail(&detail());
+            self.last_detail = Some(Instant::now());
+        }
+    }
+
+    /// Contadores del comando que se muestran después de la posición
+    pub fn set_detail(&self, detail: &str) {
+        self.bar.set_message(if detail.is_empty() { String::new() } else { format!(" | {}", detail) });
+    }
+
+    /// Posición actual sin muestreo (para llamadas que ya son espaciadas)
+    pub fn set_position(&self, position: u64) {
+        self.bar.set_position(position);
+    }
+
+    pub fn position(&self) -> u64 {
+        self.bar.position()
+    }
+
+    /// Quita la barra; el comando imprime después su línea de resumen
+    pub fn finish(&self) {
+        if !self.bar.is_finished() {
+            self.bar.finish_and_clear();
+            if let Some(bars) = BARS.get() {
+                bars.remove(&self.bar);
+            }
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Archivo en curso en comandos que recorren una lista de archivos (merge)
+struct FileProgress {
+    name: String,
+    index: usize,
+    count: usize,
+    bar: Progress,
+    start_processed: u64,
+    start_time: Instant,
+}
+
+/// Progreso de comandos de varios archivos: barra del total (sobre la estimación de
+/// líneas de todos los archivos) y debajo la del archivo en curso, para ver enseguida
+/// en qué archivo se trabó una lectura
 pub struct ProgressTracker {
     start_time: Instant,
-    last_report_time: Instant,
     total_processed: u64,
-    report_interval: u64,
+    total: Progress,
+    current_file: Option<FileProgress>,
 }
 
 impl ProgressTracker {
-    pub fn new(report_interval: u64) -> Self {
-        let now = Instant::now();
+    pub fn new(estimated_total: u64) -> Self {
         Self {
-            start_time: now,
-            last_report_time: now,
+            start_time: Instant::now(),
             total_processed: 0,
-            report_interval,
+            total: Progress::rows_of("Total", estimated_total),
+            current_file: None,
         }
     }
-    
+
+    /// Líneas procesadas en total (de todos los archivos)
     pub fn update(&mut self, processed: u64) {
-        self.total_processed = processed; // Cambio: asignar en lugar de sumar
-        
-        if self.total_processed % self.report_interval == 0 {
-            self.report();
+        self.total_processed = processed;
+        self.total.set_position(processed);
+        if let Some(file) = &self.current_file {
+            file.bar.set_position(processed - file.start_processed);
         }
     }
-    
-    fn report(&mut self) {
-        let elapsed = self.start_time.elapsed().as_secs_f64();
-        let rate = if elapsed > 0.0 {
-            self.total_processed as f64 / elapsed
-        } else {
-            0.0
+
+    /// Empieza el archivo `index` (desde 1) de `count`
+    pub fn start_file(&mut self, name: &str, index: usize, count: usize, estimated_lines: Option<u64>) {
+        match estimated_lines {
+            Some(lines) => info!("📂 [{}/{}] {} (~{} lines)", index, count, name, lines),
+            None => info!("📂 [{}/{}] {}", index, count, name),
+        }
+
+        let label = format!("[{}/{}] {}", index, count, name);
+        self.current_file = Some(FileProgress {
+            name: name.to_string(),
+            index,
+            count,
+            bar: match estimated_lines {
+                Some(lines) if lines > 0 => Progress::rows_of(&label, lines),
+                _ => Progress::rows(&label),
+            },
+            start_processed: self.total_processed,
+            start_time: Instant::now(),
+        });
+    }
+
+    /// Cierra el archivo en curso con una línea de resumen (`detail` se agrega al final)
+    pub fn finish_file(&mut self, detail: &str) {
+        let Some(file) = self.current_file.take() else {
+            return;
         };
-        
-        print!("\r📊 Processed: {} | Rate: {:.0} rec/s | Time: {:.1}s", 
-               self.total_processed, 
-               rate,
-               elapsed);
-        io::stdout().flush().ok();
-        
-        self.last_report_time = Instant::now();
-    }
-    
-    /// Finaliza el progreso sin mensaje personalizado
+        file.bar.finish();
+
+        let lines = self.total_processed - file.start_processed;
+        let elapsed = file.start_time.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 { lines as f64 / elapsed } else { 0.0 };
+
+        info!("✅ [{}/{}] {}: {} lines in {:.1}s ({:.0} rec/s){}",
+                 file.index, file.count, file.name, lines, elapsed, rate, detail);
+    }
+
+    /// Quita las barras e imprime el total
     pub fn finish(&self) {
+        if let Some(file) = &self.current_file {
+            file.bar.finish();
+        }
+        self.total.finish();
         let elapsed = self.start_time.elapsed().as_secs_f64();
         let rate = if elapsed > 0.0 {
             self.total_processed as f64 / elapsed
         } else {
             0.0
         };
-        
-        println!("\n✅ Complete: {} records in {:.1}s ({:.0} rec/s)", 
+
+        info!("✅ Complete: {} records in {:.1}s ({:.0} rec/s)",
                  self.total_processed,
                  elapsed,
                  rate);
     }
-    
+
     pub fn total(&self) -> u64 {
         self.total_processed
     }
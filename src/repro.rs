@@ -0,0 +1,179 @@
+// Semilla global de la corrida (`--seed`) y reporte de reproducibilidad
+// para auditoría: opciones efectivas + hashes SHA-256 de entradas/salidas
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::CsvToolsError;
+use crate::file_utils::format_bytes;
+use crate::info;
+use crate::logging;
+
+/// Semilla usada cuando no se pasa `--seed` (las corridas siguen siendo deterministas)
+pub const DEFAULT_SEED: u64 = 0x5EED_0C57_7001;
+
+static SEED: OnceLock<u64> = OnceLock::new();
+
+/// Semilla efectiva de la corrida (sampling, particiones por hash, sketches)
+pub fn seed() -> u64 {
+    *SEED.get().unwrap_or(&DEFAULT_SEED)
+}
+
+/// Fija la semilla global (una sola vez, antes de ejecutar el comando)
+pub fn set_seed(seed: u64) {
+    SEED.set(seed).ok();
+}
+
+#[derive(Serialize)]
+struct FileDigest {
+    path: String,
+    size: u64,
+    sha256: String,
+    #[serde(skip)]
+    modified: Option<SystemTime>,
+}
+
+/// Reporte de reproducibilidad: dos corridas con el mismo comando, opciones,
+/// semilla y hashes de entrada deben producir hashes de salida idénticos
+#[derive(Serialize)]
+pub struct ReproReport {
+    tool_version: String,
+    command: String,
+    arguments: Vec<String>,
+    options: BTreeMap<String, String>,
+    seed: u64,
+    inputs: Vec<FileDigest>,
+    outputs: Vec<FileDigest>,
+    duration_secs: f64,
+    #[serde(skip)]
+    started: Instant,
+}
+
+impl ReproReport {
+    /// Registra la corrida y hashea los archivos que existen antes de ejecutarla
+    pub fn begin(args: &[String]) -> Result<Self, CsvToolsError> {
+        let command = args.get(1).cloned().unwrap_or_default();
+        let arguments: Vec<String> = args.iter().skip(2).cloned().collect();
+
+        let mut options = BTreeMap::new();
+        let mut i = 0;
+        while i < arguments.len() {
+            let arg = &arguments[i];
+            if let Some(flag) = arg.strip_prefix("--") {
+                match flag.split_once('=') {
+                    Some((k, v)) => {
+                        options.insert(k.to_string(), v.to_string());
+                    }
+                    None => {
+                        let value = arguments.get(i + 1).filter(|v| !v.starts_with("--"));
+                        options.insert(flag.to_string(), value.cloned().unwrap_or_else(|| "true".to_string()));
+                        if value.is_some() {
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        info!("🔐 Hashing inputs for reproducibility report...");
+        let mut inputs = Vec::new();
+        for path in candidate_paths(&arguments) {
+            inputs.push(digest_file(&path)?);
+        }
+
+        Ok(ReproReport {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            command,
+            arguments,
+            options,
+            seed: seed(),
+            inputs,
+            outputs: Vec::new(),
+            duration_secs: 0.0,
+            started: Instant::now(),
+        })
+    }
+
+    /// Hashea las salidas (archivos creados o modificados durante la corrida),
+    /// imprime el resumen y lo guarda como JSON si se pidió `--repro-report`
+    pub fn finish(mut self, report_path: Option<&str>) -> Result<(), CsvToolsError> {
+        self.duration_secs = self.started.elapsed().as_secs_f64();
+
+        // Un archivo preexistente que se reescribió era una salida, no una entrada
+        for path in candidate_paths(&self.arguments) {
+            let metadata = std::fs::metadata(&path)?;
+            let unchanged = self.inputs.iter().any(|d| {
+                d.path == path && d.size == metadata.len() && d.modified == metadata.modified().ok()
+            });
+            if !unchanged {
+                self.inputs.retain(|d| d.path != path);
+                self.outputs.push(digest_file(&path)?);
+            }
+        }
+
+        info!();
+        logging::banner("Reproducibility Report");
+        info!("🛠️  csv_tools {} — {}", self.tool_version, self.command);
+        info!("🎲 Seed: {}", self.seed);
+        for (k, v) in &self.options {
+            info!("⚙️  --{} = {}", k, v);
+        }
+        for d in &self.inputs {
+            info!("📄 IN  {} ({}) sha256={}", d.path, format_bytes(d.size), d.sha256);
+        }
+        for d in &self.outputs {
+            info!("📝 OUT {} ({}) sha256={}", d.path, format_bytes(d.size), d.sha256);
+        }
+        info!("⏱️  Duration: {:.2}s", self.duration_secs);
+
+        if let Some(path) = report_path {
+            std::fs::write(path, serde_json::to_string_pretty(&self)?)?;
+            info!("💾 Report saved: {}", path);
+        }
+
+        Ok(())
+    }
+}
+
+/// Argumentos que apuntan a archivos existentes (descarta flags)
+fn candidate_paths(arguments: &[String]) -> Vec<String> {
+    arguments.iter()
+        .filter(|a| !a.starts_with("--") && Path::new(a.as_str()).is_file())
+        .cloned()
+        .collect()
+}
+
+fn digest_file(path: &str) -> Result<FileDigest, CsvToolsError> {
+    let metadata = std::fs::metadata(path)?;
+    Ok(FileDigest {
+        path: path.to_string(),
+        size: metadata.len(),
+        sha256: sha256_file(path)?,
+        modified: metadata.modified().ok(),
+    })
+}
+
+/// SHA-256 en streaming (no carga el archivo en memoria)
+pub fn sha256_file(path: &str) -> Result<String, CsvToolsError> {
+    let mut reader = BufReader::with_capacity(1 << 20, File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
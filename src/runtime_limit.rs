@@ -0,0 +1,111 @@
+// Límite de tiempo de la corrida (`--max-runtime 3h50m`) para ventanas de batch:
+// los comandos largos lo consultan entre registros y, al vencer, guardan checkpoint,
+// cierran las salidas parciales y terminan como "incomplete" para retomar con --resume
+
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Código de salida de una corrida cortada por --max-runtime (EX_TEMPFAIL: reintentar luego)
+pub const EXIT_INCOMPLETE: i32 = 75;
+
+static DEADLINE: OnceLock<(Instant, Duration)> = OnceLock::new();
+
+/// Fija el límite contando desde ahora (una sola vez, antes de ejecutar el comando)
+pub fn set_max_runtime(limit: Duration) {
+    DEADLINE.set((Instant::now(), limit)).ok();
+}
+
+pub fn max_runtime() -> Option<Duration> {
+    DEADLINE.get().map(|(_, limit)| *limit)
+}
+
+/// true si se pasó `--max-runtime` y ya venció
+pub fn exceeded() -> bool {
+    DEADLINE.get().is_some_and(|(start, limit)| start.elapsed() >= *limit)
+}
+
+/// Interpreta duraciones como "3h50m", "90m", "1h30m15s" o "45s" (un número solo son segundos)
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid duration '{}' (expected e.g. 3h50m, 90m, 45s)", text);
+    let value = text.trim().to_lowercase();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Ok(Duration::from_secs(seconds));
+    }
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let amount: u64 = digits.parse().map_err(|_| invalid())?;
+        total += amount * match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        digits.clear();
+    }
+
+    if !digits.is_empty() || total == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Formato corto de una duración ("3h50m", "45s")
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    let mut text = String::new();
+    if h > 0 {
+        text.push_str(&format!("{}h", h));
+    }
+    if m > 0 {
+        text.push_str(&format!("{}m", m));
+    }
+    if s > 0 || text.is_empty() {
+        text.push_str(&format!("{}s", s));
+    }
+    text
+}
+
+/// Error de una corrida detenida por --max-runtime (no es una falla: queda para retomar)
+#[derive(Debug)]
+pub struct Incomplete {
+    pub checkpoint_path: String,
+}
+
+impl fmt::Display for Incomplete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Max runtime of {} reached: run stopped as incomplete (checkpoint: {})",
+            max_runtime().map(format_duration).unwrap_or_default(),
+            self.checkpoint_path
+        )
+    }
+}
+
+impl std::error::Error for Incomplete {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("3h50m"), Ok(Duration::from_secs(3 * 3600 + 50 * 60)));
+        assert_eq!(parse_duration("90m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("1h30m15s"), Ok(Duration::from_secs(5415)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+        assert!(parse_duration("3h50").is_err());
+        assert!(parse_duration("2d").is_err());
+        assert!(parse_duration("").is_err());
+        assert_eq!(format_duration(Duration::from_secs(13800)), "3h50m");
+    }
+}
@@ -0,0 +1,457 @@
+use serde::Deserialize;
+use std::path::Path;
+use chrono::NaiveDate;
+
+use crate::commands::file_ops::convert_date_dd_mm_yyyy_to_iso;
+use crate::error::CsvToolsError;
+use crate::models::DynamoDbModel;
+
+/// Schema de columnas declarado en YAML (o derivado de un DynamoDbModel)
+///
+/// ```yaml
+/// name: siisa_morosos
+/// columns:
+///   - name: Cuil
+///     type: int
+///     key: partition
+///   - name: IdRegion
+///     type: int
+///     nullable: true     # vacío permitido (se preserva o se completa con `default`)
+///   - name: CreateDate
+///     type: date
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schema {
+    #[serde(default)]
+    pub name: String,
+    pub columns: Vec<ColumnSpec>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ColumnSpec {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub column_type: ColumnType,
+    #[serde(default)]
+    pub key: Option<KeyRole>,
+    /// Par (verdadero, falso) de salida para columnas bool, ej. ["S", "N"]
+    #[serde(default)]
+    pub bool_pair: Option<(String, String)>,
+    /// Columna numérica que admite vacíos (por defecto un vacío en int/number/decimal es inválido)
+    #[serde(default)]
+    pub nullable: bool,
+    /// Valor con el que se completan los vacíos (en vez de preservarlos)
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyRole {
+    Partition,
+    Sort,
+}
+
+/// Tipos canónicos soportados por `coerce`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(try_from = "String")]
+pub enum ColumnType {
+    #[default]
+    String,
+    Int,
+    Number,
+    Date,
+    Bool,
+    /// decimal(p,s): precisión total y dígitos decimales
+    Decimal { precision: u32, scale: u32 },
+}
+
+impl TryFrom<String> for ColumnType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let normalized = value.trim().to_lowercase().replace(' ', "");
+
+        if let Some(params) = normalized.strip_prefix("decimal(").and_then(|p| p.strip_suffix(')')) {
+            let (p, s) = params.split_once(',')
+                .ok_or_else(|| format!("Invalid decimal type '{}' (expected decimal(p,s))", value))?;
+            let precision: u32 = p.parse().map_err(|_| format!("Invalid precision in '{}'", value))?;
+            let scale: u32 = s.parse().map_err(|_| format!("Invalid scale in '{}'", value))?;
+
+            if precision == 0 || scale > precision {
+                return Err(format!("Invalid decimal type '{}' (need 0 < p and s <= p)", value));
+            }
+            return Ok(ColumnType::Decimal { precision, scale });
+        }
+
+        match normalized.as_str() {
+            "string" | "str" | "text" => Ok(ColumnType::String),
+            "int" | "integer" | "long" => Ok(ColumnType::Int),
+            "number" | "float" | "double" => Ok(ColumnType::Number),
+            "date" | "datetime" => Ok(ColumnType::Date),
+            "bool" | "boolean" => Ok(ColumnType::Bool),
+            other => Err(format!(
+                "Unknown column type '{}' (expected string, int, number, date, bool, decimal(p,s))",
+                other
+            )),
+        }
+    }
+}
+
+impl ColumnType {
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, ColumnType::Int | ColumnType::Number | ColumnType::Decimal { .. })
+    }
+}
+
+impl std::fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ColumnType::String => "string",
+            ColumnType::Int => "int",
+            ColumnType::Number => "number",
+            ColumnType::Date => "date",
+            ColumnType::Bool => "bool",
+            ColumnType::Decimal { precision, scale } => {
+                return write!(f, "decimal({},{})", precision, scale);
+            }
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Schema {
+    /// Carga un schema desde un archivo YAML o, si no existe, desde un nombre de modelo
+    pub fn load(spec: &str) -> Result<Self, CsvToolsError> {
+        if Path::new(spec).exists() {
+            let content = std::fs::read_to_string(spec)?;
+            let schema: Schema = serde_yaml::from_str(&content)
+                .map_err(|e| format!("Invalid schema file '{}': {}", spec, e))?;
+            return Ok(schema);
+        }
+
+        let model = DynamoDbModel::from_model_type(spec)
+            .ok_or_else(|| format!("Schema file not found and unknown model type: {}", spec))?;
+        Ok(Self::from_model(&model))
+    }
+
+    /// Deriva el schema de un DynamoDbModel (Type N → number, resto string)
+    pub fn from_model(model: &DynamoDbModel) -> Self {
+        let mut columns: Vec<(&str, usize)> = model.column_mapping
+            .iter()
+            .map(|(name, idx)| (*name, *idx))
+            .collect();
+        columns.sort_by_key(|(_, idx)| *idx);
+
+        Schema {
+            name: model.table_name.to_string(),
+            columns: columns.into_iter()
+                .map(|(name, _)| ColumnSpec {
+                    name: name.to_string(),
+                    column_type: if model.numeric_fields.contains(&name) {
+                        ColumnType::Number
+                    } else {
+                        ColumnType::String
+                    },
+                    key: if name == model.partition_key {
+                        Some(KeyRole::Partition)
+                    } else if name == model.sort_key {
+                        Some(KeyRole::Sort)
+                    } else {
+                        None
+                    },
+                    bool_pair: None,
+                    nullable: false,
+                    default: None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn column(&self, name: &str) -> Option<&ColumnSpec> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+}
+
+impl ColumnSpec {
+    /// Valor que reemplaza a un vacío (el `default` o el mismo vacío);
+    /// None si la columna no admite vacíos: claves y numéricas sin `nullable`
+    pub fn empty_value(&self) -> Option<&str> {
+        if self.key.is_some() || (self.column_type.is_numeric() && !self.nullable) {
+            return None;
+        }
+        Some(self.default.as_deref().unwrap_or(""))
+    }
+}
+
+/// Reescribe un valor en la representación canónica de su tipo
+/// - string: trim
+/// - int: entero sin decimales ("12.00" → "12")
+/// - number: número decimal válido (sin '+' inicial)
+/// - date: ISO yyyy-MM-dd o yyyy-MM-ddTHH:mm:ss
+/// - bool: par configurable por columna (`bool_pair`, default 1/0)
+/// - decimal(p,s): punto decimal, sin símbolo de moneda, exactamente s decimales
+///
+/// Los valores vacíos se preservan o se completan con `default` (salvo columnas clave)
+pub fn coerce_value(value: &str, spec: &ColumnSpec) -> Result<String, String> {
+    let v = value.trim();
+
+    if v.is_empty() {
+        return match spec.key {
+            Some(_) => Err("empty value in key column".to_string()),
+            None => Ok(spec.default.clone().unwrap_or_default()),
+        };
+    }
+
+    match spec.column_type {
+        ColumnType::String => Ok(v.to_string()),
+        ColumnType::Int => coerce_int(v),
+        ColumnType::Number => coerce_number(v),
+        ColumnType::Date => coerce_date(v),
+        ColumnType::Bool => coerce_bool(v, spec.bool_pair.as_ref()),
+        ColumnType::Decimal { precision, scale } => coerce_decimal(v, precision, scale),
+    }
+}
+
+/// Reconoce los booleanos de los distintos sistemas origen
+/// si/no, sí/no, true/false, S/N, Y/N, yes/no, 1/0 (sin distinguir mayúsculas)
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "t" | "si" | "sí" | "s" | "y" | "yes" => Some(true),
+        "0" | "false" | "f" | "no" | "n" => Some(false),
+        _ => None,
+    }
+}
+
+/// Infiere el tipo de una columna a partir de una muestra de valores
+/// (vacíos se ignoran; sin valores → string). Orden: int, number, bool, date
+pub fn infer_type<'a>(samples: impl IntoIterator<Item = &'a str>) -> ColumnType {
+    let values: Vec<&str> = samples.into_iter()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect();
+
+    if values.is_empty() {
+        return ColumnType::String;
+    }
+
+    let all = |f: fn(&str) -> bool| values.iter().all(|v| f(v));
+
+    if all(|v| v.strip_prefix('-').unwrap_or(v).chars().all(|c| c.is_ascii_digit())) {
+        ColumnType::Int
+    } else if all(|v| coerce_number(v).is_ok()) {
+        ColumnType::Number
+    } else if all(|v| parse_bool(v).is_some()) {
+        ColumnType::Bool
+    } else if all(|v| coerce_date(v).is_ok()) {
+        ColumnType::Date
+    } else {
+        ColumnType::String
+    }
+}
+
+fn coerce_int(v: &str) -> Result<String, String> {
+    let unsigned = v.strip_prefix('+').unwrap_or(v);
+
+    if let Ok(n) = unsigned.parse::<i64>() {
+        return Ok(n.to_string());
+    }
+
+    // "123.000" → "123" (solo si la parte decimal es cero)
+    if let Some((int_part, frac_part)) = unsigned.split_once('.') {
+        if !frac_part.is_empty() && frac_part.chars().all(|c| c == '0') {
+            if let Ok(n) = int_part.parse::<i64>() {
+                return Ok(n.to_string());
+            }
+        }
+    }
+
+    Err(format!("not an integer: '{}'", v))
+}
+
+fn coerce_number(v: &str) -> Result<String, String> {
+    let unsigned = v.strip_prefix('+').unwrap_or(v);
+
+    match unsigned.parse::<f64>() {
+        Ok(n) if n.is_finite() => {
+            if unsigned.chars().all(|c| c.is_ascii_digit() || c == '.' || c == '-') {
+                Ok(unsigned.to_string())
+            } else {
+                Ok(n.to_string())
+            }
+        }
+        _ => Err(format!("not a number: '{}'", v)),
+    }
+}
+
+fn coerce_date(v: &str) -> Result<String, String> {
+    if let Ok(iso) = convert_date_dd_mm_yyyy_to_iso(v) {
+        return Ok(iso);
+    }
+
+    for format in ["%Y-%m-%d", "%d/%m/%Y", "%m/%d/%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(v, format) {
+            return Ok(date.format("%Y-%m-%d").to_string());
+        }
+    }
+
+    Err(format!("unrecognized date: '{}'", v))
+}
+
+/// Normaliza importes: quita símbolos de moneda ($, €, ARS, USD...),
+/// resuelve coma vs punto decimal y valida precisión/escala declaradas
+///
+/// Con ambos separadores el último es el decimal ("1.234,56" / "1,234.56");
+/// con uno solo repetido es de miles ("1.234.567"); si aparece una vez es decimal
+fn coerce_decimal(v: &str, precision: u32, scale: u32) -> Result<String, String> {
+    let cleaned: String = v.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '$' | '€' | '£' | '¥'))
+        .collect();
+    let cleaned = cleaned.trim_matches(|c: char| c.is_ascii_alphabetic());
+
+    let (negative, digits) = match cleaned.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, cleaned.strip_prefix('+').unwrap_or(cleaned)),
+    };
+
+    let commas = digits.matches(',').count();
+    let dots = digits.matches('.').count();
+
+    let decimal_sep = match (commas, dots) {
+        (0, 0) => None,
+        (_, 0) => if commas == 1 { Some(',') } else { None },
+        (0, _) => if dots == 1 { Some('.') } else { None },
+        _ => if digits.rfind(',') > digits.rfind('.') { Some(',') } else { Some('.') },
+    };
+
+    let (int_part, frac_part) = match decimal_sep {
+        Some(sep) => digits.rsplit_once(sep).unwrap_or((digits, "")),
+        None => (digits, ""),
+    };
+    let int_digits: String = int_part.chars().filter(|c| *c != ',' && *c != '.').collect();
+
+    if int_digits.is_empty() && frac_part.is_empty()
+        || !int_digits.chars().all(|c| c.is_ascii_digit())
+        || !frac_part.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("not a decimal amount: '{}'", v));
+    }
+
+    let frac_trimmed = frac_part.trim_end_matches('0');
+    if frac_trimmed.len() as u32 > scale {
+        return Err(format!("'{}' exceeds declared scale {} ({} decimals)", v, scale, frac_trimmed.len()));
+    }
+
+    let int_normalized = int_digits.trim_start_matches('0');
+    let int_normalized = if int_normalized.is_empty() { "0" } else { int_normalized };
+    if int_normalized.len() as u32 > precision - scale {
+        return Err(format!("'{}' exceeds declared precision decimal({},{})", v, precision, scale));
+    }
+
+    let mut result = String::new();
+    if negative && (int_normalized != "0" || !frac_trimmed.is_empty()) {
+        result.push('-');
+    }
+    result.push_str(int_normalized);
+    if scale > 0 {
+        result.push('.');
+        result.push_str(frac_trimmed);
+        for _ in frac_trimmed.len() as u32..scale {
+            result.push('0');
+        }
+    }
+
+    Ok(result)
+}
+
+fn coerce_bool(v: &str, pair: Option<&(String, String)>) -> Result<String, String> {
+    let (true_value, false_value) = match pair {
+        Some((t, f)) => (t.as_str(), f.as_str()),
+        None => ("1", "0"),
+    };
+
+    match parse_bool(v) {
+        Some(true) => Ok(true_value.to_string()),
+        Some(false) => Ok(false_value.to_string()),
+        None => Err(format!("not a boolean: '{}' (expected si/no, true/false, S/N, Y/N, 1/0)", v)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(column_type: ColumnType) -> ColumnSpec {
+        ColumnSpec { name: "c".to_string(), column_type, key: None, bool_pair: None, nullable: false, default: None }
+    }
+
+    #[test]
+    fn test_coerce_value() {
+        assert_eq!(coerce_value("  Perez ", &spec(ColumnType::String)).unwrap(), "Perez");
+        assert_eq!(coerce_value("12.00", &spec(ColumnType::Int)).unwrap(), "12");
+        assert_eq!(coerce_value("+7", &spec(ColumnType::Int)).unwrap(), "7");
+        assert!(coerce_value("12.5", &spec(ColumnType::Int)).is_err());
+        assert_eq!(coerce_value("-3.25", &spec(ColumnType::Number)).unwrap(), "-3.25");
+        assert!(coerce_value("abc", &spec(ColumnType::Number)).is_err());
+        assert_eq!(coerce_value("13/08/2025", &spec(ColumnType::Date)).unwrap(), "2025-08-13");
+        assert_eq!(coerce_value("13/08/2025 10:30", &spec(ColumnType::Date)).unwrap(), "2025-08-13T10:30:00");
+        assert_eq!(coerce_value("TRUE", &spec(ColumnType::Bool)).unwrap(), "1");
+        assert_eq!(coerce_value("Sí", &spec(ColumnType::Bool)).unwrap(), "1");
+        assert_eq!(coerce_value("N", &spec(ColumnType::Bool)).unwrap(), "0");
+        assert!(coerce_value("maybe", &spec(ColumnType::Bool)).is_err());
+
+        let pair = ColumnSpec { bool_pair: Some(("S".to_string(), "N".to_string())), ..spec(ColumnType::Bool) };
+        assert_eq!(coerce_value("yes", &pair).unwrap(), "S");
+        assert_eq!(coerce_value("0", &pair).unwrap(), "N");
+        assert_eq!(coerce_value("", &spec(ColumnType::Int)).unwrap(), "");
+
+        let key = ColumnSpec { key: Some(KeyRole::Partition), ..spec(ColumnType::Int) };
+        assert!(coerce_value("", &key).is_err());
+        assert_eq!(key.empty_value(), None);
+        assert_eq!(spec(ColumnType::Int).empty_value(), None);
+        assert_eq!(spec(ColumnType::String).empty_value(), Some(""));
+
+        let region = ColumnSpec { nullable: true, default: Some("0".to_string()), ..spec(ColumnType::Int) };
+        assert_eq!(region.empty_value(), Some("0"));
+        assert_eq!(coerce_value(" ", &region).unwrap(), "0");
+    }
+
+    #[test]
+    fn test_coerce_decimal() {
+        let importe = spec(ColumnType::Decimal { precision: 12, scale: 2 });
+        assert_eq!(coerce_value("$ 1.234,5", &importe).unwrap(), "1234.50");
+        assert_eq!(coerce_value("1,234.56", &importe).unwrap(), "1234.56");
+        assert_eq!(coerce_value("ARS 99,9", &importe).unwrap(), "99.90");
+        assert_eq!(coerce_value("-0,50", &importe).unwrap(), "-0.50");
+        assert_eq!(coerce_value("1.234.567", &importe).unwrap(), "1234567.00");
+        assert_eq!(coerce_value("10.500", &importe).unwrap(), "10.50");
+        assert!(coerce_value("10.555", &importe).is_err());
+        assert!(coerce_value("12345678901", &importe).is_err());
+        assert!(coerce_value("abc", &importe).is_err());
+
+        assert_eq!(
+            ColumnType::try_from("decimal(12, 2)".to_string()).unwrap(),
+            ColumnType::Decimal { precision: 12, scale: 2 }
+        );
+        assert!(ColumnType::try_from("decimal(2,3)".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_infer_type() {
+        assert_eq!(infer_type(["20123456789", "", "-5"]), ColumnType::Int);
+        assert_eq!(infer_type(["1.5", "2"]), ColumnType::Number);
+        assert_eq!(infer_type(["S", "N", "si"]), ColumnType::Bool);
+        assert_eq!(infer_type(["2024-05-01", "13/08/2025"]), ColumnType::Date);
+        assert_eq!(infer_type(["PEREZ JUAN", "1"]), ColumnType::String);
+        assert_eq!(infer_type(["", " "]), ColumnType::String);
+    }
+
+    #[test]
+    fn test_parse_schema_yaml() {
+        let yaml = "name: t\ncolumns:\n  - name: Cuil\n    type: int\n    key: partition\n  - name: Nombre\n";
+        let schema: Schema = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(schema.columns.len(), 2);
+        assert_eq!(schema.columns[0].column_type, ColumnType::Int);
+        assert_eq!(schema.columns[0].key, Some(KeyRole::Partition));
+        assert_eq!(schema.columns[1].column_type, ColumnType::String);
+    }
+}
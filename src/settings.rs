@@ -0,0 +1,145 @@
+// Opciones globales (válidas para cualquier comando, antes o después del subcomando):
+// clap las parsea como argumentos `global` y `apply` las deja activas antes del dispatch
+
+use std::time::Duration;
+
+use clap::Args;
+
+use crate::collation::{self, Collation};
+use crate::compression::{self, OutputCompression};
+use crate::config::{self, Config};
+use crate::encoding::{self, InputEncoding};
+use crate::error::CsvToolsError;
+use crate::error_report::{self, ErrorReportFormat};
+use crate::logging::{self, Level, OutputFormat};
+use crate::{diagnostics, io_retry, repro, runtime_limit};
+
+/// Opciones globales de la línea de comandos
+#[derive(Args, Debug, Default)]
+pub struct GlobalOptions {
+    #[arg(long, global = true, value_name = "N", help = "Deterministic seed for sampling / hash partitions / sketches")]
+    pub seed: Option<u64>,
+    #[arg(long, global = true, value_name = "PATH", help = "Save the reproducibility report as JSON")]
+    pub repro_report: Option<String>,
+    #[arg(long, global = true, help = "Treat data-quality warnings as errors (non-zero exit code)")]
+    pub strict: bool,
+    #[arg(long, global = true, value_name = "N", help = "Fail the run when more than N warnings are emitted")]
+    pub max_warnings: Option<usize>,
+    #[arg(long, global = true, value_name = "NAME", value_parser = parse_collation,
+          help = "Key comparison for sort/dedup: binary, case-insensitive, es_AR")]
+    pub collation: Option<Collation>,
+    #[arg(long, global = true, value_name = "N", help = "Retry transient read/write I/O errors N times with backoff")]
+    pub io_retries: Option<u32>,
+    #[arg(long, global = true, value_name = "PATH", help = "Write a JSON manifest of every file the run created")]
+    pub manifest: Option<String>,
+    #[arg(long, global = true, value_name = "DURATION", value_parser = runtime_limit::parse_duration,
+          help = "Stop gracefully after e.g. 3h50m: checkpoint + exit code 75")]
+    pub max_runtime: Option<Duration>,
+    #[arg(long, global = true, value_name = "CHAR", value_parser = config::parse_delimiter_setting,
+          help = "Field delimiter for every CSV read or written (e.g. ';' or '\\t'); 'auto' sniffs it from the input [default: ,]")]
+    pub delimiter: Option<String>,
+    #[arg(long, global = true, value_enum, value_name = "FORMAT",
+          help = "Compress every CSV output (gzip or zstd), adding .gz / .zst to names that lack it")]
+    pub compress: Option<OutputCompression>,
+    #[arg(long, global = true, value_name = "ENC", value_parser = encoding::parse_input_encoding,
+          help = "Encoding of every input (utf-8, latin1, windows-1252, utf-16le, ...); 'auto' detects it per file [default: utf-8]")]
+    pub input_encoding: Option<InputEncoding>,
+    #[arg(long, global = true, value_name = "ENC", value_parser = encoding::parse_encoding,
+          help = "Encoding of the CSV outputs [default: utf-8]")]
+    pub output_encoding: Option<&'static encoding_rs::Encoding>,
+    #[arg(long, global = true, help = "Keep a UTF-8 byte order mark at the start of inputs (stripped by default)")]
+    pub keep_bom: bool,
+    #[arg(long, global = true, conflicts_with = "verbose",
+          help = "Only data, warnings and errors: no banners, status lines or progress")]
+    pub quiet: bool,
+    #[arg(long, global = true, help = "Also print step details (effective config, timings)")]
+    pub verbose: bool,
+    #[arg(long, global = true, value_name = "PATH", help = "Append every status message, warning and error to a log file")]
+    pub log_file: Option<String>,
+    #[arg(long, global = true, value_name = "PATH",
+          help = "Also write every data error as a fixed-schema record (line,byte_offset,error_type,column,value,message); '-' = stdout")]
+    pub error_report: Option<String>,
+    #[arg(long, global = true, value_enum, value_name = "FORMAT", requires = "error_report",
+          help = "Error report format [default: ndjson for .ndjson/.jsonl, csv otherwise]")]
+    pub error_report_format: Option<ErrorReportFormat>,
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text,
+          help = "Final summary format; json prints one object on stdout (messages go to stderr); markdown renders head/tail as a table")]
+    pub format: OutputFormat,
+}
+
+fn parse_collation(value: &str) -> Result<Collation, String> {
+    Collation::try_from(value)
+}
+
+impl GlobalOptions {
+    /// Aplica las opciones (semilla, collation, reintentos, límite de tiempo, compresión, codificación, modo estricto,
+    /// nivel de salida, reporte de errores) y fija la configuración de la corrida: los flags pisan los valores del archivo
+    pub fn apply(&self, mut config: Config) -> Result<(), CsvToolsError> {
+        if let Some(d) = &self.delimiter {
+            config.delimiter = Some(d.clone());
+        }
+        if self.quiet {
+            config.quiet = true;
+        }
+        let level = match (self.verbose, config.quiet) {
+            (true, _) => Level::Verbose,
+            (false, true) => Level::Quiet,
+            (false, false) => Level::Normal,
+        };
+        config::set_current(config);
+        logging::configure(level, self.format, self.log_file.as_deref())?;
+        if let Some(path) = &self.error_report {
+            error_report::configure(path, self.error_report_format)?;
+        }
+
+        if let Some(seed) = self.seed {
+            repro::set_seed(seed);
+        }
+        if let Some(c) = self.collation {
+            collation::set_current(c);
+        }
+        if let Some(retries) = self.io_retries {
+            io_retry::set_retries(retries);
+        }
+        if let Some(limit) = self.max_runtime {
+            runtime_limit::set_max_runtime(limit);
+        }
+        if let Some(c) = self.compress {
+            compression::set_output_compression(c);
+        }
+        if let Some(e) = self.input_encoding {
+            encoding::set_input_encoding(e);
+        }
+        if let Some(e) = self.output_encoding {
+            encoding::set_output_encoding(e);
+        }
+        encoding::set_keep_bom(self.keep_bom);
+        diagnostics::configure(self.strict, self.max_warnings);
+        Ok(())
+    }
+
+    /// El reporte de reproducibilidad se genera solo si se pidió explícitamente
+    pub fn wants_report(&self) -> bool {
+        self.seed.is_some() || self.repro_report.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::cli::{command_argv, Cli};
+
+    #[test]
+    fn test_global_options_anywhere() {
+        let args: Vec<String> = ["csv_tools", "--strict", "count", "in.csv", "--seed", "42", "--repro-report=r.json"]
+            .iter().map(|s| s.to_string()).collect();
+        let cli = Cli::try_parse_from(&args).unwrap();
+
+        assert_eq!(cli.global.seed, Some(42));
+        assert!(cli.global.strict);
+        assert_eq!(cli.global.repro_report.as_deref(), Some("r.json"));
+        assert_eq!(command_argv(&args), vec!["csv_tools", "count", "in.csv"]);
+        assert!(Cli::try_parse_from(["csv_tools", "count", "in.csv", "--quiet", "--verbose"]).is_err());
+    }
+}
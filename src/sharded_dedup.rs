@@ -0,0 +1,139 @@
+// Dedup de líneas en paralelo (merge_dedup --threads N):
+// - Un solo lector recorre los archivos en orden y reparte cada línea por hash de su
+//   clave entre N workers; las repeticiones de una línea caen siempre en el mismo
+// - Cada worker tiene su propio conjunto de vistas (sin locks) y escribe sus líneas
+//   nuevas en un temporal; como recibe las líneas en orden de entrada, conserva la
+//   primera aparición igual que el modo secuencial
+// - `finish` concatena los temporales: la salida queda agrupada por shard
+// Las líneas viajan en lotes para que el costo del canal no domine
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::thread::JoinHandle;
+
+use crate::collation::Collation;
+use crate::dedup::SeenLines;
+use crate::error::CsvToolsError;
+use crate::sketch::seeded_hash;
+use crate::temp_files::TempFiles;
+
+/// Líneas por lote enviado a un worker
+const BATCH_LINES: usize = 4096;
+/// Lotes en vuelo por worker antes de que el lector espere
+const QUEUE_BATCHES: usize = 4;
+/// Semilla del reparto (distinta de las huellas de `SeenLines::Hashed`)
+const SHARD_SEED: u64 = 0x5bd1_e995_0000_0001;
+
+/// Línea y sufijo que no participa de la comparación (columnas de procedencia)
+type Batch = Vec<(String, String)>;
+
+pub struct ShardedDedup {
+    collation: Collation,
+    batches: Vec<Batch>,
+    senders: Vec<SyncSender<Batch>>,
+    workers: Vec<JoinHandle<Result<usize, CsvToolsError>>>,
+    paths: Vec<PathBuf>,
+}
+
+impl ShardedDedup {
+    /// Lanza `shards` workers; con `hash_only` cada uno guarda huellas de 128 bits
+    pub fn new(shards: usize, hash_only: bool, collation: Collation, temp_files: &mut TempFiles) -> Result<Self, CsvToolsError> {
+        let mut dedup = ShardedDedup { collation, batches: Vec::new(), senders: Vec::new(), workers: Vec::new(), paths: Vec::new() };
+        for shard in 0..shards.max(1) {
+            let path = temp_files.path(&format!("shard_{}.txt", shard));
+            let mut writer = BufWriter::with_capacity(1 << 20, File::create(&path)?);
+            let (sender, receiver) = sync_channel::<Batch>(QUEUE_BATCHES);
+
+            dedup.workers.push(std::thread::spawn(move || {
+                let mut seen = if hash_only { SeenLines::hashed() } else { SeenLines::exact() };
+                let mut unique = 0;
+                for batch in receiver {
+                    for (line, suffix) in batch {
+                        if seen.insert(&collation.dedup_key(&line)) {
+                            writer.write_all(line.as_bytes())?;
+                            writer.write_all(suffix.as_bytes())?;
+                            writer.write_all(b"\n")?;
+                            unique += 1;
+                        }
+                    }
+                }
+                writer.flush()?;
+                Ok(unique)
+            }));
+            dedup.senders.push(sender);
+            dedup.batches.push(Vec::with_capacity(BATCH_LINES));
+            dedup.paths.push(path);
+        }
+        Ok(dedup)
+    }
+
+    /// Encola la línea en el worker de su shard
+    pub fn push(&mut self, line: String, suffix: String) -> Result<(), CsvToolsError> {
+        let shard = (seeded_hash(self.collation.dedup_key(&line).as_bytes(), SHARD_SEED) % self.senders.len() as u64) as usize;
+        self.batches[shard].push((line, suffix));
+        if self.batches[shard].len() >= BATCH_LINES {
+            let batch = std::mem::replace(&mut self.batches[shard], Vec::with_capacity(BATCH_LINES));
+            self.send(shard, batch)?;
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, shard: usize, batch: Batch) -> Result<(), CsvToolsError> {
+        if self.senders[shard].send(batch).is_err() {
+            // El worker terminó antes de tiempo: su error sale al esperarlo
+            let worker = self.workers.remove(shard);
+            return Err(match worker.join() {
+                Ok(Err(e)) => e,
+                _ => format!("Dedup worker {} stopped unexpectedly", shard).into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Espera a los workers y concatena sus líneas en `output`; devuelve las únicas
+    pub fn finish(mut self, output: &mut dyn Write) -> Result<usize, CsvToolsError> {
+        for shard in 0..self.senders.len() {
+            let batch = std::mem::take(&mut self.batches[shard]);
+            if !batch.is_empty() {
+                self.send(shard, batch)?;
+            }
+        }
+        // Cerrar los canales termina el loop de cada worker
+        self.senders.clear();
+
+        let mut unique = 0;
+        for (shard, worker) in self.workers.into_iter().enumerate() {
+            unique += worker.join().map_err(|_| format!("Dedup worker {} panicked", shard))??;
+        }
+        for path in &self.paths {
+            std::io::copy(&mut BufReader::with_capacity(1 << 20, File::open(path)?), output)?;
+            std::fs::remove_file(path)?;
+        }
+        Ok(unique)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shards_keep_first_occurrence() {
+        let mut temp_files = TempFiles::new(&std::env::temp_dir());
+        let mut dedup = ShardedDedup::new(4, false, Collation::CaseInsensitive, &mut temp_files).unwrap();
+        for i in 0..10_000 {
+            dedup.push(format!("k{}", i % 1000), format!(",{}", i)).unwrap();
+            dedup.push(format!("K{}", i % 1000), ",upper".to_string()).unwrap();
+        }
+        let mut output = Vec::new();
+        assert_eq!(dedup.finish(&mut output).unwrap(), 1000);
+
+        let mut lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+        lines.sort();
+        assert_eq!(lines.len(), 1000);
+        // La primera aparición de cada clave es la minúscula del primer ciclo
+        assert!(lines.contains(&"k0,0") && lines.contains(&"k999,999"));
+    }
+}
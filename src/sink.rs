@@ -0,0 +1,144 @@
+// Destinos de salida de registros (`RecordSink`): los comandos escriben header y
+// registros sin saber si van a un CSV local o a un destino registrado por esquema
+// de URI (ej. `mq://cola`), así se agregan destinos nuevos sin tocar cada comando
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Mutex, OnceLock};
+
+use csv::StringRecord;
+
+use crate::compression::create_output;
+use crate::config;
+use crate::error::CsvToolsError;
+
+/// Destino de los registros que produce un comando
+pub trait RecordSink {
+    fn write_header(&mut self, header: &StringRecord) -> Result<(), CsvToolsError>;
+    fn write_record(&mut self, record: &StringRecord) -> Result<(), CsvToolsError>;
+    /// Vacía buffers y cierra el destino; después no se escribe más
+    fn finish(&mut self) -> Result<(), CsvToolsError>;
+}
+
+/// Crea el destino a partir del URI completo (`esquema://resto`)
+pub type SinkFactory = fn(&str) -> Result<Box<dyn RecordSink>, CsvToolsError>;
+
+static FACTORIES: OnceLock<Mutex<HashMap<String, SinkFactory>>> = OnceLock::new();
+
+fn factories() -> &'static Mutex<HashMap<String, SinkFactory>> {
+    FACTORIES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Registra un destino para `scheme://...` (reemplaza uno previo con el mismo esquema)
+pub fn register_sink(scheme: &str, factory: SinkFactory) {
+    factories().lock().unwrap().insert(scheme.to_lowercase(), factory);
+}
+
+/// Abre el destino de salida de un comando: ruta local (o `file://ruta`) como CSV,
+/// `.gz` comprimido; cualquier otro esquema debe estar registrado con `register_sink`
+pub fn open_sink(target: &str) -> Result<Box<dyn RecordSink>, CsvToolsError> {
+    let Some((scheme, rest)) = split_scheme(target) else {
+        return Ok(Box::new(CsvSink::create(target)?));
+    };
+    if scheme == "file" {
+        return Ok(Box::new(CsvSink::create(rest)?));
+    }
+
+    let factory = factories().lock().unwrap().get(&scheme).copied();
+    match factory {
+        Some(factory) => factory(target),
+        None => {
+            let mut known: Vec<String> = factories().lock().unwrap().keys().cloned().collect();
+            known.sort();
+            known.insert(0, "file".to_string());
+            Err(format!("Unknown output scheme '{}://' (available: {})", scheme, known.join(", ")).into())
+        }
+    }
+}
+
+/// Separa `esquema://resto`; las rutas locales (también `C:\...`) no tienen esquema
+fn split_scheme(target: &str) -> Option<(String, &str)> {
+    let (scheme, rest) = target.split_once("://")?;
+    let valid = scheme.len() > 1
+        && scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+    valid.then(|| (scheme.to_lowercase(), rest))
+}
+
+/// Destino por defecto: CSV con comillas solo donde hace falta (RFC 4180)
+pub struct CsvSink {
+    writer: csv::Writer<Box<dyn Write>>,
+}
+
+impl CsvSink {
+    pub fn create(path: &str) -> Result<Self, CsvToolsError> {
+        Ok(Self::from_writer(create_output(path, 1)?))
+    }
+
+    /// CSV sobre un writer cualquiera (ej. stdout)
+    pub fn from_writer(output: Box<dyn Write>) -> Self {
+        let writer = csv::WriterBuilder::new()
+            .delimiter(config::delimiter())
+            .quote_style(csv::QuoteStyle::Necessary)
+            .from_writer(output);
+        CsvSink { writer }
+    }
+}
+
+impl RecordSink for CsvSink {
+    fn write_header(&mut self, header: &StringRecord) -> Result<(), CsvToolsError> {
+        self.write_record(header)
+    }
+
+    fn write_record(&mut self, record: &StringRecord) -> Result<(), CsvToolsError> {
+        self.writer.write_record(record)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), CsvToolsError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static CAPTURED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct MemorySink;
+
+    impl RecordSink for MemorySink {
+        fn write_header(&mut self, header: &StringRecord) -> Result<(), CsvToolsError> {
+            CAPTURED.lock().unwrap().push(format!("H:{}", header.iter().collect::<Vec<_>>().join("|")));
+            Ok(())
+        }
+
+        fn write_record(&mut self, record: &StringRecord) -> Result<(), CsvToolsError> {
+            CAPTURED.lock().unwrap().push(record.iter().collect::<Vec<_>>().join("|"));
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<(), CsvToolsError> {
+            CAPTURED.lock().unwrap().push("END".to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_open_sink_by_scheme() {
+        assert_eq!(split_scheme("mq://queue/a"), Some(("mq".to_string(), "queue/a")));
+        assert_eq!(split_scheme(r"C:\data\out.csv"), None);
+        assert_eq!(split_scheme("out.csv"), None);
+        assert!(open_sink("unknown://x").is_err());
+
+        register_sink("test-mem", |_| Ok(Box::new(MemorySink)));
+        let mut sink = open_sink("TEST-MEM://anything").unwrap();
+        sink.write_header(&StringRecord::from(vec!["a", "b"])).unwrap();
+        sink.write_record(&StringRecord::from(vec!["1", "2"])).unwrap();
+        sink.finish().unwrap();
+
+        assert_eq!(*CAPTURED.lock().unwrap(), vec!["H:a|b", "1|2", "END"]);
+    }
+}
@@ -0,0 +1,373 @@
+// Estructuras aproximadas de memoria acotada (conteos sobre archivos de cualquier tamaño)
+// Los hashes dependen de la semilla global (`--seed`) para que las corridas sean repetibles
+
+use std::collections::{BTreeSet, HashMap};
+
+/// Hash FNV-1a de 64 bits mezclado con la semilla (finalizador SplitMix64)
+pub fn seeded_hash(bytes: &[u8], seed: u64) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325 ^ seed;
+    for b in bytes {
+        h ^= *b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^ (h >> 31)
+}
+
+/// Count-min sketch: sobreestima conteos (nunca subestima) con error
+/// acotado por `total / width` con probabilidad 1 - 2^-depth
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    counters: Vec<u32>,
+    seed: u64,
+}
+
+impl CountMinSketch {
+    pub fn new(width: usize, depth: usize, seed: u64) -> Self {
+        CountMinSketch {
+            width: width.max(1),
+            depth: depth.max(1),
+            counters: vec![0; width.max(1) * depth.max(1)],
+            seed,
+        }
+    }
+
+    /// Suma 1 al item y devuelve el conteo estimado actualizado
+    pub fn add(&mut self, item: &[u8]) -> u64 {
+        let (h1, h2) = self.hashes(item);
+        let mut estimate = u32::MAX;
+
+        for row in 0..self.depth {
+            let idx = row * self.width + self.column(h1, h2, row);
+            self.counters[idx] = self.counters[idx].saturating_add(1);
+            estimate = estimate.min(self.counters[idx]);
+        }
+
+        estimate as u64
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.counters.len() * std::mem::size_of::<u32>()
+    }
+
+    fn hashes(&self, item: &[u8]) -> (u64, u64) {
+        let h1 = seeded_hash(item, self.seed);
+        let h2 = seeded_hash(item, self.seed ^ 0x9e37_79b9_7f4a_7c15) | 1;
+        (h1, h2)
+    }
+
+    // Doble hashing (Kirsch-Mitzenmacher): h1 + i*h2
+    fn column(&self, h1: u64, h2: u64, row: usize) -> usize {
+        (h1.wrapping_add((row as u64).wrapping_mul(h2)) % self.width as u64) as usize
+    }
+}
+
+/// Filtro de Bloom: responde "visto" sin falsos negativos y con falsos positivos
+/// acotados por la `fpp` con la que se dimensiona para `expected_items`
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    bits_set: u64,
+    seed: u64,
+}
+
+impl BloomFilter {
+    /// Tamaño óptimo: m = -n·ln(p) / ln(2)², k = m/n·ln(2)
+    pub fn new(expected_items: u64, fpp: f64, seed: u64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = ((-n * fpp.ln() / (ln2 * ln2)).ceil() as u64).max(64);
+        let num_hashes = ((num_bits as f64 / n * ln2).round() as u32).clamp(1, 30);
+        BloomFilter {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+            bits_set: 0,
+            seed,
+        }
+    }
+
+    /// Agrega el item; devuelve false si (probablemente) ya estaba
+    pub fn insert(&mut self, item: &[u8]) -> bool {
+        let h1 = seeded_hash(item, self.seed);
+        let h2 = seeded_hash(item, self.seed ^ 0x9e37_79b9_7f4a_7c15) | 1;
+        let mut new = false;
+        for i in 0..self.num_hashes as u64 {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits;
+            let (word, mask) = ((bit / 64) as usize, 1u64 << (bit % 64));
+            if self.bits[word] & mask == 0 {
+                self.bits[word] |= mask;
+                self.bits_set += 1;
+                new = true;
+            }
+        }
+        new
+    }
+
+    /// true si el item (probablemente) ya se agregó; no modifica el filtro
+    pub fn contains(&self, item: &[u8]) -> bool {
+        let h1 = seeded_hash(item, self.seed);
+        let h2 = seeded_hash(item, self.seed ^ 0x9e37_79b9_7f4a_7c15) | 1;
+        (0..self.num_hashes as u64).all(|i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits;
+            self.bits[(bit / 64) as usize] & (1u64 << (bit % 64)) != 0
+        })
+    }
+
+    /// Probabilidad actual de falso positivo según los bits ocupados
+    pub fn false_positive_rate(&self) -> f64 {
+        (self.bits_set as f64 / self.num_bits as f64).powi(self.num_hashes as i32)
+    }
+
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.bits.len() * std::mem::size_of::<u64>()
+    }
+}
+
+/// Top-K por conteo con memoria acotada a `capacity` claves
+/// (la clave con menor conteo se desaloja cuando llega una mayor)
+pub struct TopK {
+    capacity: usize,
+    counts: HashMap<String, u64>,
+    ordered: BTreeSet<(u64, String)>,
+}
+
+impl TopK {
+    pub fn new(capacity: usize) -> Self {
+        TopK {
+            capacity: capacity.max(1),
+            counts: HashMap::new(),
+            ordered: BTreeSet::new(),
+        }
+    }
+
+    /// Registra el conteo (estimado) más reciente de una clave
+    pub fn offer(&mut self, key: &str, count: u64) {
+        if let Some(current) = self.counts.get_mut(key) {
+            if count > *current {
+                self.ordered.remove(&(*current, key.to_string()));
+                *current = count;
+                self.ordered.insert((count, key.to_string()));
+            }
+            return;
+        }
+
+        if self.counts.len() >= self.capacity {
+            match self.ordered.first() {
+                Some((min, _)) if *min < count => {
+                    if let Some((_, evicted)) = self.ordered.pop_first() {
+                        self.counts.remove(&evicted);
+                    }
+                }
+                _ => return,
+            }
+        }
+
+        self.counts.insert(key.to_string(), count);
+        self.ordered.insert((count, key.to_string()));
+    }
+
+    /// Claves de mayor a menor conteo
+    pub fn into_sorted(self) -> Vec<(String, u64)> {
+        self.ordered.into_iter().rev().map(|(count, key)| (key, count)).collect()
+    }
+}
+
+/// Centroide de un t-digest: media y cantidad de valores que resume
+#[derive(Debug, Clone, Copy)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// T-digest (variante merging): percentiles aproximados en una sola pasada con
+/// memoria acotada por `compression` centroides; el error es menor en las colas (p99)
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    count: u64,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> Self {
+        TDigest {
+            compression: compression.max(10.0),
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buffer.push(value);
+
+        if self.buffer.len() >= (self.compression as usize) * 5 {
+            self.compress();
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Une el buffer con los centroides respetando el límite de tamaño de la
+    /// función de escala k1 (centroides chicos cerca de q=0 y q=1)
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut all: Vec<Centroid> = self.buffer.drain(..)
+            .map(|mean| Centroid { mean, weight: 1.0 })
+            .chain(self.centroids.drain(..))
+            .collect();
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total = self.count as f64;
+        let delta = self.compression;
+        let k = |q: f64| delta / (2.0 * std::f64::consts::PI) * (2.0 * q - 1.0).asin();
+        let k_inv = |k: f64| ((k * 2.0 * std::f64::consts::PI / delta).sin() + 1.0) / 2.0;
+
+        let mut merged = Vec::with_capacity(delta as usize * 2);
+        let mut current = all[0];
+        let mut weight_so_far = 0.0;
+        let mut q_limit = k_inv(k(0.0) + 1.0);
+
+        for next in all.into_iter().skip(1) {
+            let q = (weight_so_far + current.weight + next.weight) / total;
+            if q <= q_limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_so_far += current.weight;
+                merged.push(current);
+                q_limit = k_inv((k(weight_so_far / total) + 1.0).min(delta / 4.0));
+                current = next;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+
+    /// Percentil aproximado (`q` entre 0 y 1), interpolando entre centroides vecinos
+    pub fn quantile(&mut self, q: f64) -> Option<f64> {
+        self.compress();
+        if self.centroids.is_empty() {
+            return None;
+        }
+
+        let q = q.clamp(0.0, 1.0);
+        let total = self.count as f64;
+        let target = q * total;
+        let mut cumulative = 0.0;
+
+        for (i, c) in self.centroids.iter().enumerate() {
+            let mid = cumulative + c.weight / 2.0;
+            if target < mid {
+                let value = if i == 0 {
+                    self.min + (c.mean - self.min) * (target / mid)
+                } else {
+                    let prev = self.centroids[i - 1];
+                    let prev_mid = cumulative - prev.weight / 2.0;
+                    prev.mean + (c.mean - prev.mean) * (target - prev_mid) / (mid - prev_mid)
+                };
+                return Some(value.clamp(self.min, self.max));
+            }
+            cumulative += c.weight;
+        }
+
+        let last = self.centroids[self.centroids.len() - 1];
+        let last_mid = total - last.weight / 2.0;
+        if total <= last_mid {
+            return Some(self.max);
+        }
+        let value = last.mean + (self.max - last.mean) * (target - last_mid) / (total - last_mid);
+        Some(value.clamp(self.min, self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_min_never_underestimates() {
+        let mut cms = CountMinSketch::new(64, 4, 42);
+        for i in 0..1000u64 {
+            let estimate = cms.add(format!("k{}", i % 50).as_bytes());
+            assert!(estimate > i / 50);
+        }
+    }
+
+    #[test]
+    fn test_bloom_filter_no_false_negatives() {
+        let mut bloom = BloomFilter::new(10_000, 0.01, 42);
+        // Falsos positivos al cargar: por debajo de la fpp pedida (el filtro se va llenando)
+        let false_positives = (0..10_000u64).filter(|i| !bloom.insert(&i.to_le_bytes())).count();
+        assert!(false_positives < 100, "{} false positives", false_positives);
+        for i in 0..10_000u64 {
+            assert!(!bloom.insert(&i.to_le_bytes()));
+        }
+        assert!(bloom.false_positive_rate() < 0.02);
+    }
+
+    #[test]
+    fn test_top_k_keeps_heaviest() {
+        let mut top = TopK::new(2);
+        top.offer("a", 1);
+        top.offer("b", 5);
+        top.offer("c", 3);
+        top.offer("a", 2);
+        top.offer("b", 6);
+
+        let keys: Vec<String> = top.into_sorted().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_t_digest_percentiles() {
+        let mut small = TDigest::new(100.0);
+        for v in 1..=101 {
+            small.add(v as f64);
+        }
+        assert_eq!(small.quantile(0.5), Some(51.0));
+
+        // 100k valores en orden pseudoaleatorio: error relativo chico sin ordenar
+        let mut digest = TDigest::new(100.0);
+        for i in 0..100_000u64 {
+            digest.add((seeded_hash(&i.to_le_bytes(), 7) % 100_000) as f64);
+        }
+        for (q, expected) in [(0.5, 50_000.0), (0.95, 95_000.0), (0.99, 99_000.0)] {
+            let estimate = digest.quantile(q).unwrap();
+            assert!((estimate - expected).abs() < expected * 0.01, "p{} = {}", q * 100.0, estimate);
+        }
+        assert!(digest.centroids.len() <= 200);
+    }
+}
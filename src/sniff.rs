@@ -0,0 +1,223 @@
+// Detección del dialecto de un CSV a partir de una muestra del comienzo del archivo:
+// separador, comillas, si la primera fila es header y cantidad de columnas
+// La usan el comando `sniff` y `--delimiter auto` (mismo detector en ambos)
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::CsvToolsError;
+use crate::file_utils::{is_csv_name, open_input};
+use crate::{config, diagnostics, verbose};
+
+/// Muestra por defecto: los primeros 64 KB (descomprimidos si la entrada es .gz)
+pub const DEFAULT_SAMPLE_BYTES: usize = 64 * 1024;
+/// Por debajo de esta proporción de filas con la misma cantidad de columnas se advierte
+pub const CONSISTENT_ROWS: f64 = 0.9;
+
+// Separadores candidatos, en orden de preferencia ante un empate
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+const QUOTES: [u8; 2] = [b'"', b'\''];
+
+/// Dialecto más probable de la muestra
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Dialect {
+    pub delimiter: char,
+    /// Comilla vista alrededor de campos (None: la muestra no tiene campos entre comillas)
+    pub quote: Option<char>,
+    pub has_header: bool,
+    /// Columnas de la mayoría de las filas
+    pub columns: usize,
+    /// Proporción de filas con esa cantidad de columnas (0-1)
+    pub consistency: f64,
+    pub rows_sampled: usize,
+    pub bytes_sampled: usize,
+}
+
+/// Lee hasta `sample_bytes` del comienzo de `path` y detecta el dialecto
+pub fn sniff_file(path: &str, sample_bytes: usize) -> Result<Dialect, CsvToolsError> {
+    let mut sample = Vec::with_capacity(sample_bytes.min(DEFAULT_SAMPLE_BYTES));
+    open_input(path)?.take(sample_bytes as u64 + 1).read_to_end(&mut sample)?;
+    // Si la muestra corta el archivo, la última fila queda incompleta: se descarta
+    if sample.len() > sample_bytes {
+        sample.truncate(sample_bytes);
+        if let Some(end) = sample.iter().rposition(|b| *b == b'\n') {
+            sample.truncate(end + 1);
+        }
+    }
+    Ok(sniff(&sample))
+}
+
+/// Detecta el dialecto de una muestra ya leída
+/// El separador es el que da la cantidad de columnas (> 1) más estable entre filas;
+/// a igual estabilidad gana el de más columnas
+pub fn sniff(sample: &[u8]) -> Dialect {
+    let quote = detect_quote(sample);
+    let mut best: Option<(u8, usize, f64, Vec<Vec<String>>)> = None;
+
+    for delimiter in DELIMITERS {
+        let rows = parse_rows(sample, delimiter, quote.unwrap_or(b'"'));
+        let Some((columns, consistency)) = column_mode(&rows) else {
+            continue;
+        };
+        if columns < 2 {
+            continue;
+        }
+        let better = match &best {
+            None => true,
+            Some((_, best_columns, best_consistency, _)) => {
+                consistency > *best_consistency + f64::EPSILON
+                    || ((consistency - best_consistency).abs() <= f64::EPSILON && columns > *best_columns)
+            }
+        };
+        if better {
+            best = Some((delimiter, columns, consistency, rows));
+        }
+    }
+
+    match best {
+        Some((delimiter, columns, consistency, rows)) => Dialect {
+            delimiter: delimiter as char,
+            quote: quote.map(|q| q as char),
+            has_header: detect_header(&rows),
+            columns,
+            consistency,
+            rows_sampled: rows.len(),
+            bytes_sampled: sample.len(),
+        },
+        // Una sola columna (o muestra vacía): no hay separador que detectar
+        None => {
+            let rows = parse_rows(sample, b',', b'"');
+            Dialect {
+                delimiter: ',',
+                quote: quote.map(|q| q as char),
+                has_header: detect_header(&rows),
+                columns: usize::from(!rows.is_empty()),
+                consistency: if rows.is_empty() { 0.0 } else { 1.0 },
+                rows_sampled: rows.len(),
+                bytes_sampled: sample.len(),
+            }
+        }
+    }
+}
+
+fn parse_rows(sample: &[u8], delimiter: u8, quote: u8) -> Vec<Vec<String>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .quote(quote)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(sample);
+    reader.records()
+        .map_while(Result::ok)
+        .map(|r| r.iter().map(str::to_string).collect())
+        .collect()
+}
+
+/// Cantidad de columnas más frecuente y proporción de filas que la tienen
+fn column_mode(rows: &[Vec<String>]) -> Option<(usize, f64)> {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for row in rows {
+        *counts.entry(row.len()).or_default() += 1;
+    }
+    counts.into_iter()
+        .max_by_key(|(columns, n)| (*n, *columns))
+        .map(|(columns, n)| (columns, n as f64 / rows.len() as f64))
+}
+
+/// Comilla que aparece abriendo un campo (inicio de línea o después de un separador)
+fn detect_quote(sample: &[u8]) -> Option<u8> {
+    QUOTES.into_iter()
+        .map(|q| {
+            let opening = sample.windows(2)
+                .filter(|w| w[1] == q && (w[0] == b'\n' || DELIMITERS.contains(&w[0])))
+                .count();
+            (q, opening + usize::from(sample.first() == Some(&q)))
+        })
+        .filter(|(_, n)| *n > 0)
+        .max_by_key(|(_, n)| *n)
+        .map(|(q, _)| q)
+}
+
+/// La primera fila es header si sus valores son textos distintos y no vacíos y
+/// alguna columna tiene números en los datos (o ningún valor del header se repite debajo)
+fn detect_header(rows: &[Vec<String>]) -> bool {
+    let Some((first, data)) = rows.split_first() else {
+        return false;
+    };
+    let mut unique = HashSet::new();
+    let textual = first.iter().all(|v| {
+        let v = v.trim();
+        !v.is_empty() && !is_number(v) && unique.insert(v)
+    });
+    if !textual {
+        return false;
+    }
+    if data.is_empty() {
+        return true;
+    }
+
+    let numeric_column = (0..first.len()).any(|i| {
+        let values: Vec<&str> = data.iter().filter_map(|r| r.get(i)).map(|v| v.trim()).filter(|v| !v.is_empty()).collect();
+        !values.is_empty() && values.iter().all(|v| is_number(v))
+    });
+    let repeated = first.iter().enumerate()
+        .any(|(i, h)| data.iter().any(|r| r.get(i).map(|v| v.trim()) == Some(h.trim())));
+    numeric_column || !repeated
+}
+
+/// Separador para mostrar o pasar a `--delimiter` (la tabulación como `\t`)
+pub fn display_delimiter(delimiter: char) -> String {
+    if delimiter == '\t' { "\\t".to_string() } else { delimiter.to_string() }
+}
+
+/// `--delimiter auto`: detecta el separador sobre la entrada del comando (el primer
+/// argumento que sea un CSV existente, o si no el primer archivo existente)
+/// Sin archivo que muestrear (stdin, planes de pipeline) queda la coma, con advertencia
+pub fn resolve_auto_delimiter(command_args: &[String]) -> Result<(), CsvToolsError> {
+    let files: Vec<&String> = command_args.iter().skip(2)
+        .filter(|a| !a.starts_with('-') && Path::new(a.as_str()).is_file())
+        .collect();
+    let input = files.iter().find(|a| is_csv_name(a)).or(files.first());
+
+    let Some(input) = input else {
+        diagnostics::warn("--delimiter auto: no input file to sample, using ','");
+        return Ok(());
+    };
+    let dialect = sniff_file(input, DEFAULT_SAMPLE_BYTES)?;
+    config::set_detected_delimiter(dialect.delimiter as u8);
+    verbose!("🔍 Delimiter: auto → '{}' (sniffed from {}, {} columns)",
+        display_delimiter(dialect.delimiter), input, dialect.columns);
+    Ok(())
+}
+
+fn is_number(value: &str) -> bool {
+    value.parse::<f64>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_dialects() {
+        let semicolon = sniff(b"Cuil;Nombre;Importe\n20123;\"Perez; Juan\";10.5\n20124;Gomez;7\n");
+        assert_eq!(semicolon.delimiter, ';');
+        assert_eq!(semicolon.quote, Some('"'));
+        assert!(semicolon.has_header);
+        assert_eq!(semicolon.columns, 3);
+        assert_eq!(semicolon.consistency, 1.0);
+
+        let tsv = sniff(b"1\t2\t3\n4\t5\t6\n");
+        assert_eq!(tsv.delimiter, '\t');
+        assert!(!tsv.has_header);
+        assert_eq!(tsv.quote, None);
+
+        // Comas dentro de los valores no ganan a un separador estable
+        let pipe = sniff(b"a|b\n1,5|x,y,z\n2|w\n");
+        assert_eq!(pipe.delimiter, '|');
+        assert_eq!(pipe.columns, 2);
+    }
+}
@@ -0,0 +1,181 @@
+// Dedup de líneas con memoria acotada (merge_dedup --max-memory):
+// 1. Mientras el conjunto de vistas entra en el límite, las líneas nuevas se escriben
+//    en el orden de entrada, como siempre
+// 2. Al llegar al límite el conjunto se congela: las líneas que ya están en él se
+//    descartan y el resto se reparte por hash en BUCKETS archivos temporales (las
+//    repeticiones de una línea caen siempre en el mismo)
+// 3. `finish` deduplica cada bucket por separado con un conjunto nuevo; si un bucket
+//    tampoco entra, se vuelve a partir con otra semilla (recursivo), así que la memoria
+//    nunca supera el límite sin importar el tamaño de la entrada
+// Las líneas derivadas a buckets se escriben al final, agrupadas por bucket
+// Cada línea puede llevar un sufijo que no participa de la comparación (las columnas de
+// procedencia de merge_dedup); en el bucket se guarda como "<largo> <sufijo><línea>"
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::collation::Collation;
+use crate::dedup::SeenLines;
+use crate::error::CsvToolsError;
+use crate::sketch::seeded_hash;
+use crate::temp_files::TempFiles;
+
+/// Buckets en que se parte el resto de la entrada al superar el límite
+const BUCKETS: usize = 64;
+/// Costo aproximado por línea guardada además de sus bytes (String + entrada del HashSet)
+const EXACT_LINE_OVERHEAD: usize = std::mem::size_of::<String>() + 24;
+/// Costo aproximado por huella de 128 bits (entrada del HashSet con su factor de carga)
+const DIGEST_OVERHEAD: usize = 32;
+
+/// Conjunto de líneas vistas con derrame a disco al superar `limit` bytes estimados
+pub struct SpillingDedup {
+    seen: SeenLines,
+    used: usize,
+    limit: usize,
+    collation: Collation,
+    /// Nombre de los temporales de este nivel ("bucket_3" → "bucket_3_7" al volver a partir)
+    prefix: String,
+    depth: u64,
+    buckets: Vec<(PathBuf, BufWriter<File>)>,
+    spilled_lines: usize,
+}
+
+impl SpillingDedup {
+    pub fn new(seen: SeenLines, limit: usize, collation: Collation) -> Self {
+        SpillingDedup::level(seen, limit, collation, "bucket".to_string(), 0)
+    }
+
+    fn level(seen: SeenLines, limit: usize, collation: Collation, prefix: String, depth: u64) -> Self {
+        SpillingDedup { seen, used: 0, limit, collation, prefix, depth, buckets: Vec::new(), spilled_lines: 0 }
+    }
+
+    /// Registra la línea; devuelve true si es nueva y hay que escribirla ya. Después de
+    /// superar el límite las líneas nuevas van a un bucket (y `finish` las escribe
+    /// seguidas de `suffix`)
+    pub fn insert(&mut self, line: &str, suffix: &str, temp_files: &mut TempFiles) -> Result<bool, CsvToolsError> {
+        let key = self.collation.dedup_key(line);
+        if self.buckets.is_empty() {
+            if !self.seen.insert(&key) {
+                return Ok(false);
+            }
+            self.used += match &self.seen {
+                SeenLines::Exact(_) => key.len() + EXACT_LINE_OVERHEAD,
+                SeenLines::Hashed(_) => DIGEST_OVERHEAD,
+                SeenLines::Bloom { .. } => 0,
+            };
+            if self.used >= self.limit {
+                self.open_buckets(temp_files)?;
+            }
+            return Ok(true);
+        }
+
+        if self.seen.contains(&key) {
+            return Ok(false);
+        }
+        let bucket = (seeded_hash(key.as_bytes(), self.depth) % BUCKETS as u64) as usize;
+        let writer = &mut self.buckets[bucket].1;
+        write!(writer, "{} {}", suffix.len(), suffix)?;
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")?;
+        self.spilled_lines += 1;
+        Ok(false)
+    }
+
+    /// true desde que se superó el límite (las líneas nuevas ya no se escriben en orden)
+    pub fn spilled(&self) -> bool {
+        !self.buckets.is_empty()
+    }
+
+    /// Líneas derivadas a buckets hasta ahora (con repetidas: se resuelven en `finish`)
+    pub fn spilled_lines(&self) -> usize {
+        self.spilled_lines
+    }
+
+    /// Conjunto de líneas vistas antes del límite (para estadísticas)
+    pub fn seen(&self) -> &SeenLines {
+        &self.seen
+    }
+
+    fn open_buckets(&mut self, temp_files: &mut TempFiles) -> Result<(), CsvToolsError> {
+        for n in 0..BUCKETS {
+            let path = temp_files.path(&format!("{}_{}.txt", self.prefix, n));
+            let writer = BufWriter::with_capacity(1 << 16, File::create(&path)?);
+            self.buckets.push((path, writer));
+        }
+        Ok(())
+    }
+
+    /// Deduplica los buckets y escribe sus líneas nuevas en `output`; devuelve cuántas
+    /// escribió. Cada bucket se resuelve con un conjunto vacío del mismo tipo
+    pub fn finish(self, output: &mut dyn Write, temp_files: &mut TempFiles) -> Result<usize, CsvToolsError> {
+        let empty_seen = match &self.seen {
+            SeenLines::Exact(_) | SeenLines::Bloom { .. } => SeenLines::exact,
+            SeenLines::Hashed(_) => SeenLines::hashed,
+        };
+        let SpillingDedup { seen, limit, collation, prefix, depth, buckets, .. } = self;
+        // El conjunto congelado ya no hace falta: su memoria queda para los buckets
+        drop(seen);
+
+        let mut written = 0;
+        for (n, (path, mut writer)) in buckets.into_iter().enumerate() {
+            writer.flush()?;
+            drop(writer);
+
+            let mut bucket = SpillingDedup::level(empty_seen(), limit, collation, format!("{}_{}", prefix, n), depth + 1);
+            for entry in BufReader::with_capacity(1 << 16, File::open(&path)?).lines() {
+                let entry = entry?;
+                let (suffix, line) = split_entry(&entry)?;
+                if bucket.insert(line, suffix, temp_files)? {
+                    output.write_all(line.as_bytes())?;
+                    output.write_all(suffix.as_bytes())?;
+                    output.write_all(b"\n")?;
+                    written += 1;
+                }
+            }
+            written += bucket.finish(output, temp_files)?;
+            std::fs::remove_file(&path)?;
+        }
+        Ok(written)
+    }
+}
+
+/// Separa una entrada de bucket "<largo> <sufijo><línea>" en (sufijo, línea)
+fn split_entry(entry: &str) -> Result<(&str, &str), CsvToolsError> {
+    let corrupt = || CsvToolsError::from(format!("Corrupt dedup bucket entry: {}", entry));
+    let (len, rest) = entry.split_once(' ').ok_or_else(corrupt)?;
+    let len: usize = len.parse().map_err(|_| corrupt())?;
+    if !rest.is_char_boundary(len) {
+        return Err(corrupt());
+    }
+    Ok(rest.split_at(len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spills_and_resolves_buckets() {
+        let mut temp_files = TempFiles::new(&std::env::temp_dir());
+        // Límite de ~3 líneas: el resto pasa por buckets (y se vuelven a partir)
+        let mut dedup = SpillingDedup::new(SeenLines::exact(), 3 * (2 + EXACT_LINE_OVERHEAD), Collation::Binary);
+        let mut output = Vec::new();
+        let lines: Vec<String> = (0..200).map(|i| format!("{:02}", i % 50)).collect();
+        for line in &lines {
+            if dedup.insert(line, "", &mut temp_files).unwrap() {
+                writeln!(output, "{}", line).unwrap();
+            }
+        }
+        assert!(dedup.spilled());
+        let in_order = output.len() / 3;
+        let from_buckets = dedup.finish(&mut output, &mut temp_files).unwrap();
+        assert_eq!((in_order, from_buckets), (3, 47));
+
+        let mut written: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+        assert_eq!(&written[..3], &["00", "01", "02"]);
+        written.sort();
+        written.dedup();
+        assert_eq!(written.len(), 50);
+    }
+}
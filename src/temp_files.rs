@@ -0,0 +1,99 @@
+// Archivos temporales de una corrida: nombres únicos por proceso dentro del workdir
+// (dos corridas concurrentes en el mismo host no se pisan) y borrado al terminar,
+// también cuando el comando falla
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::info;
+
+// Distingue registros creados dentro del mismo proceso
+static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+/// Registro de temporales de la corrida; se borran al salir de scope
+pub struct TempFiles {
+    dir: PathBuf,
+    run_id: String,
+    paths: Vec<PathBuf>,
+}
+
+impl TempFiles {
+    pub fn new(dir: &Path) -> Self {
+        TempFiles {
+            dir: dir.to_path_buf(),
+            run_id: format!(
+                "{}_{}_{}",
+                chrono::Local::now().format("%Y%m%d%H%M%S"),
+                std::process::id(),
+                SEQUENCE.fetch_add(1, Ordering::Relaxed)
+            ),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Workdir por defecto: el directorio del archivo de salida (mismo disco que se controla)
+    pub fn for_output(output_path: &str) -> Self {
+        let parent = Path::new(output_path).parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        Self::new(parent)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Ruta única para el temporal `name` (ej. "merged.csv") registrada para limpieza
+    pub fn path(&mut self, name: &str) -> PathBuf {
+        let path = self.dir.join(format!("csv_tools_tmp_{}_{}", self.run_id, name));
+        self.paths.push(path.clone());
+        path
+    }
+
+    /// Registra un temporal existente (el de una corrida anterior que se retoma)
+    pub fn adopt(&mut self, path: PathBuf) -> PathBuf {
+        self.paths.push(path.clone());
+        path
+    }
+
+    /// Deja de borrar `path` al terminar (se conserva para retomar con --resume)
+    pub fn keep(&mut self, path: &Path) {
+        self.paths.retain(|p| p != path);
+    }
+}
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            if !path.exists() {
+                continue;
+            }
+            match std::fs::remove_file(path) {
+                Ok(()) => info!("🗑️  Temporary file removed: {}", path.display()),
+                Err(e) => eprintln!("⚠️  Could not remove temporary file {}: {}", path.display(), e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_names_and_cleanup() {
+        let dir = std::env::temp_dir();
+        let (first, second);
+        {
+            let mut a = TempFiles::new(&dir);
+            let mut b = TempFiles::new(&dir);
+            first = a.path("merged.csv");
+            second = b.path("merged.csv");
+            assert_ne!(first, second);
+            std::fs::write(&first, "x").unwrap();
+            std::fs::write(&second, "y").unwrap();
+        }
+        assert!(!first.exists());
+        assert!(!second.exists());
+    }
+}
@@ -0,0 +1,40 @@
+// Utilidades de los tests unitarios: un directorio temporal por test con los CSV de
+// entrada y salida, que se borra al terminar (también si el test falla)
+
+use std::path::PathBuf;
+
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `csv_tools_<name>_<pid>` en el temporal del sistema; `name` distinto por test
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("csv_tools_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+
+    /// Ruta del directorio (para los comandos que reciben un directorio o un glob)
+    pub fn root(&self) -> String {
+        self.0.to_string_lossy().into_owned()
+    }
+
+    pub fn path(&self, name: &str) -> String {
+        self.0.join(name).to_string_lossy().into_owned()
+    }
+
+    /// Escribe `name` y devuelve su ruta
+    pub fn write(&self, name: &str, content: impl AsRef<[u8]>) -> String {
+        std::fs::write(self.0.join(name), content).unwrap();
+        self.path(name)
+    }
+
+    pub fn read(&self, name: &str) -> String {
+        std::fs::read_to_string(self.0.join(name)).unwrap_or_else(|e| panic!("{}: {}", name, e))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.0).ok();
+    }
+}
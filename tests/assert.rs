@@ -0,0 +1,23 @@
+// assert de punta a punta: 0 si pasan todas las aserciones, 1 con el motivo en stderr
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_assert_passes_and_fails_with_reasons() {
+    let dir = TempDir::new("assert");
+    dir.write("in.csv", "Cuil,IdTransmit\n1,10\nCuil,IdTransmit\n2,20\n");
+
+    let output = dir.run(&["assert", "in.csv", "--has-columns", "Cuil,IdTransmit", "--row-count-min", "2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("2 assertion(s) passed"));
+
+    let output = dir.run(&["assert", "in.csv", "--has-columns", "Cuil,Periodo", "--row-count-min", "3", "--no-duplicate-header"]);
+    assert_eq!(output.status.code(), Some(1));
+    let err = stderr(&output);
+    assert!(err.contains("missing columns: Periodo"), "{}", err);
+    assert!(err.contains("row count 2 < minimum 3"), "{}", err);
+    assert!(err.contains("header row repeated 1 time(s) in body (lines 3)"), "{}", err);
+    assert!(err.contains("3 of 3 assertion(s) failed"), "{}", err);
+}
@@ -0,0 +1,45 @@
+// coerce de punta a punta: las filas con valores que no se pueden convertir se conservan
+// tal cual (salvo --drop-invalid) y cada error va al log y a --error-report
+
+mod common;
+
+use common::{stderr, TempDir};
+
+const SCHEMA: &str = "name: test\ncolumns:\n  - name: id\n    type: int\n  - name: amount\n    type: number\n";
+
+#[test]
+fn test_coerce_keeps_invalid_rows_and_reports_errors() {
+    let dir = TempDir::new("coerce");
+    dir.write("schema.yaml", SCHEMA);
+    std::fs::write(dir.path("in.csv"), b"id,amount\n1,2.5\nx,2\n3,\xff\n").unwrap();
+
+    let output = dir.run(&["--error-report", "errors.csv", "coerce", "in.csv", "out.csv", "--schema", "schema.yaml"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "id,amount\n1,2.5\nx,2\n3,\u{fffd}\n");
+
+    let log = dir.read("out.csv.coercion_errors.log");
+    assert!(log.contains("[LINE 3] ❌ COERCION_ERROR | Field='id' | Value='x'"), "{}", log);
+    assert!(log.contains("[LINE 4] ❌ PARSE_ERROR"), "{}", log);
+    let report = dir.read("errors.csv");
+    assert!(report.contains("COERCION_ERROR") && report.contains("PARSE_ERROR"), "{}", report);
+
+    let output = dir.run(&["coerce", "in.csv", "out.csv", "--schema", "schema.yaml", "--drop-invalid"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "id,amount\n1,2.5\n");
+}
+
+#[test]
+fn test_coerce_normalizes_booleans_to_the_configured_pair() {
+    let dir = TempDir::new("coerce_bool");
+    dir.write("schema.yaml", "name: test\ncolumns:\n  - name: activo\n    type: bool\n  - name: moroso\n    type: bool\n    bool_pair: [\"true\", \"false\"]\n");
+    dir.write("in.csv", "activo,moroso\nsi,N\nN,Y\n1,no\ntalvez,0\n");
+
+    let output = dir.run(&["coerce", "in.csv", "out.csv", "--schema", "schema.yaml", "--bool-pair", "S,N"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "activo,moroso\nS,false\nN,true\nS,false\ntalvez,false\n");
+    assert!(dir.read("out.csv.coercion_errors.log").contains("Field='activo' | Value='talvez'"));
+
+    let output = dir.run(&["coerce", "in.csv", "out.csv", "--schema", "schema.yaml", "--bool-pair", "SN"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Invalid --bool-pair 'SN'"), "{}", stderr(&output));
+}
@@ -0,0 +1,66 @@
+// dedup_by_keys, overlap y dup-report de punta a punta: primera o última fila por clave
+// sobre cualquier header, claves compartidas entre dos archivos y claves más repetidas
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_dedup_by_keys_keep_first_and_last() {
+    let dir = TempDir::new("dedup_by_keys");
+    dir.write("in.csv", "Cuil,IdTransmit,v\n1,10,a\n2,10,b\n1,10,c\n1,11,d\n");
+
+    let output = dir.run(&["dedup_by_keys", "in.csv", "first.csv", "--keys", "Cuil,IdTransmit"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("first.csv"), "Cuil,IdTransmit,v\n1,10,a\n2,10,b\n1,11,d\n");
+    assert!(stdout(&output).contains("Duplicates removed: 1"), "{}", stdout(&output));
+
+    let output = dir.run(&["dedup_by_keys", "in.csv", "last.csv", "--keys", "Cuil,IdTransmit", "--keep", "last"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("last.csv"), "Cuil,IdTransmit,v\n2,10,b\n1,10,c\n1,11,d\n");
+
+    let output = dir.run(&["dedup_by_keys", "in.csv", "out.csv", "--keys", "Cuil,Periodo"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Key column 'Periodo' not found in header"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_overlap_counts_shared_keys() {
+    let dir = TempDir::new("overlap");
+    dir.write("a.csv", "Cuil,IdTransmit,v\n1,10,a\n2,10,b\n3,10,c\n");
+    dir.write("b.csv", "IdTransmit,Cuil\n10,2\n10,2\n10,4\n");
+
+    let output = dir.run(&["overlap", "a.csv", "b.csv", "--keys", "Cuil,IdTransmit"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    assert!(out.contains("Keys in both: 1 (33.33% of A's keys, 50.00% of B's keys)"), "{}", out);
+    assert!(out.contains("Rows of B whose key is in A: 2 (66.67%)"), "{}", out);
+    assert!(out.contains("Cuil=2, IdTransmit=10 (line 3 → line 2)"), "{}", out);
+
+    // Con --format json stdout es solo el resumen
+    let output = dir.run(&["--format", "json", "overlap", "a.csv", "b.csv", "--keys", "Cuil,IdTransmit"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json["shared_keys"], 1);
+    assert_eq!(json["shared_rows_b"], 2);
+}
+
+#[test]
+fn test_dup_report_lists_top_keys() {
+    let dir = TempDir::new("dup_report");
+    dir.write("in.csv", "Cuil,IdTransmit,v\n1,10,a\n2,10,b\n1,10,c\n1,10,a\n");
+
+    let output = dir.run(&["dup-report", "in.csv", "--keys", "Cuil,IdTransmit"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    assert!(out.contains("1. Cuil=1, IdTransmit=10 → 3 rows"), "{}", out);
+    assert!(out.contains("lines: 2, 4, 5"), "{}", out);
+
+    let output = dir.run(&["--quiet", "dup-report", "in.csv", "--keys", "Cuil,IdTransmit"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).is_empty(), "{}", stdout(&output));
+
+    let output = dir.run(&["dup-report", "in.csv", "--keys", "Cuil,Periodo"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Key column 'Periodo' not found in header"), "{}", stderr(&output));
+}
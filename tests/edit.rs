@@ -0,0 +1,44 @@
+// patch y delete-keys de punta a punta: la salida, el log de auditoría/eliminadas y
+// las claves sin coincidencia (advertencia; error con --strict)
+
+mod common;
+
+use common::{stderr, TempDir};
+
+#[test]
+fn test_patch_applies_corrections_and_audits_them() {
+    let dir = TempDir::new("patch");
+    dir.write("in.csv", "Cuil,IdTransmit,Nombre,IdRegion\n1,10,ana,5\n2,10,bob,6\n");
+    dir.write("fixes.csv", "Cuil,IdTransmit,column,value\n1,10,Nombre,ANA\n1,10,IdRegion,5\n9,10,Nombre,X\n");
+
+    let output = dir.run(&["patch", "in.csv", "out.csv", "--patches", "fixes.csv", "--key", "Cuil,IdTransmit"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "Cuil,IdTransmit,Nombre,IdRegion\n1,10,ANA,5\n2,10,bob,6\n");
+    assert_eq!(dir.read("out.csv.patch_audit.csv"), concat!(
+        "Line,Key,Column,OldValue,NewValue,Status\n",
+        "2,1|10,Nombre,ana,ANA,APPLIED\n",
+        "2,1|10,IdRegion,5,5,UNCHANGED\n",
+        ",9|10,Nombre,,X,NOT_FOUND\n",
+    ));
+    assert!(stderr(&output).contains("1 corrections did not match any row"));
+
+    let output = dir.run(&["--strict", "patch", "in.csv", "out.csv", "--patches", "fixes.csv", "--key", "Cuil,IdTransmit"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_delete_keys_removes_listed_keys_and_logs_them() {
+    let dir = TempDir::new("delete_keys");
+    dir.write("in.csv", "Cuil,Nombre\n1,ana\n2,bob\n1,ana bis\n3,carla\n");
+    dir.write("blacklist.txt", "Cuil\n# pedidos de supresión\n1\n7\n");
+
+    let output = dir.run(&["delete-keys", "in.csv", "out.csv", "--keys-file", "blacklist.txt", "--key-column", "Cuil", "--log", "removed.csv"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "Cuil,Nombre\n2,bob\n3,carla\n");
+    assert_eq!(dir.read("removed.csv"), "Cuil,Nombre\n1,ana\n1,ana bis\n");
+    assert!(stderr(&output).contains("1 keys were not found in the input (7)"), "{}", stderr(&output));
+
+    let output = dir.run(&["delete-keys", "in.csv", "out.csv", "--keys-file", "blacklist.txt", "--key-column", "Dni"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Column 'Dni' not found in header"), "{}", stderr(&output));
+}
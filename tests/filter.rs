@@ -0,0 +1,61 @@
+// filter, filter_regex y filter_dates de punta a punta: filas con la columna igual al
+// valor, que coinciden con el patrón o con la fecha en el rango (o las demás con --invert)
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_filter_and_invert() {
+    let dir = TempDir::new("filter");
+    dir.write("in.csv", "Cuil,IdEntidad\n1,1234\n2,55\n3,1234\n");
+
+    let output = dir.run(&["filter", "in.csv", "kept.csv", "IdEntidad", "1234"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("kept.csv"), "Cuil,IdEntidad\n1,1234\n3,1234\n");
+    assert!(stdout(&output).contains("Row filtering complete: 2 kept, 1 removed."));
+
+    let output = dir.run(&["filter", "in.csv", "rest.csv", "IdEntidad", "1234", "--invert"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("rest.csv"), "Cuil,IdEntidad\n2,55\n");
+    assert!(stdout(&output).contains("Row filtering complete: 1 kept, 2 removed."));
+
+    let output = dir.run(&["filter", "in.csv", "bad.csv", "Entidad", "1234", "--invert"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Column 'Entidad' not found in input file"), "{}", stderr(&output));
+    assert!(!dir.exists("bad.csv"));
+}
+
+#[test]
+fn test_filter_regex_and_usage_errors() {
+    let dir = TempDir::new("filter_regex");
+    dir.write("in.csv", "Cuil,Telefono\n1,011-4444\n2,n/a\n3,0351-555\n");
+
+    let output = dir.run(&["filter_regex", "in.csv", "bad.csv", "Telefono", "^[0-9-]+$", "--invert"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("bad.csv"), "Cuil,Telefono\n2,n/a\n");
+
+    let output = dir.run(&["filter_regex", "in.csv", "out.csv", "Tel", "^[0-9-]+$"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Column 'Tel' not found in input file"), "{}", stderr(&output));
+
+    let output = dir.run(&["filter_regex", "in.csv", "out.csv", "Telefono", "[0-9"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Invalid pattern '[0-9'"), "{}", stderr(&output));
+    assert!(!dir.exists("out.csv"));
+}
+
+#[test]
+fn test_filter_dates_range_and_unknown_column() {
+    let dir = TempDir::new("filter_dates");
+    dir.write("in.csv", "Cuil,FechaIngreso\n1,2024-01-15\n2,2024-03-01\n3,\n4,15/02/2024\n");
+
+    let output = dir.run(&["filter_dates", "in.csv", "out.csv", "FechaIngreso", "--from", "2024-02-01", "--to", "2024-02-29"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "Cuil,FechaIngreso\n4,15/02/2024\n");
+
+    let output = dir.run(&["filter_dates", "in.csv", "bad.csv", "Fecha", "--from", "2024-02-01"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Column 'Fecha' not found in input file"), "{}", stderr(&output));
+    assert!(!dir.exists("bad.csv"));
+}
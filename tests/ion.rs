@@ -0,0 +1,23 @@
+// to_ion de punta a punta: un item Ion por fila, Type N como número según el modelo
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_to_ion_types_items_from_the_model() {
+    let dir = TempDir::new("to_ion");
+    dir.write("in.csv", "Cuil,Cuit,FechaIngreso,FechaBaja\n020111,1.5E3,2020-01-01,\"dice \"\"hola\"\"\"\n,30111,2020-01-01,\n20333,abc,2020-01-01,\n");
+
+    let output = dir.run(&["to_ion", "in.csv", "out.ion", "--model", "siisa_empleadores_relaciones"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.ion"), "$ion_1_0 {Item:{Cuil:20111,Cuit:1.5d3,FechaIngreso:\"2020-01-01\",FechaBaja:\"dice \\\"hola\\\"\"}}\n");
+    let out = stdout(&output);
+    assert!(out.contains("Items written: 1"), "{}", out);
+    assert!(out.contains("Skipped (empty key):      1"), "{}", out);
+    assert!(out.contains("Skipped (invalid Type N): 1"), "{}", out);
+
+    let output = dir.run(&["--strict", "to_ion", "in.csv", "out.ion", "--model", "siisa_empleadores_relaciones"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("2 rows were not written"), "{}", stderr(&output));
+}
@@ -0,0 +1,26 @@
+// to_jsonl de punta a punta: un objeto JSON por fila, Type N como número con --model
+
+mod common;
+
+use common::{stderr, TempDir};
+
+#[test]
+fn test_to_jsonl_with_and_without_model() {
+    let dir = TempDir::new("to_jsonl");
+    dir.write("in.csv", "Cuil,Cuit,FechaIngreso,FechaBaja\n20111,30111,2020-01-01,\n");
+
+    let output = dir.run(&["to_jsonl", "in.csv", "out.jsonl"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.jsonl"), "{\"Cuil\":\"20111\",\"Cuit\":\"30111\",\"FechaIngreso\":\"2020-01-01\",\"FechaBaja\":\"\"}\n");
+
+    let output = dir.run(&["to_jsonl", "in.csv", "typed.jsonl", "--model", "siisa_empleadores_relaciones"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(dir.read("typed.jsonl").starts_with("{\"Cuil\":20111,\"Cuit\":30111,"), "{}", dir.read("typed.jsonl"));
+
+    let output = dir.run(&["to_jsonl", "in.csv", "bad.jsonl", "--model", "morosos"]);
+    assert_eq!(output.status.code(), Some(2));
+    let err = stderr(&output);
+    assert!(err.contains("Unknown model type: 'morosos'"), "{}", err);
+    assert!(err.contains("Supported: siisa_morosos"), "{}", err);
+    assert!(!dir.exists("bad.jsonl"));
+}
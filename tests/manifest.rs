@@ -0,0 +1,34 @@
+// --manifest de punta a punta: lista lo que creó el comando, también si falló
+
+mod common;
+
+use common::{stderr, TempDir};
+
+fn manifest(dir: &TempDir) -> serde_json::Value {
+    serde_json::from_str(&dir.read("run.json")).unwrap()
+}
+
+#[test]
+fn test_manifest_lists_created_artifacts() {
+    let dir = TempDir::new("manifest");
+    dir.write("in.csv", "Cuil,Nombre\n1,ana\n2,bob\n3,carla\n");
+    dir.write("keys.txt", "2\n");
+
+    let output = dir.run(&["--manifest", "run.json", "delete-keys", "in.csv", "out.csv", "--keys-file", "keys.txt", "--key-column", "Cuil"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let json = manifest(&dir);
+    assert_eq!(json["command"], "delete-keys");
+    assert_eq!(json["status"], "success");
+    let artifacts: Vec<(&str, &str, u64)> = json["artifacts"].as_array().unwrap().iter()
+        .map(|a| (a["path"].as_str().unwrap(), a["kind"].as_str().unwrap(), a["rows"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(artifacts, vec![("out.csv", "output", 2), ("out.csv.removed.csv", "report", 1)]);
+    assert_eq!(json["artifacts"][0]["sha256"].as_str().unwrap().len(), 64);
+
+    let output = dir.run(&["--manifest", "run.json", "delete-keys", "missing.csv", "out2.csv", "--keys-file", "keys.txt", "--key-column", "Cuil"]);
+    assert_eq!(output.status.code(), Some(3));
+    let json = manifest(&dir);
+    assert_eq!(json["status"], "failed");
+    assert!(json["error"].is_string(), "{}", json);
+    assert!(json["artifacts"].as_array().unwrap().is_empty());
+}
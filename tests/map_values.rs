@@ -0,0 +1,32 @@
+// map_values de punta a punta: reemplazo de valores de una columna según el archivo de
+// mapeo, valores sin mapeo y columna inexistente
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_map_values_replaces_and_reports_unmatched() {
+    let dir = TempDir::new("map_values");
+    dir.write("in.csv", "Cuil,IdRegion\n1,N\n2,S\n3,X\n4,\n");
+    dir.write("regions.csv", "old,new\nN,1\nS,2\n");
+
+    let output = dir.run(&["map_values", "in.csv", "out.csv", "IdRegion", "--mapping", "regions.csv"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "Cuil,IdRegion\n1,1\n2,2\n3,X\n4,\n");
+    assert!(stdout(&output).contains("Values replaced: 2"), "{}", stdout(&output));
+    assert!(stderr(&output).contains("1 rows had a value not in the mapping"), "{}", stderr(&output));
+
+    let output = dir.run(&["map_values", "in.csv", "blank.csv", "IdRegion", "--mapping", "regions.csv", "--unmatched", "blank"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("blank.csv"), "Cuil,IdRegion\n1,1\n2,2\n3,\n4,\n");
+
+    let output = dir.run(&["map_values", "in.csv", "err.csv", "IdRegion", "--mapping", "regions.csv", "--unmatched", "error"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Line 4: value 'X' in IdRegion is not in mapping"), "{}", stderr(&output));
+
+    let output = dir.run(&["map_values", "in.csv", "bad.csv", "Region", "--mapping", "regions.csv"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Column 'Region' not found in input file"), "{}", stderr(&output));
+    assert!(!dir.exists("bad.csv"));
+}
@@ -0,0 +1,153 @@
+// merge_dedup de punta a punta: cada modo deja la misma salida deduplicada (y --report
+// de merge / merge_dedup / external_dedup)
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+const DEDUPED: &str = "id,v\n1,a\n2,b\n3,c\n4,d\n5,e\n";
+
+/// Dos chunks con repetidas dentro del mismo archivo y entre archivos
+fn write_parts(dir: &TempDir) {
+    dir.write("part_1.csv", "id,v\n1,a\n2,b\n1,a\n3,c\n");
+    dir.write("part_2.csv", "id,v\n2,b\n4,d\n3,c\n5,e\n");
+}
+
+/// Header y filas ordenadas (los modos con shards o buckets no conservan el orden)
+fn sorted(csv: &str) -> String {
+    let mut lines: Vec<&str> = csv.lines().collect();
+    lines[1..].sort();
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+#[test]
+fn test_preview_reports_per_file_stats_and_writes_nothing() {
+    let dir = TempDir::new("merge_dedup_preview");
+    dir.write("a.csv", "id\n1\n2\n2\n");
+    dir.write("b.csv", "id\n1\n2\n3\n");
+
+    let output = dir.run(&["--format", "json", "merge_dedup", "*.csv", "out.csv", "--preview"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!dir.exists("out.csv"));
+
+    let summary: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(summary["records"], 6);
+    assert_eq!(summary["unique"], 3);
+    let files = summary["files"].as_array().unwrap();
+    assert_eq!(files[0]["file"], "a.csv");
+    assert_eq!(files[0]["duplicates_within_file"], 1);
+    assert_eq!(files[0]["duplicates_of_earlier_files"], 0);
+    assert_eq!(files[1]["unique"], 1);
+    assert_eq!(files[1]["duplicates_of_earlier_files"], 2);
+    assert_eq!(files[1]["most_overlapping_file"], serde_json::json!(["a.csv", 2]));
+
+    // En modo texto el detalle por archivo va por los mensajes de estado
+    let output = dir.run(&["merge_dedup", "*.csv", "out.csv", "--preview"]);
+    assert!(stdout(&output).contains("b.csv: 3 rows, 1 new, 0 repeated within the file, 2 already in earlier files"));
+    assert!(!dir.exists("out.csv"));
+
+    let output = dir.run(&["--quiet", "merge_dedup", "*.csv", "out.csv", "--preview"]);
+    assert!(stdout(&output).is_empty());
+}
+
+#[test]
+fn test_approx_bloom_dedups_and_reports_estimated_drops() {
+    let dir = TempDir::new("merge_dedup_bloom");
+    write_parts(&dir);
+
+    let output = dir.run(&["merge_dedup", "part_*.csv", "merged.csv", "--approx", "bloom", "--fpp", "0.001"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("merged.csv"), DEDUPED);
+    assert!(stdout(&output).contains("unique lines estimated dropped as false duplicates"));
+}
+
+#[test]
+fn test_hash_only_with_verification_pass() {
+    let dir = TempDir::new("merge_dedup_hash_only");
+    write_parts(&dir);
+
+    let output = dir.run(&["merge_dedup", "part_*.csv", "merged.csv", "--hash-only", "--verify-hashes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("merged.csv"), DEDUPED);
+    assert!(stdout(&output).contains("No digest collisions"));
+
+    // --verify-hashes solo tiene sentido con --hash-only
+    let output = dir.run(&["merge_dedup", "part_*.csv", "merged.csv", "--verify-hashes"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_max_memory_spills_to_buckets() {
+    let dir = TempDir::new("merge_dedup_max_memory");
+    write_parts(&dir);
+
+    // Un cap de 1 byte manda todo lo que sigue a la primera fila a los buckets en disco
+    let output = dir.run(&["merge_dedup", "part_*.csv", "merged.csv", "--max-memory", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Memory cap reached"));
+    assert_eq!(sorted(&dir.read("merged.csv")), DEDUPED);
+}
+
+#[test]
+fn test_sharded_threads_match_sequential_dedup() {
+    let dir = TempDir::new("merge_dedup_threads");
+    write_parts(&dir);
+
+    for extra in [&[][..], &["--hash-only"][..]] {
+        let mut args = vec!["merge_dedup", "part_*.csv", "merged.csv", "--threads", "4"];
+        args.extend_from_slice(extra);
+        let output = dir.run(&args);
+        assert!(output.status.success(), "{:?}: {}", extra, stderr(&output));
+        assert!(stdout(&output).contains("5 registros únicos"), "{:?}", extra);
+        assert_eq!(sorted(&dir.read("merged.csv")), DEDUPED, "{:?}", extra);
+    }
+}
+
+#[test]
+fn test_report_json_for_merge_dedup_and_external_dedup() {
+    let dir = TempDir::new("merge_dedup_report");
+    write_parts(&dir);
+
+    for command in ["merge_dedup", "external_dedup"] {
+        let output = dir.run(&[command, "part_*.csv", "merged.csv", "--report", "report.json"]);
+        assert!(output.status.success(), "{}: {}", command, stderr(&output));
+
+        let report: serde_json::Value = serde_json::from_str(&dir.read("report.json")).unwrap();
+        assert_eq!(report["command"], command);
+        assert_eq!(report["lines_read"], 8, "{}", command);
+        assert_eq!(report["lines_written"], 5, "{}", command);
+        assert_eq!(report["duplicates_removed"], 3, "{}", command);
+        let files = report["files"].as_array().unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["file"], "part_1.csv");
+        assert_eq!(files[0]["header"], "match");
+        assert_eq!(files[0]["lines"], 4);
+    }
+    assert_eq!(sorted(&dir.read("merged.csv")), DEDUPED);
+
+    // merge no deduplica: todas las filas y ningún duplicado removido
+    let output = dir.run(&["merge", "part_*.csv", "merged.csv", "--report", "report.json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let report: serde_json::Value = serde_json::from_str(&dir.read("report.json")).unwrap();
+    assert_eq!(report["command"], "merge");
+    assert_eq!(report["lines_written"], 8);
+    assert_eq!(report["duplicates_removed"], 0);
+}
+
+#[test]
+fn test_preview_counts_multiline_records_like_merge_dedup() {
+    let dir = TempDir::new("merge_dedup_preview_multiline");
+    dir.write("a.csv", "id,nota\n1,\"linea 1\nlinea 2\"\n2,x\n");
+    dir.write("b.csv", "id,nota\n1,\"linea 1\nlinea 2\"\n");
+
+    let output = dir.run(&["--format", "json", "merge_dedup", "*.csv", "out.csv", "--preview"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let summary: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(summary["records"], 3);
+    assert_eq!(summary["unique"], 2);
+    assert_eq!(summary["files"][1]["duplicates_of_earlier_files"], 1);
+
+    let output = dir.run(&["merge_dedup", "*.csv", "out.csv"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "id,nota\n1,\"linea 1\nlinea 2\"\n2,x\n");
+}
@@ -0,0 +1,30 @@
+// plan-partitions de punta a punta: plan JSON a stdout o a --output y columna clave
+// inexistente
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_plan_partitions_to_stdout_and_file() {
+    let dir = TempDir::new("plan_partitions");
+    let rows: String = (1..=100).map(|i| format!("{},x\n", i * 10)).collect();
+    dir.write("in.csv", &format!("Cuil,v\n{}", rows));
+
+    // Sin --output stdout es solo el plan
+    let output = dir.run(&["plan-partitions", "in.csv", "--key", "Cuil", "--workers", "4"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let plan: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(plan["key_type"], "number");
+    assert_eq!(plan["rows_scanned"], 100);
+    assert_eq!(plan["partitions"].as_array().unwrap().len(), 4);
+
+    let output = dir.run(&["plan-partitions", "in.csv", "--key", "Cuil", "--workers", "4", "--output", "plan.json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("✅ 4 partitions planned"), "{}", stdout(&output));
+    assert!(dir.read("plan.json").contains("\"range_semantics\""));
+
+    let output = dir.run(&["plan-partitions", "in.csv", "--key", "Cuit", "--workers", "4"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Key column 'Cuit' not found in header"), "{}", stderr(&output));
+}
@@ -0,0 +1,56 @@
+// prepare de punta a punta: validación, sanitización Type N y dedup por clave en una
+// pasada, con y sin particiones temporales
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+const INPUT: &str = "Cuil,Cuit,FechaIngreso,FechaBaja\n\
+    20111,30111,2020-01-01,\n\
+    \" 20222 \",30111,2021-01-01,\n\
+    20111,30111,2022-01-01,\n\
+    x,30111,2020-01-01,\n\
+    20333,30333\n";
+
+fn sorted_lines(content: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    lines[1..].sort();
+    lines
+}
+
+#[test]
+fn test_prepare_validates_sanitizes_and_dedups() {
+    let dir = TempDir::new("prepare");
+    dir.write("in.csv", INPUT);
+    let expected = vec!["Cuil,Cuit,FechaIngreso,FechaBaja", "20111,30111,2020-01-01,", "20222,30111,2021-01-01,"];
+
+    let output = dir.run(&["prepare", "in.csv", "out.csv", "--model", "siisa_empleadores_relaciones"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv").lines().collect::<Vec<_>>(), expected);
+    let out = stdout(&output);
+    assert!(out.contains("Wrong column count:    1"), "{}", out);
+    assert!(out.contains("Invalid Type N:        1"), "{}", out);
+    assert!(out.contains("Duplicates removed:    1"), "{}", out);
+    assert!(stderr(&output).contains("2 invalid records were skipped"));
+    let log = dir.read("out.csv.prepare_errors.log");
+    assert!(log.contains("INVALID_NUMERIC") && log.contains("IRREPARABLE_STRUCTURE"), "{}", log);
+
+    // Con particiones el orden de salida cambia, no el contenido
+    let output = dir.run(&["prepare", "in.csv", "parts.csv", "--model", "siisa_empleadores_relaciones", "--partitions", "3"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(sorted_lines(&dir.read("parts.csv")), expected);
+
+    dir.write("shuffled.csv", "Cuit,Cuil,FechaIngreso,FechaBaja\n30111,20111,2020-01-01,\n");
+    let output = dir.run(&["prepare", "shuffled.csv", "out.csv", "--model", "siisa_empleadores_relaciones"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Header columns are not in model order"), "{}", stderr(&output));
+
+    let output = dir.run(&["prepare", "in.csv", "bad.csv", "--model", "empleadores_relaciones"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Unknown model type: empleadores_relaciones"), "{}", stderr(&output));
+
+    let output = dir.run(&["prepare", "in.csv", "bad.csv", "--model", "siisa_empleadores_relaciones", "--dedup-keys", "Cuil,IdTransmit"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Dedup key column 'IdTransmit' not found in header"), "{}", stderr(&output));
+    assert!(!dir.exists("bad.csv"));
+}
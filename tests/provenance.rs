@@ -0,0 +1,43 @@
+// --add-source-column / --add-line-column de merge y merge_dedup: una vez por registro,
+// aunque un campo entre comillas ocupe varias líneas
+
+mod common;
+
+use common::{stderr, TempDir};
+
+fn write_parts(dir: &TempDir) {
+    dir.write("part_1.csv", "id,note\n1,\"a\nb\"\n2,c\n");
+    dir.write("part_2.csv", "id,note\n2,c\n3,\"x\ny\"\n");
+}
+
+const MERGED: &str = "id,note,File,Line\n1,\"a\nb\",part_1.csv,2\n2,c,part_1.csv,4\n2,c,part_2.csv,2\n3,\"x\ny\",part_2.csv,3\n";
+const DEDUPED: &str = "id,note,File,Line\n1,\"a\nb\",part_1.csv,2\n2,c,part_1.csv,4\n3,\"x\ny\",part_2.csv,3\n";
+
+#[test]
+fn test_merge_provenance_per_record() {
+    let dir = TempDir::new("provenance_merge");
+    write_parts(&dir);
+
+    let output = dir.run(&["merge", "part_*.csv", "out.csv", "--add-source-column", "File", "--add-line-column", "Line"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), MERGED);
+}
+
+#[test]
+fn test_merge_dedup_provenance_per_record() {
+    let dir = TempDir::new("provenance_merge_dedup");
+    write_parts(&dir);
+
+    let output = dir.run(&["merge_dedup", "part_*.csv", "out.csv", "--add-source-column", "File", "--add-line-column", "Line"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), DEDUPED);
+
+    // Con --threads la salida queda agrupada por shard: mismo contenido, otro orden
+    let output = dir.run(&["merge_dedup", "part_*.csv", "sharded.csv", "--threads", "2", "--add-source-column", "File", "--add-line-column", "Line"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let sharded = dir.read("sharded.csv");
+    assert_eq!(sharded.len(), DEDUPED.len());
+    for record in ["1,\"a\nb\",part_1.csv,2\n", "2,c,part_1.csv,4\n", "3,\"x\ny\",part_2.csv,3\n"] {
+        assert!(sharded.contains(record), "{:?} missing in {:?}", record, sharded);
+    }
+}
@@ -0,0 +1,25 @@
+// requote de punta a punta: mismos valores con cada estilo de comillas
+
+mod common;
+
+use common::{stderr, TempDir};
+
+#[test]
+fn test_requote_styles() {
+    let dir = TempDir::new("requote");
+    dir.write("in.csv", "Cuil,Nombre,Monto\n20111,\"PEREZ, JUAN\",10.5\n\"20222\",ANA,\n");
+
+    let requote = |style: &str| {
+        let output = dir.run(&["requote", "in.csv", "out.csv", "--style", style]);
+        assert!(output.status.success(), "{}: {}", style, stderr(&output));
+        dir.read("out.csv")
+    };
+    assert_eq!(requote("necessary"), "Cuil,Nombre,Monto\n20111,\"PEREZ, JUAN\",10.5\n20222,ANA,\n");
+    assert_eq!(requote("always"), "\"Cuil\",\"Nombre\",\"Monto\"\n\"20111\",\"PEREZ, JUAN\",\"10.5\"\n\"20222\",\"ANA\",\"\"\n");
+    assert_eq!(requote("non_numeric"), "\"Cuil\",\"Nombre\",\"Monto\"\n20111,\"PEREZ, JUAN\",10.5\n20222,\"ANA\",\"\"\n");
+
+    // Sin comillas el campo con coma rompería el registro
+    let output = dir.run(&["requote", "in.csv", "out.csv", "--style", "never"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Line 2 column 2 contains the delimiter"), "{}", stderr(&output));
+}
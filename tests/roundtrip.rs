@@ -0,0 +1,25 @@
+// verify-roundtrip de punta a punta: 0 si la reescritura es idéntica, 1 si cambia bytes
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_verify_roundtrip_via_requote() {
+    let dir = TempDir::new("roundtrip");
+    dir.write("same.csv", "id,name\n1,\"a,b\"\n2,c\n");
+
+    let output = dir.run(&["verify-roundtrip", "same.csv"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("✅ Byte-identical"));
+
+    // Comillas innecesarias: mismos valores, distinto formato
+    dir.write("quoted.csv", "id,name\n1,\"a\"\n2,c\n");
+    let output = dir.run(&["verify-roundtrip", "quoted.csv", "--via", "requote"]);
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    assert!(out.contains("first differing byte at offset 10"), "{}", out);
+    assert!(out.contains("Records differing: 0"), "{}", out);
+    assert!(out.contains("the difference is formatting only"), "{}", out);
+    assert!(stderr(&output).contains("Round-trip output differs from the input"));
+}
@@ -0,0 +1,32 @@
+// --add-row-number de punta a punta: la columna va una vez por registro, con la
+// línea donde empieza (un campo entre comillas puede ocupar varias líneas)
+
+mod common;
+
+use common::{stderr, TempDir};
+
+const MULTILINE: &str = "id,note\n1,\"a\nb\"\n2,c\n3,\"x\n\ny\"\n";
+const NUMBERED: &str = "id,note,SourceLine\n1,\"a\nb\",2\n2,c,4\n3,\"x\n\ny\",5\n";
+
+#[test]
+fn test_clean_numbers_multiline_records() {
+    let dir = TempDir::new("row_number_clean");
+    dir.write("in.csv", MULTILINE);
+
+    let output = dir.run(&["clean", "in.csv", "out.csv", "--add-row-number", "SourceLine"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), NUMBERED);
+
+    let output = dir.run(&["clean", "in.csv", "out.csv", "--add-row-number", "note"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_clean_invalid_lines_numbers_multiline_records() {
+    let dir = TempDir::new("row_number_invalid_lines");
+    dir.write("in.csv", MULTILINE);
+
+    let output = dir.run(&["clean_invalid_lines", "in.csv", "out.csv", "errors.csv", "--add-row-number", "SourceLine"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), NUMBERED);
+}
@@ -0,0 +1,65 @@
+// Comandos de schema de punta a punta: migrate, reorder-to-model y schema docs
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_migrate_renames_adds_drops_and_validates() {
+    let dir = TempDir::new("migrate");
+    dir.write("new.yaml", "name: v2\ncolumns:\n  - name: Cuil\n    type: int\n    key: partition\n  - name: NroDoc\n    type: int\n  - name: IdEntidad\n    type: int\n");
+    dir.write("map.yaml", "rename:\n  NroDocumento: NroDoc\ndefaults:\n  IdEntidad: \"0\"\ndrop: [Observaciones]\n");
+    dir.write("in.csv", "NroDocumento,Observaciones,Cuil\n111,x,20111\nabc,y,20222\n");
+
+    let output = dir.run(&["migrate", "in.csv", "out.csv", "--to", "new.yaml", "--mapping", "map.yaml"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "Cuil,NroDoc,IdEntidad\n20111,111,0\n20222,abc,0\n");
+    assert!(stderr(&output).contains("1 rows do not satisfy the target schema"), "{}", stderr(&output));
+    let log = dir.read("out.csv.migration_errors.log");
+    assert!(log.contains("[LINE 3] ❌ SCHEMA_MISMATCH | Column='NroDoc'"), "{}", log);
+
+    // Una clave nueva sin origen ni default no se puede completar
+    dir.write("map.yaml", "rename:\n  NroDocumento: NroDoc\ndefaults:\n  IdEntidad: \"0\"\n");
+    dir.write("in.csv", "NroDocumento\n111\n");
+    let output = dir.run(&["migrate", "in.csv", "out.csv", "--to", "new.yaml", "--mapping", "map.yaml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Key column 'Cuil' has no source column"), "{}", stderr(&output));
+    assert!(!stdout(&output).contains("Migration Summary"));
+}
+
+#[test]
+fn test_reorder_to_model_applies_model_order() {
+    let dir = TempDir::new("reorder");
+    dir.write("in.csv", "FechaBaja,Cuit,Extra,Cuil,FechaIngreso\n,30111,x,20111,2020-01-01\n");
+
+    let args = ["reorder-to-model", "in.csv", "out.csv", "--model", "siisa_empleadores_relaciones"];
+    let output = dir.run(&args);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Input has 1 column(s) not in model 'siisa_empleadores_relaciones': Extra"), "{}", stderr(&output));
+    assert!(!dir.exists("out.csv"));
+
+    let output = dir.run(&[&args[..], &["--drop-extra"]].concat());
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "Cuil,Cuit,FechaIngreso,FechaBaja\n20111,30111,2020-01-01,\n");
+    assert!(stderr(&output).contains("Dropping 1 column(s)"));
+}
+
+#[test]
+fn test_schema_docs_renders_markdown_with_examples() {
+    let dir = TempDir::new("schema_docs");
+    dir.write("morosos.yaml", "name: morosos\ncolumns:\n  - name: Cuil\n    type: int\n    key: partition\n  - name: IdRegion\n    type: int\n    nullable: true\n    default: \"0\"\n  - name: Nombre\n    type: string\n");
+    dir.write("sample.csv", "Cuil,Nombre\n20111,ANA\n20222,A|B\n20111,ANA\n");
+
+    let output = dir.run(&["schema", "docs", "morosos.yaml", "--sample", "sample.csv"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let doc = stdout(&output);
+    assert!(doc.starts_with("# Diccionario de datos: morosos\n"), "{}", doc);
+    assert!(doc.contains("| 1 | Cuil | int | partition | obligatoria | `20111`, `20222` |"), "{}", doc);
+    assert!(doc.contains("| 2 | IdRegion | int |  | admite vacío; default \"0\" |  |"), "{}", doc);
+    assert!(doc.contains("| 3 | Nombre | string |  |  | `ANA`, `A\\|B` |"), "{}", doc);
+    assert!(stderr(&output).contains("Schema column 'IdRegion' not found in sample"), "{}", stderr(&output));
+
+    let output = dir.run(&["schema", "docs", "morosos.yaml", "--doc-format", "html", "--output", "doc.html"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(dir.read("doc.html").contains("<td>IdRegion</td>"));
+}
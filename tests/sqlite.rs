@@ -0,0 +1,59 @@
+// query / to_sqlite / from_sqlite de punta a punta
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_query_without_output_writes_only_csv_to_stdout() {
+    let dir = TempDir::new("query_stdout");
+    dir.write("in.csv", "id,amount\n1,10\n2,25\n3,5\n");
+
+    let output = dir.run(&["query", "in.csv", "SELECT id FROM t WHERE CAST(amount AS INTEGER) > 6 ORDER BY id"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "id\n1\n2\n");
+    assert!(stderr(&output).contains("Query complete: 2 rows"));
+}
+
+#[test]
+fn test_to_sqlite_requires_table_and_round_trips_with_from_sqlite() {
+    let dir = TempDir::new("sqlite_round_trip");
+    dir.write("in.csv", "Cuil,IdRegion\n20111,1\n20222,2\n20333,1\n");
+
+    // Sin --table no hay un nombre por defecto: error de uso
+    let output = dir.run(&["to-sqlite", "in.csv", "db.sqlite"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--table"));
+    assert!(!dir.exists("db.sqlite"));
+
+    let output = dir.run(&["to-sqlite", "in.csv", "db.sqlite", "--table", "morosos", "--schema", "morosos"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Unknown model type: morosos"), "{}", stderr(&output));
+    assert!(!dir.exists("db.sqlite"));
+
+    let output = dir.run(&["to-sqlite", "in.csv", "db.sqlite", "--table", "morosos"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Rows inserted: 3"));
+
+    let output = dir.run(&["from-sqlite", "db.sqlite", "out.csv", "--query", "SELECT IdRegion, count(*) AS n FROM morosos GROUP BY 1 ORDER BY 1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "IdRegion,n\n1,2\n2,1\n");
+}
+
+#[test]
+fn test_query_with_output_and_invalid_sql() {
+    let dir = TempDir::new("query_output");
+    dir.write("in.csv", "IdRegion,Periodo\n1,202405\n2,202405\n1,202405\n1,202404\n");
+
+    let output = dir.run(&["query", "in.csv", "SELECT IdRegion, count(*) AS n FROM t WHERE Periodo = '202405' GROUP BY 1 ORDER BY 1", "--output", "out.csv"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(dir.read("out.csv"), "IdRegion,n\n1,2\n2,1\n");
+
+    let output = dir.run(&["query", "in.csv", "SELECT Missing FROM t", "--output", "bad.csv"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Missing"), "{}", stderr(&output));
+
+    let output = dir.run(&["query", "in.csv", "SELECT * FROM t", "--schema", "morosos"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Unknown model type: morosos"), "{}", stderr(&output));
+}
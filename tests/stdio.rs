@@ -0,0 +1,49 @@
+// Comandos con OUTPUT `-`: stdout tiene que traer solo el CSV (banners, conteos y
+// resúmenes van a stderr)
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_status_goes_to_stderr_when_output_is_stdout() {
+    let dir = TempDir::new("stdio");
+    dir.write("in.csv", "a,b\n2, x\n1,\"y, z\"\n");
+    dir.write("ids.txt", "1\n");
+
+    let cases: &[(&[&str], &str)] = &[
+        (&["select", "in.csv", "-", "--columns", "b"], "b\n x\n\"y, z\"\n"),
+        (&["drop", "in.csv", "-", "--columns", "a"], "b\n x\n\"y, z\"\n"),
+        (&["rename_columns", "in.csv", "-", "--map", "a=A"], "A,b\n2, x\n1,\"y, z\"\n"),
+        (&["trim", "in.csv", "-", "--all"], "a,b\n2,x\n1,\"y, z\"\n"),
+        (&["recase", "in.csv", "-", "--columns", "b", "--to", "upper"], "a,b\n2, X\n1,\"Y, Z\"\n"),
+        (&["normalize_nulls", "in.csv", "-"], "a,b\n2, x\n1,\"y, z\"\n"),
+        (&["split_column", "in.csv", "-", "--column", "b", "--sep", ",", "--into", "P,Q"], "a,P,Q\n2,x,\n1,y,z\n"),
+        (&["filter_regex", "in.csv", "-", "a", "^1$"], "a,b\n1,\"y, z\"\n"),
+        (&["filter_in", "in.csv", "-", "a", "--values-file", "ids.txt"], "a,b\n1,\"y, z\"\n"),
+        (&["sort", "in.csv", "-", "--column", "a"], "a,b\n1,\"y, z\"\n2, x\n"),
+    ];
+    for (args, expected) in cases {
+        let output = dir.run(args);
+        assert!(output.status.success(), "{:?}: {}", args, stderr(&output));
+        assert_eq!(stdout(&output), *expected, "{:?}", args);
+        assert!(stderr(&output).contains('╔'), "{:?}: banner expected on stderr", args);
+    }
+    assert!(dir.exists("stdout.split_errors.log") && !dir.exists("-.split_errors.log"));
+}
+
+#[test]
+fn test_filter_in_stdin_to_stdout() {
+    let dir = TempDir::new("stdio_filter_in");
+    dir.write("ids.txt", "b\n");
+
+    let output = dir.run_stdin(&["filter_in", "-", "-", "id", "--values-file", "ids.txt"], "id,v\na,1\nb,2\n");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "id,v\nb,2\n");
+    assert!(stderr(&output).contains("Rows written: 1"));
+
+    let output = dir.run_stdin(&["filter_in", "-", "-", "name", "--values-file", "ids.txt"], "id,v\na,1\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Column 'name' not found in input file"), "{}", stderr(&output));
+    assert!(stdout(&output).is_empty(), "{}", stdout(&output));
+}
@@ -0,0 +1,73 @@
+// validate_schema y sanitize_dynamodb de punta a punta: ejemplos por campo acotados con
+// totales exactos y columnas numéricas `nullable` del schema
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_validate_schema_caps_examples_but_counts_every_error() {
+    let dir = TempDir::new("validate_examples");
+    dir.write("in.csv", "Cuil,Cuit,FechaIngreso,FechaBaja\nx,30111,2020-01-01,\n20111,30111,2020-01-01,\ny,30222,2020-01-01,\nx,30333,2020-01-01,\n");
+
+    let output = dir.run(&["validate_schema", "in.csv", "siisa_empleadores_relaciones", "--max-examples-per-field", "1"]);
+    assert_eq!(output.status.code(), Some(1));
+    let err = stderr(&output);
+    assert!(err.contains("Line 2, Field [0] Cuil: INVALID 'x'"), "{}", err);
+    assert!(!err.contains("Line 4,"), "{}", err);
+    assert!(err.contains("Field Cuil: more than 1 errors, further examples suppressed"), "{}", err);
+
+    let out = stdout(&output);
+    assert!(out.contains("❌ Invalid records: 3"), "{}", out);
+    assert!(out.contains("❌ Cuil: 3 invalid value(s), 2 distinct"), "{}", out);
+    assert!(out.contains("2 × 'x'"), "{}", out);
+
+    dir.write("ok.csv", "Cuil,Cuit,FechaIngreso,FechaBaja\n20111,30111,2020-01-01,\n");
+    let output = dir.run(&["validate_schema", "ok.csv", "siisa_empleadores_relaciones"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("All records valid for DynamoDB import"));
+}
+
+const MOROSOS_HEADER: &str = "Cuil,IdTransmit,NroDoc,ApellidoNombre,IdCliente,IdRegion,RazonSocial,Telefono,NombreRegion,NombreCategoria,Periodo,IdEntidad,CreateDate,CreateUser";
+
+#[test]
+fn test_nullable_numeric_columns_in_sanitize_and_validate() {
+    let dir = TempDir::new("nullable");
+    dir.write("schema.yaml", "name: morosos\ncolumns:\n  - name: IdRegion\n    type: int\n    nullable: true\n    default: \"0\"\n  - name: NroDoc\n    type: int\n");
+    dir.write("in.csv", &format!(
+        "{}\n20111,1,111,ANA,5,,RS,555,NORTE,A,202405,7,2024-05-01,u\n20222,2,,BOB,5,3,RS,555,NORTE,A,202405,7,2024-05-01,u\n",
+        MOROSOS_HEADER
+    ));
+
+    // IdRegion vacío se completa con el default; NroDoc vacío descarta la fila
+    let output = dir.run(&["sanitize_dynamodb", "in.csv", "out.csv", "siisa_morosos", "--schema", "schema.yaml"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = dir.read("out.csv");
+    assert!(out.contains("20111,1,111,ANA,5,0,"), "{}", out);
+    assert!(!out.contains("20222"), "{}", out);
+    assert!(stderr(&output).contains("Empty value for non-nullable NroDoc"), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Empty values set to schema default: 1"));
+
+    let output = dir.run(&["validate_schema", "in.csv", "siisa_morosos", "--schema", "schema.yaml"]);
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    assert!(out.contains("Nullable (empty allowed): IdRegion"), "{}", out);
+    assert!(out.contains("❌ Invalid records: 1"), "{}", out);
+    assert!(out.contains("❌ NroDoc: 1 invalid value(s)"), "{}", out);
+}
+
+#[test]
+fn test_validate_model_honors_quiet_and_log_file() {
+    let dir = TempDir::new("validate_model_quiet");
+    dir.write("in.csv", &format!("{}\n20111,1,111,ANA,5,3,RS,555,NORTE,A,202405,7,2024-05-01,u\n", MOROSOS_HEADER));
+
+    let output = dir.run(&["validate_model", "in.csv", "errors.csv", "siisa_morosos"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("📊 Processed: 1 records"), "{}", stdout(&output));
+
+    // Con --quiet no sale nada por stdout, pero el log recibe los mensajes de estado
+    let output = dir.run(&["--quiet", "--log-file", "run.log", "validate_model", "in.csv", "errors.csv", "siisa_morosos"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).is_empty(), "{}", stdout(&output));
+    assert!(dir.read("run.log").contains("📊 Processed: 1 records"));
+}
@@ -0,0 +1,34 @@
+// watch --once de punta a punta: cada CSV de la carpeta pasa por el plan y termina en
+// processed/ o en errors/ (con su log); exit 1 si falló alguno
+
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+#[test]
+fn test_watch_once_processes_and_moves_files() {
+    let dir = TempDir::new("watch");
+    std::fs::create_dir(dir.path("in")).unwrap();
+    dir.write("in/good.csv", "Cuil,Nombre\n1,ana\n2,bob\n");
+    dir.write("in/bad.csv", "Dni,Nombre\n1,ana\n");
+    dir.write("in/notes.txt", "no es un CSV\n");
+    dir.write("keys.txt", "2\n");
+    dir.write("plan.yaml", concat!(
+        "steps:\n",
+        "  - command: assert\n",
+        "    args: [\"${file}\", \"--has-columns\", \"Cuil\"]\n",
+        "  - command: delete-keys\n",
+        "    args: [\"${file}\", \"${output_dir}/${name}_clean.csv\", \"--keys-file\", \"keys.txt\", \"--key-column\", \"Cuil\"]\n",
+    ));
+
+    let output = dir.run(&["watch", "in", "--pipeline", "plan.yaml", "--once"]);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Processed: 1 | ❌ Failed: 1"), "{}", stdout(&output));
+    assert!(stderr(&output).contains("1 of 2 files failed"), "{}", stderr(&output));
+
+    assert_eq!(dir.read("in/output/good_clean.csv"), "Cuil,Nombre\n1,ana\n");
+    assert!(dir.exists("in/processed/good.csv") && !dir.exists("in/good.csv"));
+    assert!(dir.exists("in/errors/bad.csv") && !dir.exists("in/bad.csv"));
+    assert!(dir.read("in/errors/bad.error.log").contains("exit code: 1"));
+    assert!(dir.exists("in/notes.txt"));
+}